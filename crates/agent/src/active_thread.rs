@@ -9,15 +9,18 @@ use crate::thread_store::ThreadStore;
 use crate::tool_use::{PendingToolUseStatus, ToolUse, ToolUseStatus};
 use crate::ui::{AddedContext, AgentNotification, AgentNotificationEvent, ContextPill};
 use anyhow::Context as _;
-use assistant_settings::{AssistantSettings, NotifyWhenAgentWaiting};
+use assistant_settings::{
+    AssistantSettings, NotifyWhenAgentWaiting, ToolPermissionAction, ToolPermissionRule,
+};
+use assistant_tool::ToolPermissionReason;
 use collections::HashMap;
 use editor::scroll::Autoscroll;
 use editor::{Editor, MultiBuffer};
 use gpui::{
     AbsoluteLength, Animation, AnimationExt, AnyElement, App, ClickEvent, DefiniteLength,
-    EdgesRefinement, Empty, Entity, Focusable, Hsla, Length, ListAlignment, ListState, MouseButton,
-    PlatformDisplay, ScrollHandle, Stateful, StyleRefinement, Subscription, Task,
-    TextStyleRefinement, Transformation, UnderlineStyle, WeakEntity, WindowHandle,
+    EdgesRefinement, Empty, Entity, Focusable, Hsla, Image, Length, ListAlignment, ListOffset,
+    ListState, MouseButton, PlatformDisplay, ScrollHandle, Stateful, StyleRefinement, Subscription,
+    Task, TextStyleRefinement, Transformation, UnderlineStyle, WeakEntity, WindowHandle, img,
     linear_color_stop, linear_gradient, list, percentage, pulsating_between,
 };
 use language::{Buffer, LanguageRegistry};
@@ -31,6 +34,7 @@ use std::time::Duration;
 use text::ToPoint;
 use theme::ThemeSettings;
 use ui::{Disclosure, IconButton, KeyBinding, Scrollbar, ScrollbarState, Tooltip, prelude::*};
+use url::Url;
 use util::ResultExt as _;
 use workspace::{OpenOptions, Workspace};
 
@@ -48,6 +52,7 @@ pub struct ActiveThread {
     scrollbar_state: ScrollbarState,
     rendered_messages_by_id: HashMap<MessageId, RenderedMessage>,
     rendered_tool_use_labels: HashMap<LanguageModelToolUseId, Entity<Markdown>>,
+    tool_result_images: HashMap<LanguageModelToolUseId, Vec<Arc<Image>>>,
     editing_message: Option<(MessageId, EditMessageState)>,
     expanded_tool_uses: HashMap<LanguageModelToolUseId, bool>,
     expanded_thinking_segments: HashMap<(MessageId, usize), bool>,
@@ -320,10 +325,28 @@ fn open_markdown_link(
                 });
             }
         }),
+        Some(MentionLink::Resource(uri)) => open_resource_uri(&uri, &workspace, window, cx),
         None => cx.open_url(&text),
     }
 }
 
+/// Opens the URI of a context server resource, if it points to a file or a web page.
+fn open_resource_uri(uri: &Url, workspace: &Entity<Workspace>, window: &mut Window, cx: &mut App) {
+    match uri.scheme() {
+        "file" => {
+            if let Ok(path) = uri.to_file_path() {
+                workspace.update(cx, |workspace, cx| {
+                    workspace
+                        .open_abs_path(path, OpenOptions::default(), window, cx)
+                        .detach_and_log_err(cx);
+                });
+            }
+        }
+        "http" | "https" => cx.open_url(uri.as_str()),
+        _ => {}
+    }
+}
+
 struct EditMessageState {
     editor: Entity<Editor>,
 }
@@ -361,6 +384,7 @@ impl ActiveThread {
             messages: Vec::new(),
             rendered_messages_by_id: HashMap::default(),
             rendered_tool_use_labels: HashMap::default(),
+            tool_result_images: HashMap::default(),
             expanded_tool_uses: HashMap::default(),
             expanded_thinking_segments: HashMap::default(),
             list_state: list_state.clone(),
@@ -383,6 +407,7 @@ impl ActiveThread {
                     window,
                     cx,
                 );
+                this.decode_tool_result_images(&tool_use.id, cx);
             }
         }
 
@@ -397,6 +422,17 @@ impl ActiveThread {
         self.messages.is_empty()
     }
 
+    /// Scrolls the list so that the given message is at the top.
+    pub fn scroll_to_message(&mut self, message_id: MessageId, cx: &mut Context<Self>) {
+        if let Some(ix) = self.messages.iter().position(|id| *id == message_id) {
+            self.list_state.scroll_to(ListOffset {
+                item_ix: ix,
+                offset_in_item: px(0.),
+            });
+            cx.notify();
+        }
+    }
+
     pub fn summary(&self, cx: &App) -> Option<SharedString> {
         self.thread.read(cx).summary()
     }
@@ -489,6 +525,20 @@ impl ActiveThread {
         );
     }
 
+    fn decode_tool_result_images(&mut self, tool_use_id: &LanguageModelToolUseId, cx: &App) {
+        let Some(tool_result) = self.thread.read(cx).tool_result(tool_use_id) else {
+            return;
+        };
+
+        let images = tool_result
+            .images()
+            .filter_map(|image| image.to_image())
+            .collect::<Vec<_>>();
+        if !images.is_empty() {
+            self.tool_result_images.insert(tool_use_id.clone(), images);
+        }
+    }
+
     fn handle_thread_event(
         &mut self,
         _thread: &Entity<Thread>,
@@ -578,11 +628,12 @@ impl ActiveThread {
                 }
             }
             ThreadEvent::ToolFinished {
+                tool_use_id,
                 pending_tool_use,
                 canceled,
-                ..
             } => {
                 let canceled = *canceled;
+                self.decode_tool_result_images(tool_use_id, cx);
                 if let Some(tool_use) = pending_tool_use {
                     self.render_tool_use_label_markdown(
                         tool_use.id.clone(),
@@ -645,6 +696,10 @@ impl ActiveThread {
                 }
             }
             ThreadEvent::CheckpointChanged => cx.notify(),
+            ThreadEvent::Compacted(_) => {
+                self.save_thread(cx);
+                cx.notify();
+            }
         }
     }
 
@@ -778,6 +833,41 @@ impl ActiveThread {
         }));
     }
 
+    /// Creates a new thread sharing this thread's history up to and including the given
+    /// message, and makes it the active thread.
+    fn fork_thread(&mut self, message_id: MessageId, window: &mut Window, cx: &mut Context<Self>) {
+        let fork_task = self.thread_store.update(cx, |thread_store, cx| {
+            thread_store.fork_thread(&self.thread, message_id, cx)
+        });
+        let workspace = self.workspace.clone();
+        cx.spawn_in(window, async move |_this, cx| {
+            let thread = fork_task.await?;
+            workspace.update_in(cx, |workspace, window, cx| {
+                if let Some(panel) = workspace.panel::<AssistantPanel>(cx) {
+                    panel.update(cx, |panel, cx| panel.set_active_thread(thread, window, cx));
+                }
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn render_fork_button(
+        &self,
+        ix: usize,
+        message_id: MessageId,
+        cx: &mut Context<Self>,
+    ) -> IconButton {
+        IconButton::new(("fork-thread", ix), IconName::GitBranch)
+            .icon_size(IconSize::XSmall)
+            .icon_color(Color::Muted)
+            .shape(ui::IconButtonShape::Square)
+            .disabled(self.thread.read(cx).is_generating())
+            .tooltip(Tooltip::text("Fork Thread From Here"))
+            .on_click(cx.listener(move |this, _, window, cx| {
+                this.fork_thread(message_id, window, cx);
+            }))
+    }
+
     fn start_editing_message(
         &mut self,
         message_id: MessageId,
@@ -982,6 +1072,10 @@ impl ActiveThread {
         let context = thread.context_for_message(message_id).collect::<Vec<_>>();
         let tool_uses = thread.tool_uses_for_message(message_id, cx);
         let has_tool_uses = !tool_uses.is_empty();
+        let is_compaction_point = thread
+            .compactions()
+            .iter()
+            .any(|compaction| compaction.message_id == message_id);
 
         // Don't render user messages that are just there for returning tool results.
         if message.role == Role::User && thread.message_has_tool_results(message_id) {
@@ -1161,6 +1255,7 @@ impl ActiveThread {
                     })
             });
 
+        let assistant_message_group = SharedString::from(format!("assistant-message-{ix}"));
         let styled_message = match message.role {
             Role::User => v_flex()
                 .id(("message-container", ix))
@@ -1250,6 +1345,9 @@ impl ActiveThread {
                                                 )
                                             },
                                         )
+                                        .when(edit_message_editor.is_none(), |this| {
+                                            this.child(self.render_fork_button(ix, message_id, cx))
+                                        })
                                         .when(
                                             edit_message_editor.is_none() && allow_editing_message,
                                             |this| {
@@ -1277,6 +1375,7 @@ impl ActiveThread {
                 ),
             Role::Assistant => v_flex()
                 .id(("message-container", ix))
+                .group(assistant_message_group.clone())
                 .ml_2()
                 .pl_2()
                 .pr_4()
@@ -1293,7 +1392,13 @@ impl ActiveThread {
                                 .map(|tool_use| self.render_tool_use(tool_use, cx)),
                         ),
                     )
-                }),
+                })
+                .child(
+                    h_flex().justify_end().child(
+                        self.render_fork_button(ix, message_id, cx)
+                            .visible_on_hover(assistant_message_group),
+                    ),
+                ),
             Role::System => div().id(("message-container", ix)).py_1().px_2().child(
                 v_flex()
                     .bg(colors.editor_background)
@@ -1377,6 +1482,26 @@ impl ActiveThread {
                 )
             })
             .child(styled_message)
+            .when(is_compaction_point, |parent| {
+                parent.child(
+                    h_flex()
+                        .id(("compaction-point", ix))
+                        .pt_2p5()
+                        .px_2p5()
+                        .w_full()
+                        .gap_1()
+                        .child(ui::Divider::horizontal())
+                        .child(
+                            Label::new("Summarized")
+                                .size(LabelSize::XSmall)
+                                .color(Color::Muted),
+                        )
+                        .child(ui::Divider::horizontal())
+                        .tooltip(Tooltip::text(
+                            "The messages above are sent to the model as a summary",
+                        )),
+                )
+            })
             .when(
                 show_feedback && !self.thread.read(cx).is_generating(),
                 |parent| {
@@ -1680,11 +1805,13 @@ impl ActiveThread {
         tool_use: ToolUse,
         cx: &mut Context<Self>,
     ) -> impl IntoElement + use<> {
+        // Tools that render their own card (e.g. the live output of a command)
+        // are expanded by default, so their progress is visible as it happens.
         let is_open = self
             .expanded_tool_uses
             .get(&tool_use.id)
             .copied()
-            .unwrap_or_default();
+            .unwrap_or(tool_use.card.is_some());
 
         let is_status_finished = matches!(&tool_use.status, ToolUseStatus::Finished(_));
 
@@ -1693,6 +1820,31 @@ impl ActiveThread {
             .upgrade()
             .map(|workspace| workspace.read(cx).app_state().fs.clone());
         let needs_confirmation = matches!(&tool_use.status, ToolUseStatus::NeedsConfirmation);
+        let confirmation = self
+            .thread
+            .read(cx)
+            .pending_tool(&tool_use.id)
+            .and_then(|tool_use| match &tool_use.status {
+                PendingToolUseStatus::NeedsConfirmation(confirmation) => Some(confirmation.clone()),
+                _ => None,
+            });
+        // Rules that explicitly ask for confirmation take precedence over allow rules,
+        // so we only offer to persist one when no rule matched.
+        let allow_rule =
+            confirmation
+                .as_ref()
+                .and_then(|confirmation| match confirmation.permission.reason {
+                    ToolPermissionReason::Rule(_) => None,
+                    _ => Some(ToolPermissionRule {
+                        tool: confirmation.tool.name().into(),
+                        input: confirmation.tool.permission_rule_input(&confirmation.input),
+                        outside_worktree: false,
+                        action: ToolPermissionAction::Allow,
+                    }),
+                });
+        let permission_reason = confirmation
+            .as_ref()
+            .map(|confirmation| SharedString::from(confirmation.permission.reason.to_string()));
 
         let status_icons = div().child(match &tool_use.status {
             ToolUseStatus::Pending | ToolUseStatus::NeedsConfirmation => {
@@ -1740,8 +1892,15 @@ impl ActiveThread {
                         .buffer_font(cx),
                     ),
             )
-            .map(|container| match tool_use.status {
-                ToolUseStatus::Finished(output) => container.child(
+            .map(|container| match (tool_use.card, tool_use.status) {
+                (Some(card), ToolUseStatus::Running | ToolUseStatus::Finished(_)) => container
+                    .child(
+                        content_container()
+                            .border_t_1()
+                            .border_color(self.tool_card_border_color(cx))
+                            .child(card),
+                    ),
+                (_, ToolUseStatus::Finished(output)) => container.child(
                     content_container()
                         .border_t_1()
                         .border_color(self.tool_card_border_color(cx))
@@ -1751,9 +1910,14 @@ impl ActiveThread {
                                 .color(Color::Muted)
                                 .buffer_font(cx),
                         )
-                        .child(Label::new(output).size(LabelSize::Small).buffer_font(cx)),
+                        .child(Label::new(output).size(LabelSize::Small).buffer_font(cx))
+                        .children(self.tool_result_images.get(&tool_use.id).map(|images| {
+                            v_flex().gap_1().pt_1().children(images.iter().map(|image| {
+                                img(image.clone()).max_w_full().max_h_80().rounded_sm()
+                            }))
+                        })),
                 ),
-                ToolUseStatus::Running => container.child(
+                (_, ToolUseStatus::Running) => container.child(
                     content_container().child(
                         h_flex()
                             .gap_1()
@@ -1782,7 +1946,7 @@ impl ActiveThread {
                             ),
                     ),
                 ),
-                ToolUseStatus::Error(err) => container.child(
+                (_, ToolUseStatus::Error(err)) => container.child(
                     content_container()
                         .border_t_1()
                         .border_color(self.tool_card_border_color(cx))
@@ -1794,8 +1958,8 @@ impl ActiveThread {
                         )
                         .child(Label::new(err).size(LabelSize::Small).buffer_font(cx)),
                 ),
-                ToolUseStatus::Pending => container,
-                ToolUseStatus::NeedsConfirmation => container.child(
+                (_, ToolUseStatus::Pending) => container,
+                (_, ToolUseStatus::NeedsConfirmation) => container.child(
                     content_container()
                         .border_t_1()
                         .border_color(self.tool_card_border_color(cx))
@@ -2005,51 +2169,69 @@ impl ActiveThread {
                                 .border_t_1()
                                 .border_color(self.tool_card_border_color(cx))
                                 .rounded_b_lg()
-                                .child(Label::new("Action Confirmation").color(Color::Muted).size(LabelSize::Small))
+                                .child(
+                                    h_flex()
+                                        .gap_1()
+                                        .min_w_0()
+                                        .child(Label::new("Action Confirmation").color(Color::Muted).size(LabelSize::Small))
+                                        .when_some(permission_reason, |this, reason| {
+                                            this.child(
+                                                Label::new(reason)
+                                                    .color(Color::Muted)
+                                                    .size(LabelSize::XSmall)
+                                                    .truncate(),
+                                            )
+                                        }),
+                                )
                                 .child(
                                     h_flex()
                                         .gap_0p5()
-                                        .child({
+                                        .when_some(allow_rule, |this, allow_rule| {
                                             let tool_id = tool_use.id.clone();
-                                            Button::new(
-                                                "always-allow-tool-action",
-                                                "Always Allow",
-                                            )
-                                            .label_size(LabelSize::Small)
-                                            .icon(IconName::CheckDouble)
-                                            .icon_position(IconPosition::Start)
-                                            .icon_size(IconSize::Small)
-                                            .icon_color(Color::Success)
-                                            .tooltip(move |window, cx|  {
-                                                Tooltip::with_meta(
-                                                    "Never ask for permission",
-                                                    None,
-                                                    "Restore the original behavior in your Agent Panel settings",
-                                                    window,
-                                                    cx,
+                                            let rule_description =
+                                                SharedString::from(allow_rule.to_string());
+                                            this.child(
+                                                Button::new(
+                                                    "always-allow-tool-action",
+                                                    "Always Allow",
                                                 )
-                                            })
-                                            .on_click(cx.listener(
-                                                move |this, event, window, cx| {
-                                                    if let Some(fs) = fs.clone() {
-                                                        update_settings_file::<AssistantSettings>(
-                                                            fs.clone(),
-                                                            cx,
-                                                            |settings, _| {
-                                                                settings.set_always_allow_tool_actions(true);
-                                                            },
-                                                        );
-                                                    }
-                                                    this.handle_allow_tool(
-                                                        tool_id.clone(),
-                                                        event,
+                                                .label_size(LabelSize::Small)
+                                                .icon(IconName::CheckDouble)
+                                                .icon_position(IconPosition::Start)
+                                                .icon_size(IconSize::Small)
+                                                .icon_color(Color::Success)
+                                                .tooltip(move |window, cx|  {
+                                                    Tooltip::with_meta(
+                                                        "Always allow this pattern",
+                                                        None,
+                                                        format!("Adds a rule to your Agent Panel settings: {rule_description}"),
                                                         window,
                                                         cx,
                                                     )
-                                                },
-                                            ))
+                                                })
+                                                .on_click(cx.listener(
+                                                    move |this, event, window, cx| {
+                                                        if let Some(fs) = fs.clone() {
+                                                            let allow_rule = allow_rule.clone();
+                                                            update_settings_file::<AssistantSettings>(
+                                                                fs.clone(),
+                                                                cx,
+                                                                move |settings, _| {
+                                                                    settings.add_tool_permission_rule(allow_rule);
+                                                                },
+                                                            );
+                                                        }
+                                                        this.handle_allow_tool(
+                                                            tool_id.clone(),
+                                                            event,
+                                                            window,
+                                                            cx,
+                                                        )
+                                                    },
+                                                )),
+                                            )
+                                            .child(ui::Divider::vertical())
                                         })
-                                        .child(ui::Divider::vertical())
                                         .child({
                                             let tool_id = tool_use.id.clone();
                                             Button::new("allow-tool-action", "Allow")
@@ -2358,5 +2540,9 @@ pub(crate) fn open_context(
                 }
             })
         }
+        AssistantContext::Resource(resource_context) => {
+            let uri = resource_context.resource_id.uri.clone();
+            open_resource_uri(&uri, &workspace, window, cx);
+        }
     }
 }
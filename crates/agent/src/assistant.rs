@@ -65,6 +65,8 @@ actions!(
         RemoveFocusedContext,
         AcceptSuggestedContext,
         OpenActiveThreadAsMarkdown,
+        ExportThread,
+        ImportThread,
         OpenAgentDiff,
         Keep,
         Reject,
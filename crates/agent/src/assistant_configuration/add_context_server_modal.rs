@@ -77,6 +77,7 @@ impl AddContextServerModal {
                                 args,
                                 env: None,
                             }),
+                            http: None,
                             settings: Some(json!({})),
                         },
                     );
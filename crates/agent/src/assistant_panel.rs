@@ -1,7 +1,7 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
-use anyhow::{Result, anyhow};
+use anyhow::{Context as _, Result, anyhow};
 use assistant_context_editor::{
     AssistantPanelDelegate, ConfigurationError, ContextEditor, SlashCommandCompletionProvider,
     make_lsp_adapter_delegate, render_remaining_tokens,
@@ -15,8 +15,8 @@ use editor::{Editor, MultiBuffer};
 use fs::Fs;
 use gpui::{
     Action, AnyElement, App, AsyncWindowContext, Corner, Entity, EventEmitter, FocusHandle,
-    Focusable, FontWeight, KeyContext, Pixels, Subscription, Task, UpdateGlobal, WeakEntity,
-    action_with_deprecated_aliases, prelude::*,
+    Focusable, FontWeight, KeyContext, PathPromptOptions, Pixels, Subscription, Task, UpdateGlobal,
+    WeakEntity, action_with_deprecated_aliases, prelude::*,
 };
 use language::LanguageRegistry;
 use language_model::{LanguageModelProviderTosView, LanguageModelRegistry};
@@ -32,18 +32,19 @@ use ui::{
 use util::ResultExt as _;
 use workspace::Workspace;
 use workspace::dock::{DockPosition, Panel, PanelEvent};
+use workspace::notifications::DetachAndPromptErr as _;
 use zed_actions::assistant::ToggleFocus;
 
 use crate::active_thread::ActiveThread;
 use crate::assistant_configuration::{AssistantConfiguration, AssistantConfigurationEvent};
 use crate::history_store::{HistoryEntry, HistoryStore};
 use crate::message_editor::MessageEditor;
-use crate::thread::{Thread, ThreadError, ThreadId};
+use crate::thread::{MessageId, Thread, ThreadError, ThreadId};
 use crate::thread_history::{PastContext, PastThread, ThreadHistory};
-use crate::thread_store::ThreadStore;
+use crate::thread_store::{ExportedThread, ThreadStore};
 use crate::{
-    AgentDiff, InlineAssistant, NewPromptEditor, NewThread, OpenActiveThreadAsMarkdown,
-    OpenAgentDiff, OpenConfiguration, OpenHistory, ToggleContextPicker,
+    AgentDiff, ExportThread, ImportThread, InlineAssistant, NewPromptEditor, NewThread,
+    OpenActiveThreadAsMarkdown, OpenAgentDiff, OpenConfiguration, OpenHistory, ToggleContextPicker,
 };
 
 action_with_deprecated_aliases!(
@@ -88,6 +89,12 @@ pub fn init(cx: &mut App) {
                         });
                     }
                 })
+                .register_action(|workspace, _: &ImportThread, window, cx| {
+                    if let Some(panel) = workspace.panel::<AssistantPanel>(cx) {
+                        workspace.focus_panel::<AssistantPanel>(window, cx);
+                        panel.update(cx, |panel, cx| panel.import_thread(window, cx));
+                    }
+                })
                 .register_action(|workspace, _: &OpenAgentDiff, window, cx| {
                     if let Some(panel) = workspace.panel::<AssistantPanel>(cx) {
                         workspace.focus_panel::<AssistantPanel>(window, cx);
@@ -227,7 +234,9 @@ impl AssistantPanel {
             )
             .unwrap(),
             history_store: history_store.clone(),
-            history: cx.new(|cx| ThreadHistory::new(weak_self, history_store, cx)),
+            history: cx.new(|cx| {
+                ThreadHistory::new(weak_self, history_store, thread_store.clone(), window, cx)
+            }),
             assistant_dropdown_menu_handle: PopoverMenuHandle::default(),
             width: None,
             height: None,
@@ -435,40 +444,69 @@ impl AssistantPanel {
         cx.spawn_in(window, async move |this, cx| {
             let thread = open_thread_task.await?;
             this.update_in(cx, |this, window, cx| {
-                this.active_view = ActiveView::Thread;
-                let message_editor_context_store = cx.new(|_cx| {
-                    crate::context_store::ContextStore::new(
-                        this.workspace.clone(),
-                        Some(this.thread_store.downgrade()),
-                    )
-                });
-                this.thread = cx.new(|cx| {
-                    ActiveThread::new(
-                        thread.clone(),
-                        this.thread_store.clone(),
-                        this.language_registry.clone(),
-                        message_editor_context_store.clone(),
-                        this.workspace.clone(),
-                        window,
-                        cx,
-                    )
-                });
-                this.message_editor = cx.new(|cx| {
-                    MessageEditor::new(
-                        this.fs.clone(),
-                        this.workspace.clone(),
-                        message_editor_context_store,
-                        this.thread_store.downgrade(),
-                        thread,
-                        window,
-                        cx,
-                    )
+                this.set_active_thread(thread, window, cx);
+            })
+        })
+    }
+
+    /// Opens the given thread, scrolled to the given message.
+    pub(crate) fn open_thread_at_message(
+        &mut self,
+        thread_id: &ThreadId,
+        message_id: MessageId,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let open_thread_task = self.open_thread(thread_id, window, cx);
+        cx.spawn(async move |this, cx| {
+            open_thread_task.await?;
+            this.update(cx, |this, cx| {
+                this.thread.update(cx, |thread, cx| {
+                    thread.scroll_to_message(message_id, cx);
                 });
-                this.message_editor.focus_handle(cx).focus(window);
             })
         })
     }
 
+    pub(crate) fn set_active_thread(
+        &mut self,
+        thread: Entity<Thread>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.active_view = ActiveView::Thread;
+        let message_editor_context_store = cx.new(|_cx| {
+            crate::context_store::ContextStore::new(
+                self.workspace.clone(),
+                Some(self.thread_store.downgrade()),
+            )
+        });
+        self.thread = cx.new(|cx| {
+            ActiveThread::new(
+                thread.clone(),
+                self.thread_store.clone(),
+                self.language_registry.clone(),
+                message_editor_context_store.clone(),
+                self.workspace.clone(),
+                window,
+                cx,
+            )
+        });
+        self.message_editor = cx.new(|cx| {
+            MessageEditor::new(
+                self.fs.clone(),
+                self.workspace.clone(),
+                message_editor_context_store,
+                self.thread_store.downgrade(),
+                thread,
+                window,
+                cx,
+            )
+        });
+        self.message_editor.focus_handle(cx).focus(window);
+        cx.notify();
+    }
+
     pub fn open_agent_diff(
         &mut self,
         _: &OpenAgentDiff,
@@ -560,6 +598,82 @@ impl AssistantPanel {
         .detach_and_log_err(cx);
     }
 
+    pub(crate) fn export_active_thread(
+        &mut self,
+        _: &ExportThread,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let directory = self
+            .project
+            .read(cx)
+            .visible_worktrees(cx)
+            .next()
+            .map(|worktree| worktree.read(cx).abs_path().to_path_buf())
+            .unwrap_or_else(|| paths::home_dir().clone());
+        let path = cx.prompt_for_new_path(&directory);
+        let export_task = self
+            .active_thread(cx)
+            .update(cx, |thread, cx| thread.export(cx));
+        let fs = self.fs.clone();
+
+        cx.spawn(async move |_this, _cx| {
+            let Some(path) = path.await?? else {
+                return Ok(());
+            };
+            let exported_thread = export_task.await?;
+            fs.atomic_write(path, exported_thread.to_json()?).await
+        })
+        .detach_and_prompt_err("Failed to export thread", window, cx, |_, _, _| None);
+    }
+
+    fn import_thread(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let paths = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: false,
+        });
+
+        cx.spawn_in(window, async move |this, cx| {
+            let Some(path) = paths.await??.and_then(|paths| paths.into_iter().next()) else {
+                return Ok(());
+            };
+            this.update_in(cx, |this, window, cx| {
+                this.import_thread_from_path(&path, window, cx)
+            })?
+            .await
+        })
+        .detach_and_prompt_err("Failed to import thread", window, cx, |_, _, _| None);
+    }
+
+    /// Imports a thread from a file written by [`Thread::export`], and opens it.
+    pub fn import_thread_from_path(
+        &mut self,
+        path: &Path,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let path = path.to_path_buf();
+        let fs = self.fs.clone();
+        let thread_store = self.thread_store.clone();
+
+        cx.spawn_in(window, async move |this, cx| {
+            let json = fs.load(&path).await?;
+            let exported_thread = ExportedThread::from_json(json.as_bytes())
+                .with_context(|| format!("failed to read exported thread {path:?}"))?;
+            let thread_id = thread_store
+                .update(cx, |thread_store, cx| {
+                    thread_store.import_thread(exported_thread, cx)
+                })?
+                .await?;
+
+            this.update_in(cx, |this, window, cx| {
+                this.open_thread(&thread_id, window, cx)
+            })?
+            .await
+        })
+    }
+
     fn handle_assistant_configuration_event(
         &mut self,
         _entity: &Entity<AssistantConfiguration>,
@@ -831,6 +945,16 @@ impl AssistantPanel {
                                                         }),
                                                     )
                                                 })
+                                                .when(!is_empty, |menu| {
+                                                    menu.action(
+                                                        "Export Thread…",
+                                                        ExportThread.boxed_clone(),
+                                                    )
+                                                })
+                                                .action(
+                                                    "Import Thread…",
+                                                    ImportThread.boxed_clone(),
+                                                )
                                                 .separator()
                                                 .action("History", OpenHistory.boxed_clone())
                                                 .action("Settings", OpenConfiguration.boxed_clone())
@@ -1325,6 +1449,7 @@ impl Render for AssistantPanel {
                 this.open_configuration(window, cx);
             }))
             .on_action(cx.listener(Self::open_active_thread_as_markdown))
+            .on_action(cx.listener(Self::export_active_thread))
             .on_action(cx.listener(Self::deploy_prompt_library))
             .on_action(cx.listener(Self::open_agent_diff))
             .child(self.render_toolbar(window, cx))
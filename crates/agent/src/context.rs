@@ -7,6 +7,7 @@ use project::ProjectPath;
 use serde::{Deserialize, Serialize};
 use text::{Anchor, BufferId};
 use ui::IconName;
+use url::Url;
 use util::post_inc;
 
 use crate::thread::Thread;
@@ -25,6 +26,7 @@ pub enum ContextKind {
     Symbol,
     FetchedUrl,
     Thread,
+    Resource,
 }
 
 impl ContextKind {
@@ -35,6 +37,7 @@ impl ContextKind {
             ContextKind::Symbol => IconName::Code,
             ContextKind::FetchedUrl => IconName::Globe,
             ContextKind::Thread => IconName::MessageBubbles,
+            ContextKind::Resource => IconName::Server,
         }
     }
 }
@@ -46,6 +49,7 @@ pub enum AssistantContext {
     Symbol(SymbolContext),
     FetchedUrl(FetchedUrlContext),
     Thread(ThreadContext),
+    Resource(ResourceContext),
 }
 
 impl AssistantContext {
@@ -56,6 +60,7 @@ impl AssistantContext {
             Self::Symbol(symbol) => symbol.id,
             Self::FetchedUrl(url) => url.id,
             Self::Thread(thread) => thread.id,
+            Self::Resource(resource) => resource.id,
         }
     }
 }
@@ -105,6 +110,21 @@ impl ThreadContext {
     }
 }
 
+/// A resource read from a context server.
+#[derive(Debug, Clone)]
+pub struct ResourceContext {
+    pub id: ContextId,
+    pub resource_id: ContextResourceId,
+    pub name: SharedString,
+    pub text: SharedString,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ContextResourceId {
+    pub server_id: Arc<str>,
+    pub uri: Url,
+}
+
 // TODO: Model<Buffer> holds onto the buffer even if the file is deleted and closed. Should remove
 // the context from the message editor in this case.
 
@@ -156,6 +176,7 @@ pub fn attach_context_to_message<'a>(
     let mut symbol_context = Vec::new();
     let mut fetch_context = Vec::new();
     let mut thread_context = Vec::new();
+    let mut resource_context = Vec::new();
 
     for context in contexts {
         match context {
@@ -164,6 +185,7 @@ pub fn attach_context_to_message<'a>(
             AssistantContext::Symbol(context) => symbol_context.push(context),
             AssistantContext::FetchedUrl(context) => fetch_context.push(context),
             AssistantContext::Thread(context) => thread_context.push(context),
+            AssistantContext::Resource(context) => resource_context.push(context),
         }
     }
 
@@ -204,6 +226,15 @@ pub fn attach_context_to_message<'a>(
         context_chunks.push("\n</fetched_urls>\n");
     }
 
+    if !resource_context.is_empty() {
+        context_chunks.push("<resources>\n");
+        for context in &resource_context {
+            context_chunks.push(context.resource_id.uri.as_str());
+            context_chunks.push(&context.text);
+        }
+        context_chunks.push("\n</resources>\n");
+    }
+
     // Need to own the SharedString for summary so that it can be referenced.
     let mut thread_context_chunks = Vec::new();
     if !thread_context.is_empty() {
@@ -1,6 +1,7 @@
 mod completion_provider;
 mod fetch_context_picker;
 mod file_context_picker;
+mod resource_context_picker;
 mod symbol_context_picker;
 mod thread_context_picker;
 
@@ -17,11 +18,13 @@ use gpui::{
 };
 use multi_buffer::MultiBufferRow;
 use project::{Entry, ProjectPath};
+use resource_context_picker::{ResourceContextEntry, ResourceContextPicker};
 use symbol_context_picker::SymbolContextPicker;
 use thread_context_picker::{ThreadContextEntry, render_thread_context_entry};
 use ui::{
     ButtonLike, ContextMenu, ContextMenuEntry, ContextMenuItem, Disclosure, TintColor, prelude::*,
 };
+use url::Url;
 use workspace::{Workspace, notifications::NotifyResultExt};
 
 use crate::AssistantPanel;
@@ -45,6 +48,7 @@ enum ContextPickerMode {
     Symbol,
     Fetch,
     Thread,
+    Resource,
 }
 
 impl TryFrom<&str> for ContextPickerMode {
@@ -56,6 +60,7 @@ impl TryFrom<&str> for ContextPickerMode {
             "symbol" => Ok(Self::Symbol),
             "fetch" => Ok(Self::Fetch),
             "thread" => Ok(Self::Thread),
+            "resource" => Ok(Self::Resource),
             _ => Err(format!("Invalid context picker mode: {}", value)),
         }
     }
@@ -68,6 +73,7 @@ impl ContextPickerMode {
             Self::Symbol => "symbol",
             Self::Fetch => "fetch",
             Self::Thread => "thread",
+            Self::Resource => "resource",
         }
     }
 
@@ -77,6 +83,7 @@ impl ContextPickerMode {
             Self::Symbol => "Symbols",
             Self::Fetch => "Fetch",
             Self::Thread => "Threads",
+            Self::Resource => "Resources",
         }
     }
 
@@ -86,6 +93,7 @@ impl ContextPickerMode {
             Self::Symbol => IconName::Code,
             Self::Fetch => IconName::Globe,
             Self::Thread => IconName::MessageBubbles,
+            Self::Resource => IconName::Server,
         }
     }
 }
@@ -97,6 +105,7 @@ enum ContextPickerState {
     Symbol(Entity<SymbolContextPicker>),
     Fetch(Entity<FetchContextPicker>),
     Thread(Entity<ThreadContextPicker>),
+    Resource(Entity<ResourceContextPicker>),
 }
 
 pub(super) struct ContextPicker {
@@ -252,6 +261,20 @@ impl ContextPicker {
                     }));
                 }
             }
+            ContextPickerMode::Resource => {
+                if let Some(thread_store) = self.thread_store.as_ref() {
+                    self.mode = ContextPickerState::Resource(cx.new(|cx| {
+                        ResourceContextPicker::new(
+                            thread_store.clone(),
+                            context_picker.clone(),
+                            self.context_store.clone(),
+                            self.confirm_behavior,
+                            window,
+                            cx,
+                        )
+                    }));
+                }
+            }
         }
 
         cx.notify();
@@ -382,6 +405,7 @@ impl Focusable for ContextPicker {
             ContextPickerState::Symbol(symbol_picker) => symbol_picker.focus_handle(cx),
             ContextPickerState::Fetch(fetch_picker) => fetch_picker.focus_handle(cx),
             ContextPickerState::Thread(thread_picker) => thread_picker.focus_handle(cx),
+            ContextPickerState::Resource(resource_picker) => resource_picker.focus_handle(cx),
         }
     }
 }
@@ -397,6 +421,9 @@ impl Render for ContextPicker {
                 ContextPickerState::Symbol(symbol_picker) => parent.child(symbol_picker.clone()),
                 ContextPickerState::Fetch(fetch_picker) => parent.child(fetch_picker.clone()),
                 ContextPickerState::Thread(thread_picker) => parent.child(thread_picker.clone()),
+                ContextPickerState::Resource(resource_picker) => {
+                    parent.child(resource_picker.clone())
+                }
             })
     }
 }
@@ -418,6 +445,7 @@ fn supported_context_picker_modes(
     ];
     if thread_store.is_some() {
         modes.push(ContextPickerMode::Thread);
+        modes.push(ContextPickerMode::Resource);
     }
     modes
 }
@@ -610,6 +638,7 @@ pub enum MentionLink {
     File(ProjectPath, Entry),
     Symbol(ProjectPath, String),
     Thread(ThreadId),
+    Resource(Url),
 }
 
 impl MentionLink {
@@ -629,6 +658,13 @@ impl MentionLink {
         format!("[@{}](thread:{})", thread.summary, thread.id)
     }
 
+    pub fn for_resource(resource: &ResourceContextEntry) -> String {
+        format!(
+            "[@{}](resource:{}:{})",
+            resource.name, resource.server_id, resource.uri
+        )
+    }
+
     pub fn try_parse(link: &str, workspace: &Entity<Workspace>, cx: &App) -> Option<Self> {
         fn extract_project_path_from_link(
             path: &str,
@@ -675,6 +711,10 @@ impl MentionLink {
                 let thread_id = ThreadId::from(thread_id);
                 Some(MentionLink::Thread(thread_id))
             }
+            (Some("resource"), Some(_server_id), Some(uri)) => {
+                let uri = Url::parse(uri).ok()?;
+                Some(MentionLink::Resource(uri))
+            }
             _ => None,
         }
     }
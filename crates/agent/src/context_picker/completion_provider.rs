@@ -23,6 +23,7 @@ use crate::context_store::ContextStore;
 use crate::thread_store::ThreadStore;
 
 use super::fetch_context_picker::fetch_url_content;
+use super::resource_context_picker::ResourceContextEntry;
 use super::thread_context_picker::ThreadContextEntry;
 use super::{
     ContextPickerMode, MentionLink, recent_context_picker_entries, supported_context_picker_modes,
@@ -192,6 +193,47 @@ impl ContextPickerCompletionProvider {
         }
     }
 
+    fn completion_for_resource(
+        resource: ResourceContextEntry,
+        excerpt_id: ExcerptId,
+        source_range: Range<Anchor>,
+        editor: Entity<Editor>,
+        context_store: Entity<ContextStore>,
+    ) -> Completion {
+        let new_text = MentionLink::for_resource(&resource);
+        let new_text_len = new_text.len();
+        Completion {
+            old_range: source_range.clone(),
+            new_text,
+            label: CodeLabel::plain(resource.name.to_string(), None),
+            documentation: None,
+            source: project::CompletionSource::Custom,
+            icon_path: Some(IconName::Server.path().into()),
+            confirm: Some(confirm_completion_callback(
+                IconName::Server.path().into(),
+                resource.name.clone(),
+                excerpt_id,
+                source_range.start,
+                new_text_len,
+                editor.clone(),
+                move |cx| {
+                    let resource = resource.clone();
+                    context_store.update(cx, |context_store, cx| {
+                        context_store
+                            .add_resource(
+                                resource.server_id,
+                                resource.uri,
+                                resource.name,
+                                false,
+                                cx,
+                            )
+                            .detach_and_log_err(cx);
+                    });
+                },
+            )),
+        }
+    }
+
     fn completion_for_fetch(
         source_range: Range<Anchor>,
         url_to_fetch: SharedString,
@@ -545,6 +587,33 @@ impl CompletionProvider for ContextPickerCompletionProvider {
                         }
                     }
                 }
+                Some(ContextPickerMode::Resource) => {
+                    if let Some((thread_store, editor)) = thread_store
+                        .and_then(|thread_store| thread_store.upgrade())
+                        .zip(editor.upgrade())
+                    {
+                        let resources = cx
+                            .update(|cx| {
+                                let context_server_manager =
+                                    thread_store.read(cx).context_server_manager();
+                                super::resource_context_picker::search_resources(
+                                    query,
+                                    context_server_manager,
+                                    cx,
+                                )
+                            })?
+                            .await;
+                        for resource in resources {
+                            completions.push(Self::completion_for_resource(
+                                resource,
+                                excerpt_id,
+                                source_range.clone(),
+                                editor.clone(),
+                                context_store.clone(),
+                            ));
+                        }
+                    }
+                }
                 None => {
                     cx.update(|cx| {
                         if let Some(editor) = editor.upgrade() {
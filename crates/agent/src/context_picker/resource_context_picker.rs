@@ -0,0 +1,307 @@
+use std::sync::Arc;
+
+use context_server::manager::ContextServerManager;
+use context_server::protocol::ServerCapability;
+use futures::future::join_all;
+use fuzzy::StringMatchCandidate;
+use gpui::{App, DismissEvent, Entity, FocusHandle, Focusable, Task, WeakEntity};
+use picker::{Picker, PickerDelegate};
+use ui::{ListItem, prelude::*};
+use url::Url;
+use util::ResultExt as _;
+
+use crate::context::ContextResourceId;
+use crate::context_picker::{ConfirmBehavior, ContextPicker};
+use crate::context_store::ContextStore;
+use crate::thread_store::ThreadStore;
+
+pub struct ResourceContextPicker {
+    picker: Entity<Picker<ResourceContextPickerDelegate>>,
+}
+
+impl ResourceContextPicker {
+    pub fn new(
+        thread_store: WeakEntity<ThreadStore>,
+        context_picker: WeakEntity<ContextPicker>,
+        context_store: WeakEntity<ContextStore>,
+        confirm_behavior: ConfirmBehavior,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let delegate = ResourceContextPickerDelegate::new(
+            thread_store,
+            context_picker,
+            context_store,
+            confirm_behavior,
+        );
+        let picker = cx.new(|cx| Picker::uniform_list(delegate, window, cx));
+
+        ResourceContextPicker { picker }
+    }
+}
+
+impl Focusable for ResourceContextPicker {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for ResourceContextPicker {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        self.picker.clone()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ResourceContextEntry {
+    pub server_id: Arc<str>,
+    pub uri: Url,
+    pub name: SharedString,
+}
+
+impl ResourceContextEntry {
+    pub fn resource_id(&self) -> ContextResourceId {
+        ContextResourceId {
+            server_id: self.server_id.clone(),
+            uri: self.uri.clone(),
+        }
+    }
+}
+
+pub struct ResourceContextPickerDelegate {
+    thread_store: WeakEntity<ThreadStore>,
+    context_picker: WeakEntity<ContextPicker>,
+    context_store: WeakEntity<ContextStore>,
+    confirm_behavior: ConfirmBehavior,
+    matches: Vec<ResourceContextEntry>,
+    selected_index: usize,
+}
+
+impl ResourceContextPickerDelegate {
+    pub fn new(
+        thread_store: WeakEntity<ThreadStore>,
+        context_picker: WeakEntity<ContextPicker>,
+        context_store: WeakEntity<ContextStore>,
+        confirm_behavior: ConfirmBehavior,
+    ) -> Self {
+        ResourceContextPickerDelegate {
+            thread_store,
+            context_picker,
+            context_store,
+            confirm_behavior,
+            matches: Vec::new(),
+            selected_index: 0,
+        }
+    }
+}
+
+impl PickerDelegate for ResourceContextPickerDelegate {
+    type ListItem = ListItem;
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn no_matches_text(&self, _window: &mut Window, _cx: &mut App) -> Option<SharedString> {
+        Some("No resources found in the running context servers".into())
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _cx: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        "Search resources…".into()
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let Some(thread_store) = self.thread_store.upgrade() else {
+            return Task::ready(());
+        };
+
+        let context_server_manager = thread_store.read(cx).context_server_manager();
+        let search_task = search_resources(query, context_server_manager, cx);
+        cx.spawn_in(window, async move |this, cx| {
+            let matches = search_task.await;
+            this.update(cx, |this, cx| {
+                this.delegate.matches = matches;
+                this.delegate.selected_index = 0;
+                cx.notify();
+            })
+            .ok();
+        })
+    }
+
+    fn confirm(&mut self, _secondary: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(entry) = self.matches.get(self.selected_index).cloned() else {
+            return;
+        };
+
+        let Some(context_store) = self.context_store.upgrade() else {
+            return;
+        };
+
+        let add_resource_task = context_store.update(cx, |context_store, cx| {
+            context_store.add_resource(entry.server_id, entry.uri, entry.name, true, cx)
+        });
+
+        cx.spawn_in(window, async move |this, cx| {
+            add_resource_task.await?;
+            this.update_in(cx, |this, window, cx| {
+                match this.delegate.confirm_behavior {
+                    ConfirmBehavior::KeepOpen => {}
+                    ConfirmBehavior::Close => this.delegate.dismissed(window, cx),
+                }
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn dismissed(&mut self, _window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        self.context_picker
+            .update(cx, |_, cx| {
+                cx.emit(DismissEvent);
+            })
+            .ok();
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let resource = &self.matches[ix];
+
+        Some(ListItem::new(ix).inset(true).toggle_state(selected).child(
+            render_resource_context_entry(resource, self.context_store.clone(), cx),
+        ))
+    }
+}
+
+pub fn render_resource_context_entry(
+    resource: &ResourceContextEntry,
+    context_store: WeakEntity<ContextStore>,
+    cx: &App,
+) -> Div {
+    let added = context_store.upgrade().map_or(false, |context_store| {
+        context_store
+            .read(cx)
+            .includes_resource(&resource.resource_id())
+            .is_some()
+    });
+
+    h_flex()
+        .gap_1p5()
+        .w_full()
+        .justify_between()
+        .child(
+            h_flex()
+                .gap_1p5()
+                .max_w_72()
+                .child(
+                    Icon::new(IconName::Server)
+                        .size(IconSize::XSmall)
+                        .color(Color::Muted),
+                )
+                .child(Label::new(resource.name.clone()).truncate())
+                .child(
+                    Label::new(resource.server_id.to_string())
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                ),
+        )
+        .when(added, |el| {
+            el.child(
+                h_flex()
+                    .gap_1()
+                    .child(
+                        Icon::new(IconName::Check)
+                            .size(IconSize::Small)
+                            .color(Color::Success),
+                    )
+                    .child(Label::new("Added").size(LabelSize::Small)),
+            )
+        })
+}
+
+/// Lists the resources of every running context server, and fuzzy matches them against the query.
+pub(crate) fn search_resources(
+    query: String,
+    context_server_manager: Entity<ContextServerManager>,
+    cx: &mut App,
+) -> Task<Vec<ResourceContextEntry>> {
+    let servers = context_server_manager.read(cx).running_servers();
+
+    let executor = cx.background_executor().clone();
+    cx.background_spawn(async move {
+        let list_tasks = servers.into_iter().filter_map(|server| {
+            let client = server.client()?;
+            if !client.capable(ServerCapability::Resources) {
+                return None;
+            }
+
+            Some(async move {
+                let response = client.list_resources().await.log_err()?;
+                Some(
+                    response
+                        .resources
+                        .into_iter()
+                        .map(|resource| ResourceContextEntry {
+                            server_id: server.id(),
+                            uri: resource.uri,
+                            name: resource.name.into(),
+                        })
+                        .collect::<Vec<_>>(),
+                )
+            })
+        });
+        let resources = join_all(list_tasks)
+            .await
+            .into_iter()
+            .flatten()
+            .flatten()
+            .collect::<Vec<_>>();
+
+        if query.is_empty() {
+            resources
+        } else {
+            let candidates = resources
+                .iter()
+                .enumerate()
+                .map(|(id, resource)| {
+                    StringMatchCandidate::new(id, &format!("{} {}", resource.name, resource.uri))
+                })
+                .collect::<Vec<_>>();
+            let matches = fuzzy::match_strings(
+                &candidates,
+                &query,
+                false,
+                100,
+                &Default::default(),
+                executor,
+            )
+            .await;
+
+            matches
+                .into_iter()
+                .map(|mat| resources[mat.candidate_id].clone())
+                .collect()
+        }
+    })
+}
@@ -1,23 +1,29 @@
+use std::fmt::Write as _;
+use std::mem;
 use std::ops::Range;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use anyhow::{Context as _, Result, anyhow};
 use collections::{BTreeMap, HashMap, HashSet};
+use context_server::manager::{ContextServer, ContextServerManager};
+use context_server::types::ResourceContentsType;
 use futures::future::join_all;
 use futures::{self, Future, FutureExt, future};
-use gpui::{App, AppContext as _, Context, Entity, SharedString, Task, WeakEntity};
+use gpui::{App, AppContext as _, Context, Entity, SharedString, Subscription, Task, WeakEntity};
 use language::{Buffer, File};
 use project::{ProjectItem, ProjectPath, Worktree};
 use rope::Rope;
 use text::{Anchor, BufferId, OffsetRangeExt};
+use url::Url;
 use util::{ResultExt as _, maybe};
 use workspace::Workspace;
 
 use crate::ThreadStore;
 use crate::context::{
-    AssistantContext, ContextBuffer, ContextId, ContextSymbol, ContextSymbolId, DirectoryContext,
-    FetchedUrlContext, FileContext, SymbolContext, ThreadContext,
+    AssistantContext, ContextBuffer, ContextId, ContextResourceId, ContextSymbol, ContextSymbolId,
+    DirectoryContext, FetchedUrlContext, FileContext, ResourceContext, SymbolContext,
+    ThreadContext,
 };
 use crate::context_strip::SuggestedContext;
 use crate::thread::{Thread, ThreadId};
@@ -36,6 +42,8 @@ pub struct ContextStore {
     threads: HashMap<ThreadId, ContextId>,
     thread_summary_tasks: Vec<Task<()>>,
     fetched_urls: HashMap<String, ContextId>,
+    resources: HashMap<ContextResourceId, ContextId>,
+    context_server_subscription: Option<Subscription>,
 }
 
 impl ContextStore {
@@ -56,6 +64,8 @@ impl ContextStore {
             threads: HashMap::default(),
             thread_summary_tasks: Vec::new(),
             fetched_urls: HashMap::default(),
+            resources: HashMap::default(),
+            context_server_subscription: None,
         }
     }
 
@@ -67,12 +77,15 @@ impl ContextStore {
         self.context().iter().find(|context| context.id() == id)
     }
 
-    pub fn clear(&mut self) {
+    pub fn clear(&mut self, cx: &mut Context<Self>) {
         self.context.clear();
         self.files.clear();
         self.directories.clear();
         self.threads.clear();
         self.fetched_urls.clear();
+        for resource_id in mem::take(&mut self.resources).into_keys() {
+            self.unsubscribe_resource(resource_id, cx);
+        }
     }
 
     pub fn add_file_from_path(
@@ -98,11 +111,11 @@ impl ContextStore {
             let buffer = open_buffer_task.await?;
             let buffer_id = this.update(cx, |_, cx| buffer.read(cx).remote_id())?;
 
-            let already_included = this.update(cx, |this, _cx| {
+            let already_included = this.update(cx, |this, cx| {
                 match this.will_include_buffer(buffer_id, &project_path.path) {
                     Some(FileInclusion::Direct(context_id)) => {
                         if remove_if_exists {
-                            this.remove_context(context_id);
+                            this.remove_context(context_id, cx);
                         }
                         true
                     }
@@ -171,7 +184,7 @@ impl ContextStore {
         let already_included = match self.includes_directory(&project_path.path) {
             Some(FileInclusion::Direct(context_id)) => {
                 if remove_if_exists {
-                    self.remove_context(context_id);
+                    self.remove_context(context_id, cx);
                 }
                 true
             }
@@ -286,7 +299,7 @@ impl ContextStore {
 
             if let Some(id) = matching_symbol_id {
                 if remove_if_exists {
-                    self.remove_context(id);
+                    self.remove_context(id, cx);
                 }
                 return Task::ready(Ok(false));
             }
@@ -338,7 +351,7 @@ impl ContextStore {
     ) {
         if let Some(context_id) = self.includes_thread(&thread.read(cx).id()) {
             if remove_if_exists {
-                self.remove_context(context_id);
+                self.remove_context(context_id, cx);
             }
         } else {
             self.insert_thread(thread, cx);
@@ -402,6 +415,164 @@ impl ContextStore {
             }));
     }
 
+    pub fn add_resource(
+        &mut self,
+        server_id: Arc<str>,
+        uri: Url,
+        name: SharedString,
+        remove_if_exists: bool,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let resource_id = ContextResourceId { server_id, uri };
+        if let Some(context_id) = self.includes_resource(&resource_id) {
+            if remove_if_exists {
+                self.remove_context(context_id, cx);
+            }
+            return Task::ready(Ok(()));
+        }
+
+        let Some(context_server_manager) = self.context_server_manager(cx) else {
+            return Task::ready(Err(anyhow!("context servers are not available")));
+        };
+        let Some(server) = context_server_manager
+            .read(cx)
+            .get_server(&resource_id.server_id)
+        else {
+            return Task::ready(Err(anyhow!(
+                "context server {} is not running",
+                resource_id.server_id
+            )));
+        };
+
+        // Keep the resource up to date with the updates reported by the server.
+        if self.context_server_subscription.is_none() {
+            self.context_server_subscription =
+                Some(cx.subscribe(&context_server_manager, Self::handle_context_server_event));
+        }
+
+        cx.spawn(async move |this, cx| {
+            let text = read_resource_text(&server, &resource_id.uri).await?;
+            this.update(cx, |this, _cx| {
+                this.insert_resource(resource_id.clone(), name, text);
+            })?;
+
+            if let Some(client) = server
+                .client()
+                .filter(|client| client.can_subscribe_to_resources())
+            {
+                client
+                    .subscribe_resource(resource_id.uri.clone())
+                    .await
+                    .log_err();
+            }
+
+            anyhow::Ok(())
+        })
+    }
+
+    fn insert_resource(
+        &mut self,
+        resource_id: ContextResourceId,
+        name: SharedString,
+        text: SharedString,
+    ) {
+        let id = self.next_context_id.post_inc();
+        self.resources.insert(resource_id.clone(), id);
+        self.context
+            .push(AssistantContext::Resource(ResourceContext {
+                id,
+                resource_id,
+                name,
+                text,
+            }));
+    }
+
+    fn context_server_manager(&self, cx: &App) -> Option<Entity<ContextServerManager>> {
+        let thread_store = self.thread_store.as_ref()?.upgrade()?;
+        Some(thread_store.read(cx).context_server_manager())
+    }
+
+    fn handle_context_server_event(
+        &mut self,
+        context_server_manager: Entity<ContextServerManager>,
+        event: &context_server::manager::Event,
+        cx: &mut Context<Self>,
+    ) {
+        match event {
+            context_server::manager::Event::ResourceUpdated { server_id, uri } => {
+                let resource_id = ContextResourceId {
+                    server_id: server_id.clone(),
+                    uri: uri.clone(),
+                };
+                self.refresh_resource(resource_id, &context_server_manager, false, cx);
+            }
+            // A server that was reconnected doesn't know about our subscriptions anymore,
+            // and its resources may have changed while it was disconnected.
+            context_server::manager::Event::ServerStarted { server_id } => {
+                let resource_ids = self
+                    .resources
+                    .keys()
+                    .filter(|resource_id| resource_id.server_id == *server_id)
+                    .cloned()
+                    .collect::<Vec<_>>();
+                for resource_id in resource_ids {
+                    self.refresh_resource(resource_id, &context_server_manager, true, cx);
+                }
+            }
+            context_server::manager::Event::ServerStopped { .. } => {}
+        }
+    }
+
+    /// Reads a resource in the context again, subscribing to its updates again if `resubscribe`
+    /// is set.
+    fn refresh_resource(
+        &self,
+        resource_id: ContextResourceId,
+        context_server_manager: &Entity<ContextServerManager>,
+        resubscribe: bool,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(AssistantContext::Resource(resource_context)) = self
+            .includes_resource(&resource_id)
+            .and_then(|context_id| self.context_for_id(context_id))
+        else {
+            return;
+        };
+        let Some(server) = context_server_manager
+            .read(cx)
+            .get_server(&resource_id.server_id)
+        else {
+            return;
+        };
+
+        let id = resource_context.id;
+        let name = resource_context.name.clone();
+        cx.spawn(async move |this, cx| {
+            let text = read_resource_text(&server, &resource_id.uri).await?;
+            let is_included = this.update(cx, |this, _cx| {
+                this.replace_context(AssistantContext::Resource(ResourceContext {
+                    id,
+                    resource_id: resource_id.clone(),
+                    name,
+                    text,
+                }));
+                this.includes_resource(&resource_id).is_some()
+            })?;
+
+            if resubscribe && is_included {
+                if let Some(client) = server
+                    .client()
+                    .filter(|client| client.can_subscribe_to_resources())
+                {
+                    client.subscribe_resource(resource_id.uri).await?;
+                }
+            }
+
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
     pub fn accept_suggested_context(
         &mut self,
         suggested: &SuggestedContext,
@@ -426,7 +597,7 @@ impl ContextStore {
         Task::ready(Ok(()))
     }
 
-    pub fn remove_context(&mut self, id: ContextId) {
+    pub fn remove_context(&mut self, id: ContextId, cx: &mut Context<Self>) {
         let Some(ix) = self.context.iter().position(|context| context.id() == id) else {
             return;
         };
@@ -457,9 +628,27 @@ impl ContextStore {
             AssistantContext::Thread(_) => {
                 self.threads.retain(|_, context_id| *context_id != id);
             }
+            AssistantContext::Resource(resource) => {
+                self.resources.retain(|_, context_id| *context_id != id);
+                self.unsubscribe_resource(resource.resource_id, cx);
+            }
         }
     }
 
+    /// Stops the updates of a resource that is no longer in the context.
+    fn unsubscribe_resource(&self, resource_id: ContextResourceId, cx: &mut Context<Self>) {
+        let Some(client) = self
+            .context_server_manager(cx)
+            .and_then(|manager| manager.read(cx).get_server(&resource_id.server_id))
+            .and_then(|server| server.client())
+            .filter(|client| client.can_subscribe_to_resources())
+        else {
+            return;
+        };
+        cx.spawn(async move |_, _| client.unsubscribe_resource(resource_id.uri).await)
+            .detach_and_log_err(cx);
+    }
+
     /// Returns whether the buffer is already included directly in the context, or if it will be
     /// included in the context via a directory. Directory inclusion is based on paths rather than
     /// buffer IDs as the directory will be re-scanned.
@@ -538,6 +727,10 @@ impl ContextStore {
         self.fetched_urls.get(url).copied()
     }
 
+    pub fn includes_resource(&self, resource_id: &ContextResourceId) -> Option<ContextId> {
+        self.resources.get(resource_id).copied()
+    }
+
     /// Replaces the context that matches the ID of the new context, if any match.
     fn replace_context(&mut self, new_context: AssistantContext) {
         let id = new_context.id();
@@ -560,7 +753,8 @@ impl ContextStore {
                 AssistantContext::Directory(_)
                 | AssistantContext::Symbol(_)
                 | AssistantContext::FetchedUrl(_)
-                | AssistantContext::Thread(_) => None,
+                | AssistantContext::Thread(_)
+                | AssistantContext::Resource(_) => None,
             })
             .collect()
     }
@@ -692,6 +886,30 @@ fn to_fenced_codeblock(path: &Path, content: Rope) -> SharedString {
     buffer.into()
 }
 
+async fn read_resource_text(server: &ContextServer, uri: &Url) -> Result<SharedString> {
+    let client = server
+        .client()
+        .with_context(|| format!("context server {} is not running", server.id()))?;
+    let response = client.read_resource(uri.clone()).await?;
+
+    let mut text = String::new();
+    for contents in response.contents {
+        match contents {
+            ResourceContentsType::Text(contents) => {
+                text.push_str(&contents.text);
+                if !text.ends_with('\n') {
+                    text.push('\n');
+                }
+            }
+            ResourceContentsType::Blob(contents) => {
+                writeln!(text, "(binary contents of {} omitted)", contents.uri)?;
+            }
+        }
+    }
+
+    Ok(text.into())
+}
+
 fn collect_files_in_path(worktree: &Worktree, path: &Path) -> Vec<Arc<Path>> {
     let mut files = Vec::new();
 
@@ -759,6 +977,8 @@ pub fn refresh_context_store_text(
                 // and doing the caching properly could be tricky (unless it's already handled by
                 // the HttpClient?).
                 AssistantContext::FetchedUrl(_) => {}
+                // Resources are refreshed when their context server reports an update.
+                AssistantContext::Resource(_) => {}
             }
 
             None
@@ -923,3 +1143,269 @@ fn refresh_context_symbol(
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use assistant_settings::AssistantSettings;
+    use context_server::ContextServerSettings;
+    use futures::channel::mpsc;
+    use futures::{AsyncReadExt as _, StreamExt as _, TryStreamExt as _};
+    use gpui::TestAppContext;
+    use http_client::{AsyncBody, FakeHttpClient, Method, Response};
+    use language_model::{LanguageModelRequestMessage, Role};
+    use parking_lot::Mutex;
+    use project::{FakeFs, Project};
+    use prompt_store::PromptBuilder;
+    use serde_json::json;
+    use settings::{Settings as _, SettingsStore};
+    use util::path;
+
+    use super::*;
+    use crate::context::attach_context_to_message;
+    use crate::thread_store;
+
+    /// The state of a context server that serves a single resource over streamable HTTP.
+    struct FakeContextServer {
+        /// Bumped when the server restarts, which invalidates the sessions it handed out.
+        session: u32,
+        text: String,
+        /// The subscription requests the server received, along with their session.
+        subscription_requests: Vec<(String, String)>,
+        event_stream: Option<mpsc::UnboundedSender<String>>,
+    }
+
+    impl FakeContextServer {
+        fn send_resource_updated(&self, uri: &str) {
+            let notification = json!({
+                "jsonrpc": "2.0",
+                "method": "notifications/resources/updated",
+                "params": { "uri": uri },
+            });
+            self.event_stream
+                .as_ref()
+                .unwrap()
+                .unbounded_send(format!("data: {notification}\n\n"))
+                .unwrap();
+        }
+    }
+
+    fn fake_context_server_client(
+        server: Arc<Mutex<FakeContextServer>>,
+    ) -> Arc<dyn http_client::HttpClient> {
+        FakeHttpClient::create(move |request| {
+            let server = server.clone();
+            async move {
+                let session_id = request
+                    .headers()
+                    .get("Mcp-Session-Id")
+                    .and_then(|value| value.to_str().ok())
+                    .map(str::to_string);
+                let response = Response::builder();
+                match request.method().clone() {
+                    Method::GET => {
+                        let (events_tx, events_rx) = mpsc::unbounded::<String>();
+                        server.lock().event_stream = Some(events_tx);
+                        let events =
+                            events_rx.map(|event| Ok::<_, std::io::Error>(event.into_bytes()));
+                        return Ok(response
+                            .header("Content-Type", "text/event-stream")
+                            .body(AsyncBody::from_reader(events.into_async_read()))?);
+                    }
+                    Method::POST => {}
+                    _ => return Ok(response.status(202).body(AsyncBody::empty())?),
+                }
+
+                let mut body = String::new();
+                request.into_body().read_to_string(&mut body).await?;
+                let message = serde_json::from_str::<serde_json::Value>(&body)?;
+                let method = message["method"].as_str().unwrap_or_default();
+
+                let mut server = server.lock();
+                let current_session_id = format!("session-{}", server.session);
+                let mut response = response.header("Content-Type", "application/json");
+                let result = if method == "initialize" {
+                    response = response.header("Mcp-Session-Id", &current_session_id);
+                    json!({
+                        "protocolVersion": "2024-11-05",
+                        "capabilities": { "resources": { "subscribe": true } },
+                        "serverInfo": { "name": "fake", "version": "1" },
+                    })
+                } else if session_id.as_ref() != Some(&current_session_id) {
+                    return Ok(response.status(404).body(AsyncBody::empty())?);
+                } else if message.get("id").is_none() {
+                    return Ok(response.status(202).body(AsyncBody::empty())?);
+                } else if method == "resources/read" {
+                    json!({
+                        "contents": [{ "uri": message["params"]["uri"], "text": server.text }],
+                    })
+                } else {
+                    if method.starts_with("resources/") {
+                        server
+                            .subscription_requests
+                            .push((method.to_string(), current_session_id));
+                    }
+                    json!({})
+                };
+
+                let response_body =
+                    json!({ "jsonrpc": "2.0", "id": message["id"], "result": result });
+                Ok(response.body(response_body.to_string().into())?)
+            }
+        })
+    }
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let store = SettingsStore::test(cx);
+            cx.set_global(store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            client::init_settings(cx);
+            language::init(cx);
+            Project::init_settings(cx);
+            workspace::init_settings(cx);
+            editor::init_settings(cx);
+            AssistantSettings::register(cx);
+            ContextServerSettings::register(cx);
+            thread_store::init(cx);
+        });
+    }
+
+    fn context_text(context_store: &Entity<ContextStore>, cx: &mut TestAppContext) -> String {
+        context_store.read_with(cx, |context_store, cx| {
+            let mut message = LanguageModelRequestMessage {
+                role: Role::User,
+                content: Vec::new(),
+                cache: false,
+            };
+            attach_context_to_message(&mut message, context_store.context().iter(), cx);
+            message.string_contents()
+        })
+    }
+
+    #[gpui::test]
+    async fn test_context_server_resources(cx: &mut TestAppContext) {
+        init_test(cx);
+        let server = Arc::new(Mutex::new(FakeContextServer {
+            session: 1,
+            text: "first".into(),
+            subscription_requests: Vec::new(),
+            event_stream: None,
+        }));
+        cx.update(|cx| {
+            cx.set_http_client(fake_context_server_client(server.clone()));
+            cx.update_global::<SettingsStore, _>(|store, cx| {
+                store
+                    .set_user_settings(
+                        r#"{"context_servers": {"fake": {"http": {"url": "http://localhost:8080/mcp"}}}}"#,
+                        cx,
+                    )
+                    .unwrap();
+            });
+        });
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/test"), json!({})).await;
+        let project = Project::test(fs, [path!("/test").as_ref()], cx).await;
+        let window = cx.add_window(|window, cx| Workspace::test_new(project.clone(), window, cx));
+        let workspace = window.root(cx).unwrap();
+        let thread_store = cx.update(|cx| {
+            ThreadStore::new(
+                project.clone(),
+                Arc::default(),
+                Arc::new(PromptBuilder::new(None).unwrap()),
+                cx,
+            )
+            .unwrap()
+        });
+        let context_store =
+            cx.new(|_| ContextStore::new(workspace.downgrade(), Some(thread_store.downgrade())));
+        cx.run_until_parked();
+
+        // Attaching a resource adds its contents to the context and subscribes to its updates.
+        let uri = "file:///notes.md";
+        context_store
+            .update(cx, |context_store, cx| {
+                context_store.add_resource(
+                    "fake".into(),
+                    Url::parse(uri).unwrap(),
+                    "notes.md".into(),
+                    false,
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        let text = context_text(&context_store, cx);
+        assert!(
+            text.contains("<resources>\nfile:///notes.md\nfirst\n"),
+            "unexpected context {text:?}"
+        );
+        assert_eq!(
+            server.lock().subscription_requests,
+            [("resources/subscribe".to_string(), "session-1".to_string())]
+        );
+
+        // An update reported by the server refreshes the resource.
+        server.lock().text = "second".into();
+        server.lock().send_resource_updated(uri);
+        cx.run_until_parked();
+        assert!(context_text(&context_store, cx).contains("\nsecond\n"));
+
+        // When the server restarts, the connection to it is lost on the next request. Once
+        // it is reconnected, the resource is refreshed and subscribed to again.
+        {
+            let mut server = server.lock();
+            server.session += 1;
+            server.text = "third".into();
+            server.send_resource_updated(uri);
+        }
+        cx.run_until_parked();
+        cx.executor().advance_clock(Duration::from_secs(5));
+        cx.run_until_parked();
+        assert!(context_text(&context_store, cx).contains("\nthird\n"));
+        assert_eq!(
+            server.lock().subscription_requests,
+            [
+                ("resources/subscribe".to_string(), "session-1".to_string()),
+                ("resources/subscribe".to_string(), "session-2".to_string()),
+            ]
+        );
+
+        // Removing the resource from the context, or clearing it, ends the subscription.
+        let context_id = context_store.read_with(cx, |context_store, _| {
+            context_store.context().first().unwrap().id()
+        });
+        context_store.update(cx, |context_store, cx| {
+            context_store.remove_context(context_id, cx)
+        });
+        cx.run_until_parked();
+        assert!(!context_text(&context_store, cx).contains("<resources>"));
+
+        context_store
+            .update(cx, |context_store, cx| {
+                context_store.add_resource(
+                    "fake".into(),
+                    Url::parse(uri).unwrap(),
+                    "notes.md".into(),
+                    false,
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+        context_store.update(cx, |context_store, cx| context_store.clear(cx));
+        cx.run_until_parked();
+        assert!(context_store.read_with(cx, |context_store, _| context_store.context().is_empty()));
+        assert_eq!(
+            server.lock().subscription_requests[2..],
+            [
+                ("resources/unsubscribe".to_string(), "session-2".to_string()),
+                ("resources/subscribe".to_string(), "session-2".to_string()),
+                ("resources/unsubscribe".to_string(), "session-2".to_string()),
+            ]
+        );
+    }
+}
@@ -4,8 +4,8 @@ use std::ops::Range;
 use std::sync::Arc;
 
 use anyhow::{Context as _, Result, anyhow};
-use assistant_settings::AssistantSettings;
-use assistant_tool::{ActionLog, Tool, ToolWorkingSet};
+use assistant_settings::{AssistantSettings, ToolPermissionAction};
+use assistant_tool::{ActionLog, Tool, ToolResult, ToolWorkingSet, evaluate_tool_permission};
 use chrono::{DateTime, Utc};
 use collections::{BTreeMap, HashMap, HashSet};
 use fs::Fs;
@@ -26,17 +26,24 @@ use prompt_store::{
 };
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use settings::Settings;
+use settings::Settings as _;
 use util::{ResultExt as _, TryFutureExt as _, maybe, post_inc};
 use uuid::Uuid;
 
 use crate::context::{AssistantContext, ContextId, attach_context_to_message};
 use crate::thread_store::{
-    SerializedMessage, SerializedMessageSegment, SerializedThread, SerializedToolResult,
-    SerializedToolUse,
+    ExportedMessageContext, ExportedModel, ExportedThread, SerializedMessage,
+    SerializedMessageSegment, SerializedThread, SerializedToolResult, SerializedToolUse,
 };
 use crate::tool_use::{PendingToolUse, ToolUse, ToolUseState, USING_TOOL_MARKER};
 
+/// Introduces the summary that replaces the compacted messages of a thread in requests.
+const COMPACTION_SUMMARY_HEADER: &str =
+    "The earlier part of this conversation was summarized to save space. Here is the summary:";
+
+/// The number of most recent messages that compaction sends as they are, rather than summarized.
+const COMPACTION_KEPT_MESSAGES: usize = 4;
+
 #[derive(Debug, Clone, Copy)]
 pub enum RequestKind {
     Chat,
@@ -170,6 +177,13 @@ pub struct GitState {
     pub diff: Option<String>,
 }
 
+/// The message of another thread that a [`Thread`] was forked from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ThreadForkPoint {
+    pub thread_id: ThreadId,
+    pub message_id: MessageId,
+}
+
 #[derive(Clone)]
 pub struct ThreadCheckpoint {
     message_id: MessageId,
@@ -214,6 +228,15 @@ pub enum DetailedSummaryState {
     },
 }
 
+/// A point in a thread up to which the messages were replaced by a summary when
+/// talking to the model, to keep the thread within the model's context window.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThreadCompaction {
+    /// The last message covered by the summary.
+    pub message_id: MessageId,
+    pub summary: SharedString,
+}
+
 /// A thread of conversation with the LLM.
 pub struct Thread {
     id: ThreadId,
@@ -239,6 +262,10 @@ pub struct Thread {
     initial_project_snapshot: Shared<Task<Option<Arc<ProjectSnapshot>>>>,
     cumulative_token_usage: TokenUsage,
     feedback: Option<ThreadFeedback>,
+    forked_from: Option<ThreadForkPoint>,
+    compactions: Vec<ThreadCompaction>,
+    pending_compaction: Option<Task<()>>,
+    last_request_token_usage: TokenUsage,
 }
 
 impl Thread {
@@ -277,6 +304,10 @@ impl Thread {
             },
             cumulative_token_usage: TokenUsage::default(),
             feedback: None,
+            forked_from: None,
+            compactions: Vec::new(),
+            pending_compaction: None,
+            last_request_token_usage: TokenUsage::default(),
         }
     }
 
@@ -339,9 +370,88 @@ impl Thread {
             initial_project_snapshot: Task::ready(serialized.initial_project_snapshot).shared(),
             cumulative_token_usage: serialized.cumulative_token_usage,
             feedback: None,
+            forked_from: serialized.forked_from,
+            compactions: serialized.compactions,
+            pending_compaction: None,
+            last_request_token_usage: TokenUsage::default(),
         }
     }
 
+    /// Creates a new thread that shares this thread's messages up to and including the
+    /// given message, along with their context, tool uses and checkpoints.
+    pub fn fork(&self, message_id: MessageId, cx: &mut Context<Self>) -> Option<Entity<Thread>> {
+        let message_ix = self
+            .messages
+            .iter()
+            .position(|message| message.id == message_id)?;
+        let messages = self.messages[..=message_ix].to_vec();
+        let message_ids = messages
+            .iter()
+            .map(|message| message.id)
+            .collect::<HashSet<_>>();
+
+        let context_by_message = self
+            .context_by_message
+            .iter()
+            .filter(|(id, _)| message_ids.contains(id))
+            .map(|(id, context_ids)| (*id, context_ids.clone()))
+            .collect::<HashMap<_, _>>();
+        let context = context_by_message
+            .values()
+            .flatten()
+            .filter_map(|context_id| {
+                let context = self.context.get(context_id)?;
+                Some((*context_id, context.clone()))
+            })
+            .collect();
+        let checkpoints_by_message = self
+            .checkpoints_by_message
+            .iter()
+            .filter(|(id, _)| message_ids.contains(id))
+            .map(|(id, checkpoint)| (*id, checkpoint.clone()))
+            .collect();
+
+        Some(cx.new(|cx| {
+            Self {
+                id: ThreadId::new(),
+                updated_at: Utc::now(),
+                summary: self.summary.clone(),
+                pending_summary: Task::ready(None),
+                detailed_summary_state: DetailedSummaryState::NotGenerated,
+                messages,
+                next_message_id: self.next_message_id,
+                context,
+                context_by_message,
+                system_prompt_context: None,
+                checkpoints_by_message,
+                completion_count: 0,
+                pending_completions: Vec::new(),
+                project: self.project.clone(),
+                prompt_builder: self.prompt_builder.clone(),
+                tools: self.tools.clone(),
+                tool_use: self.tool_use.fork(&message_ids),
+                action_log: cx.new(|_| ActionLog::new()),
+                last_restore_checkpoint: None,
+                pending_checkpoint: None,
+                initial_project_snapshot: self.initial_project_snapshot.clone(),
+                cumulative_token_usage: TokenUsage::default(),
+                feedback: None,
+                forked_from: Some(ThreadForkPoint {
+                    thread_id: self.id.clone(),
+                    message_id,
+                }),
+                compactions: self
+                    .compactions
+                    .iter()
+                    .filter(|compaction| compaction.message_id <= message_id)
+                    .cloned()
+                    .collect(),
+                pending_compaction: None,
+                last_request_token_usage: TokenUsage::default(),
+            }
+        }))
+    }
+
     pub fn id(&self) -> &ThreadId {
         &self.id
     }
@@ -354,6 +464,16 @@ impl Thread {
         self.updated_at
     }
 
+    /// The points up to which this thread's messages were summarized, oldest first.
+    pub fn compactions(&self) -> &[ThreadCompaction] {
+        &self.compactions
+    }
+
+    /// The message this thread was forked from, if it was created by [`Thread::fork`].
+    pub fn forked_from(&self) -> Option<&ThreadForkPoint> {
+        self.forked_from.as_ref()
+    }
+
     pub fn touch_updated_at(&mut self) {
         self.updated_at = Utc::now();
     }
@@ -394,7 +514,9 @@ impl Thread {
     }
 
     pub fn is_generating(&self) -> bool {
-        !self.pending_completions.is_empty() || !self.all_tools_finished()
+        !self.pending_completions.is_empty()
+            || self.pending_compaction.is_some()
+            || !self.all_tools_finished()
     }
 
     pub fn tools(&self) -> &Arc<ToolWorkingSet> {
@@ -533,6 +655,7 @@ impl Thread {
             self.context_by_message.remove(&deleted_message.id);
             self.checkpoints_by_message.remove(&deleted_message.id);
         }
+        self.discard_compactions_covering(message_id);
         cx.notify();
     }
 
@@ -623,6 +746,7 @@ impl Thread {
         };
         message.role = new_role;
         message.segments = new_segments;
+        self.discard_compactions_covering(id);
         self.touch_updated_at();
         cx.emit(ThreadEvent::MessageEdited(id));
         true
@@ -634,11 +758,18 @@ impl Thread {
         };
         self.messages.remove(index);
         self.context_by_message.remove(&id);
+        self.discard_compactions_covering(id);
         self.touch_updated_at();
         cx.emit(ThreadEvent::MessageDeleted(id));
         true
     }
 
+    /// Discards the summaries that include the given message, as they no longer reflect the thread.
+    fn discard_compactions_covering(&mut self, message_id: MessageId) {
+        self.compactions
+            .retain(|compaction| compaction.message_id < message_id);
+    }
+
     /// Returns the representation of this [`Thread`] in a textual form.
     ///
     /// This is the representation we use when attaching a thread as context to another thread.
@@ -716,6 +847,64 @@ impl Thread {
                 initial_project_snapshot,
                 cumulative_token_usage: this.cumulative_token_usage.clone(),
                 detailed_summary_state: this.detailed_summary_state.clone(),
+                forked_from: this.forked_from.clone(),
+                compactions: this.compactions.clone(),
+            })
+        })
+    }
+
+    /// Serializes the thread into a portable format that can be shared and imported elsewhere.
+    pub fn export(&self, cx: &mut Context<Self>) -> Task<Result<ExportedThread>> {
+        let serialize_task = self.serialize(cx);
+        cx.spawn(async move |this, cx| {
+            let thread = serialize_task.await?;
+            this.read_with(cx, |this, cx| {
+                let model = LanguageModelRegistry::read_global(cx)
+                    .active_model()
+                    .map(|model| ExportedModel {
+                        provider: model.provider_id().0.to_string(),
+                        model: model.id().0.to_string(),
+                    });
+
+                let context = this
+                    .messages()
+                    .filter_map(|message| {
+                        let mut context_message = LanguageModelRequestMessage {
+                            role: Role::User,
+                            content: Vec::new(),
+                            cache: false,
+                        };
+                        attach_context_to_message(
+                            &mut context_message,
+                            this.context_for_message(message.id),
+                            cx,
+                        );
+                        if context_message.content.is_empty() {
+                            return None;
+                        }
+
+                        Some(ExportedMessageContext {
+                            message_id: message.id,
+                            text: context_message.string_contents(),
+                        })
+                    })
+                    .collect();
+
+                let mut checkpoints = this
+                    .checkpoints_by_message
+                    .keys()
+                    .copied()
+                    .collect::<Vec<_>>();
+                checkpoints.sort();
+
+                ExportedThread {
+                    version: ExportedThread::VERSION,
+                    exported_at: Utc::now(),
+                    model,
+                    context,
+                    checkpoints,
+                    thread,
+                }
             })
         })
     }
@@ -836,6 +1025,21 @@ impl Thread {
         model: Arc<dyn LanguageModel>,
         request_kind: RequestKind,
         cx: &mut Context<Self>,
+    ) {
+        // Compact the thread first if it no longer fits comfortably in the model's context
+        // window, and send the request once the summary is ready.
+        if self.should_compact(cx) && self.start_compaction(Some((model.clone(), request_kind)), cx)
+        {
+            return;
+        }
+        self.send_request_to_model(model, request_kind, cx);
+    }
+
+    fn send_request_to_model(
+        &mut self,
+        model: Arc<dyn LanguageModel>,
+        request_kind: RequestKind,
+        cx: &mut Context<Self>,
     ) {
         let mut request = self.to_completion_request(request_kind, cx);
         if model.supports_tools() {
@@ -872,12 +1076,24 @@ impl Thread {
         &self,
         request_kind: RequestKind,
         cx: &App,
+    ) -> LanguageModelRequest {
+        self.to_completion_request_until(request_kind, None, cx)
+    }
+
+    /// Builds a request out of the messages up to and including the given one, or out of all
+    /// messages if none is given.
+    fn to_completion_request_until(
+        &self,
+        request_kind: RequestKind,
+        last_message_id: Option<MessageId>,
+        cx: &App,
     ) -> LanguageModelRequest {
         let mut request = LanguageModelRequest {
             messages: vec![],
             tools: Vec::new(),
             stop: Vec::new(),
             temperature: None,
+            max_tokens: None,
         };
 
         if let Some(system_prompt_context) = self.system_prompt_context.as_ref() {
@@ -897,9 +1113,29 @@ impl Thread {
             log::error!("system_prompt_context not set.")
         }
 
+        let compaction = self.compactions.last();
+        if let Some(compaction) = compaction {
+            request.messages.push(LanguageModelRequestMessage {
+                role: Role::User,
+                content: vec![MessageContent::Text(format!(
+                    "{COMPACTION_SUMMARY_HEADER}\n\n{}",
+                    compaction.summary
+                ))],
+                cache: false,
+            });
+        }
+
         let mut added_context_ids = HashSet::<ContextId>::default();
 
         for message in &self.messages {
+            if last_message_id.is_some_and(|last_message_id| message.id > last_message_id) {
+                break;
+            }
+            // Messages covered by the latest compaction are replaced by its summary.
+            if compaction.is_some_and(|compaction| message.id <= compaction.message_id) {
+                continue;
+            }
+
             let mut request_message = LanguageModelRequestMessage {
                 role: message.role,
                 content: Vec::new(),
@@ -957,7 +1193,7 @@ impl Thread {
 
         // Set a cache breakpoint at the second-to-last message.
         // https://docs.anthropic.com/en/docs/build-with-claude/prompt-caching
-        let breakpoint_index = request.messages.len() - 2;
+        let breakpoint_index = request.messages.len().saturating_sub(2);
         for (index, message) in request.messages.iter_mut().enumerate() {
             message.cache = index == breakpoint_index;
         }
@@ -1052,6 +1288,7 @@ impl Thread {
                                 thread.cumulative_token_usage =
                                     thread.cumulative_token_usage.clone() + token_usage.clone()
                                         - current_token_usage.clone();
+                                thread.last_request_token_usage = token_usage.clone();
                                 current_token_usage = token_usage;
                             }
                             LanguageModelCompletionEvent::Text(chunk) => {
@@ -1329,6 +1566,138 @@ impl Thread {
         Some(task)
     }
 
+    /// Whether the last request used enough of the model's context window for
+    /// the thread to be compacted, if automatic compaction is enabled.
+    fn should_compact(&self, cx: &App) -> bool {
+        let settings = AssistantSettings::get_global(cx);
+        if !settings.auto_compact_threads || self.pending_compaction.is_some() {
+            return false;
+        }
+
+        let Some(model) = LanguageModelRegistry::read_global(cx).active_model() else {
+            return false;
+        };
+
+        let usage = &self.last_request_token_usage;
+        let used_tokens = usage.input_tokens
+            + usage.output_tokens
+            + usage.cache_creation_input_tokens
+            + usage.cache_read_input_tokens;
+
+        used_tokens as f32 >= model.max_token_count() as f32 * settings.auto_compact_threshold
+    }
+
+    /// The last message that compacting the thread would summarize. The most recent messages
+    /// are kept as they are, and so are the tool uses whose results they hold or that are still
+    /// running, as a tool use can't be sent without its results.
+    fn compaction_boundary(&self) -> Option<MessageId> {
+        let mut ix = self
+            .messages
+            .len()
+            .checked_sub(COMPACTION_KEPT_MESSAGES + 1)?;
+        while self.tool_use.message_has_tool_uses(self.messages[ix].id) {
+            ix = ix.checked_sub(1)?;
+        }
+
+        let message_id = self.messages[ix].id;
+        if self
+            .compactions
+            .last()
+            .is_some_and(|compaction| compaction.message_id >= message_id)
+        {
+            return None;
+        }
+        Some(message_id)
+    }
+
+    /// Summarizes the older messages of the thread, so that later requests send the summary
+    /// instead of the messages it covers. The messages themselves are kept in the thread.
+    pub fn compact(&mut self, cx: &mut Context<Self>) {
+        self.start_compaction(None, cx);
+    }
+
+    /// Starts summarizing the older messages of the thread, sending the given request once the
+    /// summary is ready. Returns whether there was anything to summarize.
+    fn start_compaction(
+        &mut self,
+        then_send: Option<(Arc<dyn LanguageModel>, RequestKind)>,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        let Some(last_message_id) = self.compaction_boundary() else {
+            return false;
+        };
+        let Some(provider) = LanguageModelRegistry::read_global(cx).active_provider() else {
+            return false;
+        };
+        let Some(model) = LanguageModelRegistry::read_global(cx).active_model() else {
+            return false;
+        };
+
+        if !provider.is_authenticated(cx) {
+            return false;
+        }
+
+        let mut request =
+            self.to_completion_request_until(RequestKind::Summarize, Some(last_message_id), cx);
+        request.messages.push(LanguageModelRequestMessage {
+            role: Role::User,
+            content: vec![
+                "Summarize this conversation so far, so that the summary can replace it \
+                and the conversation can continue from it. Include:\n\
+                1. The user's requests and the current task\n\
+                2. The files, symbols and commands that were involved\n\
+                3. Key decisions, findings and changes made so far\n\
+                4. Anything that still needs to be done\n\
+                Be thorough, but leave out details that are no longer relevant. \
+                Go straight to the summary, without any preamble."
+                    .into(),
+            ],
+            cache: false,
+        });
+
+        self.pending_compaction = Some(cx.spawn(async move |thread, cx| {
+            let summary = async {
+                let mut messages = model.stream_completion_text(request, &cx).await?;
+                let mut summary = String::new();
+                while let Some(chunk) = messages.stream.next().await {
+                    summary.push_str(&chunk?);
+                }
+                anyhow::Ok(summary)
+            }
+            .await
+            .log_err();
+
+            thread
+                .update(cx, |thread, cx| {
+                    thread.pending_compaction = None;
+
+                    // Skip the summary if its last message was removed while it was generated.
+                    let summary = summary.filter(|summary| {
+                        !summary.trim().is_empty()
+                            && thread
+                                .messages
+                                .iter()
+                                .any(|message| message.id == last_message_id)
+                    });
+                    if let Some(summary) = summary {
+                        thread.compactions.push(ThreadCompaction {
+                            message_id: last_message_id,
+                            summary: summary.into(),
+                        });
+                        thread.last_request_token_usage = TokenUsage::default();
+                        cx.emit(ThreadEvent::Compacted(last_message_id));
+                    }
+
+                    // Send the request even if the summary failed, as it may still fit.
+                    if let Some((model, request_kind)) = then_send {
+                        thread.send_request_to_model(model, request_kind, cx);
+                    }
+                })
+                .log_err();
+        }));
+        true
+    }
+
     pub fn is_generating_detailed_summary(&self) -> bool {
         matches!(
             self.detailed_summary_state,
@@ -1352,26 +1721,50 @@ impl Thread {
 
         for tool_use in pending_tool_uses.iter() {
             if let Some(tool) = self.tools.tool(&tool_use.name, cx) {
-                if tool.needs_confirmation()
-                    && !AssistantSettings::get_global(cx).always_allow_tool_actions
-                {
-                    self.tool_use.confirm_tool_use(
-                        tool_use.id.clone(),
-                        tool_use.ui_text.clone(),
-                        tool_use.input.clone(),
-                        messages.clone(),
-                        tool,
-                    );
-                    cx.emit(ThreadEvent::ToolConfirmationNeeded);
-                } else {
-                    self.run_tool(
-                        tool_use.id.clone(),
-                        tool_use.ui_text.clone(),
-                        tool_use.input.clone(),
-                        &messages,
-                        tool,
-                        cx,
-                    );
+                let permission = evaluate_tool_permission(
+                    tool.as_ref(),
+                    &tool_use.input,
+                    self.project.read(cx),
+                    cx,
+                );
+                match permission.action {
+                    ToolPermissionAction::Allow => {
+                        self.run_tool(
+                            tool_use.id.clone(),
+                            tool_use.ui_text.clone(),
+                            tool_use.input.clone(),
+                            &messages,
+                            tool,
+                            cx,
+                        );
+                    }
+                    ToolPermissionAction::Ask => {
+                        self.tool_use.confirm_tool_use(
+                            tool_use.id.clone(),
+                            tool_use.ui_text.clone(),
+                            tool_use.input.clone(),
+                            messages.clone(),
+                            tool,
+                            permission,
+                        );
+                        cx.emit(ThreadEvent::ToolConfirmationNeeded);
+                    }
+                    ToolPermissionAction::Deny => {
+                        let err = Err(anyhow!(
+                            "Permission to run tool action denied. {}",
+                            permission.reason
+                        ));
+                        let pending_tool_use = self.tool_use.insert_tool_output(
+                            tool_use.id.clone(),
+                            tool_use.name.clone(),
+                            err,
+                        );
+                        cx.emit(ThreadEvent::ToolFinished {
+                            tool_use_id: tool_use.id.clone(),
+                            pending_tool_use,
+                            canceled: false,
+                        });
+                    }
                 }
             }
         }
@@ -1403,8 +1796,8 @@ impl Thread {
     ) -> Task<()> {
         let tool_name: Arc<str> = tool.name().into();
 
-        let run_tool = if self.tools.is_disabled(&tool.source(), &tool_name) {
-            Task::ready(Err(anyhow!("tool is disabled: {tool_name}")))
+        let tool_result: ToolResult = if self.tools.is_disabled(&tool.source(), &tool_name) {
+            Task::ready(Err(anyhow!("tool is disabled: {tool_name}"))).into()
         } else {
             tool.run(
                 input,
@@ -1415,6 +1808,12 @@ impl Thread {
             )
         };
 
+        if let Some(card) = tool_result.card {
+            self.tool_use
+                .insert_tool_result_card(tool_use_id.clone(), card);
+        }
+        let run_tool = tool_result.output;
+
         cx.spawn({
             async move |thread: WeakEntity<Thread>, cx| {
                 let output = run_tool.await;
@@ -1467,6 +1866,8 @@ impl Thread {
     pub fn cancel_last_completion(&mut self, cx: &mut Context<Self>) -> bool {
         let canceled = if self.pending_completions.pop().is_some() {
             true
+        } else if self.pending_compaction.take().is_some() {
+            true
         } else {
             let mut canceled = false;
             for pending_tool_use in self.tool_use.cancel_pending() {
@@ -1688,7 +2089,7 @@ impl Thread {
                 }
 
                 writeln!(markdown, "**\n")?;
-                writeln!(markdown, "{}", tool_result.content)?;
+                writeln!(markdown, "{}", tool_result.text_contents())?;
             }
         }
 
@@ -1798,6 +2199,8 @@ pub enum ThreadEvent {
     },
     CheckpointChanged,
     ToolConfirmationNeeded,
+    /// The messages up to and including the given message were summarized.
+    Compacted(MessageId),
 }
 
 impl EventEmitter<ThreadEvent> for Thread {}
@@ -1806,3 +2209,423 @@ struct PendingCompletion {
     id: usize,
     _task: Task<()>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ThreadStore, thread_store};
+    use assistant_tool::ToolResultOutput;
+    use context_server::ContextServerSettings;
+    use gpui::TestAppContext;
+    use language_model::LanguageModelToolUse;
+    use project::{FakeFs, Project};
+    use serde_json::json;
+    use settings::{Settings as _, SettingsStore};
+    use util::path;
+
+    #[gpui::test]
+    async fn test_compaction_threshold(cx: &mut TestAppContext) {
+        let (thread, model) = init_test(cx).await;
+        thread.update(cx, |thread, cx| {
+            insert_messages(thread, 6, cx);
+            thread.last_request_token_usage = TokenUsage {
+                input_tokens: 900_000,
+                ..TokenUsage::default()
+            };
+            thread.send_to_model(model.clone(), RequestKind::Chat, cx);
+        });
+        cx.run_until_parked();
+        let requests = model.as_fake().pending_completions();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(
+            request_texts(&requests[0]).len(),
+            6,
+            "Threads shouldn't be compacted unless automatic compaction is enabled"
+        );
+        model.as_fake().end_last_completion_stream();
+        cx.run_until_parked();
+
+        enable_auto_compaction(cx);
+        thread.update(cx, |thread, cx| {
+            thread.last_request_token_usage = TokenUsage {
+                input_tokens: 700_000,
+                output_tokens: 90_000,
+                ..TokenUsage::default()
+            };
+            thread.send_to_model(model.clone(), RequestKind::Chat, cx);
+        });
+        cx.run_until_parked();
+        let requests = model.as_fake().pending_completions();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(
+            request_texts(&requests[0]).len(),
+            6,
+            "Threads below the threshold shouldn't be compacted"
+        );
+        model.as_fake().end_last_completion_stream();
+        cx.run_until_parked();
+
+        thread.update(cx, |thread, cx| {
+            thread.last_request_token_usage = TokenUsage {
+                input_tokens: 700_000,
+                output_tokens: 50_000,
+                cache_read_input_tokens: 50_000,
+                ..TokenUsage::default()
+            };
+            thread.send_to_model(model.clone(), RequestKind::Chat, cx);
+        });
+        cx.run_until_parked();
+        let requests = model.as_fake().pending_completions();
+        assert_eq!(requests.len(), 1, "The request should wait for the summary");
+        let texts = request_texts(&requests[0]);
+        assert_eq!(texts[..2], ["Message 0", "Message 1"]);
+        assert!(
+            texts[2].starts_with("Summarize this conversation so far"),
+            "Only the messages before the kept ones should be summarized, got {texts:?}"
+        );
+
+        model
+            .as_fake()
+            .stream_last_completion_response("The summary".into());
+        model.as_fake().end_last_completion_stream();
+        cx.run_until_parked();
+        thread.read_with(cx, |thread, _| {
+            assert_eq!(thread.compactions().len(), 1);
+            assert_eq!(thread.compactions()[0].message_id, MessageId(1));
+            assert_eq!(thread.last_request_token_usage, TokenUsage::default());
+        });
+        let requests = model.as_fake().pending_completions();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(
+            request_texts(&requests[0]),
+            [
+                format!("{COMPACTION_SUMMARY_HEADER}\n\nThe summary"),
+                "Message 2".into(),
+                "Message 3".into(),
+                "Message 4".into(),
+                "Message 5".into(),
+            ],
+            "The request should be sent with the summary once it's ready"
+        );
+    }
+
+    #[gpui::test]
+    async fn test_compaction_keeps_tool_uses_with_their_results(cx: &mut TestAppContext) {
+        let (thread, model) = init_test(cx).await;
+        thread.update(cx, |thread, cx| {
+            insert_messages(thread, 8, cx);
+            let tool_use_id = LanguageModelToolUseId::from("tool-use");
+            thread.tool_use.request_tool_use(
+                MessageId(3),
+                LanguageModelToolUse {
+                    id: tool_use_id.clone(),
+                    name: "the-tool".into(),
+                    input: json!({}),
+                },
+                cx,
+            );
+            thread.tool_use.insert_tool_output(
+                tool_use_id,
+                "the-tool".into(),
+                Ok(ToolResultOutput::from("The result".to_string())),
+            );
+            // A tool use that is still running in the last message.
+            thread.tool_use.request_tool_use(
+                MessageId(7),
+                LanguageModelToolUse {
+                    id: LanguageModelToolUseId::from("pending-tool-use"),
+                    name: "the-tool".into(),
+                    input: json!({}),
+                },
+                cx,
+            );
+
+            assert_eq!(
+                thread.compaction_boundary(),
+                Some(MessageId(2)),
+                "The message with the tool use should be kept along with its results"
+            );
+            thread.compact(cx);
+        });
+        cx.run_until_parked();
+        let requests = model.as_fake().pending_completions();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(request_texts(&requests[0]).len(), 4);
+
+        model
+            .as_fake()
+            .stream_last_completion_response("The summary".into());
+        model.as_fake().end_last_completion_stream();
+        cx.run_until_parked();
+        thread.read_with(cx, |thread, cx| {
+            assert_eq!(thread.compactions()[0].message_id, MessageId(2));
+            assert_eq!(
+                thread.compaction_boundary(),
+                None,
+                "Compacting again shouldn't summarize the kept messages"
+            );
+
+            let request = thread.to_completion_request(RequestKind::Chat, cx);
+            assert_eq!(request.messages.len(), 6);
+            assert!(
+                request.messages[1]
+                    .content
+                    .iter()
+                    .any(|content| matches!(content, MessageContent::ToolUse(_)))
+            );
+            assert!(
+                request.messages[2]
+                    .content
+                    .iter()
+                    .any(|content| matches!(content, MessageContent::ToolResult(_)))
+            );
+        });
+    }
+
+    #[gpui::test]
+    async fn test_fork_thread(cx: &mut TestAppContext) {
+        let (thread, _) = init_test(cx).await;
+        let forked_thread = thread.update(cx, |thread, cx| {
+            insert_messages(thread, 8, cx);
+            let tool_use_id = LanguageModelToolUseId::from("tool-use");
+            thread.tool_use.request_tool_use(
+                MessageId(3),
+                LanguageModelToolUse {
+                    id: tool_use_id.clone(),
+                    name: "the-tool".into(),
+                    input: json!({}),
+                },
+                cx,
+            );
+            thread.tool_use.insert_tool_output(
+                tool_use_id,
+                "the-tool".into(),
+                Ok(ToolResultOutput::from("The result".to_string())),
+            );
+            thread.tool_use.request_tool_use(
+                MessageId(3),
+                LanguageModelToolUse {
+                    id: LanguageModelToolUseId::from("pending-tool-use"),
+                    name: "the-tool".into(),
+                    input: json!({}),
+                },
+                cx,
+            );
+            for message_id in [MessageId(1), MessageId(5)] {
+                thread.compactions.push(ThreadCompaction {
+                    message_id,
+                    summary: "The summary".into(),
+                });
+            }
+            thread.cumulative_token_usage = TokenUsage {
+                input_tokens: 900_000,
+                output_tokens: 50_000,
+                ..TokenUsage::default()
+            };
+            thread.last_request_token_usage = thread.cumulative_token_usage.clone();
+            assert!(thread.is_getting_too_long(cx));
+
+            thread.fork(MessageId(4), cx).unwrap()
+        });
+
+        forked_thread.read_with(cx, |forked_thread, cx| {
+            let thread_id = thread.read(cx).id().clone();
+            assert_eq!(
+                forked_thread.forked_from(),
+                Some(&ThreadForkPoint {
+                    thread_id,
+                    message_id: MessageId(4),
+                })
+            );
+            assert_eq!(
+                forked_thread
+                    .messages()
+                    .map(|message| message.id)
+                    .collect::<Vec<_>>(),
+                (0..=4).map(MessageId).collect::<Vec<_>>()
+            );
+            assert_eq!(
+                forked_thread
+                    .compactions()
+                    .iter()
+                    .map(|compaction| compaction.message_id)
+                    .collect::<Vec<_>>(),
+                [MessageId(1)],
+                "Only the compactions within the shared messages should be kept"
+            );
+            assert!(
+                forked_thread.tool_use.pending_tool_uses().is_empty(),
+                "Tool uses without results shouldn't be forked"
+            );
+
+            let request = forked_thread.to_completion_request(RequestKind::Chat, cx);
+            assert!(request.messages.iter().any(|message| {
+                message
+                    .content
+                    .iter()
+                    .any(|content| matches!(content, MessageContent::ToolUse(_)))
+            }));
+            assert!(request.messages.iter().any(|message| {
+                message
+                    .content
+                    .iter()
+                    .any(|content| matches!(content, MessageContent::ToolResult(_)))
+            }));
+
+            assert_eq!(
+                forked_thread.cumulative_token_usage(),
+                TokenUsage::default(),
+                "The fork should only count the tokens of its own requests"
+            );
+            assert_eq!(
+                forked_thread.last_request_token_usage,
+                TokenUsage::default()
+            );
+            assert!(!forked_thread.is_getting_too_long(cx));
+        });
+    }
+
+    #[gpui::test]
+    async fn test_export_and_import_thread(cx: &mut TestAppContext) {
+        let (thread, _) = init_test(cx).await;
+        thread.update(cx, |thread, cx| {
+            insert_messages(thread, 4, cx);
+            let tool_use_id = LanguageModelToolUseId::from("tool-use");
+            thread.tool_use.request_tool_use(
+                MessageId(1),
+                LanguageModelToolUse {
+                    id: tool_use_id.clone(),
+                    name: "the-tool".into(),
+                    input: json!({ "path": "a.rs" }),
+                },
+                cx,
+            );
+            thread.tool_use.insert_tool_output(
+                tool_use_id,
+                "the-tool".into(),
+                Ok(ToolResultOutput::from("The result".to_string())),
+            );
+        });
+
+        let exported_thread = thread
+            .update(cx, |thread, cx| thread.export(cx))
+            .await
+            .unwrap();
+        let json = exported_thread.to_json().unwrap();
+        let imported_thread = ExportedThread::from_json(json.as_bytes()).unwrap();
+        assert_eq!(imported_thread.version, ExportedThread::VERSION);
+        assert_eq!(imported_thread.checkpoints, exported_thread.checkpoints);
+
+        let (project, tools, prompt_builder) = thread.read_with(cx, |thread, _| {
+            (
+                thread.project.clone(),
+                thread.tools.clone(),
+                thread.prompt_builder.clone(),
+            )
+        });
+        let imported_thread = cx.new(|cx| {
+            Thread::deserialize(
+                ThreadId::new(),
+                imported_thread.thread,
+                project,
+                tools,
+                prompt_builder,
+                cx,
+            )
+        });
+
+        thread.read_with(cx, |thread, cx| {
+            let imported_thread = imported_thread.read(cx);
+            assert_eq!(imported_thread.summary(), thread.summary());
+            assert_eq!(
+                request_texts(&imported_thread.to_completion_request(RequestKind::Chat, cx)),
+                request_texts(&thread.to_completion_request(RequestKind::Chat, cx))
+            );
+            for message in thread.messages() {
+                assert_eq!(
+                    imported_thread.tool_results_for_message(message.id),
+                    thread.tool_results_for_message(message.id)
+                );
+            }
+            assert_eq!(
+                imported_thread.tool_results_for_message(MessageId(1)).len(),
+                1
+            );
+        });
+
+        assert!(ExportedThread::from_json(br#"{"version": 999}"#).is_err());
+        assert!(ExportedThread::from_json(br#"{"messages": []}"#).is_err());
+    }
+
+    async fn init_test(cx: &mut TestAppContext) -> (Entity<Thread>, Arc<dyn LanguageModel>) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            language::init(cx);
+            Project::init_settings(cx);
+            AssistantSettings::register(cx);
+            thread_store::init(cx);
+            ContextServerSettings::register(cx);
+            LanguageModelRegistry::test(cx);
+        });
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/test"), json!({})).await;
+        let project = Project::test(fs, [path!("/test").as_ref()], cx).await;
+        let thread_store = cx.update(|cx| {
+            ThreadStore::new(
+                project.clone(),
+                Arc::default(),
+                Arc::new(PromptBuilder::new(None).unwrap()),
+                cx,
+            )
+            .unwrap()
+        });
+        let thread = thread_store.update(cx, |store, cx| store.create_thread(cx));
+        // Keep finished completions from requesting a summary of the thread.
+        thread.update(cx, |thread, _| thread.summary = Some("Thread".into()));
+        let model = cx.update(|cx| {
+            LanguageModelRegistry::read_global(cx)
+                .active_model()
+                .unwrap()
+        });
+        (thread, model)
+    }
+
+    fn enable_auto_compaction(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            cx.update_global::<SettingsStore, _>(|store, cx| {
+                store
+                    .set_user_settings(
+                        r#"{"assistant": {"version": "2", "auto_compact_threads": true}}"#,
+                        cx,
+                    )
+                    .unwrap();
+            })
+        });
+    }
+
+    /// Inserts messages alternating between the user and the assistant, starting with the user.
+    fn insert_messages(thread: &mut Thread, count: usize, cx: &mut Context<Thread>) {
+        for ix in 0..count {
+            let role = if ix % 2 == 0 {
+                Role::User
+            } else {
+                Role::Assistant
+            };
+            thread.insert_message(
+                role,
+                vec![MessageSegment::Text(format!("Message {ix}"))],
+                cx,
+            );
+        }
+    }
+
+    fn request_texts(request: &LanguageModelRequest) -> Vec<String> {
+        request
+            .messages
+            .iter()
+            .map(|message| message.string_contents())
+            .collect()
+    }
+}
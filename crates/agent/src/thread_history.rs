@@ -1,35 +1,111 @@
+use std::time::Duration;
+
 use assistant_context_editor::SavedContextMetadata;
+use collections::HashMap;
+use editor::{Editor, EditorEvent};
 use gpui::{
-    App, Entity, FocusHandle, Focusable, ScrollStrategy, UniformListScrollHandle, WeakEntity,
-    uniform_list,
+    App, Entity, FocusHandle, Focusable, ScrollStrategy, Subscription, Task,
+    UniformListScrollHandle, WeakEntity, uniform_list,
 };
 use time::{OffsetDateTime, UtcOffset};
 use ui::{IconButtonShape, ListItem, ListItemSpacing, Tooltip, prelude::*};
+use util::ResultExt as _;
 
 use crate::history_store::{HistoryEntry, HistoryStore};
-use crate::thread_store::SerializedThreadMetadata;
+use crate::thread_store::{SerializedThreadMetadata, ThreadSearchMatch, ThreadStore};
 use crate::{AssistantPanel, RemoveSelectedThread};
 
+/// How long to wait after the search query changes before searching.
+const SEARCH_DEBOUNCE: Duration = Duration::from_millis(150);
+
 pub struct ThreadHistory {
     focus_handle: FocusHandle,
     assistant_panel: WeakEntity<AssistantPanel>,
     history_store: Entity<HistoryStore>,
+    thread_store: Entity<ThreadStore>,
+    search_editor: Entity<Editor>,
+    /// The threads matching the search query, or `None` when the query is empty.
+    search_matches: Option<Vec<(SerializedThreadMetadata, ThreadSearchMatch)>>,
+    search_task: Task<()>,
     scroll_handle: UniformListScrollHandle,
     selected_index: usize,
+    _subscriptions: Vec<Subscription>,
 }
 
 impl ThreadHistory {
     pub(crate) fn new(
         assistant_panel: WeakEntity<AssistantPanel>,
         history_store: Entity<HistoryStore>,
+        thread_store: Entity<ThreadStore>,
+        window: &mut Window,
         cx: &mut Context<Self>,
     ) -> Self {
+        let search_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Search threads…", cx);
+            editor
+        });
+        let search_subscription =
+            cx.subscribe(&search_editor, |this, _, event: &EditorEvent, cx| {
+                if let EditorEvent::BufferEdited = event {
+                    this.update_search(cx);
+                }
+            });
+
         Self {
             focus_handle: cx.focus_handle(),
             assistant_panel,
             history_store,
+            thread_store,
+            search_editor,
+            search_matches: None,
+            search_task: Task::ready(()),
             scroll_handle: UniformListScrollHandle::default(),
             selected_index: 0,
+            _subscriptions: vec![search_subscription],
+        }
+    }
+
+    fn update_search(&mut self, cx: &mut Context<Self>) {
+        let query = self.search_editor.read(cx).text(cx);
+        self.selected_index = 0;
+
+        if query.trim().is_empty() {
+            self.search_matches = None;
+            self.search_task = Task::ready(());
+            cx.notify();
+            return;
+        }
+
+        let thread_store = self.thread_store.clone();
+        self.search_task = cx.spawn(async move |this, cx| {
+            cx.background_executor().timer(SEARCH_DEBOUNCE).await;
+
+            let Some(search) = thread_store
+                .update(cx, |thread_store, cx| {
+                    thread_store.search_threads(&query, cx)
+                })
+                .log_err()
+            else {
+                return;
+            };
+            let matches = search.await.log_err().unwrap_or_default();
+
+            this.update(cx, |this, cx| {
+                this.search_matches = Some(matches);
+                this.selected_index = 0;
+                cx.notify();
+            })
+            .ok();
+        });
+    }
+
+    fn entry_count(&self, cx: &mut Context<Self>) -> usize {
+        match &self.search_matches {
+            Some(search_matches) => search_matches.len(),
+            None => self
+                .history_store
+                .update(cx, |this, cx| this.entry_count(cx)),
         }
     }
 
@@ -39,9 +115,7 @@ impl ThreadHistory {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        let count = self
-            .history_store
-            .update(cx, |this, cx| this.entry_count(cx));
+        let count = self.entry_count(cx);
         if count > 0 {
             if self.selected_index == 0 {
                 self.set_selected_index(count - 1, window, cx);
@@ -57,9 +131,7 @@ impl ThreadHistory {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        let count = self
-            .history_store
-            .update(cx, |this, cx| this.entry_count(cx));
+        let count = self.entry_count(cx);
         if count > 0 {
             if self.selected_index == count - 1 {
                 self.set_selected_index(0, window, cx);
@@ -70,18 +142,14 @@ impl ThreadHistory {
     }
 
     fn select_first(&mut self, _: &menu::SelectFirst, window: &mut Window, cx: &mut Context<Self>) {
-        let count = self
-            .history_store
-            .update(cx, |this, cx| this.entry_count(cx));
+        let count = self.entry_count(cx);
         if count > 0 {
             self.set_selected_index(0, window, cx);
         }
     }
 
     fn select_last(&mut self, _: &menu::SelectLast, window: &mut Window, cx: &mut Context<Self>) {
-        let count = self
-            .history_store
-            .update(cx, |this, cx| this.entry_count(cx));
+        let count = self.entry_count(cx);
         if count > 0 {
             self.set_selected_index(count - 1, window, cx);
         }
@@ -95,6 +163,24 @@ impl ThreadHistory {
     }
 
     fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(search_matches) = &self.search_matches {
+            if let Some((thread, search_match)) = search_matches.get(self.selected_index) {
+                let thread_id = thread.id.clone();
+                let message_id = search_match.message_id;
+                self.assistant_panel
+                    .update(cx, |this, cx| match message_id {
+                        Some(message_id) => this
+                            .open_thread_at_message(&thread_id, message_id, window, cx)
+                            .detach_and_log_err(cx),
+                        None => this
+                            .open_thread(&thread_id, window, cx)
+                            .detach_and_log_err(cx),
+                    })
+                    .ok();
+            }
+            return;
+        }
+
         let entries = self.history_store.update(cx, |this, cx| this.entries(cx));
 
         if let Some(entry) = entries.get(self.selected_index) {
@@ -123,6 +209,17 @@ impl ThreadHistory {
         _window: &mut Window,
         cx: &mut Context<Self>,
     ) {
+        if let Some(search_matches) = &mut self.search_matches {
+            if self.selected_index < search_matches.len() {
+                let (thread, _) = search_matches.remove(self.selected_index);
+                self.assistant_panel
+                    .update(cx, |this, cx| this.delete_thread(&thread.id, cx))
+                    .ok();
+                cx.notify();
+            }
+            return;
+        }
+
         let entries = self.history_store.update(cx, |this, cx| this.entries(cx));
 
         if let Some(entry) = entries.get(self.selected_index) {
@@ -149,8 +246,8 @@ impl ThreadHistory {
 }
 
 impl Focusable for ThreadHistory {
-    fn focus_handle(&self, _cx: &App) -> FocusHandle {
-        self.focus_handle.clone()
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.search_editor.focus_handle(cx)
     }
 }
 
@@ -158,6 +255,23 @@ impl Render for ThreadHistory {
     fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         let history_entries = self.history_store.update(cx, |this, cx| this.entries(cx));
         let selected_index = self.selected_index;
+        let thread_summaries = history_entries
+            .iter()
+            .filter_map(|entry| match entry {
+                HistoryEntry::Thread(thread) => Some((thread.id.clone(), thread.summary.clone())),
+                HistoryEntry::Context(_) => None,
+            })
+            .collect::<HashMap<_, _>>();
+        let search_matches = self.search_matches.clone();
+
+        let empty_state = |message: &'static str| {
+            v_flex().flex_grow().justify_center().child(
+                h_flex()
+                    .w_full()
+                    .justify_center()
+                    .child(Label::new(message).size(LabelSize::Small)),
+            )
+        };
 
         v_flex()
             .id("thread-history-container")
@@ -172,49 +286,92 @@ impl Render for ThreadHistory {
             .on_action(cx.listener(Self::select_last))
             .on_action(cx.listener(Self::confirm))
             .on_action(cx.listener(Self::remove_selected_thread))
-            .map(|history| {
-                if history_entries.is_empty() {
-                    history
-                        .justify_center()
-                        .child(
-                            h_flex().w_full().justify_center().child(
-                                Label::new("You don't have any past threads yet.")
-                                    .size(LabelSize::Small),
-                            ),
-                        )
-                } else {
-                    history.child(
-                        uniform_list(
-                            cx.entity().clone(),
-                            "thread-history",
-                            history_entries.len(),
-                            move |history, range, _window, _cx| {
-                                history_entries[range]
-                                    .iter()
-                                    .enumerate()
-                                    .map(|(index, entry)| {
-                                        h_flex().w_full().pb_1().child(match entry {
-                                            HistoryEntry::Thread(thread) => PastThread::new(
+            .child(
+                h_flex()
+                    .gap_1p5()
+                    .mb_1()
+                    .px_2()
+                    .py_1p5()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(
+                        Icon::new(IconName::MagnifyingGlass)
+                            .size(IconSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .child(self.search_editor.clone()),
+            )
+            .map(|history| match search_matches {
+                Some(search_matches) if search_matches.is_empty() => {
+                    history.child(empty_state("No threads match your search."))
+                }
+                Some(search_matches) => history.child(
+                    uniform_list(
+                        cx.entity().clone(),
+                        "thread-search-results",
+                        search_matches.len(),
+                        move |history, range, _window, _cx| {
+                            let start = range.start;
+                            search_matches[range]
+                                .iter()
+                                .enumerate()
+                                .map(|(index, (thread, search_match))| {
+                                    h_flex().w_full().pb_1().child(
+                                        PastThread::new(
+                                            thread.clone(),
+                                            history.assistant_panel.clone(),
+                                            selected_index == start + index,
+                                        )
+                                        .search_match(Some(search_match.clone())),
+                                    )
+                                })
+                                .collect()
+                        },
+                    )
+                    .track_scroll(self.scroll_handle.clone())
+                    .flex_grow(),
+                ),
+                None if history_entries.is_empty() => {
+                    history.child(empty_state("You don't have any past threads yet."))
+                }
+                None => history.child(
+                    uniform_list(
+                        cx.entity().clone(),
+                        "thread-history",
+                        history_entries.len(),
+                        move |history, range, _window, _cx| {
+                            history_entries[range]
+                                .iter()
+                                .enumerate()
+                                .map(|(index, entry)| {
+                                    h_flex().w_full().pb_1().child(match entry {
+                                        HistoryEntry::Thread(thread) => {
+                                            let parent_summary =
+                                                thread.forked_from.as_ref().and_then(|fork| {
+                                                    thread_summaries.get(&fork.thread_id).cloned()
+                                                });
+                                            PastThread::new(
                                                 thread.clone(),
                                                 history.assistant_panel.clone(),
                                                 selected_index == index,
                                             )
-                                            .into_any_element(),
-                                            HistoryEntry::Context(context) => PastContext::new(
-                                                context.clone(),
-                                                history.assistant_panel.clone(),
-                                                selected_index == index,
-                                            )
-                                            .into_any_element(),
-                                        })
+                                            .parent_summary(parent_summary)
+                                            .into_any_element()
+                                        }
+                                        HistoryEntry::Context(context) => PastContext::new(
+                                            context.clone(),
+                                            history.assistant_panel.clone(),
+                                            selected_index == index,
+                                        )
+                                        .into_any_element(),
                                     })
-                                    .collect()
-                            },
-                        )
-                        .track_scroll(self.scroll_handle.clone())
-                        .flex_grow(),
+                                })
+                                .collect()
+                        },
                     )
-                }
+                    .track_scroll(self.scroll_handle.clone())
+                    .flex_grow(),
+                ),
             })
     }
 }
@@ -224,6 +381,8 @@ pub struct PastThread {
     thread: SerializedThreadMetadata,
     assistant_panel: WeakEntity<AssistantPanel>,
     selected: bool,
+    parent_summary: Option<SharedString>,
+    search_match: Option<ThreadSearchMatch>,
 }
 
 impl PastThread {
@@ -236,8 +395,22 @@ impl PastThread {
             thread,
             assistant_panel,
             selected,
+            parent_summary: None,
+            search_match: None,
         }
     }
+
+    /// Sets the summary of the thread this one was forked from, if it still exists.
+    pub fn parent_summary(mut self, parent_summary: Option<SharedString>) -> Self {
+        self.parent_summary = parent_summary;
+        self
+    }
+
+    /// Sets where the thread matched the search query, which is shown below its summary.
+    pub fn search_match(mut self, search_match: Option<ThreadSearchMatch>) -> Self {
+        self.search_match = search_match;
+        self
+    }
 }
 
 impl RenderOnce for PastThread {
@@ -258,18 +431,71 @@ impl RenderOnce for PastThread {
             .toggle_state(self.selected)
             .spacing(ListItemSpacing::Sparse)
             .start_slot(
-                div()
+                v_flex()
                     .max_w_4_5()
-                    .child(Label::new(summary).size(LabelSize::Small).truncate()),
+                    .child(Label::new(summary).size(LabelSize::Small).truncate())
+                    .when_some(self.search_match.as_ref(), |this, search_match| {
+                        this.child(
+                            Label::new(search_match.snippet.clone())
+                                .size(LabelSize::XSmall)
+                                .color(Color::Muted)
+                                .truncate(),
+                        )
+                    }),
             )
             .end_slot(
                 h_flex()
                     .gap_1p5()
-                    .child(
-                        Label::new("Thread")
-                            .color(Color::Muted)
-                            .size(LabelSize::XSmall),
+                    .when_some(
+                        self.search_match
+                            .as_ref()
+                            .filter(|search_match| search_match.match_count > 1),
+                        |this, search_match| {
+                            this.child(
+                                Label::new(format!("{} matches", search_match.match_count))
+                                    .color(Color::Muted)
+                                    .size(LabelSize::XSmall),
+                            )
+                            .child(
+                                div()
+                                    .size(px(3.))
+                                    .rounded_full()
+                                    .bg(cx.theme().colors().text_disabled),
+                            )
+                        },
                     )
+                    .map(|this| {
+                        if self.thread.forked_from.is_some() {
+                            let tooltip = match self.parent_summary {
+                                Some(parent_summary) => {
+                                    SharedString::from(format!("Forked from \"{parent_summary}\""))
+                                }
+                                None => "Forked from a deleted thread".into(),
+                            };
+                            this.child(
+                                h_flex()
+                                    .id("forked-from")
+                                    .gap_0p5()
+                                    .child(
+                                        Icon::new(IconName::GitBranch)
+                                            .size(IconSize::XSmall)
+                                            .color(Color::Muted),
+                                    )
+                                    .child(
+                                        Label::new("Fork")
+                                            .color(Color::Muted)
+                                            .size(LabelSize::XSmall),
+                                    )
+                                    .tooltip(Tooltip::text(tooltip)),
+                            )
+                        } else {
+                            this.child(
+                                Label::new("Thread")
+                                    .color(Color::Muted)
+                                    .size(LabelSize::XSmall),
+                            )
+                        }
+                    })
                     .child(
                         div()
                             .size(px(3.))
@@ -302,10 +528,17 @@ impl RenderOnce for PastThread {
             .on_click({
                 let assistant_panel = self.assistant_panel.clone();
                 let id = self.thread.id.clone();
+                let message_id = self
+                    .search_match
+                    .as_ref()
+                    .and_then(|search_match| search_match.message_id);
                 move |_event, window, cx| {
                     assistant_panel
-                        .update(cx, |this, cx| {
-                            this.open_thread(&id, window, cx).detach_and_log_err(cx);
+                        .update(cx, |this, cx| match message_id {
+                            Some(message_id) => this
+                                .open_thread_at_message(&id, message_id, window, cx)
+                                .detach_and_log_err(cx),
+                            None => this.open_thread(&id, window, cx).detach_and_log_err(cx),
                         })
                         .ok();
                 }
@@ -17,15 +17,18 @@ use gpui::{
 };
 use heed::Database;
 use heed::types::SerdeBincode;
-use language_model::{LanguageModelToolUseId, Role, TokenUsage};
+use language_model::{LanguageModelToolResultContent, LanguageModelToolUseId, Role, TokenUsage};
+use parking_lot::Mutex;
 use project::Project;
 use prompt_store::PromptBuilder;
+use regex::{Regex, RegexBuilder};
 use serde::{Deserialize, Serialize};
 use settings::{Settings as _, SettingsStore};
 use util::ResultExt as _;
 
 use crate::thread::{
-    DetailedSummaryState, MessageId, ProjectSnapshot, Thread, ThreadEvent, ThreadId,
+    DetailedSummaryState, MessageId, ProjectSnapshot, Thread, ThreadCompaction, ThreadEvent,
+    ThreadForkPoint, ThreadId,
 };
 
 pub fn init(cx: &mut App) {
@@ -153,6 +156,87 @@ impl ThreadStore {
         })
     }
 
+    /// Creates a new thread sharing the history of the given thread up to and including the
+    /// given message, and saves it.
+    pub fn fork_thread(
+        &self,
+        thread: &Entity<Thread>,
+        message_id: MessageId,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Entity<Thread>>> {
+        let Some(forked_thread) = thread.update(cx, |thread, cx| thread.fork(message_id, cx))
+        else {
+            return Task::ready(Err(anyhow!("no message found with ID: {message_id:?}")));
+        };
+
+        let save_task = self.save_thread(&forked_thread, cx);
+        cx.spawn(async move |_this, cx| {
+            let (system_prompt_context, load_error) = forked_thread
+                .update(cx, |thread, cx| thread.load_system_prompt_context(cx))?
+                .await;
+            forked_thread.update(cx, |thread, cx| {
+                thread.set_system_prompt_context(system_prompt_context);
+                if let Some(load_error) = load_error {
+                    cx.emit(ThreadEvent::ShowError(load_error));
+                }
+            })?;
+
+            save_task.await?;
+            Ok(forked_thread)
+        })
+    }
+
+    /// Adds a thread exported from another Zed to the history, returning the ID it was given.
+    pub fn import_thread(
+        &self,
+        exported_thread: ExportedThread,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<ThreadId>> {
+        let mut thread = exported_thread.thread;
+        // The thread it was forked from only exists on the exporting machine.
+        thread.forked_from = None;
+
+        let id = ThreadId::new();
+        let database_future = ThreadsDatabase::global_future(cx);
+        cx.spawn(async move |this, cx| {
+            let database = database_future.await.map_err(|err| anyhow!(err))?;
+            database.save_thread(id.clone(), thread).await?;
+
+            this.update(cx, |this, cx| this.reload(cx))?.await?;
+            Ok(id)
+        })
+    }
+
+    /// Searches the messages, tool uses, and tool results of every thread, returning the matches
+    /// ordered from the most recently updated thread.
+    pub fn search_threads(
+        &self,
+        query: &str,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<(SerializedThreadMetadata, ThreadSearchMatch)>>> {
+        let query = query.to_string();
+        let database_future = ThreadsDatabase::global_future(cx);
+        cx.spawn(async move |this, cx| {
+            let database = database_future.await.map_err(|err| anyhow!(err))?;
+            let matches = database.search_threads(&query).await?;
+
+            this.read_with(cx, |this, _cx| {
+                let mut matches = matches
+                    .into_iter()
+                    .filter_map(|search_match| {
+                        let thread = this
+                            .threads
+                            .iter()
+                            .find(|thread| thread.id == search_match.thread_id)?;
+                        Some((thread.clone(), search_match))
+                    })
+                    .collect::<Vec<_>>();
+                matches.sort_unstable_by_key(|(thread, _)| std::cmp::Reverse(thread.updated_at));
+                matches
+            })
+        })
+    }
+
     pub fn save_thread(&self, thread: &Entity<Thread>, cx: &mut Context<Self>) -> Task<Result<()>> {
         let (metadata, serialized_thread) =
             thread.update(cx, |thread, cx| (thread.id().clone(), thread.serialize(cx)));
@@ -311,6 +395,7 @@ impl ThreadStore {
                     self.load_default_profile(cx);
                 }
             }
+            context_server::manager::Event::ResourceUpdated { .. } => {}
         }
     }
 }
@@ -320,6 +405,8 @@ pub struct SerializedThreadMetadata {
     pub id: ThreadId,
     pub summary: SharedString,
     pub updated_at: DateTime<Utc>,
+    #[serde(default)]
+    pub forked_from: Option<ThreadForkPoint>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -334,6 +421,12 @@ pub struct SerializedThread {
     pub cumulative_token_usage: TokenUsage,
     #[serde(default)]
     pub detailed_summary_state: DetailedSummaryState,
+    /// The message of another thread this thread was forked from.
+    #[serde(default)]
+    pub forked_from: Option<ThreadForkPoint>,
+    /// The points up to which the messages were summarized when talking to the model.
+    #[serde(default)]
+    pub compactions: Vec<ThreadCompaction>,
 }
 
 impl SerializedThread {
@@ -364,6 +457,69 @@ impl SerializedThread {
     }
 }
 
+/// A thread written to a standalone file, so it can be shared and imported by another Zed.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportedThread {
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    /// The model that was selected when the thread was exported.
+    #[serde(default)]
+    pub model: Option<ExportedModel>,
+    /// The text of the context attached to each message.
+    ///
+    /// This is only informational, as the context refers to the exporting machine's project.
+    #[serde(default)]
+    pub context: Vec<ExportedMessageContext>,
+    /// The messages that had a project checkpoint when the thread was exported.
+    ///
+    /// Checkpoints refer to the exporting machine's repositories, so they can't be restored.
+    #[serde(default)]
+    pub checkpoints: Vec<MessageId>,
+    #[serde(deserialize_with = "deserialize_exported_thread")]
+    pub thread: SerializedThread,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportedModel {
+    pub provider: String,
+    pub model: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportedMessageContext {
+    pub message_id: MessageId,
+    pub text: String,
+}
+
+impl ExportedThread {
+    pub const VERSION: u32 = 1;
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn from_json(json: &[u8]) -> Result<Self> {
+        let exported_thread_json = serde_json::from_slice::<serde_json::Value>(json)?;
+        match exported_thread_json.get("version") {
+            Some(version) if version.as_u64() == Some(Self::VERSION as u64) => Ok(
+                serde_json::from_value::<ExportedThread>(exported_thread_json)?,
+            ),
+            Some(version) => Err(anyhow!("unrecognized exported thread version: {version}")),
+            None => Err(anyhow!("not an exported thread")),
+        }
+    }
+}
+
+/// Reads the exported thread's messages, upgrading them if they were written by an older version.
+fn deserialize_exported_thread<'de, D>(deserializer: D) -> Result<SerializedThread, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let thread_json = serde_json::Value::deserialize(deserializer)?;
+    let thread_json = serde_json::to_vec(&thread_json).map_err(serde::de::Error::custom)?;
+    SerializedThread::from_json(&thread_json).map_err(serde::de::Error::custom)
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SerializedMessage {
     pub id: MessageId,
@@ -396,7 +552,28 @@ pub struct SerializedToolUse {
 pub struct SerializedToolResult {
     pub tool_use_id: LanguageModelToolUseId,
     pub is_error: bool,
-    pub content: Arc<str>,
+    #[serde(deserialize_with = "deserialize_tool_result_content")]
+    pub content: Vec<LanguageModelToolResultContent>,
+}
+
+/// Reads tool result content stored as a list of parts, or as plain text by older versions.
+fn deserialize_tool_result_content<'de, D>(
+    deserializer: D,
+) -> Result<Vec<LanguageModelToolResultContent>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum ToolResultContent {
+        Text(Arc<str>),
+        Parts(Vec<LanguageModelToolResultContent>),
+    }
+
+    Ok(match ToolResultContent::deserialize(deserializer)? {
+        ToolResultContent::Text(text) => vec![LanguageModelToolResultContent::Text { text }],
+        ToolResultContent::Parts(parts) => parts,
+    })
 }
 
 #[derive(Serialize, Deserialize)]
@@ -418,6 +595,8 @@ impl LegacySerializedThread {
             initial_project_snapshot: self.initial_project_snapshot,
             cumulative_token_usage: TokenUsage::default(),
             detailed_summary_state: DetailedSummaryState::default(),
+            forked_from: None,
+            compactions: Vec::new(),
         }
     }
 }
@@ -455,6 +634,155 @@ pub(crate) struct ThreadsDatabase {
     executor: BackgroundExecutor,
     env: heed::Env,
     threads: Database<SerdeBincode<ThreadId>, SerializedThread>,
+    search_index: Database<SerdeBincode<ThreadId>, SerdeBincode<ThreadSearchDocument>>,
+    last_search: Arc<Mutex<LastThreadSearch>>,
+}
+
+/// The threads that matched the last search, which are the only ones that can match a query
+/// refining it, as happens while the query is being typed.
+#[derive(Default)]
+struct LastThreadSearch {
+    /// Incremented whenever a thread is saved or deleted, invalidating the last search.
+    generation: usize,
+    matches: Option<(Vec<String>, Vec<ThreadId>)>,
+}
+
+impl LastThreadSearch {
+    fn invalidate(&mut self) {
+        self.generation += 1;
+        self.matches = None;
+    }
+
+    /// Returns the threads to search for the given lowercase terms, or `None` if every thread
+    /// needs to be searched.
+    fn candidates(&self, terms: &[String]) -> Option<Vec<ThreadId>> {
+        let (last_terms, thread_ids) = self.matches.as_ref()?;
+        // A message containing every new term contains every term it extends.
+        let refines_last_search = last_terms
+            .iter()
+            .all(|last_term| terms.iter().any(|term| term.contains(last_term.as_str())));
+        refines_last_search.then(|| thread_ids.clone())
+    }
+}
+
+/// The number of characters shown around a search match in its snippet.
+const SEARCH_SNIPPET_CONTEXT_CHARS: usize = 40;
+
+/// The searchable text of a thread, stored separately so that searching doesn't need to
+/// deserialize every thread.
+#[derive(Debug, Serialize, Deserialize)]
+struct ThreadSearchDocument {
+    summary: String,
+    messages: Vec<(MessageId, String)>,
+}
+
+impl ThreadSearchDocument {
+    fn new(thread: &SerializedThread) -> Self {
+        let messages = thread
+            .messages
+            .iter()
+            .map(|message| {
+                let mut text = String::new();
+                for segment in &message.segments {
+                    match segment {
+                        SerializedMessageSegment::Text { text: segment_text }
+                        | SerializedMessageSegment::Thinking { text: segment_text } => {
+                            text.push_str(segment_text);
+                            text.push('\n');
+                        }
+                    }
+                }
+                for tool_use in &message.tool_uses {
+                    text.push_str(&tool_use.name);
+                    text.push(' ');
+                    text.push_str(&tool_use.input.to_string());
+                    text.push('\n');
+                }
+                for tool_result in &message.tool_results {
+                    for content in &tool_result.content {
+                        if let Some(content) = content.to_text() {
+                            text.push_str(&content);
+                            text.push('\n');
+                        }
+                    }
+                }
+                (message.id, text)
+            })
+            .collect();
+
+        Self {
+            summary: thread.summary.to_string(),
+            messages,
+        }
+    }
+
+    /// Returns where the given terms all appear in a single message, or in the summary.
+    fn search(&self, thread_id: ThreadId, terms: &[Regex]) -> Option<ThreadSearchMatch> {
+        let contains_terms = |text: &str| terms.iter().all(|term| term.is_match(text));
+
+        let mut first_match = None;
+        let mut match_count = 0;
+        for (message_id, text) in &self.messages {
+            if contains_terms(text) {
+                match_count += 1;
+                if first_match.is_none() {
+                    first_match = Some((Some(*message_id), search_snippet(text, &terms[0])));
+                }
+            }
+        }
+
+        if first_match.is_none() && contains_terms(&self.summary) {
+            first_match = Some((None, self.summary.clone()));
+        }
+
+        let (message_id, snippet) = first_match?;
+        Some(ThreadSearchMatch {
+            thread_id,
+            message_id,
+            snippet: snippet.into(),
+            match_count,
+        })
+    }
+}
+
+/// Returns the text surrounding the first occurrence of the given term, on a single line.
+fn search_snippet(text: &str, term: &Regex) -> String {
+    let match_range = term
+        .find(text)
+        .map_or(0..0, |term_match| term_match.range());
+    let match_char_ix = text[..match_range.start].chars().count();
+    let start_char_ix = match_char_ix.saturating_sub(SEARCH_SNIPPET_CONTEXT_CHARS);
+    let snippet_char_count = text[match_range].chars().count() + SEARCH_SNIPPET_CONTEXT_CHARS * 2;
+
+    let mut chars = text.chars().skip(start_char_ix);
+    let snippet = chars
+        .by_ref()
+        .take(snippet_char_count)
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let mut result = String::new();
+    if start_char_ix > 0 {
+        result.push('…');
+    }
+    result.push_str(&snippet);
+    if chars.next().is_some() {
+        result.push('…');
+    }
+    result
+}
+
+/// A thread whose messages match a search query.
+#[derive(Debug, Clone)]
+pub struct ThreadSearchMatch {
+    pub thread_id: ThreadId,
+    /// The first matching message, or `None` if only the summary matched.
+    pub message_id: Option<MessageId>,
+    pub snippet: SharedString,
+    /// The number of messages in the thread that match the query.
+    pub match_count: usize,
 }
 
 impl heed::BytesEncode<'_> for SerializedThread {
@@ -504,19 +832,47 @@ impl ThreadsDatabase {
         let env = unsafe {
             heed::EnvOpenOptions::new()
                 .map_size(ONE_GB_IN_BYTES)
-                .max_dbs(1)
+                .max_dbs(2)
                 .open(path)?
         };
 
         let mut txn = env.write_txn()?;
-        let threads = env.create_database(&mut txn, Some("threads"))?;
+        let threads: Database<SerdeBincode<ThreadId>, SerializedThread> =
+            env.create_database(&mut txn, Some("threads"))?;
+        let search_index = env.create_database(&mut txn, Some("search_index"))?;
         txn.commit()?;
 
-        Ok(Self {
+        let this = Self {
             executor,
             env,
             threads,
-        })
+            search_index,
+            last_search: Arc::default(),
+        };
+        this.index_unindexed_threads()?;
+        Ok(this)
+    }
+
+    /// Adds the threads saved before the search index existed to it.
+    fn index_unindexed_threads(&self) -> Result<()> {
+        let mut txn = self.env.write_txn()?;
+        let mut documents = Vec::new();
+        for entry in self.threads.lazily_decode_data().iter(&txn)? {
+            let (id, thread) = entry?;
+            if self.search_index.get(&txn, &id)?.is_some() {
+                continue;
+            }
+
+            if let Some(thread) = thread.decode().map_err(|err| anyhow!(err)).log_err() {
+                documents.push((id, ThreadSearchDocument::new(&thread)));
+            }
+        }
+
+        for (id, document) in documents {
+            self.search_index.put(&mut txn, &id, &document)?;
+        }
+        txn.commit()?;
+        Ok(())
     }
 
     pub fn list_threads(&self) -> Task<Result<Vec<SerializedThreadMetadata>>> {
@@ -532,6 +888,7 @@ impl ThreadsDatabase {
                     id: key,
                     summary: value.summary,
                     updated_at: value.updated_at,
+                    forked_from: value.forked_from,
                 });
             }
 
@@ -554,10 +911,15 @@ impl ThreadsDatabase {
         let env = self.env.clone();
         let threads = self.threads;
 
+        let search_index = self.search_index;
+        let last_search = self.last_search.clone();
+
         self.executor.spawn(async move {
             let mut txn = env.write_txn()?;
+            search_index.put(&mut txn, &id, &ThreadSearchDocument::new(&thread))?;
             threads.put(&mut txn, &id, &thread)?;
             txn.commit()?;
+            last_search.lock().invalidate();
             Ok(())
         })
     }
@@ -566,11 +928,117 @@ impl ThreadsDatabase {
         let env = self.env.clone();
         let threads = self.threads;
 
+        let search_index = self.search_index;
+        let last_search = self.last_search.clone();
+
         self.executor.spawn(async move {
             let mut txn = env.write_txn()?;
             threads.delete(&mut txn, &id)?;
+            search_index.delete(&mut txn, &id)?;
             txn.commit()?;
+            last_search.lock().invalidate();
             Ok(())
         })
     }
+
+    /// Returns the threads with a message containing every word of the query, ignoring case.
+    pub fn search_threads(&self, query: &str) -> Task<Result<Vec<ThreadSearchMatch>>> {
+        let env = self.env.clone();
+        let search_index = self.search_index;
+        let last_search = self.last_search.clone();
+        let terms = query
+            .split_whitespace()
+            .map(|term| term.to_lowercase())
+            .collect::<Vec<_>>();
+
+        self.executor.spawn(async move {
+            if terms.is_empty() {
+                return Ok(Vec::new());
+            }
+            let term_regexes = terms
+                .iter()
+                .map(|term| {
+                    RegexBuilder::new(&regex::escape(term))
+                        .case_insensitive(true)
+                        .build()
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let (generation, candidates) = {
+                let last_search = last_search.lock();
+                (last_search.generation, last_search.candidates(&terms))
+            };
+
+            let txn = env.read_txn()?;
+            let mut matches = Vec::new();
+            match candidates {
+                Some(candidates) => {
+                    for id in candidates {
+                        if let Some(document) = search_index.get(&txn, &id)? {
+                            matches.extend(document.search(id, &term_regexes));
+                        }
+                    }
+                }
+                None => {
+                    for entry in search_index.iter(&txn)? {
+                        let (id, document) = entry?;
+                        matches.extend(document.search(id, &term_regexes));
+                    }
+                }
+            }
+
+            let mut last_search = last_search.lock();
+            if last_search.generation == generation {
+                let thread_ids = matches
+                    .iter()
+                    .map(|search_match| search_match.thread_id.clone())
+                    .collect();
+                last_search.matches = Some((terms, thread_ids));
+            }
+
+            Ok(matches)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_thread_search_ignores_case() {
+        let document = ThreadSearchDocument {
+            summary: "Trip planning".into(),
+            messages: vec![
+                (MessageId(0), "Flights to Paris".into()),
+                (MessageId(1), "İstanbul has the best ÇAY".into()),
+                (MessageId(2), "More about çay in İstanbul".into()),
+            ],
+        };
+        let terms = |query: &str| {
+            query
+                .split_whitespace()
+                .map(|term| {
+                    RegexBuilder::new(&regex::escape(&term.to_lowercase()))
+                        .case_insensitive(true)
+                        .build()
+                        .unwrap()
+                })
+                .collect::<Vec<_>>()
+        };
+        let thread_id = ThreadId::new();
+
+        let search_match = document.search(thread_id.clone(), &terms("çay")).unwrap();
+        assert_eq!(search_match.message_id, Some(MessageId(1)));
+        assert_eq!(search_match.match_count, 2);
+        assert_eq!(search_match.snippet, "İstanbul has the best ÇAY");
+
+        let search_match = document
+            .search(thread_id.clone(), &terms("PLANNING"))
+            .unwrap();
+        assert_eq!(search_match.message_id, None);
+        assert_eq!(search_match.snippet, "Trip planning");
+
+        assert!(document.search(thread_id, &terms("paris çay")).is_none());
+    }
 }
@@ -1,11 +1,11 @@
 use std::sync::Arc;
 
 use anyhow::Result;
-use assistant_tool::{Tool, ToolWorkingSet};
-use collections::HashMap;
+use assistant_tool::{Tool, ToolPermissionDecision, ToolResultOutput, ToolWorkingSet};
+use collections::{HashMap, HashSet};
 use futures::FutureExt as _;
 use futures::future::Shared;
-use gpui::{App, SharedString, Task};
+use gpui::{AnyView, App, SharedString, Task};
 use language_model::{
     LanguageModelRequestMessage, LanguageModelToolResult, LanguageModelToolUse,
     LanguageModelToolUseId, MessageContent, Role,
@@ -24,6 +24,8 @@ pub struct ToolUse {
     pub input: serde_json::Value,
     pub icon: ui::IconName,
     pub needs_confirmation: bool,
+    /// The card rendering the tool's progress, if the tool provided one.
+    pub card: Option<AnyView>,
 }
 
 #[derive(Debug, Clone)]
@@ -41,6 +43,7 @@ pub struct ToolUseState {
     tool_uses_by_user_message: HashMap<MessageId, Vec<LanguageModelToolUseId>>,
     tool_results: HashMap<LanguageModelToolUseId, LanguageModelToolResult>,
     pending_tool_uses_by_id: HashMap<LanguageModelToolUseId, PendingToolUse>,
+    tool_result_cards: HashMap<LanguageModelToolUseId, AnyView>,
 }
 
 pub const USING_TOOL_MARKER: &str = "<using_tool>";
@@ -53,6 +56,7 @@ impl ToolUseState {
             tool_uses_by_user_message: HashMap::default(),
             tool_results: HashMap::default(),
             pending_tool_uses_by_id: HashMap::default(),
+            tool_result_cards: HashMap::default(),
         }
     }
 
@@ -130,6 +134,53 @@ impl ToolUseState {
         this
     }
 
+    /// Returns a copy of the finished tool uses and results attached to the given messages.
+    ///
+    /// Tool uses whose results aren't attached to any of the messages are omitted.
+    pub fn fork(&self, message_ids: &HashSet<MessageId>) -> Self {
+        let mut this = Self::new(self.tools.clone());
+
+        for (message_id, tool_use_ids) in &self.tool_uses_by_user_message {
+            if !message_ids.contains(message_id) {
+                continue;
+            }
+
+            let tool_use_ids = tool_use_ids
+                .iter()
+                .filter(|tool_use_id| self.tool_results.contains_key(tool_use_id))
+                .cloned()
+                .collect::<Vec<_>>();
+            for tool_use_id in &tool_use_ids {
+                this.tool_results
+                    .insert(tool_use_id.clone(), self.tool_results[tool_use_id].clone());
+                if let Some(card) = self.tool_result_cards.get(tool_use_id) {
+                    this.tool_result_cards
+                        .insert(tool_use_id.clone(), card.clone());
+                }
+            }
+            this.tool_uses_by_user_message
+                .insert(*message_id, tool_use_ids);
+        }
+
+        for (message_id, tool_uses) in &self.tool_uses_by_assistant_message {
+            if !message_ids.contains(message_id) {
+                continue;
+            }
+
+            let tool_uses = tool_uses
+                .iter()
+                .filter(|tool_use| this.tool_results.contains_key(&tool_use.id))
+                .cloned()
+                .collect::<Vec<_>>();
+            if !tool_uses.is_empty() {
+                this.tool_uses_by_assistant_message
+                    .insert(*message_id, tool_uses);
+            }
+        }
+
+        this
+    }
+
     pub fn cancel_pending(&mut self) -> Vec<PendingToolUse> {
         let mut pending_tools = Vec::new();
         for (tool_use_id, tool_use) in self.pending_tool_uses_by_id.drain() {
@@ -138,7 +189,7 @@ impl ToolUseState {
                 LanguageModelToolResult {
                     tool_use_id,
                     tool_name: tool_use.name.clone(),
-                    content: "Tool canceled by user".into(),
+                    content: vec!["Tool canceled by user".into()],
                     is_error: true,
                 },
             );
@@ -164,9 +215,9 @@ impl ToolUseState {
             let status = (|| {
                 if let Some(tool_result) = tool_result {
                     return if tool_result.is_error {
-                        ToolUseStatus::Error(tool_result.content.clone().into())
+                        ToolUseStatus::Error(tool_result.text_contents().into())
                     } else {
-                        ToolUseStatus::Finished(tool_result.content.clone().into())
+                        ToolUseStatus::Finished(tool_result.text_contents().into())
                     };
                 }
 
@@ -188,7 +239,7 @@ impl ToolUseState {
 
             let (icon, needs_confirmation) = if let Some(tool) = self.tools.tool(&tool_use.name, cx)
             {
-                (tool.icon(), tool.needs_confirmation())
+                (tool.icon(), tool.needs_confirmation(&tool_use.input, cx))
             } else {
                 (IconName::Cog, false)
             };
@@ -201,6 +252,7 @@ impl ToolUseState {
                 status,
                 icon,
                 needs_confirmation,
+                card: self.tool_result_cards.get(&tool_use.id).cloned(),
             })
         }
 
@@ -231,6 +283,12 @@ impl ToolUseState {
             .collect()
     }
 
+    pub fn message_has_tool_uses(&self, message_id: MessageId) -> bool {
+        self.tool_uses_by_assistant_message
+            .get(&message_id)
+            .map_or(false, |tool_uses| !tool_uses.is_empty())
+    }
+
     pub fn message_has_tool_results(&self, message_id: MessageId) -> bool {
         self.tool_uses_by_user_message
             .get(&message_id)
@@ -292,6 +350,10 @@ impl ToolUseState {
         }
     }
 
+    pub fn insert_tool_result_card(&mut self, tool_use_id: LanguageModelToolUseId, card: AnyView) {
+        self.tool_result_cards.insert(tool_use_id, card);
+    }
+
     pub fn confirm_tool_use(
         &mut self,
         tool_use_id: LanguageModelToolUseId,
@@ -299,6 +361,7 @@ impl ToolUseState {
         input: serde_json::Value,
         messages: Arc<Vec<LanguageModelRequestMessage>>,
         tool: Arc<dyn Tool>,
+        permission: ToolPermissionDecision,
     ) {
         if let Some(tool_use) = self.pending_tool_uses_by_id.get_mut(&tool_use_id) {
             let ui_text = ui_text.into();
@@ -309,6 +372,7 @@ impl ToolUseState {
                 messages,
                 tool,
                 ui_text,
+                permission,
             };
             tool_use.status = PendingToolUseStatus::NeedsConfirmation(Arc::new(confirmation));
         }
@@ -318,16 +382,16 @@ impl ToolUseState {
         &mut self,
         tool_use_id: LanguageModelToolUseId,
         tool_name: Arc<str>,
-        output: Result<String>,
+        output: Result<ToolResultOutput>,
     ) -> Option<PendingToolUse> {
         match output {
-            Ok(tool_result) => {
+            Ok(output) => {
                 self.tool_results.insert(
                     tool_use_id.clone(),
                     LanguageModelToolResult {
                         tool_use_id: tool_use_id.clone(),
                         tool_name,
-                        content: tool_result.into(),
+                        content: output.content,
                         is_error: false,
                     },
                 );
@@ -339,7 +403,7 @@ impl ToolUseState {
                     LanguageModelToolResult {
                         tool_use_id: tool_use_id.clone(),
                         tool_name,
-                        content: err.to_string().into(),
+                        content: vec![err.to_string().into()],
                         is_error: true,
                     },
                 );
@@ -408,10 +472,10 @@ impl ToolUseState {
                             tool_use_id: tool_use_id.clone(),
                             tool_name: tool_result.tool_name.clone(),
                             is_error: tool_result.is_error,
-                            content: if tool_result.content.is_empty() {
+                            content: if tool_result.is_empty() {
                                 // Surprisingly, the API fails if we return an empty string here.
                                 // It thinks we are sending a tool use without a tool result.
-                                "<Tool returned an empty string>".into()
+                                vec!["<Tool returned an empty string>".into()]
                             } else {
                                 tool_result.content.clone()
                             },
@@ -442,6 +506,8 @@ pub struct Confirmation {
     pub ui_text: Arc<str>,
     pub messages: Arc<Vec<LanguageModelRequestMessage>>,
     pub tool: Arc<dyn Tool>,
+    /// The evaluation of the `tool_permissions` rules that led to asking for confirmation.
+    pub permission: ToolPermissionDecision,
 }
 
 #[derive(Debug, Clone)]
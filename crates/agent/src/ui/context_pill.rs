@@ -221,7 +221,8 @@ impl RenderOnce for ContextPill {
                         ContextKind::Thread
                         | ContextKind::Directory
                         | ContextKind::FetchedUrl
-                        | ContextKind::Symbol => "Active",
+                        | ContextKind::Symbol
+                        | ContextKind::Resource => "Active",
                     })
                     .size(LabelSize::XSmall)
                     .color(Color::Muted),
@@ -336,6 +337,16 @@ impl AddedContext {
                     .read(cx)
                     .is_generating_detailed_summary(),
             },
+
+            AssistantContext::Resource(resource_context) => AddedContext {
+                id: resource_context.id,
+                kind: ContextKind::Resource,
+                name: resource_context.name.clone(),
+                parent: Some(resource_context.resource_id.server_id.to_string().into()),
+                tooltip: Some(resource_context.resource_id.uri.to_string().into()),
+                icon_path: None,
+                summarizing: false,
+            },
         }
     }
 }
@@ -2973,6 +2973,7 @@ impl CodegenAlternative {
             tools: Vec::new(),
             stop: Vec::new(),
             temperature: None,
+            max_tokens: None,
         })
     }
 
@@ -296,6 +296,7 @@ impl TerminalInlineAssistant {
             tools: Vec::new(),
             stop: Vec::new(),
             temperature: None,
+            max_tokens: None,
         })
     }
 
@@ -2555,6 +2555,7 @@ impl AssistantContext {
             tools: Vec::new(),
             stop: Vec::new(),
             temperature: None,
+            max_tokens: None,
         };
         for message in self.messages(cx) {
             if message.status != MessageStatus::Done {
@@ -26,6 +26,7 @@ impl Judge {
                 cache: false,
             }],
             temperature: Some(0.0),
+            max_tokens: None,
             tools: Vec::new(),
             stop: Vec::new(),
         };
@@ -1,4 +1,5 @@
 mod agent_profile;
+mod tool_permissions;
 
 use std::sync::Arc;
 
@@ -17,6 +18,7 @@ use serde::{Deserialize, Serialize};
 use settings::{Settings, SettingsSources};
 
 pub use crate::agent_profile::*;
+pub use crate::tool_permissions::*;
 
 #[derive(Copy, Clone, Default, Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
@@ -85,6 +87,10 @@ pub struct AssistantSettings {
     pub profiles: IndexMap<AgentProfileId, AgentProfile>,
     pub always_allow_tool_actions: bool,
     pub notify_when_agent_waiting: NotifyWhenAgentWaiting,
+    pub bash_tool_timeout_secs: u64,
+    pub tool_permissions: Vec<ToolPermissionRule>,
+    pub auto_compact_threads: bool,
+    pub auto_compact_threshold: f32,
 }
 
 impl AssistantSettings {
@@ -193,6 +199,10 @@ impl AssistantSettingsContent {
                     profiles: None,
                     always_allow_tool_actions: None,
                     notify_when_agent_waiting: None,
+                    bash_tool_timeout_secs: None,
+                    tool_permissions: None,
+                    auto_compact_threads: None,
+                    auto_compact_threshold: None,
                 },
                 VersionedAssistantSettingsContent::V2(settings) => settings.clone(),
             },
@@ -218,6 +228,10 @@ impl AssistantSettingsContent {
                 profiles: None,
                 always_allow_tool_actions: None,
                 notify_when_agent_waiting: None,
+                bash_tool_timeout_secs: None,
+                tool_permissions: None,
+                auto_compact_threads: None,
+                auto_compact_threshold: None,
             },
         }
     }
@@ -334,6 +348,19 @@ impl AssistantSettingsContent {
         settings.always_allow_tool_actions = Some(allow);
     }
 
+    pub fn add_tool_permission_rule(&mut self, rule: ToolPermissionRule) {
+        let AssistantSettingsContent::Versioned(VersionedAssistantSettingsContent::V2(settings)) =
+            self
+        else {
+            return;
+        };
+
+        let rules = settings.tool_permissions.get_or_insert_default();
+        if !rules.contains(&rule) {
+            rules.push(rule);
+        }
+    }
+
     pub fn set_profile(&mut self, profile_id: AgentProfileId) {
         let AssistantSettingsContent::Versioned(VersionedAssistantSettingsContent::V2(settings)) =
             self
@@ -410,6 +437,10 @@ impl Default for VersionedAssistantSettingsContent {
             profiles: None,
             always_allow_tool_actions: None,
             notify_when_agent_waiting: None,
+            bash_tool_timeout_secs: None,
+            tool_permissions: None,
+            auto_compact_threads: None,
+            auto_compact_threshold: None,
         })
     }
 }
@@ -461,6 +492,30 @@ pub struct AssistantSettingsContentV2 {
     ///
     /// Default: "primary_screen"
     notify_when_agent_waiting: Option<NotifyWhenAgentWaiting>,
+    /// How long a command run by the agent's bash tool may run before it is killed.
+    /// Set to 0 to let commands run until they finish.
+    ///
+    /// Default: 300
+    bash_tool_timeout_secs: Option<u64>,
+    /// Rules deciding which tool uses are allowed, denied, or need confirmation,
+    /// based on the tool's name and its input.
+    ///
+    /// When several rules match a tool use, "deny" wins over "ask", which wins over "allow".
+    /// When no rule matches, the tool's own default applies.
+    ///
+    /// Default: []
+    tool_permissions: Option<Vec<ToolPermissionRule>>,
+    /// Whether to summarize the older messages of a thread once it gets close to
+    /// the model's context window, instead of sending the whole thread again.
+    /// The full thread stays visible and is still saved.
+    ///
+    /// Default: false
+    auto_compact_threads: Option<bool>,
+    /// The fraction of the model's context window a thread may use before it gets
+    /// compacted, when `auto_compact_threads` is enabled.
+    ///
+    /// Default: 0.8
+    auto_compact_threshold: Option<f32>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
@@ -616,6 +671,19 @@ impl Settings for AssistantSettings {
                 value.notify_when_agent_waiting,
             );
             merge(&mut settings.default_profile, value.default_profile);
+            merge(
+                &mut settings.bash_tool_timeout_secs,
+                value.bash_tool_timeout_secs,
+            );
+            merge(&mut settings.tool_permissions, value.tool_permissions);
+            merge(
+                &mut settings.auto_compact_threads,
+                value.auto_compact_threads,
+            );
+            merge(
+                &mut settings.auto_compact_threshold,
+                value.auto_compact_threshold,
+            );
 
             if let Some(profiles) = value.profiles {
                 settings
@@ -713,6 +781,10 @@ mod tests {
                             profiles: None,
                             always_allow_tool_actions: None,
                             notify_when_agent_waiting: None,
+                            bash_tool_timeout_secs: None,
+                            tool_permissions: None,
+                            auto_compact_threads: None,
+                            auto_compact_threshold: None,
                         }),
                     )
                 },
@@ -0,0 +1,70 @@
+use std::fmt;
+use std::sync::Arc;
+
+use indexmap::IndexMap;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// What to do when a [`ToolPermissionRule`] matches a tool use.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolPermissionAction {
+    /// Run the tool without asking for confirmation.
+    Allow,
+    /// Ask for confirmation before running the tool.
+    Ask,
+    /// Never run the tool, and report the denial back to the model.
+    Deny,
+}
+
+impl fmt::Display for ToolPermissionAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ToolPermissionAction::Allow => write!(f, "allow"),
+            ToolPermissionAction::Ask => write!(f, "ask"),
+            ToolPermissionAction::Deny => write!(f, "deny"),
+        }
+    }
+}
+
+/// A rule deciding whether a tool use is allowed, denied, or needs confirmation.
+///
+/// A rule matches a tool use when all of its conditions hold.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ToolPermissionRule {
+    /// The name of the tool this rule applies to, or "*" for every tool.
+    pub tool: Arc<str>,
+    /// Glob patterns matched against the tool's input, keyed by input field name.
+    /// For example, `{ "command": "cargo test*" }` for the `bash` tool.
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub input: IndexMap<Arc<str>, Arc<str>>,
+    /// Whether the rule only applies when the tool would write outside of the project's worktrees.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub outside_worktree: bool,
+    /// What to do when the rule matches.
+    pub action: ToolPermissionAction,
+}
+
+impl fmt::Display for ToolPermissionRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.tool.as_ref() == "*" {
+            write!(f, "{} any tool", self.action)?;
+        } else {
+            write!(f, "{} `{}`", self.action, self.tool)?;
+        }
+
+        for (ix, (field, pattern)) in self.input.iter().enumerate() {
+            let conjunction = if ix == 0 { "when" } else { "and" };
+            write!(f, " {conjunction} `{field}` matches `{pattern}`")?;
+        }
+
+        if self.outside_worktree {
+            let conjunction = if self.input.is_empty() { "when" } else { "and" };
+            write!(f, " {conjunction} writing outside the project")?;
+        }
+
+        Ok(())
+    }
+}
@@ -1,4 +1,5 @@
 mod action_log;
+mod tool_permissions;
 mod tool_registry;
 mod tool_working_set;
 
@@ -8,13 +9,16 @@ use std::fmt::Formatter;
 use std::sync::Arc;
 
 use anyhow::Result;
-use gpui::{App, Entity, SharedString, Task};
+use gpui::{AnyView, App, Entity, SharedString, Task};
 use icons::IconName;
+use indexmap::IndexMap;
 use language_model::LanguageModelRequestMessage;
 use language_model::LanguageModelToolSchemaFormat;
+use language_model::{LanguageModelImage, LanguageModelToolResultContent};
 use project::Project;
 
 pub use crate::action_log::*;
+pub use crate::tool_permissions::*;
 pub use crate::tool_registry::*;
 pub use crate::tool_working_set::*;
 
@@ -30,6 +34,56 @@ pub enum ToolSource {
     ContextServer { id: SharedString },
 }
 
+/// The result of running a [`Tool`].
+pub struct ToolResult {
+    /// The output that is sent back to the model once the tool finishes running.
+    pub output: Task<Result<ToolResultOutput>>,
+    /// An optional card that renders the tool's progress (e.g. its live output) in the thread.
+    pub card: Option<AnyView>,
+}
+
+impl From<Task<Result<ToolResultOutput>>> for ToolResult {
+    fn from(output: Task<Result<ToolResultOutput>>) -> Self {
+        Self { output, card: None }
+    }
+}
+
+/// The output of a [`Tool`], made of text, image and JSON parts.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ToolResultOutput {
+    pub content: Vec<LanguageModelToolResultContent>,
+}
+
+impl From<String> for ToolResultOutput {
+    fn from(text: String) -> Self {
+        Self {
+            content: vec![text.into()],
+        }
+    }
+}
+
+impl From<&str> for ToolResultOutput {
+    fn from(text: &str) -> Self {
+        Self {
+            content: vec![text.into()],
+        }
+    }
+}
+
+impl From<LanguageModelImage> for ToolResultOutput {
+    fn from(image: LanguageModelImage) -> Self {
+        Self {
+            content: vec![image.into()],
+        }
+    }
+}
+
+impl From<Vec<LanguageModelToolResultContent>> for ToolResultOutput {
+    fn from(content: Vec<LanguageModelToolResultContent>) -> Self {
+        Self { content }
+    }
+}
+
 /// A tool that can be used by a language model.
 pub trait Tool: 'static + Send + Sync {
     /// Returns the name of the tool.
@@ -47,8 +101,30 @@ pub trait Tool: 'static + Send + Sync {
     }
 
     /// Returns true iff the tool needs the users's confirmation
-    /// before having permission to run.
-    fn needs_confirmation(&self) -> bool;
+    /// before having permission to run with the given input.
+    ///
+    /// This is the default used when none of the `tool_permissions` rules match.
+    fn needs_confirmation(&self, input: &serde_json::Value, cx: &App) -> bool;
+
+    /// Returns the paths the tool would write to when run with the given input,
+    /// used to match `tool_permissions` rules that apply outside of the project.
+    fn write_paths(&self, _input: &serde_json::Value) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Returns the uses of other tools that this tool would run with the given input, as
+    /// pairs of tool name and input. Their `tool_permissions` rules apply to this use too.
+    fn nested_tool_uses(&self, _input: &serde_json::Value) -> Vec<(String, serde_json::Value)> {
+        Vec::new()
+    }
+
+    /// Returns the input patterns for a `tool_permissions` rule that would allow
+    /// this and similar uses of the tool, e.g. when the user chooses "Always Allow".
+    ///
+    /// An empty map produces a rule that allows every use of the tool.
+    fn permission_rule_input(&self, _input: &serde_json::Value) -> IndexMap<Arc<str>, Arc<str>> {
+        IndexMap::default()
+    }
 
     /// Returns the JSON schema that describes the tool's input.
     fn input_schema(&self, _: LanguageModelToolSchemaFormat) -> serde_json::Value {
@@ -66,7 +142,7 @@ pub trait Tool: 'static + Send + Sync {
         project: Entity<Project>,
         action_log: Entity<ActionLog>,
         cx: &mut App,
-    ) -> Task<Result<String>>;
+    ) -> ToolResult;
 }
 
 impl Debug for dyn Tool {
@@ -1,17 +1,31 @@
 use crate::schema::json_schema_for;
 use anyhow::{Context as _, Result, anyhow};
-use assistant_tool::{ActionLog, Tool};
-use gpui::{App, Entity, Task};
+use assistant_settings::AssistantSettings;
+use assistant_tool::{ActionLog, Tool, ToolResult};
+use collections::HashMap;
+use futures::FutureExt as _;
+use gpui::{Entity, Subscription, Task, WeakEntity};
+use indexmap::IndexMap;
 use language_model::{LanguageModelRequestMessage, LanguageModelToolSchemaFormat};
 use project::Project;
+use project::terminals::TerminalKind;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use settings::Settings;
 use std::path::Path;
 use std::sync::Arc;
-use ui::IconName;
-use util::command::new_smol_command;
+use std::sync::atomic::{AtomicUsize, Ordering::SeqCst};
+use std::time::Duration;
+use task::{HideStrategy, RevealStrategy, RevealTarget, Shell, SpawnInTerminal, TaskId};
+use terminal::{TaskStatus, Terminal};
+use ui::prelude::*;
 use util::markdown::MarkdownString;
 
+/// The number of trailing output lines shown in the tool card while a command is running.
+const CARD_OUTPUT_LINES: usize = 20;
+
+static NEXT_BASH_TASK_ID: AtomicUsize = AtomicUsize::new(0);
+
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct BashToolInput {
     /// The bash command to execute as a one-liner.
@@ -27,10 +41,20 @@ impl Tool for BashTool {
         "bash".to_string()
     }
 
-    fn needs_confirmation(&self) -> bool {
+    fn needs_confirmation(&self, _: &serde_json::Value, _: &App) -> bool {
         true
     }
 
+    fn permission_rule_input(&self, input: &serde_json::Value) -> IndexMap<Arc<str>, Arc<str>> {
+        let Ok(input) = serde_json::from_value::<BashToolInput>(input.clone()) else {
+            return IndexMap::default();
+        };
+
+        let mut rule_input = IndexMap::default();
+        rule_input.insert("command".into(), command_pattern(&input.command).into());
+        rule_input
+    }
+
     fn description(&self) -> String {
         include_str!("./bash_tool/description.md").to_string()
     }
@@ -74,80 +98,522 @@ impl Tool for BashTool {
         project: Entity<Project>,
         _action_log: Entity<ActionLog>,
         cx: &mut App,
-    ) -> Task<Result<String>> {
+    ) -> ToolResult {
         let input: BashToolInput = match serde_json::from_value(input) {
             Ok(input) => input,
-            Err(err) => return Task::ready(Err(anyhow!(err))),
+            Err(err) => return Task::ready(Err(anyhow!(err))).into(),
         };
 
-        let project = project.read(cx);
-        let input_path = Path::new(&input.cd);
-        let working_dir = if input.cd == "." {
-            // Accept "." as meaning "the one worktree" if we only have one worktree.
-            let mut worktrees = project.worktrees(cx);
-
-            let only_worktree = match worktrees.next() {
-                Some(worktree) => worktree,
-                None => return Task::ready(Err(anyhow!("No worktrees found in the project"))),
-            };
-
-            if worktrees.next().is_some() {
-                return Task::ready(Err(anyhow!(
-                    "'.' is ambiguous in multi-root workspaces. Please specify a root directory explicitly."
-                )));
-            }
+        if project.read(cx).is_via_collab() {
+            return Task::ready(Err(anyhow!(
+                "Running commands is not supported in collaborative projects"
+            )))
+            .into();
+        }
 
-            only_worktree.read(cx).abs_path()
-        } else if input_path.is_absolute() {
-            // Absolute paths are allowed, but only if they're in one of the project's worktrees.
-            if !project
-                .worktrees(cx)
-                .any(|worktree| input_path.starts_with(&worktree.read(cx).abs_path()))
-            {
-                return Task::ready(Err(anyhow!(
-                    "The absolute path must be within one of the project's worktrees"
-                )));
-            }
+        let working_dir = match working_dir(&input, project.read(cx), cx) {
+            Ok(working_dir) => working_dir,
+            Err(err) => return Task::ready(Err(err)).into(),
+        };
 
-            input_path.into()
-        } else {
-            let Some(worktree) = project.worktree_for_root_name(&input.cd, cx) else {
-                return Task::ready(Err(anyhow!(
-                    "`cd` directory {} not found in the project",
-                    &input.cd
-                )));
-            };
-
-            worktree.read(cx).abs_path()
+        let Some(window) = cx.active_window() else {
+            return Task::ready(Err(anyhow!("No window available to run the command in"))).into();
         };
 
-        cx.spawn(async move |_| {
+        let timeout_secs = AssistantSettings::get_global(cx).bash_tool_timeout_secs;
+        let spawn_task = SpawnInTerminal {
+            id: TaskId(format!(
+                "agent-bash-{}",
+                NEXT_BASH_TASK_ID.fetch_add(1, SeqCst)
+            )),
+            full_label: input.command.clone(),
+            label: input.command.clone(),
+            command: "bash".to_string(),
             // Add 2>&1 to merge stderr into stdout for proper interleaving.
-            let command = format!("({}) 2>&1", input.command);
+            args: vec!["-c".to_string(), format!("({}) 2>&1", input.command)],
+            command_label: input.command.clone(),
+            cwd: Some(working_dir.to_path_buf()),
+            env: HashMap::default(),
+            use_new_terminal: true,
+            allow_concurrent_runs: true,
+            reveal: RevealStrategy::Never,
+            reveal_target: RevealTarget::Dock,
+            hide: HideStrategy::Never,
+            shell: Shell::System,
+            show_summary: false,
+            show_command: false,
+            show_rerun: false,
+        };
 
-            let output = new_smol_command("bash")
-                .arg("-c")
-                .arg(&command)
-                .current_dir(working_dir)
-                .output()
-                .await
-                .context("Failed to execute bash command")?;
+        let terminal = project.update(cx, |project, cx| {
+            project.create_terminal(TerminalKind::Task(spawn_task), window, cx)
+        });
+        let card = cx.new(|_| BashToolCard::new());
 
-            let output_string = String::from_utf8_lossy(&output.stdout).to_string();
+        let output = cx.spawn({
+            let card = card.clone();
+            async move |cx| {
+                let terminal = terminal.await.context("Failed to start bash command")?;
+                card.update(cx, |card, cx| card.set_terminal(&terminal, cx))?;
 
-            if output.status.success() {
-                if output_string.is_empty() {
-                    Ok("Command executed successfully.".to_string())
+                let completion =
+                    terminal.update(cx, |terminal, cx| terminal.wait_for_completed_task(cx))?;
+                let timed_out = if timeout_secs == 0 {
+                    completion.await;
+                    false
                 } else {
-                    Ok(output_string)
+                    let timeout = cx
+                        .background_executor()
+                        .timer(Duration::from_secs(timeout_secs));
+                    futures::select_biased! {
+                        _ = completion.fuse() => false,
+                        _ = timeout.fuse() => true,
+                    }
+                };
+
+                if timed_out {
+                    terminal.update(cx, |terminal, _| terminal.kill_active_task())?;
                 }
-            } else {
-                Ok(format!(
-                    "Command failed with exit code {}\n{}",
-                    output.status.code().unwrap_or(-1),
-                    &output_string
-                ))
+
+                let (output, status) = terminal.read_with(cx, |terminal, _| {
+                    (
+                        terminal.get_content().trim_end().to_string(),
+                        terminal.task().map(|task| task.status),
+                    )
+                })?;
+                let stopped_by_user = card.update(cx, |card, cx| {
+                    card.finish(output.clone(), status, timed_out, cx);
+                    card.stopped_by_user
+                })?;
+
+                let output = if timed_out {
+                    format!(
+                        "Command timed out after {timeout_secs} seconds and was killed.\n{output}"
+                    )
+                } else if stopped_by_user {
+                    format!("Command was stopped by the user.\n{output}")
+                } else if matches!(status, Some(TaskStatus::Completed { success: true })) {
+                    if output.is_empty() {
+                        "Command executed successfully.".to_string()
+                    } else {
+                        output
+                    }
+                } else {
+                    format!("Command failed\n{output}")
+                };
+
+                Ok(output.into())
             }
-        })
+        });
+
+        ToolResult {
+            output,
+            card: Some(card.into()),
+        }
+    }
+}
+
+fn working_dir(input: &BashToolInput, project: &Project, cx: &App) -> Result<Arc<Path>> {
+    let input_path = Path::new(&input.cd);
+    if input.cd == "." {
+        // Accept "." as meaning "the one worktree" if we only have one worktree.
+        let mut worktrees = project.worktrees(cx);
+
+        let Some(only_worktree) = worktrees.next() else {
+            return Err(anyhow!("No worktrees found in the project"));
+        };
+
+        if worktrees.next().is_some() {
+            return Err(anyhow!(
+                "'.' is ambiguous in multi-root workspaces. Please specify a root directory explicitly."
+            ));
+        }
+
+        Ok(only_worktree.read(cx).abs_path())
+    } else if input_path.is_absolute() {
+        // Absolute paths are allowed, but only if they're in one of the project's worktrees.
+        if !project
+            .worktrees(cx)
+            .any(|worktree| input_path.starts_with(&worktree.read(cx).abs_path()))
+        {
+            return Err(anyhow!(
+                "The absolute path must be within one of the project's worktrees"
+            ));
+        }
+
+        Ok(input_path.into())
+    } else {
+        let Some(worktree) = project.worktree_for_root_name(&input.cd, cx) else {
+            return Err(anyhow!(
+                "`cd` directory {} not found in the project",
+                &input.cd
+            ));
+        };
+
+        Ok(worktree.read(cx).abs_path())
+    }
+}
+
+/// Returns a glob pattern that matches the given command and its variations,
+/// e.g. `cargo test*` for `cargo test -p editor`.
+///
+/// Permission rules only allow commands chaining several programs if every one of them
+/// matches, so those get an alternative for each program, e.g. `{cargo build*,cargo test*}`.
+fn command_pattern(command: &str) -> String {
+    let mut prefixes = Vec::new();
+    for part in assistant_tool::shell_command_parts(command) {
+        let prefix = command_prefix(part);
+        if !prefixes.contains(&prefix) {
+            prefixes.push(prefix);
+        }
+    }
+
+    if prefixes.len() == 1 {
+        prefixes.remove(0)
+    } else {
+        format!("{{{}}}", prefixes.join(","))
+    }
+}
+
+fn command_prefix(command: &str) -> String {
+    let mut words = command.split_whitespace();
+    let mut prefix = escape_glob(words.next().unwrap_or_default());
+    if let Some(subcommand) = words.next().filter(|word| !word.starts_with('-')) {
+        prefix.push(' ');
+        prefix.push_str(&escape_glob(subcommand));
+    }
+    prefix.push('*');
+    prefix
+}
+
+/// Escapes the characters that have a meaning in glob patterns, including alternatives.
+fn escape_glob(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '?' | '*' | '[' | ']' | '{' | '}' | ',') {
+            escaped.push('[');
+            escaped.push(c);
+            escaped.push(']');
+        } else {
+            escaped.push(c);
+        }
+    }
+    escaped
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BashToolCardStatus {
+    Starting,
+    Running,
+    Succeeded,
+    Failed,
+    Stopped,
+    TimedOut,
+}
+
+/// Renders the live output of a command run by the [`BashTool`].
+pub struct BashToolCard {
+    status: BashToolCardStatus,
+    terminal: Option<WeakEntity<Terminal>>,
+    output: String,
+    stopped_by_user: bool,
+    _subscription: Option<Subscription>,
+}
+
+impl BashToolCard {
+    fn new() -> Self {
+        Self {
+            status: BashToolCardStatus::Starting,
+            terminal: None,
+            output: String::new(),
+            stopped_by_user: false,
+            _subscription: None,
+        }
+    }
+
+    fn set_terminal(&mut self, terminal: &Entity<Terminal>, cx: &mut Context<Self>) {
+        self.status = BashToolCardStatus::Running;
+        self.terminal = Some(terminal.downgrade());
+        self._subscription = Some(cx.subscribe(terminal, |this, terminal, event, cx| {
+            if let terminal::Event::Wakeup = event {
+                this.output = terminal
+                    .read(cx)
+                    .last_n_non_empty_lines(CARD_OUTPUT_LINES)
+                    .join("\n");
+                cx.notify();
+            }
+        }));
+        cx.notify();
+    }
+
+    fn finish(
+        &mut self,
+        output: String,
+        status: Option<TaskStatus>,
+        timed_out: bool,
+        cx: &mut Context<Self>,
+    ) {
+        self.status = if timed_out {
+            BashToolCardStatus::TimedOut
+        } else if self.stopped_by_user {
+            BashToolCardStatus::Stopped
+        } else if matches!(status, Some(TaskStatus::Completed { success: true })) {
+            BashToolCardStatus::Succeeded
+        } else {
+            BashToolCardStatus::Failed
+        };
+        self.output = output;
+        self.terminal = None;
+        self._subscription = None;
+        cx.notify();
+    }
+
+    fn stop(&mut self, cx: &mut Context<Self>) {
+        let Some(terminal) = self
+            .terminal
+            .as_ref()
+            .and_then(|terminal| terminal.upgrade())
+        else {
+            return;
+        };
+        self.stopped_by_user = true;
+        terminal.update(cx, |terminal, _| terminal.kill_active_task());
+        cx.notify();
+    }
+}
+
+impl Render for BashToolCard {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let status_label = match self.status {
+            BashToolCardStatus::Starting => "Starting…",
+            BashToolCardStatus::Running if self.stopped_by_user => "Stopping…",
+            BashToolCardStatus::Running => "Running…",
+            BashToolCardStatus::Succeeded => "Output",
+            BashToolCardStatus::Failed => "Failed",
+            BashToolCardStatus::Stopped => "Stopped",
+            BashToolCardStatus::TimedOut => "Timed Out",
+        };
+        let is_running = self.status == BashToolCardStatus::Running && !self.stopped_by_user;
+
+        v_flex()
+            .gap_0p5()
+            .child(
+                h_flex()
+                    .justify_between()
+                    .child(
+                        Label::new(status_label)
+                            .size(LabelSize::XSmall)
+                            .color(Color::Muted)
+                            .buffer_font(cx),
+                    )
+                    .when(is_running, |this| {
+                        this.child(
+                            Button::new("stop-bash-command", "Stop")
+                                .label_size(LabelSize::Small)
+                                .icon(IconName::Stop)
+                                .icon_position(IconPosition::Start)
+                                .icon_size(IconSize::Small)
+                                .icon_color(Color::Error)
+                                .on_click(cx.listener(|this, _, _, cx| this.stop(cx))),
+                        )
+                    }),
+            )
+            .when(!self.output.is_empty(), |this| {
+                this.child(
+                    Label::new(self.output.clone())
+                        .size(LabelSize::Small)
+                        .buffer_font(cx),
+                )
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assistant_tool::ToolResultOutput;
+    use gpui::{Empty, TestAppContext};
+    use language_model::LanguageModelToolResultContent;
+    use project::RealFs;
+    use serde_json::json;
+    use settings::SettingsStore;
+    use terminal::terminal_settings::TerminalSettings;
+    use util::test::TempTree;
+
+    use super::*;
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            language::init(cx);
+            Project::init_settings(cx);
+            AssistantSettings::register(cx);
+            TerminalSettings::register(cx);
+        });
+    }
+
+    async fn test_project(cx: &mut TestAppContext) -> (TempTree, Entity<Project>) {
+        let tree = TempTree::new(json!({ "project": {} }));
+        let fs = Arc::new(RealFs::new(None, cx.executor()));
+        let project = Project::test(fs, [tree.path().join("project").as_path()], cx).await;
+
+        // Terminals are created in the active window.
+        let window = cx.add_window(|_, _| Empty);
+        window
+            .update(cx, |_, window, _| window.activate_window())
+            .unwrap();
+
+        (tree, project)
+    }
+
+    fn run_command(
+        command: &str,
+        project: &Entity<Project>,
+        cx: &mut TestAppContext,
+    ) -> (Task<Result<ToolResultOutput>>, Entity<BashToolCard>) {
+        let input = json!({ "command": command, "cd": "project" });
+        let action_log = cx.new(|_| ActionLog::new());
+        let result =
+            cx.update(|cx| Arc::new(BashTool).run(input, &[], project.clone(), action_log, cx));
+        let card = result.card.unwrap().downcast::<BashToolCard>().unwrap();
+        (result.output, card)
+    }
+
+    /// Lets time pass until the condition holds. The terminal picks up the output of the
+    /// command on a timer, which only fires as the test clock advances.
+    fn run_until(cx: &mut TestAppContext, mut condition: impl FnMut(&mut TestAppContext) -> bool) {
+        for _ in 0..1000 {
+            cx.run_until_parked();
+            if condition(cx) {
+                return;
+            }
+            cx.executor().advance_clock(Duration::from_millis(10));
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        panic!("the condition never held");
+    }
+
+    fn output_text(mut output: Task<Result<ToolResultOutput>>, cx: &mut TestAppContext) -> String {
+        let mut result = None;
+        run_until(cx, |_| {
+            result = (&mut output).now_or_never();
+            result.is_some()
+        });
+        let output = result.unwrap().unwrap();
+        output
+            .content
+            .iter()
+            .map(|part| match part {
+                LanguageModelToolResultContent::Text { text } => text.to_string(),
+                _ => panic!("unexpected output {part:?}"),
+            })
+            .collect()
+    }
+
+    #[gpui::test]
+    async fn test_bash_tool_success(cx: &mut TestAppContext) {
+        if cfg!(windows) {
+            return;
+        }
+        init_test(cx);
+        let (_tree, project) = test_project(cx).await;
+
+        let (output, card) = run_command("echo hello", &project, cx);
+        assert_eq!(output_text(output, cx), "hello");
+        card.read_with(cx, |card, _| {
+            assert_eq!(card.status, BashToolCardStatus::Succeeded);
+            assert_eq!(card.output, "hello");
+        });
+
+        let (output, _) = run_command("true", &project, cx);
+        assert_eq!(output_text(output, cx), "Command executed successfully.");
+    }
+
+    #[gpui::test]
+    async fn test_bash_tool_failure(cx: &mut TestAppContext) {
+        if cfg!(windows) {
+            return;
+        }
+        init_test(cx);
+        let (_tree, project) = test_project(cx).await;
+
+        // The command's error output is merged into its output.
+        let (output, card) = run_command("echo oops >&2; exit 3", &project, cx);
+        assert_eq!(output_text(output, cx), "Command failed\noops");
+        card.read_with(cx, |card, _| {
+            assert_eq!(card.status, BashToolCardStatus::Failed);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_bash_tool_timeout(cx: &mut TestAppContext) {
+        if cfg!(windows) {
+            return;
+        }
+        init_test(cx);
+        cx.update(|cx| {
+            AssistantSettings::override_global(
+                AssistantSettings {
+                    bash_tool_timeout_secs: 1,
+                    ..Default::default()
+                },
+                cx,
+            );
+        });
+        let (_tree, project) = test_project(cx).await;
+
+        let (output, card) = run_command("echo started; sleep 100", &project, cx);
+        assert_eq!(
+            output_text(output, cx),
+            "Command timed out after 1 seconds and was killed.\nstarted"
+        );
+        card.read_with(cx, |card, _| {
+            assert_eq!(card.status, BashToolCardStatus::TimedOut);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_bash_tool_stop(cx: &mut TestAppContext) {
+        if cfg!(windows) {
+            return;
+        }
+        init_test(cx);
+        let (_tree, project) = test_project(cx).await;
+
+        let (output, card) = run_command("seq 1 100; sleep 100", &project, cx);
+        run_until(cx, |cx| {
+            card.read_with(cx, |card, _| card.output.lines().last() == Some("100"))
+        });
+        // While the command runs, the card only shows the last lines of its output.
+        card.read_with(cx, |card, _| {
+            assert_eq!(card.status, BashToolCardStatus::Running);
+            assert_eq!(
+                card.output.lines().collect::<Vec<_>>(),
+                (81..=100).map(|n| n.to_string()).collect::<Vec<_>>()
+            );
+        });
+
+        card.update(cx, |card, cx| card.stop(cx));
+        let output = output_text(output, cx);
+        assert!(
+            output.starts_with("Command was stopped by the user.\n1\n2\n"),
+            "unexpected output {output:?}"
+        );
+        assert!(output.ends_with("\n100"), "unexpected output {output:?}");
+        card.read_with(cx, |card, _| {
+            assert_eq!(card.status, BashToolCardStatus::Stopped);
+        });
+    }
+
+    #[test]
+    fn test_command_pattern() {
+        assert_eq!(command_pattern("cargo test -p editor"), "cargo test*");
+        assert_eq!(command_pattern("ls -la"), "ls*");
+        assert_eq!(
+            command_pattern("cargo build && cargo test; cargo build"),
+            "{cargo build*,cargo test*}"
+        );
+        assert_eq!(command_pattern("echo {a,b}*"), "echo [{]a[,]b[}][*]*");
     }
 }
@@ -1,9 +1,11 @@
 use crate::schema::json_schema_for;
 use anyhow::{Result, anyhow};
-use assistant_tool::{ActionLog, Tool, ToolWorkingSet};
+use assistant_tool::{ActionLog, Tool, ToolRegistry, ToolResult, ToolResultOutput, ToolWorkingSet};
 use futures::future::join_all;
 use gpui::{App, AppContext, Entity, Task};
-use language_model::{LanguageModelRequestMessage, LanguageModelToolSchemaFormat};
+use language_model::{
+    LanguageModelRequestMessage, LanguageModelToolResultContent, LanguageModelToolSchemaFormat,
+};
 use project::Project;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -151,8 +153,28 @@ impl Tool for BatchTool {
         "batch_tool".into()
     }
 
-    fn needs_confirmation(&self) -> bool {
-        true
+    fn needs_confirmation(&self, input: &serde_json::Value, cx: &App) -> bool {
+        let Ok(input) = serde_json::from_value::<BatchToolInput>(input.clone()) else {
+            return true;
+        };
+        let registry = ToolRegistry::global(cx);
+        input.invocations.iter().any(|invocation| {
+            registry
+                .tool(&invocation.name)
+                .is_none_or(|tool| tool.needs_confirmation(&invocation.input, cx))
+        })
+    }
+
+    fn nested_tool_uses(&self, input: &serde_json::Value) -> Vec<(String, serde_json::Value)> {
+        serde_json::from_value::<BatchToolInput>(input.clone())
+            .map(|input| {
+                input
+                    .invocations
+                    .into_iter()
+                    .map(|invocation| (invocation.name, invocation.input))
+                    .collect()
+            })
+            .unwrap_or_default()
     }
 
     fn description(&self) -> String {
@@ -210,14 +232,14 @@ impl Tool for BatchTool {
         project: Entity<Project>,
         action_log: Entity<ActionLog>,
         cx: &mut App,
-    ) -> Task<Result<String>> {
+    ) -> ToolResult {
         let input = match serde_json::from_value::<BatchToolInput>(input) {
             Ok(input) => input,
-            Err(err) => return Task::ready(Err(anyhow!(err))),
+            Err(err) => return Task::ready(Err(anyhow!(err))).into(),
         };
 
         if input.invocations.is_empty() {
-            return Task::ready(Err(anyhow!("No tool invocations provided")));
+            return Task::ready(Err(anyhow!("No tool invocations provided"))).into();
         }
 
         let run_tools_concurrently = input.run_tools_concurrently;
@@ -248,11 +270,11 @@ impl Tool for BatchTool {
                     let project = project.clone();
                     let action_log = action_log.clone();
                     let messages = messages.clone();
-                    let task = cx
+                    let tool_result = cx
                         .update(|cx| tool.run(invocation.input, &messages, project, action_log, cx))
                         .map_err(|err| anyhow!("Failed to start tool '{}': {}", tool_name, err))?;
 
-                    tasks.push(task);
+                    tasks.push(tool_result.output);
                 }
 
                 Ok((tasks, tool_names))
@@ -271,31 +293,191 @@ impl Tool for BatchTool {
                 }
             };
 
-            let mut formatted_results = String::new();
-            let mut error_occurred = false;
+            let content = combine_results(&tool_names, results);
+            Ok(content.into())
+        })
+        .into()
+    }
+}
 
-            for (i, result) in results.into_iter().enumerate() {
-                let tool_name = &tool_names[i];
+/// Combines the outputs of the tools into text, keeping images in place between the text
+/// of the results. Text parts that would be empty are left out, since models reject them.
+fn combine_results(
+    tool_names: &[String],
+    results: Vec<Result<ToolResultOutput>>,
+) -> Vec<LanguageModelToolResultContent> {
+    fn push_text(content: &mut Vec<LanguageModelToolResultContent>, text: &str) {
+        let text = text.trim();
+        if !text.is_empty() {
+            content.push(text.into());
+        }
+    }
 
-                match result {
-                    Ok(output) => {
-                        formatted_results
-                            .push_str(&format!("Tool '{}' result:\n{}\n\n", tool_name, output));
-                    }
-                    Err(err) => {
-                        error_occurred = true;
-                        formatted_results
-                            .push_str(&format!("Tool '{}' error: {}\n\n", tool_name, err));
+    let mut content = Vec::new();
+    let mut formatted_results = String::new();
+    let mut error_occurred = false;
+
+    for (tool_name, result) in tool_names.iter().zip(results) {
+        match result {
+            Ok(output) => {
+                formatted_results.push_str(&format!("Tool '{}' result:\n", tool_name));
+                for part in output.content {
+                    match part {
+                        LanguageModelToolResultContent::Image { .. } => {
+                            push_text(&mut content, &std::mem::take(&mut formatted_results));
+                            content.push(part);
+                        }
+                        part => {
+                            formatted_results.push_str(&part.to_text().unwrap_or_default());
+                            formatted_results.push('\n');
+                        }
                     }
                 }
+                formatted_results.push('\n');
             }
-
-            if error_occurred {
-                formatted_results
-                    .push_str("Note: Some tool invocations failed. See individual results above.");
+            Err(err) => {
+                error_occurred = true;
+                formatted_results.push_str(&format!("Tool '{}' error: {}\n\n", tool_name, err));
             }
+        }
+    }
 
-            Ok(formatted_results.trim().to_string())
-        })
+    if error_occurred {
+        formatted_results
+            .push_str("Note: Some tool invocations failed. See individual results above.");
+    }
+
+    push_text(&mut content, &formatted_results);
+    content
+}
+
+#[cfg(test)]
+mod tests {
+    use assistant_settings::{AssistantSettings, ToolPermissionAction, ToolPermissionRule};
+    use assistant_tool::evaluate_tool_permission;
+    use gpui::TestAppContext;
+    use language_model::LanguageModelImage;
+    use project::{FakeFs, Project};
+    use serde_json::json;
+    use settings::{Settings as _, SettingsStore};
+    use util::path;
+
+    use crate::bash_tool::BashTool;
+
+    use super::*;
+
+    fn image() -> LanguageModelToolResultContent {
+        let image: LanguageModelImage = serde_json::from_value(json!({
+            "source": "aW1hZ2U=",
+            "size": { "width": 1, "height": 1 }
+        }))
+        .unwrap();
+        image.into()
+    }
+
+    #[test]
+    fn test_combine_image_only_results() {
+        let content = combine_results(
+            &["screenshot".to_string(), "screenshot".to_string()],
+            vec![Ok(vec![image()].into()), Ok(vec![image()].into())],
+        );
+        assert_eq!(
+            content,
+            vec![
+                "Tool 'screenshot' result:".into(),
+                image(),
+                "Tool 'screenshot' result:".into(),
+                image(),
+            ]
+        );
+        assert!(content.iter().all(|part| match part {
+            LanguageModelToolResultContent::Text { text } => !text.trim().is_empty(),
+            _ => true,
+        }));
+    }
+
+    #[test]
+    fn test_combine_text_and_error_results() {
+        let content = combine_results(
+            &["read_file".to_string(), "fetch".to_string()],
+            vec![Ok(vec!["contents".into()].into()), Err(anyhow!("offline"))],
+        );
+        assert_eq!(
+            content,
+            vec![
+                concat!(
+                    "Tool 'read_file' result:\n",
+                    "contents\n\n",
+                    "Tool 'fetch' error: offline\n\n",
+                    "Note: Some tool invocations failed. See individual results above."
+                )
+                .into()
+            ]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_batch_permission_includes_invocations(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            language::init(cx);
+            Project::init_settings(cx);
+            AssistantSettings::register(cx);
+            AssistantSettings::override_global(
+                AssistantSettings {
+                    always_allow_tool_actions: true,
+                    tool_permissions: vec![ToolPermissionRule {
+                        tool: "bash".into(),
+                        input: [("command".into(), "*rm -rf*".into())]
+                            .into_iter()
+                            .collect(),
+                        outside_worktree: false,
+                        action: ToolPermissionAction::Deny,
+                    }],
+                    ..Default::default()
+                },
+                cx,
+            );
+            let registry = ToolRegistry::default_global(cx);
+            registry.register_tool(BashTool);
+            registry.register_tool(BatchTool);
+        });
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/dir"), json!({})).await;
+        let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+
+        let batch = |commands: &[&str]| {
+            json!({
+                "invocations": commands
+                    .iter()
+                    .map(|command| json!({
+                        "name": "bash",
+                        "input": { "command": command, "cd": "dir" },
+                    }))
+                    .collect::<Vec<_>>(),
+            })
+        };
+        let evaluate = |input: serde_json::Value, cx: &mut TestAppContext| {
+            project.read_with(cx, |project, cx| {
+                evaluate_tool_permission(&BatchTool, &input, project, cx).action
+            })
+        };
+
+        assert_eq!(
+            evaluate(batch(&["cargo test", "ls"]), cx),
+            ToolPermissionAction::Allow
+        );
+        // Wrapping a denied command in a batch doesn't get it past its rule.
+        assert_eq!(
+            evaluate(batch(&["ls", "rm -rf ~"]), cx),
+            ToolPermissionAction::Deny
+        );
+
+        // Without `always_allow_tool_actions`, the batch's commands need confirmation.
+        cx.update(|cx| {
+            AssistantSettings::override_global(AssistantSettings::default(), cx);
+        });
+        assert_eq!(evaluate(batch(&["ls"]), cx), ToolPermissionAction::Ask);
     }
 }
@@ -1,6 +1,6 @@
 use crate::schema::json_schema_for;
-use anyhow::{Result, anyhow};
-use assistant_tool::{ActionLog, Tool};
+use anyhow::anyhow;
+use assistant_tool::{ActionLog, Tool, ToolResult};
 use gpui::{App, AppContext, Entity, Task};
 use language_model::LanguageModelRequestMessage;
 use language_model::LanguageModelToolSchemaFormat;
@@ -43,10 +43,17 @@ impl Tool for CopyPathTool {
         "copy_path".into()
     }
 
-    fn needs_confirmation(&self) -> bool {
+    fn needs_confirmation(&self, _: &serde_json::Value, _: &App) -> bool {
         true
     }
 
+    fn write_paths(&self, input: &serde_json::Value) -> Vec<String> {
+        match serde_json::from_value::<CopyPathToolInput>(input.clone()) {
+            Ok(input) => vec![input.destination_path],
+            Err(_) => Vec::new(),
+        }
+    }
+
     fn description(&self) -> String {
         include_str!("./copy_path_tool/description.md").into()
     }
@@ -77,10 +84,10 @@ impl Tool for CopyPathTool {
         project: Entity<Project>,
         _action_log: Entity<ActionLog>,
         cx: &mut App,
-    ) -> Task<Result<String>> {
+    ) -> ToolResult {
         let input = match serde_json::from_value::<CopyPathToolInput>(input) {
             Ok(input) => input,
-            Err(err) => return Task::ready(Err(anyhow!(err))),
+            Err(err) => return Task::ready(Err(anyhow!(err))).into(),
         };
         let copy_task = project.update(cx, |project, cx| {
             match project
@@ -105,10 +112,9 @@ impl Tool for CopyPathTool {
 
         cx.background_spawn(async move {
             match copy_task.await {
-                Ok(_) => Ok(format!(
-                    "Copied {} to {}",
-                    input.source_path, input.destination_path
-                )),
+                Ok(_) => Ok(
+                    format!("Copied {} to {}", input.source_path, input.destination_path).into(),
+                ),
                 Err(err) => Err(anyhow!(
                     "Failed to copy {} to {}: {}",
                     input.source_path,
@@ -117,5 +123,6 @@ impl Tool for CopyPathTool {
                 )),
             }
         })
+        .into()
     }
 }
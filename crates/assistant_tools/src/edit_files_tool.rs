@@ -4,7 +4,7 @@ pub mod log;
 use crate::replace::{replace_exact, replace_with_flexible_indent};
 use crate::schema::json_schema_for;
 use anyhow::{Context, Result, anyhow};
-use assistant_tool::{ActionLog, Tool};
+use assistant_tool::{ActionLog, Tool, ToolResult};
 use collections::HashSet;
 use edit_action::{EditAction, EditActionParser, edit_model_prompt};
 use futures::{SinkExt, StreamExt, channel::mpsc};
@@ -81,7 +81,7 @@ impl Tool for EditFilesTool {
         "edit_files".into()
     }
 
-    fn needs_confirmation(&self) -> bool {
+    fn needs_confirmation(&self, _: &serde_json::Value, _: &App) -> bool {
         false
     }
 
@@ -111,10 +111,10 @@ impl Tool for EditFilesTool {
         project: Entity<Project>,
         action_log: Entity<ActionLog>,
         cx: &mut App,
-    ) -> Task<Result<String>> {
+    ) -> ToolResult {
         let input = match serde_json::from_value::<EditFilesToolInput>(input) {
             Ok(input) => input,
-            Err(err) => return Task::ready(Err(anyhow!(err))),
+            Err(err) => return Task::ready(Err(anyhow!(err))).into(),
         };
 
         match EditToolLog::try_global(cx) {
@@ -143,11 +143,16 @@ impl Tool for EditFilesTool {
                     log.update(cx, |log, cx| log.set_tool_output(req_id, str_result, cx))
                         .log_err();
 
-                    result
+                    result.map(Into::into)
                 })
+                .into()
             }
 
-            None => EditToolRequest::new(input, messages, project, action_log, None, cx),
+            None => {
+                let task = EditToolRequest::new(input, messages, project, action_log, None, cx);
+                cx.background_spawn(async move { task.await.map(Into::into) })
+                    .into()
+            }
         }
     }
 }
@@ -239,6 +244,7 @@ impl EditToolRequest {
                 tools: vec![],
                 stop: vec![],
                 temperature: Some(0.0),
+                max_tokens: None,
             };
 
             let (mut tx, mut rx) = mpsc::channel::<String>(32);
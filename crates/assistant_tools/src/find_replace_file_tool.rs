@@ -1,6 +1,6 @@
 use crate::{replace::replace_with_flexible_indent, schema::json_schema_for};
 use anyhow::{Context as _, Result, anyhow};
-use assistant_tool::{ActionLog, Tool};
+use assistant_tool::{ActionLog, Tool, ToolResult};
 use gpui::{App, AppContext, AsyncApp, Entity, Task};
 use language_model::{LanguageModelRequestMessage, LanguageModelToolSchemaFormat};
 use project::Project;
@@ -129,10 +129,17 @@ impl Tool for FindReplaceFileTool {
         "find_replace_file".into()
     }
 
-    fn needs_confirmation(&self) -> bool {
+    fn needs_confirmation(&self, _: &serde_json::Value, _: &App) -> bool {
         false
     }
 
+    fn write_paths(&self, input: &serde_json::Value) -> Vec<String> {
+        match serde_json::from_value::<FindReplaceFileToolInput>(input.clone()) {
+            Ok(input) => vec![input.path.to_string_lossy().into_owned()],
+            Err(_) => Vec::new(),
+        }
+    }
+
     fn description(&self) -> String {
         include_str!("find_replace_tool/description.md").to_string()
     }
@@ -159,10 +166,10 @@ impl Tool for FindReplaceFileTool {
         project: Entity<Project>,
         action_log: Entity<ActionLog>,
         cx: &mut App,
-    ) -> Task<Result<String>> {
+    ) -> ToolResult {
         let input = match serde_json::from_value::<FindReplaceFileToolInput>(input) {
             Ok(input) => input,
-            Err(err) => return Task::ready(Err(anyhow!(err))),
+            Err(err) => return Task::ready(Err(anyhow!(err))).into(),
         };
 
         cx.spawn(async move |cx: &mut AsyncApp| {
@@ -251,8 +258,9 @@ impl Tool for FindReplaceFileTool {
             }).await;
 
 
-            Ok(format!("Edited {}:\n\n```diff\n{}\n```", input.path.display(), diff_str))
+            Ok(format!("Edited {}:\n\n```diff\n{}\n```", input.path.display(), diff_str).into())
 
         })
+        .into()
     }
 }
@@ -2,11 +2,13 @@ use std::path::Path;
 use std::sync::Arc;
 
 use crate::schema::json_schema_for;
-use anyhow::{Result, anyhow};
-use assistant_tool::{ActionLog, Tool};
-use gpui::{App, Entity, Task};
+use anyhow::anyhow;
+use assistant_tool::{ActionLog, Tool, ToolResult};
+use gpui::{App, Entity, Img, Task};
 use itertools::Itertools;
-use language_model::{LanguageModelRequestMessage, LanguageModelToolSchemaFormat};
+use language_model::{
+    LanguageModelImage, LanguageModelRequestMessage, LanguageModelToolSchemaFormat,
+};
 use project::Project;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -47,7 +49,7 @@ impl Tool for ReadFileTool {
         "read_file".into()
     }
 
-    fn needs_confirmation(&self) -> bool {
+    fn needs_confirmation(&self, _: &serde_json::Value, _: &App) -> bool {
         false
     }
 
@@ -80,19 +82,40 @@ impl Tool for ReadFileTool {
         project: Entity<Project>,
         action_log: Entity<ActionLog>,
         cx: &mut App,
-    ) -> Task<Result<String>> {
+    ) -> ToolResult {
         let input = match serde_json::from_value::<ReadFileToolInput>(input) {
             Ok(input) => input,
-            Err(err) => return Task::ready(Err(anyhow!(err))),
+            Err(err) => return Task::ready(Err(anyhow!(err))).into(),
         };
 
         let Some(project_path) = project.read(cx).find_project_path(&input.path, cx) else {
             return Task::ready(Err(anyhow!(
                 "Path {} not found in project",
                 &input.path.display()
-            )));
+            )))
+            .into();
         };
 
+        if is_image_path(&input.path) {
+            return cx
+                .spawn(async move |cx| {
+                    let image_item = project
+                        .update(cx, |project, cx| project.open_image(project_path, cx))?
+                        .await?;
+                    let bytes = image_item
+                        .read_with(cx, |image_item, _| image_item.image.bytes().to_vec())?;
+                    let image = cx
+                        .update(|cx| LanguageModelImage::from_bytes(bytes, cx))?
+                        .await
+                        .ok_or_else(|| {
+                            anyhow!("Failed to decode image {}", input.path.display())
+                        })?;
+
+                    Ok(image.into())
+                })
+                .into();
+        }
+
         cx.spawn(async move |cx| {
             let buffer = cx
                 .update(|cx| {
@@ -120,7 +143,17 @@ impl Tool for ReadFileTool {
                 log.buffer_read(buffer, cx);
             })?;
 
-            anyhow::Ok(result)
+            anyhow::Ok(result.into())
         })
+        .into()
     }
 }
+
+/// Returns whether the path points to a raster image, which is read as an image rather than as text.
+fn is_image_path(path: &Path) -> bool {
+    let Some(extension) = path.extension().and_then(|extension| extension.to_str()) else {
+        return false;
+    };
+    let extension = extension.to_lowercase();
+    extension != "svg" && Img::extensions().contains(&extension.as_str())
+}
@@ -71,6 +71,9 @@ struct Args {
     /// Custom path to Zed.app or the zed binary
     #[arg(long)]
     zed: Option<PathBuf>,
+    /// Import an agent thread exported from Zed, and open it in the agent panel
+    #[arg(long, value_name = "PATH")]
+    import_thread: Option<PathBuf>,
     /// Run zed in dev-server mode
     #[arg(long)]
     dev_server_token: Option<String>,
@@ -238,6 +241,12 @@ fn main() -> Result<()> {
         }
     }
 
+    if let Some(path) = args.import_thread.as_ref() {
+        let path = fs::canonicalize(path)
+            .with_context(|| format!("reading exported thread {}", path.display()))?;
+        urls.push(format!("zed-import-thread://{}", path.display()));
+    }
+
     if let Some(_) = args.dev_server_token {
         return Err(anyhow::anyhow!(
             "Dev servers were removed in v0.157.x please upgrade to SSH remoting: https://zed.dev/docs/remote-development"
@@ -117,6 +117,10 @@ CREATE TABLE "project_repositories" (
     "is_deleted" BOOL NOT NULL,
     "current_merge_conflicts" VARCHAR,
     "branch_summary" VARCHAR,
+    "rebase_state" VARCHAR,
+    "submodules" VARCHAR,
+    "sequencer_command" INTEGER,
+    "detached_head" VARCHAR,
     PRIMARY KEY (project_id, id)
 );
 
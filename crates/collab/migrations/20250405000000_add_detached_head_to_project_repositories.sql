@@ -0,0 +1 @@
+ALTER TABLE project_repositories ADD COLUMN detached_head VARCHAR NULL;
@@ -348,9 +348,14 @@ impl Database {
                                         .unwrap(),
                                 )),
 
-                                // Old clients do not use abs path or entry ids.
+                                // Old clients do not use abs path, entry ids, rebase state, submodules,
+                                // sequencer commands or detached heads.
                                 abs_path: ActiveValue::set(String::new()),
                                 entry_ids: ActiveValue::set("[]".into()),
+                                rebase_state: ActiveValue::set(None),
+                                submodules: ActiveValue::set(None),
+                                sequencer_command: ActiveValue::set(None),
+                                detached_head: ActiveValue::set(None),
                             }
                         }),
                     )
@@ -493,6 +498,22 @@ impl Database {
                 current_merge_conflicts: ActiveValue::Set(Some(
                     serde_json::to_string(&update.current_merge_conflicts).unwrap(),
                 )),
+                rebase_state: ActiveValue::Set(
+                    update
+                        .rebase_state
+                        .as_ref()
+                        .map(|rebase_state| serde_json::to_string(rebase_state).unwrap()),
+                ),
+                submodules: ActiveValue::Set(Some(
+                    serde_json::to_string(&update.submodules).unwrap(),
+                )),
+                sequencer_command: ActiveValue::Set(update.sequencer_command),
+                detached_head: ActiveValue::Set(
+                    update
+                        .detached_head
+                        .as_ref()
+                        .map(|detached_head| serde_json::to_string(detached_head).unwrap()),
+                ),
             })
             .on_conflict(
                 OnConflict::columns([
@@ -505,6 +526,10 @@ impl Database {
                     project_repository::Column::EntryIds,
                     project_repository::Column::AbsPath,
                     project_repository::Column::CurrentMergeConflicts,
+                    project_repository::Column::RebaseState,
+                    project_repository::Column::Submodules,
+                    project_repository::Column::SequencerCommand,
+                    project_repository::Column::DetachedHead,
                 ])
                 .to_owned(),
             )
@@ -928,6 +953,25 @@ impl Database {
                     .transpose()?
                     .unwrap_or_default();
 
+                let rebase_state = db_repository_entry
+                    .rebase_state
+                    .as_ref()
+                    .map(|rebase_state| serde_json::from_str(&rebase_state))
+                    .transpose()?;
+
+                let submodules = db_repository_entry
+                    .submodules
+                    .as_ref()
+                    .map(|submodules| serde_json::from_str(&submodules))
+                    .transpose()?
+                    .unwrap_or_default();
+
+                let detached_head = db_repository_entry
+                    .detached_head
+                    .as_ref()
+                    .map(|detached_head| serde_json::from_str(&detached_head))
+                    .transpose()?;
+
                 let entry_ids = serde_json::from_str(&db_repository_entry.entry_ids)
                     .context("failed to deserialize repository's entry ids")?;
 
@@ -956,6 +1000,10 @@ impl Database {
                         branch_summary,
                         scan_id: db_repository_entry.scan_id as u64,
                         is_last_update: true,
+                        rebase_state,
+                        submodules,
+                        sequencer_command: db_repository_entry.sequencer_command,
+                        detached_head,
                     });
                 }
             }
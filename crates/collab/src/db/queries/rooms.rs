@@ -755,6 +755,25 @@ impl Database {
                         .transpose()?
                         .unwrap_or_default();
 
+                    let rebase_state = db_repository
+                        .rebase_state
+                        .as_ref()
+                        .map(|rebase_state| serde_json::from_str(&rebase_state))
+                        .transpose()?;
+
+                    let submodules = db_repository
+                        .submodules
+                        .as_ref()
+                        .map(|submodules| serde_json::from_str(&submodules))
+                        .transpose()?
+                        .unwrap_or_default();
+
+                    let detached_head = db_repository
+                        .detached_head
+                        .as_ref()
+                        .map(|detached_head| serde_json::from_str(&detached_head))
+                        .transpose()?;
+
                     let entry_ids = serde_json::from_str(&db_repository.entry_ids)
                         .context("failed to deserialize repository's entry ids")?;
 
@@ -783,6 +802,10 @@ impl Database {
                             abs_path: db_repository.abs_path,
                             scan_id: db_repository.scan_id as u64,
                             is_last_update: true,
+                            rebase_state,
+                            submodules,
+                            sequencer_command: db_repository.sequencer_command,
+                            detached_head,
                         });
                     }
                 }
@@ -18,6 +18,14 @@ pub struct Model {
     pub current_merge_conflicts: Option<String>,
     // A JSON object representing the current Branch values
     pub branch_summary: Option<String>,
+    // A JSON object representing the rebase in progress
+    pub rebase_state: Option<String>,
+    // A JSON array representing the repository's submodules
+    pub submodules: Option<String>,
+    // The cherry-pick or revert in progress, as a `proto::SequencerCommand`
+    pub sequencer_command: Option<i32>,
+    // A JSON object representing the commit checked out when HEAD is detached
+    pub detached_head: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
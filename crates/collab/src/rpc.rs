@@ -312,6 +312,15 @@ impl Server {
             .add_request_handler(forward_read_only_project_request::<proto::InlayHints>)
             .add_request_handler(forward_read_only_project_request::<proto::ResolveInlayHint>)
             .add_request_handler(forward_mutating_project_request::<proto::GetCodeLens>)
+            .add_request_handler(forward_read_only_project_request::<proto::GetSemanticTokens>)
+            .add_request_handler(forward_read_only_project_request::<proto::PrepareCallHierarchy>)
+            .add_request_handler(forward_read_only_project_request::<proto::GetIncomingCalls>)
+            .add_request_handler(forward_read_only_project_request::<proto::GetOutgoingCalls>)
+            .add_request_handler(forward_read_only_project_request::<proto::PrepareTypeHierarchy>)
+            .add_request_handler(forward_read_only_project_request::<proto::GetSupertypes>)
+            .add_request_handler(forward_read_only_project_request::<proto::GetSubtypes>)
+            .add_request_handler(forward_read_only_project_request::<proto::GetFoldingRanges>)
+            .add_request_handler(forward_read_only_project_request::<proto::ResolveCodeLens>)
             .add_request_handler(forward_read_only_project_request::<proto::OpenBufferByPath>)
             .add_request_handler(forward_read_only_project_request::<proto::GitGetBranches>)
             .add_request_handler(forward_read_only_project_request::<proto::OpenUnstagedDiff>)
@@ -361,6 +370,9 @@ impl Server {
             .add_request_handler(update_buffer)
             .add_message_handler(broadcast_project_message_from_host::<proto::RefreshInlayHints>)
             .add_message_handler(broadcast_project_message_from_host::<proto::RefreshCodeLens>)
+            .add_message_handler(
+                broadcast_project_message_from_host::<proto::RefreshSemanticTokens>,
+            )
             .add_message_handler(broadcast_project_message_from_host::<proto::UpdateBufferFile>)
             .add_message_handler(broadcast_project_message_from_host::<proto::BufferReloaded>)
             .add_message_handler(broadcast_project_message_from_host::<proto::BufferSaved>)
@@ -412,10 +424,30 @@ impl Server {
             .add_request_handler(forward_mutating_project_request::<proto::Commit>)
             .add_request_handler(forward_mutating_project_request::<proto::GitInit>)
             .add_request_handler(forward_read_only_project_request::<proto::GetRemotes>)
+            .add_request_handler(forward_read_only_project_request::<proto::GitGetRemoteUrl>)
             .add_request_handler(forward_read_only_project_request::<proto::GitShow>)
             .add_request_handler(forward_read_only_project_request::<proto::LoadCommitDiff>)
             .add_request_handler(forward_read_only_project_request::<proto::GitReset>)
             .add_request_handler(forward_read_only_project_request::<proto::GitCheckoutFiles>)
+            .add_request_handler(forward_mutating_project_request::<proto::GitStashPush>)
+            .add_request_handler(forward_read_only_project_request::<proto::GitStashList>)
+            .add_request_handler(forward_mutating_project_request::<proto::GitStashApply>)
+            .add_request_handler(forward_mutating_project_request::<proto::GitStashPop>)
+            .add_request_handler(forward_mutating_project_request::<proto::GitStashDrop>)
+            .add_request_handler(forward_read_only_project_request::<proto::GitStashShow>)
+            .add_request_handler(forward_read_only_project_request::<proto::GitLog>)
+            .add_request_handler(forward_mutating_project_request::<proto::GitRebase>)
+            .add_request_handler(forward_mutating_project_request::<proto::GitRebaseOperation>)
+            .add_request_handler(forward_read_only_project_request::<proto::GitWorktrees>)
+            .add_request_handler(forward_mutating_project_request::<proto::GitCreateWorktree>)
+            .add_request_handler(forward_mutating_project_request::<proto::GitRemoveWorktree>)
+            .add_request_handler(forward_mutating_project_request::<proto::GitCherryPick>)
+            .add_request_handler(forward_mutating_project_request::<proto::GitRevert>)
+            .add_request_handler(forward_mutating_project_request::<proto::GitSequencerOperation>)
+            .add_request_handler(forward_read_only_project_request::<proto::GitTags>)
+            .add_request_handler(forward_mutating_project_request::<proto::GitCreateTag>)
+            .add_request_handler(forward_mutating_project_request::<proto::GitDeleteTag>)
+            .add_request_handler(forward_mutating_project_request::<proto::GitCheckoutTag>)
             .add_request_handler(forward_mutating_project_request::<proto::SetIndexText>)
             .add_request_handler(forward_mutating_project_request::<proto::ToggleBreakpoint>)
             .add_message_handler(broadcast_project_message_from_host::<proto::BreakpointsForFile>)
@@ -2,8 +2,9 @@ use anyhow::{Context, Result, anyhow};
 use collections::HashMap;
 use futures::{FutureExt, StreamExt, channel::oneshot, select};
 use gpui::{AppContext as _, AsyncApp, BackgroundExecutor, Task};
+use http_client::{HttpClient, Url};
 use parking_lot::Mutex;
-use postage::barrier;
+use postage::{barrier, prelude::Stream as _};
 use serde::{Deserialize, Serialize, de::DeserializeOwned};
 use serde_json::{Value, value::RawValue};
 use smol::channel;
@@ -18,7 +19,9 @@ use std::{
 };
 use util::TryFutureExt;
 
-use crate::transport::{StdioTransport, Transport};
+use crate::HttpTransportKind;
+use crate::transport::{HttpTransport, StdioTransport, Transport};
+use crate::types;
 
 const JSON_RPC_VERSION: &str = "2.0";
 const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
@@ -32,6 +35,7 @@ pub const INTERNAL_ERROR: i32 = -32603;
 
 type ResponseHandler = Box<dyn Send + FnOnce(Result<String, Error>)>;
 type NotificationHandler = Box<dyn Send + FnMut(Value, AsyncApp)>;
+type RequestHandler = Box<dyn Send + FnMut(Value, AsyncApp) -> Task<Result<Value>>>;
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
 #[serde(untagged)]
@@ -46,6 +50,7 @@ pub struct Client {
     outbound_tx: channel::Sender<String>,
     name: Arc<str>,
     notification_handlers: Arc<Mutex<HashMap<&'static str, NotificationHandler>>>,
+    request_handlers: Arc<Mutex<HashMap<&'static str, RequestHandler>>>,
     response_handlers: Arc<Mutex<Option<HashMap<RequestId, ResponseHandler>>>>,
     #[allow(clippy::type_complexity)]
     #[allow(dead_code)]
@@ -55,6 +60,7 @@ pub struct Client {
     executor: BackgroundExecutor,
     #[allow(dead_code)]
     transport: Arc<dyn Transport>,
+    input_done_rx: barrier::Receiver,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -106,6 +112,25 @@ enum CspResult<T> {
     Error(Option<Error>),
 }
 
+#[derive(Debug, Clone, Deserialize)]
+struct AnyRequest<'a> {
+    jsonrpc: &'a str,
+    id: RequestId,
+    method: String,
+    #[serde(default)]
+    params: Option<Value>,
+}
+
+#[derive(Serialize)]
+struct OutgoingResponse {
+    jsonrpc: &'static str,
+    id: RequestId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<Error>,
+}
+
 #[derive(Serialize, Deserialize)]
 struct Notification<'a, T> {
     jsonrpc: &'static str,
@@ -124,6 +149,8 @@ struct AnyNotification<'a> {
 
 #[derive(Debug, Serialize, Deserialize)]
 struct Error {
+    #[serde(default)]
+    code: i32,
     message: String,
 }
 
@@ -134,6 +161,13 @@ pub struct ModelContextServerBinary {
     pub env: Option<HashMap<String, String>>,
 }
 
+#[derive(Debug, Clone)]
+pub struct ModelContextServerEndpoint {
+    pub url: Url,
+    pub transport: HttpTransportKind,
+    pub headers: HashMap<String, String>,
+}
+
 impl Client {
     /// Creates a new Client instance for a context server.
     ///
@@ -158,23 +192,62 @@ impl Client {
             .unwrap_or_else(String::new);
 
         let transport = Arc::new(StdioTransport::new(binary, &cx)?);
+        Self::with_transport(server_id, server_name.into(), transport, cx)
+    }
+
+    /// Creates a new Client instance for a context server that runs as an HTTP service.
+    pub fn http(
+        server_id: ContextServerId,
+        endpoint: ModelContextServerEndpoint,
+        http_client: Arc<dyn HttpClient>,
+        cx: AsyncApp,
+    ) -> Result<Self> {
+        log::info!(
+            "connecting to context server (url={}, transport={:?})",
+            endpoint.url,
+            endpoint.transport
+        );
+
+        let server_name = endpoint.url.host_str().unwrap_or_default().to_string();
+        let transport = Arc::new(HttpTransport::new(endpoint, http_client, &cx)?);
+        Self::with_transport(server_id, server_name.into(), transport, cx)
+    }
 
+    fn with_transport(
+        server_id: ContextServerId,
+        server_name: Arc<str>,
+        transport: Arc<dyn Transport>,
+        cx: AsyncApp,
+    ) -> Result<Self> {
         let (outbound_tx, outbound_rx) = channel::unbounded::<String>();
         let (output_done_tx, output_done_rx) = barrier::channel();
+        let (input_done_tx, input_done_rx) = barrier::channel();
 
         let notification_handlers =
             Arc::new(Mutex::new(HashMap::<_, NotificationHandler>::default()));
+        let request_handlers = Arc::new(Mutex::new(HashMap::<_, RequestHandler>::default()));
         let response_handlers =
             Arc::new(Mutex::new(Some(HashMap::<_, ResponseHandler>::default())));
 
         let stdout_input_task = cx.spawn({
             let notification_handlers = notification_handlers.clone();
+            let request_handlers = request_handlers.clone();
             let response_handlers = response_handlers.clone();
             let transport = transport.clone();
+            let outbound_tx = outbound_tx.clone();
             async move |cx| {
-                Self::handle_input(transport, notification_handlers, response_handlers, cx)
-                    .log_err()
-                    .await
+                let result = Self::handle_input(
+                    transport,
+                    notification_handlers,
+                    request_handlers,
+                    response_handlers,
+                    outbound_tx,
+                    cx,
+                )
+                .log_err()
+                .await;
+                drop(input_done_tx);
+                result
             }
         });
         let stderr_input_task = cx.spawn({
@@ -200,33 +273,76 @@ impl Client {
         Ok(Self {
             server_id,
             notification_handlers,
+            request_handlers,
             response_handlers,
-            name: server_name.into(),
+            name: server_name,
             next_id: Default::default(),
             outbound_tx,
             executor: cx.background_executor().clone(),
             io_tasks: Mutex::new(Some((input_task, output_task))),
             output_done_rx: Mutex::new(Some(output_done_rx)),
             transport,
+            input_done_rx,
         })
     }
 
     /// Handles input from the server's stdout.
     ///
     /// This function continuously reads lines from the provided stdout stream,
-    /// parses them as JSON-RPC responses or notifications, and dispatches them
-    /// to the appropriate handlers. It processes both responses (which are matched
-    /// to pending requests) and notifications (which trigger registered handlers).
+    /// parses them as JSON-RPC requests, responses or notifications, and dispatches them
+    /// to the appropriate handlers. It processes requests (which are answered by registered
+    /// handlers), responses (which are matched to pending requests) and notifications
+    /// (which trigger registered handlers).
     async fn handle_input(
         transport: Arc<dyn Transport>,
         notification_handlers: Arc<Mutex<HashMap<&'static str, NotificationHandler>>>,
+        request_handlers: Arc<Mutex<HashMap<&'static str, RequestHandler>>>,
         response_handlers: Arc<Mutex<Option<HashMap<RequestId, ResponseHandler>>>>,
+        outbound_tx: channel::Sender<String>,
         cx: &mut AsyncApp,
     ) -> anyhow::Result<()> {
         let mut receiver = transport.receive();
 
         while let Some(message) = receiver.next().await {
-            if let Ok(response) = serde_json::from_str::<AnyResponse>(&message) {
+            if let Ok(request) = serde_json::from_str::<AnyRequest>(&message) {
+                let response = request_handlers
+                    .lock()
+                    .get_mut(request.method.as_str())
+                    .map(|handler| handler(request.params.unwrap_or(Value::Null), cx.clone()));
+                let outbound_tx = outbound_tx.clone();
+                let AnyRequest { id, method, .. } = request;
+                cx.background_spawn(
+                    async move {
+                        let response = match response {
+                            Some(response) => response.await.map_err(|error| Error {
+                                code: INTERNAL_ERROR,
+                                message: error.to_string(),
+                            }),
+                            None if method == types::RequestType::Ping.as_str() => {
+                                Ok(Value::Object(Default::default()))
+                            }
+                            None => Err(Error {
+                                code: METHOD_NOT_FOUND,
+                                message: format!("method not found: {method}"),
+                            }),
+                        };
+                        let (result, error) = match response {
+                            Ok(result) => (Some(result), None),
+                            Err(error) => (None, Some(error)),
+                        };
+                        let response = serde_json::to_string(&OutgoingResponse {
+                            jsonrpc: JSON_RPC_VERSION,
+                            id,
+                            result,
+                            error,
+                        })?;
+                        outbound_tx.send(response).await?;
+                        anyhow::Ok(())
+                    }
+                    .log_err(),
+                )
+                .detach();
+            } else if let Ok(response) = serde_json::from_str::<AnyResponse>(&message) {
                 if let Some(handlers) = response_handlers.lock().as_mut() {
                     if let Some(handler) = handlers.remove(&response.id) {
                         handler(Ok(message.to_string()));
@@ -367,6 +483,25 @@ impl Client {
             .insert(method, Box::new(f));
     }
 
+    /// Returns a future that resolves once the connection to the context server
+    /// is closed, e.g. because the server exited or the network connection dropped.
+    pub fn closed(&self) -> impl Future<Output = ()> + use<> {
+        let mut input_done_rx = self.input_done_rx.clone();
+        async move {
+            input_done_rx.recv().await;
+        }
+    }
+
+    /// Registers a handler for requests sent by the context server.
+    ///
+    /// The value returned by the handler's task is sent back as the result of the request.
+    pub fn on_request<F>(&self, method: &'static str, f: F)
+    where
+        F: 'static + Send + FnMut(Value, AsyncApp) -> Task<Result<Value>>,
+    {
+        self.request_handlers.lock().insert(method, Box::new(f));
+    }
+
     pub fn name(&self) -> &str {
         &self.name
     }
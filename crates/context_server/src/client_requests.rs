@@ -0,0 +1,326 @@
+//! Handlers for the requests context servers can send to Zed.
+//!
+//! These let context servers list the project's roots, and delegate language
+//! model calls to the model configured in Zed, after the user approves them.
+
+use std::sync::Arc;
+
+use anyhow::{Context as _, Result, anyhow, bail};
+use futures::StreamExt as _;
+use gpui::{App, AsyncApp, PromptLevel, WeakEntity};
+use language_model::{
+    LanguageModel, LanguageModelRegistry, LanguageModelRequest, LanguageModelRequestMessage,
+    MessageContent, Role,
+};
+use project::Project;
+use serde_json::Value;
+use url::Url;
+
+use crate::client::Client;
+use crate::types;
+
+/// The maximum number of characters of a sampling request shown when asking the user to approve it.
+const MAX_PROMPT_PREVIEW_CHARS: usize = 500;
+
+/// Returns the capabilities Zed supports as a context server client.
+pub(crate) fn capabilities() -> types::ClientCapabilities {
+    types::ClientCapabilities {
+        experimental: None,
+        sampling: Some(Value::Object(Default::default())),
+        roots: Some(types::RootsCapabilities {
+            list_changed: Some(true),
+        }),
+    }
+}
+
+/// Registers handlers for the requests context servers can send to Zed.
+pub(crate) fn register_handlers(
+    client: &Client,
+    server_id: Arc<str>,
+    project: WeakEntity<Project>,
+) {
+    client.on_request(types::RequestType::ListRoots.as_str(), move |_, cx| {
+        let project = project.clone();
+        cx.spawn(async move |cx| {
+            let response = list_roots(&project, cx)?;
+            Ok(serde_json::to_value(response)?)
+        })
+    });
+
+    client.on_request(
+        types::RequestType::CreateMessage.as_str(),
+        move |params, cx| {
+            let server_id = server_id.clone();
+            cx.spawn(async move |cx| {
+                let params = serde_json::from_value(params)?;
+                let response = create_message(&server_id, params, cx).await?;
+                Ok(serde_json::to_value(response)?)
+            })
+        },
+    );
+}
+
+/// Lists the project's visible worktrees as roots.
+fn list_roots(project: &WeakEntity<Project>, cx: &AsyncApp) -> Result<types::ListRootsResponse> {
+    project.read_with(cx, |project, cx| {
+        let roots = project
+            .visible_worktrees(cx)
+            .filter_map(|worktree| {
+                let worktree = worktree.read(cx);
+                Some(types::Root {
+                    uri: Url::from_file_path(worktree.abs_path()).ok()?,
+                    name: Some(worktree.root_name().to_string()),
+                })
+            })
+            .collect();
+
+        types::ListRootsResponse { roots, meta: None }
+    })
+}
+
+/// Generates a response to a sampling request with one of the user's language models.
+async fn create_message(
+    server_id: &str,
+    params: types::CreateMessageRequest,
+    cx: &mut AsyncApp,
+) -> Result<types::CreateMessageResult> {
+    let model = cx
+        .update(|cx| select_model(params.model_preferences.as_ref(), cx))?
+        .context("no language model is configured")?;
+
+    let mut messages = Vec::new();
+    if let Some(system_prompt) = params.system_prompt.filter(|prompt| !prompt.is_empty()) {
+        messages.push(LanguageModelRequestMessage {
+            role: Role::System,
+            content: vec![MessageContent::Text(system_prompt)],
+            cache: false,
+        });
+    }
+    for message in params.messages {
+        messages.push(to_request_message(message)?);
+    }
+
+    if !request_approval(server_id, model.as_ref(), &messages, cx).await? {
+        bail!("the user rejected the sampling request");
+    }
+
+    let request = LanguageModelRequest {
+        messages,
+        tools: Vec::new(),
+        stop: params.stop_sequences.unwrap_or_default(),
+        temperature: params.temperature.map(|temperature| temperature as f32),
+        max_tokens: Some(params.max_tokens),
+    };
+    let mut stream = model.stream_completion_text(request, cx).await?.stream;
+    let mut text = String::new();
+    while let Some(chunk) = stream.next().await {
+        text.push_str(&chunk?);
+    }
+
+    Ok(types::CreateMessageResult {
+        role: types::Role::Assistant,
+        content: types::MessageContent::Text {
+            text,
+            annotations: None,
+        },
+        model: model.id().0.to_string(),
+        stop_reason: Some("endTurn".to_string()),
+    })
+}
+
+/// Picks the first authenticated model matching the server's hints, falling
+/// back to the user's active model.
+fn select_model(
+    preferences: Option<&types::ModelPreferences>,
+    cx: &App,
+) -> Option<Arc<dyn LanguageModel>> {
+    let registry = LanguageModelRegistry::read_global(cx);
+    let hints = preferences
+        .and_then(|preferences| preferences.hints.as_ref())
+        .into_iter()
+        .flatten()
+        .filter_map(|hint| hint.name.as_deref());
+
+    for hint in hints {
+        let matching_model = registry.available_models(cx).find(|model| {
+            let is_authenticated = registry
+                .provider(&model.provider_id())
+                .is_some_and(|provider| provider.is_authenticated(cx));
+            is_authenticated && (model.id().0.contains(hint) || model.name().0.contains(hint))
+        });
+        if let Some(model) = matching_model {
+            return Some(model);
+        }
+    }
+
+    registry.active_model()
+}
+
+fn to_request_message(message: types::SamplingMessage) -> Result<LanguageModelRequestMessage> {
+    let role = match message.role {
+        types::Role::User => Role::User,
+        types::Role::Assistant => Role::Assistant,
+    };
+    let text = match message.content {
+        types::MessageContent::Text { text, .. } => text,
+        types::MessageContent::Image { .. } => {
+            return Err(anyhow!("images are not supported in sampling requests"));
+        }
+        types::MessageContent::Resource { .. } => {
+            return Err(anyhow!("resources are not supported in sampling requests"));
+        }
+    };
+
+    Ok(LanguageModelRequestMessage {
+        role,
+        content: vec![MessageContent::Text(text)],
+        cache: false,
+    })
+}
+
+/// Asks the user whether the context server may use the given model, returning whether they approved.
+async fn request_approval(
+    server_id: &str,
+    model: &dyn LanguageModel,
+    messages: &[LanguageModelRequestMessage],
+    cx: &mut AsyncApp,
+) -> Result<bool> {
+    let Some(window) = cx.update(|cx| cx.active_window())? else {
+        bail!("no window is available to approve the sampling request");
+    };
+
+    let message = format!(
+        "The context server \"{server_id}\" wants to use {} to generate a response.",
+        model.name().0
+    );
+    let prompt = messages
+        .iter()
+        .map(|message| message.string_contents())
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    let detail = util::truncate_and_trailoff(&prompt, MAX_PROMPT_PREVIEW_CHARS);
+
+    let answer = window.update(cx, |_, window, cx| {
+        window.prompt(
+            PromptLevel::Info,
+            &message,
+            Some(&detail),
+            &["Allow", "Deny"],
+            cx,
+        )
+    })?;
+
+    Ok(answer.await? == 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use serde_json::json;
+
+    #[gpui::test]
+    async fn test_create_message(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            LanguageModelRegistry::test(cx);
+        });
+        cx.add_empty_window();
+        let model = cx.update(|cx| {
+            LanguageModelRegistry::read_global(cx)
+                .active_model()
+                .unwrap()
+        });
+
+        let params = serde_json::from_value(json!({
+            "messages": [
+                { "role": "user", "content": { "type": "text", "text": "Hello" } },
+            ],
+            "systemPrompt": "Be brief",
+            "temperature": 0.5,
+            "maxTokens": 100,
+            "stopSequences": ["END"],
+        }))
+        .unwrap();
+        let response =
+            cx.spawn(|mut cx| async move { create_message("server", params, &mut cx).await });
+        cx.run_until_parked();
+        assert!(cx.has_pending_prompt());
+        assert_eq!(model.as_fake().completion_count(), 0);
+
+        cx.simulate_prompt_answer("Allow");
+        cx.run_until_parked();
+        let requests = model.as_fake().pending_completions();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(
+            requests[0]
+                .messages
+                .iter()
+                .map(|message| (message.role, message.string_contents()))
+                .collect::<Vec<_>>(),
+            [
+                (Role::System, "Be brief".to_string()),
+                (Role::User, "Hello".to_string())
+            ]
+        );
+        assert_eq!(requests[0].temperature, Some(0.5));
+        assert_eq!(requests[0].max_tokens, Some(100));
+        assert_eq!(requests[0].stop, ["END".to_string()]);
+
+        model
+            .as_fake()
+            .stream_last_completion_response("Hi there".into());
+        model.as_fake().end_last_completion_stream();
+        let response = response.await.unwrap();
+        assert_eq!(response.model, model.id().0.to_string());
+        match response.content {
+            types::MessageContent::Text { text, .. } => assert_eq!(text, "Hi there"),
+            content => panic!("expected a text response, got {content:?}"),
+        }
+    }
+
+    #[gpui::test]
+    async fn test_create_message_rejected(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            LanguageModelRegistry::test(cx);
+        });
+        cx.add_empty_window();
+        let model = cx.update(|cx| {
+            LanguageModelRegistry::read_global(cx)
+                .active_model()
+                .unwrap()
+        });
+
+        let params = serde_json::from_value(json!({
+            "messages": [
+                { "role": "user", "content": { "type": "text", "text": "Hello" } },
+            ],
+            "maxTokens": 100,
+        }))
+        .unwrap();
+        let response =
+            cx.spawn(|mut cx| async move { create_message("server", params, &mut cx).await });
+        cx.run_until_parked();
+        cx.simulate_prompt_answer("Deny");
+        assert!(response.await.is_err());
+        assert_eq!(model.as_fake().completion_count(), 0);
+    }
+
+    #[test]
+    fn test_to_request_message() {
+        let message = serde_json::from_value(json!({
+            "role": "assistant",
+            "content": { "type": "text", "text": "Hello" },
+        }))
+        .unwrap();
+        let message = to_request_message(message).unwrap();
+        assert_eq!(message.role, Role::Assistant);
+        assert_eq!(message.string_contents(), "Hello");
+
+        let message = serde_json::from_value(json!({
+            "role": "user",
+            "content": { "type": "image", "data": "", "mime_type": "image/png" },
+        }))
+        .unwrap();
+        assert!(to_request_message(message).is_err());
+    }
+}
@@ -1,4 +1,5 @@
 pub mod client;
+mod client_requests;
 mod context_server_tool;
 mod extension_context_server;
 pub mod manager;
@@ -8,7 +9,9 @@ mod transport;
 pub mod types;
 
 use command_palette_hooks::CommandPaletteFilter;
-pub use context_server_settings::{ContextServerSettings, ServerCommand, ServerConfig};
+pub use context_server_settings::{
+    ContextServerSettings, HttpServerConfig, HttpTransportKind, ServerCommand, ServerConfig,
+};
 use gpui::{App, actions};
 
 pub use crate::context_server_tool::ContextServerTool;
@@ -1,10 +1,13 @@
 use std::sync::Arc;
 
-use anyhow::{Result, anyhow, bail};
-use assistant_tool::{ActionLog, Tool, ToolSource};
+use anyhow::{anyhow, bail};
+use assistant_tool::{ActionLog, Tool, ToolResult, ToolSource};
 use gpui::{App, Entity, Task};
 use icons::IconName;
-use language_model::{LanguageModelRequestMessage, LanguageModelToolSchemaFormat};
+use language_model::{
+    LanguageModelImage, LanguageModelRequestMessage, LanguageModelToolResultContent,
+    LanguageModelToolSchemaFormat,
+};
 use project::Project;
 
 use crate::manager::ContextServerManager;
@@ -49,7 +52,7 @@ impl Tool for ContextServerTool {
         }
     }
 
-    fn needs_confirmation(&self) -> bool {
+    fn needs_confirmation(&self, _: &serde_json::Value, _: &App) -> bool {
         true
     }
 
@@ -76,13 +79,13 @@ impl Tool for ContextServerTool {
         _project: Entity<Project>,
         _action_log: Entity<ActionLog>,
         cx: &mut App,
-    ) -> Task<Result<String>> {
+    ) -> ToolResult {
         if let Some(server) = self.server_manager.read(cx).get_server(&self.server_id) {
             let tool_name = self.tool.name.clone();
             let server_clone = server.clone();
             let input_clone = input.clone();
 
-            cx.spawn(async move |_cx| {
+            cx.spawn(async move |cx| {
                 let Some(protocol) = server_clone.client() else {
                     bail!("Context server not initialized");
                 };
@@ -100,24 +103,43 @@ impl Tool for ContextServerTool {
                 );
                 let response = protocol.run_tool(tool_name, arguments).await?;
 
-                let mut result = String::new();
-                for content in response.content {
-                    match content {
+                let mut content = Vec::new();
+                for part in response.content {
+                    match part {
                         types::ToolResponseContent::Text { text } => {
-                            result.push_str(&text);
+                            content.push(LanguageModelToolResultContent::from(text));
                         }
-                        types::ToolResponseContent::Image { .. } => {
-                            log::warn!("Ignoring image content from tool response");
+                        types::ToolResponseContent::Image { data, mime_type } => {
+                            let image = cx
+                                .update(|cx| LanguageModelImage::from_base64(&data, cx))?
+                                .await;
+                            match image {
+                                Some(image) => content.push(image.into()),
+                                None => log::warn!(
+                                    "Ignoring {mime_type} image from tool response that could not be decoded"
+                                ),
+                            }
                         }
-                        types::ToolResponseContent::Resource { .. } => {
-                            log::warn!("Ignoring resource content from tool response");
+                        types::ToolResponseContent::Resource { resource } => {
+                            content.push(format!("Resource: {}", resource.uri).into());
                         }
                     }
                 }
-                Ok(result)
+
+                if response.is_error == Some(true) {
+                    let message = content
+                        .iter()
+                        .filter_map(|part| part.to_text())
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                    bail!(message);
+                }
+
+                Ok(content.into())
             })
+            .into()
         } else {
-            Task::ready(Err(anyhow!("Context server not found")))
+            Task::ready(Err(anyhow!("Context server not found"))).into()
         }
     }
 }
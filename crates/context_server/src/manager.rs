@@ -16,11 +16,13 @@
 
 use std::path::Path;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use anyhow::{Result, bail};
+use anyhow::{Context as _, Result, bail};
 use collections::HashMap;
 use command_palette_hooks::CommandPaletteFilter;
 use gpui::{AsyncApp, Context, Entity, EventEmitter, Subscription, Task, WeakEntity};
+use http_client::Url;
 use log;
 use parking_lot::RwLock;
 use project::Project;
@@ -32,9 +34,18 @@ use crate::{ContextServerSettings, ServerConfig};
 use crate::{
     CONTEXT_SERVERS_NAMESPACE, ContextServerFactoryRegistry,
     client::{self, Client},
-    types,
+    client_requests, types,
 };
 
+/// How long to wait before reconnecting to a context server whose connection closed.
+/// The delay doubles with each consecutive attempt, up to [`MAX_RECONNECT_DELAY`].
+const RECONNECT_DELAY: Duration = Duration::from_secs(2);
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(5 * 60);
+/// How many times in a row to try reconnecting to a context server before giving up.
+const MAX_RECONNECT_ATTEMPTS: u32 = 10;
+/// How long a connection must stay open for the next reconnection to start from the initial delay.
+const STABLE_CONNECTION_DURATION: Duration = Duration::from_secs(60);
+
 pub struct ContextServer {
     pub id: Arc<str>,
     pub config: Arc<ServerConfig>,
@@ -62,27 +73,55 @@ impl ContextServer {
         self.client.read().clone()
     }
 
-    pub async fn start(self: Arc<Self>, cx: &AsyncApp) -> Result<()> {
+    pub async fn start(self: Arc<Self>, project: WeakEntity<Project>, cx: &AsyncApp) -> Result<()> {
         log::info!("starting context server {}", self.id);
-        let Some(command) = &self.config.command else {
-            bail!("no command specified for server {}", self.id);
+        let client = if let Some(http) = &self.config.http {
+            let mut headers = http.headers.clone();
+            if let Some(env_var) = &http.auth_token_env_var {
+                let token = std::env::var(env_var).with_context(|| {
+                    format!(
+                        "failed to read auth token for server {} from ${env_var}",
+                        self.id
+                    )
+                })?;
+                headers.insert("Authorization".into(), format!("Bearer {token}"));
+            }
+
+            Client::http(
+                client::ContextServerId(self.id.clone()),
+                client::ModelContextServerEndpoint {
+                    url: Url::parse(&http.url)
+                        .with_context(|| format!("invalid url for server {}", self.id))?,
+                    transport: http.transport,
+                    headers,
+                },
+                cx.update(|cx| cx.http_client())?,
+                cx.clone(),
+            )?
+        } else if let Some(command) = &self.config.command {
+            Client::new(
+                client::ContextServerId(self.id.clone()),
+                client::ModelContextServerBinary {
+                    executable: Path::new(&command.path).to_path_buf(),
+                    args: command.args.clone(),
+                    env: command.env.clone(),
+                },
+                cx.clone(),
+            )?
+        } else {
+            bail!("no command or url specified for server {}", self.id);
         };
-        let client = Client::new(
-            client::ContextServerId(self.id.clone()),
-            client::ModelContextServerBinary {
-                executable: Path::new(&command.path).to_path_buf(),
-                args: command.args.clone(),
-                env: command.env.clone(),
-            },
-            cx.clone(),
-        )?;
+
+        client_requests::register_handlers(&client, self.id.clone(), project);
 
         let protocol = crate::protocol::ModelContextProtocol::new(client);
         let client_info = types::Implementation {
             name: "Zed".to_string(),
             version: env!("CARGO_PKG_VERSION").to_string(),
         };
-        let initialized_protocol = protocol.initialize(client_info).await?;
+        let initialized_protocol = protocol
+            .initialize(client_info, client_requests::capabilities())
+            .await?;
 
         log::debug!(
             "context server {} initialized: {:?}",
@@ -109,12 +148,15 @@ pub struct ContextServerManager {
     registry: Entity<ContextServerFactoryRegistry>,
     update_servers_task: Option<Task<Result<()>>>,
     needs_server_update: bool,
+    /// The number of consecutive reconnection attempts to each server whose connection closed.
+    reconnect_attempts: HashMap<Arc<str>, u32>,
     _subscriptions: Vec<Subscription>,
 }
 
 pub enum Event {
     ServerStarted { server_id: Arc<str> },
     ServerStopped { server_id: Arc<str> },
+    ResourceUpdated { server_id: Arc<str>, uri: Url },
 }
 
 impl EventEmitter<Event> for ContextServerManager {}
@@ -133,12 +175,19 @@ impl ContextServerManager {
                 cx.observe_global::<SettingsStore>(|this, cx| {
                     this.available_context_servers_changed(cx);
                 }),
+                cx.subscribe(&project, |this, _project, event, _cx| match event {
+                    project::Event::WorktreeAdded(_) | project::Event::WorktreeRemoved(_) => {
+                        this.notify_roots_list_changed();
+                    }
+                    _ => {}
+                }),
             ],
             project,
             registry,
             needs_server_update: false,
             servers: HashMap::default(),
             update_servers_task: None,
+            reconnect_attempts: HashMap::default(),
         };
         this.available_context_servers_changed(cx);
         this
@@ -186,10 +235,15 @@ impl ContextServerManager {
         server: Arc<ContextServer>,
         cx: &mut Context<Self>,
     ) -> Task<anyhow::Result<()>> {
+        let project = self.project.downgrade();
         cx.spawn(async move |this, cx| {
             let id = server.id.clone();
-            server.start(&cx).await?;
-            this.update(cx, |_, cx| cx.emit(Event::ServerStarted { server_id: id }))?;
+            server.clone().start(project, &cx).await?;
+            this.update(cx, |this, cx| {
+                this.forward_resource_updates(&server, cx);
+                this.reconnect_when_closed(server, cx);
+                cx.emit(Event::ServerStarted { server_id: id })
+            })?;
             Ok(())
         })
     }
@@ -212,14 +266,21 @@ impl ContextServerManager {
         cx: &mut Context<Self>,
     ) -> Task<anyhow::Result<()>> {
         let id = id.clone();
+        let project = self.project.downgrade();
         cx.spawn(async move |this, cx| {
-            if let Some(server) = this.update(cx, |this, _cx| this.servers.remove(&id))? {
+            let server = this.update(cx, |this, _cx| {
+                this.reconnect_attempts.remove(&id);
+                this.servers.remove(&id)
+            })?;
+            if let Some(server) = server {
                 server.stop()?;
                 let config = server.config();
                 let new_server = Arc::new(ContextServer::new(id.clone(), config));
-                new_server.clone().start(&cx).await?;
+                new_server.clone().start(project, &cx).await?;
                 this.update(cx, |this, cx| {
-                    this.servers.insert(id.clone(), new_server);
+                    this.servers.insert(id.clone(), new_server.clone());
+                    this.forward_resource_updates(&new_server, cx);
+                    this.reconnect_when_closed(new_server, cx);
                     cx.emit(Event::ServerStopped {
                         server_id: id.clone(),
                     });
@@ -244,6 +305,92 @@ impl ContextServerManager {
             .collect()
     }
 
+    fn notify_roots_list_changed(&self) {
+        for server in self.running_servers() {
+            if let Some(client) = server.client() {
+                client.notify_roots_list_changed().log_err();
+            }
+        }
+    }
+
+    /// Emits [`Event::ResourceUpdated`] whenever the server reports an update to a subscribed resource.
+    fn forward_resource_updates(&self, server: &Arc<ContextServer>, cx: &mut Context<Self>) {
+        let Some(client) = server.client() else {
+            return;
+        };
+
+        let this = cx.weak_entity();
+        let server_id = server.id();
+        client.on_resource_updated(move |uri, mut cx| {
+            let server_id = server_id.clone();
+            this.update(&mut cx, |_, cx| {
+                cx.emit(Event::ResourceUpdated { server_id, uri })
+            })
+            .ok();
+        });
+    }
+
+    /// Restarts the given server if its connection closes while it is running,
+    /// e.g. because the server crashed or the network connection dropped.
+    fn reconnect_when_closed(&self, server: Arc<ContextServer>, cx: &mut Context<Self>) {
+        let Some(closed) = server.client().map(|client| client.closed()) else {
+            return;
+        };
+
+        let connected_at = Instant::now();
+        cx.spawn(async move |this, cx| {
+            closed.await;
+
+            this.update(cx, |this, cx| {
+                // Servers that were stopped or replaced in the meantime closed on purpose.
+                let is_running = server.client().is_some()
+                    && this
+                        .servers
+                        .get(&server.id)
+                        .is_some_and(|current| Arc::ptr_eq(current, &server));
+                if !is_running {
+                    return;
+                }
+
+                log::warn!("lost connection to context server {}", server.id);
+                this.servers.remove(&server.id);
+                server.stop().log_err();
+                cx.emit(Event::ServerStopped {
+                    server_id: server.id(),
+                });
+
+                if connected_at.elapsed() >= STABLE_CONNECTION_DURATION {
+                    this.reconnect_attempts.remove(&server.id);
+                }
+                this.schedule_reconnect(server.id(), cx);
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Starts the given server again after a delay that grows with each consecutive attempt,
+    /// unless too many attempts were made already.
+    fn schedule_reconnect(&mut self, id: Arc<str>, cx: &mut Context<Self>) {
+        let attempts = self.reconnect_attempts.entry(id.clone()).or_insert(0);
+        *attempts += 1;
+        if *attempts > MAX_RECONNECT_ATTEMPTS {
+            log::error!(
+                "giving up reconnecting to context server {id} after {MAX_RECONNECT_ATTEMPTS} attempts"
+            );
+            self.reconnect_attempts.remove(&id);
+            return;
+        }
+        let delay = reconnect_delay(*attempts);
+
+        // Servers missing from the map are started again when the servers are updated.
+        self.servers.remove(&id);
+        cx.spawn(async move |this, cx| {
+            cx.background_executor().timer(delay).await;
+            this.update(cx, |this, cx| this.available_context_servers_changed(cx))
+        })
+        .detach_and_log_err(cx);
+    }
+
     async fn maintain_servers(this: WeakEntity<Self>, cx: &mut AsyncApp) -> Result<()> {
         let mut desired_servers = HashMap::default();
 
@@ -267,7 +414,7 @@ impl ContextServerManager {
             registry.read_with(cx, |registry, _| registry.context_server_factories())?
         {
             let config = desired_servers.entry(id).or_default();
-            if config.command.is_none() {
+            if config.command.is_none() && config.http.is_none() {
                 if let Some(extension_command) = factory(project.clone(), &cx).await.log_err() {
                     config.command = Some(extension_command);
                 }
@@ -307,11 +454,48 @@ impl ContextServerManager {
         }
 
         for (id, server) in servers_to_start {
-            if server.start(&cx).await.log_err().is_some() {
-                this.update(cx, |_, cx| cx.emit(Event::ServerStarted { server_id: id }))?;
+            if server
+                .clone()
+                .start(project.downgrade(), &cx)
+                .await
+                .log_err()
+                .is_some()
+            {
+                this.update(cx, |this, cx| {
+                    this.forward_resource_updates(&server, cx);
+                    this.reconnect_when_closed(server, cx);
+                    cx.emit(Event::ServerStarted { server_id: id })
+                })?;
+            } else {
+                this.update(cx, |this, cx| {
+                    if this.reconnect_attempts.contains_key(&id) {
+                        this.schedule_reconnect(id, cx);
+                    }
+                })?;
             }
         }
 
         Ok(())
     }
 }
+
+/// The delay before the given reconnection attempt, counting from 1.
+fn reconnect_delay(attempt: u32) -> Duration {
+    RECONNECT_DELAY
+        .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+        .min(MAX_RECONNECT_DELAY)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reconnect_delay() {
+        assert_eq!(reconnect_delay(1), Duration::from_secs(2));
+        assert_eq!(reconnect_delay(2), Duration::from_secs(4));
+        assert_eq!(reconnect_delay(5), Duration::from_secs(32));
+        assert_eq!(reconnect_delay(10), MAX_RECONNECT_DELAY);
+        assert_eq!(reconnect_delay(u32::MAX), MAX_RECONNECT_DELAY);
+    }
+}
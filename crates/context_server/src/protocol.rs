@@ -7,6 +7,8 @@
 
 use anyhow::Result;
 use collections::HashMap;
+use gpui::AsyncApp;
+use url::Url;
 
 use crate::client::Client;
 use crate::types;
@@ -29,14 +31,11 @@ impl ModelContextProtocol {
     pub async fn initialize(
         self,
         client_info: types::Implementation,
+        capabilities: types::ClientCapabilities,
     ) -> Result<InitializedContextServerProtocol> {
         let params = types::InitializeParams {
             protocol_version: types::ProtocolVersion(types::LATEST_PROTOCOL_VERSION.to_string()),
-            capabilities: types::ClientCapabilities {
-                experimental: None,
-                sampling: None,
-                roots: None,
-            },
+            capabilities,
             meta: None,
             client_info,
         };
@@ -84,6 +83,19 @@ pub enum ServerCapability {
 }
 
 impl InitializedContextServerProtocol {
+    /// Returns a future that resolves once the connection to the server is closed.
+    pub fn closed(&self) -> impl Future<Output = ()> + use<> {
+        self.inner.closed()
+    }
+
+    /// Notifies the server that the list of roots changed, e.g. because a worktree was added.
+    pub fn notify_roots_list_changed(&self) -> Result<()> {
+        self.inner.notify(
+            types::NotificationType::RootsListChanged.as_str(),
+            serde_json::json!({}),
+        )
+    }
+
     /// Check if the server supports a specific capability
     pub fn capable(&self, capability: ServerCapability) -> bool {
         match capability {
@@ -136,6 +148,78 @@ impl InitializedContextServerProtocol {
         Ok(response)
     }
 
+    /// Whether the server sends notifications when subscribed resources are updated.
+    pub fn can_subscribe_to_resources(&self) -> bool {
+        self.initialize
+            .capabilities
+            .resources
+            .as_ref()
+            .and_then(|resources| resources.subscribe)
+            .unwrap_or(false)
+    }
+
+    /// Read the contents of the MCP resource with the given URI.
+    pub async fn read_resource(&self, uri: Url) -> Result<types::ResourcesReadResponse> {
+        self.check_capability(ServerCapability::Resources)?;
+
+        let params = types::ResourcesReadParams { uri, meta: None };
+        let response: types::ResourcesReadResponse = self
+            .inner
+            .request(types::RequestType::ResourcesRead.as_str(), params)
+            .await?;
+
+        Ok(response)
+    }
+
+    /// Subscribe to updates of the MCP resource with the given URI.
+    ///
+    /// Updates are reported to the handler registered with [`Self::on_resource_updated`].
+    pub async fn subscribe_resource(&self, uri: Url) -> Result<()> {
+        if !self.can_subscribe_to_resources() {
+            return Err(anyhow::anyhow!(
+                "Server does not support resource subscriptions"
+            ));
+        }
+
+        let params = types::ResourcesSubscribeParams { uri, meta: None };
+        self.inner
+            .request::<serde_json::Value>(types::RequestType::ResourcesSubscribe.as_str(), params)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Unsubscribe from updates of the MCP resource with the given URI.
+    pub async fn unsubscribe_resource(&self, uri: Url) -> Result<()> {
+        if !self.can_subscribe_to_resources() {
+            return Err(anyhow::anyhow!(
+                "Server does not support resource subscriptions"
+            ));
+        }
+
+        let params = types::ResourcesUnsubscribeParams { uri, meta: None };
+        self.inner
+            .request::<serde_json::Value>(types::RequestType::ResourcesUnsubscribe.as_str(), params)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Registers a handler that is called with the URI of each subscribed resource the server updates.
+    pub fn on_resource_updated<F>(&self, mut f: F)
+    where
+        F: 'static + Send + FnMut(Url, AsyncApp),
+    {
+        self.inner.on_notification(
+            types::NotificationType::ResourcesUpdated.as_str(),
+            move |params, cx| match serde_json::from_value::<types::ResourcesUpdatedParams>(params)
+            {
+                Ok(params) => f(params.uri, cx),
+                Err(error) => log::warn!("invalid resource updated notification: {error}"),
+            },
+        );
+    }
+
     /// Executes a prompt with the given arguments and returns the result.
     pub async fn run_prompt<P: AsRef<str>>(
         &self,
@@ -0,0 +1,769 @@
+use std::pin::Pin;
+use std::sync::Arc;
+
+use anyhow::{Context as _, Result, anyhow, bail};
+use async_trait::async_trait;
+use collections::HashMap;
+use futures::channel::oneshot;
+use futures::future::Shared;
+use futures::io::BufReader;
+use futures::{AsyncBufRead, AsyncBufReadExt as _, AsyncReadExt as _, FutureExt as _, Stream};
+use gpui::{AsyncApp, BackgroundExecutor, Task};
+use http_client::http::{HeaderMap, header};
+use http_client::{AsyncBody, HttpClient, Method, Request, Response, StatusCode, Url};
+use parking_lot::Mutex;
+use serde_json::value::RawValue;
+use smol::channel;
+use util::ResultExt as _;
+
+use crate::HttpTransportKind;
+use crate::client::{INTERNAL_ERROR, ModelContextServerEndpoint};
+use crate::transport::Transport;
+
+const SESSION_ID_HEADER: &str = "Mcp-Session-Id";
+const EVENT_STREAM_CONTENT_TYPE: &str = "text/event-stream";
+
+/// A transport for context servers that run as HTTP services.
+///
+/// Supports both the streamable HTTP transport, where every message is posted to
+/// the server and responses come back in the response body, and the older SSE
+/// transport, where responses arrive over a long-lived event stream.
+///
+/// When the connection to the server is lost, the incoming message stream ends,
+/// which lets the [`ContextServerManager`](crate::manager::ContextServerManager)
+/// reconnect to the server.
+pub struct HttpTransport {
+    state: Arc<HttpTransportState>,
+    inbound_rx: channel::Receiver<String>,
+    err_rx: channel::Receiver<String>,
+}
+
+/// The state shared between the transport and the tasks that post its messages.
+struct HttpTransportState {
+    http_client: Arc<dyn HttpClient>,
+    executor: BackgroundExecutor,
+    kind: HttpTransportKind,
+    url: Url,
+    headers: HashMap<String, String>,
+    /// The URL messages are posted to. SSE servers announce it on their event stream.
+    post_url: Shared<oneshot::Receiver<Url>>,
+    session_id: Mutex<Option<String>>,
+    inbound_tx: channel::Sender<String>,
+    err_tx: channel::Sender<String>,
+    event_stream_task: Mutex<Option<Task<()>>>,
+}
+
+impl HttpTransport {
+    pub fn new(
+        endpoint: ModelContextServerEndpoint,
+        http_client: Arc<dyn HttpClient>,
+        cx: &AsyncApp,
+    ) -> Result<Self> {
+        let executor = cx.background_executor().clone();
+        let (inbound_tx, inbound_rx) = channel::unbounded::<String>();
+        let (err_tx, err_rx) = channel::unbounded::<String>();
+        let (post_url_tx, post_url_rx) = oneshot::channel();
+
+        let event_stream_task = match endpoint.transport {
+            HttpTransportKind::StreamableHttp => {
+                post_url_tx.send(endpoint.url.clone()).ok();
+                None
+            }
+            HttpTransportKind::Sse => {
+                let request = build_request(Method::GET, &endpoint.url, &endpoint.headers)
+                    .header(header::ACCEPT, EVENT_STREAM_CONTENT_TYPE)
+                    .body(AsyncBody::empty())?;
+                let http_client = http_client.clone();
+                let url = endpoint.url.clone();
+                let inbound_tx = inbound_tx.clone();
+                let err_tx = err_tx.clone();
+                Some(executor.spawn(async move {
+                    let result = Self::handle_sse_stream(
+                        http_client,
+                        request,
+                        url,
+                        post_url_tx,
+                        &inbound_tx,
+                    )
+                    .await;
+                    if let Err(error) = result {
+                        err_tx.send(format!("{error:#}")).await.ok();
+                    }
+                    // Without the event stream we can't receive any more messages.
+                    inbound_tx.close();
+                }))
+            }
+        };
+
+        Ok(Self {
+            state: Arc::new(HttpTransportState {
+                http_client,
+                executor,
+                kind: endpoint.transport,
+                url: endpoint.url,
+                headers: endpoint.headers,
+                post_url: post_url_rx.shared(),
+                session_id: Mutex::new(None),
+                inbound_tx,
+                err_tx,
+                event_stream_task: Mutex::new(event_stream_task),
+            }),
+            inbound_rx,
+            err_rx,
+        })
+    }
+
+    async fn handle_sse_stream(
+        http_client: Arc<dyn HttpClient>,
+        request: Request<AsyncBody>,
+        url: Url,
+        post_url_tx: oneshot::Sender<Url>,
+        inbound_tx: &channel::Sender<String>,
+    ) -> Result<()> {
+        let response = http_client.send(request).await?;
+        if !response.status().is_success() {
+            bail!(
+                "failed to open event stream: {}",
+                error_message(response).await
+            );
+        }
+
+        let mut post_url_tx = Some(post_url_tx);
+        let mut body = BufReader::new(response.into_body());
+        while let Some(event) = next_sse_event(&mut body).await? {
+            match event.event.as_str() {
+                "endpoint" => {
+                    let post_url = url
+                        .join(event.data.trim())
+                        .context("invalid endpoint announced by context server")?;
+                    // The configured headers are sent along with every message, so they must not
+                    // be sent to another origin than the one they were configured for.
+                    if post_url.origin() != url.origin() {
+                        bail!("context server announced an endpoint on another origin: {post_url}");
+                    }
+                    if let Some(post_url_tx) = post_url_tx.take() {
+                        post_url_tx.send(post_url).ok();
+                    }
+                }
+                "message" => inbound_tx.send(event.data).await?,
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl HttpTransportState {
+    /// Posts a message to the server and forwards the messages in the response.
+    ///
+    /// A reply with an error status only fails the request that was posted. Losing the
+    /// connection or the session fails the whole transport.
+    async fn post(&self, post_url: Url, message: String) -> Result<()> {
+        let mut request = build_request(Method::POST, &post_url, &self.headers)
+            .header(header::CONTENT_TYPE, "application/json")
+            .header(
+                header::ACCEPT,
+                format!("application/json, {EVENT_STREAM_CONTENT_TYPE}"),
+            );
+        let session_id = self.session_id.lock().clone();
+        if let Some(session_id) = &session_id {
+            request = request.header(SESSION_ID_HEADER, session_id);
+        }
+
+        let response = self
+            .http_client
+            .send(request.body(message.clone().into())?)
+            .await?;
+        let status = response.status();
+        if status == StatusCode::NOT_FOUND && session_id.is_some() {
+            bail!("context server session expired");
+        } else if !status.is_success() {
+            let error = format!(
+                "context server request failed: {}",
+                error_message(response).await
+            );
+            return self.fail_message(&message, error).await;
+        }
+
+        if self.kind == HttpTransportKind::StreamableHttp {
+            if let Some(new_session_id) = header_value(response.headers(), SESSION_ID_HEADER) {
+                let is_new_session = self
+                    .session_id
+                    .lock()
+                    .replace(new_session_id.to_string())
+                    .is_none();
+                if is_new_session {
+                    self.listen_for_server_messages();
+                }
+            }
+        }
+
+        let content_type = header_value(response.headers(), header::CONTENT_TYPE.as_str())
+            .unwrap_or_default()
+            .to_string();
+        if content_type.starts_with(EVENT_STREAM_CONTENT_TYPE) {
+            // The server may send requests of its own before responding, which are
+            // forwarded as they stream in.
+            if let Err(error) = forward_sse_messages(response, &self.inbound_tx).await {
+                self.fail_message(&message, format!("{error:#}")).await?;
+            }
+        } else if content_type.starts_with("application/json") {
+            let mut body = String::new();
+            let messages = async {
+                response.into_body().read_to_string(&mut body).await?;
+                split_json_messages(&body)
+            }
+            .await;
+            match messages {
+                Ok(messages) => {
+                    for message in messages {
+                        self.inbound_tx.send(message).await?;
+                    }
+                }
+                Err(error) => self.fail_message(&message, format!("{error:#}")).await?,
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reports an error for a message that the server didn't handle. A request is answered
+    /// with an error response, so that only the request itself fails.
+    async fn fail_message(&self, message: &str, error: String) -> Result<()> {
+        let request = serde_json::from_str::<serde_json::Value>(message)
+            .ok()
+            .filter(|message| message.get("method").is_some())
+            .and_then(|message| message.get("id").cloned());
+        if let Some(id) = request {
+            let response = serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": { "code": INTERNAL_ERROR, "message": error },
+            });
+            self.inbound_tx.send(response.to_string()).await?;
+        } else {
+            self.err_tx.send(error).await.ok();
+        }
+        Ok(())
+    }
+
+    /// Opens an event stream on which the server can send requests and notifications
+    /// that aren't responses to one of our requests.
+    fn listen_for_server_messages(&self) {
+        let mut request = build_request(Method::GET, &self.url, &self.headers)
+            .header(header::ACCEPT, EVENT_STREAM_CONTENT_TYPE);
+        if let Some(session_id) = self.session_id.lock().clone() {
+            request = request.header(SESSION_ID_HEADER, session_id);
+        }
+        let Some(request) = request.body(AsyncBody::empty()).log_err() else {
+            return;
+        };
+
+        let http_client = self.http_client.clone();
+        let inbound_tx = self.inbound_tx.clone();
+        let err_tx = self.err_tx.clone();
+        let task = self.executor.spawn(async move {
+            let result = async {
+                let response = http_client.send(request).await?;
+                // Servers aren't required to support this stream.
+                if response.status() == StatusCode::METHOD_NOT_ALLOWED {
+                    return Ok(());
+                }
+                if !response.status().is_success() {
+                    bail!(
+                        "failed to open event stream: {}",
+                        error_message(response).await
+                    );
+                }
+                forward_sse_messages(response, &inbound_tx).await
+            }
+            .await;
+
+            if let Err(error) = result {
+                err_tx.send(format!("{error:#}")).await.ok();
+            }
+        });
+        *self.event_stream_task.lock() = Some(task);
+    }
+}
+
+#[async_trait]
+impl Transport for HttpTransport {
+    /// Dispatches a message to the server. Its response is read in the background, so that
+    /// a slow request doesn't hold up the messages sent after it.
+    async fn send(&self, message: String) -> Result<()> {
+        let state = self.state.clone();
+        let post_url = state.post_url.clone().await.map_err(|_| {
+            // The client can't recover from a lost message, so we end the
+            // incoming stream to have the server reconnected.
+            state.inbound_tx.close();
+            anyhow!("context server did not announce an endpoint to post to")
+        })?;
+
+        self.state
+            .executor
+            .spawn(async move {
+                if let Err(error) = state.post(post_url, message).await {
+                    state.err_tx.send(format!("{error:#}")).await.ok();
+                    state.inbound_tx.close();
+                }
+            })
+            .detach();
+        Ok(())
+    }
+
+    fn receive(&self) -> Pin<Box<dyn Stream<Item = String> + Send>> {
+        Box::pin(self.inbound_rx.clone())
+    }
+
+    fn receive_err(&self) -> Pin<Box<dyn Stream<Item = String> + Send>> {
+        Box::pin(self.err_rx.clone())
+    }
+}
+
+impl Drop for HttpTransport {
+    fn drop(&mut self) {
+        let state = &self.state;
+        state.inbound_tx.close();
+        state.event_stream_task.lock().take();
+
+        // Let the server know it can clean up the session.
+        if let Some(session_id) = state.session_id.lock().take() {
+            let request = build_request(Method::DELETE, &state.url, &state.headers)
+                .header(SESSION_ID_HEADER, session_id)
+                .body(AsyncBody::empty());
+            if let Some(request) = request.log_err() {
+                state
+                    .executor
+                    .spawn(state.http_client.send(request).map(|_| ()))
+                    .detach();
+            }
+        }
+    }
+}
+
+fn build_request(
+    method: Method,
+    url: &Url,
+    headers: &HashMap<String, String>,
+) -> http_client::http::request::Builder {
+    let mut request = Request::builder().method(method).uri(url.as_str());
+    for (name, value) in headers {
+        request = request.header(name, value);
+    }
+    request
+}
+
+fn header_value<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name).and_then(|value| value.to_str().ok())
+}
+
+async fn error_message(response: Response<AsyncBody>) -> String {
+    let status = response.status();
+    let mut body = String::new();
+    response.into_body().read_to_string(&mut body).await.ok();
+    if body.trim().is_empty() {
+        status.to_string()
+    } else {
+        format!("{status}: {}", body.trim())
+    }
+}
+
+async fn forward_sse_messages(
+    response: Response<AsyncBody>,
+    inbound_tx: &channel::Sender<String>,
+) -> Result<()> {
+    let mut body = BufReader::new(response.into_body());
+    while let Some(event) = next_sse_event(&mut body).await? {
+        if event.event == "message" {
+            inbound_tx.send(event.data).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Splits a JSON response body into individual JSON-RPC messages, as servers
+/// may respond with a batch.
+fn split_json_messages(body: &str) -> Result<Vec<String>> {
+    if body.trim_start().starts_with('[') {
+        let messages: Vec<Box<RawValue>> = serde_json::from_str(body)?;
+        Ok(messages
+            .into_iter()
+            .map(|message| message.get().to_string())
+            .collect())
+    } else if body.trim().is_empty() {
+        Ok(Vec::new())
+    } else {
+        Ok(vec![body.to_string()])
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct SseEvent {
+    event: String,
+    data: String,
+}
+
+/// Reads the next event from a server-sent event stream, returning `None` once the stream ends.
+async fn next_sse_event<R: AsyncBufRead + Unpin>(reader: &mut R) -> Result<Option<SseEvent>> {
+    let mut event = None;
+    let mut data: Option<String> = None;
+    let mut line = String::new();
+
+    loop {
+        line.clear();
+        if reader.read_line(&mut line).await? == 0 {
+            return Ok(None);
+        }
+
+        let line = line.trim_end_matches(['\r', '\n']);
+        if line.is_empty() {
+            if let Some(data) = data.take() {
+                return Ok(Some(SseEvent {
+                    event: event.take().unwrap_or_else(|| "message".to_string()),
+                    data,
+                }));
+            }
+            event = None;
+            continue;
+        }
+
+        // Lines starting with a colon are comments, often used as keep-alives.
+        if line.starts_with(':') {
+            continue;
+        }
+
+        let (field, value) = line.split_once(':').unwrap_or((line, ""));
+        let value = value.strip_prefix(' ').unwrap_or(value);
+        match field {
+            "event" => event = Some(value.to_string()),
+            "data" => match &mut data {
+                Some(data) => {
+                    data.push('\n');
+                    data.push_str(value);
+                }
+                None => data = Some(value.to_string()),
+            },
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::{StreamExt as _, TryStreamExt as _};
+    use gpui::TestAppContext;
+    use http_client::FakeHttpClient;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_next_sse_event() {
+        let stream = concat!(
+            ": keep-alive\n",
+            "\n",
+            "event: endpoint\n",
+            "data: /messages?session=1\n",
+            "\n",
+            "data: {\"a\":\n",
+            "data: 1}\r\n",
+            "\r\n",
+            "event: ignored\n",
+        );
+        smol::block_on(async {
+            let mut reader = BufReader::new(stream.as_bytes());
+
+            assert_eq!(
+                next_sse_event(&mut reader).await.unwrap(),
+                Some(SseEvent {
+                    event: "endpoint".into(),
+                    data: "/messages?session=1".into()
+                })
+            );
+            assert_eq!(
+                next_sse_event(&mut reader).await.unwrap(),
+                Some(SseEvent {
+                    event: "message".into(),
+                    data: "{\"a\":\n1}".into()
+                })
+            );
+            assert_eq!(next_sse_event(&mut reader).await.unwrap(), None);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_streamable_http_transport(cx: &mut TestAppContext) {
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let http_client = FakeHttpClient::create({
+            let requests = requests.clone();
+            move |request| {
+                let requests = requests.clone();
+                async move {
+                    let method = request.method().clone();
+                    let session_id =
+                        header_value(request.headers(), SESSION_ID_HEADER).map(str::to_string);
+                    let authorization =
+                        header_value(request.headers(), "Authorization").map(str::to_string);
+                    let mut body = String::new();
+                    request.into_body().read_to_string(&mut body).await?;
+                    requests.lock().push((
+                        method.clone(),
+                        session_id.clone(),
+                        authorization,
+                        body.clone(),
+                    ));
+
+                    let response = Response::builder();
+                    Ok(match method {
+                        Method::GET => response.status(405).body(AsyncBody::empty())?,
+                        Method::POST if body.contains("\"initialize\"") => response
+                            .header(SESSION_ID_HEADER, "session-1")
+                            .header(header::CONTENT_TYPE, "application/json")
+                            .body(r#"{"jsonrpc":"2.0","id":0,"result":{}}"#.into())?,
+                        Method::POST if body.contains("\"tools/list\"") => response
+                            .header(header::CONTENT_TYPE, EVENT_STREAM_CONTENT_TYPE)
+                            .body(
+                                concat!(
+                                    "data: {\"jsonrpc\":\"2.0\",\"method\":\"notifications/progress\"}\n\n",
+                                    "data: {\"jsonrpc\":\"2.0\",\"id\":1,\"result\":{\"tools\":[]}}\n\n",
+                                )
+                                .into(),
+                            )?,
+                        _ => response.status(202).body(AsyncBody::empty())?,
+                    })
+                }
+            }
+        });
+
+        let endpoint = ModelContextServerEndpoint {
+            url: Url::parse("http://localhost:8080/mcp").unwrap(),
+            transport: HttpTransportKind::StreamableHttp,
+            headers: HashMap::from_iter([("Authorization".into(), "Bearer secret".into())]),
+        };
+        let transport = HttpTransport::new(endpoint, http_client, &cx.to_async()).unwrap();
+        let mut messages = transport.receive();
+
+        transport
+            .send(r#"{"jsonrpc":"2.0","id":0,"method":"initialize"}"#.into())
+            .await
+            .unwrap();
+        assert_eq!(
+            messages.next().await.unwrap(),
+            r#"{"jsonrpc":"2.0","id":0,"result":{}}"#
+        );
+
+        transport
+            .send(r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#.into())
+            .await
+            .unwrap();
+        transport
+            .send(r#"{"jsonrpc":"2.0","id":1,"method":"tools/list"}"#.into())
+            .await
+            .unwrap();
+        assert_eq!(
+            messages.next().await.unwrap(),
+            r#"{"jsonrpc":"2.0","method":"notifications/progress"}"#
+        );
+        assert_eq!(
+            messages.next().await.unwrap(),
+            r#"{"jsonrpc":"2.0","id":1,"result":{"tools":[]}}"#
+        );
+
+        cx.run_until_parked();
+        let requests = requests.lock().clone();
+        let post_session_ids = requests
+            .iter()
+            .filter(|(method, _, _, _)| method == Method::POST)
+            .map(|(_, session_id, _, _)| session_id.as_deref())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            post_session_ids,
+            [None, Some("session-1"), Some("session-1")]
+        );
+        // Once a session is established, we listen for messages from the server.
+        assert!(requests.iter().any(|(method, session_id, _, _)| {
+            method == Method::GET && session_id.as_deref() == Some("session-1")
+        }));
+        assert!(
+            requests
+                .iter()
+                .all(|(_, _, authorization, _)| authorization.as_deref() == Some("Bearer secret"))
+        );
+    }
+
+    #[gpui::test]
+    async fn test_sse_transport(cx: &mut TestAppContext) {
+        let (event_tx, event_rx) = futures::channel::mpsc::unbounded::<String>();
+        let event_rx = Arc::new(Mutex::new(Some(event_rx)));
+        let posted = Arc::new(Mutex::new(Vec::new()));
+        let http_client = FakeHttpClient::create({
+            let posted = posted.clone();
+            move |request| {
+                let event_rx = event_rx.clone();
+                let posted = posted.clone();
+                async move {
+                    match request.method().clone() {
+                        Method::GET => {
+                            let events = event_rx
+                                .lock()
+                                .take()
+                                .unwrap()
+                                .map(|event| Ok::<_, std::io::Error>(event.into_bytes()));
+                            Ok(Response::builder()
+                                .header(header::CONTENT_TYPE, EVENT_STREAM_CONTENT_TYPE)
+                                .body(AsyncBody::from_reader(events.into_async_read()))?)
+                        }
+                        _ => {
+                            let uri = request.uri().to_string();
+                            let mut body = String::new();
+                            request.into_body().read_to_string(&mut body).await?;
+                            posted.lock().push((uri, body));
+                            Ok(Response::builder().status(202).body(AsyncBody::empty())?)
+                        }
+                    }
+                }
+            }
+        });
+
+        let endpoint = ModelContextServerEndpoint {
+            url: Url::parse("http://localhost:8080/sse").unwrap(),
+            transport: HttpTransportKind::Sse,
+            headers: HashMap::default(),
+        };
+        let transport = HttpTransport::new(endpoint, http_client, &cx.to_async()).unwrap();
+        let mut messages = transport.receive();
+
+        event_tx
+            .unbounded_send("event: endpoint\ndata: /messages?session=1\n\n".into())
+            .unwrap();
+        transport
+            .send(r#"{"jsonrpc":"2.0","id":0,"method":"initialize"}"#.into())
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        assert_eq!(
+            posted.lock().clone(),
+            [(
+                "http://localhost:8080/messages?session=1".to_string(),
+                r#"{"jsonrpc":"2.0","id":0,"method":"initialize"}"#.to_string()
+            )]
+        );
+
+        event_tx
+            .unbounded_send("event: message\ndata: {\"jsonrpc\":\"2.0\",\"id\":0}\n\n".into())
+            .unwrap();
+        assert_eq!(
+            messages.next().await.unwrap(),
+            r#"{"jsonrpc":"2.0","id":0}"#
+        );
+
+        // Closing the event stream ends the incoming messages, so the server can be reconnected.
+        drop(event_tx);
+        assert_eq!(messages.next().await, None);
+    }
+
+    #[gpui::test]
+    async fn test_sse_transport_rejects_endpoint_on_other_origin(cx: &mut TestAppContext) {
+        let posted = Arc::new(Mutex::new(Vec::new()));
+        let http_client = FakeHttpClient::create({
+            let posted = posted.clone();
+            move |request| {
+                let posted = posted.clone();
+                async move {
+                    match request.method().clone() {
+                        Method::GET => Ok(Response::builder()
+                            .header(header::CONTENT_TYPE, EVENT_STREAM_CONTENT_TYPE)
+                            .body(
+                                "event: endpoint\ndata: https://example.com/messages\n\n".into(),
+                            )?),
+                        _ => {
+                            posted.lock().push(request.uri().to_string());
+                            Ok(Response::builder().status(202).body(AsyncBody::empty())?)
+                        }
+                    }
+                }
+            }
+        });
+
+        let endpoint = ModelContextServerEndpoint {
+            url: Url::parse("http://localhost:8080/sse").unwrap(),
+            transport: HttpTransportKind::Sse,
+            headers: HashMap::from_iter([("Authorization".into(), "Bearer secret".into())]),
+        };
+        let transport = HttpTransport::new(endpoint, http_client, &cx.to_async()).unwrap();
+        assert!(
+            transport
+                .send(r#"{"jsonrpc":"2.0","id":0,"method":"initialize"}"#.into())
+                .await
+                .is_err()
+        );
+        assert!(posted.lock().is_empty());
+    }
+
+    #[gpui::test]
+    async fn test_http_transport_posts_concurrently(cx: &mut TestAppContext) {
+        let (slow_reply_tx, slow_reply_rx) = oneshot::channel::<()>();
+        let slow_reply_rx = slow_reply_rx.shared();
+        let http_client = FakeHttpClient::create(move |request| {
+            let slow_reply_rx = slow_reply_rx.clone();
+            async move {
+                let mut body = String::new();
+                request.into_body().read_to_string(&mut body).await?;
+                let response = Response::builder();
+                if body.contains("\"slow\"") {
+                    slow_reply_rx.await.ok();
+                    Ok(response
+                        .header(header::CONTENT_TYPE, "application/json")
+                        .body(r#"{"jsonrpc":"2.0","id":0,"result":{}}"#.into())?)
+                } else if body.contains("\"fails\"") {
+                    Ok(response.status(500).body("oops".into())?)
+                } else {
+                    Ok(response
+                        .header(header::CONTENT_TYPE, "application/json")
+                        .body(r#"{"jsonrpc":"2.0","id":1,"result":{}}"#.into())?)
+                }
+            }
+        });
+
+        let endpoint = ModelContextServerEndpoint {
+            url: Url::parse("http://localhost:8080/mcp").unwrap(),
+            transport: HttpTransportKind::StreamableHttp,
+            headers: HashMap::default(),
+        };
+        let transport = HttpTransport::new(endpoint, http_client, &cx.to_async()).unwrap();
+        let mut messages = transport.receive();
+
+        // A request that the server is slow to answer doesn't hold up the ones after it.
+        transport
+            .send(r#"{"jsonrpc":"2.0","id":0,"method":"slow"}"#.into())
+            .await
+            .unwrap();
+        transport
+            .send(r#"{"jsonrpc":"2.0","id":1,"method":"fast"}"#.into())
+            .await
+            .unwrap();
+        assert_eq!(
+            messages.next().await.unwrap(),
+            r#"{"jsonrpc":"2.0","id":1,"result":{}}"#
+        );
+
+        // A request the server fails to handle gets an error response, without ending the
+        // incoming messages.
+        transport
+            .send(r#"{"jsonrpc":"2.0","id":2,"method":"fails"}"#.into())
+            .await
+            .unwrap();
+        let response =
+            serde_json::from_str::<serde_json::Value>(&messages.next().await.unwrap()).unwrap();
+        assert_eq!(response["id"], 2);
+        assert_eq!(response["error"]["code"], INTERNAL_ERROR);
+
+        slow_reply_tx.send(()).unwrap();
+        assert_eq!(
+            messages.next().await.unwrap(),
+            r#"{"jsonrpc":"2.0","id":0,"result":{}}"#
+        );
+    }
+}
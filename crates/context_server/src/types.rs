@@ -19,6 +19,7 @@ pub enum RequestType {
     ListTools,
     ListResourceTemplates,
     ListRoots,
+    CreateMessage,
 }
 
 impl RequestType {
@@ -38,6 +39,7 @@ impl RequestType {
             RequestType::ListTools => "tools/list",
             RequestType::ListResourceTemplates => "resources/templates/list",
             RequestType::ListRoots => "roots/list",
+            RequestType::CreateMessage => "sampling/createMessage",
         }
     }
 }
@@ -90,6 +92,14 @@ pub struct ResourcesReadParams {
     pub meta: Option<HashMap<String, serde_json::Value>>,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourcesUpdatedParams {
+    pub uri: Url,
+    #[serde(rename = "_meta", skip_serializing_if = "Option::is_none")]
+    pub meta: Option<HashMap<String, serde_json::Value>>,
+}
+
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct LoggingSetLevelParams {
@@ -196,7 +206,7 @@ pub struct SamplingMessage {
     pub content: MessageContent,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateMessageRequest {
     pub messages: Vec<SamplingMessage>,
@@ -215,7 +225,7 @@ pub struct CreateMessageRequest {
     pub metadata: Option<serde_json::Value>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateMessageResult {
     pub role: Role,
@@ -404,7 +414,7 @@ pub struct Implementation {
     pub version: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Resource {
     pub uri: Url,
@@ -581,7 +591,11 @@ pub enum ToolResponseContent {
     #[serde(rename = "text")]
     Text { text: String },
     #[serde(rename = "image")]
-    Image { data: String, mime_type: String },
+    Image {
+        data: String,
+        #[serde(rename = "mimeType")]
+        mime_type: String,
+    },
     #[serde(rename = "resource")]
     Resource { resource: ResourceContents },
 }
@@ -606,7 +620,7 @@ pub struct ListResourceTemplatesResponse {
     pub meta: Option<HashMap<String, serde_json::Value>>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ListRootsResponse {
     pub roots: Vec<Root>,
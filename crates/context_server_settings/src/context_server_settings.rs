@@ -18,6 +18,10 @@ pub struct ServerConfig {
     ///
     /// This will override the command set by an extension.
     pub command: Option<ServerCommand>,
+    /// The HTTP endpoint of a context server running as a service.
+    ///
+    /// When set, Zed connects to this endpoint instead of running `command`.
+    pub http: Option<HttpServerConfig>,
     /// The settings for this context server.
     ///
     /// Consult the documentation for the context server to see what settings
@@ -40,6 +44,34 @@ pub struct ServerCommand {
     pub env: Option<HashMap<String, String>>,
 }
 
+#[derive(Deserialize, Serialize, Clone, PartialEq, Eq, JsonSchema, Debug)]
+pub struct HttpServerConfig {
+    /// The URL of the server's MCP endpoint.
+    pub url: String,
+    /// The protocol used to talk to the server.
+    ///
+    /// Default: streamable_http
+    #[serde(default)]
+    pub transport: HttpTransportKind,
+    /// Headers sent with every request to the server.
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    /// The name of an environment variable holding a token that is sent as
+    /// `Authorization: Bearer <token>` with every request to the server.
+    pub auth_token_env_var: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Default, Clone, Copy, PartialEq, Eq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum HttpTransportKind {
+    /// Post each message to the endpoint, receiving responses as JSON or as an event stream.
+    #[default]
+    StreamableHttp,
+    /// Receive messages over a long-lived event stream, and post messages to
+    /// the endpoint announced by the server on that stream.
+    Sse,
+}
+
 #[derive(Deserialize, Serialize, Default, Clone, PartialEq, Eq, JsonSchema, Debug)]
 pub struct ContextServerSettings {
     /// Settings for context servers used in the Assistant.
@@ -1016,6 +1016,7 @@ fn random_diagnostic(
             is_primary,
             is_disk_based: false,
             is_unnecessary: false,
+            is_pulled: false,
             data: None,
         },
     }
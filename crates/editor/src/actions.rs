@@ -298,7 +298,9 @@ actions!(
         FindAllReferences,
         Fold,
         FoldAll,
+        FoldComments,
         FoldFunctionBodies,
+        FoldImports,
         FoldRecursive,
         FoldSelectedRanges,
         ToggleFold,
@@ -0,0 +1,345 @@
+use std::{collections::BTreeMap, sync::Arc, time::Duration};
+
+use collections::{HashMap, HashSet};
+use gpui::{App, Context, Entity, Task, WeakEntity, Window};
+use language::{
+    Bias, Buffer, OffsetRangeExt as _, Point, ToPoint as _, language_settings::language_settings,
+};
+use project::{CodeAction, LspAction};
+use text::BufferId;
+use ui::prelude::*;
+use util::ResultExt as _;
+
+use crate::{
+    Editor, EditorMode,
+    display_map::{
+        BlockContext, BlockPlacement, BlockProperties, BlockStyle, CustomBlockId, RenderBlock,
+    },
+};
+
+/// How long to wait after an edit before asking for the new code lenses of a buffer.
+const UPDATE_DEBOUNCE: Duration = Duration::from_millis(250);
+
+/// The code lenses of the buffers shown in an editor, and the blocks that display them.
+#[derive(Default)]
+pub(super) struct CodeLenses {
+    buffer_lenses: HashMap<BufferId, Vec<CodeAction>>,
+    blocks: HashMap<BufferId, Vec<CustomBlockId>>,
+    refresh_tasks: HashMap<BufferId, Task<()>>,
+    /// The lenses being resolved, by their buffer and their index among its lenses.
+    resolve_tasks: HashMap<(BufferId, usize), Task<()>>,
+}
+
+impl CodeLenses {
+    pub(super) fn has_lenses(&self, buffer_id: BufferId) -> bool {
+        self.buffer_lenses.contains_key(&buffer_id)
+    }
+
+    fn set_lenses(&mut self, buffer_id: BufferId, lenses: Option<Vec<CodeAction>>) -> bool {
+        self.resolve_tasks.retain(|(id, _), _| *id != buffer_id);
+        match lenses {
+            Some(lenses) => self.buffer_lenses.insert(buffer_id, lenses).is_some(),
+            None => self.buffer_lenses.remove(&buffer_id).is_some(),
+        }
+    }
+
+    #[cfg(test)]
+    pub(super) fn titles(&self, buffer_id: BufferId) -> Vec<&str> {
+        self.buffer_lenses
+            .get(&buffer_id)
+            .into_iter()
+            .flatten()
+            .map(|lens| lens.lsp_action.title())
+            .collect()
+    }
+}
+
+/// Requests the code lenses of the given buffer, or of all the editor's buffers, and displays
+/// them once they arrive.
+pub(super) fn refresh_code_lenses(
+    editor: &mut Editor,
+    buffer_id: Option<BufferId>,
+    debounce: bool,
+    cx: &mut Context<Editor>,
+) {
+    if editor.mode != EditorMode::Full {
+        return;
+    }
+    let Some(provider) = editor.semantics_provider.clone() else {
+        return;
+    };
+    let buffers = match buffer_id {
+        Some(buffer_id) => editor
+            .buffer
+            .read(cx)
+            .buffer(buffer_id)
+            .into_iter()
+            .collect(),
+        None => editor
+            .buffer
+            .read(cx)
+            .all_buffers()
+            .into_iter()
+            .collect::<Vec<_>>(),
+    };
+
+    for buffer in buffers {
+        let buffer_id = buffer.read(cx).remote_id();
+        if !code_lens_enabled(&buffer, cx) {
+            editor.code_lenses.refresh_tasks.remove(&buffer_id);
+            if editor.code_lenses.set_lenses(buffer_id, None) {
+                update_buffer_blocks(editor, buffer_id, cx);
+            }
+            continue;
+        }
+
+        let provider = provider.clone();
+        let task = cx.spawn(async move |editor, cx| {
+            if debounce {
+                cx.background_executor().timer(UPDATE_DEBOUNCE).await;
+            }
+            let Some(lenses) = cx
+                .update(|cx| provider.code_lens(&buffer, cx))
+                .ok()
+                .flatten()
+            else {
+                return;
+            };
+            let Some(lenses) = lenses.await.log_err() else {
+                return;
+            };
+            editor
+                .update(cx, |editor, cx| {
+                    editor.code_lenses.set_lenses(buffer_id, Some(lenses));
+                    update_buffer_blocks(editor, buffer_id, cx);
+                    resolve_visible_code_lenses(editor, cx);
+                })
+                .ok();
+        });
+        editor.code_lenses.refresh_tasks.insert(buffer_id, task);
+    }
+}
+
+/// Requests the lenses of the buffers that had them turned on, and drops those of the buffers
+/// that had them turned off.
+pub(super) fn code_lens_settings_changed(editor: &mut Editor, cx: &mut Context<Editor>) {
+    let buffers = editor.buffer.read(cx).all_buffers();
+    for buffer in buffers {
+        let buffer_id = buffer.read(cx).remote_id();
+        let requested = editor.code_lenses.refresh_tasks.contains_key(&buffer_id);
+        if code_lens_enabled(&buffer, cx) != requested {
+            refresh_code_lenses(editor, Some(buffer_id), false, cx);
+        }
+    }
+}
+
+/// Displays the lenses of every buffer again, dropping those of the buffers that were removed
+/// from the editor.
+pub(super) fn update_code_lens_blocks(editor: &mut Editor, cx: &mut Context<Editor>) {
+    let buffer_ids = editor
+        .code_lenses
+        .blocks
+        .keys()
+        .chain(editor.code_lenses.buffer_lenses.keys())
+        .copied()
+        .collect::<HashSet<_>>();
+    for buffer_id in buffer_ids {
+        if editor.buffer.read(cx).buffer(buffer_id).is_none() {
+            editor.code_lenses.set_lenses(buffer_id, None);
+            editor.code_lenses.refresh_tasks.remove(&buffer_id);
+        }
+        update_buffer_blocks(editor, buffer_id, cx);
+    }
+    resolve_visible_code_lenses(editor, cx);
+}
+
+/// Resolves the lenses on the visible lines that were sent without their commands, which hold
+/// their titles. Lenses are only resolved once they are shown, as servers can send many of them.
+pub(super) fn resolve_visible_code_lenses(editor: &mut Editor, cx: &mut Context<Editor>) {
+    if editor.code_lenses.buffer_lenses.is_empty() {
+        return;
+    }
+    let (Some(provider), Some(visible_line_count)) = (
+        editor.semantics_provider.clone(),
+        editor.visible_line_count(),
+    ) else {
+        return;
+    };
+    let multibuffer = editor.buffer.read(cx);
+    let snapshot = multibuffer.snapshot(cx);
+    let visible_start = editor.scroll_manager.anchor().anchor.to_point(&snapshot);
+    let visible_end = snapshot.clip_point(
+        visible_start + Point::new(visible_line_count.ceil() as u32, 0),
+        Bias::Left,
+    );
+
+    let mut unresolved_lenses = Vec::new();
+    for (buffer_snapshot, visible_range, _) in
+        snapshot.range_to_buffer_ranges(visible_start..visible_end)
+    {
+        let buffer_id = buffer_snapshot.remote_id();
+        let (Some(buffer), Some(lenses)) = (
+            multibuffer.buffer(buffer_id),
+            editor.code_lenses.buffer_lenses.get(&buffer_id),
+        ) else {
+            continue;
+        };
+        let visible_rows = visible_range.to_point(buffer_snapshot);
+        let visible_rows = visible_rows.start.row..=visible_rows.end.row;
+        for (ix, lens) in lenses.iter().enumerate() {
+            let unresolved = !lens.resolved
+                && matches!(&lens.lsp_action, LspAction::CodeLens(code_lens) if code_lens.command.is_none());
+            if unresolved
+                && visible_rows.contains(&lens.range.start.to_point(buffer_snapshot).row)
+                && !editor
+                    .code_lenses
+                    .resolve_tasks
+                    .contains_key(&(buffer_id, ix))
+            {
+                unresolved_lenses.push((buffer.clone(), ix, lens.clone()));
+            }
+        }
+    }
+
+    for (buffer, ix, lens) in unresolved_lenses {
+        let buffer_id = buffer.read(cx).remote_id();
+        let Some(resolve_lens) = provider.resolve_code_lens(buffer, lens, cx) else {
+            continue;
+        };
+        let task = cx.spawn(async move |editor, cx| {
+            let Some(resolved_lens) = resolve_lens.await.log_err() else {
+                return;
+            };
+            editor
+                .update(cx, |editor, cx| {
+                    if let Some(lens) = editor
+                        .code_lenses
+                        .buffer_lenses
+                        .get_mut(&buffer_id)
+                        .and_then(|lenses| lenses.get_mut(ix))
+                    {
+                        *lens = resolved_lens;
+                        update_buffer_blocks(editor, buffer_id, cx);
+                    }
+                })
+                .ok();
+        });
+        // Keep the finished tasks too, so that lenses that failed to resolve are not requested
+        // again on every scroll.
+        editor
+            .code_lenses
+            .resolve_tasks
+            .insert((buffer_id, ix), task);
+    }
+}
+
+fn code_lens_enabled(buffer: &Entity<Buffer>, cx: &App) -> bool {
+    let buffer = buffer.read(cx);
+    language_settings(buffer.language().map(|l| l.name()), buffer.file(), cx).code_lens
+}
+
+/// Replaces the blocks of the buffer with one block above each line that has lenses, in every
+/// excerpt of the buffer that shows that line.
+fn update_buffer_blocks(editor: &mut Editor, buffer_id: BufferId, cx: &mut Context<Editor>) {
+    if let Some(block_ids) = editor.code_lenses.blocks.remove(&buffer_id) {
+        editor.remove_blocks(block_ids.into_iter().collect(), None, cx);
+    }
+    let Some(lenses) = editor.code_lenses.buffer_lenses.get(&buffer_id) else {
+        return;
+    };
+    let multibuffer = editor.buffer.read(cx);
+    let Some(buffer) = multibuffer.buffer(buffer_id) else {
+        return;
+    };
+    let buffer_snapshot = buffer.read(cx).snapshot();
+    let snapshot = multibuffer.snapshot(cx);
+
+    // Lenses without commands have nothing to show.
+    let mut lenses_by_row = BTreeMap::<u32, Vec<CodeAction>>::new();
+    for lens in lenses {
+        if matches!(&lens.lsp_action, LspAction::CodeLens(code_lens) if code_lens.command.is_some())
+        {
+            let row = lens.range.start.to_point(&buffer_snapshot).row;
+            lenses_by_row.entry(row).or_default().push(lens.clone());
+        }
+    }
+
+    let editor_handle = cx.weak_entity();
+    let mut blocks = Vec::new();
+    for (excerpt_id, excerpt_range) in multibuffer.excerpts_for_buffer(buffer_id, cx) {
+        let context = excerpt_range.context.to_point(&buffer_snapshot);
+        for (row, lenses) in lenses_by_row.range(context.start.row..=context.end.row) {
+            let indent = buffer_snapshot.indent_size_for_line(*row).len;
+            let position = buffer_snapshot.anchor_after(Point::new(*row, indent));
+            let Some(anchor) = snapshot.anchor_in_excerpt(excerpt_id, position) else {
+                continue;
+            };
+            blocks.push(BlockProperties {
+                placement: BlockPlacement::Above(anchor),
+                height: 1,
+                style: BlockStyle::Flex,
+                render: render_code_lenses(editor_handle.clone(), buffer.clone(), lenses.clone()),
+                priority: 0,
+            });
+        }
+    }
+
+    let block_ids = editor.insert_blocks(blocks, None, cx);
+    editor.code_lenses.blocks.insert(buffer_id, block_ids);
+}
+
+fn render_code_lenses(
+    editor: WeakEntity<Editor>,
+    buffer: Entity<Buffer>,
+    lenses: Vec<CodeAction>,
+) -> RenderBlock {
+    Arc::new(move |cx: &mut BlockContext| {
+        h_flex()
+            .id(cx.block_id)
+            .h(cx.line_height)
+            .pl(cx.anchor_x)
+            .gap_2()
+            .block_mouse_down()
+            .children(lenses.iter().enumerate().map(|(ix, lens)| {
+                let editor = editor.clone();
+                let buffer = buffer.clone();
+                let lens = lens.clone();
+                Button::new(ix, lens.lsp_action.title().to_string())
+                    .size(ButtonSize::None)
+                    .style(ButtonStyle::Transparent)
+                    .label_size(LabelSize::Small)
+                    .color(Color::Muted)
+                    .on_click(move |_, window, cx| {
+                        editor
+                            .update(cx, |editor, cx| {
+                                run_code_lens(editor, buffer.clone(), lens.clone(), window, cx)
+                            })
+                            .ok();
+                    })
+            }))
+            .into_any_element()
+    })
+}
+
+/// Runs the command of the lens, opening the edits that it made.
+fn run_code_lens(
+    editor: &mut Editor,
+    buffer: Entity<Buffer>,
+    lens: CodeAction,
+    window: &mut Window,
+    cx: &mut Context<Editor>,
+) {
+    let (Some(project), Some(workspace)) = (editor.project.clone(), editor.workspace()) else {
+        return;
+    };
+    let title = lens.lsp_action.title().to_string();
+    let apply_code_lens = project.update(cx, |project, cx| {
+        project.apply_code_action(buffer, lens, true, cx)
+    });
+    let workspace = workspace.downgrade();
+    cx.spawn_in(window, async move |editor, cx| {
+        let project_transaction = apply_code_lens.await?;
+        Editor::open_project_transaction(&editor, workspace, project_transaction, title, cx).await
+    })
+    .detach_and_log_err(cx);
+}
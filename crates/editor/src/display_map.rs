@@ -81,6 +81,19 @@ pub trait ToDisplayPoint {
 
 type TextHighlights = TreeMap<TypeId, Arc<(HighlightStyle, Vec<Range<Anchor>>)>>;
 type InlayHighlights = TreeMap<TypeId, TreeMap<InlayId, (HighlightStyle, InlayHighlight)>>;
+/// The styled semantic tokens of each buffer, ordered by their position in the multibuffer.
+pub(crate) type SemanticTokenHighlights =
+    TreeMap<BufferId, Arc<Vec<(Range<Anchor>, HighlightStyle)>>>;
+/// The foldable ranges that language servers reported for each buffer, ordered by their start and,
+/// among those starting on the same row, outermost first.
+type LspFoldRanges = TreeMap<BufferId, Arc<Vec<LspFoldRange>>>;
+
+/// A range that a language server reported as foldable, in one excerpt of its buffer.
+#[derive(Clone, Debug)]
+pub(crate) struct LspFoldRange {
+    pub range: Range<Anchor>,
+    pub kind: Option<lsp::FoldingRangeKind>,
+}
 
 /// Decides how text in a [`MultiBuffer`] should be displayed in a buffer, handling inlay hints,
 /// folding, hard tabs, soft wrapping, custom blocks (like diagnostics), and highlighting.
@@ -104,6 +117,11 @@ pub struct DisplayMap {
     text_highlights: TextHighlights,
     /// Regions of inlays that should be highlighted.
     inlay_highlights: InlayHighlights,
+    /// Semantic tokens reported by language servers, highlighted over the syntax highlighting.
+    semantic_token_highlights: SemanticTokenHighlights,
+    /// Foldable ranges reported by language servers, which supersede the indentation based fold
+    /// range suggestions in their buffers.
+    lsp_fold_ranges: LspFoldRanges,
     /// A container for explicitly foldable ranges, which supersede indentation based fold range suggestions.
     crease_map: CreaseMap,
     pub(crate) fold_placeholder: FoldPlaceholder,
@@ -147,6 +165,8 @@ impl DisplayMap {
             fold_placeholder,
             text_highlights: Default::default(),
             inlay_highlights: Default::default(),
+            semantic_token_highlights: Default::default(),
+            lsp_fold_ranges: Default::default(),
             clip_at_line_ends: false,
             masked: false,
         }
@@ -174,6 +194,8 @@ impl DisplayMap {
             crease_snapshot: self.crease_map.snapshot(),
             text_highlights: self.text_highlights.clone(),
             inlay_highlights: self.inlay_highlights.clone(),
+            semantic_token_highlights: self.semantic_token_highlights.clone(),
+            lsp_fold_ranges: self.lsp_fold_ranges.clone(),
             clip_at_line_ends: self.clip_at_line_ends,
             masked: self.masked,
             fold_placeholder: self.fold_placeholder.clone(),
@@ -498,6 +520,30 @@ impl DisplayMap {
         let highlights = self.text_highlights.get(&type_id)?;
         Some((highlights.0, &highlights.1))
     }
+    /// Replaces the highlighted semantic tokens of the buffer.
+    pub(crate) fn set_semantic_token_highlights(
+        &mut self,
+        buffer_id: BufferId,
+        highlights: Vec<(Range<Anchor>, HighlightStyle)>,
+    ) {
+        if highlights.is_empty() {
+            self.semantic_token_highlights.remove(&buffer_id);
+        } else {
+            self.semantic_token_highlights
+                .insert(buffer_id, Arc::new(highlights));
+        }
+    }
+
+    /// Replaces the foldable ranges that language servers reported for the buffer. Without any,
+    /// the buffer folds by indentation.
+    pub(crate) fn set_lsp_fold_ranges(&mut self, buffer_id: BufferId, ranges: Vec<LspFoldRange>) {
+        if ranges.is_empty() {
+            self.lsp_fold_ranges.remove(&buffer_id);
+        } else {
+            self.lsp_fold_ranges.insert(buffer_id, Arc::new(ranges));
+        }
+    }
+
     pub fn clear_highlights(&mut self, type_id: TypeId) -> bool {
         let mut cleared = self.text_highlights.remove(&type_id).is_some();
         cleared |= self.inlay_highlights.remove(&type_id).is_some();
@@ -593,6 +639,7 @@ impl DisplayMap {
 pub(crate) struct Highlights<'a> {
     pub text_highlights: Option<&'a TextHighlights>,
     pub inlay_highlights: Option<&'a InlayHighlights>,
+    pub semantic_token_highlights: Option<&'a SemanticTokenHighlights>,
     pub styles: HighlightStyles,
 }
 
@@ -727,6 +774,8 @@ pub struct DisplaySnapshot {
     block_snapshot: BlockSnapshot,
     text_highlights: TextHighlights,
     inlay_highlights: InlayHighlights,
+    semantic_token_highlights: SemanticTokenHighlights,
+    lsp_fold_ranges: LspFoldRanges,
     clip_at_line_ends: bool,
     masked: bool,
     pub(crate) fold_placeholder: FoldPlaceholder,
@@ -893,6 +942,7 @@ impl DisplaySnapshot {
             Highlights {
                 text_highlights: Some(&self.text_highlights),
                 inlay_highlights: Some(&self.inlay_highlights),
+                semantic_token_highlights: Some(&self.semantic_token_highlights),
                 styles: highlight_styles,
             },
         )
@@ -1208,6 +1258,59 @@ impl DisplaySnapshot {
             .unwrap_or(false)
     }
 
+    /// Returns the foldable ranges that language servers reported for the buffer shown at the row,
+    /// if they reported any.
+    fn lsp_fold_ranges_for_buffer_row(
+        &self,
+        buffer_row: MultiBufferRow,
+    ) -> Option<&[LspFoldRange]> {
+        let (buffer, _) = self.buffer_snapshot.buffer_line_for_row(buffer_row)?;
+        let ranges = self.lsp_fold_ranges.get(&buffer.remote_id())?;
+        Some(ranges.as_slice())
+    }
+
+    /// Returns the outermost of the ranges that starts at the row and still spans several rows.
+    ///
+    /// Every range is considered, as edits since the ranges were reported can collapse them or
+    /// move them out of order.
+    fn lsp_fold_range_at(
+        &self,
+        ranges: &[LspFoldRange],
+        buffer_row: MultiBufferRow,
+    ) -> Option<Range<Point>> {
+        ranges
+            .iter()
+            .map(|range| range.range.to_point(&self.buffer_snapshot))
+            .filter(|range| range.start.row == buffer_row.0 && range.end.row > buffer_row.0)
+            .max_by_key(|range| range.end)
+    }
+
+    /// Whether a range folds at the row: one that a language server reported or, in buffers
+    /// without any, one that the indentation suggests.
+    pub fn starts_fold_range(&self, buffer_row: MultiBufferRow) -> bool {
+        match self.lsp_fold_ranges_for_buffer_row(buffer_row) {
+            Some(ranges) => self.lsp_fold_range_at(ranges, buffer_row).is_some(),
+            None => self.starts_indent(buffer_row),
+        }
+    }
+
+    /// Returns the ranges of the given kind that language servers reported as foldable.
+    pub fn lsp_fold_ranges_of_kind(
+        &self,
+        kind: lsp::FoldingRangeKind,
+    ) -> impl Iterator<Item = Range<Point>> + '_ {
+        self.lsp_fold_ranges
+            .values()
+            .flat_map(|ranges| ranges.iter())
+            .filter(move |range| range.kind.as_ref() == Some(&kind))
+            .map(|range| range.range.to_point(&self.buffer_snapshot))
+    }
+
+    /// Whether language servers reported foldable ranges for the buffer shown at the row.
+    pub fn has_lsp_fold_ranges(&self, buffer_row: MultiBufferRow) -> bool {
+        self.lsp_fold_ranges_for_buffer_row(buffer_row).is_some()
+    }
+
     pub fn crease_for_buffer_row(&self, buffer_row: MultiBufferRow) -> Option<Crease<Point>> {
         let start = MultiBufferPoint::new(buffer_row.0, self.buffer_snapshot.line_len(buffer_row));
         if let Some(crease) = self
@@ -1244,6 +1347,15 @@ impl DisplaySnapshot {
                     render_toggle: render_toggle.clone(),
                 }),
             }
+        } else if let Some(ranges) = self.lsp_fold_ranges_for_buffer_row(buffer_row) {
+            let range = self.lsp_fold_range_at(ranges, buffer_row)?;
+            (!self.is_line_folded(buffer_row)).then(|| Crease::Inline {
+                range,
+                placeholder: self.fold_placeholder.clone(),
+                render_toggle: None,
+                render_trailer: None,
+                metadata: None,
+            })
         } else if self.starts_indent(MultiBufferRow(start.row))
             && !self.is_line_folded(MultiBufferRow(start.row))
         {
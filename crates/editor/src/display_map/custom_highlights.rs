@@ -12,6 +12,8 @@ use std::{
 };
 use sum_tree::TreeMap;
 
+use super::SemanticTokenHighlights;
+
 pub struct CustomHighlightsChunks<'a> {
     buffer_chunks: MultiBufferChunks<'a>,
     buffer_chunk: Option<Chunk<'a>>,
@@ -21,6 +23,9 @@ pub struct CustomHighlightsChunks<'a> {
     highlight_endpoints: Peekable<vec::IntoIter<HighlightEndpoint>>,
     active_highlights: BTreeMap<TypeId, HighlightStyle>,
     text_highlights: Option<&'a TreeMap<TypeId, Arc<(HighlightStyle, Vec<Range<Anchor>>)>>>,
+    /// The non-empty semantic token ranges, ordered by their start.
+    semantic_token_ranges: Peekable<vec::IntoIter<(Range<usize>, HighlightStyle)>>,
+    semantic_token_highlights: Option<&'a SemanticTokenHighlights>,
 }
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
@@ -36,6 +41,7 @@ impl<'a> CustomHighlightsChunks<'a> {
         range: Range<usize>,
         language_aware: bool,
         text_highlights: Option<&'a TreeMap<TypeId, Arc<(HighlightStyle, Vec<Range<Anchor>>)>>>,
+        semantic_token_highlights: Option<&'a SemanticTokenHighlights>,
         multibuffer_snapshot: &'a MultiBufferSnapshot,
     ) -> Self {
         Self {
@@ -50,6 +56,12 @@ impl<'a> CustomHighlightsChunks<'a> {
                 multibuffer_snapshot,
             ),
             active_highlights: Default::default(),
+            semantic_token_ranges: create_semantic_token_ranges(
+                &range,
+                semantic_token_highlights,
+                multibuffer_snapshot,
+            ),
+            semantic_token_highlights,
             multibuffer_snapshot,
         }
     }
@@ -57,6 +69,11 @@ impl<'a> CustomHighlightsChunks<'a> {
     pub fn seek(&mut self, new_range: Range<usize>) {
         self.highlight_endpoints =
             create_highlight_endpoints(&new_range, self.text_highlights, self.multibuffer_snapshot);
+        self.semantic_token_ranges = create_semantic_token_ranges(
+            &new_range,
+            self.semantic_token_highlights,
+            self.multibuffer_snapshot,
+        );
         self.offset = new_range.start;
         self.buffer_chunks.seek(new_range);
         self.buffer_chunk.take();
@@ -112,6 +129,34 @@ fn create_highlight_endpoints(
     highlight_endpoints.into_iter().peekable()
 }
 
+fn create_semantic_token_ranges(
+    range: &Range<usize>,
+    semantic_token_highlights: Option<&SemanticTokenHighlights>,
+    buffer: &MultiBufferSnapshot,
+) -> iter::Peekable<vec::IntoIter<(Range<usize>, HighlightStyle)>> {
+    let mut token_ranges = Vec::new();
+    if let Some(semantic_token_highlights) = semantic_token_highlights {
+        let start = buffer.anchor_after(range.start);
+        let end = buffer.anchor_after(range.end);
+        for (_, tokens) in semantic_token_highlights.iter() {
+            let start_ix = tokens
+                .partition_point(|(token_range, _)| token_range.end.cmp(&start, &buffer).is_le());
+            for (token_range, style) in &tokens[start_ix..] {
+                if token_range.start.cmp(&end, &buffer).is_ge() {
+                    break;
+                }
+                let token_range =
+                    token_range.start.to_offset(&buffer)..token_range.end.to_offset(&buffer);
+                if !token_range.is_empty() {
+                    token_ranges.push((token_range, *style));
+                }
+            }
+        }
+        token_ranges.sort_by_key(|(token_range, _)| token_range.start);
+    }
+    token_ranges.into_iter().peekable()
+}
+
 impl<'a> Iterator for CustomHighlightsChunks<'a> {
     type Item = Chunk<'a>;
 
@@ -131,6 +176,21 @@ impl<'a> Iterator for CustomHighlightsChunks<'a> {
             }
         }
 
+        while self
+            .semantic_token_ranges
+            .next_if(|(token_range, _)| token_range.end <= self.offset)
+            .is_some()
+        {}
+        let mut semantic_token_style = None;
+        if let Some((token_range, style)) = self.semantic_token_ranges.peek() {
+            if token_range.start <= self.offset {
+                semantic_token_style = Some(*style);
+                next_highlight_endpoint = next_highlight_endpoint.min(token_range.end);
+            } else {
+                next_highlight_endpoint = next_highlight_endpoint.min(token_range.start);
+            }
+        }
+
         let chunk = self
             .buffer_chunk
             .get_or_insert_with(|| self.buffer_chunks.next().unwrap());
@@ -148,8 +208,10 @@ impl<'a> Iterator for CustomHighlightsChunks<'a> {
             text: prefix,
             ..chunk.clone()
         };
-        if !self.active_highlights.is_empty() {
-            let mut highlight_style = HighlightStyle::default();
+        if semantic_token_style.is_some() || !self.active_highlights.is_empty() {
+            // Semantic tokens refine the syntax highlighting, so the other highlights are drawn
+            // over them.
+            let mut highlight_style = semantic_token_style.unwrap_or_default();
             for active_highlight in self.active_highlights.values() {
                 highlight_style.highlight(*active_highlight);
             }
@@ -16,10 +16,12 @@ pub mod actions;
 mod blink_manager;
 mod clangd_ext;
 mod code_context_menus;
+mod code_lens;
 pub mod display_map;
 mod editor_settings;
 mod editor_settings_controls;
 mod element;
+mod folding_ranges;
 mod git;
 mod highlight_matching_bracket;
 mod hover_links;
@@ -37,6 +39,7 @@ mod proposed_changes_editor;
 mod rust_analyzer_ext;
 pub mod scroll;
 mod selections_collection;
+mod semantic_tokens;
 pub mod tasks;
 
 #[cfg(test)]
@@ -151,10 +154,11 @@ use multi_buffer::{
 };
 use parking_lot::Mutex;
 use project::{
-    CodeAction, Completion, CompletionIntent, CompletionSource, DocumentHighlight, InlayHint,
-    Location, LocationLink, PrepareRenameResponse, Project, ProjectItem, ProjectTransaction,
-    TaskSourceKind,
+    CodeAction, Completion, CompletionIntent, CompletionSource, DocumentHighlight, FoldingRange,
+    InlayHint, Location, LocationLink, PrepareRenameResponse, Project, ProjectItem,
+    ProjectTransaction, TaskSourceKind,
     debugger::breakpoint_store::Breakpoint,
+    lsp_command::BufferSemanticTokens,
     lsp_store::{CompletionDocumentation, FormatTrigger, LspFormatTarget, OpenLspBufferHandle},
     project_settings::{GitGutterSetting, ProjectSettings},
 };
@@ -729,6 +733,9 @@ pub struct Editor {
     document_highlights_task: Option<Task<()>>,
     linked_editing_range_task: Option<Task<Option<()>>>,
     linked_edit_ranges: linked_editing_ranges::LinkedEditingRanges,
+    semantic_tokens: semantic_tokens::SemanticTokens,
+    code_lenses: code_lens::CodeLenses,
+    folding_ranges: folding_ranges::FoldingRanges,
     pending_rename: Option<RenameState>,
     searchable: bool,
     cursor_shape: CursorShape,
@@ -1334,12 +1341,20 @@ impl Editor {
                     window,
                     |editor, _, event, window, cx| match event {
                         project::Event::RefreshCodeLens => {
-                            // we always query lens with actions, without storing them, always refreshing them
+                            code_lens::refresh_code_lenses(editor, None, false, cx);
                         }
                         project::Event::RefreshInlayHints => {
                             editor
                                 .refresh_inlay_hints(InlayHintRefreshReason::RefreshRequested, cx);
                         }
+                        project::Event::RefreshSemanticTokens => {
+                            semantic_tokens::refresh_semantic_tokens(editor, None, false, cx);
+                        }
+                        project::Event::LanguageServerAdded(..) => {
+                            semantic_tokens::refresh_semantic_tokens(editor, None, false, cx);
+                            code_lens::refresh_code_lenses(editor, None, false, cx);
+                            folding_ranges::refresh_folding_ranges(editor, None, false, cx);
+                        }
                         project::Event::SnippetEdit(id, snippet_edits) => {
                             if let Some(buffer) = editor.buffer.read(cx).buffer(*id) {
                                 let focus_handle = editor.focus_handle(cx);
@@ -1580,6 +1595,9 @@ impl Editor {
             ],
             tasks_update_task: None,
             linked_edit_ranges: Default::default(),
+            semantic_tokens: Default::default(),
+            code_lenses: Default::default(),
+            folding_ranges: Default::default(),
             in_project_search: false,
             previous_search_ranges: None,
             breadcrumb_header: None,
@@ -1633,6 +1651,9 @@ impl Editor {
             }
 
             this.go_to_active_debug_line(window, cx);
+            semantic_tokens::refresh_semantic_tokens(&mut this, None, false, cx);
+            code_lens::refresh_code_lenses(&mut this, None, false, cx);
+            folding_ranges::refresh_folding_ranges(&mut this, None, false, cx);
 
             if let Some(buffer) = buffer.read(cx).as_singleton() {
                 if let Some(project) = this.project.as_ref() {
@@ -14712,6 +14733,68 @@ impl Editor {
         self.fold_creases(creases, true, window, cx);
     }
 
+    pub fn fold_comments(
+        &mut self,
+        _: &actions::FoldComments,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.fold_ranges_of_kind(lsp::FoldingRangeKind::Comment, window, cx);
+    }
+
+    pub fn fold_imports(
+        &mut self,
+        _: &actions::FoldImports,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.fold_ranges_of_kind(lsp::FoldingRangeKind::Imports, window, cx);
+    }
+
+    /// Folds the ranges of the given kind that language servers reported and, in buffers without
+    /// any, the comments that tree-sitter finds.
+    fn fold_ranges_of_kind(
+        &mut self,
+        kind: lsp::FoldingRangeKind,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
+        let buffer = &display_map.buffer_snapshot;
+        let mut ranges = display_map
+            .lsp_fold_ranges_of_kind(kind.clone())
+            .collect::<Vec<_>>();
+        if kind == lsp::FoldingRangeKind::Comment {
+            let comment_ranges = buffer
+                .text_object_ranges(0..buffer.len(), TreeSitterOptions::default())
+                .filter(|(_, object)| *object == TextObject::AroundComment)
+                .filter_map(|(range, _)| {
+                    let start = range.start.to_point(buffer);
+                    let end = range.end.to_point(buffer);
+                    if display_map.has_lsp_fold_ranges(MultiBufferRow(start.row)) {
+                        return None;
+                    }
+                    // Keep the first line of the comment visible, like the folds of other ranges.
+                    let end_row = if end.column == 0 {
+                        end.row.saturating_sub(1)
+                    } else {
+                        end.row
+                    };
+                    (end_row > start.row).then(|| {
+                        Point::new(start.row, buffer.line_len(MultiBufferRow(start.row)))
+                            ..Point::new(end_row, buffer.line_len(MultiBufferRow(end_row)))
+                    })
+                });
+            ranges.extend(comment_ranges);
+        }
+
+        let creases = ranges
+            .into_iter()
+            .map(|range| Crease::simple(range, display_map.fold_placeholder.clone()))
+            .collect();
+        self.fold_creases(creases, true, window, cx);
+    }
+
     pub fn fold_recursive(
         &mut self,
         _: &actions::FoldRecursive,
@@ -16907,6 +16990,9 @@ impl Editor {
                             })
                         }
                     }
+                    semantic_tokens::refresh_semantic_tokens(self, Some(buffer_id), true, cx);
+                    code_lens::refresh_code_lenses(self, Some(buffer_id), true, cx);
+                    folding_ranges::refresh_folding_ranges(self, Some(buffer_id), true, cx);
                 }
                 cx.emit(EditorEvent::BufferEdited);
                 cx.emit(SearchEvent::MatchesInvalidated);
@@ -16972,9 +17058,27 @@ impl Editor {
                     excerpts: excerpts.clone(),
                 });
                 self.refresh_inlay_hints(InlayHintRefreshReason::NewLinesShown, cx);
+                if self.semantic_tokens.has_tokens(buffer_id) {
+                    semantic_tokens::update_semantic_token_highlights(self, cx);
+                } else {
+                    semantic_tokens::refresh_semantic_tokens(self, Some(buffer_id), false, cx);
+                }
+                if self.code_lenses.has_lenses(buffer_id) {
+                    code_lens::update_code_lens_blocks(self, cx);
+                } else {
+                    code_lens::refresh_code_lenses(self, Some(buffer_id), false, cx);
+                }
+                if self.folding_ranges.has_ranges(buffer_id) {
+                    folding_ranges::update_fold_ranges(self, cx);
+                } else {
+                    folding_ranges::refresh_folding_ranges(self, Some(buffer_id), false, cx);
+                }
             }
             multi_buffer::Event::ExcerptsRemoved { ids } => {
                 self.refresh_inlay_hints(InlayHintRefreshReason::ExcerptsRemoved(ids.clone()), cx);
+                semantic_tokens::update_semantic_token_highlights(self, cx);
+                code_lens::update_code_lens_blocks(self, cx);
+                folding_ranges::update_fold_ranges(self, cx);
                 let buffer = self.buffer.read(cx);
                 self.registered_buffers
                     .retain(|buffer_id, _| buffer.buffer(*buffer_id).is_some());
@@ -16994,6 +17098,9 @@ impl Editor {
             }
             multi_buffer::Event::ExcerptsExpanded { ids } => {
                 self.refresh_inlay_hints(InlayHintRefreshReason::NewLinesShown, cx);
+                semantic_tokens::update_semantic_token_highlights(self, cx);
+                code_lens::update_code_lens_blocks(self, cx);
+                folding_ranges::update_fold_ranges(self, cx);
                 cx.emit(EditorEvent::ExcerptsExpanded { ids: ids.clone() })
             }
             multi_buffer::Event::Reparsed(buffer_id) => {
@@ -17007,6 +17114,9 @@ impl Editor {
             }
             multi_buffer::Event::LanguageChanged(buffer_id) => {
                 linked_editing_ranges::refresh_linked_ranges(self, window, cx);
+                semantic_tokens::refresh_semantic_tokens(self, Some(*buffer_id), false, cx);
+                code_lens::refresh_code_lenses(self, Some(*buffer_id), false, cx);
+                folding_ranges::refresh_folding_ranges(self, Some(*buffer_id), false, cx);
                 jsx_tag_auto_close::refresh_enabled_in_any_buffer(self, multibuffer, cx);
                 cx.emit(EditorEvent::Reparsed(*buffer_id));
                 cx.notify();
@@ -17077,6 +17187,10 @@ impl Editor {
             if self.git_blame_inline_enabled != inline_blame_enabled {
                 self.toggle_git_blame_inline_internal(false, window, cx);
             }
+
+            semantic_tokens::semantic_tokens_settings_changed(self, cx);
+            code_lens::code_lens_settings_changed(self, cx);
+            folding_ranges::folding_ranges_settings_changed(self, cx);
         }
 
         cx.notify();
@@ -18337,6 +18451,31 @@ pub trait SemanticsProvider {
 
     fn supports_inlay_hints(&self, buffer: &Entity<Buffer>, cx: &mut App) -> bool;
 
+    fn semantic_tokens(
+        &self,
+        buffer: &Entity<Buffer>,
+        cx: &mut App,
+    ) -> Option<Task<anyhow::Result<BufferSemanticTokens>>>;
+
+    fn code_lens(
+        &self,
+        buffer: &Entity<Buffer>,
+        cx: &mut App,
+    ) -> Option<Task<anyhow::Result<Vec<CodeAction>>>>;
+
+    fn resolve_code_lens(
+        &self,
+        buffer: Entity<Buffer>,
+        lens: CodeAction,
+        cx: &mut App,
+    ) -> Option<Task<anyhow::Result<CodeAction>>>;
+
+    fn folding_ranges(
+        &self,
+        buffer: &Entity<Buffer>,
+        cx: &mut App,
+    ) -> Option<Task<anyhow::Result<Vec<FoldingRange>>>>;
+
     fn document_highlights(
         &self,
         buffer: &Entity<Buffer>,
@@ -18790,6 +18929,44 @@ impl SemanticsProvider for Entity<Project> {
         }))
     }
 
+    fn semantic_tokens(
+        &self,
+        buffer: &Entity<Buffer>,
+        cx: &mut App,
+    ) -> Option<Task<anyhow::Result<BufferSemanticTokens>>> {
+        Some(self.update(cx, |project, cx| project.semantic_tokens(buffer, cx)))
+    }
+
+    fn code_lens(
+        &self,
+        buffer: &Entity<Buffer>,
+        cx: &mut App,
+    ) -> Option<Task<anyhow::Result<Vec<CodeAction>>>> {
+        Some(self.update(cx, |project, cx| {
+            let len = buffer.read(cx).len();
+            project.code_lens(buffer, 0..len, cx)
+        }))
+    }
+
+    fn resolve_code_lens(
+        &self,
+        buffer: Entity<Buffer>,
+        lens: CodeAction,
+        cx: &mut App,
+    ) -> Option<Task<anyhow::Result<CodeAction>>> {
+        Some(self.update(cx, |project, cx| {
+            project.resolve_code_lens(buffer, lens, cx)
+        }))
+    }
+
+    fn folding_ranges(
+        &self,
+        buffer: &Entity<Buffer>,
+        cx: &mut App,
+    ) -> Option<Task<anyhow::Result<Vec<FoldingRange>>>> {
+        Some(self.update(cx, |project, cx| project.folding_ranges(buffer, cx)))
+    }
+
     fn range_for_rename(
         &self,
         buffer: &Entity<Buffer>,
@@ -19146,7 +19323,7 @@ impl EditorSnapshot {
             }
         }
 
-        is_foldable |= self.starts_indent(buffer_row);
+        is_foldable |= self.starts_fold_range(buffer_row);
 
         if folded || (is_foldable && (row_contains_cursor || self.gutter_hovered)) {
             Some(
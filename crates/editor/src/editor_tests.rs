@@ -6254,6 +6254,80 @@ async fn test_fold_function_bodies(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_lsp_folding_ranges(cx: &mut TestAppContext) {
+    init_test(cx, |settings| {
+        settings.defaults.lsp_folding_ranges = Some(true)
+    });
+
+    let mut cx = EditorLspTestContext::new_rust(
+        lsp::ServerCapabilities {
+            folding_range_provider: Some(lsp::FoldingRangeProviderCapability::Simple(true)),
+            ..Default::default()
+        },
+        cx,
+    )
+    .await;
+
+    let folding_range = |start_line, end_line, kind| lsp::FoldingRange {
+        start_line,
+        start_character: None,
+        end_line,
+        end_character: None,
+        kind,
+        collapsed_text: None,
+    };
+    cx.set_request_handler::<lsp::request::FoldingRangeRequest, _, _>(move |_, _, _| async move {
+        Ok(Some(vec![
+            folding_range(0, 1, Some(lsp::FoldingRangeKind::Imports)),
+            folding_range(3, 5, Some(lsp::FoldingRangeKind::Comment)),
+            folding_range(6, 7, None),
+        ]))
+    });
+    cx.set_state(indoc! {"
+        ˇuse a::b;
+        use c::d;
+
+        // one
+        // two
+        // three
+        fn main() {
+            let x = 1;
+        }
+    "});
+    cx.executor().advance_clock(Duration::from_millis(500));
+    cx.run_until_parked();
+
+    cx.update_editor(|editor, window, cx| {
+        let snapshot = editor.snapshot(window, cx);
+        assert!(
+            snapshot.starts_fold_range(MultiBufferRow(3)),
+            "The comment should be foldable, although it isn't indented"
+        );
+        assert!(!snapshot.starts_fold_range(MultiBufferRow(2)));
+
+        editor.fold_comments(&FoldComments, window, cx);
+        assert_eq!(
+            editor.display_text(cx),
+            "use a::b;\nuse c::d;\n\n// one⋯\nfn main() {\n    let x = 1;\n}\n"
+        );
+        editor.unfold_all(&UnfoldAll, window, cx);
+
+        editor.fold_imports(&FoldImports, window, cx);
+        assert_eq!(
+            editor.display_text(cx),
+            "use a::b;⋯\n\n// one\n// two\n// three\nfn main() {\n    let x = 1;\n}\n"
+        );
+        editor.unfold_all(&UnfoldAll, window, cx);
+
+        editor.fold_all(&FoldAll, window, cx);
+        assert_eq!(
+            editor.display_text(cx),
+            "use a::b;⋯\n\n// one⋯\nfn main() {⋯\n}\n"
+        );
+    });
+}
+
 #[gpui::test]
 async fn test_autoindent(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -18260,6 +18334,148 @@ async fn test_apply_code_lens_actions_with_commands(cx: &mut gpui::TestAppContex
     });
 }
 
+#[gpui::test]
+async fn test_code_lens_blocks(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |settings| settings.defaults.code_lens = Some(true));
+
+    let far_lines = "\n".repeat(30);
+    let text = format!("function a() {{}}\n\nfunction b() {{}}\n{far_lines}function c() {{}}\n");
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "a.ts": text.clone(),
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(Arc::new(Language::new(
+        LanguageConfig {
+            name: "TypeScript".into(),
+            matcher: LanguageMatcher {
+                path_suffixes: vec!["ts".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        Some(tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into()),
+    )));
+    let mut fake_language_servers = language_registry.register_fake_lsp(
+        "TypeScript",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                code_lens_provider: Some(lsp::CodeLensOptions {
+                    resolve_provider: Some(true),
+                }),
+                execute_command_provider: Some(lsp::ExecuteCommandOptions {
+                    commands: vec!["_the/command".to_string()],
+                    ..lsp::ExecuteCommandOptions::default()
+                }),
+                ..lsp::ServerCapabilities::default()
+            },
+            ..FakeLspAdapter::default()
+        },
+    );
+
+    let workspace = cx.add_window(|window, cx| Workspace::test_new(project.clone(), window, cx));
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+    let worktree_id = workspace
+        .update(cx, |workspace, _window, cx| {
+            workspace.project().update(cx, |project, cx| {
+                project.worktrees(cx).next().unwrap().read(cx).id()
+            })
+        })
+        .unwrap();
+    let editor = workspace
+        .update(cx, |workspace, window, cx| {
+            workspace.open_path((worktree_id, "a.ts"), None, true, window, cx)
+        })
+        .unwrap()
+        .await
+        .unwrap()
+        .downcast::<Editor>()
+        .unwrap();
+    let fake_server = fake_language_servers.next().await.unwrap();
+    editor.update_in(cx, |editor, window, cx| {
+        editor.set_visible_line_count(10., window, cx)
+    });
+
+    let command = |title: &str, command: &str| lsp::Command {
+        title: title.to_owned(),
+        command: command.to_owned(),
+        arguments: None,
+    };
+    let row_range = |row| lsp::Range::new(lsp::Position::new(row, 0), lsp::Position::new(row, 8));
+    fake_server.set_request_handler::<lsp::request::CodeLensRequest, _, _>(
+        move |_, _| async move {
+            Ok(Some(vec![
+                lsp::CodeLens {
+                    range: row_range(0),
+                    command: Some(command("1 reference", "_the/command")),
+                    data: None,
+                },
+                lsp::CodeLens {
+                    range: row_range(0),
+                    command: None,
+                    data: Some(json!("run")),
+                },
+                lsp::CodeLens {
+                    range: row_range(2),
+                    command: Some(command("Not executable", "not in capabilities")),
+                    data: None,
+                },
+                lsp::CodeLens {
+                    range: row_range(33),
+                    command: None,
+                    data: Some(json!("far")),
+                },
+            ]))
+        },
+    );
+    let resolve_requests = Arc::new(AtomicUsize::new(0));
+    fake_server.set_request_handler::<lsp::request::CodeLensResolve, _, _>({
+        let resolve_requests = resolve_requests.clone();
+        move |mut lens, _| {
+            resolve_requests.fetch_add(1, atomic::Ordering::Release);
+            async move {
+                assert_eq!(
+                    lens.data,
+                    Some(json!("run")),
+                    "Only the lenses on the visible lines should be resolved"
+                );
+                lens.command = Some(command("Run", "_the/command"));
+                Ok(lens)
+            }
+        }
+    });
+    project.update(cx, |_, cx| cx.emit(project::Event::RefreshCodeLens));
+    cx.run_until_parked();
+
+    editor.update(cx, |editor, cx| {
+        let buffer_id = editor
+            .buffer()
+            .read(cx)
+            .as_singleton()
+            .unwrap()
+            .read(cx)
+            .remote_id();
+        assert_eq!(
+            editor.code_lenses.titles(buffer_id),
+            ["1 reference", "Run", "Unknown command"],
+            "Visible lenses should be resolved, and those with commands the server can't run dropped"
+        );
+        assert_eq!(
+            editor.display_text(cx),
+            format!("\nfunction a() {{}}\n\nfunction b() {{}}\n{far_lines}function c() {{}}\n"),
+            "The lenses of a line should share a single block above it, and unresolved lenses have no block"
+        );
+    });
+    assert_eq!(resolve_requests.load(atomic::Ordering::Acquire), 1);
+}
+
 #[gpui::test]
 async fn test_editor_restore_data_different_in_panes(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -0,0 +1,190 @@
+use std::time::Duration;
+
+use collections::HashMap;
+use gpui::{App, Context, Entity, Task};
+use language::{Buffer, language_settings::language_settings};
+use project::FoldingRange;
+use text::BufferId;
+use util::ResultExt as _;
+
+use crate::{Editor, EditorMode, display_map::LspFoldRange};
+
+/// How long to wait after an edit before asking for the new folding ranges of a buffer.
+const UPDATE_DEBOUNCE: Duration = Duration::from_millis(250);
+
+/// The folding ranges that language servers reported for the buffers shown in an editor.
+#[derive(Default)]
+pub(super) struct FoldingRanges {
+    buffer_ranges: HashMap<BufferId, Vec<FoldingRange>>,
+    refresh_tasks: HashMap<BufferId, Task<()>>,
+}
+
+impl FoldingRanges {
+    pub(super) fn has_ranges(&self, buffer_id: BufferId) -> bool {
+        self.buffer_ranges.contains_key(&buffer_id)
+    }
+}
+
+/// Requests the folding ranges of the given buffer, or of all the editor's buffers, and folds by
+/// them once they arrive.
+pub(super) fn refresh_folding_ranges(
+    editor: &mut Editor,
+    buffer_id: Option<BufferId>,
+    debounce: bool,
+    cx: &mut Context<Editor>,
+) {
+    if editor.mode != EditorMode::Full {
+        return;
+    }
+    let Some(provider) = editor.semantics_provider.clone() else {
+        return;
+    };
+    let buffers = match buffer_id {
+        Some(buffer_id) => editor
+            .buffer
+            .read(cx)
+            .buffer(buffer_id)
+            .into_iter()
+            .collect(),
+        None => editor
+            .buffer
+            .read(cx)
+            .all_buffers()
+            .into_iter()
+            .collect::<Vec<_>>(),
+    };
+
+    for buffer in buffers {
+        let buffer_id = buffer.read(cx).remote_id();
+        if !folding_ranges_enabled(&buffer, cx) {
+            editor.folding_ranges.refresh_tasks.remove(&buffer_id);
+            if editor
+                .folding_ranges
+                .buffer_ranges
+                .remove(&buffer_id)
+                .is_some()
+            {
+                update_buffer_fold_ranges(editor, buffer_id, cx);
+            }
+            continue;
+        }
+
+        let provider = provider.clone();
+        let task = cx.spawn(async move |editor, cx| {
+            if debounce {
+                cx.background_executor().timer(UPDATE_DEBOUNCE).await;
+            }
+            let Some(ranges) = cx
+                .update(|cx| provider.folding_ranges(&buffer, cx))
+                .ok()
+                .flatten()
+            else {
+                return;
+            };
+            let Some(ranges) = ranges.await.log_err() else {
+                return;
+            };
+            editor
+                .update(cx, |editor, cx| {
+                    editor
+                        .folding_ranges
+                        .buffer_ranges
+                        .insert(buffer_id, ranges);
+                    update_buffer_fold_ranges(editor, buffer_id, cx);
+                })
+                .ok();
+        });
+        editor.folding_ranges.refresh_tasks.insert(buffer_id, task);
+    }
+}
+
+/// Requests the ranges of the buffers that had them turned on, and drops those of the buffers
+/// that had them turned off.
+pub(super) fn folding_ranges_settings_changed(editor: &mut Editor, cx: &mut Context<Editor>) {
+    let buffers = editor.buffer.read(cx).all_buffers();
+    for buffer in buffers {
+        let buffer_id = buffer.read(cx).remote_id();
+        let requested = editor.folding_ranges.refresh_tasks.contains_key(&buffer_id);
+        if folding_ranges_enabled(&buffer, cx) != requested {
+            refresh_folding_ranges(editor, Some(buffer_id), false, cx);
+        }
+    }
+}
+
+/// Passes the ranges of every buffer to the display map again, dropping those of the buffers that
+/// were removed from the editor.
+pub(super) fn update_fold_ranges(editor: &mut Editor, cx: &mut Context<Editor>) {
+    let buffer_ids = editor
+        .folding_ranges
+        .buffer_ranges
+        .keys()
+        .copied()
+        .collect::<Vec<_>>();
+    for buffer_id in buffer_ids {
+        if editor.buffer.read(cx).buffer(buffer_id).is_none() {
+            editor.folding_ranges.buffer_ranges.remove(&buffer_id);
+            editor.folding_ranges.refresh_tasks.remove(&buffer_id);
+        }
+        update_buffer_fold_ranges(editor, buffer_id, cx);
+    }
+}
+
+fn folding_ranges_enabled(buffer: &Entity<Buffer>, cx: &App) -> bool {
+    let buffer = buffer.read(cx);
+    language_settings(buffer.language().map(|l| l.name()), buffer.file(), cx).lsp_folding_ranges
+}
+
+fn update_buffer_fold_ranges(editor: &mut Editor, buffer_id: BufferId, cx: &mut Context<Editor>) {
+    let ranges = editor
+        .folding_ranges
+        .buffer_ranges
+        .get(&buffer_id)
+        .map(|ranges| excerpt_fold_ranges(editor, buffer_id, ranges, cx))
+        .unwrap_or_default();
+    editor.display_map.update(cx, |display_map, _| {
+        display_map.set_lsp_fold_ranges(buffer_id, ranges)
+    });
+    cx.notify();
+}
+
+/// Maps the ranges into every excerpt of the buffer that shows them whole.
+fn excerpt_fold_ranges(
+    editor: &Editor,
+    buffer_id: BufferId,
+    ranges: &[FoldingRange],
+    cx: &App,
+) -> Vec<LspFoldRange> {
+    let multibuffer = editor.buffer.read(cx);
+    let Some(buffer) = multibuffer.buffer(buffer_id) else {
+        return Vec::new();
+    };
+    let buffer_snapshot = buffer.read(cx).snapshot();
+    let snapshot = multibuffer.snapshot(cx);
+
+    let mut fold_ranges = Vec::new();
+    for (excerpt_id, excerpt_range) in multibuffer.excerpts_for_buffer(buffer_id, cx) {
+        let context = excerpt_range.context;
+        for range in ranges {
+            if range
+                .range
+                .start
+                .cmp(&context.start, &buffer_snapshot)
+                .is_lt()
+                || range.range.end.cmp(&context.end, &buffer_snapshot).is_gt()
+            {
+                continue;
+            }
+            let (Some(start), Some(end)) = (
+                snapshot.anchor_in_excerpt(excerpt_id, range.range.start),
+                snapshot.anchor_in_excerpt(excerpt_id, range.range.end),
+            ) else {
+                continue;
+            };
+            fold_ranges.push(LspFoldRange {
+                range: start..end,
+                kind: range.kind.clone(),
+            });
+        }
+    }
+    fold_ranges
+}
@@ -219,6 +219,12 @@ pub fn deploy_context_menu(
                     } else {
                         builder.disabled_action(COPY_PERMALINK_LABEL, Box::new(CopyPermalinkToLine))
                     }
+                })
+                .when(has_git_repo, |builder| {
+                    builder
+                        .separator()
+                        .action("View File History", Box::new(git::FileHistory::default()))
+                        .action("View Line History", Box::new(git::LineHistory))
                 });
             match focus {
                 Some(focus) => builder.context(focus),
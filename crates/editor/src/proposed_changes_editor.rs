@@ -474,6 +474,39 @@ impl SemanticsProvider for BranchBufferSemanticsProvider {
         }
     }
 
+    fn semantic_tokens(
+        &self,
+        _buffer: &Entity<Buffer>,
+        _cx: &mut App,
+    ) -> Option<Task<anyhow::Result<project::lsp_command::BufferSemanticTokens>>> {
+        None
+    }
+
+    fn code_lens(
+        &self,
+        _buffer: &Entity<Buffer>,
+        _cx: &mut App,
+    ) -> Option<Task<anyhow::Result<Vec<project::CodeAction>>>> {
+        None
+    }
+
+    fn resolve_code_lens(
+        &self,
+        _buffer: Entity<Buffer>,
+        _lens: project::CodeAction,
+        _cx: &mut App,
+    ) -> Option<Task<anyhow::Result<project::CodeAction>>> {
+        None
+    }
+
+    fn folding_ranges(
+        &self,
+        _buffer: &Entity<Buffer>,
+        _cx: &mut App,
+    ) -> Option<Task<anyhow::Result<Vec<project::FoldingRange>>>> {
+        None
+    }
+
     fn document_highlights(
         &self,
         buffer: &Entity<Buffer>,
@@ -0,0 +1,212 @@
+use std::{ops::Range, time::Duration};
+
+use collections::HashMap;
+use gpui::{App, Context, Entity, HighlightStyle, Task};
+use language::{Buffer, language_settings::language_settings};
+use multi_buffer::Anchor;
+use project::lsp_command::BufferSemanticTokens;
+use text::BufferId;
+use theme::ActiveTheme as _;
+use util::ResultExt as _;
+
+use crate::{Editor, EditorMode};
+
+/// How long to wait after an edit before asking for the new semantic tokens of a buffer.
+const UPDATE_DEBOUNCE: Duration = Duration::from_millis(150);
+
+/// The semantic tokens of the buffers shown in an editor.
+#[derive(Default)]
+pub(super) struct SemanticTokens {
+    buffer_tokens: HashMap<BufferId, BufferSemanticTokens>,
+    refresh_tasks: HashMap<BufferId, Task<()>>,
+}
+
+impl SemanticTokens {
+    pub(super) fn has_tokens(&self, buffer_id: BufferId) -> bool {
+        self.buffer_tokens.contains_key(&buffer_id)
+    }
+}
+
+/// Requests the semantic tokens of the given buffer, or of all the editor's buffers, and
+/// highlights them once they arrive.
+pub(super) fn refresh_semantic_tokens(
+    editor: &mut Editor,
+    buffer_id: Option<BufferId>,
+    debounce: bool,
+    cx: &mut Context<Editor>,
+) {
+    if editor.mode != EditorMode::Full {
+        return;
+    }
+    let Some(provider) = editor.semantics_provider.clone() else {
+        return;
+    };
+    let buffers = match buffer_id {
+        Some(buffer_id) => editor
+            .buffer
+            .read(cx)
+            .buffer(buffer_id)
+            .into_iter()
+            .collect(),
+        None => editor
+            .buffer
+            .read(cx)
+            .all_buffers()
+            .into_iter()
+            .collect::<Vec<_>>(),
+    };
+
+    for buffer in buffers {
+        let buffer_id = buffer.read(cx).remote_id();
+        if !semantic_tokens_enabled(&buffer, cx) {
+            editor.semantic_tokens.refresh_tasks.remove(&buffer_id);
+            if editor
+                .semantic_tokens
+                .buffer_tokens
+                .remove(&buffer_id)
+                .is_some()
+            {
+                update_buffer_highlights(editor, buffer_id, cx);
+            }
+            continue;
+        }
+
+        let provider = provider.clone();
+        let task = cx.spawn(async move |editor, cx| {
+            if debounce {
+                cx.background_executor().timer(UPDATE_DEBOUNCE).await;
+            }
+            let Some(tokens) = cx
+                .update(|cx| provider.semantic_tokens(&buffer, cx))
+                .ok()
+                .flatten()
+            else {
+                return;
+            };
+            let Some(tokens) = tokens.await.log_err() else {
+                return;
+            };
+            editor
+                .update(cx, |editor, cx| {
+                    editor
+                        .semantic_tokens
+                        .buffer_tokens
+                        .insert(buffer_id, tokens);
+                    update_buffer_highlights(editor, buffer_id, cx);
+                })
+                .ok();
+        });
+        editor.semantic_tokens.refresh_tasks.insert(buffer_id, task);
+    }
+}
+
+/// Requests the tokens of the buffers that had them turned on, drops those of the buffers that
+/// had them turned off, and restyles the rest, as the theme may have changed.
+pub(super) fn semantic_tokens_settings_changed(editor: &mut Editor, cx: &mut Context<Editor>) {
+    let buffers = editor.buffer.read(cx).all_buffers();
+    for buffer in buffers {
+        let buffer_id = buffer.read(cx).remote_id();
+        let requested = editor
+            .semantic_tokens
+            .refresh_tasks
+            .contains_key(&buffer_id);
+        if semantic_tokens_enabled(&buffer, cx) != requested {
+            refresh_semantic_tokens(editor, Some(buffer_id), false, cx);
+        }
+    }
+    update_semantic_token_highlights(editor, cx);
+}
+
+/// Highlights the tokens of every buffer again, dropping those of the buffers that were removed
+/// from the editor.
+pub(super) fn update_semantic_token_highlights(editor: &mut Editor, cx: &mut Context<Editor>) {
+    let buffer_ids = editor
+        .semantic_tokens
+        .buffer_tokens
+        .keys()
+        .copied()
+        .collect::<Vec<_>>();
+    for buffer_id in buffer_ids {
+        if editor.buffer.read(cx).buffer(buffer_id).is_none() {
+            editor.semantic_tokens.buffer_tokens.remove(&buffer_id);
+            editor.semantic_tokens.refresh_tasks.remove(&buffer_id);
+        }
+        update_buffer_highlights(editor, buffer_id, cx);
+    }
+}
+
+fn semantic_tokens_enabled(buffer: &Entity<Buffer>, cx: &App) -> bool {
+    let buffer = buffer.read(cx);
+    language_settings(buffer.language().map(|l| l.name()), buffer.file(), cx).semantic_tokens
+}
+
+fn update_buffer_highlights(editor: &mut Editor, buffer_id: BufferId, cx: &mut Context<Editor>) {
+    let highlights = editor
+        .semantic_tokens
+        .buffer_tokens
+        .get(&buffer_id)
+        .map(|tokens| token_highlights(editor, buffer_id, tokens, cx))
+        .unwrap_or_default();
+    editor.display_map.update(cx, |display_map, _| {
+        display_map.set_semantic_token_highlights(buffer_id, highlights)
+    });
+    cx.notify();
+}
+
+/// Styles the tokens with the theme, in every excerpt of the buffer that shows them.
+fn token_highlights(
+    editor: &Editor,
+    buffer_id: BufferId,
+    tokens: &BufferSemanticTokens,
+    cx: &App,
+) -> Vec<(Range<Anchor>, HighlightStyle)> {
+    let multibuffer = editor.buffer.read(cx);
+    let Some(buffer) = multibuffer.buffer(buffer_id) else {
+        return Vec::new();
+    };
+    let buffer_snapshot = buffer.read(cx).snapshot();
+    let snapshot = multibuffer.snapshot(cx);
+    let syntax_theme = cx.theme().syntax();
+
+    let mut styles = HashMap::<(u32, u32), Option<HighlightStyle>>::default();
+    let mut highlights = Vec::new();
+    for (excerpt_id, excerpt_range) in multibuffer.excerpts_for_buffer(buffer_id, cx) {
+        let context = excerpt_range.context;
+        let start_ix = tokens.tokens.partition_point(|token| {
+            token
+                .range
+                .end
+                .cmp(&context.start, &buffer_snapshot)
+                .is_le()
+        });
+        for token in &tokens.tokens[start_ix..] {
+            if token
+                .range
+                .start
+                .cmp(&context.end, &buffer_snapshot)
+                .is_ge()
+            {
+                break;
+            }
+            let style = *styles
+                .entry((token.token_type, token.token_modifiers))
+                .or_insert_with(|| {
+                    syntax_theme.semantic_token_style(
+                        tokens.token_type(token)?,
+                        tokens.token_modifiers(token),
+                    )
+                });
+            let Some(style) = style else {
+                continue;
+            };
+            let (Some(start), Some(end)) = (
+                snapshot.anchor_in_excerpt(excerpt_id, token.range.start),
+                snapshot.anchor_in_excerpt(excerpt_id, token.range.end),
+            ) else {
+                continue;
+            };
+            highlights.push((start..end, style));
+        }
+    }
+    highlights
+}
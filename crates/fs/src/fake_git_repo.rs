@@ -1,20 +1,28 @@
-use crate::FakeFs;
+use crate::{FakeFs, Fs as _, RemoveOptions};
 use anyhow::{Context as _, Result, anyhow};
 use collections::{HashMap, HashSet};
 use futures::future::{self, BoxFuture};
 use git::{
     blame::Blame,
+    rebase::{RebaseAction, RebaseOperation, RebaseState, RebaseTodoEntry},
     repository::{
-        AskPassDelegate, Branch, CommitDetails, GitRepository, GitRepositoryCheckpoint,
-        PushOptions, Remote, RepoPath, ResetMode,
+        AskPassDelegate, Branch, CommitDetails, CommitDiff, CommitFile, CommitOptions,
+        DetachedHead, GitRepository, GitRepositoryCheckpoint, GitWorktree, LogEntry, LogFilter,
+        PushOptions, Remote, RemoteCommandOutput, RepoPath, ResetMode, SequencerCommand,
+        StashEntry, StashMode, Submodule, Tag,
+    },
+    status::{
+        FileStatus, GitStatus, StatusCode, TrackedStatus, UnmergedStatus, UnmergedStatusCode,
     },
-    status::{FileStatus, GitStatus, StatusCode, TrackedStatus, UnmergedStatus},
 };
-use gpui::{AsyncApp, BackgroundExecutor};
+use gpui::{AsyncApp, BackgroundExecutor, SharedString};
 use ignore::gitignore::GitignoreBuilder;
 use rope::Rope;
 use smol::future::FutureExt as _;
-use std::{path::PathBuf, sync::Arc};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
 #[derive(Clone)]
 pub struct FakeGitRepository {
@@ -33,7 +41,66 @@ pub struct FakeGitRepositoryState {
     pub blames: HashMap<RepoPath, Blame>,
     pub current_branch_name: Option<String>,
     pub branches: HashSet<String>,
+    pub stash_entries: Vec<StashEntry>,
+    /// The changes saved by each stash entry, by the entry's sha.
+    pub stashes: HashMap<SharedString, FakeStash>,
+    pub commits: HashMap<String, FakeCommit>,
+    /// The commits reachable from HEAD, oldest first.
+    pub head_commits: Vec<String>,
+    pub rebase: Option<FakeRebase>,
+    pub sequencer: Option<FakeSequencer>,
+    pub detached_head: Option<DetachedHead>,
+    pub tags: Vec<Tag>,
+    /// The linked worktrees of the repository.
+    pub worktrees: Vec<GitWorktree>,
+    pub submodules: Vec<Submodule>,
     pub simulated_index_write_error_message: Option<String>,
+    next_object_id: usize,
+}
+
+/// A commit of a fake repository, along with the contents of the files it changed.
+#[derive(Debug, Clone)]
+pub struct FakeCommit {
+    pub sha: String,
+    pub subject: String,
+    pub timestamp: i64,
+    pub files: Vec<FakeCommitFile>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FakeCommitFile {
+    pub path: RepoPath,
+    pub old_text: Option<String>,
+    pub new_text: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct FakeStash {
+    /// The stashed files, from their contents in HEAD to their contents in the working copy.
+    pub files: Vec<FakeCommitFile>,
+    /// The contents of the stashed files in the index.
+    pub index_contents: HashMap<RepoPath, Option<String>>,
+}
+
+/// A rebase that stopped at an `edit` entry.
+#[derive(Debug, Clone)]
+pub struct FakeRebase {
+    pub state: RebaseState,
+    pub remaining_entries: Vec<RebaseTodoEntry>,
+    pub original_head_commits: Vec<String>,
+}
+
+/// A cherry-pick or revert that stopped with a conflict.
+#[derive(Debug, Clone)]
+pub struct FakeSequencer {
+    pub command: SequencerCommand,
+    /// The commit whose changes conflicted.
+    pub current: String,
+    pub remaining: Vec<String>,
+    pub no_commit: bool,
+    original_head_contents: HashMap<RepoPath, String>,
+    original_index_contents: HashMap<RepoPath, String>,
+    original_head_commits: Vec<String>,
 }
 
 impl FakeGitRepositoryState {
@@ -47,11 +114,325 @@ impl FakeGitRepositoryState {
             blames: Default::default(),
             current_branch_name: Default::default(),
             branches: Default::default(),
+            stash_entries: Default::default(),
+            stashes: Default::default(),
+            commits: Default::default(),
+            head_commits: Default::default(),
+            rebase: Default::default(),
+            sequencer: Default::default(),
+            detached_head: Default::default(),
+            tags: Default::default(),
+            worktrees: Default::default(),
+            submodules: Default::default(),
             simulated_index_write_error_message: Default::default(),
+            next_object_id: 0,
+        }
+    }
+
+    fn next_sha_and_timestamp(&mut self) -> (String, i64) {
+        self.next_object_id += 1;
+        (
+            format!("{:040x}", self.next_object_id),
+            self.next_object_id as i64,
+        )
+    }
+
+    pub fn head_sha(&self) -> Option<&String> {
+        self.head_commits.last()
+    }
+
+    /// Commits the given changes on top of HEAD, updating the contents of HEAD and the index.
+    pub fn commit(&mut self, subject: impl Into<String>, files: Vec<FakeCommitFile>) -> String {
+        let (sha, timestamp) = self.next_sha_and_timestamp();
+        for file in &files {
+            set_text(&mut self.head_contents, &file.path, file.new_text.clone());
+            set_text(&mut self.index_contents, &file.path, file.new_text.clone());
+        }
+        self.commits.insert(
+            sha.clone(),
+            FakeCommit {
+                sha: sha.clone(),
+                subject: subject.into(),
+                timestamp,
+                files,
+            },
+        );
+        self.head_commits.push(sha.clone());
+        sha
+    }
+
+    fn stash_entry(&self, index: usize) -> Result<(&StashEntry, &FakeStash)> {
+        let entry = self
+            .stash_entries
+            .iter()
+            .find(|entry| entry.index == index)
+            .with_context(|| format!("no stash entry at index {index}"))?;
+        let stash = self
+            .stashes
+            .get(&entry.sha)
+            .with_context(|| format!("no changes saved for stash entry {index}"))?;
+        Ok((entry, stash))
+    }
+
+    fn drop_stash_entry(&mut self, index: usize) -> Result<()> {
+        let position = self
+            .stash_entries
+            .iter()
+            .position(|entry| entry.index == index)
+            .with_context(|| format!("no stash entry at index {index}"))?;
+        let entry = self.stash_entries.remove(position);
+        self.stashes.remove(&entry.sha);
+        for entry in &mut self.stash_entries[position..] {
+            entry.index -= 1;
+        }
+        Ok(())
+    }
+
+    fn apply_stash_entry(
+        &mut self,
+        index: usize,
+        working_copy: &mut FakeWorkingCopy,
+    ) -> Result<()> {
+        let (_, stash) = self.stash_entry(index)?;
+        let stash = stash.clone();
+        for file in &stash.files {
+            if working_copy.get(&file.path) != self.head_contents.get(&file.path) {
+                return Err(anyhow!(
+                    "your local changes to {:?} would be overwritten",
+                    file.path.0
+                ));
+            }
+        }
+        for file in stash.files {
+            if let Some(index_text) = stash.index_contents.get(&file.path) {
+                set_text(&mut self.index_contents, &file.path, index_text.clone());
+            }
+            working_copy.write(file.path, file.new_text);
+        }
+        Ok(())
+    }
+
+    /// Applies the todo entries of an interactive rebase to the commits of HEAD, stopping
+    /// at the first `edit` entry.
+    fn apply_rebase_entries(
+        &mut self,
+        entries: Vec<RebaseTodoEntry>,
+        onto: SharedString,
+        original_head_commits: Vec<String>,
+        total: usize,
+    ) -> Result<()> {
+        let mut entries = entries.into_iter();
+        while let Some(entry) = entries.next() {
+            let commit = self
+                .commits
+                .get(entry.sha.as_ref())
+                .with_context(|| format!("unknown commit {}", entry.sha))?
+                .clone();
+            match entry.action {
+                RebaseAction::Pick | RebaseAction::Edit => self.head_commits.push(commit.sha),
+                RebaseAction::Reword => {
+                    let message = entry.message.unwrap_or(commit.subject);
+                    let (sha, timestamp) = self.next_sha_and_timestamp();
+                    self.commits.insert(
+                        sha.clone(),
+                        FakeCommit {
+                            sha: sha.clone(),
+                            subject: message.lines().next().unwrap_or_default().to_string(),
+                            timestamp,
+                            files: commit.files,
+                        },
+                    );
+                    self.head_commits.push(sha);
+                }
+                RebaseAction::Squash | RebaseAction::Fixup => {
+                    let previous_sha = self
+                        .head_commits
+                        .pop()
+                        .context("cannot squash without a previous commit")?;
+                    let mut previous = self.commits[&previous_sha].clone();
+                    for file in commit.files {
+                        match previous.files.iter_mut().find(|f| f.path == file.path) {
+                            Some(previous_file) => previous_file.new_text = file.new_text,
+                            None => previous.files.push(file),
+                        }
+                    }
+                    let (sha, timestamp) = self.next_sha_and_timestamp();
+                    previous.sha = sha.clone();
+                    previous.timestamp = timestamp;
+                    self.commits.insert(sha.clone(), previous);
+                    self.head_commits.push(sha);
+                }
+                RebaseAction::Drop => {
+                    for file in commit.files {
+                        set_text(&mut self.head_contents, &file.path, file.old_text.clone());
+                        set_text(&mut self.index_contents, &file.path, file.old_text);
+                    }
+                }
+            }
+
+            if entry.action == RebaseAction::Edit {
+                let remaining_entries = entries.collect::<Vec<_>>();
+                self.rebase = Some(FakeRebase {
+                    state: RebaseState {
+                        branch: self.current_branch_name.clone().map(Into::into),
+                        onto,
+                        step: total - remaining_entries.len(),
+                        total,
+                    },
+                    remaining_entries,
+                    original_head_commits,
+                });
+                return Ok(());
+            }
+        }
+        self.rebase = None;
+        Ok(())
+    }
+
+    /// Applies the changes of the given commits on top of HEAD, or reverts them, stopping at
+    /// the first commit that changes a file with staged changes.
+    fn apply_sequencer_commits(
+        &mut self,
+        command: SequencerCommand,
+        commits: Vec<String>,
+        no_commit: bool,
+        working_copy: &mut FakeWorkingCopy,
+    ) -> Result<()> {
+        let original_head_contents = self.head_contents.clone();
+        let original_index_contents = self.index_contents.clone();
+        let original_head_commits = self.head_commits.clone();
+        let mut commits = commits.into_iter();
+        while let Some(sha) = commits.next() {
+            let commit = self
+                .commits
+                .get(&sha)
+                .with_context(|| format!("unknown commit {sha}"))?
+                .clone();
+            let files = sequencer_files(command, &commit);
+
+            let conflicted_paths = files
+                .iter()
+                .filter(|file| {
+                    self.index_contents.get(&file.path) != self.head_contents.get(&file.path)
+                })
+                .map(|file| file.path.clone())
+                .collect::<Vec<_>>();
+            if !conflicted_paths.is_empty() {
+                for path in conflicted_paths {
+                    self.unmerged_paths.insert(
+                        path,
+                        UnmergedStatus {
+                            first_head: UnmergedStatusCode::Updated,
+                            second_head: UnmergedStatusCode::Updated,
+                        },
+                    );
+                }
+                self.sequencer = Some(FakeSequencer {
+                    command,
+                    current: sha.clone(),
+                    remaining: commits.collect(),
+                    no_commit,
+                    original_head_contents,
+                    original_index_contents,
+                    original_head_commits,
+                });
+                return Err(anyhow!("could not apply {sha}"));
+            }
+
+            self.apply_sequencer_commit(command, &commit, files, no_commit, working_copy);
+        }
+        self.sequencer = None;
+        Ok(())
+    }
+
+    fn apply_sequencer_commit(
+        &mut self,
+        command: SequencerCommand,
+        commit: &FakeCommit,
+        files: Vec<FakeCommitFile>,
+        no_commit: bool,
+        working_copy: &mut FakeWorkingCopy,
+    ) {
+        for file in &files {
+            working_copy.write(file.path.clone(), file.new_text.clone());
+        }
+        if no_commit {
+            for file in files {
+                set_text(&mut self.index_contents, &file.path, file.new_text);
+            }
+        } else {
+            let subject = match command {
+                SequencerCommand::CherryPick => commit.subject.clone(),
+                SequencerCommand::Revert => format!("Revert \"{}\"", commit.subject),
+            };
+            self.commit(subject, files);
+        }
+    }
+}
+
+/// Returns the changes that cherry-picking or reverting a commit makes.
+fn sequencer_files(command: SequencerCommand, commit: &FakeCommit) -> Vec<FakeCommitFile> {
+    match command {
+        SequencerCommand::CherryPick => commit.files.clone(),
+        SequencerCommand::Revert => commit
+            .files
+            .iter()
+            .map(|file| FakeCommitFile {
+                path: file.path.clone(),
+                old_text: file.new_text.clone(),
+                new_text: file.old_text.clone(),
+            })
+            .collect(),
+    }
+}
+
+fn set_text(contents: &mut HashMap<RepoPath, String>, path: &RepoPath, text: Option<String>) {
+    match text {
+        Some(text) => {
+            contents.insert(path.clone(), text);
+        }
+        None => {
+            contents.remove(path);
         }
     }
 }
 
+/// The files in the working copy of a fake repository, and the changes an operation makes to
+/// them.
+struct FakeWorkingCopy {
+    contents: HashMap<RepoPath, String>,
+    writes: Vec<(RepoPath, Option<String>)>,
+}
+
+impl FakeWorkingCopy {
+    fn load(fs: &FakeFs, work_directory: &Path) -> Self {
+        let contents = fs
+            .files_with_contents(work_directory)
+            .into_iter()
+            .filter_map(|(path, content)| {
+                let repo_path = path.strip_prefix(work_directory).ok()?;
+                if repo_path.starts_with(".git") {
+                    return None;
+                }
+                Some((repo_path.into(), String::from_utf8(content).ok()?))
+            })
+            .collect();
+        Self {
+            contents,
+            writes: Vec::new(),
+        }
+    }
+
+    fn get(&self, path: &RepoPath) -> Option<&String> {
+        self.contents.get(path)
+    }
+
+    fn write(&mut self, path: RepoPath, text: Option<String>) {
+        set_text(&mut self.contents, &path, text.clone());
+        self.writes.push((path, text));
+    }
+}
+
 impl FakeGitRepository {
     fn with_state<F, T>(&self, f: F) -> T
     where
@@ -76,6 +457,52 @@ impl FakeGitRepository {
         }
         .boxed()
     }
+
+    /// Updates the state of the repository along with the files in its working copy.
+    ///
+    /// The changes made to the working copy are written even when `f` fails, like git does
+    /// when an operation stops with a conflict.
+    fn with_state_and_working_copy_async<F>(&self, f: F) -> BoxFuture<'static, Result<()>>
+    where
+        F: 'static + Send + FnOnce(&mut FakeGitRepositoryState, &mut FakeWorkingCopy) -> Result<()>,
+    {
+        let fs = self.fs.clone();
+        let executor = self.executor.clone();
+        let dot_git_path = self.dot_git_path.clone();
+        async move {
+            executor.simulate_random_delay().await;
+            let work_directory = dot_git_path
+                .parent()
+                .context("no working directory")?
+                .to_path_buf();
+            let mut working_copy = FakeWorkingCopy::load(&fs, &work_directory);
+            let result =
+                fs.with_git_state(&dot_git_path, true, |state| f(state, &mut working_copy))?;
+            for (path, text) in working_copy.writes {
+                let abs_path = work_directory.join(&path.0);
+                match text {
+                    Some(text) => {
+                        if let Some(parent) = abs_path.parent() {
+                            fs.create_dir(parent).await?;
+                        }
+                        fs.write_file_internal(&abs_path, text.into_bytes(), false)?;
+                    }
+                    None => {
+                        fs.remove_file(
+                            &abs_path,
+                            RemoveOptions {
+                                ignore_if_not_exists: true,
+                                ..Default::default()
+                            },
+                        )
+                        .await?;
+                    }
+                }
+            }
+            result
+        }
+        .boxed()
+    }
 }
 
 impl GitRepository for FakeGitRepository {
@@ -111,12 +538,491 @@ impl GitRepository for FakeGitRepository {
         .boxed()
     }
 
-    fn load_commit(
+    fn load_commit(&self, commit: String, _cx: AsyncApp) -> BoxFuture<Result<CommitDiff>> {
+        self.with_state_async(false, move |state| {
+            let commit = state
+                .commits
+                .get(&commit)
+                .with_context(|| format!("unknown commit {commit}"))?;
+            Ok(commit_diff(&commit.files))
+        })
+    }
+
+    fn stash_push(
         &self,
-        _commit: String,
+        message: Option<String>,
+        mode: StashMode,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        self.with_state_and_working_copy_async(move |state, working_copy| {
+            let mut paths = state
+                .head_contents
+                .keys()
+                .chain(state.index_contents.keys())
+                .chain(working_copy.contents.keys())
+                .cloned()
+                .collect::<HashSet<_>>()
+                .into_iter()
+                .collect::<Vec<_>>();
+            paths.sort();
+
+            let mut stash = FakeStash {
+                files: Vec::new(),
+                index_contents: HashMap::default(),
+            };
+            for path in paths {
+                let head_text = state.head_contents.get(&path).cloned();
+                let index_text = state.index_contents.get(&path).cloned();
+                let working_copy_text = working_copy.get(&path).cloned();
+                let is_stashed = match &mode {
+                    StashMode::All => head_text != index_text || index_text != working_copy_text,
+                    StashMode::Staged => head_text != index_text,
+                    StashMode::Paths(paths) => {
+                        paths.iter().any(|prefix| path.starts_with(prefix))
+                            && (head_text != index_text || index_text != working_copy_text)
+                    }
+                };
+                if !is_stashed {
+                    continue;
+                }
+
+                stash.index_contents.insert(path.clone(), index_text);
+                set_text(&mut state.index_contents, &path, head_text.clone());
+                working_copy.write(path.clone(), head_text.clone());
+                stash.files.push(FakeCommitFile {
+                    path,
+                    old_text: head_text,
+                    new_text: working_copy_text,
+                });
+            }
+            if stash.files.is_empty() {
+                return Err(anyhow!("no local changes to save"));
+            }
+
+            let (sha, timestamp) = state.next_sha_and_timestamp();
+            let sha = SharedString::from(sha);
+            let message = message.unwrap_or_else(|| {
+                format!(
+                    "WIP on {}",
+                    state
+                        .current_branch_name
+                        .as_deref()
+                        .unwrap_or("(no branch)")
+                )
+            });
+            for entry in &mut state.stash_entries {
+                entry.index += 1;
+            }
+            state.stash_entries.insert(
+                0,
+                StashEntry {
+                    index: 0,
+                    sha: sha.clone(),
+                    message: message.into(),
+                    timestamp,
+                },
+            );
+            state.stashes.insert(sha, stash);
+            Ok(())
+        })
+    }
+
+    fn stash_list(&self) -> BoxFuture<Result<Vec<StashEntry>>> {
+        self.with_state_async(false, |state| Ok(state.stash_entries.clone()))
+    }
+
+    fn stash_apply(
+        &self,
+        index: usize,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        self.with_state_and_working_copy_async(move |state, working_copy| {
+            state.apply_stash_entry(index, working_copy)
+        })
+    }
+
+    fn stash_pop(&self, index: usize, _env: Arc<HashMap<String, String>>) -> BoxFuture<Result<()>> {
+        self.with_state_and_working_copy_async(move |state, working_copy| {
+            state.apply_stash_entry(index, working_copy)?;
+            state.drop_stash_entry(index)
+        })
+    }
+
+    fn stash_drop(
+        &self,
+        index: usize,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        self.with_state_async(true, move |state| state.drop_stash_entry(index))
+    }
+
+    fn stash_show(&self, index: usize, _cx: AsyncApp) -> BoxFuture<Result<CommitDiff>> {
+        self.with_state_async(false, move |state| {
+            let (_, stash) = state.stash_entry(index)?;
+            Ok(commit_diff(&stash.files))
+        })
+    }
+
+    fn log(
+        &self,
+        filter: LogFilter,
+        skip: usize,
+        limit: usize,
+    ) -> BoxFuture<Result<Vec<LogEntry>>> {
+        self.with_state_async(false, move |state| {
+            let path = match &filter {
+                LogFilter::Head => None,
+                LogFilter::Path { path, .. } | LogFilter::Lines { path, .. } => Some(path),
+            };
+            let mut parent_shas = HashMap::default();
+            for pair in state.head_commits.windows(2) {
+                parent_shas.insert(&pair[1], &pair[0]);
+            }
+            Ok(state
+                .head_commits
+                .iter()
+                .rev()
+                .map(|sha| &state.commits[sha])
+                .filter(|commit| {
+                    path.is_none_or(|path| {
+                        commit.files.iter().any(|file| file.path.starts_with(path))
+                    })
+                })
+                .skip(skip)
+                .take(limit)
+                .map(|commit| {
+                    let mut refs = Vec::new();
+                    if state.head_sha() == Some(&commit.sha) {
+                        refs.push(match &state.current_branch_name {
+                            Some(branch) => format!("HEAD -> {branch}").into(),
+                            None => "HEAD".into(),
+                        });
+                    }
+                    refs.extend(
+                        state
+                            .tags
+                            .iter()
+                            .filter(|tag| tag.sha.as_ref() == commit.sha)
+                            .map(|tag| format!("tag: {}", tag.name).into()),
+                    );
+                    LogEntry {
+                        sha: commit.sha.clone().into(),
+                        parent_shas: parent_shas
+                            .get(&commit.sha)
+                            .map(|sha| SharedString::from((*sha).clone()))
+                            .into_iter()
+                            .collect(),
+                        author_name: "Fake Author".into(),
+                        author_email: "fake@example.com".into(),
+                        commit_timestamp: commit.timestamp,
+                        refs,
+                        subject: commit.subject.clone().into(),
+                    }
+                })
+                .collect())
+        })
+    }
+
+    fn rebase_state(&self) -> BoxFuture<Option<RebaseState>> {
+        let state =
+            self.with_state(|state| state.rebase.as_ref().map(|rebase| rebase.state.clone()));
+        async move { state }.boxed()
+    }
+
+    fn rebase_interactive(
+        &self,
+        base: Option<String>,
+        entries: Vec<RebaseTodoEntry>,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        self.with_state_async(true, move |state| {
+            if state.rebase.is_some() {
+                return Err(anyhow!("a rebase is already in progress"));
+            }
+            let base_position = match &base {
+                Some(base) => {
+                    state
+                        .head_commits
+                        .iter()
+                        .position(|sha| sha == base)
+                        .with_context(|| format!("{base} is not an ancestor of HEAD"))?
+                        + 1
+                }
+                None => 0,
+            };
+            for entry in &entries {
+                if !state.head_commits[base_position..]
+                    .iter()
+                    .any(|sha| sha.as_str() == entry.sha.as_ref())
+                {
+                    return Err(anyhow!("{} is not a commit being rebased", entry.sha));
+                }
+            }
+
+            let original_head_commits = state.head_commits.clone();
+            state.head_commits.truncate(base_position);
+            let onto = base.unwrap_or_default().into();
+            let total = entries.len();
+            state.apply_rebase_entries(entries, onto, original_head_commits, total)
+        })
+    }
+
+    fn rebase_operation(
+        &self,
+        operation: RebaseOperation,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        self.with_state_async(true, move |state| {
+            let rebase = state.rebase.take().context("no rebase in progress")?;
+            match operation {
+                RebaseOperation::Continue | RebaseOperation::Skip => state.apply_rebase_entries(
+                    rebase.remaining_entries,
+                    rebase.state.onto,
+                    rebase.original_head_commits,
+                    rebase.state.total,
+                ),
+                RebaseOperation::Abort => {
+                    state.head_commits = rebase.original_head_commits;
+                    Ok(())
+                }
+            }
+        })
+    }
+
+    fn sequencer_command(&self) -> BoxFuture<Option<SequencerCommand>> {
+        let command =
+            self.with_state(|state| state.sequencer.as_ref().map(|sequencer| sequencer.command));
+        async move { command }.boxed()
+    }
+
+    fn cherry_pick(
+        &self,
+        commits: Vec<String>,
+        no_commit: bool,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        self.with_state_and_working_copy_async(move |state, working_copy| {
+            state.apply_sequencer_commits(
+                SequencerCommand::CherryPick,
+                commits,
+                no_commit,
+                working_copy,
+            )
+        })
+    }
+
+    fn revert(
+        &self,
+        commits: Vec<String>,
+        no_commit: bool,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        self.with_state_and_working_copy_async(move |state, working_copy| {
+            state.apply_sequencer_commits(
+                SequencerCommand::Revert,
+                commits,
+                no_commit,
+                working_copy,
+            )
+        })
+    }
+
+    fn sequencer_operation(
+        &self,
+        command: SequencerCommand,
+        operation: RebaseOperation,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        self.with_state_and_working_copy_async(move |state, working_copy| {
+            if state.sequencer.as_ref().map(|sequencer| sequencer.command) != Some(command) {
+                return Err(anyhow!("no {command:?} in progress"));
+            }
+            let sequencer = state.sequencer.take().unwrap();
+            let commit = state.commits[&sequencer.current].clone();
+            let files = sequencer_files(command, &commit);
+            for file in &files {
+                state.unmerged_paths.remove(&file.path);
+            }
+            match operation {
+                RebaseOperation::Continue => {
+                    // The conflicts are resolved with the staged contents of the files.
+                    let files = files
+                        .into_iter()
+                        .map(|file| FakeCommitFile {
+                            new_text: state.index_contents.get(&file.path).cloned(),
+                            ..file
+                        })
+                        .collect();
+                    state.apply_sequencer_commit(
+                        command,
+                        &commit,
+                        files,
+                        sequencer.no_commit,
+                        working_copy,
+                    );
+                }
+                RebaseOperation::Skip => {}
+                RebaseOperation::Abort => {
+                    for path in state
+                        .index_contents
+                        .keys()
+                        .chain(sequencer.original_index_contents.keys())
+                        .cloned()
+                        .collect::<HashSet<_>>()
+                    {
+                        let original_text = sequencer.original_index_contents.get(&path);
+                        if state.index_contents.get(&path) != original_text {
+                            working_copy.write(path, original_text.cloned());
+                        }
+                    }
+                    state.head_contents = sequencer.original_head_contents;
+                    state.index_contents = sequencer.original_index_contents;
+                    state.head_commits = sequencer.original_head_commits;
+                    return Ok(());
+                }
+            }
+            state.apply_sequencer_commits(
+                command,
+                sequencer.remaining,
+                sequencer.no_commit,
+                working_copy,
+            )
+        })
+    }
+
+    fn submodules(&self) -> BoxFuture<Result<Vec<Submodule>>> {
+        self.with_state_async(false, |state| Ok(state.submodules.clone()))
+    }
+
+    fn update_submodules(
+        &self,
+        paths: Vec<RepoPath>,
+        _askpass: AskPassDelegate,
+        _env: Arc<HashMap<String, String>>,
         _cx: AsyncApp,
-    ) -> BoxFuture<Result<git::repository::CommitDiff>> {
-        unimplemented!()
+    ) -> BoxFuture<Result<RemoteCommandOutput>> {
+        self.with_state_async(true, move |state| {
+            let mut stdout = String::new();
+            for submodule in &mut state.submodules {
+                if !paths.is_empty() && !paths.contains(&submodule.path) {
+                    continue;
+                }
+                if submodule.workdir_sha != submodule.head_sha {
+                    submodule.workdir_sha = submodule.head_sha.clone();
+                    stdout.push_str(&format!(
+                        "Submodule path '{}': checked out '{}'\n",
+                        submodule.path.display(),
+                        submodule.head_sha.as_deref().unwrap_or_default()
+                    ));
+                }
+                submodule.status.uninitialized = false;
+                submodule.status.new_commits = false;
+            }
+            Ok(RemoteCommandOutput {
+                stdout,
+                stderr: String::new(),
+            })
+        })
+    }
+
+    fn worktrees(&self) -> BoxFuture<Result<Vec<GitWorktree>>> {
+        self.with_state_async(false, |state| {
+            let main_worktree = GitWorktree {
+                path: state
+                    .path
+                    .parent()
+                    .context("no working directory")?
+                    .to_path_buf(),
+                sha: state.head_sha().cloned().map(Into::into),
+                branch: state.current_branch_name.clone().map(Into::into),
+                is_main: true,
+                is_locked: false,
+            };
+            Ok(std::iter::once(main_worktree)
+                .chain(state.worktrees.iter().cloned())
+                .collect())
+        })
+    }
+
+    fn create_worktree(
+        &self,
+        path: PathBuf,
+        branch: String,
+        create_branch: bool,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        let fs = self.fs.clone();
+        let update_state = self.with_state_async(true, {
+            let path = path.clone();
+            move |state| {
+                if state.worktrees.iter().any(|worktree| worktree.path == path) {
+                    return Err(anyhow!("{path:?} is already a worktree"));
+                }
+                if create_branch {
+                    if !state.branches.insert(branch.clone()) {
+                        return Err(anyhow!("a branch named '{branch}' already exists"));
+                    }
+                } else if !state.branches.contains(&branch) {
+                    return Err(anyhow!("invalid reference: {branch}"));
+                } else if state.current_branch_name.as_ref() == Some(&branch)
+                    || state
+                        .worktrees
+                        .iter()
+                        .any(|worktree| worktree.branch.as_deref() == Some(branch.as_str()))
+                {
+                    return Err(anyhow!("'{branch}' is already checked out"));
+                }
+                state.worktrees.push(GitWorktree {
+                    path,
+                    sha: state.head_sha().cloned().map(Into::into),
+                    branch: Some(branch.into()),
+                    is_main: false,
+                    is_locked: false,
+                });
+                Ok(())
+            }
+        });
+        async move {
+            update_state.await?;
+            fs.create_dir(&path).await
+        }
+        .boxed()
+    }
+
+    fn remove_worktree(
+        &self,
+        path: PathBuf,
+        force: bool,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        let fs = self.fs.clone();
+        let update_state = self.with_state_async(true, {
+            let path = path.clone();
+            move |state| {
+                let position = state
+                    .worktrees
+                    .iter()
+                    .position(|worktree| worktree.path == path)
+                    .with_context(|| format!("{path:?} is not a linked worktree"))?;
+                if state.worktrees[position].is_locked && !force {
+                    return Err(anyhow!("cannot remove a locked worktree"));
+                }
+                state.worktrees.remove(position);
+                Ok(())
+            }
+        });
+        async move {
+            update_state.await?;
+            fs.remove_dir(
+                &path,
+                RemoveOptions {
+                    recursive: true,
+                    ignore_if_not_exists: true,
+                },
+            )
+            .await
+        }
+        .boxed()
     }
 
     fn set_index_text(
@@ -142,7 +1048,7 @@ impl GitRepository for FakeGitRepository {
     }
 
     fn head_sha(&self) -> Option<String> {
-        None
+        self.with_state(|state| state.head_sha().cloned())
     }
 
     fn merge_head_shas(&self) -> Vec<String> {
@@ -343,6 +1249,111 @@ impl GitRepository for FakeGitRepository {
         })
     }
 
+    fn detached_head(&self) -> BoxFuture<Option<DetachedHead>> {
+        let detached_head = self.with_state(|state| state.detached_head.clone());
+        async move { detached_head }.boxed()
+    }
+
+    fn tags(&self) -> BoxFuture<Result<Vec<Tag>>> {
+        self.with_state_async(false, |state| Ok(state.tags.clone()))
+    }
+
+    fn create_tag(
+        &self,
+        name: String,
+        target: Option<String>,
+        message: Option<String>,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        self.with_state_async(true, move |state| {
+            if state.tags.iter().any(|tag| tag.name.as_ref() == name) {
+                return Err(anyhow!("tag '{name}' already exists"));
+            }
+            let sha = match target {
+                Some(target) => state
+                    .commits
+                    .get(&target)
+                    .map(|commit| commit.sha.clone())
+                    .with_context(|| format!("unknown commit {target}"))?,
+                None => state.head_sha().cloned().context("no commit on HEAD")?,
+            };
+            let timestamp = match &message {
+                Some(_) => state.next_sha_and_timestamp().1,
+                None => state.commits[&sha].timestamp,
+            };
+            state.tags.push(Tag {
+                name: name.into(),
+                sha: sha.into(),
+                message: message.map(|message| {
+                    message
+                        .lines()
+                        .next()
+                        .unwrap_or_default()
+                        .to_string()
+                        .into()
+                }),
+                timestamp,
+            });
+            state.tags.sort_by(|a, b| a.name.cmp(&b.name));
+            Ok(())
+        })
+    }
+
+    fn delete_tag(
+        &self,
+        name: String,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        self.with_state_async(true, move |state| {
+            let position = state
+                .tags
+                .iter()
+                .position(|tag| tag.name.as_ref() == name)
+                .with_context(|| format!("tag '{name}' not found"))?;
+            state.tags.remove(position);
+            Ok(())
+        })
+    }
+
+    fn checkout_tag(
+        &self,
+        name: String,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        self.with_state_async(true, move |state| {
+            let tag = state
+                .tags
+                .iter()
+                .find(|tag| tag.name.as_ref() == name)
+                .with_context(|| format!("tag '{name}' not found"))?;
+            state.detached_head = Some(DetachedHead {
+                sha: tag.sha.clone(),
+                tag: Some(tag.name.clone()),
+            });
+            state.current_branch_name = None;
+            Ok(())
+        })
+    }
+
+    fn push_tag(
+        &self,
+        name: String,
+        remote: String,
+        _askpass: AskPassDelegate,
+        _env: Arc<HashMap<String, String>>,
+        _cx: AsyncApp,
+    ) -> BoxFuture<Result<RemoteCommandOutput>> {
+        self.with_state_async(false, move |state| {
+            if !state.tags.iter().any(|tag| tag.name.as_ref() == name) {
+                return Err(anyhow!("src refspec {name} does not match any"));
+            }
+            Ok(RemoteCommandOutput {
+                stdout: String::new(),
+                stderr: format!("To {remote}\n * [new tag]         {name} -> {name}\n"),
+            })
+        })
+    }
+
     fn blame(&self, path: RepoPath, _content: Rope) -> BoxFuture<Result<git::blame::Blame>> {
         self.with_state_async(false, move |state| {
             state
@@ -373,6 +1384,7 @@ impl GitRepository for FakeGitRepository {
         &self,
         _message: gpui::SharedString,
         _name_and_email: Option<(gpui::SharedString, gpui::SharedString)>,
+        _options: CommitOptions,
         _env: Arc<HashMap<String, String>>,
     ) -> BoxFuture<Result<()>> {
         unimplemented!()
@@ -410,12 +1422,37 @@ impl GitRepository for FakeGitRepository {
         unimplemented!()
     }
 
+    fn fetch_ref(
+        &self,
+        remote_name: String,
+        remote_ref: String,
+        branch_name: String,
+        _askpass: AskPassDelegate,
+        _env: Arc<HashMap<String, String>>,
+        _cx: AsyncApp,
+    ) -> BoxFuture<Result<RemoteCommandOutput>> {
+        self.with_state_async(true, move |state| {
+            if state.current_branch_name.as_ref() == Some(&branch_name) {
+                return Err(anyhow!(
+                    "refusing to fetch into branch '{branch_name}' checked out"
+                ));
+            }
+            state.branches.insert(branch_name.clone());
+            Ok(RemoteCommandOutput {
+                stdout: String::new(),
+                stderr: format!(
+                    "From {remote_name}\n * [new ref]         {remote_ref} -> {branch_name}\n"
+                ),
+            })
+        })
+    }
+
     fn get_remotes(&self, _branch: Option<String>) -> BoxFuture<Result<Vec<Remote>>> {
         unimplemented!()
     }
 
     fn check_for_pushed_commit(&self) -> BoxFuture<Result<Vec<gpui::SharedString>>> {
-        future::ready(Ok(Vec::new())).boxed()
+        async { Ok(Vec::new()) }.boxed()
     }
 
     fn diff(&self, _diff: git::repository::DiffType) -> BoxFuture<Result<String>> {
@@ -450,3 +1487,16 @@ impl GitRepository for FakeGitRepository {
         unimplemented!()
     }
 }
+
+fn commit_diff(files: &[FakeCommitFile]) -> CommitDiff {
+    CommitDiff {
+        files: files
+            .iter()
+            .map(|file| CommitFile {
+                path: file.path.clone(),
+                old_text: file.old_text.clone(),
+                new_text: file.new_text.clone(),
+            })
+            .collect(),
+    }
+}
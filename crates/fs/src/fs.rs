@@ -1411,6 +1411,28 @@ impl FakeFs {
         .unwrap();
     }
 
+    /// Commits the given contents on top of the repository's HEAD, deleting the paths whose
+    /// contents are `None`, and returns the sha of the new commit.
+    pub fn commit_for_repo(
+        &self,
+        dot_git: &Path,
+        subject: &str,
+        contents_by_path: &[(RepoPath, Option<String>)],
+    ) -> String {
+        self.with_git_state(dot_git, true, |state| {
+            let files = contents_by_path
+                .iter()
+                .map(|(path, content)| fake_git_repo::FakeCommitFile {
+                    path: path.clone(),
+                    old_text: state.head_contents.get(path).cloned(),
+                    new_text: content.clone(),
+                })
+                .collect();
+            state.commit(subject, files)
+        })
+        .unwrap()
+    }
+
     pub fn set_blame_for_repo(&self, dot_git: &Path, blames: Vec<(RepoPath, git::blame::Blame)>) {
         self.with_git_state(dot_git, true, |state| {
             state.blames.clear();
@@ -1,6 +1,7 @@
 pub mod blame;
 pub mod commit;
 mod hosting_provider;
+pub mod rebase;
 mod remote;
 pub mod repository;
 pub mod status;
@@ -12,11 +13,13 @@ pub use git2 as libgit;
 use gpui::action_with_deprecated_aliases;
 use gpui::actions;
 use gpui::impl_action_with_deprecated_aliases;
+use gpui::impl_actions;
 pub use repository::WORK_DIRECTORY_REPO_PATH;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::ffi::OsStr;
 use std::fmt;
+use std::path::PathBuf;
 use std::str::FromStr;
 use std::sync::LazyLock;
 
@@ -36,26 +39,64 @@ actions!(
         ToggleStaged,
         StageAndNext,
         UnstageAndNext,
+        // per-conflict
+        AcceptOurs,
+        AcceptTheirs,
+        AcceptBoth,
+        GoToConflict,
+        GoToPreviousConflict,
         // per-file
         StageFile,
         UnstageFile,
+        StashFile,
+        LineHistory,
+        UpdateSubmodule,
+        OpenSubmoduleDiff,
         // repo-wide
         StageAll,
         UnstageAll,
         RestoreTrackedFiles,
         TrashUntrackedFiles,
+        StashAll,
+        StashStaged,
+        StashPop,
         Uncommit,
         Push,
         ForcePush,
         Pull,
         Fetch,
         Commit,
+        ToggleAmend,
+        Fixup,
+        ToggleSignoff,
+        ToggleGpgSign,
         ExpandCommitEditor,
         GenerateCommitMessage,
         Init,
+        ViewHistory,
+        InteractiveRebase,
+        RebaseContinue,
+        RebaseSkip,
+        RebaseAbort,
+        ManageWorktrees,
+        UpdateSubmodules,
+        ViewPullRequests,
+        SetHostingProviderToken,
+        ShowReviewComments,
+        HideReviewComments,
     ]
 );
 
+/// Opens the commit history of a file or directory.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, JsonSchema)]
+pub struct FileHistory {
+    /// The absolute path to show the history of. Defaults to the file of the active editor.
+    #[serde(default)]
+    pub path: Option<PathBuf>,
+}
+
+impl_actions!(git, [FileHistory]);
+
 #[derive(Clone, Debug, Default, PartialEq, Deserialize, JsonSchema)]
 pub struct RestoreFile {
     #[serde(default)]
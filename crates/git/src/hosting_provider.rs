@@ -15,6 +15,64 @@ pub struct PullRequest {
     pub url: Url,
 }
 
+/// A pull request (or merge request), as reported by the API of a Git hosting provider.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct PullRequestDetails {
+    pub number: u32,
+    pub title: SharedString,
+    pub url: Url,
+    pub author: Option<SharedString>,
+    /// The name of the branch the pull request merges from.
+    pub head_branch: SharedString,
+    /// The name of the branch the pull request merges into.
+    pub base_branch: SharedString,
+    pub head_sha: Option<SharedString>,
+    pub is_draft: bool,
+}
+
+/// The combined status of the CI checks of a commit.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum CiStatus {
+    Pending,
+    Success,
+    Failure,
+}
+
+/// A review comment left on a line of a pull request.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ReviewComment {
+    pub id: u64,
+    pub author: Option<SharedString>,
+    pub body: SharedString,
+    /// The path of the commented file, relative to the root of the repository.
+    pub path: String,
+    /// The 1-based line the comment is attached to, if it's still part of the diff.
+    pub line: Option<u32>,
+    pub url: Option<Url>,
+}
+
+/// A client for the API of a Git hosting provider.
+#[async_trait]
+pub trait GitHostingProviderApi: Send + Sync {
+    /// Returns the open pull requests of the repository, optionally only those merging from
+    /// the given branch.
+    async fn pull_requests(
+        &self,
+        remote: &ParsedGitRemote,
+        head_branch: Option<&str>,
+    ) -> Result<Vec<PullRequestDetails>>;
+
+    /// Returns the combined status of the CI checks of the given commit, if it has any.
+    async fn ci_status(&self, remote: &ParsedGitRemote, sha: &str) -> Result<Option<CiStatus>>;
+
+    /// Returns the review comments of the given pull request.
+    async fn review_comments(
+        &self,
+        remote: &ParsedGitRemote,
+        number: u32,
+    ) -> Result<Vec<ReviewComment>>;
+}
+
 #[derive(Clone)]
 pub struct GitRemote {
     pub host: Arc<dyn GitHostingProvider + Send + Sync + 'static>,
@@ -121,6 +179,22 @@ pub trait GitHostingProvider {
     ) -> Result<Option<Url>> {
         Ok(None)
     }
+
+    /// Returns a client for the API of this provider, if it has one.
+    ///
+    /// The token, if any, is used to authenticate the requests.
+    fn api_client(
+        &self,
+        _http_client: Arc<dyn HttpClient>,
+        _token: Option<String>,
+    ) -> Option<Arc<dyn GitHostingProviderApi>> {
+        None
+    }
+
+    /// Returns the ref under which the remote exposes the head of the given pull request.
+    fn pull_request_ref(&self, _number: u32) -> Option<String> {
+        None
+    }
 }
 
 #[derive(Default, Deref, DerefMut)]
@@ -0,0 +1,315 @@
+use anyhow::{Result, anyhow};
+use gpui::SharedString;
+use std::path::{Path, PathBuf};
+
+/// What to do with a commit during an interactive rebase.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RebaseAction {
+    Pick,
+    Reword,
+    Edit,
+    Squash,
+    Fixup,
+    Drop,
+}
+
+impl RebaseAction {
+    pub const ALL: [RebaseAction; 6] = [
+        RebaseAction::Pick,
+        RebaseAction::Reword,
+        RebaseAction::Edit,
+        RebaseAction::Squash,
+        RebaseAction::Fixup,
+        RebaseAction::Drop,
+    ];
+
+    /// The command used for this action in a rebase todo list.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RebaseAction::Pick => "pick",
+            RebaseAction::Reword => "reword",
+            RebaseAction::Edit => "edit",
+            RebaseAction::Squash => "squash",
+            RebaseAction::Fixup => "fixup",
+            RebaseAction::Drop => "drop",
+        }
+    }
+
+    /// Whether the action folds the commit into the previous one.
+    pub fn is_meld(&self) -> bool {
+        matches!(self, RebaseAction::Squash | RebaseAction::Fixup)
+    }
+}
+
+/// A line of an interactive rebase todo list.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RebaseTodoEntry {
+    pub action: RebaseAction,
+    pub sha: SharedString,
+    pub subject: SharedString,
+    /// The new message of a [`RebaseAction::Reword`] entry.
+    pub message: Option<String>,
+}
+
+/// What to do with a rebase that stopped, because of a conflict or an `edit` entry.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RebaseOperation {
+    Continue,
+    Skip,
+    Abort,
+}
+
+impl RebaseOperation {
+    pub fn as_arg(&self) -> &'static str {
+        match self {
+            RebaseOperation::Continue => "--continue",
+            RebaseOperation::Skip => "--skip",
+            RebaseOperation::Abort => "--abort",
+        }
+    }
+}
+
+/// The state of a rebase that is in progress.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RebaseState {
+    /// The branch being rebased, or `None` when rebasing a detached HEAD.
+    pub branch: Option<SharedString>,
+    pub onto: SharedString,
+    /// The 1-based index of the current step of the rebase.
+    pub step: usize,
+    pub total: usize,
+}
+
+impl RebaseState {
+    /// Reads the state of the rebase in progress in the given git directory, if any.
+    pub fn load(git_dir: &Path) -> Option<Self> {
+        // Interactive and merge-based rebases keep their state in `rebase-merge`, while
+        // rebases that apply patches keep it in `rebase-apply`.
+        let (state_dir, step_file, total_file) = if git_dir.join("rebase-merge").is_dir() {
+            (git_dir.join("rebase-merge"), "msgnum", "end")
+        } else if git_dir.join("rebase-apply").join("onto").is_file() {
+            (git_dir.join("rebase-apply"), "next", "last")
+        } else {
+            return None;
+        };
+
+        let read = |name: &str| {
+            std::fs::read_to_string(state_dir.join(name))
+                .ok()
+                .map(|contents| contents.trim().to_string())
+        };
+        let branch = read("head-name")
+            .filter(|head_name| head_name != "detached HEAD")
+            .map(|head_name| {
+                head_name
+                    .strip_prefix("refs/heads/")
+                    .unwrap_or(&head_name)
+                    .to_string()
+                    .into()
+            });
+        Some(Self {
+            branch,
+            onto: read("onto").unwrap_or_default().into(),
+            step: read(step_file)
+                .and_then(|step| step.parse().ok())
+                .unwrap_or(0),
+            total: read(total_file)
+                .and_then(|total| total.parse().ok())
+                .unwrap_or(0),
+        })
+    }
+}
+
+/// A `GIT_SEQUENCE_EDITOR` that replaces the todo list git generates for `git rebase -i`
+/// with a prepared one. You must retain the session until the rebase command exits.
+pub struct SequenceEditorSession {
+    script_path: PathBuf,
+    _temp_dir: tempfile::TempDir,
+}
+
+impl SequenceEditorSession {
+    pub async fn new(entries: &[RebaseTodoEntry]) -> Result<Self> {
+        validate_todo(entries)?;
+
+        let temp_dir = tempfile::Builder::new()
+            .prefix("zed-sequence-editor")
+            .tempdir()?;
+        let mut message_paths = Vec::new();
+        for (ix, entry) in entries.iter().enumerate() {
+            if let Some(message) = &entry.message {
+                let path = temp_dir.path().join(format!("message-{ix}.txt"));
+                smol::fs::write(&path, message).await?;
+                message_paths.push((ix, path));
+            }
+        }
+        let todo = todo_contents(entries, |ix| {
+            message_paths
+                .iter()
+                .find(|(message_ix, _)| *message_ix == ix)
+                .map(|(_, path)| path.clone())
+        });
+        let todo_path = temp_dir.path().join("git-rebase-todo");
+        smol::fs::write(&todo_path, todo).await?;
+
+        // Git invokes the editor with the path of the todo list it generated.
+        let script_path = temp_dir.path().join("sequence-editor.sh");
+        let script = format!(
+            "#!/bin/sh\ncat {todo} > \"$1\"\n",
+            todo = shell_quote(&todo_path),
+        );
+        smol::fs::write(&script_path, script).await?;
+        #[cfg(unix)]
+        {
+            use smol::fs::unix::PermissionsExt as _;
+            smol::fs::set_permissions(&script_path, std::fs::Permissions::from_mode(0o755)).await?;
+        }
+
+        Ok(Self {
+            script_path,
+            _temp_dir: temp_dir,
+        })
+    }
+
+    /// The value of `GIT_SEQUENCE_EDITOR`, which git evaluates as a shell command.
+    pub fn editor_command(&self) -> String {
+        shell_quote(&self.script_path)
+    }
+}
+
+fn validate_todo(entries: &[RebaseTodoEntry]) -> Result<()> {
+    if let Some(entry) = entries.iter().find(|entry| !is_object_id(&entry.sha)) {
+        return Err(anyhow!("invalid commit {:?}", entry.sha));
+    }
+    let first_kept = entries
+        .iter()
+        .find(|entry| entry.action != RebaseAction::Drop)
+        .ok_or_else(|| anyhow!("Cannot drop every commit of a rebase"))?;
+    if first_kept.action.is_meld() {
+        return Err(anyhow!(
+            "Cannot {} {} without a previous commit",
+            first_kept.action.as_str(),
+            first_kept.sha
+        ));
+    }
+    Ok(())
+}
+
+fn is_object_id(sha: &str) -> bool {
+    (4..=64).contains(&sha.len()) && sha.bytes().all(|byte| byte.is_ascii_hexdigit())
+}
+
+/// Formats a rebase todo list. Reworded commits are picked and then amended with the message
+/// at the path returned by `message_path`, so that git doesn't need to open an editor.
+///
+/// Subjects are left out, since git ignores them and they could otherwise add lines to the list.
+pub(crate) fn todo_contents(
+    entries: &[RebaseTodoEntry],
+    message_path: impl Fn(usize) -> Option<PathBuf>,
+) -> String {
+    let mut todo = String::new();
+    for (ix, entry) in entries.iter().enumerate() {
+        let message_path = if entry.action == RebaseAction::Reword {
+            message_path(ix)
+        } else {
+            None
+        };
+        let action = if message_path.is_some() {
+            RebaseAction::Pick
+        } else {
+            entry.action
+        };
+        todo.push_str(&format!("{} {}\n", action.as_str(), entry.sha));
+        if let Some(message_path) = message_path {
+            todo.push_str(&format!(
+                "exec git commit --amend --only --no-verify --cleanup=strip -F {}\n",
+                shell_quote(&message_path)
+            ));
+        }
+    }
+    todo
+}
+
+fn shell_quote(path: &Path) -> String {
+    format!("'{}'", path.to_string_lossy().replace('\'', "'\\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(action: RebaseAction, sha: &str, subject: &str) -> RebaseTodoEntry {
+        RebaseTodoEntry {
+            action,
+            sha: sha.to_string().into(),
+            subject: subject.to_string().into(),
+            message: None,
+        }
+    }
+
+    #[test]
+    fn test_todo_contents() {
+        let entries = vec![
+            entry(RebaseAction::Pick, "aaaaaaa", "First"),
+            RebaseTodoEntry {
+                message: Some("Second, reworded".into()),
+                ..entry(RebaseAction::Reword, "bbbbbbb", "Second")
+            },
+            entry(RebaseAction::Fixup, "ccccccc", "fixup! Second"),
+            entry(RebaseAction::Drop, "ddddddd", "Fourth"),
+            entry(RebaseAction::Reword, "eeeeeee", "Fifth"),
+        ];
+        let todo = todo_contents(&entries, |ix| {
+            entries[ix]
+                .message
+                .as_ref()
+                .map(|_| PathBuf::from(format!("/tmp/it's/message-{ix}.txt")))
+        });
+        pretty_assertions::assert_eq!(
+            todo,
+            concat!(
+                "pick aaaaaaa\n",
+                "pick bbbbbbb\n",
+                "exec git commit --amend --only --no-verify --cleanup=strip -F '/tmp/it'\\''s/message-1.txt'\n",
+                "fixup ccccccc\n",
+                "drop ddddddd\n",
+                "reword eeeeeee\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_validate_todo() {
+        assert!(
+            validate_todo(&[
+                entry(RebaseAction::Drop, "aaaaaaa", "First"),
+                entry(RebaseAction::Pick, "bbbbbbb", "Second"),
+                entry(RebaseAction::Squash, "ccccccc", "Third"),
+            ])
+            .is_ok()
+        );
+        assert!(
+            validate_todo(&[
+                entry(RebaseAction::Drop, "aaaaaaa", "First"),
+                entry(RebaseAction::Fixup, "bbbbbbb", "Second"),
+            ])
+            .is_err()
+        );
+        assert!(validate_todo(&[entry(RebaseAction::Drop, "aaaaaaa", "First")]).is_err());
+        assert!(validate_todo(&[entry(RebaseAction::Pick, "HEAD", "First")]).is_err());
+        assert!(
+            validate_todo(&[entry(RebaseAction::Pick, "aaaaaaa\nexec true", "First")]).is_err()
+        );
+    }
+
+    #[test]
+    fn test_todo_contents_ignores_subject_lines() {
+        let entries = vec![entry(
+            RebaseAction::Pick,
+            "aaaaaaa",
+            "First\nexec touch /tmp/pwned\r\nexec true",
+        )];
+        let todo = todo_contents(&entries, |_| None);
+        assert_eq!(todo, "pick aaaaaaa\n");
+        assert!(validate_todo(&entries).is_ok());
+    }
+}
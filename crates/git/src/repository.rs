@@ -1,4 +1,5 @@
 use crate::commit::parse_git_diff_name_status;
+use crate::rebase::{RebaseOperation, RebaseState, RebaseTodoEntry, SequenceEditorSession};
 use crate::status::{GitStatus, StatusCode};
 use crate::{Oid, SHORT_SHA_LENGTH};
 use anyhow::{Context as _, Result, anyhow};
@@ -160,6 +161,138 @@ pub struct Remote {
     pub name: SharedString,
 }
 
+/// A tag of a repository, as listed by [`GitRepository::tags`].
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct Tag {
+    pub name: SharedString,
+    /// The commit the tag points at.
+    pub sha: SharedString,
+    /// The subject of the tag's message, or `None` for a lightweight tag.
+    pub message: Option<SharedString>,
+    /// This is a unix timestamp of when the tag was created, or of its commit for a lightweight
+    /// tag.
+    pub timestamp: i64,
+}
+
+/// The commit checked out when HEAD doesn't point at a branch.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct DetachedHead {
+    pub sha: SharedString,
+    /// A tag pointing at the commit, if any.
+    pub tag: Option<SharedString>,
+}
+
+impl DetachedHead {
+    pub fn short_sha(&self) -> SharedString {
+        self.sha[..SHORT_SHA_LENGTH.min(self.sha.len())]
+            .to_string()
+            .into()
+    }
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct StashEntry {
+    /// The position of the entry in the stash, where `0` is the most recent entry.
+    pub index: usize,
+    pub sha: SharedString,
+    pub message: SharedString,
+    /// This is a unix timestamp
+    pub timestamp: i64,
+}
+
+impl StashEntry {
+    /// Returns the revision that refers to this entry, such as `stash@{0}`.
+    pub fn reference(&self) -> String {
+        stash_reference(self.index)
+    }
+}
+
+/// The changes saved by [`GitRepository::stash_push`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StashMode {
+    /// Stash all changes, including untracked files.
+    All,
+    /// Stash only the changes that have been staged.
+    Staged,
+    /// Stash all changes to the given paths, including untracked files.
+    Paths(Vec<RepoPath>),
+}
+
+/// A working tree of a repository, as listed by `git worktree list`.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct GitWorktree {
+    pub path: PathBuf,
+    /// The commit checked out in the worktree, or `None` for a bare repository.
+    pub sha: Option<SharedString>,
+    /// The branch checked out in the worktree, or `None` when its HEAD is detached.
+    pub branch: Option<SharedString>,
+    /// Whether this is the main worktree of the repository, rather than a linked worktree.
+    pub is_main: bool,
+    /// Whether the worktree is locked, which prevents it from being removed.
+    pub is_locked: bool,
+}
+
+/// A submodule of a repository, as configured in its `.gitmodules`.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct Submodule {
+    pub name: SharedString,
+    pub path: RepoPath,
+    pub url: Option<SharedString>,
+    /// The commit recorded for the submodule in the superproject's HEAD.
+    pub head_sha: Option<SharedString>,
+    /// The commit checked out in the submodule, or `None` when it isn't initialized.
+    pub workdir_sha: Option<SharedString>,
+    pub status: SubmoduleStatus,
+}
+
+/// How the working directory of a submodule differs from what the superproject records.
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+pub struct SubmoduleStatus {
+    /// The submodule hasn't been cloned and checked out by `git submodule update --init`.
+    pub uninitialized: bool,
+    /// The submodule's HEAD differs from the commit recorded in the superproject's index.
+    pub new_commits: bool,
+    /// The submodule has uncommitted changes to tracked files.
+    pub modified: bool,
+    /// The submodule has untracked files.
+    pub untracked: bool,
+}
+
+impl SubmoduleStatus {
+    pub fn is_clean(&self) -> bool {
+        !self.uninitialized && !self.new_commits && !self.modified && !self.untracked
+    }
+}
+
+/// A commit returned by [`GitRepository::log`].
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct LogEntry {
+    pub sha: SharedString,
+    pub parent_shas: Vec<SharedString>,
+    pub author_name: SharedString,
+    pub author_email: SharedString,
+    /// This is a unix timestamp
+    pub commit_timestamp: i64,
+    /// The branches and tags pointing at the commit, such as `HEAD -> main` or `tag: v1.0`.
+    pub refs: Vec<SharedString>,
+    pub subject: SharedString,
+}
+
+/// The commits returned by [`GitRepository::log`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LogFilter {
+    /// All commits reachable from HEAD.
+    Head,
+    /// Commits that changed the given path, optionally following it across renames.
+    Path { path: RepoPath, follow: bool },
+    /// Commits that changed the given lines of a file, as of HEAD. Lines are 1-based and inclusive.
+    Lines {
+        path: RepoPath,
+        start: u32,
+        end: u32,
+    },
+}
+
 pub enum ResetMode {
     /// Reset the branch pointer, leave index and worktree unchanged (this will make it look like things that were
     /// committed are now staged).
@@ -169,6 +302,57 @@ pub enum ResetMode {
     Mixed,
 }
 
+/// A command that applies the changes introduced by existing commits on top of HEAD.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SequencerCommand {
+    CherryPick,
+    Revert,
+}
+
+impl SequencerCommand {
+    /// The git subcommand that runs this command.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SequencerCommand::CherryPick => "cherry-pick",
+            SequencerCommand::Revert => "revert",
+        }
+    }
+
+    /// Returns the commit that a cherry-pick or revert in the given git directory stopped at,
+    /// if any.
+    fn stopped_commit(git_dir: &Path) -> Option<String> {
+        ["CHERRY_PICK_HEAD", "REVERT_HEAD"]
+            .into_iter()
+            .find_map(|name| std::fs::read_to_string(git_dir.join(name)).ok())
+            .map(|sha| sha.trim().to_string())
+    }
+
+    /// Returns the command that is in progress in the given git directory, if any.
+    pub fn in_progress(git_dir: &Path) -> Option<Self> {
+        if git_dir.join("CHERRY_PICK_HEAD").is_file() {
+            return Some(SequencerCommand::CherryPick);
+        }
+        if git_dir.join("REVERT_HEAD").is_file() {
+            return Some(SequencerCommand::Revert);
+        }
+
+        // Once the commit that stopped a sequence of several commits has been committed, only
+        // the todo list of the remaining ones is left.
+        let todo = std::fs::read_to_string(git_dir.join("sequencer").join("todo")).ok()?;
+        let command = todo
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty() && !line.starts_with('#'))?
+            .split_whitespace()
+            .next()?;
+        match command {
+            "pick" | "p" => Some(SequencerCommand::CherryPick),
+            "revert" => Some(SequencerCommand::Revert),
+            _ => None,
+        }
+    }
+}
+
 pub trait GitRepository: Send + Sync {
     fn reload_index(&self);
 
@@ -206,6 +390,43 @@ pub trait GitRepository: Send + Sync {
     fn change_branch(&self, name: String) -> BoxFuture<Result<()>>;
     fn create_branch(&self, name: String) -> BoxFuture<Result<()>>;
 
+    /// Returns the commit checked out when HEAD is detached, or `None` when HEAD points at a
+    /// branch.
+    fn detached_head(&self) -> BoxFuture<Option<DetachedHead>>;
+
+    /// Returns the tags of the repository, most recently created first.
+    fn tags(&self) -> BoxFuture<Result<Vec<Tag>>>;
+
+    /// Creates a tag pointing at `target`, or at HEAD when `target` is `None`. The tag is
+    /// annotated when a `message` is given, and lightweight otherwise.
+    fn create_tag(
+        &self,
+        name: String,
+        target: Option<String>,
+        message: Option<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>>;
+
+    fn delete_tag(&self, name: String, env: Arc<HashMap<String, String>>) -> BoxFuture<Result<()>>;
+
+    /// Checks out the commit the tag points at, detaching HEAD.
+    fn checkout_tag(
+        &self,
+        name: String,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>>;
+
+    fn push_tag(
+        &self,
+        name: String,
+        remote_name: String,
+        askpass: AskPassDelegate,
+        env: Arc<HashMap<String, String>>,
+        // This method takes an AsyncApp to ensure it's invoked on the main thread,
+        // otherwise git-credentials-manager won't work.
+        cx: AsyncApp,
+    ) -> BoxFuture<Result<RemoteCommandOutput>>;
+
     fn reset(
         &self,
         commit: String,
@@ -223,6 +444,126 @@ pub trait GitRepository: Send + Sync {
     fn show(&self, commit: String) -> BoxFuture<Result<CommitDetails>>;
 
     fn load_commit(&self, commit: String, cx: AsyncApp) -> BoxFuture<Result<CommitDiff>>;
+
+    /// Saves the given changes to a new stash entry, and reverts them in the working tree.
+    fn stash_push(
+        &self,
+        message: Option<String>,
+        mode: StashMode,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>>;
+
+    /// Returns the entries of the stash, most recent first.
+    fn stash_list(&self) -> BoxFuture<Result<Vec<StashEntry>>>;
+
+    /// Applies the changes of a stash entry to the working tree, keeping the entry.
+    fn stash_apply(&self, index: usize, env: Arc<HashMap<String, String>>)
+    -> BoxFuture<Result<()>>;
+
+    /// Applies the changes of a stash entry to the working tree, and removes the entry.
+    fn stash_pop(&self, index: usize, env: Arc<HashMap<String, String>>) -> BoxFuture<Result<()>>;
+
+    /// Removes a stash entry without applying it.
+    fn stash_drop(&self, index: usize, env: Arc<HashMap<String, String>>) -> BoxFuture<Result<()>>;
+
+    /// Loads the changes saved in a stash entry, relative to the commit it was created on.
+    fn stash_show(&self, index: usize, cx: AsyncApp) -> BoxFuture<Result<CommitDiff>>;
+
+    /// Returns up to `limit` commits matching the filter, newest first, after skipping the first `skip` of them.
+    fn log(&self, filter: LogFilter, skip: usize, limit: usize)
+    -> BoxFuture<Result<Vec<LogEntry>>>;
+
+    /// Returns the state of the rebase in progress, if any.
+    fn rebase_state(&self) -> BoxFuture<Option<RebaseState>>;
+
+    /// Rebases the commits after `base` according to the given todo list, or every commit
+    /// of the current branch when `base` is `None`.
+    ///
+    /// Returns successfully when the rebase stops, for instance because of a conflict.
+    fn rebase_interactive(
+        &self,
+        base: Option<String>,
+        entries: Vec<RebaseTodoEntry>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>>;
+
+    /// Continues, skips the current commit of, or aborts the rebase in progress.
+    fn rebase_operation(
+        &self,
+        operation: RebaseOperation,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>>;
+
+    /// Returns the cherry-pick or revert in progress, if any.
+    fn sequencer_command(&self) -> BoxFuture<Option<SequencerCommand>>;
+
+    /// Applies the changes introduced by the given commits, or ranges of commits such as
+    /// `a..b`, on top of HEAD. Unless `no_commit` is true, a commit is created for each of them.
+    ///
+    /// Returns successfully when the cherry-pick stops because of a conflict.
+    fn cherry_pick(
+        &self,
+        commits: Vec<String>,
+        no_commit: bool,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>>;
+
+    /// Undoes the changes introduced by the given commits, or ranges of commits such as `a..b`.
+    /// Unless `no_commit` is true, a commit is created for each of them.
+    ///
+    /// Returns successfully when the revert stops because of a conflict.
+    fn revert(
+        &self,
+        commits: Vec<String>,
+        no_commit: bool,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>>;
+
+    /// Continues, skips the current commit of, or aborts the cherry-pick or revert in progress.
+    fn sequencer_operation(
+        &self,
+        command: SequencerCommand,
+        operation: RebaseOperation,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>>;
+
+    /// Returns the worktrees of the repository, starting with the main worktree.
+    fn worktrees(&self) -> BoxFuture<Result<Vec<GitWorktree>>>;
+
+    /// Creates a linked worktree at `path` with the given branch checked out, creating the
+    /// branch from HEAD first if `create_branch` is true.
+    fn create_worktree(
+        &self,
+        path: PathBuf,
+        branch: String,
+        create_branch: bool,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>>;
+
+    /// Removes the linked worktree at `path`. Unless `force` is true, this fails if the
+    /// worktree has uncommitted changes.
+    fn remove_worktree(
+        &self,
+        path: PathBuf,
+        force: bool,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>>;
+
+    /// Returns the submodules of the repository, ordered by path.
+    fn submodules(&self) -> BoxFuture<Result<Vec<Submodule>>>;
+
+    /// Checks out the commits recorded for the given submodules, or for every submodule when
+    /// `paths` is empty, cloning submodules that haven't been initialized yet.
+    fn update_submodules(
+        &self,
+        paths: Vec<RepoPath>,
+        askpass: AskPassDelegate,
+        env: Arc<HashMap<String, String>>,
+        // This method takes an AsyncApp to ensure it's invoked on the main thread,
+        // otherwise git-credentials-manager won't work.
+        cx: AsyncApp,
+    ) -> BoxFuture<Result<RemoteCommandOutput>>;
+
     fn blame(&self, path: RepoPath, content: Rope) -> BoxFuture<Result<crate::blame::Blame>>;
 
     /// Returns the absolute path to the repository. For worktrees, this will be the path to the
@@ -258,6 +599,7 @@ pub trait GitRepository: Send + Sync {
         &self,
         message: SharedString,
         name_and_email: Option<(SharedString, SharedString)>,
+        options: CommitOptions,
         env: Arc<HashMap<String, String>>,
     ) -> BoxFuture<Result<()>>;
 
@@ -293,6 +635,19 @@ pub trait GitRepository: Send + Sync {
         cx: AsyncApp,
     ) -> BoxFuture<Result<RemoteCommandOutput>>;
 
+    /// Fetches a single ref of a remote into a local branch, such as the head of a pull request.
+    fn fetch_ref(
+        &self,
+        remote_name: String,
+        remote_ref: String,
+        branch_name: String,
+        askpass: AskPassDelegate,
+        env: Arc<HashMap<String, String>>,
+        // This method takes an AsyncApp to ensure it's invoked on the main thread,
+        // otherwise git-credentials-manager won't work.
+        cx: AsyncApp,
+    ) -> BoxFuture<Result<RemoteCommandOutput>>;
+
     fn get_remotes(&self, branch_name: Option<String>) -> BoxFuture<Result<Vec<Remote>>>;
 
     /// returns a list of remote branches that contain HEAD
@@ -330,6 +685,18 @@ pub enum DiffType {
     HeadToWorktree,
 }
 
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommitOptions {
+    /// Replace the tip of the current branch instead of creating a new commit.
+    pub amend: bool,
+    /// Create a `fixup!` commit for the given commit, to be squashed by `git rebase --autosquash`.
+    pub fixup: Option<String>,
+    /// Add a `Signed-off-by` trailer to the commit message.
+    pub signoff: bool,
+    /// GPG-sign the commit.
+    pub gpg_sign: bool,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, JsonSchema)]
 pub enum PushOptions {
     SetUpstream,
@@ -370,6 +737,126 @@ impl RealGitRepository {
             .context("failed to read git work directory")
             .map(Path::to_path_buf)
     }
+
+    fn run_stash_command(
+        &self,
+        command: &'static str,
+        index: usize,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'static, Result<()>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        self.executor
+            .spawn(async move {
+                let output = new_smol_command(&git_binary_path)
+                    .current_dir(&working_directory?)
+                    .envs(env.iter())
+                    .args(["stash", command, "--quiet", &stash_reference(index)])
+                    .output()
+                    .await?;
+                if !output.status.success() {
+                    return Err(anyhow!(
+                        "Failed to {command} stash entry:\n{}",
+                        String::from_utf8_lossy(&output.stderr)
+                    ));
+                }
+                Ok(())
+            })
+            .boxed()
+    }
+
+    fn run_tag_command(
+        &self,
+        action: &'static str,
+        args: Vec<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'static, Result<()>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        self.executor
+            .spawn(async move {
+                let output = new_smol_command(&git_binary_path)
+                    .current_dir(&working_directory?)
+                    .envs(env.iter())
+                    // Annotated tags are only created with a message, so git never has to ask for one.
+                    .env("GIT_EDITOR", ":")
+                    .args(&args)
+                    .output()
+                    .await?;
+                if !output.status.success() {
+                    return Err(anyhow!(
+                        "Failed to {action} tag:\n{}",
+                        String::from_utf8_lossy(&output.stderr)
+                    ));
+                }
+                Ok(())
+            })
+            .boxed()
+    }
+
+    fn run_sequencer_command(
+        &self,
+        command: SequencerCommand,
+        options: Vec<&'static str>,
+        commits: Vec<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'static, Result<()>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        let git_dir = self.path();
+        let repository = self.repository.clone();
+        self.executor
+            .spawn(async move {
+                if let Some(commit) = commits.iter().find(|commit| commit.starts_with('-')) {
+                    return Err(anyhow!("invalid commit {commit:?}"));
+                }
+                let has_conflicts = || {
+                    repository
+                        .lock()
+                        .index()
+                        .and_then(|mut index| {
+                            index.read(false)?;
+                            Ok(index.has_conflicts())
+                        })
+                        .unwrap_or(false)
+                };
+                let no_commit = options.contains(&"--no-commit");
+                let stopped_commit_before = SequencerCommand::stopped_commit(&git_dir);
+                let had_conflicts = has_conflicts();
+
+                let output = new_smol_command(&git_binary_path)
+                    .current_dir(&working_directory?)
+                    .envs(env.iter())
+                    // Keep the messages git proposes for the new commits.
+                    .env("GIT_EDITOR", ":")
+                    .arg(command.as_str())
+                    .args(&options)
+                    .args(&commits)
+                    .output()
+                    .await?;
+                if output.status.success() {
+                    return Ok(());
+                }
+
+                // A conflict stops the command at the commit that conflicted, which is then
+                // still in progress. With `--no-commit`, nothing is in progress and only the
+                // conflicts are left. Any other failure leaves neither of them behind.
+                let stopped_commit = SequencerCommand::stopped_commit(&git_dir);
+                let stopped_at_conflict = (stopped_commit.is_some()
+                    && stopped_commit != stopped_commit_before)
+                    || (no_commit && !had_conflicts && has_conflicts());
+                if stopped_at_conflict {
+                    Ok(())
+                } else {
+                    Err(anyhow!(
+                        "Failed to {}:\n{}",
+                        command.as_str(),
+                        String::from_utf8_lossy(&output.stderr)
+                    ))
+                }
+            })
+            .boxed()
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -449,93 +936,483 @@ impl GitRepository for RealGitRepository {
             let parent_sha = lines.next().unwrap().trim().trim_end_matches('\0');
             let changes = parse_git_diff_name_status(lines.next().unwrap_or(""));
 
-            let mut cat_file_process = util::command::new_std_command("git")
-                .current_dir(&working_directory)
-                .args(["--no-optional-locks", "cat-file", "--batch=%(objectsize)"])
-                .stdin(Stdio::piped())
-                .stdout(Stdio::piped())
-                .stderr(Stdio::piped())
-                .spawn()
-                .map_err(|e| anyhow!("Failed to start git cat-file process: {e}"))?;
-
-            use std::io::Write as _;
-            let mut files = Vec::<CommitFile>::new();
-            let mut stdin = BufWriter::with_capacity(512, cat_file_process.stdin.take().unwrap());
-            let mut stdout = BufReader::new(cat_file_process.stdout.take().unwrap());
-            let mut info_line = String::new();
-            let mut newline = [b'\0'];
-            for (path, status_code) in changes {
-                match status_code {
-                    StatusCode::Modified => {
-                        writeln!(&mut stdin, "{commit}:{}", path.display())?;
-                        writeln!(&mut stdin, "{parent_sha}:{}", path.display())?;
-                    }
-                    StatusCode::Added => {
-                        writeln!(&mut stdin, "{commit}:{}", path.display())?;
-                    }
-                    StatusCode::Deleted => {
-                        writeln!(&mut stdin, "{parent_sha}:{}", path.display())?;
-                    }
-                    _ => continue,
-                }
-                stdin.flush()?;
-
-                info_line.clear();
-                stdout.read_line(&mut info_line)?;
-
-                let len = info_line.trim_end().parse().with_context(|| {
-                    format!("invalid object size output from cat-file {info_line}")
-                })?;
-                let mut text = vec![0; len];
-                stdout.read_exact(&mut text)?;
-                stdout.read_exact(&mut newline)?;
-                let text = String::from_utf8_lossy(&text).to_string();
-
-                let mut old_text = None;
-                let mut new_text = None;
-                match status_code {
-                    StatusCode::Modified => {
-                        info_line.clear();
-                        stdout.read_line(&mut info_line)?;
-                        let len = info_line.trim_end().parse().with_context(|| {
-                            format!("invalid object size output from cat-file {}", info_line)
-                        })?;
-                        let mut parent_text = vec![0; len];
-                        stdout.read_exact(&mut parent_text)?;
-                        stdout.read_exact(&mut newline)?;
-                        old_text = Some(String::from_utf8_lossy(&parent_text).to_string());
-                        new_text = Some(text);
-                    }
-                    StatusCode::Added => new_text = Some(text),
-                    StatusCode::Deleted => old_text = Some(text),
-                    _ => continue,
-                }
-
-                files.push(CommitFile {
-                    path: path.into(),
-                    old_text,
-                    new_text,
-                })
-            }
-
+            let files = load_commit_files(&working_directory, parent_sha, &commit, changes)?;
             Ok(CommitDiff { files })
         })
         .boxed()
     }
 
-    fn reset(
+    fn stash_push(
         &self,
-        commit: String,
-        mode: ResetMode,
+        message: Option<String>,
+        mode: StashMode,
         env: Arc<HashMap<String, String>>,
     ) -> BoxFuture<Result<()>> {
-        async move {
-            let working_directory = self.working_directory();
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        self.executor
+            .spawn(async move {
+                let mut cmd = new_smol_command(&git_binary_path);
+                cmd.current_dir(&working_directory?)
+                    .envs(env.iter())
+                    .args(["stash", "push", "--quiet"]);
 
-            let mode_flag = match mode {
-                ResetMode::Mixed => "--mixed",
-                ResetMode::Soft => "--soft",
-            };
+                if let Some(message) = message {
+                    cmd.arg("--message").arg(message);
+                }
+
+                match &mode {
+                    StashMode::All => {
+                        cmd.arg("--include-untracked");
+                    }
+                    StashMode::Staged => {
+                        cmd.arg("--staged");
+                    }
+                    StashMode::Paths(paths) => {
+                        // Without any pathspecs, git would stash every change.
+                        if paths.is_empty() {
+                            return Ok(());
+                        }
+                        cmd.args(["--include-untracked", "--"])
+                            .args(paths.iter().map(|path| path.as_ref()));
+                    }
+                }
+
+                let output = cmd.output().await?;
+                if !output.status.success() {
+                    return Err(anyhow!(
+                        "Failed to stash changes:\n{}",
+                        String::from_utf8_lossy(&output.stderr)
+                    ));
+                }
+                Ok(())
+            })
+            .boxed()
+    }
+
+    fn stash_list(&self) -> BoxFuture<Result<Vec<StashEntry>>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        self.executor
+            .spawn(async move {
+                let fields = ["%gd", "%H", "%ct", "%gs"].join("%x00");
+                let output = new_smol_command(&git_binary_path)
+                    .current_dir(&working_directory?)
+                    .args(["stash", "list"])
+                    .arg(format!("--format={fields}"))
+                    .output()
+                    .await?;
+
+                if !output.status.success() {
+                    return Err(anyhow!(
+                        "Failed to list stash entries:\n{}",
+                        String::from_utf8_lossy(&output.stderr)
+                    ));
+                }
+
+                parse_stash_list(&String::from_utf8_lossy(&output.stdout))
+            })
+            .boxed()
+    }
+
+    fn stash_apply(
+        &self,
+        index: usize,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        self.run_stash_command("apply", index, env)
+    }
+
+    fn stash_pop(&self, index: usize, env: Arc<HashMap<String, String>>) -> BoxFuture<Result<()>> {
+        self.run_stash_command("pop", index, env)
+    }
+
+    fn stash_drop(&self, index: usize, env: Arc<HashMap<String, String>>) -> BoxFuture<Result<()>> {
+        self.run_stash_command("drop", index, env)
+    }
+
+    fn stash_show(&self, index: usize, cx: AsyncApp) -> BoxFuture<Result<CommitDiff>> {
+        let Some(working_directory) = self.repository.lock().workdir().map(ToOwned::to_owned)
+        else {
+            return future::ready(Err(anyhow!("no working directory"))).boxed();
+        };
+        cx.background_spawn(async move {
+            let stash = stash_reference(index);
+            let parent = format!("{stash}^1");
+            let diff_output = new_std_command("git")
+                .current_dir(&working_directory)
+                .args([
+                    "--no-optional-locks",
+                    "diff",
+                    "-z",
+                    "--no-renames",
+                    "--name-status",
+                    &parent,
+                    &stash,
+                ])
+                .output()
+                .map_err(|e| anyhow!("Failed to start git diff process: {e}"))?;
+            if !diff_output.status.success() {
+                return Err(anyhow!(
+                    "Failed to load stash entry:\n{}",
+                    String::from_utf8_lossy(&diff_output.stderr)
+                ));
+            }
+
+            let diff_stdout = String::from_utf8_lossy(&diff_output.stdout);
+            let changes = parse_git_diff_name_status(&diff_stdout);
+            let mut files = load_commit_files(&working_directory, &parent, &stash, changes)?;
+
+            // Untracked files are saved in a third parent, which only exists
+            // if the entry was created with `--include-untracked`.
+            let untracked = format!("{stash}^3");
+            let untracked_output = new_std_command("git")
+                .current_dir(&working_directory)
+                .args([
+                    "--no-optional-locks",
+                    "ls-tree",
+                    "-r",
+                    "-z",
+                    "--name-only",
+                    &untracked,
+                ])
+                .output()
+                .map_err(|e| anyhow!("Failed to start git ls-tree process: {e}"))?;
+            if untracked_output.status.success() {
+                let untracked_stdout = String::from_utf8_lossy(&untracked_output.stdout);
+                let changes = untracked_stdout
+                    .split('\0')
+                    .filter(|path| !path.is_empty())
+                    .map(|path| (Path::new(path), StatusCode::Added));
+                files.extend(load_commit_files(
+                    &working_directory,
+                    &parent,
+                    &untracked,
+                    changes,
+                )?);
+            }
+
+            Ok(CommitDiff { files })
+        })
+        .boxed()
+    }
+
+    fn log(
+        &self,
+        filter: LogFilter,
+        skip: usize,
+        limit: usize,
+    ) -> BoxFuture<Result<Vec<LogEntry>>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        self.executor
+            .spawn(async move {
+                let fields = ["%H", "%P", "%an", "%ae", "%ct", "%D", "%s"].join("%x00");
+                let mut command = new_smol_command(&git_binary_path);
+                command
+                    .current_dir(&working_directory?)
+                    .args(["--no-optional-locks", "log", "--no-color", "--no-patch"])
+                    .arg(format!("--format=%x1e{fields}"))
+                    .arg(format!("--skip={skip}"))
+                    .arg(format!("--max-count={limit}"));
+                match &filter {
+                    LogFilter::Head => {
+                        command.arg("--topo-order");
+                    }
+                    LogFilter::Path { path, follow } => {
+                        if *follow {
+                            command.arg("--follow");
+                        }
+                        command.arg("--").arg(path.as_os_str());
+                    }
+                    LogFilter::Lines { path, start, end } => {
+                        command.arg(format!("-L{start},{end}:{}", path.to_string_lossy()));
+                    }
+                }
+
+                let output = command.output().await?;
+                if !output.status.success() {
+                    return Err(anyhow!(
+                        "Failed to load commit history:\n{}",
+                        String::from_utf8_lossy(&output.stderr)
+                    ));
+                }
+
+                parse_log(&String::from_utf8_lossy(&output.stdout))
+            })
+            .boxed()
+    }
+
+    fn rebase_state(&self) -> BoxFuture<Option<RebaseState>> {
+        let git_dir = self.path();
+        async move { RebaseState::load(&git_dir) }.boxed()
+    }
+
+    fn rebase_interactive(
+        &self,
+        base: Option<String>,
+        entries: Vec<RebaseTodoEntry>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        let git_dir = self.path();
+        if let Some(base) = base.as_ref().filter(|base| base.starts_with('-')) {
+            return future::ready(Err(anyhow!("invalid commit {base:?}"))).boxed();
+        }
+        self.executor
+            .spawn(async move {
+                let session = SequenceEditorSession::new(&entries).await?;
+                let mut command = new_smol_command(&git_binary_path);
+                command
+                    .current_dir(&working_directory?)
+                    .envs(env.iter())
+                    .env("GIT_SEQUENCE_EDITOR", session.editor_command())
+                    // Keep the messages git proposes for squashed commits.
+                    .env("GIT_EDITOR", ":")
+                    .args(["rebase", "--interactive"]);
+                match &base {
+                    Some(base) => command.arg(base),
+                    None => command.arg("--root"),
+                };
+
+                let output = command.output().await?;
+                drop(session);
+                rebase_result("rebase", &output, &git_dir)
+            })
+            .boxed()
+    }
+
+    fn rebase_operation(
+        &self,
+        operation: RebaseOperation,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        let git_dir = self.path();
+        self.executor
+            .spawn(async move {
+                let output = new_smol_command(&git_binary_path)
+                    .current_dir(&working_directory?)
+                    .envs(env.iter())
+                    .env("GIT_EDITOR", ":")
+                    .args(["rebase", operation.as_arg()])
+                    .output()
+                    .await?;
+                rebase_result(&format!("rebase {}", operation.as_arg()), &output, &git_dir)
+            })
+            .boxed()
+    }
+
+    fn sequencer_command(&self) -> BoxFuture<Option<SequencerCommand>> {
+        let git_dir = self.path();
+        async move { SequencerCommand::in_progress(&git_dir) }.boxed()
+    }
+
+    fn cherry_pick(
+        &self,
+        commits: Vec<String>,
+        no_commit: bool,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        let options = if no_commit {
+            vec!["--no-commit"]
+        } else {
+            vec![]
+        };
+        self.run_sequencer_command(SequencerCommand::CherryPick, options, commits, env)
+    }
+
+    fn revert(
+        &self,
+        commits: Vec<String>,
+        no_commit: bool,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        let options = if no_commit {
+            vec!["--no-commit"]
+        } else {
+            vec![]
+        };
+        self.run_sequencer_command(SequencerCommand::Revert, options, commits, env)
+    }
+
+    fn sequencer_operation(
+        &self,
+        command: SequencerCommand,
+        operation: RebaseOperation,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        self.run_sequencer_command(command, vec![operation.as_arg()], Vec::new(), env)
+    }
+
+    fn submodules(&self) -> BoxFuture<Result<Vec<Submodule>>> {
+        let repo = self.repository.clone();
+        self.executor
+            .spawn(async move {
+                let repo = repo.lock();
+                let mut submodules = Vec::new();
+                for submodule in repo.submodules()? {
+                    let Some(name) = submodule.name() else {
+                        continue;
+                    };
+                    let status = repo.submodule_status(name, git2::SubmoduleIgnore::None)?;
+                    submodules.push(Submodule {
+                        name: name.to_string().into(),
+                        path: RepoPath::from(submodule.path()),
+                        url: submodule.url().map(|url| url.to_string().into()),
+                        head_sha: submodule.head_id().map(|oid| oid.to_string().into()),
+                        workdir_sha: submodule.workdir_id().map(|oid| oid.to_string().into()),
+                        status: SubmoduleStatus {
+                            uninitialized: status.is_wd_uninitialized(),
+                            new_commits: status.is_wd_modified(),
+                            modified: status.is_wd_index_modified() || status.is_wd_wd_modified(),
+                            untracked: status.is_wd_untracked(),
+                        },
+                    });
+                }
+                submodules.sort_by(|a, b| a.path.cmp(&b.path));
+                Ok(submodules)
+            })
+            .boxed()
+    }
+
+    fn update_submodules(
+        &self,
+        paths: Vec<RepoPath>,
+        ask_pass: AskPassDelegate,
+        env: Arc<HashMap<String, String>>,
+        cx: AsyncApp,
+    ) -> BoxFuture<Result<RemoteCommandOutput>> {
+        let working_directory = self.working_directory();
+        let executor = cx.background_executor().clone();
+        let git_binary_path = self.git_binary_path.clone();
+        async move {
+            let mut command = new_smol_command(&git_binary_path);
+            command
+                .envs(env.iter())
+                .env("GIT_HTTP_USER_AGENT", "Zed")
+                .current_dir(&working_directory?)
+                .args(["submodule", "update", "--init", "--recursive", "--"])
+                .args(paths.iter().map(|path| path.as_os_str()))
+                .stdout(smol::process::Stdio::piped())
+                .stderr(smol::process::Stdio::piped());
+
+            run_git_command(env, ask_pass, command, &executor).await
+        }
+        .boxed()
+    }
+
+    fn worktrees(&self) -> BoxFuture<Result<Vec<GitWorktree>>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        self.executor
+            .spawn(async move {
+                let output = new_smol_command(&git_binary_path)
+                    .current_dir(&working_directory?)
+                    .args(["worktree", "list", "--porcelain"])
+                    .output()
+                    .await?;
+
+                if !output.status.success() {
+                    return Err(anyhow!(
+                        "Failed to list worktrees:\n{}",
+                        String::from_utf8_lossy(&output.stderr)
+                    ));
+                }
+
+                parse_worktree_list(&String::from_utf8_lossy(&output.stdout))
+            })
+            .boxed()
+    }
+
+    fn create_worktree(
+        &self,
+        path: PathBuf,
+        branch: String,
+        create_branch: bool,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        if branch.starts_with('-') {
+            return future::ready(Err(anyhow!("invalid branch name {branch:?}"))).boxed();
+        }
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        self.executor
+            .spawn(async move {
+                let mut command = new_smol_command(&git_binary_path);
+                command
+                    .current_dir(&working_directory?)
+                    .envs(env.iter())
+                    .args(["worktree", "add"]);
+                if create_branch {
+                    command
+                        .arg("-b")
+                        .arg(&branch)
+                        .arg("--")
+                        .arg(path.as_os_str());
+                } else {
+                    command.arg("--").arg(path.as_os_str()).arg(&branch);
+                }
+
+                let output = command.output().await?;
+                if !output.status.success() {
+                    return Err(anyhow!(
+                        "Failed to create worktree:\n{}",
+                        String::from_utf8_lossy(&output.stderr)
+                    ));
+                }
+                Ok(())
+            })
+            .boxed()
+    }
+
+    fn remove_worktree(
+        &self,
+        path: PathBuf,
+        force: bool,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        self.executor
+            .spawn(async move {
+                let mut command = new_smol_command(&git_binary_path);
+                command
+                    .current_dir(&working_directory?)
+                    .envs(env.iter())
+                    .args(["worktree", "remove"]);
+                if force {
+                    command.arg("--force");
+                }
+                command.arg("--").arg(path.as_os_str());
+
+                let output = command.output().await?;
+                if !output.status.success() {
+                    return Err(anyhow!(
+                        "Failed to remove worktree:\n{}",
+                        String::from_utf8_lossy(&output.stderr)
+                    ));
+                }
+                Ok(())
+            })
+            .boxed()
+    }
+
+    fn reset(
+        &self,
+        commit: String,
+        mode: ResetMode,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        async move {
+            let working_directory = self.working_directory();
+
+            let mode_flag = match mode {
+                ResetMode::Mixed => "--mixed",
+                ResetMode::Soft => "--soft",
+            };
 
             let output = new_smol_command(&self.git_binary_path)
                 .envs(env.iter())
@@ -720,14 +1597,16 @@ impl GitRepository for RealGitRepository {
                 true
             })
             .ok();
-        if let Some(oid) = self
-            .repository
-            .lock()
-            .find_reference("CHERRY_PICK_HEAD")
-            .ok()
-            .and_then(|reference| reference.target())
-        {
-            shas.push(oid.to_string())
+        for head in ["CHERRY_PICK_HEAD", "REVERT_HEAD"] {
+            if let Some(oid) = self
+                .repository
+                .lock()
+                .find_reference(head)
+                .ok()
+                .and_then(|reference| reference.target())
+            {
+                shas.push(oid.to_string())
+            }
         }
         shas
     }
@@ -850,6 +1729,156 @@ impl GitRepository for RealGitRepository {
             .boxed()
     }
 
+    fn detached_head(&self) -> BoxFuture<Option<DetachedHead>> {
+        let repo = self.repository.clone();
+        self.executor
+            .spawn(async move {
+                let repo = repo.lock();
+                if !repo.head_detached().ok()? {
+                    return None;
+                }
+                let head = repo.head().ok()?.peel_to_commit().ok()?.id();
+                let mut tag = None;
+                repo.tag_foreach(|oid, name| {
+                    let points_at_head = repo
+                        .find_object(oid, None)
+                        .and_then(|object| object.peel_to_commit())
+                        .is_ok_and(|commit| commit.id() == head);
+                    if points_at_head {
+                        let name = String::from_utf8_lossy(name);
+                        let name = name.strip_prefix("refs/tags/").unwrap_or(&name);
+                        tag = Some(name.to_string().into());
+                    }
+                    !points_at_head
+                })
+                // Stopping at the first matching tag makes `tag_foreach` return an error.
+                .ok();
+                Some(DetachedHead {
+                    sha: head.to_string().into(),
+                    tag,
+                })
+            })
+            .boxed()
+    }
+
+    fn tags(&self) -> BoxFuture<Result<Vec<Tag>>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        self.executor
+            .spawn(async move {
+                let fields = [
+                    "%(refname)",
+                    "%(objecttype)",
+                    "%(objectname)",
+                    "%(*objectname)",
+                    "%(creatordate:unix)",
+                    "%(contents:subject)",
+                ]
+                .join("%00");
+                let output = new_smol_command(&git_binary_path)
+                    .current_dir(&working_directory?)
+                    .args([
+                        "for-each-ref",
+                        "refs/tags",
+                        "--sort=-creatordate",
+                        "--format",
+                    ])
+                    .arg(&fields)
+                    .output()
+                    .await?;
+
+                if !output.status.success() {
+                    return Err(anyhow!(
+                        "Failed to list tags:\n{}",
+                        String::from_utf8_lossy(&output.stderr)
+                    ));
+                }
+
+                parse_tag_input(&String::from_utf8_lossy(&output.stdout))
+            })
+            .boxed()
+    }
+
+    fn create_tag(
+        &self,
+        name: String,
+        target: Option<String>,
+        message: Option<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        if name.starts_with('-') {
+            return future::ready(Err(anyhow!("invalid tag name {name:?}"))).boxed();
+        }
+        if let Some(target) = target.as_ref().filter(|target| target.starts_with('-')) {
+            return future::ready(Err(anyhow!("invalid commit {target:?}"))).boxed();
+        }
+        let mut args = vec!["tag".to_string()];
+        if let Some(message) = message {
+            args.extend(["--annotate".to_string(), "--message".to_string(), message]);
+        }
+        args.push(name);
+        args.extend(target);
+        self.run_tag_command("create", args, env)
+    }
+
+    fn delete_tag(&self, name: String, env: Arc<HashMap<String, String>>) -> BoxFuture<Result<()>> {
+        self.run_tag_command(
+            "delete",
+            vec![
+                "tag".to_string(),
+                "--delete".to_string(),
+                "--".to_string(),
+                name,
+            ],
+            env,
+        )
+    }
+
+    fn checkout_tag(
+        &self,
+        name: String,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<Result<()>> {
+        self.run_tag_command(
+            "check out",
+            vec![
+                "checkout".to_string(),
+                "--detach".to_string(),
+                format!("refs/tags/{name}"),
+            ],
+            env,
+        )
+    }
+
+    fn push_tag(
+        &self,
+        name: String,
+        remote_name: String,
+        ask_pass: AskPassDelegate,
+        env: Arc<HashMap<String, String>>,
+        cx: AsyncApp,
+    ) -> BoxFuture<Result<RemoteCommandOutput>> {
+        let working_directory = self.working_directory();
+        let executor = cx.background_executor().clone();
+        let git_binary_path = self.git_binary_path.clone();
+        async move {
+            let mut command = new_smol_command(&git_binary_path);
+            command
+                .envs(env.iter())
+                .env("GIT_HTTP_USER_AGENT", "Zed")
+                .current_dir(&working_directory?)
+                .arg("push")
+                .arg(remote_name)
+                .arg(format!("refs/tags/{name}"))
+                .stdin(smol::process::Stdio::null())
+                .stdout(smol::process::Stdio::piped())
+                .stderr(smol::process::Stdio::piped());
+
+            run_git_command(env, ask_pass, command, &executor).await
+        }
+        .boxed()
+    }
+
     fn blame(&self, path: RepoPath, content: Rope) -> BoxFuture<Result<crate::blame::Blame>> {
         let working_directory = self.working_directory();
         let git_binary_path = self.git_binary_path.clone();
@@ -964,6 +1993,7 @@ impl GitRepository for RealGitRepository {
         &self,
         message: SharedString,
         name_and_email: Option<(SharedString, SharedString)>,
+        options: CommitOptions,
         env: Arc<HashMap<String, String>>,
     ) -> BoxFuture<Result<()>> {
         let working_directory = self.working_directory();
@@ -972,9 +2002,28 @@ impl GitRepository for RealGitRepository {
                 let mut cmd = new_smol_command("git");
                 cmd.current_dir(&working_directory?)
                     .envs(env.iter())
-                    .args(["commit", "--quiet", "-m"])
-                    .arg(&message.to_string())
-                    .arg("--cleanup=strip");
+                    .args(["commit", "--quiet"]);
+
+                if let Some(fixup) = &options.fixup {
+                    // The message of a fixup commit is derived from its target.
+                    cmd.arg(format!("--fixup={fixup}"));
+                } else {
+                    cmd.arg("-m")
+                        .arg(&message.to_string())
+                        .arg("--cleanup=strip");
+                }
+
+                if options.amend {
+                    cmd.arg("--amend");
+                }
+
+                if options.signoff {
+                    cmd.arg("--signoff");
+                }
+
+                if options.gpg_sign {
+                    cmd.arg("--gpg-sign");
+                }
 
                 if let Some((name, email)) = name_and_email {
                     cmd.arg("--author").arg(&format!("{name} <{email}>"));
@@ -1077,6 +2126,35 @@ impl GitRepository for RealGitRepository {
         .boxed()
     }
 
+    fn fetch_ref(
+        &self,
+        remote_name: String,
+        remote_ref: String,
+        branch_name: String,
+        ask_pass: AskPassDelegate,
+        env: Arc<HashMap<String, String>>,
+        cx: AsyncApp,
+    ) -> BoxFuture<Result<RemoteCommandOutput>> {
+        let working_directory = self.working_directory();
+        let executor = cx.background_executor().clone();
+        let git_binary_path = self.git_binary_path.clone();
+        async move {
+            let mut command = new_smol_command(&git_binary_path);
+            command
+                .envs(env.iter())
+                .env("GIT_HTTP_USER_AGENT", "Zed")
+                .current_dir(&working_directory?)
+                .args(["fetch", &remote_name])
+                // Without a leading `+`, git refuses to rewrite local commits of the branch.
+                .arg(format!("{remote_ref}:refs/heads/{branch_name}"))
+                .stdout(smol::process::Stdio::piped())
+                .stderr(smol::process::Stdio::piped());
+
+            run_git_command(env, ask_pass, command, &executor).await
+        }
+        .boxed()
+    }
+
     fn get_remotes(&self, branch_name: Option<String>) -> BoxFuture<Result<Vec<Remote>>> {
         let working_directory = self.working_directory();
         let git_binary_path = self.git_binary_path.clone();
@@ -1626,6 +2704,204 @@ impl MapSeekTarget<RepoPath> for RepoPathDescendants<'_> {
     }
 }
 
+/// Loads the old and new contents of the given changes between two revisions, using a single
+/// `git cat-file` process.
+fn load_commit_files<'a>(
+    working_directory: &Path,
+    parent_sha: &str,
+    commit: &str,
+    changes: impl IntoIterator<Item = (&'a Path, StatusCode)>,
+) -> Result<Vec<CommitFile>> {
+    let mut cat_file_process = util::command::new_std_command("git")
+        .current_dir(working_directory)
+        .args(["--no-optional-locks", "cat-file", "--batch=%(objectsize)"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| anyhow!("Failed to start git cat-file process: {e}"))?;
+
+    use std::io::Write as _;
+    let mut files = Vec::<CommitFile>::new();
+    let mut stdin = BufWriter::with_capacity(512, cat_file_process.stdin.take().unwrap());
+    let mut stdout = BufReader::new(cat_file_process.stdout.take().unwrap());
+    let mut info_line = String::new();
+    let mut newline = [b'\0'];
+    for (path, status_code) in changes {
+        match status_code {
+            StatusCode::Modified => {
+                writeln!(&mut stdin, "{commit}:{}", path.display())?;
+                writeln!(&mut stdin, "{parent_sha}:{}", path.display())?;
+            }
+            StatusCode::Added => {
+                writeln!(&mut stdin, "{commit}:{}", path.display())?;
+            }
+            StatusCode::Deleted => {
+                writeln!(&mut stdin, "{parent_sha}:{}", path.display())?;
+            }
+            _ => continue,
+        }
+        stdin.flush()?;
+
+        info_line.clear();
+        stdout.read_line(&mut info_line)?;
+
+        let len = info_line
+            .trim_end()
+            .parse()
+            .with_context(|| format!("invalid object size output from cat-file {info_line}"))?;
+        let mut text = vec![0; len];
+        stdout.read_exact(&mut text)?;
+        stdout.read_exact(&mut newline)?;
+        let text = String::from_utf8_lossy(&text).to_string();
+
+        let mut old_text = None;
+        let mut new_text = None;
+        match status_code {
+            StatusCode::Modified => {
+                info_line.clear();
+                stdout.read_line(&mut info_line)?;
+                let len = info_line.trim_end().parse().with_context(|| {
+                    format!("invalid object size output from cat-file {}", info_line)
+                })?;
+                let mut parent_text = vec![0; len];
+                stdout.read_exact(&mut parent_text)?;
+                stdout.read_exact(&mut newline)?;
+                old_text = Some(String::from_utf8_lossy(&parent_text).to_string());
+                new_text = Some(text);
+            }
+            StatusCode::Added => new_text = Some(text),
+            StatusCode::Deleted => old_text = Some(text),
+            _ => continue,
+        }
+
+        files.push(CommitFile {
+            path: path.into(),
+            old_text,
+            new_text,
+        })
+    }
+
+    Ok(files)
+}
+
+fn parse_log(input: &str) -> Result<Vec<LogEntry>> {
+    let mut entries = Vec::new();
+    for record in input.split('\x1e') {
+        let record = record.trim_end_matches('\n');
+        if record.is_empty() {
+            continue;
+        }
+        let mut fields = record.split('\x00');
+        let sha: SharedString = fields.next().context("no commit hash")?.to_string().into();
+        let parent_shas = fields
+            .next()
+            .context("no parent hashes")?
+            .split_whitespace()
+            .map(|parent| SharedString::from(parent.to_string()))
+            .collect();
+        let author_name: SharedString = fields.next().context("no author name")?.to_string().into();
+        let author_email: SharedString =
+            fields.next().context("no author email")?.to_string().into();
+        let commit_timestamp = fields.next().context("no committer date")?.parse::<i64>()?;
+        let refs = fields
+            .next()
+            .context("no ref names")?
+            .split(", ")
+            .filter(|name| !name.is_empty())
+            .map(|name| SharedString::from(name.to_string()))
+            .collect();
+        let subject: SharedString = fields.next().context("no subject")?.to_string().into();
+
+        entries.push(LogEntry {
+            sha,
+            parent_shas,
+            author_name,
+            author_email,
+            commit_timestamp,
+            refs,
+            subject,
+        });
+    }
+
+    Ok(entries)
+}
+
+fn stash_reference(index: usize) -> String {
+    format!("stash@{{{index}}}")
+}
+
+fn parse_stash_list(input: &str) -> Result<Vec<StashEntry>> {
+    let mut entries = Vec::new();
+    for line in input.split('\n') {
+        if line.is_empty() {
+            continue;
+        }
+        let mut fields = line.split('\x00');
+        let index = fields
+            .next()
+            .context("no reflog selector")?
+            .strip_prefix("stash@{")
+            .and_then(|selector| selector.strip_suffix('}'))
+            .context("unexpected format for reflog selector")?
+            .parse::<usize>()?;
+        let sha: SharedString = fields.next().context("no commit hash")?.to_string().into();
+        let timestamp = fields.next().context("no committer date")?.parse::<i64>()?;
+        let message: SharedString = fields
+            .next()
+            .context("no reflog subject")?
+            .to_string()
+            .into();
+
+        entries.push(StashEntry {
+            index,
+            sha,
+            message,
+            timestamp,
+        });
+    }
+
+    Ok(entries)
+}
+
+/// Parses the output of `git worktree list --porcelain`, which describes each worktree in a
+/// block of `<attribute> <value>` lines, with blocks separated by an empty line.
+fn parse_worktree_list(input: &str) -> Result<Vec<GitWorktree>> {
+    let mut worktrees = Vec::new();
+    for block in input.split("\n\n") {
+        if block.trim().is_empty() {
+            continue;
+        }
+        let mut lines = block.lines();
+        let path = lines
+            .next()
+            .and_then(|line| line.strip_prefix("worktree "))
+            .context("no worktree path")?;
+        let mut worktree = GitWorktree {
+            path: PathBuf::from(path),
+            sha: None,
+            branch: None,
+            is_main: worktrees.is_empty(),
+            is_locked: false,
+        };
+        for line in lines {
+            let (attribute, value) = line.split_once(' ').unwrap_or((line, ""));
+            match attribute {
+                "HEAD" => worktree.sha = Some(value.to_string().into()),
+                "branch" => {
+                    let branch = value.strip_prefix("refs/heads/").unwrap_or(value);
+                    worktree.branch = Some(branch.to_string().into());
+                }
+                "locked" => worktree.is_locked = true,
+                _ => {}
+            }
+        }
+        worktrees.push(worktree);
+    }
+
+    Ok(worktrees)
+}
+
 fn parse_branch_input(input: &str) -> Result<Vec<Branch>> {
     let mut branches = Vec::new();
     for line in input.split('\n') {
@@ -1675,6 +2951,47 @@ fn parse_branch_input(input: &str) -> Result<Vec<Branch>> {
     Ok(branches)
 }
 
+fn parse_tag_input(input: &str) -> Result<Vec<Tag>> {
+    let mut tags = Vec::new();
+    for line in input.split('\n') {
+        if line.is_empty() {
+            continue;
+        }
+        let mut fields = line.split('\x00');
+        let name: SharedString = fields
+            .next()
+            .context("no refname")?
+            .strip_prefix("refs/tags/")
+            .context("unexpected format for refname")?
+            .to_string()
+            .into();
+        let is_annotated = fields.next().context("no objecttype")? == "tag";
+        let object_sha = fields.next().context("no objectname")?;
+        let peeled_sha = fields.next().context("no *objectname")?;
+        let timestamp = fields
+            .next()
+            .context("no creatordate")?
+            .parse::<i64>()
+            .unwrap_or_default();
+        let subject = fields.next().context("no contents:subject")?;
+
+        // For lightweight tags, the subject is the one of the tagged commit.
+        let (sha, message) = if is_annotated {
+            (peeled_sha, Some(subject.to_string().into()))
+        } else {
+            (object_sha, None)
+        };
+        tags.push(Tag {
+            name,
+            sha: sha.to_string().into(),
+            message,
+            timestamp,
+        });
+    }
+
+    Ok(tags)
+}
+
 fn parse_upstream_track(upstream_track: &str) -> Result<UpstreamTracking> {
     if upstream_track == "" {
         return Ok(UpstreamTracking::Tracked(UpstreamTrackingStatus {
@@ -1737,6 +3054,19 @@ fn check_path_to_repo_path_errors(relative_file_path: &Path) -> Result<()> {
     }
 }
 
+/// A rebase that stops because of a conflict exits with an error, but is still in progress
+/// and is resolved through [`GitRepository::rebase_operation`].
+fn rebase_result(operation: &str, output: &std::process::Output, git_dir: &Path) -> Result<()> {
+    if output.status.success() || RebaseState::load(git_dir).is_some() {
+        Ok(())
+    } else {
+        Err(anyhow!(
+            "Failed to {operation}:\n{}",
+            String::from_utf8_lossy(&output.stderr)
+        ))
+    }
+}
+
 fn checkpoint_author_envs() -> HashMap<String, String> {
     HashMap::from_iter([
         ("GIT_AUTHOR_NAME".to_string(), "Zed".to_string()),
@@ -1749,6 +3079,7 @@ fn checkpoint_author_envs() -> HashMap<String, String> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::rebase::RebaseAction;
     use gpui::TestAppContext;
 
     #[gpui::test]
@@ -1772,6 +3103,7 @@ mod tests {
         repo.commit(
             "Initial commit".into(),
             None,
+            CommitOptions::default(),
             Arc::new(checkpoint_author_envs()),
         )
         .await
@@ -1800,6 +3132,7 @@ mod tests {
         repo.commit(
             "Commit after checkpoint".into(),
             None,
+            CommitOptions::default(),
             Arc::new(checkpoint_author_envs()),
         )
         .await
@@ -1913,6 +3246,345 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_commit_options(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let file_path = repo_dir.path().join("file");
+        let repo =
+            RealGitRepository::new(&repo_dir.path().join(".git"), None, cx.executor()).unwrap();
+        let env = Arc::new(checkpoint_author_envs());
+
+        smol::fs::write(&file_path, "one").await.unwrap();
+        repo.stage_paths(vec![RepoPath::from_str("file")], env.clone())
+            .await
+            .unwrap();
+        repo.commit(
+            "First commit".into(),
+            None,
+            CommitOptions::default(),
+            env.clone(),
+        )
+        .await
+        .unwrap();
+
+        // Amending without changes rewords the commit.
+        repo.commit(
+            "Reworded commit".into(),
+            None,
+            CommitOptions {
+                amend: true,
+                signoff: true,
+                ..Default::default()
+            },
+            env.clone(),
+        )
+        .await
+        .unwrap();
+        let head = repo.show("HEAD".into()).await.unwrap();
+        assert_eq!(
+            head.message.as_ref(),
+            "Reworded commit\n\nSigned-off-by: Zed <hi@zed.dev>\n"
+        );
+        let log = repo.log(LogFilter::Head, 0, 10).await.unwrap();
+        assert_eq!(log.len(), 1);
+
+        smol::fs::write(&file_path, "two").await.unwrap();
+        repo.stage_paths(vec![RepoPath::from_str("file")], env.clone())
+            .await
+            .unwrap();
+        repo.commit(
+            "Ignored message".into(),
+            None,
+            CommitOptions {
+                fixup: Some(head.sha.to_string()),
+                ..Default::default()
+            },
+            env.clone(),
+        )
+        .await
+        .unwrap();
+        let log = repo.log(LogFilter::Head, 0, 10).await.unwrap();
+        assert_eq!(log.len(), 2);
+        assert_eq!(log[0].subject.as_ref(), "fixup! Reworded commit");
+    }
+
+    #[gpui::test]
+    async fn test_rebase_interactive(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let repo =
+            RealGitRepository::new(&repo_dir.path().join(".git"), None, cx.executor()).unwrap();
+        let env = Arc::new(checkpoint_author_envs());
+
+        for name in ["a", "b", "c", "d"] {
+            smol::fs::write(repo_dir.path().join(name), name)
+                .await
+                .unwrap();
+            repo.stage_paths(vec![RepoPath::from_str(name)], env.clone())
+                .await
+                .unwrap();
+            repo.commit(
+                format!("Add {name}").into(),
+                None,
+                CommitOptions::default(),
+                env.clone(),
+            )
+            .await
+            .unwrap();
+        }
+
+        let log = repo.log(LogFilter::Head, 0, 10).await.unwrap();
+        let entry = |ix: usize, action: RebaseAction| RebaseTodoEntry {
+            action,
+            sha: log[ix].sha.clone(),
+            subject: log[ix].subject.clone(),
+            message: None,
+        };
+        repo.rebase_interactive(
+            Some(log[3].sha.to_string()),
+            vec![
+                entry(0, RebaseAction::Pick),
+                RebaseTodoEntry {
+                    message: Some("Add b, reworded".into()),
+                    ..entry(2, RebaseAction::Reword)
+                },
+                entry(1, RebaseAction::Drop),
+            ],
+            env.clone(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(repo.rebase_state().await, None);
+        let subjects = repo
+            .log(LogFilter::Head, 0, 10)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.subject.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(subjects, ["Add b, reworded", "Add d", "Add a"]);
+        assert!(!repo_dir.path().join("c").exists());
+    }
+
+    #[gpui::test]
+    async fn test_cherry_pick_and_revert(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let repo =
+            RealGitRepository::new(&repo_dir.path().join(".git"), None, cx.executor()).unwrap();
+        let env = Arc::new(checkpoint_author_envs());
+        let subjects = async || {
+            repo.log(LogFilter::Head, 0, 10)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|entry| entry.subject.to_string())
+                .collect::<Vec<_>>()
+        };
+
+        for (name, contents, subject) in [("a", "a", "Add a"), ("b", "b", "Add b")] {
+            smol::fs::write(repo_dir.path().join(name), contents)
+                .await
+                .unwrap();
+            repo.stage_paths(vec![RepoPath::from_str(name)], env.clone())
+                .await
+                .unwrap();
+            repo.commit(subject.into(), None, CommitOptions::default(), env.clone())
+                .await
+                .unwrap();
+        }
+        let log = repo.log(LogFilter::Head, 0, 10).await.unwrap();
+        let add_b = log[0].sha.to_string();
+        let add_a = log[1].sha.to_string();
+
+        repo.revert(vec![add_b.clone()], false, env.clone())
+            .await
+            .unwrap();
+        assert_eq!(subjects().await, ["Revert \"Add b\"", "Add b", "Add a"]);
+        assert!(!repo_dir.path().join("b").exists());
+
+        repo.cherry_pick(vec![add_b.clone()], false, env.clone())
+            .await
+            .unwrap();
+        assert_eq!(
+            subjects().await,
+            ["Add b", "Revert \"Add b\"", "Add b", "Add a"]
+        );
+        assert!(repo_dir.path().join("b").exists());
+
+        // Reverting a commit whose changes were modified since then stops with a conflict.
+        smol::fs::write(repo_dir.path().join("a"), "a modified")
+            .await
+            .unwrap();
+        repo.stage_paths(vec![RepoPath::from_str("a")], env.clone())
+            .await
+            .unwrap();
+        repo.commit(
+            "Modify a".into(),
+            None,
+            CommitOptions::default(),
+            env.clone(),
+        )
+        .await
+        .unwrap();
+        repo.revert(vec![add_a], false, env.clone()).await.unwrap();
+        assert_eq!(
+            repo.sequencer_command().await,
+            Some(SequencerCommand::Revert)
+        );
+        assert_eq!(repo.merge_head_shas().len(), 1);
+
+        // Failures that aren't conflicts are still reported while the index has conflicts.
+        assert!(
+            repo.cherry_pick(vec![add_b.clone()], false, env.clone())
+                .await
+                .is_err()
+        );
+        assert!(
+            repo.revert(vec!["not-a-commit".into()], true, env.clone())
+                .await
+                .is_err()
+        );
+
+        repo.sequencer_operation(
+            SequencerCommand::Revert,
+            RebaseOperation::Abort,
+            env.clone(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(repo.sequencer_command().await, None);
+        assert_eq!(
+            smol::fs::read_to_string(repo_dir.path().join("a"))
+                .await
+                .unwrap(),
+            "a modified"
+        );
+
+        assert!(
+            repo.cherry_pick(vec!["--help".into()], false, env.clone())
+                .await
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_tags_parsing() {
+        let input = "refs/tags/v1.0\0tag\0aaaa\0bbbb\01733187470\0Release 1.0\nrefs/tags/nightly\0commit\0cccc\0\01733100000\0fix tests\n";
+        assert_eq!(
+            parse_tag_input(input).unwrap(),
+            vec![
+                Tag {
+                    name: "v1.0".into(),
+                    sha: "bbbb".into(),
+                    message: Some("Release 1.0".into()),
+                    timestamp: 1733187470,
+                },
+                Tag {
+                    name: "nightly".into(),
+                    sha: "cccc".into(),
+                    message: None,
+                    timestamp: 1733100000,
+                }
+            ]
+        )
+    }
+
+    #[gpui::test]
+    async fn test_tags(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let repo =
+            RealGitRepository::new(&repo_dir.path().join(".git"), None, cx.executor()).unwrap();
+        let env = Arc::new(checkpoint_author_envs());
+
+        for (name, subject) in [("a", "Add a"), ("b", "Add b")] {
+            smol::fs::write(repo_dir.path().join(name), name)
+                .await
+                .unwrap();
+            repo.stage_paths(vec![RepoPath::from_str(name)], env.clone())
+                .await
+                .unwrap();
+            repo.commit(subject.into(), None, CommitOptions::default(), env.clone())
+                .await
+                .unwrap();
+        }
+        let log = repo.log(LogFilter::Head, 0, 10).await.unwrap();
+        let add_b = log[0].sha.clone();
+        let add_a = log[1].sha.clone();
+
+        repo.create_tag("v1".into(), Some(add_a.to_string()), None, env.clone())
+            .await
+            .unwrap();
+        repo.create_tag(
+            "v2".into(),
+            None,
+            Some("Second release".into()),
+            env.clone(),
+        )
+        .await
+        .unwrap();
+        assert!(
+            repo.create_tag("--help".into(), None, None, env.clone())
+                .await
+                .is_err()
+        );
+
+        let mut tags = repo.tags().await.unwrap();
+        tags.sort_by(|a, b| a.name.cmp(&b.name));
+        assert_eq!(
+            tags.iter()
+                .map(|tag| (tag.name.to_string(), tag.sha.clone(), tag.message.clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("v1".to_string(), add_a.clone(), None),
+                (
+                    "v2".to_string(),
+                    add_b.clone(),
+                    Some(SharedString::from("Second release"))
+                ),
+            ]
+        );
+
+        assert_eq!(repo.detached_head().await, None);
+        repo.checkout_tag("v1".into(), env.clone()).await.unwrap();
+        assert_eq!(
+            repo.detached_head().await,
+            Some(DetachedHead {
+                sha: add_a.clone(),
+                tag: Some("v1".into()),
+            })
+        );
+
+        repo.delete_tag("v1".into(), env.clone()).await.unwrap();
+        assert_eq!(
+            repo.tags()
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|tag| tag.name.to_string())
+                .collect::<Vec<_>>(),
+            ["v2"]
+        );
+        assert_eq!(
+            repo.detached_head().await,
+            Some(DetachedHead {
+                sha: add_a,
+                tag: None,
+            })
+        );
+    }
+
     #[test]
     fn test_branches_parsing() {
         // suppress "help: octal escapes are not supported, `\0` is always null"
@@ -1940,6 +3612,240 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_stash_list_parsing() {
+        // suppress "help: octal escapes are not supported, `\0` is always null"
+        #[allow(clippy::octal_escapes)]
+        let input = concat!(
+            "stash@{0}\0a3c1f1e8b2a1c4f0e9d7b6a5c4d3e2f1a0b9c8d7\01733187470\0On main: work in progress\n",
+            "stash@{1}\0060964da10574cd9bf06463a53bf6e0769c5c45e\01733180000\0WIP on main: 1234567 generated protobuf\n",
+        );
+        assert_eq!(
+            parse_stash_list(input).unwrap(),
+            vec![
+                StashEntry {
+                    index: 0,
+                    sha: "a3c1f1e8b2a1c4f0e9d7b6a5c4d3e2f1a0b9c8d7".into(),
+                    message: "On main: work in progress".into(),
+                    timestamp: 1733187470,
+                },
+                StashEntry {
+                    index: 1,
+                    sha: "060964da10574cd9bf06463a53bf6e0769c5c45e".into(),
+                    message: "WIP on main: 1234567 generated protobuf".into(),
+                    timestamp: 1733180000,
+                },
+            ]
+        );
+        assert!(parse_stash_list("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_worktree_list_parsing() {
+        let input = concat!(
+            "worktree /home/user/zed\n",
+            "HEAD a3c1f1e8b2a1c4f0e9d7b6a5c4d3e2f1a0b9c8d7\n",
+            "branch refs/heads/main\n",
+            "\n",
+            "worktree /home/user/zed-review\n",
+            "HEAD 060964da10574cd9bf06463a53bf6e0769c5c45e\n",
+            "detached\n",
+            "locked reviewing\n",
+            "\n",
+        );
+        assert_eq!(
+            parse_worktree_list(input).unwrap(),
+            vec![
+                GitWorktree {
+                    path: PathBuf::from("/home/user/zed"),
+                    sha: Some("a3c1f1e8b2a1c4f0e9d7b6a5c4d3e2f1a0b9c8d7".into()),
+                    branch: Some("main".into()),
+                    is_main: true,
+                    is_locked: false,
+                },
+                GitWorktree {
+                    path: PathBuf::from("/home/user/zed-review"),
+                    sha: Some("060964da10574cd9bf06463a53bf6e0769c5c45e".into()),
+                    branch: None,
+                    is_main: false,
+                    is_locked: true,
+                },
+            ]
+        );
+        assert!(parse_worktree_list("").unwrap().is_empty());
+    }
+
+    #[gpui::test]
+    async fn test_create_and_remove_worktree(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        smol::fs::write(repo_dir.path().join("file"), "initial")
+            .await
+            .unwrap();
+
+        let repo =
+            RealGitRepository::new(&repo_dir.path().join(".git"), None, cx.executor()).unwrap();
+        let env = Arc::new(checkpoint_author_envs());
+        repo.stage_paths(vec![RepoPath::from_str("file")], env.clone())
+            .await
+            .unwrap();
+        repo.commit(
+            "Initial commit".into(),
+            None,
+            CommitOptions::default(),
+            env.clone(),
+        )
+        .await
+        .unwrap();
+
+        let worktree_parent = tempfile::tempdir().unwrap();
+        let worktree_path = worktree_parent.path().join("review");
+        repo.create_worktree(worktree_path.clone(), "review".into(), true, env.clone())
+            .await
+            .unwrap();
+        assert_eq!(
+            smol::fs::read_to_string(worktree_path.join("file"))
+                .await
+                .unwrap(),
+            "initial"
+        );
+
+        let worktrees = repo.worktrees().await.unwrap();
+        assert_eq!(worktrees.len(), 2);
+        assert!(worktrees[0].is_main);
+        assert_eq!(worktrees[1].branch, Some("review".into()));
+        assert_eq!(
+            worktrees[1].path.canonicalize().unwrap(),
+            worktree_path.canonicalize().unwrap()
+        );
+
+        repo.remove_worktree(worktree_path.clone(), false, env)
+            .await
+            .unwrap();
+        assert!(!worktree_path.exists());
+        assert_eq!(repo.worktrees().await.unwrap().len(), 1);
+    }
+
+    #[gpui::test]
+    async fn test_submodules(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let env = Arc::new(checkpoint_author_envs());
+        let dir = tempfile::tempdir().unwrap();
+        let submodule_dir = dir.path().join("submodule");
+        let superproject_dir = dir.path().join("superproject");
+        git2::Repository::init(&submodule_dir).unwrap();
+        git2::Repository::init(&superproject_dir).unwrap();
+
+        smol::fs::write(submodule_dir.join("file"), "initial")
+            .await
+            .unwrap();
+        let submodule =
+            RealGitRepository::new(&submodule_dir.join(".git"), None, cx.executor()).unwrap();
+        submodule
+            .stage_paths(vec![RepoPath::from_str("file")], env.clone())
+            .await
+            .unwrap();
+        submodule
+            .commit(
+                "Initial commit".into(),
+                None,
+                CommitOptions::default(),
+                env.clone(),
+            )
+            .await
+            .unwrap();
+
+        let output = std::process::Command::new("git")
+            .current_dir(&superproject_dir)
+            .envs(env.iter())
+            .args(["-c", "protocol.file.allow=always", "submodule", "add"])
+            .arg(&submodule_dir)
+            .arg("sub")
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+
+        let superproject =
+            RealGitRepository::new(&superproject_dir.join(".git"), None, cx.executor()).unwrap();
+        superproject
+            .commit(
+                "Add submodule".into(),
+                None,
+                CommitOptions::default(),
+                env.clone(),
+            )
+            .await
+            .unwrap();
+
+        let submodules = superproject.submodules().await.unwrap();
+        assert_eq!(submodules.len(), 1);
+        assert_eq!(submodules[0].name.as_ref(), "sub");
+        assert_eq!(submodules[0].path, RepoPath::from_str("sub"));
+        assert_eq!(submodules[0].head_sha, submodules[0].workdir_sha);
+        assert!(submodules[0].status.is_clean());
+
+        smol::fs::write(superproject_dir.join("sub/file"), "modified")
+            .await
+            .unwrap();
+        smol::fs::write(superproject_dir.join("sub/untracked"), "")
+            .await
+            .unwrap();
+        let status = superproject.submodules().await.unwrap()[0].status;
+        assert_eq!(
+            status,
+            SubmoduleStatus {
+                uninitialized: false,
+                new_commits: false,
+                modified: true,
+                untracked: true,
+            }
+        );
+    }
+
+    #[test]
+    fn test_log_parsing() {
+        // suppress "help: octal escapes are not supported, `\0` is always null"
+        #[allow(clippy::octal_escapes)]
+        let input = concat!(
+            "\x1ea3c1f1e8b2a1c4f0e9d7b6a5c4d3e2f1a0b9c8d7\0060964da10574cd9bf06463a53bf6e0769c5c45e 1234567890abcdef1234567890abcdef12345678\0Jane Doe\0jane@example.com\01733187470\0HEAD -> main, tag: v1.0\0Merge branch 'feature'\n",
+            "\x1e060964da10574cd9bf06463a53bf6e0769c5c45e\0\0John Doe\0john@example.com\01733180000\0\0Initial commit\n",
+        );
+        assert_eq!(
+            parse_log(input).unwrap(),
+            vec![
+                LogEntry {
+                    sha: "a3c1f1e8b2a1c4f0e9d7b6a5c4d3e2f1a0b9c8d7".into(),
+                    parent_shas: vec![
+                        "060964da10574cd9bf06463a53bf6e0769c5c45e".into(),
+                        "1234567890abcdef1234567890abcdef12345678".into(),
+                    ],
+                    author_name: "Jane Doe".into(),
+                    author_email: "jane@example.com".into(),
+                    commit_timestamp: 1733187470,
+                    refs: vec!["HEAD -> main".into(), "tag: v1.0".into()],
+                    subject: "Merge branch 'feature'".into(),
+                },
+                LogEntry {
+                    sha: "060964da10574cd9bf06463a53bf6e0769c5c45e".into(),
+                    parent_shas: Vec::new(),
+                    author_name: "John Doe".into(),
+                    author_email: "john@example.com".into(),
+                    commit_timestamp: 1733180000,
+                    refs: Vec::new(),
+                    subject: "Initial commit".into(),
+                },
+            ]
+        );
+        assert!(parse_log("").unwrap().is_empty());
+    }
+
     impl RealGitRepository {
         /// Force a Git garbage collection on the repository.
         fn gc(&self) -> BoxFuture<Result<()>> {
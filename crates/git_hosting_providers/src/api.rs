@@ -0,0 +1,187 @@
+use std::sync::Arc;
+
+use anyhow::{Context as _, Result, bail};
+use credentials_provider::CredentialsProvider;
+use futures::AsyncReadExt;
+use git::{CiStatus, GitHostingProvider, GitHostingProviderApi};
+use gpui::AsyncApp;
+use http_client::{AsyncBody, HttpClient, HttpRequestExt, Request};
+use serde::de::DeserializeOwned;
+use url::Url;
+use util::ResultExt as _;
+
+/// Returns a client for the API of the given provider, if it has one.
+///
+/// The API token is read from the credentials stored for the provider's base URL.
+pub async fn api_client_for_provider(
+    provider: &Arc<dyn GitHostingProvider + Send + Sync + 'static>,
+    http_client: Arc<dyn HttpClient>,
+    cx: &AsyncApp,
+) -> Result<Option<Arc<dyn GitHostingProviderApi>>> {
+    let credentials_provider = cx.update(|cx| <dyn CredentialsProvider>::global(cx))?;
+    let token = credentials_provider
+        .read_credentials(provider.base_url().as_str(), cx)
+        .await
+        .log_err()
+        .flatten()
+        .map(|(_, token)| String::from_utf8(token))
+        .transpose()
+        .context("invalid Git hosting provider API token")?;
+    Ok(provider.api_client(http_client, token))
+}
+
+/// Stores the API token used for the given provider, or removes it if the token is empty.
+pub async fn store_api_token(
+    provider: &Arc<dyn GitHostingProvider + Send + Sync + 'static>,
+    token: String,
+    cx: &AsyncApp,
+) -> Result<()> {
+    let credentials_provider = cx.update(|cx| <dyn CredentialsProvider>::global(cx))?;
+    let url = provider.base_url();
+    let token = token.trim();
+    if token.is_empty() {
+        credentials_provider
+            .delete_credentials(url.as_str(), cx)
+            .await
+    } else {
+        credentials_provider
+            .write_credentials(url.as_str(), "token", token.as_bytes(), cx)
+            .await
+    }
+}
+
+/// The most pages of results to follow for a single list request.
+const MAX_PAGES: usize = 20;
+
+/// Sends a GET request to the given API URL and deserializes the JSON response.
+pub(crate) async fn get_json<T: DeserializeOwned>(
+    http_client: &Arc<dyn HttpClient>,
+    url: &Url,
+    headers: &[(&str, String)],
+) -> Result<T> {
+    let (value, _) = get_json_page(http_client, url, headers).await?;
+    Ok(value)
+}
+
+/// Sends GET requests for the given API URL and the pages of results that follow it, as linked
+/// from the `Link` header of each response, and concatenates the results.
+pub(crate) async fn get_json_pages<T: DeserializeOwned>(
+    http_client: &Arc<dyn HttpClient>,
+    url: &Url,
+    headers: &[(&str, String)],
+) -> Result<Vec<T>> {
+    let mut results = Vec::new();
+    let mut next_url = Some(url.clone());
+    for _ in 0..MAX_PAGES {
+        let Some(url) = next_url.take() else {
+            break;
+        };
+        let (page, next) = get_json_page::<Vec<T>>(http_client, &url, headers).await?;
+        results.extend(page);
+        // The headers carry the API token, so they are only sent to the same origin.
+        next_url = next.filter(|next| next.origin() == url.origin());
+    }
+    Ok(results)
+}
+
+async fn get_json_page<T: DeserializeOwned>(
+    http_client: &Arc<dyn HttpClient>,
+    url: &Url,
+    headers: &[(&str, String)],
+) -> Result<(T, Option<Url>)> {
+    let mut request = Request::get(url.as_str())
+        .header("Accept", "application/json")
+        .follow_redirects(http_client::RedirectPolicy::FollowAll);
+    for (name, value) in headers {
+        request = request.header(*name, value);
+    }
+
+    let mut response = http_client
+        .send(request.body(AsyncBody::default())?)
+        .await
+        .with_context(|| format!("error sending request to {url}"))?;
+
+    let mut body = Vec::new();
+    response.body_mut().read_to_end(&mut body).await?;
+
+    if !response.status().is_success() {
+        let text = String::from_utf8_lossy(body.as_slice());
+        bail!(
+            "status error {}, response: {text:?}",
+            response.status().as_u16()
+        );
+    }
+
+    let next_url = response
+        .headers()
+        .get("Link")
+        .and_then(|link| link.to_str().ok())
+        .and_then(next_page_url);
+    let value = serde_json::from_slice(&body)
+        .with_context(|| format!("failed to deserialize response of {url}"))?;
+    Ok((value, next_url))
+}
+
+/// Returns the URL of the next page from a `Link` header, such as
+/// `<https://api.github.com/repositories/1/pulls?page=2>; rel="next", <…>; rel="last"`.
+fn next_page_url(link: &str) -> Option<Url> {
+    link.split(',').find_map(|link| {
+        let (url, params) = link.split_once(';')?;
+        params
+            .split(';')
+            .any(|param| param.trim() == "rel=\"next\"")
+            .then(|| Url::parse(url.trim().strip_prefix('<')?.strip_suffix('>')?).ok())
+            .flatten()
+    })
+}
+
+/// Combines the statuses of several CI checks: any failure fails the whole, and any pending
+/// check keeps it pending.
+pub(crate) fn combine_ci_statuses(
+    statuses: impl IntoIterator<Item = CiStatus>,
+) -> Option<CiStatus> {
+    statuses.into_iter().fold(None, |combined, status| {
+        Some(match (combined, status) {
+            (Some(CiStatus::Failure), _) | (_, CiStatus::Failure) => CiStatus::Failure,
+            (Some(CiStatus::Pending), _) | (_, CiStatus::Pending) => CiStatus::Pending,
+            _ => CiStatus::Success,
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_page_url() {
+        assert_eq!(
+            next_page_url(
+                r#"<https://api.github.com/repositories/1/pulls?page=2>; rel="next", <https://api.github.com/repositories/1/pulls?page=5>; rel="last""#
+            ),
+            Some(Url::parse("https://api.github.com/repositories/1/pulls?page=2").unwrap())
+        );
+        assert_eq!(
+            next_page_url(r#"<https://gitlab.com/api/v4/projects?page=1>; rel="first""#),
+            None
+        );
+        assert_eq!(next_page_url(""), None);
+    }
+
+    #[test]
+    fn test_combine_ci_statuses() {
+        assert_eq!(combine_ci_statuses([]), None);
+        assert_eq!(
+            combine_ci_statuses([CiStatus::Success, CiStatus::Success]),
+            Some(CiStatus::Success)
+        );
+        assert_eq!(
+            combine_ci_statuses([CiStatus::Success, CiStatus::Pending]),
+            Some(CiStatus::Pending)
+        );
+        assert_eq!(
+            combine_ci_statuses([CiStatus::Failure, CiStatus::Pending, CiStatus::Success]),
+            Some(CiStatus::Failure)
+        );
+    }
+}
@@ -1,3 +1,4 @@
+mod api;
 mod providers;
 mod settings;
 
@@ -10,6 +11,7 @@ use gpui::App;
 use url::Url;
 use util::maybe;
 
+pub use crate::api::{api_client_for_provider, store_api_token};
 pub use crate::providers::*;
 pub use crate::settings::*;
 
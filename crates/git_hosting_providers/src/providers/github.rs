@@ -11,10 +11,12 @@ use serde::Deserialize;
 use url::Url;
 
 use git::{
-    BuildCommitPermalinkParams, BuildPermalinkParams, GitHostingProvider, ParsedGitRemote,
-    PullRequest, RemoteUrl,
+    BuildCommitPermalinkParams, BuildPermalinkParams, CiStatus, GitHostingProvider,
+    GitHostingProviderApi, ParsedGitRemote, PullRequest, PullRequestDetails, RemoteUrl,
+    ReviewComment,
 };
 
+use crate::api::{combine_ci_statuses, get_json, get_json_pages};
 use crate::get_host_from_git_remote_url;
 
 fn pull_request_number_regex() -> &'static Regex {
@@ -45,10 +47,191 @@ struct User {
     pub avatar_url: String,
 }
 
+#[derive(Debug, Deserialize)]
+struct ApiUser {
+    login: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiBranch {
+    #[serde(rename = "ref")]
+    name: String,
+    sha: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiPullRequest {
+    number: u32,
+    title: String,
+    html_url: String,
+    user: Option<ApiUser>,
+    head: ApiBranch,
+    base: ApiBranch,
+    #[serde(default)]
+    draft: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiCombinedStatus {
+    state: String,
+    total_count: usize,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiCheckRuns {
+    check_runs: Vec<ApiCheckRun>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiCheckRun {
+    status: String,
+    conclusion: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiReviewComment {
+    id: u64,
+    user: Option<ApiUser>,
+    body: String,
+    path: String,
+    line: Option<u32>,
+    html_url: Option<String>,
+}
+
+/// A client for the REST API of GitHub.
+pub struct GithubApi {
+    api_url: Url,
+    http_client: Arc<dyn HttpClient>,
+    token: Option<String>,
+}
+
+impl GithubApi {
+    pub fn new(api_url: Url, http_client: Arc<dyn HttpClient>, token: Option<String>) -> Self {
+        Self {
+            api_url,
+            http_client,
+            token,
+        }
+    }
+
+    fn url(&self, remote: &ParsedGitRemote, path: &str) -> Result<Url> {
+        let ParsedGitRemote { owner, repo } = remote;
+        Ok(self.api_url.join(&format!("repos/{owner}/{repo}/{path}"))?)
+    }
+
+    fn headers(&self) -> Vec<(&'static str, String)> {
+        let mut headers = vec![("Accept", "application/vnd.github+json".to_string())];
+        if let Some(token) = &self.token {
+            headers.push(("Authorization", format!("Bearer {token}")));
+        }
+        headers
+    }
+
+    async fn get<T: serde::de::DeserializeOwned>(&self, url: Url) -> Result<T> {
+        get_json(&self.http_client, &url, &self.headers()).await
+    }
+
+    async fn get_pages<T: serde::de::DeserializeOwned>(&self, url: Url) -> Result<Vec<T>> {
+        get_json_pages(&self.http_client, &url, &self.headers()).await
+    }
+}
+
+#[async_trait]
+impl GitHostingProviderApi for GithubApi {
+    async fn pull_requests(
+        &self,
+        remote: &ParsedGitRemote,
+        head_branch: Option<&str>,
+    ) -> Result<Vec<PullRequestDetails>> {
+        let mut url = self.url(remote, "pulls")?;
+        url.query_pairs_mut()
+            .append_pair("state", "open")
+            .append_pair("per_page", "100");
+        if let Some(head_branch) = head_branch {
+            url.query_pairs_mut()
+                .append_pair("head", &format!("{}:{head_branch}", remote.owner));
+        }
+
+        self.get_pages::<ApiPullRequest>(url)
+            .await?
+            .into_iter()
+            .map(|pull_request| {
+                Ok(PullRequestDetails {
+                    number: pull_request.number,
+                    title: pull_request.title.into(),
+                    url: Url::parse(&pull_request.html_url)?,
+                    author: pull_request.user.map(|user| user.login.into()),
+                    head_branch: pull_request.head.name.into(),
+                    base_branch: pull_request.base.name.into(),
+                    head_sha: Some(pull_request.head.sha.into()),
+                    is_draft: pull_request.draft,
+                })
+            })
+            .collect()
+    }
+
+    async fn ci_status(&self, remote: &ParsedGitRemote, sha: &str) -> Result<Option<CiStatus>> {
+        // Commit statuses are reported by external services, while check runs are reported by
+        // GitHub Apps such as GitHub Actions, so both are needed for the full picture.
+        let combined_status = self
+            .get::<ApiCombinedStatus>(self.url(remote, &format!("commits/{sha}/status"))?)
+            .await?;
+        let check_runs = self
+            .get::<ApiCheckRuns>(self.url(remote, &format!("commits/{sha}/check-runs"))?)
+            .await?;
+
+        let status =
+            (combined_status.total_count > 0).then(|| match combined_status.state.as_str() {
+                "success" => CiStatus::Success,
+                "pending" => CiStatus::Pending,
+                _ => CiStatus::Failure,
+            });
+        let check_run_statuses = check_runs.check_runs.into_iter().filter_map(|check_run| {
+            if check_run.status != "completed" {
+                return Some(CiStatus::Pending);
+            }
+            match check_run.conclusion.as_deref() {
+                Some("success") => Some(CiStatus::Success),
+                Some("neutral" | "skipped") => None,
+                _ => Some(CiStatus::Failure),
+            }
+        });
+        Ok(combine_ci_statuses(
+            status.into_iter().chain(check_run_statuses),
+        ))
+    }
+
+    async fn review_comments(
+        &self,
+        remote: &ParsedGitRemote,
+        number: u32,
+    ) -> Result<Vec<ReviewComment>> {
+        let mut url = self.url(remote, &format!("pulls/{number}/comments"))?;
+        url.query_pairs_mut().append_pair("per_page", "100");
+
+        Ok(self
+            .get_pages::<ApiReviewComment>(url)
+            .await?
+            .into_iter()
+            .map(|comment| ReviewComment {
+                id: comment.id,
+                author: comment.user.map(|user| user.login.into()),
+                body: comment.body.into(),
+                path: comment.path,
+                line: comment.line,
+                url: comment.html_url.and_then(|url| Url::parse(&url).ok()),
+            })
+            .collect())
+    }
+}
+
 #[derive(Debug)]
 pub struct Github {
     name: String,
     base_url: Url,
+    /// Whether the `GITHUB_TOKEN` environment variable may be sent to this instance. Instances
+    /// detected from a remote URL aren't trusted with it, since any host could claim to be one.
+    uses_env_token: bool,
 }
 
 impl Github {
@@ -56,6 +239,7 @@ impl Github {
         Self {
             name: name.into(),
             base_url,
+            uses_env_token: true,
         }
     }
 
@@ -76,10 +260,22 @@ impl Github {
             bail!("not a GitHub URL");
         }
 
-        Ok(Self::new(
-            "GitHub Self-Hosted",
-            Url::parse(&format!("https://{}", host))?,
-        ))
+        Ok(Self {
+            uses_env_token: false,
+            ..Self::new(
+                "GitHub Self-Hosted",
+                Url::parse(&format!("https://{}", host))?,
+            )
+        })
+    }
+
+    /// Returns the URL of the REST API of this instance.
+    pub fn api_url(&self) -> Url {
+        if self.base_url.host_str() == Some("github.com") {
+            Url::parse("https://api.github.com/").unwrap()
+        } else {
+            self.base_url.join("api/v3/").unwrap()
+        }
     }
 
     async fn fetch_github_commit_author(
@@ -235,15 +431,197 @@ impl GitHostingProvider for Github {
             .transpose()?;
         Ok(avatar_url)
     }
+
+    fn api_client(
+        &self,
+        http_client: Arc<dyn HttpClient>,
+        token: Option<String>,
+    ) -> Option<Arc<dyn GitHostingProviderApi>> {
+        let token = token.or_else(|| {
+            self.uses_env_token
+                .then(|| std::env::var("GITHUB_TOKEN").ok())
+                .flatten()
+        });
+        Some(Arc::new(GithubApi::new(self.api_url(), http_client, token)))
+    }
+
+    fn pull_request_ref(&self, number: u32) -> Option<String> {
+        Some(format!("refs/pull/{number}/head"))
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use http_client::{FakeHttpClient, Response};
     use indoc::indoc;
     use pretty_assertions::assert_eq;
 
     use super::*;
 
+    fn fake_github_api() -> GithubApi {
+        let http_client = FakeHttpClient::create(|request| async move {
+            let uri = request.uri();
+            assert_eq!(
+                request
+                    .headers()
+                    .get("Authorization")
+                    .and_then(|value| value.to_str().ok()),
+                Some("Bearer the-token")
+            );
+            let body = match (uri.path(), uri.query().unwrap_or_default()) {
+                ("/repos/zed-industries/zed/pulls", "state=open&per_page=100") => {
+                    return Ok(Response::builder()
+                        .status(200)
+                        .header(
+                            "Link",
+                            r#"<http://127.0.0.1:8080/repos/zed-industries/zed/pulls?state=open&per_page=100&page=2>; rel="next""#,
+                        )
+                        .body(
+                            indoc! {r#"
+                                [{
+                                    "number": 42,
+                                    "title": "Add a feature",
+                                    "html_url": "https://github.com/zed-industries/zed/pull/42",
+                                    "user": {"login": "octocat"},
+                                    "head": {"ref": "some-branch", "sha": "abc123"},
+                                    "base": {"ref": "main", "sha": "def456"},
+                                    "draft": true
+                                }]
+                            "#}
+                            .into(),
+                        )?);
+                }
+                ("/repos/zed-industries/zed/pulls", "state=open&per_page=100&page=2") => {
+                    indoc! {r#"
+                    [{
+                        "number": 41,
+                        "title": "Fix a bug",
+                        "html_url": "https://github.com/zed-industries/zed/pull/41",
+                        "user": null,
+                        "head": {"ref": "other-branch", "sha": "fed987"},
+                        "base": {"ref": "main", "sha": "def456"},
+                        "draft": false
+                    }]
+                "#}
+                }
+                (
+                    "/repos/zed-industries/zed/pulls",
+                    "state=open&per_page=100&head=zed-industries%3Asome-branch",
+                ) => indoc! {r#"
+                    [{
+                        "number": 42,
+                        "title": "Add a feature",
+                        "html_url": "https://github.com/zed-industries/zed/pull/42",
+                        "user": {"login": "octocat"},
+                        "head": {"ref": "some-branch", "sha": "abc123"},
+                        "base": {"ref": "main", "sha": "def456"},
+                        "draft": true
+                    }]
+                "#},
+                ("/repos/zed-industries/zed/commits/abc123/status", _) => {
+                    r#"{"state": "success", "total_count": 1}"#
+                }
+                ("/repos/zed-industries/zed/commits/abc123/check-runs", _) => indoc! {r#"
+                    {"check_runs": [
+                        {"status": "completed", "conclusion": "skipped"},
+                        {"status": "in_progress", "conclusion": null}
+                    ]}
+                "#},
+                ("/repos/zed-industries/zed/pulls/42/comments", "per_page=100") => indoc! {r#"
+                    [{
+                        "id": 7,
+                        "user": {"login": "reviewer"},
+                        "body": "Nit: rename this",
+                        "path": "src/main.rs",
+                        "line": 12,
+                        "html_url": "https://github.com/zed-industries/zed/pull/42#discussion_r7"
+                    }, {
+                        "id": 8,
+                        "user": null,
+                        "body": "Outdated",
+                        "path": "src/lib.rs",
+                        "line": null,
+                        "html_url": null
+                    }]
+                "#},
+                _ => return Ok(Response::builder().status(404).body(Default::default())?),
+            };
+            Ok(Response::builder().status(200).body(body.into())?)
+        });
+        GithubApi::new(
+            Url::parse("http://127.0.0.1:8080/").unwrap(),
+            http_client,
+            Some("the-token".into()),
+        )
+    }
+
+    #[gpui::test]
+    async fn test_github_api() {
+        let remote = ParsedGitRemote {
+            owner: "zed-industries".into(),
+            repo: "zed".into(),
+        };
+        let api = fake_github_api();
+
+        let pull_requests = api
+            .pull_requests(&remote, Some("some-branch"))
+            .await
+            .unwrap();
+        assert_eq!(
+            pull_requests,
+            vec![PullRequestDetails {
+                number: 42,
+                title: "Add a feature".into(),
+                url: Url::parse("https://github.com/zed-industries/zed/pull/42").unwrap(),
+                author: Some("octocat".into()),
+                head_branch: "some-branch".into(),
+                base_branch: "main".into(),
+                head_sha: Some("abc123".into()),
+                is_draft: true,
+            }]
+        );
+
+        let all_pull_requests = api.pull_requests(&remote, None).await.unwrap();
+        assert_eq!(
+            all_pull_requests
+                .iter()
+                .map(|pull_request| pull_request.number)
+                .collect::<Vec<_>>(),
+            vec![42, 41]
+        );
+
+        assert_eq!(
+            api.ci_status(&remote, "abc123").await.unwrap(),
+            Some(CiStatus::Pending)
+        );
+        assert!(api.ci_status(&remote, "unknown").await.is_err());
+
+        let comments = api.review_comments(&remote, 42).await.unwrap();
+        assert_eq!(
+            comments
+                .iter()
+                .map(|comment| (comment.id, comment.path.as_str(), comment.line))
+                .collect::<Vec<_>>(),
+            vec![(7, "src/main.rs", Some(12)), (8, "src/lib.rs", None)]
+        );
+        assert_eq!(comments[0].author, Some("reviewer".into()));
+    }
+
+    #[test]
+    fn test_github_api_url() {
+        assert_eq!(
+            Github::public_instance().api_url().as_str(),
+            "https://api.github.com/"
+        );
+        assert_eq!(
+            Github::from_remote_url("git@github.my-enterprise.com:zed-industries/zed.git")
+                .unwrap()
+                .api_url()
+                .as_str(),
+            "https://github.my-enterprise.com/api/v3/"
+        );
+    }
+
     #[test]
     fn test_invalid_self_hosted_remote_url() {
         let remote_url = "git@github.com:zed-industries/zed.git";
@@ -262,6 +640,9 @@ mod tests {
             github.base_url,
             Url::parse("https://github.my-enterprise.com").unwrap()
         );
+        // Hosts that merely look like GitHub aren't trusted with the token from the environment.
+        assert!(!github.uses_env_token);
+        assert!(Github::public_instance().uses_env_token);
     }
 
     #[test]
@@ -1,19 +1,199 @@
 use std::str::FromStr;
+use std::sync::Arc;
 
 use anyhow::{Result, bail};
+use async_trait::async_trait;
+use http_client::HttpClient;
+use serde::Deserialize;
 use url::Url;
 
 use git::{
-    BuildCommitPermalinkParams, BuildPermalinkParams, GitHostingProvider, ParsedGitRemote,
-    RemoteUrl,
+    BuildCommitPermalinkParams, BuildPermalinkParams, CiStatus, GitHostingProvider,
+    GitHostingProviderApi, ParsedGitRemote, PullRequestDetails, RemoteUrl, ReviewComment,
 };
 
+use crate::api::{get_json, get_json_pages};
 use crate::get_host_from_git_remote_url;
 
+#[derive(Debug, Deserialize)]
+struct ApiUser {
+    username: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiMergeRequest {
+    iid: u32,
+    title: String,
+    web_url: String,
+    author: Option<ApiUser>,
+    source_branch: String,
+    target_branch: String,
+    sha: Option<String>,
+    #[serde(default)]
+    draft: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiCommit {
+    last_pipeline: Option<ApiPipeline>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiPipeline {
+    status: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiDiscussion {
+    notes: Vec<ApiNote>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiNote {
+    id: u64,
+    body: String,
+    author: Option<ApiUser>,
+    #[serde(default)]
+    system: bool,
+    position: Option<ApiNotePosition>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiNotePosition {
+    new_path: String,
+    new_line: Option<u32>,
+}
+
+/// A client for the REST API of GitLab.
+pub struct GitlabApi {
+    api_url: Url,
+    http_client: Arc<dyn HttpClient>,
+    token: Option<String>,
+}
+
+impl GitlabApi {
+    pub fn new(api_url: Url, http_client: Arc<dyn HttpClient>, token: Option<String>) -> Self {
+        Self {
+            api_url,
+            http_client,
+            token,
+        }
+    }
+
+    fn url(&self, remote: &ParsedGitRemote, path: &str) -> Result<Url> {
+        let ParsedGitRemote { owner, repo } = remote;
+        let mut url = self.api_url.join("projects/")?;
+        // The project is identified by its URL-encoded path, which may contain subgroups.
+        url.path_segments_mut()
+            .map_err(|_| anyhow::anyhow!("invalid GitLab API URL"))?
+            .pop_if_empty()
+            .push(&format!("{owner}/{repo}"))
+            .extend(path.split('/'));
+        Ok(url)
+    }
+
+    fn headers(&self) -> Vec<(&'static str, String)> {
+        let mut headers = Vec::new();
+        if let Some(token) = &self.token {
+            headers.push(("PRIVATE-TOKEN", token.clone()));
+        }
+        headers
+    }
+
+    async fn get<T: serde::de::DeserializeOwned>(&self, url: Url) -> Result<T> {
+        get_json(&self.http_client, &url, &self.headers()).await
+    }
+
+    async fn get_pages<T: serde::de::DeserializeOwned>(&self, url: Url) -> Result<Vec<T>> {
+        get_json_pages(&self.http_client, &url, &self.headers()).await
+    }
+}
+
+#[async_trait]
+impl GitHostingProviderApi for GitlabApi {
+    async fn pull_requests(
+        &self,
+        remote: &ParsedGitRemote,
+        head_branch: Option<&str>,
+    ) -> Result<Vec<PullRequestDetails>> {
+        let mut url = self.url(remote, "merge_requests")?;
+        url.query_pairs_mut()
+            .append_pair("state", "opened")
+            .append_pair("per_page", "100");
+        if let Some(head_branch) = head_branch {
+            url.query_pairs_mut()
+                .append_pair("source_branch", head_branch);
+        }
+
+        self.get_pages::<ApiMergeRequest>(url)
+            .await?
+            .into_iter()
+            .map(|merge_request| {
+                Ok(PullRequestDetails {
+                    number: merge_request.iid,
+                    title: merge_request.title.into(),
+                    url: Url::parse(&merge_request.web_url)?,
+                    author: merge_request.author.map(|user| user.username.into()),
+                    head_branch: merge_request.source_branch.into(),
+                    base_branch: merge_request.target_branch.into(),
+                    head_sha: merge_request.sha.map(Into::into),
+                    is_draft: merge_request.draft,
+                })
+            })
+            .collect()
+    }
+
+    async fn ci_status(&self, remote: &ParsedGitRemote, sha: &str) -> Result<Option<CiStatus>> {
+        let commit = self
+            .get::<ApiCommit>(self.url(remote, &format!("repository/commits/{sha}"))?)
+            .await?;
+        Ok(commit
+            .last_pipeline
+            .and_then(|pipeline| match pipeline.status.as_str() {
+                "success" => Some(CiStatus::Success),
+                "failed" | "canceled" => Some(CiStatus::Failure),
+                "skipped" => None,
+                _ => Some(CiStatus::Pending),
+            }))
+    }
+
+    async fn review_comments(
+        &self,
+        remote: &ParsedGitRemote,
+        number: u32,
+    ) -> Result<Vec<ReviewComment>> {
+        let mut url = self.url(remote, &format!("merge_requests/{number}/discussions"))?;
+        url.query_pairs_mut().append_pair("per_page", "100");
+
+        Ok(self
+            .get_pages::<ApiDiscussion>(url)
+            .await?
+            .into_iter()
+            .flat_map(|discussion| discussion.notes)
+            .filter(|note| !note.system)
+            .filter_map(|note| {
+                // Only notes on the diff have a position; the others are general comments.
+                let position = note.position?;
+                Some(ReviewComment {
+                    id: note.id,
+                    author: note.author.map(|user| user.username.into()),
+                    body: note.body.into(),
+                    path: position.new_path,
+                    line: position.new_line,
+                    url: None,
+                })
+            })
+            .collect())
+    }
+}
+
 #[derive(Debug)]
 pub struct Gitlab {
     name: String,
     base_url: Url,
+    /// Whether the `GITLAB_TOKEN` environment variable may be sent to this instance. Instances
+    /// detected from a remote URL aren't trusted with it, since any host could claim to be one.
+    uses_env_token: bool,
 }
 
 impl Gitlab {
@@ -21,6 +201,7 @@ impl Gitlab {
         Self {
             name: name.into(),
             base_url,
+            uses_env_token: true,
         }
     }
 
@@ -41,10 +222,18 @@ impl Gitlab {
             bail!("not a GitLab URL");
         }
 
-        Ok(Self::new(
-            "GitLab Self-Hosted",
-            Url::parse(&format!("https://{}", host))?,
-        ))
+        Ok(Self {
+            uses_env_token: false,
+            ..Self::new(
+                "GitLab Self-Hosted",
+                Url::parse(&format!("https://{}", host))?,
+            )
+        })
+    }
+
+    /// Returns the URL of the REST API of this instance.
+    pub fn api_url(&self) -> Url {
+        self.base_url.join("api/v4/").unwrap()
     }
 }
 
@@ -122,14 +311,136 @@ impl GitHostingProvider for Gitlab {
         );
         permalink
     }
+
+    fn api_client(
+        &self,
+        http_client: Arc<dyn HttpClient>,
+        token: Option<String>,
+    ) -> Option<Arc<dyn GitHostingProviderApi>> {
+        let token = token.or_else(|| {
+            self.uses_env_token
+                .then(|| std::env::var("GITLAB_TOKEN").ok())
+                .flatten()
+        });
+        Some(Arc::new(GitlabApi::new(self.api_url(), http_client, token)))
+    }
+
+    fn pull_request_ref(&self, number: u32) -> Option<String> {
+        Some(format!("refs/merge-requests/{number}/head"))
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use http_client::{FakeHttpClient, Response};
+    use indoc::indoc;
     use pretty_assertions::assert_eq;
 
     use super::*;
 
+    #[gpui::test]
+    async fn test_gitlab_api() {
+        let http_client = FakeHttpClient::create(|request| async move {
+            let uri = request.uri();
+            assert_eq!(
+                request
+                    .headers()
+                    .get("PRIVATE-TOKEN")
+                    .and_then(|value| value.to_str().ok()),
+                Some("the-token")
+            );
+            let body = match (uri.path(), uri.query().unwrap_or_default()) {
+                (
+                    "/api/v4/projects/group%2Fsubgroup%2Fproject/merge_requests",
+                    "state=opened&per_page=100&source_branch=some-branch",
+                ) => indoc! {r#"
+                    [{
+                        "iid": 3,
+                        "title": "Add a feature",
+                        "web_url": "https://gitlab.com/group/subgroup/project/-/merge_requests/3",
+                        "author": {"username": "tanuki"},
+                        "source_branch": "some-branch",
+                        "target_branch": "main",
+                        "sha": "abc123",
+                        "draft": false
+                    }]
+                "#},
+                ("/api/v4/projects/group%2Fsubgroup%2Fproject/repository/commits/abc123", _) => {
+                    r#"{"last_pipeline": {"status": "failed"}}"#
+                }
+                (
+                    "/api/v4/projects/group%2Fsubgroup%2Fproject/merge_requests/3/discussions",
+                    "per_page=100",
+                ) => indoc! {r#"
+                    [{
+                        "notes": [
+                            {"id": 1, "body": "assigned to @tanuki", "author": null, "system": true, "position": null},
+                            {"id": 2, "body": "Looks good overall", "author": {"username": "reviewer"}, "system": false, "position": null}
+                        ]
+                    }, {
+                        "notes": [
+                            {
+                                "id": 3,
+                                "body": "Nit: rename this",
+                                "author": {"username": "reviewer"},
+                                "system": false,
+                                "position": {"new_path": "src/main.rs", "new_line": 12}
+                            }
+                        ]
+                    }]
+                "#},
+                _ => return Ok(Response::builder().status(404).body(Default::default())?),
+            };
+            Ok(Response::builder().status(200).body(body.into())?)
+        });
+        let api = GitlabApi::new(
+            Url::parse("http://127.0.0.1:8080/api/v4/").unwrap(),
+            http_client,
+            Some("the-token".into()),
+        );
+        let remote = ParsedGitRemote {
+            owner: "group/subgroup".into(),
+            repo: "project".into(),
+        };
+
+        let merge_requests = api
+            .pull_requests(&remote, Some("some-branch"))
+            .await
+            .unwrap();
+        assert_eq!(
+            merge_requests,
+            vec![PullRequestDetails {
+                number: 3,
+                title: "Add a feature".into(),
+                url: Url::parse("https://gitlab.com/group/subgroup/project/-/merge_requests/3")
+                    .unwrap(),
+                author: Some("tanuki".into()),
+                head_branch: "some-branch".into(),
+                base_branch: "main".into(),
+                head_sha: Some("abc123".into()),
+                is_draft: false,
+            }]
+        );
+
+        assert_eq!(
+            api.ci_status(&remote, "abc123").await.unwrap(),
+            Some(CiStatus::Failure)
+        );
+
+        let comments = api.review_comments(&remote, 3).await.unwrap();
+        assert_eq!(
+            comments,
+            vec![ReviewComment {
+                id: 3,
+                author: Some("reviewer".into()),
+                body: "Nit: rename this".into(),
+                path: "src/main.rs".into(),
+                line: Some(12),
+                url: None,
+            }]
+        );
+    }
+
     #[test]
     fn test_invalid_self_hosted_remote_url() {
         let remote_url = "https://gitlab.com/zed-industries/zed.git";
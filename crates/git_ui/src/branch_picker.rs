@@ -1,22 +1,25 @@
 use anyhow::{Context as _, anyhow};
 use fuzzy::StringMatchCandidate;
 
-use git::repository::Branch;
+use git::repository::{Branch, Tag};
 use gpui::{
     App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, InteractiveElement,
-    IntoElement, Modifiers, ModifiersChangedEvent, ParentElement, Render, SharedString, Styled,
-    Subscription, Task, Window, rems,
+    IntoElement, Modifiers, ModifiersChangedEvent, ParentElement, PromptLevel, Render,
+    SharedString, Styled, Subscription, Task, Window, rems,
 };
 use picker::{Picker, PickerDelegate, PickerEditorPosition};
 use project::git_store::Repository;
 use std::sync::Arc;
+use theme::ActiveTheme;
 use time::OffsetDateTime;
 use time_format::format_local_timestamp;
-use ui::{HighlightedLabel, ListItem, ListItemSpacing, prelude::*};
+use ui::{HighlightedLabel, ListItem, ListItemSpacing, ToggleButton, Tooltip, prelude::*};
 use util::ResultExt;
 use workspace::notifications::DetachAndPromptErr;
 use workspace::{ModalView, Workspace};
 
+use crate::git_panel::GitPanel;
+
 pub fn register(workspace: &mut Workspace) {
     workspace.register_action(open);
     workspace.register_action(switch);
@@ -89,11 +92,19 @@ impl BranchList {
         let all_branches_request = repository
             .clone()
             .map(|repository| repository.read(cx).branches());
+        let all_tags_request = repository
+            .clone()
+            .map(|repository| repository.read(cx).tags());
 
         cx.spawn_in(window, async move |this, cx| {
             let mut all_branches = all_branches_request
                 .context("No active repository")?
                 .await??;
+            // Tags are secondary, so failing to list them shouldn't hide the branches.
+            let all_tags = match all_tags_request {
+                Some(request) => request.await?.log_err().unwrap_or_default(),
+                None => Vec::new(),
+            };
 
             all_branches.sort_by_key(|branch| {
                 branch
@@ -105,6 +116,7 @@ impl BranchList {
             this.update_in(cx, |this, window, cx| {
                 this.picker.update(cx, |picker, cx| {
                     picker.delegate.all_branches = Some(all_branches);
+                    picker.delegate.all_tags = all_tags;
                     picker.refresh(window, cx);
                 })
             })?;
@@ -162,9 +174,43 @@ impl Render for BranchList {
     }
 }
 
+/// The kinds of refs listed by the picker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RefFilter {
+    All,
+    Branches,
+    Tags,
+}
+
+#[derive(Debug, Clone)]
+enum GitRef {
+    Branch(Branch),
+    Tag(Tag),
+}
+
+impl GitRef {
+    fn name(&self) -> &SharedString {
+        match self {
+            GitRef::Branch(branch) => &branch.name,
+            GitRef::Tag(tag) => &tag.name,
+        }
+    }
+
+    /// The unix timestamp of the ref's last update, used to list recent refs first.
+    fn timestamp(&self) -> Option<i64> {
+        match self {
+            GitRef::Branch(branch) => branch
+                .most_recent_commit
+                .as_ref()
+                .map(|commit| commit.commit_timestamp),
+            GitRef::Tag(tag) => Some(tag.timestamp),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 struct BranchEntry {
-    branch: Branch,
+    reference: GitRef,
     positions: Vec<usize>,
     is_new: bool,
 }
@@ -172,6 +218,8 @@ struct BranchEntry {
 pub struct BranchListDelegate {
     matches: Vec<BranchEntry>,
     all_branches: Option<Vec<Branch>>,
+    all_tags: Vec<Tag>,
+    filter: RefFilter,
     repo: Option<Entity<Repository>>,
     style: BranchListStyle,
     selected_index: usize,
@@ -186,12 +234,137 @@ impl BranchListDelegate {
             repo,
             style,
             all_branches: None,
+            all_tags: Vec::new(),
+            filter: RefFilter::Branches,
             selected_index: 0,
             last_query: Default::default(),
             modifiers: Default::default(),
         }
     }
 
+    /// The refs matching the current filter, most recently updated first.
+    fn filtered_refs(&self) -> Option<Vec<GitRef>> {
+        let branches = self
+            .all_branches
+            .as_ref()?
+            .iter()
+            .cloned()
+            .map(GitRef::Branch);
+        let tags = self.all_tags.iter().cloned().map(GitRef::Tag);
+        Some(match self.filter {
+            RefFilter::Branches => branches.collect(),
+            RefFilter::Tags => tags.collect(),
+            RefFilter::All => {
+                let mut refs = branches.chain(tags).collect::<Vec<_>>();
+                refs.sort_by_key(|reference| reference.timestamp().map(|timestamp| 0 - timestamp));
+                refs
+            }
+        })
+    }
+
+    fn create_tag(
+        &self,
+        name: SharedString,
+        message: Option<SharedString>,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) {
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        cx.spawn(async move |_, cx| {
+            cx.update(|cx| {
+                repo.read(cx).create_tag(
+                    name.to_string(),
+                    None,
+                    message.map(|message| message.to_string()),
+                )
+            })?
+            .await??;
+            Ok(())
+        })
+        .detach_and_prompt_err("Failed to create tag", window, cx, |e, _, _| {
+            Some(e.to_string())
+        });
+        cx.emit(DismissEvent);
+    }
+
+    /// Checks out the tag in detached HEAD state.
+    fn checkout_tag(
+        &self,
+        name: SharedString,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) {
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        let is_checked_out = repo
+            .read(cx)
+            .detached_head
+            .as_ref()
+            .is_some_and(|detached_head| detached_head.tag.as_ref() == Some(&name));
+        if !is_checked_out {
+            cx.spawn(async move |_, cx| {
+                cx.update(|cx| repo.read(cx).checkout_tag(name.to_string()))?
+                    .await??;
+                Ok(())
+            })
+            .detach_and_prompt_err(
+                "Failed to check out tag",
+                window,
+                cx,
+                |e, _, _| Some(e.to_string()),
+            );
+        }
+        cx.emit(DismissEvent);
+    }
+
+    fn delete_tag(
+        &mut self,
+        name: SharedString,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) {
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        let prompt = window.prompt(
+            PromptLevel::Warning,
+            &format!("Delete the tag {name}?"),
+            Some("The tag is only deleted locally, not from any remote."),
+            &["Delete", "Cancel"],
+            cx,
+        );
+        cx.spawn_in(window, async move |picker, cx| {
+            if prompt.await != Ok(0) {
+                return anyhow::Ok(());
+            }
+            cx.update(|_, cx| repo.read(cx).delete_tag(name.to_string()))?
+                .await??;
+            picker.update_in(cx, |picker, window, cx| {
+                picker.delegate.all_tags.retain(|tag| tag.name != name);
+                picker.refresh(window, cx);
+            })?;
+            anyhow::Ok(())
+        })
+        .detach_and_prompt_err("Failed to delete tag", window, cx, |e, _, _| {
+            Some(e.to_string())
+        });
+    }
+
+    fn push_tag(&self, name: SharedString, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(panel) = window
+            .root::<Workspace>()
+            .flatten()
+            .and_then(|workspace| workspace.read(cx).panel::<GitPanel>(cx))
+        else {
+            return;
+        };
+        panel.update(cx, |panel, cx| panel.push_tag(name, window, cx));
+        cx.emit(DismissEvent);
+    }
+
     fn create_branch(
         &self,
         new_branch_name: SharedString,
@@ -219,7 +392,11 @@ impl PickerDelegate for BranchListDelegate {
     type ListItem = ListItem;
 
     fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
-        "Select branch...".into()
+        match self.filter {
+            RefFilter::Branches => "Select branch...".into(),
+            RefFilter::Tags => "Select tag...".into(),
+            RefFilter::All => "Select branch or tag...".into(),
+        }
     }
 
     fn editor_position(&self) -> PickerEditorPosition {
@@ -252,27 +429,28 @@ impl PickerDelegate for BranchListDelegate {
         window: &mut Window,
         cx: &mut Context<Picker<Self>>,
     ) -> Task<()> {
-        let Some(all_branches) = self.all_branches.clone() else {
+        let Some(all_refs) = self.filtered_refs() else {
             return Task::ready(());
         };
+        let filter = self.filter;
 
         const RECENT_BRANCHES_COUNT: usize = 10;
         cx.spawn_in(window, async move |picker, cx| {
             let mut matches: Vec<BranchEntry> = if query.is_empty() {
-                all_branches
+                all_refs
                     .into_iter()
                     .take(RECENT_BRANCHES_COUNT)
-                    .map(|branch| BranchEntry {
-                        branch,
+                    .map(|reference| BranchEntry {
+                        reference,
                         positions: Vec::new(),
                         is_new: false,
                     })
                     .collect()
             } else {
-                let candidates = all_branches
+                let candidates = all_refs
                     .iter()
                     .enumerate()
-                    .map(|(ix, command)| StringMatchCandidate::new(ix, &command.name.clone()))
+                    .map(|(ix, reference)| StringMatchCandidate::new(ix, reference.name()))
                     .collect::<Vec<StringMatchCandidate>>();
                 fuzzy::match_strings(
                     &candidates,
@@ -286,7 +464,7 @@ impl PickerDelegate for BranchListDelegate {
                 .iter()
                 .cloned()
                 .map(|candidate| BranchEntry {
-                    branch: all_branches[candidate.candidate_id].clone(),
+                    reference: all_refs[candidate.candidate_id].clone(),
                     positions: candidate.positions,
                     is_new: false,
                 })
@@ -294,19 +472,37 @@ impl PickerDelegate for BranchListDelegate {
             };
             picker
                 .update(cx, |picker, _| {
+                    // Tag names can't contain whitespace, so anything after the name is the
+                    // message of an annotated tag.
+                    let new_name = match filter {
+                        RefFilter::Tags => query.split_whitespace().next().unwrap_or_default(),
+                        RefFilter::All | RefFilter::Branches => query.as_str(),
+                    };
                     #[allow(clippy::nonminimal_bool)]
-                    if !query.is_empty()
+                    if !new_name.is_empty()
                         && !matches
                             .first()
-                            .is_some_and(|entry| entry.branch.name == query)
+                            .is_some_and(|entry| entry.reference.name() == new_name)
                     {
-                        matches.push(BranchEntry {
-                            branch: Branch {
+                        let reference = match filter {
+                            RefFilter::Tags => GitRef::Tag(Tag {
+                                name: new_name.to_string().into(),
+                                sha: SharedString::default(),
+                                message: query
+                                    .trim()
+                                    .split_once(char::is_whitespace)
+                                    .map(|(_, message)| message.trim().to_string().into()),
+                                timestamp: 0,
+                            }),
+                            RefFilter::All | RefFilter::Branches => GitRef::Branch(Branch {
                                 name: query.clone().into(),
                                 is_head: false,
                                 upstream: None,
                                 most_recent_commit: None,
-                            },
+                            }),
+                        };
+                        matches.push(BranchEntry {
+                            reference,
                             positions: Vec::new(),
                             is_new: true,
                         })
@@ -329,8 +525,20 @@ impl PickerDelegate for BranchListDelegate {
         let Some(entry) = self.matches.get(self.selected_index()) else {
             return;
         };
+        let branch = match &entry.reference {
+            GitRef::Branch(branch) => branch.clone(),
+            GitRef::Tag(tag) => {
+                let tag = tag.clone();
+                if entry.is_new {
+                    self.create_tag(tag.name, tag.message, window, cx);
+                } else {
+                    self.checkout_tag(tag.name, window, cx);
+                }
+                return;
+            }
+        };
         if entry.is_new {
-            self.create_branch(entry.branch.name.clone(), window, cx);
+            self.create_branch(branch.name, window, cx);
             return;
         }
 
@@ -342,39 +550,36 @@ impl PickerDelegate for BranchListDelegate {
 
         if current_branch
             .flatten()
-            .is_some_and(|current_branch| current_branch == entry.branch.name)
+            .is_some_and(|current_branch| current_branch == branch.name)
         {
             cx.emit(DismissEvent);
             return;
         }
 
-        cx.spawn_in(window, {
-            let branch = entry.branch.clone();
-            async move |picker, cx| {
-                let branch_change_task = picker.update(cx, |this, cx| {
-                    let repo = this
-                        .delegate
-                        .repo
-                        .as_ref()
-                        .ok_or_else(|| anyhow!("No active repository"))?
-                        .clone();
-
-                    let cx = cx.to_async();
-
-                    anyhow::Ok(async move {
-                        cx.update(|cx| repo.read(cx).change_branch(branch.name.to_string()))?
-                            .await?
-                    })
-                })??;
-
-                branch_change_task.await?;
+        cx.spawn_in(window, async move |picker, cx| {
+            let branch_change_task = picker.update(cx, |this, cx| {
+                let repo = this
+                    .delegate
+                    .repo
+                    .as_ref()
+                    .ok_or_else(|| anyhow!("No active repository"))?
+                    .clone();
 
-                picker.update(cx, |_, cx| {
-                    cx.emit(DismissEvent);
+                let cx = cx.to_async();
 
-                    anyhow::Ok(())
+                anyhow::Ok(async move {
+                    cx.update(|cx| repo.read(cx).change_branch(branch.name.to_string()))?
+                        .await?
                 })
-            }
+            })??;
+
+            branch_change_task.await?;
+
+            picker.update(cx, |_, cx| {
+                cx.emit(DismissEvent);
+
+                anyhow::Ok(())
+            })
         })
         .detach_and_prompt_err("Failed to change branch", window, cx, |_, _, _| None);
     }
@@ -396,22 +601,48 @@ impl PickerDelegate for BranchListDelegate {
     ) -> Option<Self::ListItem> {
         let entry = &self.matches[ix];
 
-        let (commit_time, subject) = entry
-            .branch
-            .most_recent_commit
-            .as_ref()
-            .map(|commit| {
-                let subject = commit.subject.clone();
-                let commit_time = OffsetDateTime::from_unix_timestamp(commit.commit_timestamp)
+        let commit_time = entry
+            .reference
+            .timestamp()
+            .filter(|_| !entry.is_new)
+            .map(|timestamp| {
+                let commit_time = OffsetDateTime::from_unix_timestamp(timestamp)
                     .unwrap_or_else(|_| OffsetDateTime::now_utc());
-                let formatted_time = format_local_timestamp(
+                format_local_timestamp(
                     commit_time,
                     OffsetDateTime::now_utc(),
                     time_format::TimestampFormat::Relative,
-                );
-                (Some(formatted_time), Some(subject))
+                )
+            });
+
+        let title = match (&entry.reference, entry.is_new) {
+            (GitRef::Branch(branch), true) => {
+                Label::new(format!("Create branch \"{}\"…", branch.name))
+                    .single_line()
+                    .into_any_element()
+            }
+            (GitRef::Tag(tag), true) => Label::new(if tag.message.is_some() {
+                format!("Create annotated tag \"{}\"…", tag.name)
+            } else {
+                format!("Create tag \"{}\"…", tag.name)
             })
-            .unwrap_or_else(|| (None, None));
+            .single_line()
+            .into_any_element(),
+            (reference, false) => {
+                HighlightedLabel::new(reference.name().clone(), entry.positions.clone())
+                    .truncate()
+                    .into_any_element()
+            }
+        };
+
+        let icon = match &entry.reference {
+            GitRef::Branch(_) => IconName::GitBranchSmall,
+            GitRef::Tag(_) => IconName::Hash,
+        };
+        let tag_name = match &entry.reference {
+            GitRef::Tag(tag) if !entry.is_new => Some(tag.name.clone()),
+            _ => None,
+        };
 
         Some(
             ListItem::new(SharedString::from(format!("vcs-menu-{ix}")))
@@ -422,6 +653,9 @@ impl PickerDelegate for BranchListDelegate {
                 })
                 .spacing(ListItemSpacing::Sparse)
                 .toggle_state(selected)
+                .when(self.filter == RefFilter::All, |this| {
+                    this.start_slot(Icon::new(icon).size(IconSize::Small).color(Color::Muted))
+                })
                 .child(
                     v_flex()
                         .w_full()
@@ -432,23 +666,7 @@ impl PickerDelegate for BranchListDelegate {
                                 .overflow_x_hidden()
                                 .gap_2()
                                 .justify_between()
-                                .child(div().flex_shrink().overflow_x_hidden().child(
-                                    if entry.is_new {
-                                        Label::new(format!(
-                                            "Create branch \"{}\"…",
-                                            entry.branch.name
-                                        ))
-                                        .single_line()
-                                        .into_any_element()
-                                    } else {
-                                        HighlightedLabel::new(
-                                            entry.branch.name.clone(),
-                                            entry.positions.clone(),
-                                        )
-                                        .truncate()
-                                        .into_any_element()
-                                    },
-                                ))
+                                .child(div().flex_shrink().overflow_x_hidden().child(title))
                                 .when_some(commit_time, |el, commit_time| {
                                     el.child(
                                         Label::new(commit_time)
@@ -460,18 +678,46 @@ impl PickerDelegate for BranchListDelegate {
                         )
                         .when(self.style == BranchListStyle::Modal, |el| {
                             el.child(div().max_w_96().child({
-                                let message = if entry.is_new {
-                                    if let Some(current_branch) =
-                                        self.repo.as_ref().and_then(|repo| {
-                                            repo.read(cx).branch.as_ref().map(|b| b.name.clone())
-                                        })
-                                    {
-                                        format!("based off {}", current_branch)
-                                    } else {
-                                        "based off the current branch".to_string()
+                                let message = match (&entry.reference, entry.is_new) {
+                                    (GitRef::Branch(_), true) => {
+                                        if let Some(current_branch) =
+                                            self.repo.as_ref().and_then(|repo| {
+                                                repo.read(cx)
+                                                    .branch
+                                                    .as_ref()
+                                                    .map(|b| b.name.clone())
+                                            })
+                                        {
+                                            format!("based off {}", current_branch)
+                                        } else {
+                                            "based off the current branch".to_string()
+                                        }
                                     }
-                                } else {
-                                    subject.unwrap_or("no commits found".into()).to_string()
+                                    (GitRef::Tag(tag), true) => match &tag.message {
+                                        Some(message) => {
+                                            format!("at HEAD, with message \"{message}\"")
+                                        }
+                                        None => {
+                                            "at HEAD; type a message after the name to annotate it"
+                                                .to_string()
+                                        }
+                                    },
+                                    (GitRef::Branch(branch), false) => branch
+                                        .most_recent_commit
+                                        .as_ref()
+                                        .map_or("no commits found".to_string(), |commit| {
+                                            commit.subject.to_string()
+                                        }),
+                                    (GitRef::Tag(tag), false) => match &tag.message {
+                                        Some(message) => message.to_string(),
+                                        None => format!(
+                                            "lightweight tag at {}",
+                                            tag.sha
+                                                .chars()
+                                                .take(git::SHORT_SHA_LENGTH)
+                                                .collect::<String>()
+                                        ),
+                                    },
                                 };
                                 Label::new(message)
                                     .size(LabelSize::Small)
@@ -479,7 +725,64 @@ impl PickerDelegate for BranchListDelegate {
                                     .color(Color::Muted)
                             }))
                         }),
-                ),
+                )
+                .when_some(tag_name, |this, tag_name| {
+                    this.end_hover_slot(
+                        h_flex()
+                            .gap_1()
+                            .child(
+                                IconButton::new(("push-tag", ix), IconName::ArrowUp)
+                                    .icon_size(IconSize::Small)
+                                    .icon_color(Color::Muted)
+                                    .tooltip(Tooltip::text("Push Tag"))
+                                    .on_click(cx.listener({
+                                        let tag_name = tag_name.clone();
+                                        move |picker, _, window, cx| {
+                                            picker.delegate.push_tag(tag_name.clone(), window, cx)
+                                        }
+                                    })),
+                            )
+                            .child(
+                                IconButton::new(("delete-tag", ix), IconName::Trash)
+                                    .icon_size(IconSize::Small)
+                                    .icon_color(Color::Muted)
+                                    .tooltip(Tooltip::text("Delete Tag"))
+                                    .on_click(cx.listener(move |picker, _, window, cx| {
+                                        picker.delegate.delete_tag(tag_name.clone(), window, cx)
+                                    })),
+                            ),
+                    )
+                }),
+        )
+    }
+
+    fn render_footer(
+        &self,
+        _window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Option<AnyElement> {
+        let filter_button = |id: &'static str, label: &'static str, filter: RefFilter| {
+            ToggleButton::new(id, label)
+                .style(ButtonStyle::Filled)
+                .size(ButtonSize::Compact)
+                .toggle_state(self.filter == filter)
+                .on_click(cx.listener(move |picker, _, window, cx| {
+                    picker.delegate.filter = filter;
+                    picker.refresh_placeholder(window, cx);
+                    picker.refresh(window, cx);
+                }))
+        };
+        Some(
+            h_flex()
+                .w_full()
+                .p_1p5()
+                .justify_end()
+                .border_t_1()
+                .border_color(cx.theme().colors().border_variant)
+                .child(filter_button("filter-branches", "Branches", RefFilter::Branches).first())
+                .child(filter_button("filter-tags", "Tags", RefFilter::Tags).middle())
+                .child(filter_button("filter-all", "All", RefFilter::All).last())
+                .into_any(),
         )
     }
 
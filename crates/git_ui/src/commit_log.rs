@@ -0,0 +1,740 @@
+use crate::commit_view::CommitView;
+use buffer_diff::BufferDiffSnapshot;
+use editor::Editor;
+use git::{
+    FileHistory, LineHistory, ViewHistory,
+    repository::{CommitSummary, LogEntry, LogFilter, RepoPath},
+};
+use gpui::{
+    AnyElement, App, Entity, EventEmitter, FocusHandle, Focusable, Hsla, PathBuilder, Pixels,
+    Point, Subscription, Task, UniformListScrollHandle, WeakEntity, canvas, fill, point, px, size,
+    uniform_list,
+};
+use language::{Anchor, Point as BufferPoint, TextBufferSnapshot};
+use project::git_store::{Repository, RepositoryEvent};
+use std::ops::Range;
+use theme::{AccentColors, ActiveTheme};
+use time::OffsetDateTime;
+use time_format::format_local_timestamp;
+use ui::{ListItem, Tooltip, prelude::*};
+use util::ResultExt as _;
+use workspace::{
+    Workspace,
+    item::{Item, ItemEvent, TabContentParams},
+};
+
+/// The number of commits loaded at a time.
+const PAGE_SIZE: usize = 200;
+/// The next page is loaded once fewer than this many commits are left below the visible ones.
+const LOAD_MORE_THRESHOLD: usize = 50;
+const LANE_WIDTH: Pixels = px(14.);
+const ROW_HEIGHT: Pixels = px(28.);
+
+pub(crate) fn register(workspace: &mut Workspace) {
+    workspace.register_action(|workspace, _: &ViewHistory, window, cx| {
+        let Some(repository) = workspace.project().read(cx).active_repository(cx) else {
+            return;
+        };
+        CommitLog::open(LogFilter::Head, repository, workspace, window, cx);
+    });
+
+    workspace.register_action(|workspace, action: &FileHistory, window, cx| {
+        let project = workspace.project().read(cx);
+        let git_store = project.git_store().read(cx);
+        let target = if let Some(abs_path) = &action.path {
+            project
+                .find_project_path(abs_path, cx)
+                .and_then(|project_path| {
+                    let is_dir = project
+                        .entry_for_path(&project_path, cx)
+                        .is_some_and(|entry| entry.is_dir());
+                    let (repository, path) =
+                        git_store.repository_and_path_for_project_path(&project_path, cx)?;
+                    Some((repository, path, is_dir))
+                })
+        } else {
+            workspace
+                .active_item_as::<Editor>(cx)
+                .and_then(|editor| editor.read(cx).active_excerpt(cx))
+                .and_then(|(_, buffer, _)| {
+                    git_store.repository_and_path_for_buffer_id(buffer.read(cx).remote_id(), cx)
+                })
+                .map(|(repository, path)| (repository, path, false))
+        };
+        let Some((repository, path, is_dir)) = target else {
+            return;
+        };
+
+        // `--follow` only works for a single file.
+        let filter = LogFilter::Path {
+            path,
+            follow: !is_dir,
+        };
+        CommitLog::open(filter, repository, workspace, window, cx);
+    });
+
+    workspace.register_action(|workspace, _: &LineHistory, window, cx| {
+        let Some(editor) = workspace.active_item_as::<Editor>(cx) else {
+            return;
+        };
+        let selection = editor.update(cx, |editor, cx| editor.selections.newest::<BufferPoint>(cx));
+        let multibuffer = editor.read(cx).buffer().read(cx);
+        let snapshot = multibuffer.snapshot(cx);
+        let Some((buffer, start, _)) = snapshot.point_to_buffer_point(selection.start) else {
+            return;
+        };
+        let Some((_, mut end, _)) = snapshot.point_to_buffer_point(selection.end) else {
+            return;
+        };
+        if end.column == 0 && end.row > start.row {
+            end.row -= 1;
+        }
+        let Some(buffer) = multibuffer.buffer(buffer.remote_id()) else {
+            return;
+        };
+        let Some((repository, path)) = workspace
+            .project()
+            .read(cx)
+            .git_store()
+            .read(cx)
+            .repository_and_path_for_buffer_id(buffer.read(cx).remote_id(), cx)
+        else {
+            return;
+        };
+
+        // `git log -L` takes the lines as they are at HEAD, so the selected rows of the working
+        // copy are mapped through its diff against HEAD.
+        let diff = workspace.project().update(cx, |project, cx| {
+            project.open_uncommitted_diff(buffer.clone(), cx)
+        });
+        cx.spawn_in(window, async move |workspace, cx| {
+            let diff = diff.await?;
+            let Some((start, end)) = diff.read_with(cx, |diff, cx| {
+                if !diff.base_text_exists() {
+                    return None;
+                }
+                let diff = diff.snapshot(cx);
+                let buffer = buffer.read(cx).text_snapshot();
+                let start = row_in_head(&diff, &buffer, start.row);
+                let end = row_in_head(&diff, &buffer, end.row).max(start);
+                Some((start, end))
+            })?
+            else {
+                return Ok(());
+            };
+
+            workspace.update_in(cx, |workspace, window, cx| {
+                let filter = LogFilter::Lines {
+                    path,
+                    start: start + 1,
+                    end: end + 1,
+                };
+                CommitLog::open(filter, repository, workspace, window, cx);
+            })
+        })
+        .detach_and_log_err(cx);
+    });
+}
+
+/// Maps a row of the buffer to the row it corresponds to at HEAD, given the buffer's diff against
+/// HEAD. Added rows map to the first row of what they replaced, or to the row they were inserted
+/// before.
+fn row_in_head(diff: &BufferDiffSnapshot, buffer: &TextBufferSnapshot, row: u32) -> u32 {
+    let base_text = diff.base_text();
+    let max_point = base_text.max_point();
+    let last_row = if max_point.column == 0 {
+        max_point.row.saturating_sub(1)
+    } else {
+        max_point.row
+    };
+    // Hunk ranges end at the start of the row following them, unless they end the text.
+    let end_row = |end: BufferPoint| if end.column > 0 { end.row + 1 } else { end.row };
+
+    let mut head_row = row;
+    for hunk in diff.hunks_intersecting_range(Anchor::MIN..Anchor::MAX, buffer) {
+        let start_row = hunk.range.start.row;
+        if start_row > row {
+            break;
+        }
+        let hunk_end_row = end_row(hunk.range.end);
+        let base_start_row = base_text
+            .offset_to_point(hunk.diff_base_byte_range.start)
+            .row;
+        let base_end_row = end_row(base_text.offset_to_point(hunk.diff_base_byte_range.end));
+        if row < hunk_end_row {
+            head_row = base_start_row
+                + (row - start_row).min(base_end_row.saturating_sub(base_start_row + 1));
+            break;
+        }
+        head_row = base_end_row + (row - hunk_end_row);
+    }
+    head_row.min(last_row)
+}
+
+/// A row of the commit graph. Lanes are the columns that lines of history are drawn in.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct GraphRow {
+    /// The lane of the row's commit.
+    lane: usize,
+    /// Lanes that pass by the commit, from the top to the bottom of the row.
+    pass_through: Vec<usize>,
+    /// Lanes that end at the commit, because it's their parent.
+    incoming: Vec<usize>,
+    /// Lanes that lead from the commit to its parents.
+    outgoing: Vec<usize>,
+}
+
+/// Assigns lanes to commits in topological order, newest first.
+#[derive(Default)]
+struct GraphBuilder {
+    /// The commit that each lane is waiting for.
+    lanes: Vec<Option<SharedString>>,
+}
+
+impl GraphBuilder {
+    fn push(&mut self, entry: &LogEntry) -> GraphRow {
+        let sha = Some(&entry.sha);
+        let lane = self
+            .lanes
+            .iter()
+            .position(|waiting_for| waiting_for.as_ref() == sha)
+            .unwrap_or_else(|| self.free_lane());
+
+        let mut pass_through = Vec::new();
+        let mut incoming = Vec::new();
+        for (ix, waiting_for) in self.lanes.iter_mut().enumerate() {
+            if waiting_for.as_ref() == sha {
+                incoming.push(ix);
+                *waiting_for = None;
+            } else if waiting_for.is_some() {
+                pass_through.push(ix);
+            }
+        }
+        if lane >= self.lanes.len() {
+            self.lanes.resize(lane + 1, None);
+        }
+
+        let mut outgoing = Vec::new();
+        for (ix, parent) in entry.parent_shas.iter().enumerate() {
+            if let Some(existing) = self
+                .lanes
+                .iter()
+                .position(|waiting_for| waiting_for.as_ref() == Some(parent))
+            {
+                outgoing.push(existing);
+                continue;
+            }
+            let target = if ix == 0 && self.lanes[lane].is_none() {
+                lane
+            } else {
+                let free = self.free_lane();
+                if free >= self.lanes.len() {
+                    self.lanes.push(None);
+                }
+                free
+            };
+            self.lanes[target] = Some(parent.clone());
+            outgoing.push(target);
+        }
+
+        while self.lanes.last().is_some_and(|lane| lane.is_none()) {
+            self.lanes.pop();
+        }
+
+        GraphRow {
+            lane,
+            pass_through,
+            incoming,
+            outgoing,
+        }
+    }
+
+    fn free_lane(&self) -> usize {
+        self.lanes
+            .iter()
+            .position(|waiting_for| waiting_for.is_none())
+            .unwrap_or(self.lanes.len())
+    }
+}
+
+/// A paginated list of the commits in a repository, or of those that changed a file or some of its lines.
+pub struct CommitLog {
+    repository: Entity<Repository>,
+    workspace: WeakEntity<Workspace>,
+    filter: LogFilter,
+    entries: Vec<LogEntry>,
+    /// Only commits from HEAD are in topological order, so other filters don't have a graph.
+    graph: Option<(GraphBuilder, Vec<GraphRow>)>,
+    graph_width: usize,
+    head_sha: Option<SharedString>,
+    has_more: bool,
+    load_task: Option<Task<()>>,
+    scroll_handle: UniformListScrollHandle,
+    focus_handle: FocusHandle,
+    _subscription: Subscription,
+}
+
+impl CommitLog {
+    pub fn open(
+        filter: LogFilter,
+        repository: Entity<Repository>,
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let existing = workspace.items_of_type::<Self>(cx).find(|log| {
+            let log = log.read(cx);
+            log.filter == filter && log.repository == repository
+        });
+        if let Some(existing) = existing {
+            workspace.activate_item(&existing, true, true, window, cx);
+            return;
+        }
+
+        let workspace_handle = workspace.weak_handle();
+        let log = cx.new(|cx| Self::new(filter, repository, workspace_handle, cx));
+        workspace.add_item_to_active_pane(Box::new(log), None, true, window, cx);
+    }
+
+    fn new(
+        filter: LogFilter,
+        repository: Entity<Repository>,
+        workspace: WeakEntity<Workspace>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let subscription = cx.subscribe(&repository, |this, repository, event, cx| {
+            if let RepositoryEvent::Updated { .. } = event {
+                let head_sha = Self::head_sha(&repository, cx);
+                if head_sha != this.head_sha {
+                    this.head_sha = head_sha;
+                    this.reload(cx);
+                }
+            }
+        });
+
+        let mut this = Self {
+            head_sha: Self::head_sha(&repository, cx),
+            repository,
+            workspace,
+            graph: None,
+            filter,
+            entries: Vec::new(),
+            graph_width: 0,
+            has_more: true,
+            load_task: None,
+            scroll_handle: UniformListScrollHandle::new(),
+            focus_handle: cx.focus_handle(),
+            _subscription: subscription,
+        };
+        this.reload(cx);
+        this
+    }
+
+    fn head_sha(repository: &Entity<Repository>, cx: &App) -> Option<SharedString> {
+        repository
+            .read(cx)
+            .branch
+            .as_ref()
+            .and_then(|branch| branch.most_recent_commit.as_ref())
+            .map(|commit| commit.sha.clone())
+    }
+
+    fn reload(&mut self, cx: &mut Context<Self>) {
+        self.entries.clear();
+        self.graph = (self.filter == LogFilter::Head).then(Default::default);
+        self.graph_width = 0;
+        self.has_more = true;
+        self.load_task = None;
+        self.load_more(cx);
+    }
+
+    fn load_more(&mut self, cx: &mut Context<Self>) {
+        if self.load_task.is_some() || !self.has_more {
+            return;
+        }
+
+        let page = self
+            .repository
+            .read(cx)
+            .log(self.filter.clone(), self.entries.len(), PAGE_SIZE);
+        self.load_task = Some(cx.spawn(async move |this, cx| {
+            let page = page.await.log_err().and_then(|page| page.log_err());
+            this.update(cx, |this, cx| {
+                this.load_task = None;
+                let Some(page) = page else {
+                    this.has_more = false;
+                    cx.notify();
+                    return;
+                };
+                this.has_more = page.len() == PAGE_SIZE;
+                if let Some((builder, rows)) = &mut this.graph {
+                    for entry in &page {
+                        let row = builder.push(entry);
+                        let row_width = row
+                            .pass_through
+                            .iter()
+                            .chain(&row.incoming)
+                            .chain(&row.outgoing)
+                            .chain([&row.lane])
+                            .max()
+                            .map_or(0, |lane| lane + 1);
+                        this.graph_width = this.graph_width.max(row_width);
+                        rows.push(row);
+                    }
+                }
+                this.entries.extend(page);
+                cx.notify();
+            })
+            .ok();
+        }));
+    }
+
+    fn open_commit(&mut self, ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(entry) = self.entries.get(ix) else {
+            return;
+        };
+        let commit = CommitSummary {
+            sha: entry.sha.clone(),
+            subject: entry.subject.clone(),
+            commit_timestamp: entry.commit_timestamp,
+            has_parent: !entry.parent_shas.is_empty(),
+        };
+        CommitView::open(
+            commit,
+            self.repository.downgrade(),
+            self.workspace.clone(),
+            window,
+            cx,
+        );
+    }
+
+    fn title(&self) -> SharedString {
+        match &self.filter {
+            LogFilter::Head => "History".into(),
+            LogFilter::Path { path, .. } => format!("History: {}", file_name(path)).into(),
+            LogFilter::Lines { path, start, end } => {
+                format!("History: {}:{start}-{end}", file_name(path)).into()
+            }
+        }
+    }
+
+    fn render_entries(
+        &mut self,
+        range: Range<usize>,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Vec<AnyElement> {
+        if range.end + LOAD_MORE_THRESHOLD >= self.entries.len() {
+            self.load_more(cx);
+        }
+
+        let accents = cx.theme().accents().clone();
+        let now = OffsetDateTime::now_utc();
+        range
+            .filter_map(|ix| {
+                let entry = self.entries.get(ix)?;
+                let graph = self.graph.as_ref().and_then(|(_, rows)| {
+                    let row = rows.get(ix)?.clone();
+                    Some(render_graph_row(row, self.graph_width, accents.clone()))
+                });
+                let commit_time = OffsetDateTime::from_unix_timestamp(entry.commit_timestamp)
+                    .unwrap_or_else(|_| OffsetDateTime::now_utc());
+                let formatted_time = format_local_timestamp(
+                    commit_time,
+                    now,
+                    time_format::TimestampFormat::Relative,
+                );
+                let short_sha = entry
+                    .sha
+                    .chars()
+                    .take(git::SHORT_SHA_LENGTH)
+                    .collect::<String>();
+
+                Some(
+                    ListItem::new(("commit", ix))
+                        .child(
+                            h_flex()
+                                .h(ROW_HEIGHT)
+                                .w_full()
+                                .gap_2()
+                                .children(graph)
+                                .child(
+                                    Label::new(short_sha)
+                                        .size(LabelSize::Small)
+                                        .color(Color::Muted)
+                                        .buffer_font(cx),
+                                )
+                                .children(entry.refs.iter().map(|name| {
+                                    div()
+                                        .px_1()
+                                        .rounded_sm()
+                                        .border_1()
+                                        .border_color(cx.theme().colors().border)
+                                        .child(Label::new(name.clone()).size(LabelSize::XSmall))
+                                }))
+                                .child(
+                                    div()
+                                        .flex_1()
+                                        .overflow_hidden()
+                                        .child(Label::new(entry.subject.clone()).truncate()),
+                                )
+                                .child(
+                                    Label::new(entry.author_name.clone())
+                                        .size(LabelSize::Small)
+                                        .color(Color::Muted),
+                                )
+                                .child(
+                                    Label::new(formatted_time)
+                                        .size(LabelSize::Small)
+                                        .color(Color::Muted),
+                                ),
+                        )
+                        .tooltip(Tooltip::text(entry.author_email.clone()))
+                        .on_click(
+                            cx.listener(move |this, _, window, cx| {
+                                this.open_commit(ix, window, cx)
+                            }),
+                        )
+                        .into_any_element(),
+                )
+            })
+            .collect()
+    }
+}
+
+fn file_name(path: &RepoPath) -> String {
+    path.file_name()
+        .unwrap_or(path.as_os_str())
+        .to_string_lossy()
+        .to_string()
+}
+
+fn render_graph_row(row: GraphRow, width: usize, accents: AccentColors) -> impl IntoElement {
+    canvas(
+        |_, _, _| {},
+        move |bounds, _, window, _| {
+            let lane_x = |lane: usize| bounds.left() + LANE_WIDTH * (lane as f32 + 0.5);
+            let lane_color = |lane: usize| accents.color_for_index(lane as u32);
+            let top = bounds.top();
+            let middle = bounds.center().y;
+            let bottom = bounds.bottom();
+            let commit = point(lane_x(row.lane), middle);
+
+            for &lane in &row.pass_through {
+                paint_line(
+                    point(lane_x(lane), top),
+                    point(lane_x(lane), bottom),
+                    lane_color(lane),
+                    window,
+                );
+            }
+            for &lane in &row.incoming {
+                paint_line(point(lane_x(lane), top), commit, lane_color(lane), window);
+            }
+            for &lane in &row.outgoing {
+                paint_line(
+                    commit,
+                    point(lane_x(lane), bottom),
+                    lane_color(lane),
+                    window,
+                );
+            }
+            window.paint_quad(
+                fill(
+                    gpui::Bounds::centered_at(commit, size(px(8.), px(8.))),
+                    lane_color(row.lane),
+                )
+                .corner_radii(px(4.)),
+            );
+        },
+    )
+    .flex_none()
+    .w(LANE_WIDTH * width as f32)
+    .h_full()
+}
+
+fn paint_line(from: Point<Pixels>, to: Point<Pixels>, color: Hsla, window: &mut Window) {
+    let mut builder = PathBuilder::stroke(px(1.5));
+    builder.move_to(from);
+    builder.line_to(to);
+    if let Some(path) = builder.build().log_err() {
+        window.paint_path(path, color);
+    }
+}
+
+impl EventEmitter<ItemEvent> for CommitLog {}
+
+impl Focusable for CommitLog {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for CommitLog {
+    type Event = ItemEvent;
+
+    fn to_item_events(event: &ItemEvent, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::HistoryRerun).color(Color::Muted))
+    }
+
+    fn tab_content(&self, params: TabContentParams, _window: &Window, _: &App) -> AnyElement {
+        Label::new(self.title())
+            .color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn tab_tooltip_text(&self, _: &App) -> Option<SharedString> {
+        Some(self.title())
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("Commit Log Opened")
+    }
+
+    fn clone_on_split(
+        &self,
+        _workspace_id: Option<workspace::WorkspaceId>,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Option<Entity<Self>>
+    where
+        Self: Sized,
+    {
+        Some(cx.new(|cx| {
+            Self::new(
+                self.filter.clone(),
+                self.repository.clone(),
+                self.workspace.clone(),
+                cx,
+            )
+        }))
+    }
+}
+
+impl Render for CommitLog {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let content = if self.entries.is_empty() {
+            let message = if self.has_more {
+                "Loading history…"
+            } else {
+                "No commits"
+            };
+            h_flex()
+                .size_full()
+                .justify_center()
+                .child(Label::new(message).color(Color::Muted))
+                .into_any_element()
+        } else {
+            uniform_list(
+                cx.entity(),
+                "commit-log",
+                self.entries.len(),
+                Self::render_entries,
+            )
+            .size_full()
+            .track_scroll(self.scroll_handle.clone())
+            .into_any_element()
+        };
+
+        v_flex()
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .p_1()
+            .bg(cx.theme().colors().editor_background)
+            .child(content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use buffer_diff::BufferDiff;
+    use language::Buffer;
+
+    fn entry(sha: &str, parents: &[&str]) -> LogEntry {
+        LogEntry {
+            sha: sha.to_string().into(),
+            parent_shas: parents
+                .iter()
+                .map(|parent| parent.to_string().into())
+                .collect(),
+            author_name: "".into(),
+            author_email: "".into(),
+            commit_timestamp: 0,
+            refs: Vec::new(),
+            subject: "".into(),
+        }
+    }
+
+    #[gpui::test]
+    fn test_row_in_head(cx: &mut App) {
+        let head_text = "one\ntwo\nthree\nfour\nfive\n";
+        let text = "zero\none\nTWO\nthree\nfive\nsix\n";
+        let buffer = cx.new(|cx| Buffer::local(text, cx));
+        let diff = BufferDiff::new_with_base_text(head_text, &buffer, cx).snapshot(cx);
+        let buffer = buffer.read(cx).text_snapshot();
+
+        assert_eq!(
+            (0..6)
+                .map(|row| row_in_head(&diff, &buffer, row))
+                .collect::<Vec<_>>(),
+            // "zero" was inserted before "one", "TWO" replaced "two", "four" was deleted, and
+            // "six" was added after the last row.
+            [0, 0, 1, 2, 4, 4]
+        );
+    }
+
+    #[test]
+    fn test_graph_lanes() {
+        // d merges c into b, and both branch off of a.
+        let mut builder = GraphBuilder::default();
+        let rows = [
+            entry("d", &["b", "c"]),
+            entry("c", &["a"]),
+            entry("b", &["a"]),
+            entry("a", &[]),
+        ]
+        .iter()
+        .map(|entry| builder.push(entry))
+        .collect::<Vec<_>>();
+
+        assert_eq!(
+            rows,
+            vec![
+                GraphRow {
+                    lane: 0,
+                    pass_through: vec![],
+                    incoming: vec![],
+                    outgoing: vec![0, 1],
+                },
+                GraphRow {
+                    lane: 1,
+                    pass_through: vec![0],
+                    incoming: vec![1],
+                    outgoing: vec![1],
+                },
+                GraphRow {
+                    lane: 0,
+                    pass_through: vec![1],
+                    incoming: vec![0],
+                    outgoing: vec![1],
+                },
+                GraphRow {
+                    lane: 1,
+                    pass_through: vec![],
+                    incoming: vec![1],
+                    outgoing: vec![],
+                },
+            ]
+        );
+        assert!(builder.lanes.is_empty());
+    }
+}
@@ -2,7 +2,7 @@ use crate::branch_picker::{self, BranchList};
 use crate::git_panel::{GitPanel, commit_message_editor};
 use git::{Commit, GenerateCommitMessage};
 use panel::{panel_button, panel_editor_style, panel_filled_button};
-use ui::{KeybindingHint, PopoverMenu, PopoverMenuHandle, Tooltip, prelude::*};
+use ui::{ContextMenu, KeybindingHint, PopoverMenu, PopoverMenuHandle, Tooltip, prelude::*};
 
 use editor::{Editor, EditorElement};
 use gpui::*;
@@ -58,6 +58,7 @@ pub struct CommitModal {
     restore_dock: RestoreDock,
     properties: ModalContainerProperties,
     branch_list_handle: PopoverMenuHandle<BranchList>,
+    commit_options_handle: PopoverMenuHandle<ContextMenu>,
 }
 
 impl Focusable for CommitModal {
@@ -164,7 +165,9 @@ impl CommitModal {
         let focus_handle = commit_editor.focus_handle(cx);
 
         cx.on_focus_out(&focus_handle, window, |this, _, window, cx| {
-            if !this.branch_list_handle.is_focused(window, cx) {
+            if !this.branch_list_handle.is_focused(window, cx)
+                && !this.commit_options_handle.is_focused(window, cx)
+            {
                 cx.emit(DismissEvent);
             }
         })
@@ -178,6 +181,7 @@ impl CommitModal {
             restore_dock,
             properties,
             branch_list_handle: PopoverMenuHandle::default(),
+            commit_options_handle: PopoverMenuHandle::default(),
         }
     }
 
@@ -215,22 +219,32 @@ impl CommitModal {
     }
 
     pub fn render_footer(&self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
-        let (can_commit, tooltip, commit_label, co_authors, generate_commit_message, active_repo) =
-            self.git_panel.update(cx, |git_panel, cx| {
-                let (can_commit, tooltip) = git_panel.configure_commit_button(cx);
-                let title = git_panel.commit_button_title();
-                let co_authors = git_panel.render_co_authors(cx);
-                let generate_commit_message = git_panel.render_generate_commit_message_button(cx);
-                let active_repo = git_panel.active_repository.clone();
-                (
-                    can_commit,
-                    tooltip,
-                    title,
-                    co_authors,
-                    generate_commit_message,
-                    active_repo,
-                )
-            });
+        let (
+            can_commit,
+            tooltip,
+            commit_label,
+            co_authors,
+            generate_commit_message,
+            commit_options_menu,
+            active_repo,
+        ) = self.git_panel.update(cx, |git_panel, cx| {
+            let (can_commit, tooltip) = git_panel.configure_commit_button(cx);
+            let title = git_panel.commit_button_title();
+            let co_authors = git_panel.render_co_authors(cx);
+            let generate_commit_message = git_panel.render_generate_commit_message_button(cx);
+            let commit_options_menu =
+                git_panel.render_commit_options_menu(self.commit_options_handle.clone(), cx);
+            let active_repo = git_panel.active_repository.clone();
+            (
+                can_commit,
+                tooltip,
+                title,
+                co_authors,
+                generate_commit_message,
+                commit_options_menu,
+                active_repo,
+            )
+        });
 
         let branch = active_repo
             .as_ref()
@@ -313,7 +327,8 @@ impl CommitModal {
                             .child(branch_picker),
                     )
                     .children(generate_commit_message)
-                    .children(co_authors),
+                    .children(co_authors)
+                    .child(commit_options_menu),
             )
             .child(div().flex_1())
             .child(
@@ -366,6 +381,33 @@ impl Render for CommitModal {
                     panel.generate_commit_message(cx);
                 })
             }))
+            .on_action(cx.listener(|this, _: &git::ToggleAmend, window, cx| {
+                this.git_panel.update(cx, |panel, cx| {
+                    let amend = !panel.commit_options().amend;
+                    panel.set_amend(amend, window, cx);
+                })
+            }))
+            .on_action(cx.listener(|this, _: &git::Fixup, window, cx| {
+                this.git_panel.update(cx, |panel, cx| {
+                    panel
+                        .select_fixup_commit(true, window, cx)
+                        .detach_and_log_err(cx);
+                })
+            }))
+            .on_action(
+                cx.listener(|this, action: &git::ToggleSignoff, window, cx| {
+                    this.git_panel.update(cx, |panel, cx| {
+                        panel.toggle_signoff(action, window, cx);
+                    })
+                }),
+            )
+            .on_action(
+                cx.listener(|this, action: &git::ToggleGpgSign, window, cx| {
+                    this.git_panel.update(cx, |panel, cx| {
+                        panel.toggle_gpg_sign(action, window, cx);
+                    })
+                }),
+            )
             .on_action(
                 cx.listener(|this, _: &zed_actions::git::Branch, window, cx| {
                     this.toggle_branch_selector(window, cx);
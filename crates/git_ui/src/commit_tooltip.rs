@@ -1,12 +1,12 @@
-use crate::commit_view::CommitView;
+use crate::commit_view::{CommitView, add_commit_operations};
 use editor::hover_markdown_style;
 use futures::Future;
 use git::blame::BlameEntry;
 use git::repository::CommitSummary;
 use git::{GitRemote, blame::ParsedCommitMessage};
 use gpui::{
-    App, Asset, ClipboardItem, Element, Entity, MouseButton, ParentElement, Render, ScrollHandle,
-    StatefulInteractiveElement, WeakEntity, prelude::*,
+    App, Asset, ClipboardItem, Corner, Element, Entity, MouseButton, ParentElement, Render,
+    ScrollHandle, StatefulInteractiveElement, WeakEntity, prelude::*,
 };
 use markdown::Markdown;
 use project::git_store::Repository;
@@ -15,7 +15,9 @@ use std::hash::Hash;
 use theme::ThemeSettings;
 use time::{OffsetDateTime, UtcOffset};
 use time_format::format_local_timestamp;
-use ui::{Avatar, Divider, IconButtonShape, prelude::*, tooltip_container};
+use ui::{
+    Avatar, ContextMenu, Divider, IconButtonShape, PopoverMenu, prelude::*, tooltip_container,
+};
 use workspace::Workspace;
 
 #[derive(Clone, Debug)]
@@ -217,6 +219,27 @@ impl Render for CommitTooltip {
         let message_max_height = window.line_height() * 12 + (ui_font_size / 0.4);
         let repo = self.repository.clone();
         let workspace = self.workspace.clone();
+        let commit_operations_menu = {
+            let sha = self.commit.sha.clone();
+            let repo = self.repository.downgrade();
+            let workspace = self.workspace.clone();
+            PopoverMenu::new("commit-operations-menu")
+                .trigger(
+                    IconButton::new("commit-operations", IconName::Ellipsis)
+                        .shape(IconButtonShape::Square)
+                        .icon_size(IconSize::Small)
+                        .icon_color(Color::Muted),
+                )
+                .menu(move |window, cx| {
+                    let sha = sha.clone();
+                    let repo = repo.clone();
+                    let workspace = workspace.clone();
+                    Some(ContextMenu::build(window, cx, move |menu, _, _| {
+                        add_commit_operations(menu, sha, repo, workspace)
+                    }))
+                })
+                .anchor(Corner::TopRight)
+        };
         let commit_summary = CommitSummary {
             sha: self.commit.sha.clone(),
             subject: self
@@ -335,7 +358,8 @@ impl Render for CommitTooltip {
                                                         ClipboardItem::new_string(full_sha.clone()),
                                                     )
                                                 }),
-                                        ),
+                                        )
+                                        .child(commit_operations_menu),
                                 ),
                         ),
                 )
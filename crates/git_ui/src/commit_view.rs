@@ -1,10 +1,13 @@
 use anyhow::{Result, anyhow};
 use buffer_diff::{BufferDiff, BufferDiffSnapshot};
 use editor::{Editor, EditorEvent, MultiBuffer};
-use git::repository::{CommitDetails, CommitDiff, CommitSummary, RepoPath};
+use futures::channel::oneshot;
+use git::repository::{
+    CommitDetails, CommitDiff, CommitSummary, RepoPath, SequencerCommand, StashEntry,
+};
 use gpui::{
-    AnyElement, AnyView, App, AppContext as _, AsyncApp, Context, Entity, EventEmitter,
-    FocusHandle, Focusable, IntoElement, Render, WeakEntity, Window,
+    AnyElement, AnyView, App, AppContext as _, AsyncApp, ClipboardItem, Context, Entity,
+    EventEmitter, FocusHandle, Focusable, IntoElement, Render, SharedString, WeakEntity, Window,
 };
 use language::{
     Anchor, Buffer, Capability, DiskState, File, LanguageRegistry, LineEnding, OffsetRangeExt as _,
@@ -19,7 +22,7 @@ use std::{
     path::{Path, PathBuf},
     sync::Arc,
 };
-use ui::{Color, Icon, IconName, Label, LabelCommon as _};
+use ui::{Color, ContextMenu, Icon, IconName, Label, LabelCommon as _};
 use util::{ResultExt, truncate_and_trailoff};
 use workspace::{
     Item, ItemHandle as _, ItemNavHistory, ToolbarItemLocation, Workspace,
@@ -27,6 +30,8 @@ use workspace::{
     searchable::SearchableItemHandle,
 };
 
+use crate::git_panel::GitPanel;
+
 pub struct CommitView {
     commit: CommitDetails,
     editor: Entity<Editor>,
@@ -58,26 +63,67 @@ impl CommitView {
         let commit_diff = repo
             .update(cx, |repo, _| repo.load_commit_diff(commit.sha.to_string()))
             .ok();
-        let commit_details = repo
-            .update(cx, |repo, _| repo.show(commit.sha.to_string()))
-            .ok();
+        Self::open_diff(commit.sha, commit_diff, false, repo, workspace, window, cx);
+    }
+
+    /// Opens a view of the changes saved in a stash entry.
+    pub fn open_stash(
+        entry: StashEntry,
+        repo: WeakEntity<Repository>,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        let stash_diff = repo.update(cx, |repo, _| repo.stash_show(entry.index)).ok();
+        Self::open_diff(entry.sha, stash_diff, true, repo, workspace, window, cx);
+    }
+
+    fn open_diff(
+        sha: SharedString,
+        commit_diff: Option<oneshot::Receiver<Result<CommitDiff>>>,
+        is_stash: bool,
+        repo: WeakEntity<Repository>,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        let commit_details = repo.update(cx, |repo, _| repo.show(sha.to_string())).ok();
+        let tags = if is_stash {
+            None
+        } else {
+            repo.update(cx, |repo, _| repo.tags()).ok()
+        };
 
         window
             .spawn(cx, async move |cx| {
                 let (commit_diff, commit_details) = futures::join!(commit_diff?, commit_details?);
                 let commit_diff = commit_diff.log_err()?.log_err()?;
                 let commit_details = commit_details.log_err()?.log_err()?;
+                let tags = match tags {
+                    Some(tags) => tags.await.ok().and_then(|tags| tags.log_err()),
+                    None => None,
+                };
+                let tags = tags
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter(|tag| tag.sha == commit_details.sha)
+                    .map(|tag| tag.name)
+                    .collect();
                 let repo = repo.upgrade()?;
 
                 workspace
                     .update_in(cx, |workspace, window, cx| {
                         let project = workspace.project();
+                        let weak_workspace = cx.entity().downgrade();
                         let commit_view = cx.new(|cx| {
                             CommitView::new(
                                 commit_details,
                                 commit_diff,
+                                tags,
+                                is_stash,
                                 repo,
                                 project.clone(),
+                                weak_workspace,
                                 window,
                                 cx,
                             )
@@ -87,8 +133,7 @@ impl CommitView {
                         pane.update(cx, |pane, cx| {
                             let ix = pane.items().position(|item| {
                                 let commit_view = item.downcast::<CommitView>();
-                                commit_view
-                                    .map_or(false, |view| view.read(cx).commit.sha == commit.sha)
+                                commit_view.map_or(false, |view| view.read(cx).commit.sha == sha)
                             });
                             if let Some(ix) = ix {
                                 pane.activate_item(ix, true, true, window, cx);
@@ -106,8 +151,11 @@ impl CommitView {
     fn new(
         commit: CommitDetails,
         commit_diff: CommitDiff,
+        tags: Vec<SharedString>,
+        is_stash: bool,
         repository: Entity<Repository>,
         project: Entity<Project>,
+        workspace: WeakEntity<Workspace>,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) -> Self {
@@ -118,6 +166,30 @@ impl CommitView {
                 Editor::for_multibuffer(multibuffer.clone(), Some(project.clone()), window, cx);
             editor.disable_inline_diagnostics();
             editor.set_expand_all_diff_hunks(cx);
+            // Stash entries aren't commits of the history, so they can't be cherry-picked.
+            if !is_stash {
+                let sha = commit.sha.clone();
+                let repository = repository.downgrade();
+                editor.set_custom_context_menu(move |_, _, window, cx| {
+                    let sha = sha.clone();
+                    let repository = repository.clone();
+                    let workspace = workspace.clone();
+                    Some(ContextMenu::build(window, cx, move |menu, _, _| {
+                        let menu = menu
+                            .action("Copy", Box::new(editor::actions::Copy))
+                            .entry("Copy SHA", None, {
+                                let sha = sha.clone();
+                                move |_, cx| {
+                                    cx.write_to_clipboard(ClipboardItem::new_string(
+                                        sha.to_string(),
+                                    ))
+                                }
+                            })
+                            .separator();
+                        add_commit_operations(menu, sha, repository, workspace)
+                    }))
+                });
+            }
             editor
         });
 
@@ -138,7 +210,7 @@ impl CommitView {
                     0,
                     cx.entity_id().as_non_zero_u64().into(),
                     LineEnding::default(),
-                    format_commit(&commit).into(),
+                    format_commit(&commit, &tags).into(),
                 );
                 metadata_buffer_id = Some(buffer.remote_id());
                 Buffer::build(buffer, Some(file.clone()), Capability::ReadWrite)
@@ -215,6 +287,64 @@ impl CommitView {
     }
 }
 
+/// Adds the operations that apply a commit to the current branch of its repository to a menu.
+pub(crate) fn add_commit_operations(
+    menu: ContextMenu,
+    sha: SharedString,
+    repository: WeakEntity<Repository>,
+    workspace: WeakEntity<Workspace>,
+) -> ContextMenu {
+    let apply_commit = |command: SequencerCommand, no_commit: bool| {
+        let sha = sha.clone();
+        let repository = repository.clone();
+        let workspace = workspace.clone();
+        move |window: &mut Window, cx: &mut App| {
+            let Some(repository) = repository.upgrade() else {
+                return;
+            };
+            let Some(panel) = workspace
+                .read_with(cx, |workspace, cx| workspace.panel::<GitPanel>(cx))
+                .ok()
+                .flatten()
+            else {
+                return;
+            };
+            panel.update(cx, |panel, cx| {
+                panel.apply_commits(
+                    repository,
+                    command,
+                    vec![sha.to_string()],
+                    no_commit,
+                    window,
+                    cx,
+                )
+            });
+        }
+    };
+
+    menu.entry(
+        "Cherry-pick Commit",
+        None,
+        apply_commit(SequencerCommand::CherryPick, false),
+    )
+    .entry(
+        "Cherry-pick Without Committing",
+        None,
+        apply_commit(SequencerCommand::CherryPick, true),
+    )
+    .separator()
+    .entry(
+        "Revert Commit",
+        None,
+        apply_commit(SequencerCommand::Revert, false),
+    )
+    .entry(
+        "Revert Without Committing",
+        None,
+        apply_commit(SequencerCommand::Revert, true),
+    )
+}
+
 impl language::File for GitBlob {
     fn as_local(&self) -> Option<&dyn language::LocalFile> {
         None
@@ -369,9 +499,19 @@ async fn build_buffer_diff(
     })
 }
 
-fn format_commit(commit: &CommitDetails) -> String {
+/// Formats the commit like `git show` does, decorating it with the tags that point at it.
+fn format_commit(commit: &CommitDetails, tags: &[SharedString]) -> String {
     let mut result = String::new();
-    writeln!(&mut result, "commit {}", commit.sha).unwrap();
+    write!(&mut result, "commit {}", commit.sha).unwrap();
+    if !tags.is_empty() {
+        let decorations = tags
+            .iter()
+            .map(|tag| format!("tag: {tag}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        write!(&mut result, " ({decorations})").unwrap();
+    }
+    result.push('\n');
     writeln!(
         &mut result,
         "Author: {} <{}>",
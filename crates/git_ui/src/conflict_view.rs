@@ -0,0 +1,866 @@
+use anyhow::Result;
+use collections::{HashMap, HashSet};
+use editor::{
+    Addon, Anchor, Direction, Editor, EditorEvent, EditorMode, MultiBufferSnapshot,
+    display_map::{BlockPlacement, BlockProperties, BlockStyle, CustomBlockId, RenderBlock},
+    scroll::Autoscroll,
+};
+use futures::StreamExt;
+use git::{AcceptBoth, AcceptOurs, AcceptTheirs, GoToConflict, GoToPreviousConflict};
+use gpui::{
+    Action, AnyElement, App, AppContext as _, AsyncWindowContext, Entity, EventEmitter,
+    FocusHandle, Focusable, Render, Subscription, Task, WeakEntity, actions,
+};
+use language::{Buffer, BufferId, Capability, Conflict, ConflictResolution, OffsetRangeExt as _};
+use multi_buffer::{MultiBuffer, PathKey};
+use project::{
+    Project, ProjectPath,
+    git_store::{GitStore, GitStoreEvent, RepositoryEvent},
+};
+use std::{
+    any::{Any, TypeId},
+    ops::Range,
+    sync::Arc,
+    time::Duration,
+};
+use theme::ActiveTheme;
+use ui::prelude::*;
+use util::ResultExt as _;
+use workspace::{
+    ItemNavHistory, Workspace,
+    item::{BreadcrumbText, Item, ItemEvent, TabContentParams},
+    searchable::SearchableItemHandle,
+};
+
+actions!(git, [ResolveConflicts]);
+
+struct ConflictOurs;
+struct ConflictBase;
+struct ConflictTheirs;
+
+/// How long to wait after a buffer is edited before looking for its conflicts again.
+const REFRESH_DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// Highlights the merge conflicts of conflicted files in an editor, and lets the user resolve them.
+struct ConflictAddon {
+    conflicts: Entity<EditorConflicts>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl Addon for ConflictAddon {
+    fn to_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[derive(Default)]
+struct EditorConflicts {
+    /// The conflicts shown in the editor, in multibuffer order.
+    conflicts: Vec<EditorConflict>,
+    /// The conflicts of each conflicted buffer, kept until the buffer is edited so that
+    /// unedited buffers aren't parsed again.
+    buffer_conflicts: HashMap<BufferId, Vec<Conflict>>,
+    block_ids: HashSet<CustomBlockId>,
+    refresh_task: Option<Task<()>>,
+}
+
+#[derive(Clone, PartialEq)]
+struct EditorConflict {
+    buffer: Entity<Buffer>,
+    conflict: Conflict,
+    range: Range<Anchor>,
+    ours: Range<Anchor>,
+    base: Option<Range<Anchor>>,
+}
+
+pub(crate) fn register_editor(
+    editor: &mut Editor,
+    window: Option<&mut Window>,
+    cx: &mut Context<Editor>,
+) {
+    let Some(window) = window else {
+        return;
+    };
+    if editor.mode() != EditorMode::Full {
+        return;
+    }
+    let Some(project) = editor.project.clone() else {
+        return;
+    };
+
+    let git_store = project.read(cx).git_store().clone();
+    let mut subscriptions = vec![
+        cx.subscribe_in(
+            editor.buffer(),
+            window,
+            |editor, _, event, _window, cx| match event {
+                multi_buffer::Event::Edited {
+                    edited_buffer: Some(buffer),
+                    ..
+                } => schedule_refresh(editor, buffer.read(cx).remote_id(), cx),
+                multi_buffer::Event::Edited { .. }
+                | multi_buffer::Event::ExcerptsAdded { .. }
+                | multi_buffer::Event::ExcerptsRemoved { .. }
+                | multi_buffer::Event::ExcerptsExpanded { .. } => refresh_conflicts(editor, cx),
+                _ => {}
+            },
+        ),
+        cx.subscribe_in(
+            &git_store,
+            window,
+            |editor, _, event, _window, cx| match event {
+                GitStoreEvent::RepositoryUpdated(_, RepositoryEvent::Updated { .. }, _)
+                | GitStoreEvent::RepositoryRemoved(_) => refresh_conflicts(editor, cx),
+                _ => {}
+            },
+        ),
+    ];
+
+    let editor_handle = cx.weak_entity();
+    subscriptions.push(editor.register_action({
+        let editor = editor_handle.clone();
+        move |_: &GoToConflict, window, cx| {
+            editor
+                .update(cx, |editor, cx| {
+                    go_to_conflict(editor, Direction::Next, window, cx)
+                })
+                .ok();
+        }
+    }));
+    subscriptions.push(editor.register_action({
+        let editor = editor_handle.clone();
+        move |_: &GoToPreviousConflict, window, cx| {
+            editor
+                .update(cx, |editor, cx| {
+                    go_to_conflict(editor, Direction::Prev, window, cx)
+                })
+                .ok();
+        }
+    }));
+    subscriptions.push(register_resolution::<AcceptOurs>(
+        editor,
+        ConflictResolution::Ours,
+        cx,
+    ));
+    subscriptions.push(register_resolution::<AcceptTheirs>(
+        editor,
+        ConflictResolution::Theirs,
+        cx,
+    ));
+    subscriptions.push(register_resolution::<AcceptBoth>(
+        editor,
+        ConflictResolution::Both,
+        cx,
+    ));
+
+    editor.register_addon(ConflictAddon {
+        conflicts: cx.new(|_| EditorConflicts::default()),
+        _subscriptions: subscriptions,
+    });
+    refresh_conflicts(editor, cx);
+}
+
+fn register_resolution<A: Action>(
+    editor: &mut Editor,
+    resolution: ConflictResolution,
+    cx: &mut Context<Editor>,
+) -> Subscription {
+    let editor_handle = cx.weak_entity();
+    editor.register_action(move |_: &A, _window, cx| {
+        editor_handle
+            .update(cx, |editor, cx| {
+                resolve_conflict_at_cursor(editor, resolution, cx)
+            })
+            .ok();
+    })
+}
+
+/// Forgets the conflicts of an edited buffer, and refreshes the conflicts once the edits stop.
+fn schedule_refresh(editor: &mut Editor, buffer_id: BufferId, cx: &mut Context<Editor>) {
+    let Some(state) = editor
+        .addon::<ConflictAddon>()
+        .map(|addon| addon.conflicts.clone())
+    else {
+        return;
+    };
+    let refresh_task = cx.spawn(async move |editor, cx| {
+        cx.background_executor().timer(REFRESH_DEBOUNCE).await;
+        editor
+            .update(cx, |editor, cx| refresh_conflicts(editor, cx))
+            .ok();
+    });
+    state.update(cx, |state, _| {
+        state.buffer_conflicts.remove(&buffer_id);
+        state.refresh_task = Some(refresh_task);
+    });
+}
+
+/// Recomputes the conflicts of the editor's conflicted buffers, and redraws their highlights and buttons.
+///
+/// Only the buffers edited since the last refresh are parsed again.
+fn refresh_conflicts(editor: &mut Editor, cx: &mut Context<Editor>) {
+    let Some(state) = editor
+        .addon::<ConflictAddon>()
+        .map(|addon| addon.conflicts.clone())
+    else {
+        return;
+    };
+    let Some(git_store) = editor
+        .project
+        .as_ref()
+        .map(|project| project.read(cx).git_store().clone())
+    else {
+        return;
+    };
+
+    let mut cached_buffer_conflicts =
+        state.update(cx, |state, _| std::mem::take(&mut state.buffer_conflicts));
+    let mut buffer_conflicts = HashMap::default();
+    let mut conflicts = Vec::new();
+    let multibuffer = editor.buffer().read(cx);
+    let snapshot = multibuffer.snapshot(cx);
+    for buffer in multibuffer.all_buffers() {
+        let buffer_id = buffer.read(cx).remote_id();
+        let is_conflicted = git_store
+            .read(cx)
+            .repository_and_path_for_buffer_id(buffer_id, cx)
+            .is_some_and(|(repository, repo_path)| repository.read(cx).has_conflict(&repo_path));
+        if !is_conflicted {
+            continue;
+        }
+
+        let buffer_snapshot = buffer.read(cx).snapshot();
+        let conflicts_in_buffer = cached_buffer_conflicts
+            .remove(&buffer_id)
+            .unwrap_or_else(|| buffer_snapshot.conflicts());
+        for (excerpt_id, excerpt_range) in multibuffer.excerpts_for_buffer(buffer_id, cx) {
+            for conflict in &conflicts_in_buffer {
+                let context = &excerpt_range.context;
+                if conflict
+                    .range
+                    .end
+                    .cmp(&context.start, &buffer_snapshot)
+                    .is_lt()
+                    || conflict
+                        .range
+                        .start
+                        .cmp(&context.end, &buffer_snapshot)
+                        .is_gt()
+                {
+                    continue;
+                }
+                let anchor_range = |range: &Range<language::Anchor>| {
+                    Some(
+                        snapshot.anchor_in_excerpt(excerpt_id, range.start)?
+                            ..snapshot.anchor_in_excerpt(excerpt_id, range.end)?,
+                    )
+                };
+                let (Some(range), Some(ours)) =
+                    (anchor_range(&conflict.range), anchor_range(&conflict.ours))
+                else {
+                    continue;
+                };
+                conflicts.push(EditorConflict {
+                    buffer: buffer.clone(),
+                    conflict: conflict.clone(),
+                    range,
+                    ours,
+                    base: conflict.base.as_ref().and_then(anchor_range),
+                });
+            }
+        }
+        buffer_conflicts.insert(buffer_id, conflicts_in_buffer);
+    }
+    conflicts.sort_by(|a, b| a.range.start.cmp(&b.range.start, &snapshot));
+
+    let unchanged = state.update(cx, |state, _| {
+        state.buffer_conflicts = buffer_conflicts;
+        state.conflicts == conflicts
+    });
+    if unchanged {
+        return;
+    }
+
+    let old_block_ids = state.update(cx, |state, _| std::mem::take(&mut state.block_ids));
+    editor.remove_blocks(old_block_ids, None, cx);
+    editor.clear_row_highlights::<ConflictOurs>();
+    editor.clear_row_highlights::<ConflictBase>();
+    editor.clear_row_highlights::<ConflictTheirs>();
+
+    let colors = cx.theme().colors();
+    let ours_color = colors.version_control_added.opacity(0.2);
+    let base_color = colors.version_control_conflict.opacity(0.2);
+    let theirs_color = colors.version_control_modified.opacity(0.2);
+    for conflict in &conflicts {
+        // The marker lines are highlighted along with the side that follows them.
+        editor.highlight_rows::<ConflictOurs>(
+            conflict.range.start..conflict.ours.end,
+            ours_color,
+            false,
+            cx,
+        );
+        let theirs_start = if let Some(base) = &conflict.base {
+            editor.highlight_rows::<ConflictBase>(
+                conflict.ours.end..base.end,
+                base_color,
+                false,
+                cx,
+            );
+            base.end
+        } else {
+            conflict.ours.end
+        };
+        editor.highlight_rows::<ConflictTheirs>(
+            theirs_start..conflict.range.end,
+            theirs_color,
+            false,
+            cx,
+        );
+    }
+
+    let blocks = conflicts
+        .iter()
+        .map(|conflict| BlockProperties {
+            placement: BlockPlacement::Above(conflict.range.start),
+            height: 1,
+            style: BlockStyle::Sticky,
+            render: render_conflict_buttons(conflict.buffer.clone(), conflict.conflict.clone()),
+            priority: 0,
+        })
+        .collect::<Vec<_>>();
+    let block_ids = editor.insert_blocks(blocks, None, cx);
+
+    state.update(cx, |state, _| {
+        state.conflicts = conflicts;
+        state.block_ids = block_ids.into_iter().collect();
+    });
+}
+
+fn render_conflict_buttons(buffer: Entity<Buffer>, conflict: Conflict) -> RenderBlock {
+    Arc::new(move |cx| {
+        let button = |label: &'static str, resolution: ConflictResolution| {
+            let buffer = buffer.clone();
+            let conflict = conflict.clone();
+            Button::new(label, label)
+                .label_size(LabelSize::Small)
+                .style(ButtonStyle::Subtle)
+                .on_click(move |_, _, cx| {
+                    buffer.update(cx, |buffer, cx| {
+                        buffer.resolve_conflict(&conflict, resolution, cx)
+                    });
+                })
+        };
+
+        h_flex()
+            .id(cx.block_id)
+            .h(cx.line_height)
+            .pl(cx.gutter_dimensions.full_width())
+            .gap_1()
+            .child(button("Accept Ours", ConflictResolution::Ours))
+            .child(button("Accept Theirs", ConflictResolution::Theirs))
+            .child(button("Accept Both", ConflictResolution::Both))
+            .into_any_element()
+    })
+}
+
+fn go_to_conflict(
+    editor: &mut Editor,
+    direction: Direction,
+    window: &mut Window,
+    cx: &mut Context<Editor>,
+) {
+    let Some(state) = editor
+        .addon::<ConflictAddon>()
+        .map(|addon| addon.conflicts.clone())
+    else {
+        return;
+    };
+    let snapshot = editor.buffer().read(cx).snapshot(cx);
+    let head = editor.selections.newest_anchor().head();
+    let conflicts = &state.read(cx).conflicts;
+    let target = match direction {
+        Direction::Next => conflicts
+            .iter()
+            .find(|conflict| conflict.range.start.cmp(&head, &snapshot).is_gt())
+            .or(conflicts.first()),
+        Direction::Prev => conflicts
+            .iter()
+            .rev()
+            .find(|conflict| conflict.range.start.cmp(&head, &snapshot).is_lt())
+            .or(conflicts.last()),
+    };
+    let Some(position) = target.map(|conflict| conflict.range.start) else {
+        return;
+    };
+    editor.change_selections(Some(Autoscroll::center()), window, cx, |selections| {
+        selections.select_anchor_ranges([position..position]);
+    });
+}
+
+fn resolve_conflict_at_cursor(
+    editor: &mut Editor,
+    resolution: ConflictResolution,
+    cx: &mut Context<Editor>,
+) {
+    let Some(state) = editor
+        .addon::<ConflictAddon>()
+        .map(|addon| addon.conflicts.clone())
+    else {
+        return;
+    };
+    let snapshot = editor.buffer().read(cx).snapshot(cx);
+    let head = editor.selections.newest_anchor().head();
+    let Some(conflict) = conflict_containing(&state.read(cx).conflicts, head, &snapshot).cloned()
+    else {
+        return;
+    };
+    conflict.buffer.update(cx, |buffer, cx| {
+        buffer.resolve_conflict(&conflict.conflict, resolution, cx)
+    });
+}
+
+fn conflict_containing<'a>(
+    conflicts: &'a [EditorConflict],
+    position: Anchor,
+    snapshot: &MultiBufferSnapshot,
+) -> Option<&'a EditorConflict> {
+    conflicts.iter().find(|conflict| {
+        conflict.range.start.cmp(&position, snapshot).is_le()
+            && conflict.range.end.cmp(&position, snapshot).is_ge()
+    })
+}
+
+/// A multibuffer of the merge conflicts in the active repository. Files are staged
+/// once their last conflict has been resolved.
+pub struct ProjectConflicts {
+    project: Entity<Project>,
+    multibuffer: Entity<MultiBuffer>,
+    editor: Entity<Editor>,
+    git_store: Entity<GitStore>,
+    workspace: WeakEntity<Workspace>,
+    focus_handle: FocusHandle,
+    update_needed: postage::watch::Sender<()>,
+    conflicted_buffers: HashSet<BufferId>,
+    _task: Task<Result<()>>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl ProjectConflicts {
+    pub(crate) fn register(workspace: &mut Workspace) {
+        workspace.register_action(Self::deploy);
+    }
+
+    fn deploy(
+        workspace: &mut Workspace,
+        _: &ResolveConflicts,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        if let Some(existing) = workspace.item_of_type::<Self>(cx) {
+            workspace.activate_item(&existing, true, true, window, cx);
+        } else {
+            let workspace_handle = cx.entity();
+            let project_conflicts =
+                cx.new(|cx| Self::new(workspace.project().clone(), workspace_handle, window, cx));
+            workspace.add_item_to_active_pane(Box::new(project_conflicts), None, true, window, cx);
+        }
+    }
+
+    fn new(
+        project: Entity<Project>,
+        workspace: Entity<Workspace>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let focus_handle = cx.focus_handle();
+        let multibuffer = cx.new(|_| MultiBuffer::new(Capability::ReadWrite));
+        let editor = cx.new(|cx| {
+            let mut editor =
+                Editor::for_multibuffer(multibuffer.clone(), Some(project.clone()), window, cx);
+            editor.disable_inline_diagnostics();
+            editor
+        });
+
+        let git_store = project.read(cx).git_store().clone();
+        let subscriptions = vec![
+            cx.subscribe_in(
+                &git_store,
+                window,
+                move |this, _git_store, event, _window, _cx| match event {
+                    GitStoreEvent::ActiveRepositoryChanged(_)
+                    | GitStoreEvent::RepositoryUpdated(
+                        _,
+                        RepositoryEvent::Updated { .. },
+                        true,
+                    ) => {
+                        *this.update_needed.borrow_mut() = ();
+                    }
+                    _ => {}
+                },
+            ),
+            cx.subscribe_in(
+                &multibuffer,
+                window,
+                |this, _multibuffer, event, window, cx| {
+                    if let multi_buffer::Event::Edited {
+                        edited_buffer: Some(buffer),
+                        ..
+                    } = event
+                    {
+                        this.buffer_edited(buffer, window, cx);
+                    }
+                },
+            ),
+        ];
+
+        let (mut send, recv) = postage::watch::channel::<()>();
+        let worker = window.spawn(cx, {
+            let this = cx.weak_entity();
+            async |cx| Self::handle_status_updates(this, recv, cx).await
+        });
+        *send.borrow_mut() = ();
+
+        Self {
+            project,
+            multibuffer,
+            editor,
+            git_store,
+            workspace: workspace.downgrade(),
+            focus_handle,
+            update_needed: send,
+            conflicted_buffers: HashSet::default(),
+            _task: worker,
+            _subscriptions: subscriptions,
+        }
+    }
+
+    fn load_buffers(
+        &mut self,
+        cx: &mut Context<Self>,
+    ) -> Vec<Task<Result<(PathKey, Entity<Buffer>)>>> {
+        let Some(repo) = self.git_store.read(cx).active_repository() else {
+            self.multibuffer.update(cx, |multibuffer, cx| {
+                multibuffer.clear(cx);
+            });
+            return vec![];
+        };
+
+        let mut previous_paths = self.multibuffer.read(cx).paths().collect::<HashSet<_>>();
+        let mut result = vec![];
+        repo.update(cx, |repo, cx| {
+            for entry in repo.cached_status() {
+                if !repo.has_conflict(&entry.repo_path) {
+                    continue;
+                }
+                let Some(project_path) = repo.repo_path_to_project_path(&entry.repo_path, cx)
+                else {
+                    continue;
+                };
+                let path_key = PathKey::namespaced(0, entry.repo_path.0.clone());
+                previous_paths.remove(&path_key);
+
+                let load_buffer = self
+                    .project
+                    .update(cx, |project, cx| project.open_buffer(project_path, cx));
+                result.push(cx.spawn(async move |_, _| Ok((path_key, load_buffer.await?))));
+            }
+        });
+        self.multibuffer.update(cx, |multibuffer, cx| {
+            for path in previous_paths {
+                multibuffer.remove_excerpts_for_path(path, cx);
+            }
+        });
+        result
+    }
+
+    fn register_buffer(
+        &mut self,
+        path_key: PathKey,
+        buffer: Entity<Buffer>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let snapshot = buffer.read(cx).snapshot();
+        let conflict_ranges = snapshot
+            .conflicts()
+            .into_iter()
+            .map(|conflict| conflict.range.to_point(&snapshot))
+            .collect::<Vec<_>>();
+        if conflict_ranges.is_empty() {
+            self.conflicted_buffers.remove(&snapshot.remote_id());
+            self.multibuffer.update(cx, |multibuffer, cx| {
+                multibuffer.remove_excerpts_for_path(path_key, cx);
+            });
+            return;
+        }
+
+        self.conflicted_buffers.insert(snapshot.remote_id());
+        let was_empty = self.multibuffer.update(cx, |multibuffer, cx| {
+            let was_empty = multibuffer.is_empty();
+            multibuffer.set_excerpts_for_path(
+                path_key,
+                buffer,
+                conflict_ranges,
+                editor::DEFAULT_MULTIBUFFER_CONTEXT,
+                cx,
+            );
+            was_empty
+        });
+        if was_empty {
+            self.editor.update(cx, |editor, cx| {
+                editor.change_selections(None, window, cx, |selections| {
+                    selections.select_ranges([0..0])
+                });
+            });
+        }
+    }
+
+    /// Saves and stages a file once its last conflict has been resolved.
+    fn buffer_edited(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let buffer_id = buffer.read(cx).remote_id();
+        if !self.conflicted_buffers.contains(&buffer_id)
+            || !buffer.read(cx).snapshot().conflicts().is_empty()
+        {
+            return;
+        }
+        self.conflicted_buffers.remove(&buffer_id);
+
+        let Some((repo, repo_path)) = self
+            .git_store
+            .read(cx)
+            .repository_and_path_for_buffer_id(buffer_id, cx)
+        else {
+            return;
+        };
+        let save = self
+            .project
+            .update(cx, |project, cx| project.save_buffer(buffer.clone(), cx));
+        cx.spawn_in(window, async move |_, cx| {
+            save.await?;
+            repo.update(cx, |repo, cx| repo.stage_entries(vec![repo_path], cx))?
+                .await
+        })
+        .detach_and_log_err(cx);
+    }
+
+    async fn handle_status_updates(
+        this: WeakEntity<Self>,
+        mut recv: postage::watch::Receiver<()>,
+        cx: &mut AsyncWindowContext,
+    ) -> Result<()> {
+        while let Some(_) = recv.next().await {
+            let buffers_to_load = this.update(cx, |this, cx| this.load_buffers(cx))?;
+            for buffer_to_load in buffers_to_load {
+                if let Some((path_key, buffer)) = buffer_to_load.await.log_err() {
+                    cx.update(|window, cx| {
+                        this.update(cx, |this, cx| {
+                            this.register_buffer(path_key, buffer, window, cx)
+                        })
+                        .ok();
+                    })?;
+                }
+            }
+            this.update(cx, |_, cx| cx.notify())?;
+        }
+
+        Ok(())
+    }
+}
+
+impl EventEmitter<EditorEvent> for ProjectConflicts {}
+
+impl Focusable for ProjectConflicts {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        if self.multibuffer.read(cx).is_empty() {
+            self.focus_handle.clone()
+        } else {
+            self.editor.focus_handle(cx)
+        }
+    }
+}
+
+impl Item for ProjectConflicts {
+    type Event = EditorEvent;
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::GitBranch).color(Color::Muted))
+    }
+
+    fn to_item_events(event: &EditorEvent, f: impl FnMut(ItemEvent)) {
+        Editor::to_item_events(event, f)
+    }
+
+    fn deactivated(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.editor
+            .update(cx, |editor, cx| editor.deactivated(window, cx));
+    }
+
+    fn navigate(
+        &mut self,
+        data: Box<dyn Any>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        self.editor
+            .update(cx, |editor, cx| editor.navigate(data, window, cx))
+    }
+
+    fn tab_tooltip_text(&self, _: &App) -> Option<SharedString> {
+        Some("Merge Conflicts".into())
+    }
+
+    fn tab_content(&self, params: TabContentParams, _window: &Window, _: &App) -> AnyElement {
+        Label::new("Merge Conflicts")
+            .color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("Merge Conflicts Opened")
+    }
+
+    fn as_searchable(&self, _: &Entity<Self>) -> Option<Box<dyn SearchableItemHandle>> {
+        Some(Box::new(self.editor.clone()))
+    }
+
+    fn for_each_project_item(
+        &self,
+        cx: &App,
+        f: &mut dyn FnMut(gpui::EntityId, &dyn project::ProjectItem),
+    ) {
+        self.editor.for_each_project_item(cx, f)
+    }
+
+    fn is_singleton(&self, _: &App) -> bool {
+        false
+    }
+
+    fn set_nav_history(
+        &mut self,
+        nav_history: ItemNavHistory,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.editor.update(cx, |editor, _| {
+            editor.set_nav_history(Some(nav_history));
+        });
+    }
+
+    fn clone_on_split(
+        &self,
+        _workspace_id: Option<workspace::WorkspaceId>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Option<Entity<Self>>
+    where
+        Self: Sized,
+    {
+        let workspace = self.workspace.upgrade()?;
+        Some(cx.new(|cx| ProjectConflicts::new(self.project.clone(), workspace, window, cx)))
+    }
+
+    fn is_dirty(&self, cx: &App) -> bool {
+        self.multibuffer.read(cx).is_dirty(cx)
+    }
+
+    fn has_conflict(&self, cx: &App) -> bool {
+        self.multibuffer.read(cx).has_conflict(cx)
+    }
+
+    fn can_save(&self, _: &App) -> bool {
+        true
+    }
+
+    fn save(
+        &mut self,
+        format: bool,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        self.editor.save(format, project, window, cx)
+    }
+
+    fn save_as(
+        &mut self,
+        _: Entity<Project>,
+        _: ProjectPath,
+        _window: &mut Window,
+        _: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        unreachable!()
+    }
+
+    fn reload(
+        &mut self,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        self.editor.reload(project, window, cx)
+    }
+
+    fn act_as_type<'a>(
+        &'a self,
+        type_id: TypeId,
+        self_handle: &'a Entity<Self>,
+        _: &'a App,
+    ) -> Option<gpui::AnyView> {
+        if type_id == TypeId::of::<Self>() {
+            Some(self_handle.to_any())
+        } else if type_id == TypeId::of::<Editor>() {
+            Some(self.editor.to_any())
+        } else {
+            None
+        }
+    }
+
+    fn breadcrumb_location(&self, _: &App) -> workspace::ToolbarItemLocation {
+        workspace::ToolbarItemLocation::PrimaryLeft
+    }
+
+    fn breadcrumbs(&self, theme: &theme::Theme, cx: &App) -> Option<Vec<BreadcrumbText>> {
+        self.editor.breadcrumbs(theme, cx)
+    }
+
+    fn added_to_workspace(
+        &mut self,
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.editor.update(cx, |editor, cx| {
+            editor.added_to_workspace(workspace, window, cx)
+        });
+    }
+}
+
+impl Render for ProjectConflicts {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let is_empty = self.multibuffer.read(cx).is_empty();
+
+        div()
+            .track_focus(&self.focus_handle)
+            .key_context(if is_empty {
+                "EmptyPane"
+            } else {
+                "GitConflicts"
+            })
+            .bg(cx.theme().colors().editor_background)
+            .flex()
+            .items_center()
+            .justify_center()
+            .size_full()
+            .when(is_empty, |el| el.child(Label::new("No merge conflicts")))
+            .when(!is_empty, |el| el.child(self.editor.clone()))
+    }
+}
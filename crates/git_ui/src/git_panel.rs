@@ -2,8 +2,10 @@ use crate::askpass_modal::AskPassModal;
 use crate::commit_modal::CommitModal;
 use crate::commit_tooltip::CommitTooltip;
 use crate::commit_view::CommitView;
+use crate::conflict_view::ResolveConflicts;
 use crate::git_panel_settings::StatusStyle;
 use crate::project_diff::{self, Diff, ProjectDiff};
+use crate::pull_requests::{HostedRemote, pull_request_branch_name};
 use crate::remote_output::{self, RemoteAction, SuccessMessage};
 use crate::{branch_picker, picker_prompt, render_remote_button};
 use crate::{
@@ -18,15 +20,20 @@ use editor::{
     Editor, EditorElement, EditorMode, EditorSettings, MultiBuffer, ShowScrollbar,
     scroll::ScrollbarAutoHide,
 };
-use futures::StreamExt as _;
+use futures::{StreamExt as _, channel::oneshot};
 use git::blame::ParsedCommitMessage;
+use git::rebase::RebaseOperation;
 use git::repository::{
-    Branch, CommitDetails, CommitSummary, DiffType, PushOptions, Remote, RemoteCommandOutput,
-    ResetMode, Upstream, UpstreamTracking, UpstreamTrackingStatus,
+    Branch, CommitDetails, CommitOptions, CommitSummary, DiffType, LogFilter, PushOptions, Remote,
+    RemoteCommandOutput, ResetMode, SequencerCommand, StashEntry, StashMode, SubmoduleStatus,
+    Upstream, UpstreamTracking, UpstreamTrackingStatus,
 };
 use git::status::StageStatus;
 use git::{Commit, ToggleStaged, repository::RepoPath, status::FileStatus};
-use git::{ExpandCommitEditor, RestoreTrackedFiles, StageAll, TrashUntrackedFiles, UnstageAll};
+use git::{
+    ExpandCommitEditor, PullRequestDetails, RestoreTrackedFiles, StageAll, StashAll, StashPop,
+    StashStaged, TrashUntrackedFiles, UnstageAll,
+};
 use gpui::{
     Action, Animation, AnimationExt as _, Axis, ClickEvent, Corner, DismissEvent, Entity,
     EventEmitter, FocusHandle, Focusable, KeyContext, ListHorizontalSizingBehavior,
@@ -60,8 +67,8 @@ use std::{collections::HashSet, sync::Arc, time::Duration, usize};
 use strum::{IntoEnumIterator, VariantNames};
 use time::OffsetDateTime;
 use ui::{
-    Checkbox, ContextMenu, ElevationIndex, PopoverMenu, Scrollbar, ScrollbarState, Tooltip,
-    prelude::*,
+    Checkbox, ContextMenu, Disclosure, ElevationIndex, ListItem, PopoverMenu, PopoverMenuHandle,
+    Scrollbar, ScrollbarState, Tooltip, prelude::*,
 };
 use util::{ResultExt, TryFutureExt, maybe, post_inc};
 use workspace::AppState;
@@ -106,6 +113,13 @@ enum TrashCancel {
     Cancel,
 }
 
+#[derive(strum::EnumIter, strum::VariantNames)]
+#[strum(serialize_all = "title_case")]
+enum DropCancel {
+    Drop,
+    Cancel,
+}
+
 fn git_panel_context_menu(
     focus_handle: FocusHandle,
     window: &mut Window,
@@ -117,7 +131,25 @@ fn git_panel_context_menu(
             .action("Stage All", StageAll.boxed_clone())
             .action("Unstage All", UnstageAll.boxed_clone())
             .separator()
+            .action("Stash All", StashAll.boxed_clone())
+            .action("Stash Staged", StashStaged.boxed_clone())
+            .action("Pop Latest Stash", StashPop.boxed_clone())
+            .separator()
+            .action("Update Submodules", git::UpdateSubmodules.boxed_clone())
+            .separator()
             .action("Open Diff", project_diff::Diff.boxed_clone())
+            .action("View History", git::ViewHistory.boxed_clone())
+            .action("Interactive Rebase…", git::InteractiveRebase.boxed_clone())
+            .action("Manage Worktrees…", git::ManageWorktrees.boxed_clone())
+            .action("Pull Requests…", git::ViewPullRequests.boxed_clone())
+            .action(
+                "Set Hosting Provider Token…",
+                git::SetHostingProviderToken.boxed_clone(),
+            )
+            .action(
+                "Show Review Comments",
+                git::ShowReviewComments.boxed_clone(),
+            )
             .separator()
             .action("Discard Tracked Changes", RestoreTrackedFiles.boxed_clone())
             .action("Trash Untracked Files", TrashUntrackedFiles.boxed_clone())
@@ -128,6 +160,9 @@ const GIT_PANEL_KEY: &str = "GitPanel";
 
 const UPDATE_DEBOUNCE: Duration = Duration::from_millis(50);
 
+/// The number of recent commits offered as targets of a fixup commit.
+const FIXUP_CANDIDATE_COUNT: usize = 50;
+
 pub fn register(workspace: &mut Workspace) {
     workspace.register_action(|workspace, _: &ToggleFocus, window, cx| {
         workspace.toggle_panel_focus::<GitPanel>(window, cx);
@@ -297,6 +332,7 @@ pub struct GitPanel {
     conflicted_staged_count: usize,
     current_modifiers: Modifiers,
     add_coauthors: bool,
+    commit_options: CommitOptions,
     generate_commit_message_task: Option<Task<Option<()>>>,
     entries: Vec<GitListEntry>,
     single_staged_entry: Option<GitStatusEntry>,
@@ -323,6 +359,9 @@ pub struct GitPanel {
     workspace: WeakEntity<Workspace>,
     context_menu: Option<(Entity<ContextMenu>, Point<Pixels>, Subscription)>,
     modal_open: bool,
+    stash_entries: Vec<StashEntry>,
+    stash_list_expanded: bool,
+    load_stash_entries_task: Task<()>,
     _settings_subscription: Subscription,
 }
 
@@ -465,6 +504,7 @@ impl GitPanel {
             conflicted_staged_count: 0,
             current_modifiers: window.modifiers(),
             add_coauthors: true,
+            commit_options: CommitOptions::default(),
             generate_commit_message_task: None,
             entries: Vec::new(),
             focus_handle: cx.focus_handle(),
@@ -488,6 +528,9 @@ impl GitPanel {
             context_menu: None,
             workspace,
             modal_open: false,
+            stash_entries: Vec::new(),
+            stash_list_expanded: false,
+            load_stash_entries_task: Task::ready(()),
             entry_count: 0,
             horizontal_scrollbar,
             vertical_scrollbar,
@@ -1250,6 +1293,231 @@ impl GitPanel {
         self.change_file_stage(false, entries, cx);
     }
 
+    pub fn stash_all(&mut self, _: &StashAll, window: &mut Window, cx: &mut Context<Self>) {
+        self.stash_changes(StashMode::All, window, cx);
+    }
+
+    pub fn stash_staged(&mut self, _: &StashStaged, window: &mut Window, cx: &mut Context<Self>) {
+        self.stash_changes(StashMode::Staged, window, cx);
+    }
+
+    fn stash_selected(&mut self, _: &git::StashFile, window: &mut Window, cx: &mut Context<Self>) {
+        let paths = if self.marked_entries.is_empty() {
+            self.get_selected_entry()
+                .and_then(|entry| entry.status_entry())
+                .map(|entry| vec![entry.repo_path.clone()])
+                .unwrap_or_default()
+        } else {
+            self.marked_entries
+                .iter()
+                .filter_map(|ix| self.entries.get(*ix)?.status_entry())
+                .map(|entry| entry.repo_path.clone())
+                .collect()
+        };
+        if !paths.is_empty() {
+            self.stash_changes(StashMode::Paths(paths), window, cx);
+        }
+    }
+
+    pub fn stash_pop(&mut self, _: &StashPop, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(index) = self.stash_entries.first().map(|entry| entry.index) {
+            self.pop_stash_entry(index, window, cx);
+        }
+    }
+
+    fn stash_changes(&mut self, mode: StashMode, window: &mut Window, cx: &mut Context<Self>) {
+        telemetry::event!("Git Stashed");
+        self.run_stash_operation("stash", window, cx, |repo| repo.stash_push(None, mode));
+    }
+
+    fn apply_stash_entry(&mut self, index: usize, window: &mut Window, cx: &mut Context<Self>) {
+        self.run_stash_operation("stash apply", window, cx, |repo| repo.stash_apply(index));
+    }
+
+    fn pop_stash_entry(&mut self, index: usize, window: &mut Window, cx: &mut Context<Self>) {
+        self.run_stash_operation("stash pop", window, cx, |repo| repo.stash_pop(index));
+    }
+
+    fn drop_stash_entry(&mut self, entry: StashEntry, window: &mut Window, cx: &mut Context<Self>) {
+        let prompt = prompt(
+            "Drop this stash entry?",
+            Some(entry.message.as_ref()),
+            window,
+            cx,
+        );
+        cx.spawn_in(window, async move |this, cx| {
+            if let Ok(DropCancel::Drop) = prompt.await {
+                this.update_in(cx, |this, window, cx| {
+                    this.run_stash_operation("stash drop", window, cx, |repo| {
+                        repo.stash_drop(entry.index)
+                    });
+                })
+                .ok();
+            }
+        })
+        .detach();
+    }
+
+    fn run_stash_operation(
+        &mut self,
+        action: &'static str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        operation: impl FnOnce(&Repository) -> oneshot::Receiver<Result<()>>,
+    ) {
+        let Some(repo) = self.active_repository.clone() else {
+            return;
+        };
+        let operation = operation(repo.read(cx));
+        cx.spawn_in(window, async move |this, cx| {
+            let result = operation.await;
+            this.update(cx, |this, cx| {
+                match result {
+                    Ok(Ok(())) => {}
+                    Ok(Err(e)) => this.show_error_toast(action, e, cx),
+                    Err(e) => this.show_error_toast(action, e.into(), cx),
+                }
+                this.load_stash_entries(cx);
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    fn rebase_continue(
+        &mut self,
+        _: &git::RebaseContinue,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.run_rebase_operation(RebaseOperation::Continue, window, cx);
+    }
+
+    fn rebase_skip(&mut self, _: &git::RebaseSkip, window: &mut Window, cx: &mut Context<Self>) {
+        self.run_rebase_operation(RebaseOperation::Skip, window, cx);
+    }
+
+    fn rebase_abort(&mut self, _: &git::RebaseAbort, window: &mut Window, cx: &mut Context<Self>) {
+        self.run_rebase_operation(RebaseOperation::Abort, window, cx);
+    }
+
+    fn run_rebase_operation(
+        &mut self,
+        operation: RebaseOperation,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(repo) = self.active_repository.clone() else {
+            return;
+        };
+        if repo.read(cx).rebase_state.is_none() {
+            return;
+        }
+        telemetry::event!("Git Rebase Operation", operation = operation.as_arg());
+        let action = match operation {
+            RebaseOperation::Continue => "rebase --continue",
+            RebaseOperation::Skip => "rebase --skip",
+            RebaseOperation::Abort => "rebase --abort",
+        };
+        let task = repo.update(cx, |repo, _| repo.rebase_operation(operation));
+        cx.spawn_in(window, async move |this, cx| {
+            let result = task.await;
+            this.update(cx, |this, cx| match result {
+                Ok(Ok(())) => {}
+                Ok(Err(e)) => this.show_error_toast(action, e, cx),
+                Err(e) => this.show_error_toast(action, e.into(), cx),
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    /// Cherry-picks or reverts commits of the given repository. When this stops because of
+    /// conflicts, they are listed in the panel, along with controls to resolve the operation.
+    pub(crate) fn apply_commits(
+        &mut self,
+        repository: Entity<Repository>,
+        command: SequencerCommand,
+        commits: Vec<String>,
+        no_commit: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.has_write_access(cx) {
+            return;
+        }
+        telemetry::event!(
+            "Git Commits Applied",
+            command = command.as_str(),
+            no_commit = no_commit
+        );
+        let task = repository.update(cx, |repository, _| match command {
+            SequencerCommand::CherryPick => repository.cherry_pick(commits, no_commit),
+            SequencerCommand::Revert => repository.revert(commits, no_commit),
+        });
+        cx.spawn_in(window, async move |this, cx| {
+            let result = task.await;
+            this.update(cx, |this, cx| match result {
+                Ok(Ok(())) => {}
+                Ok(Err(e)) => this.show_error_toast(command.as_str(), e, cx),
+                Err(e) => this.show_error_toast(command.as_str(), e.into(), cx),
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    fn run_sequencer_operation(
+        &mut self,
+        operation: RebaseOperation,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(repo) = self.active_repository.clone() else {
+            return;
+        };
+        let Some(command) = repo.read(cx).sequencer_command else {
+            return;
+        };
+        telemetry::event!(
+            "Git Sequencer Operation",
+            command = command.as_str(),
+            operation = operation.as_arg()
+        );
+        let action = format!("{} {}", command.as_str(), operation.as_arg());
+        let task = repo.update(cx, |repo, _| repo.sequencer_operation(command, operation));
+        cx.spawn_in(window, async move |this, cx| {
+            let result = task.await;
+            this.update(cx, |this, cx| match result {
+                Ok(Ok(())) => {}
+                Ok(Err(e)) => this.show_error_toast(action, e, cx),
+                Err(e) => this.show_error_toast(action, e.into(), cx),
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    fn load_stash_entries(&mut self, cx: &mut Context<Self>) {
+        let Some(repo) = self.active_repository.as_ref() else {
+            self.stash_entries.clear();
+            return;
+        };
+        let stash_list = repo.read(cx).stash_list();
+        self.load_stash_entries_task = cx.spawn(async move |this, cx| {
+            let Some(stash_entries) = stash_list.await.log_err().and_then(|r| r.log_err()) else {
+                return;
+            };
+            this.update(cx, |this, cx| {
+                if this.stash_entries != stash_entries {
+                    this.stash_entries = stash_entries;
+                    cx.notify();
+                }
+            })
+            .ok();
+        });
+    }
+
     fn toggle_staged_for_entry(
         &mut self,
         entry: &GitListEntry,
@@ -1452,21 +1720,26 @@ impl GitPanel {
             return;
         }
 
-        let commit_message = self.custom_or_suggested_commit_message(cx);
+        let options = self.commit_options.clone();
+        // The message of a fixup commit is derived from the commit it fixes up.
+        let commit_message = self
+            .custom_or_suggested_commit_message(cx)
+            .or_else(|| options.fixup.as_ref().map(|_| String::new()));
 
         let Some(mut message) = commit_message else {
             self.commit_editor.read(cx).focus_handle(cx).focus(window);
             return;
         };
 
-        if self.add_coauthors {
+        if self.add_coauthors && options.fixup.is_none() {
             self.fill_co_authors(&mut message, cx);
         }
 
         let task = if self.has_staged_changes() {
             // Repository serializes all git operations, so we can just send a commit immediately
-            let commit_task =
-                active_repository.update(cx, |repo, cx| repo.commit(message.into(), None, cx));
+            let commit_task = active_repository.update(cx, |repo, cx| {
+                repo.commit(message.into(), None, options, cx)
+            });
             cx.background_spawn(async move { commit_task.await? })
         } else {
             let changed_files = self
@@ -1477,7 +1750,8 @@ impl GitPanel {
                 .map(|status_entry| status_entry.repo_path.clone())
                 .collect::<Vec<_>>();
 
-            if changed_files.is_empty() {
+            // Amending without any changes only rewords the last commit.
+            if changed_files.is_empty() && !options.amend {
                 error_spawn("No changes to commit", window, cx);
                 return;
             }
@@ -1486,8 +1760,9 @@ impl GitPanel {
                 active_repository.update(cx, |repo, cx| repo.stage_entries(changed_files, cx));
             cx.spawn(async move |_, cx| {
                 stage_task.await?;
-                let commit_task = active_repository
-                    .update(cx, |repo, cx| repo.commit(message.into(), None, cx))?;
+                let commit_task = active_repository.update(cx, |repo, cx| {
+                    repo.commit(message.into(), None, options, cx)
+                })?;
                 commit_task.await?
             })
         };
@@ -1497,6 +1772,8 @@ impl GitPanel {
                 this.pending_commit.take();
                 match result {
                     Ok(()) => {
+                        this.commit_options.amend = false;
+                        this.commit_options.fixup = None;
                         this.commit_editor
                             .update(cx, |editor, cx| editor.clear(window, cx));
                     }
@@ -1553,6 +1830,127 @@ impl GitPanel {
         self.pending_commit = Some(task);
     }
 
+    pub(crate) fn commit_options(&self) -> &CommitOptions {
+        &self.commit_options
+    }
+
+    fn toggle_amend(&mut self, _: &git::ToggleAmend, window: &mut Window, cx: &mut Context<Self>) {
+        self.set_amend(!self.commit_options.amend, window, cx);
+    }
+
+    /// Switches between creating a new commit and amending the last one. When amending
+    /// with an empty commit message, the message of the last commit is loaded for editing.
+    pub(crate) fn set_amend(&mut self, amend: bool, window: &mut Window, cx: &mut Context<Self>) {
+        self.commit_options.amend = amend;
+        if amend {
+            self.commit_options.fixup = None;
+        }
+        cx.notify();
+
+        if !amend || !self.commit_editor.read(cx).text(cx).trim().is_empty() {
+            return;
+        }
+
+        let head = self.load_commit_details("HEAD".to_string(), cx);
+        cx.spawn_in(window, async move |this, cx| {
+            let head = head.await?;
+            this.update_in(cx, |this, window, cx| {
+                if this.commit_options.amend
+                    && this.commit_editor.read(cx).text(cx).trim().is_empty()
+                {
+                    this.commit_editor.update(cx, |editor, cx| {
+                        editor.set_text(head.message.trim_end(), window, cx)
+                    });
+                }
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn fixup(&mut self, _: &git::Fixup, window: &mut Window, cx: &mut Context<Self>) {
+        self.select_fixup_commit(false, window, cx)
+            .detach_and_log_err(cx);
+    }
+
+    /// Prompts for one of the recent commits to create a fixup commit for. The commit modal
+    /// is dismissed by the prompt, so it can be reopened once the prompt is done.
+    pub(crate) fn select_fixup_commit(
+        &mut self,
+        reopen_commit_modal: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let Some(repo) = self.active_repository.clone() else {
+            return Task::ready(Ok(()));
+        };
+        let workspace = self.workspace.clone();
+        let log = repo.update(cx, |repo, _| {
+            repo.log(LogFilter::Head, 0, FIXUP_CANDIDATE_COUNT)
+        });
+
+        cx.spawn_in(window, async move |this, cx| {
+            let entries = log.await??;
+            let candidates: Vec<SharedString> = entries
+                .iter()
+                .map(|entry| {
+                    let short_sha = entry.sha.get(..7).unwrap_or(entry.sha.as_ref());
+                    format!("{short_sha} {}", entry.subject).into()
+                })
+                .collect();
+            let selection = cx
+                .update(|window, cx| {
+                    picker_prompt::prompt(
+                        "Select a commit to fix up",
+                        candidates,
+                        workspace.clone(),
+                        window,
+                        cx,
+                    )
+                })?
+                .await;
+
+            if let Some(ix) = selection {
+                this.update(cx, |this, cx| {
+                    this.commit_options.fixup = Some(entries[ix].sha.to_string());
+                    this.commit_options.amend = false;
+                    cx.notify();
+                })?;
+            }
+
+            if reopen_commit_modal {
+                workspace.update_in(cx, |workspace, window, cx| {
+                    CommitModal::toggle(workspace, window, cx)
+                })?;
+            }
+            Ok(())
+        })
+    }
+
+    pub(crate) fn clear_fixup(&mut self, cx: &mut Context<Self>) {
+        self.commit_options.fixup = None;
+        cx.notify();
+    }
+
+    pub(crate) fn toggle_signoff(
+        &mut self,
+        _: &git::ToggleSignoff,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.commit_options.signoff = !self.commit_options.signoff;
+        cx.notify();
+    }
+
+    pub(crate) fn toggle_gpg_sign(
+        &mut self,
+        _: &git::ToggleGpgSign,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.commit_options.gpg_sign = !self.commit_options.gpg_sign;
+        cx.notify();
+    }
+
     fn check_for_pushed_commits(
         &mut self,
         window: &mut Window,
@@ -1702,6 +2100,7 @@ impl GitPanel {
                     tools: Vec::new(),
                     stop: Vec::new(),
                     temperature: None,
+                    max_tokens: None,
                 };
 
                 let stream = model.stream_completion_text(request, &cx);
@@ -1769,6 +2168,159 @@ impl GitPanel {
             .detach_and_log_err(cx);
     }
 
+    fn update_submodules(
+        &mut self,
+        _: &git::UpdateSubmodules,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.run_submodule_update(Vec::new(), window, cx);
+    }
+
+    fn update_selected_submodule(
+        &mut self,
+        _: &git::UpdateSubmodule,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(submodule_path) = self.selected_submodule_path(cx) {
+            self.run_submodule_update(vec![submodule_path], window, cx);
+        }
+    }
+
+    fn run_submodule_update(
+        &mut self,
+        paths: Vec<RepoPath>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.can_push_and_pull(cx) {
+            return;
+        }
+
+        let Some(repo) = self.active_repository.clone() else {
+            return;
+        };
+        telemetry::event!("Git Submodules Updated");
+        let guard = self.start_remote_operation();
+        let askpass = self.askpass_delegate("git submodule update", window, cx);
+        let this = cx.weak_entity();
+        window
+            .spawn(cx, async move |cx| {
+                let update =
+                    repo.update(cx, |repo, cx| repo.update_submodules(paths, askpass, cx))?;
+
+                let remote_message = update.await?;
+                drop(guard);
+                this.update(cx, |this, cx| {
+                    let action = RemoteAction::UpdateSubmodules;
+                    match remote_message {
+                        Ok(remote_message) => this.show_remote_output(action, remote_message, cx),
+                        Err(e) => this.show_error_toast(action.name(), e, cx),
+                    }
+                })
+                .ok();
+                anyhow::Ok(())
+            })
+            .detach_and_log_err(cx);
+    }
+
+    /// Fetches the head of a pull request into a local branch named after it, and checks it out.
+    pub(crate) fn checkout_pull_request(
+        &mut self,
+        hosted_remote: HostedRemote,
+        pull_request: PullRequestDetails,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.can_push_and_pull(cx) {
+            return;
+        }
+
+        let Some(repo) = self.active_repository.clone() else {
+            return;
+        };
+        let number = pull_request.number;
+        let Some(remote_ref) = hosted_remote.provider.pull_request_ref(number) else {
+            return;
+        };
+        telemetry::event!("Git Pull Request Checked Out");
+        let branch_name = pull_request_branch_name(number);
+        let remote_name = hosted_remote.remote_name.to_string();
+        let guard = self.start_remote_operation();
+        let askpass = self.askpass_delegate(format!("git fetch {remote_name}"), window, cx);
+        let this = cx.weak_entity();
+        window
+            .spawn(cx, async move |cx| {
+                let fetch = repo.update(cx, |repo, cx| {
+                    repo.fetch_ref(remote_name, remote_ref, branch_name.clone(), askpass, cx)
+                })?;
+                let result = async {
+                    let output = fetch.await??;
+                    repo.update(cx, |repo, _| repo.change_branch(branch_name.clone()))?
+                        .await??;
+                    anyhow::Ok(output)
+                }
+                .await;
+                drop(guard);
+                this.update(cx, |this, cx| {
+                    let action = RemoteAction::CheckoutPullRequest(number, branch_name.into());
+                    match result {
+                        Ok(output) => this.show_remote_output(action, output, cx),
+                        Err(e) => this.show_error_toast(action.name(), e, cx),
+                    }
+                })
+                .ok();
+                anyhow::Ok(())
+            })
+            .detach_and_log_err(cx);
+    }
+
+    /// Opens the project diff for the repository of the selected submodule.
+    fn open_submodule_diff(
+        &mut self,
+        _: &git::OpenSubmoduleDiff,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some((repo, submodule_path)) = self
+            .active_repository
+            .clone()
+            .zip(self.selected_submodule_path(cx))
+        else {
+            return;
+        };
+        let Some(submodule_repo) = self
+            .project
+            .read(cx)
+            .git_store()
+            .read(cx)
+            .repository_for_submodule(&repo, &submodule_path, cx)
+        else {
+            self.show_error_toast(
+                "open submodule diff",
+                anyhow::anyhow!("{submodule_path} is not a checked out submodule"),
+                cx,
+            );
+            return;
+        };
+        submodule_repo.update(cx, |submodule_repo, cx| {
+            submodule_repo.set_as_active_repository(cx)
+        });
+        self.workspace
+            .update(cx, |workspace, cx| {
+                ProjectDiff::deploy_at(workspace, None, window, cx);
+            })
+            .ok();
+    }
+
+    fn selected_submodule_path(&self, cx: &App) -> Option<RepoPath> {
+        let entry = self.get_selected_entry()?.status_entry()?;
+        let repo = self.active_repository.as_ref()?.read(cx);
+        repo.submodule_for_path(&entry.repo_path)
+            .map(|submodule| submodule.path.clone())
+    }
+
     pub(crate) fn git_init(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         let worktrees = self
             .project
@@ -1942,12 +2494,103 @@ impl GitPanel {
                     return Ok(());
                 }
                 Err(e) => {
-                    log::error!("Failed to get current remote: {}", e);
-                    this.update(cx, |this, cx| this.show_error_toast("push", e, cx))
+                    log::error!("Failed to get current remote: {}", e);
+                    this.update(cx, |this, cx| this.show_error_toast("push", e, cx))
+                        .ok();
+                    return Ok(());
+                }
+            };
+
+            let askpass_delegate = this.update_in(cx, |this, window, cx| {
+                this.askpass_delegate(format!("git push {}", remote.name), window, cx)
+            })?;
+
+            let guard = this
+                .update(cx, |this, _| this.start_remote_operation())
+                .ok();
+
+            let push = repo.update(cx, |repo, cx| {
+                repo.push(
+                    branch.name.clone(),
+                    remote.name.clone(),
+                    options,
+                    askpass_delegate,
+                    cx,
+                )
+            })?;
+
+            let remote_output = push.await?;
+            drop(guard);
+
+            let action = RemoteAction::Push(branch.name, remote);
+            this.update(cx, |this, cx| match remote_output {
+                Ok(remote_message) => this.show_remote_output(action, remote_message, cx),
+                Err(e) => {
+                    log::error!("Error while pushing {:?}", e);
+                    this.show_error_toast(action.name(), e, cx)
+                }
+            })?;
+
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Pushes a tag to a remote, asking which one when the repository has several.
+    pub(crate) fn push_tag(
+        &mut self,
+        tag_name: SharedString,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.can_push_and_pull(cx) {
+            return;
+        }
+        let Some(repo) = self.active_repository.clone() else {
+            return;
+        };
+        telemetry::event!("Git Tag Pushed");
+        let remotes = repo.read(cx).get_remotes(None);
+        let workspace = self.workspace.clone();
+
+        cx.spawn_in(window, async move |this, cx| {
+            let mut remotes = match remotes.await? {
+                Ok(remotes) => remotes,
+                Err(e) => {
+                    this.update(cx, |this, cx| this.show_error_toast("tag push", e, cx))
                         .ok();
                     return Ok(());
                 }
             };
+            let remote = if remotes.len() > 1 {
+                let remote_names = remotes
+                    .iter()
+                    .map(|remote| remote.name.clone())
+                    .collect::<Vec<_>>();
+                let selection = cx
+                    .update(|window, cx| {
+                        picker_prompt::prompt(
+                            "Pick which remote to push the tag to",
+                            remote_names,
+                            workspace,
+                            window,
+                            cx,
+                        )
+                    })?
+                    .await;
+                let Some(selection) = selection else {
+                    return Ok(());
+                };
+                remotes.swap_remove(selection)
+            } else if let Some(remote) = remotes.pop() {
+                remote
+            } else {
+                this.update(cx, |this, cx| {
+                    this.show_error_toast("tag push", anyhow::anyhow!("No remotes"), cx)
+                })
+                .ok();
+                return Ok(());
+            };
 
             let askpass_delegate = this.update_in(cx, |this, window, cx| {
                 this.askpass_delegate(format!("git push {}", remote.name), window, cx)
@@ -1958,10 +2601,9 @@ impl GitPanel {
                 .ok();
 
             let push = repo.update(cx, |repo, cx| {
-                repo.push(
-                    branch.name.clone(),
-                    remote.name.clone(),
-                    options,
+                repo.push_tag(
+                    tag_name.to_string(),
+                    remote.name.to_string(),
                     askpass_delegate,
                     cx,
                 )
@@ -1970,11 +2612,11 @@ impl GitPanel {
             let remote_output = push.await?;
             drop(guard);
 
-            let action = RemoteAction::Push(branch.name, remote);
+            let action = RemoteAction::PushTag(tag_name, remote);
             this.update(cx, |this, cx| match remote_output {
                 Ok(remote_message) => this.show_remote_output(action, remote_message, cx),
                 Err(e) => {
-                    log::error!("Error while pushing {:?}", e);
+                    log::error!("Error while pushing tag {:?}", e);
                     this.show_error_toast(action.name(), e, cx)
                 }
             })?;
@@ -2125,51 +2767,7 @@ impl GitPanel {
     }
 
     fn fill_co_authors(&mut self, message: &mut String, cx: &mut Context<Self>) {
-        const CO_AUTHOR_PREFIX: &str = "Co-authored-by: ";
-
-        let existing_text = message.to_ascii_lowercase();
-        let lowercase_co_author_prefix = CO_AUTHOR_PREFIX.to_lowercase();
-        let mut ends_with_co_authors = false;
-        let existing_co_authors = existing_text
-            .lines()
-            .filter_map(|line| {
-                let line = line.trim();
-                if line.starts_with(&lowercase_co_author_prefix) {
-                    ends_with_co_authors = true;
-                    Some(line)
-                } else {
-                    ends_with_co_authors = false;
-                    None
-                }
-            })
-            .collect::<HashSet<_>>();
-
-        let new_co_authors = self
-            .potential_co_authors(cx)
-            .into_iter()
-            .filter(|(_, email)| {
-                !existing_co_authors
-                    .iter()
-                    .any(|existing| existing.contains(email.as_str()))
-            })
-            .collect::<Vec<_>>();
-
-        if new_co_authors.is_empty() {
-            return;
-        }
-
-        if !ends_with_co_authors {
-            message.push('\n');
-        }
-        for (name, email) in new_co_authors {
-            message.push('\n');
-            message.push_str(CO_AUTHOR_PREFIX);
-            message.push_str(&name);
-            message.push_str(" <");
-            message.push_str(&email);
-            message.push('>');
-        }
-        message.push('\n');
+        append_co_authors(message, self.potential_co_authors(cx));
     }
 
     fn schedule_update(
@@ -2189,6 +2787,7 @@ impl GitPanel {
                             git_panel.clear_pending();
                         }
                         git_panel.update_visible_entries(cx);
+                        git_panel.load_stash_entries(cx);
                         git_panel.update_scrollbar_properties(window, cx);
                     })
                     .ok();
@@ -2722,14 +3321,111 @@ impl GitPanel {
         }
     }
 
+    pub(crate) fn render_commit_options_menu(
+        &self,
+        handle: PopoverMenuHandle<ContextMenu>,
+        cx: &Context<Self>,
+    ) -> impl IntoElement {
+        let git_panel = cx.entity().downgrade();
+        let has_options = self.commit_options != CommitOptions::default();
+
+        PopoverMenu::new("commit-options-menu")
+            .trigger_with_tooltip(
+                IconButton::new("commit-options", IconName::Sliders)
+                    .shape(ui::IconButtonShape::Square)
+                    .icon_color(Color::Muted)
+                    .selected_icon_color(Color::Selected)
+                    .toggle_state(has_options),
+                Tooltip::text("Commit Options"),
+            )
+            .menu(move |window, cx| {
+                let git_panel = git_panel.upgrade()?;
+                let options = git_panel.read(cx).commit_options.clone();
+                Some(ContextMenu::build(window, cx, |menu, _, _| {
+                    let fixup_label = match &options.fixup {
+                        Some(sha) => format!("Fixup {}", sha.get(..7).unwrap_or(sha.as_str())),
+                        None => "Fixup Commit…".to_string(),
+                    };
+                    menu.toggleable_entry(
+                        "Amend Last Commit",
+                        options.amend,
+                        IconPosition::Start,
+                        Some(git::ToggleAmend.boxed_clone()),
+                        {
+                            let git_panel = git_panel.clone();
+                            move |window, cx| {
+                                git_panel.update(cx, |git_panel, cx| {
+                                    git_panel.set_amend(!options.amend, window, cx)
+                                })
+                            }
+                        },
+                    )
+                    .toggleable_entry(
+                        fixup_label,
+                        options.fixup.is_some(),
+                        IconPosition::Start,
+                        Some(git::Fixup.boxed_clone()),
+                        {
+                            let git_panel = git_panel.clone();
+                            let has_fixup = options.fixup.is_some();
+                            move |window, cx| {
+                                git_panel.update(cx, |git_panel, cx| {
+                                    if has_fixup {
+                                        git_panel.clear_fixup(cx);
+                                    } else {
+                                        git_panel
+                                            .select_fixup_commit(true, window, cx)
+                                            .detach_and_log_err(cx);
+                                    }
+                                })
+                            }
+                        },
+                    )
+                    .separator()
+                    .toggleable_entry(
+                        "Sign Off",
+                        options.signoff,
+                        IconPosition::Start,
+                        Some(git::ToggleSignoff.boxed_clone()),
+                        {
+                            let git_panel = git_panel.clone();
+                            move |window, cx| {
+                                git_panel.update(cx, |git_panel, cx| {
+                                    git_panel.toggle_signoff(&git::ToggleSignoff, window, cx)
+                                })
+                            }
+                        },
+                    )
+                    .toggleable_entry(
+                        "GPG Sign",
+                        options.gpg_sign,
+                        IconPosition::Start,
+                        Some(git::ToggleGpgSign.boxed_clone()),
+                        move |window, cx| {
+                            git_panel.update(cx, |git_panel, cx| {
+                                git_panel.toggle_gpg_sign(&git::ToggleGpgSign, window, cx)
+                            })
+                        },
+                    )
+                }))
+            })
+            .with_handle(handle)
+            .anchor(Corner::BottomLeft)
+    }
+
     pub fn configure_commit_button(&self, cx: &mut Context<Self>) -> (bool, &'static str) {
         if self.has_unstaged_conflicts() {
             (false, "You must resolve conflicts before committing")
-        } else if !self.has_staged_changes() && !self.has_tracked_changes() {
+        } else if !self.has_staged_changes()
+            && !self.has_tracked_changes()
+            && !self.commit_options.amend
+        {
             (false, "No changes to commit")
         } else if self.pending_commit.is_some() {
             (false, "Commit in progress")
-        } else if self.custom_or_suggested_commit_message(cx).is_none() {
+        } else if self.commit_options.fixup.is_none()
+            && self.custom_or_suggested_commit_message(cx).is_none()
+        {
             (false, "No commit message")
         } else if !self.has_write_access(cx) {
             (false, "You do not have write access to this project")
@@ -2739,7 +3435,22 @@ impl GitPanel {
     }
 
     pub fn commit_button_title(&self) -> &'static str {
-        if self.has_staged_changes() {
+        let has_staged_changes = self.has_staged_changes();
+        if self.commit_options.fixup.is_some() {
+            if has_staged_changes {
+                "Fixup"
+            } else {
+                "Fixup Tracked"
+            }
+        } else if self.commit_options.amend {
+            if has_staged_changes {
+                "Amend"
+            } else if self.has_tracked_changes() {
+                "Amend Tracked"
+            } else {
+                "Reword"
+            }
+        } else if has_staged_changes {
             "Commit"
         } else {
             "Commit Tracked"
@@ -3076,6 +3787,321 @@ impl GitPanel {
         )
     }
 
+    fn render_rebase_state(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        let rebase_state = self
+            .active_repository
+            .as_ref()?
+            .read(cx)
+            .rebase_state
+            .clone()?;
+        let has_write_access = self.has_write_access(cx);
+        let onto = rebase_state
+            .onto
+            .get(..7)
+            .unwrap_or(rebase_state.onto.as_ref());
+        let title = match &rebase_state.branch {
+            Some(branch) => format!("Rebasing {branch} onto {onto}"),
+            None => format!("Rebasing onto {onto}"),
+        };
+        let has_conflicts = self.conflicted_count > 0;
+
+        Some(
+            v_flex()
+                .id("rebase-state")
+                .px(rems(0.75))
+                .py_1()
+                .gap_1()
+                .border_t_1()
+                .border_color(cx.theme().colors().border)
+                .child(
+                    h_flex()
+                        .gap_1p5()
+                        .child(
+                            Icon::new(IconName::GitBranch)
+                                .size(IconSize::Small)
+                                .color(Color::Warning),
+                        )
+                        .child(Label::new(title).size(LabelSize::Small).truncate())
+                        .when(rebase_state.total > 0, |this| {
+                            this.child(
+                                Label::new(format!("{}/{}", rebase_state.step, rebase_state.total))
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                            )
+                        }),
+                )
+                .when(has_write_access, |this| {
+                    this.child(
+                        h_flex()
+                            .gap_1()
+                            .justify_end()
+                            .child(
+                                Button::new("rebase-abort", "Abort")
+                                    .label_size(LabelSize::Small)
+                                    .tooltip(Tooltip::for_action_title(
+                                        "Abort the rebase and restore the original branch",
+                                        &git::RebaseAbort,
+                                    ))
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.run_rebase_operation(
+                                            RebaseOperation::Abort,
+                                            window,
+                                            cx,
+                                        )
+                                    })),
+                            )
+                            .child(
+                                Button::new("rebase-skip", "Skip")
+                                    .label_size(LabelSize::Small)
+                                    .tooltip(Tooltip::for_action_title(
+                                        "Skip the current commit",
+                                        &git::RebaseSkip,
+                                    ))
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.run_rebase_operation(RebaseOperation::Skip, window, cx)
+                                    })),
+                            )
+                            .child(
+                                Button::new("rebase-continue", "Continue")
+                                    .label_size(LabelSize::Small)
+                                    .disabled(has_conflicts)
+                                    .tooltip(move |window, cx| {
+                                        if has_conflicts {
+                                            Tooltip::simple("Resolve all conflicts to continue", cx)
+                                        } else {
+                                            Tooltip::for_action(
+                                                "Continue Rebase",
+                                                &git::RebaseContinue,
+                                                window,
+                                                cx,
+                                            )
+                                        }
+                                    })
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.run_rebase_operation(
+                                            RebaseOperation::Continue,
+                                            window,
+                                            cx,
+                                        )
+                                    })),
+                            ),
+                    )
+                }),
+        )
+    }
+
+    fn render_sequencer_state(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        let repo = self.active_repository.as_ref()?.read(cx);
+        let command = repo.sequencer_command?;
+        // The commit that stopped the operation, when it hasn't been committed yet.
+        let stopped_at = repo
+            .merge_head_shas
+            .last()
+            .map(|sha| sha.get(..7).unwrap_or(sha.as_ref()).to_string());
+        let title = match (command, stopped_at) {
+            (SequencerCommand::CherryPick, Some(sha)) => format!("Cherry-picking {sha}"),
+            (SequencerCommand::CherryPick, None) => "Cherry-picking commits".to_string(),
+            (SequencerCommand::Revert, Some(sha)) => format!("Reverting {sha}"),
+            (SequencerCommand::Revert, None) => "Reverting commits".to_string(),
+        };
+        let has_write_access = self.has_write_access(cx);
+        let has_conflicts = self.conflicted_count > 0;
+
+        Some(
+            v_flex()
+                .id("sequencer-state")
+                .px(rems(0.75))
+                .py_1()
+                .gap_1()
+                .border_t_1()
+                .border_color(cx.theme().colors().border)
+                .child(
+                    h_flex()
+                        .gap_1p5()
+                        .child(
+                            Icon::new(IconName::GitBranch)
+                                .size(IconSize::Small)
+                                .color(Color::Warning),
+                        )
+                        .child(Label::new(title).size(LabelSize::Small).truncate()),
+                )
+                .when(has_write_access, |this| {
+                    this.child(
+                        h_flex()
+                            .gap_1()
+                            .justify_end()
+                            .child(
+                                Button::new("sequencer-abort", "Abort")
+                                    .label_size(LabelSize::Small)
+                                    .tooltip(Tooltip::text(match command {
+                                        SequencerCommand::CherryPick => {
+                                            "Abort the cherry-pick and restore the original branch"
+                                        }
+                                        SequencerCommand::Revert => {
+                                            "Abort the revert and restore the original branch"
+                                        }
+                                    }))
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.run_sequencer_operation(
+                                            RebaseOperation::Abort,
+                                            window,
+                                            cx,
+                                        )
+                                    })),
+                            )
+                            .child(
+                                Button::new("sequencer-skip", "Skip")
+                                    .label_size(LabelSize::Small)
+                                    .tooltip(Tooltip::text("Skip the current commit"))
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.run_sequencer_operation(
+                                            RebaseOperation::Skip,
+                                            window,
+                                            cx,
+                                        )
+                                    })),
+                            )
+                            .child(
+                                Button::new("sequencer-continue", "Continue")
+                                    .label_size(LabelSize::Small)
+                                    .disabled(has_conflicts)
+                                    .tooltip(Tooltip::text(if has_conflicts {
+                                        "Resolve all conflicts to continue"
+                                    } else {
+                                        "Commit the resolved changes and apply the remaining commits"
+                                    }))
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.run_sequencer_operation(
+                                            RebaseOperation::Continue,
+                                            window,
+                                            cx,
+                                        )
+                                    })),
+                            ),
+                    )
+                }),
+        )
+    }
+
+    fn render_stash_list(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        if self.stash_entries.is_empty() {
+            return None;
+        }
+        let repo = self.active_repository.as_ref()?.downgrade();
+        let has_write_access = self.has_write_access(cx);
+        let expanded = self.stash_list_expanded;
+
+        Some(
+            v_flex()
+                .border_t_1()
+                .border_color(cx.theme().colors().border)
+                .child(
+                    h_flex()
+                        .id("stash-list-header")
+                        .h(self.list_item_height())
+                        .px(rems(0.75))
+                        .gap_1()
+                        .cursor_pointer()
+                        .child(Disclosure::new("stash-list-disclosure", expanded))
+                        .child(
+                            Label::new(format!("Stashes ({})", self.stash_entries.len()))
+                                .color(Color::Muted)
+                                .size(LabelSize::Small)
+                                .single_line(),
+                        )
+                        .on_click(cx.listener(|this, _, _, cx| {
+                            this.stash_list_expanded = !this.stash_list_expanded;
+                            cx.notify();
+                        })),
+                )
+                .when(expanded, |this| {
+                    this.child(
+                        v_flex()
+                            .id("stash-list")
+                            .max_h_40()
+                            .overflow_y_scroll()
+                            .children(self.stash_entries.iter().map(|entry| {
+                                self.render_stash_entry(entry, repo.clone(), has_write_access, cx)
+                            })),
+                    )
+                }),
+        )
+    }
+
+    fn render_stash_entry(
+        &self,
+        entry: &StashEntry,
+        repo: WeakEntity<Repository>,
+        has_write_access: bool,
+        cx: &Context<Self>,
+    ) -> impl IntoElement {
+        let index = entry.index;
+        let workspace = self.workspace.clone();
+        ListItem::new(("stash-entry", index))
+            .inset(true)
+            .child(
+                h_flex()
+                    .gap_1p5()
+                    .overflow_hidden()
+                    .child(
+                        Label::new(entry.reference())
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .child(
+                        Label::new(entry.message.clone())
+                            .size(LabelSize::Small)
+                            .truncate(),
+                    ),
+            )
+            .on_click({
+                let entry = entry.clone();
+                move |_, window, cx| {
+                    CommitView::open_stash(
+                        entry.clone(),
+                        repo.clone(),
+                        workspace.clone(),
+                        window,
+                        cx,
+                    );
+                }
+            })
+            .when(has_write_access, |this| {
+                let entry = entry.clone();
+                this.end_hover_slot(
+                    h_flex()
+                        .gap_0p5()
+                        .child(
+                            IconButton::new(("apply-stash", index), IconName::Check)
+                                .icon_size(IconSize::Small)
+                                .icon_color(Color::Muted)
+                                .tooltip(Tooltip::text("Apply Stash"))
+                                .on_click(cx.listener(move |this, _, window, cx| {
+                                    this.apply_stash_entry(index, window, cx)
+                                })),
+                        )
+                        .child(
+                            IconButton::new(("pop-stash", index), IconName::ArrowUpFromLine)
+                                .icon_size(IconSize::Small)
+                                .icon_color(Color::Muted)
+                                .tooltip(Tooltip::text("Pop Stash"))
+                                .on_click(cx.listener(move |this, _, window, cx| {
+                                    this.pop_stash_entry(index, window, cx)
+                                })),
+                        )
+                        .child(
+                            IconButton::new(("drop-stash", index), IconName::Trash)
+                                .icon_size(IconSize::Small)
+                                .icon_color(Color::Muted)
+                                .tooltip(Tooltip::text("Drop Stash"))
+                                .on_click(cx.listener(move |this, _, window, cx| {
+                                    this.drop_stash_entry(entry.clone(), window, cx)
+                                })),
+                        ),
+                )
+            })
+    }
+
     fn render_empty_state(&self, cx: &mut Context<Self>) -> impl IntoElement {
         h_flex()
             .h_full()
@@ -3484,14 +4510,41 @@ impl GitPanel {
         } else {
             "Restore File"
         };
+        let is_conflicted = entry.status.is_conflicted();
+        let is_submodule = self.active_repository.as_ref().is_some_and(|repository| {
+            repository
+                .read(cx)
+                .submodule_for_path(&entry.repo_path)
+                .is_some()
+        });
+        let history_path = self.active_repository.as_ref().map(|repository| {
+            repository
+                .read(cx)
+                .work_directory_abs_path
+                .join(&entry.repo_path.0)
+        });
         let context_menu = ContextMenu::build(window, cx, |context_menu, _, _| {
             context_menu
                 .context(self.focus_handle.clone())
+                .when(is_conflicted, |menu| {
+                    menu.action("Resolve Conflicts", ResolveConflicts.boxed_clone())
+                        .separator()
+                })
+                .when(is_submodule, |menu| {
+                    menu.action("Open Submodule Diff", git::OpenSubmoduleDiff.boxed_clone())
+                        .action("Update Submodule", git::UpdateSubmodule.boxed_clone())
+                        .separator()
+                })
                 .action(stage_title, ToggleStaged.boxed_clone())
                 .action(restore_title, git::RestoreFile::default().boxed_clone())
+                .action("Stash File", git::StashFile.boxed_clone())
                 .separator()
                 .action("Open Diff", Confirm.boxed_clone())
                 .action("Open File", SecondaryConfirm.boxed_clone())
+                .action(
+                    "View File History",
+                    git::FileHistory { path: history_path }.boxed_clone(),
+                )
         });
         self.selected_entry = Some(ix);
         self.set_context_menu(context_menu, position, window, cx);
@@ -3551,6 +4604,15 @@ impl GitPanel {
         let has_conflict = status.is_conflicted();
         let is_modified = status.is_modified();
         let is_deleted = status.is_deleted();
+        let submodule_state = self
+            .active_repository
+            .as_ref()
+            .and_then(|repo| {
+                repo.read(cx)
+                    .submodule_for_path(&entry.repo_path)
+                    .map(|submodule| submodule.status)
+            })
+            .and_then(submodule_state_label);
 
         let label_color = if status_style == StatusStyle::LabelColor {
             if has_conflict {
@@ -3750,7 +4812,17 @@ impl GitPanel {
                     .child(
                         self.entry_label(display_name.clone(), label_color)
                             .when(status.is_deleted(), |this| this.strikethrough()),
-                    ),
+                    )
+                    .when_some(submodule_state, |this, submodule_state| {
+                        this.child(
+                            div().pl_1p5().child(
+                                Label::new(submodule_state)
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted)
+                                    .single_line(),
+                            ),
+                        )
+                    }),
             )
             .into_any_element()
     }
@@ -3760,6 +4832,24 @@ impl GitPanel {
     }
 }
 
+/// Describes how a submodule differs from the commit recorded in the superproject.
+fn submodule_state_label(status: SubmoduleStatus) -> Option<&'static str> {
+    let label = if status.uninitialized {
+        "not initialized"
+    } else if status.new_commits && (status.modified || status.untracked) {
+        "new commits, dirty"
+    } else if status.new_commits {
+        "new commits"
+    } else if status.modified {
+        "modified content"
+    } else if status.untracked {
+        "untracked content"
+    } else {
+        return None;
+    };
+    Some(label)
+}
+
 fn current_language_model(cx: &Context<'_, GitPanel>) -> Option<Arc<dyn LanguageModel>> {
     assistant_settings::AssistantSettings::get_global(cx)
         .enabled
@@ -3797,6 +4887,10 @@ impl Render for GitPanel {
             .when(has_write_access && !project.is_read_only(cx), |this| {
                 this.on_action(cx.listener(Self::toggle_staged_for_selected))
                     .on_action(cx.listener(GitPanel::commit))
+                    .on_action(cx.listener(Self::toggle_amend))
+                    .on_action(cx.listener(Self::fixup))
+                    .on_action(cx.listener(Self::toggle_signoff))
+                    .on_action(cx.listener(Self::toggle_gpg_sign))
                     .on_action(cx.listener(Self::stage_all))
                     .on_action(cx.listener(Self::unstage_all))
                     .on_action(cx.listener(Self::stage_selected))
@@ -3804,6 +4898,16 @@ impl Render for GitPanel {
                     .on_action(cx.listener(Self::restore_tracked_files))
                     .on_action(cx.listener(Self::revert_selected))
                     .on_action(cx.listener(Self::clean_all))
+                    .on_action(cx.listener(Self::stash_all))
+                    .on_action(cx.listener(Self::stash_staged))
+                    .on_action(cx.listener(Self::stash_selected))
+                    .on_action(cx.listener(Self::stash_pop))
+                    .on_action(cx.listener(Self::rebase_continue))
+                    .on_action(cx.listener(Self::rebase_skip))
+                    .on_action(cx.listener(Self::rebase_abort))
+                    .on_action(cx.listener(Self::update_submodules))
+                    .on_action(cx.listener(Self::update_selected_submodule))
+                    .on_action(cx.listener(Self::open_submodule_diff))
                     .on_action(cx.listener(Self::generate_commit_message_action))
             })
             .on_action(cx.listener(Self::select_first))
@@ -3842,6 +4946,9 @@ impl Render for GitPanel {
                             this.child(self.render_empty_state(cx).into_any_element())
                         }
                     })
+                    .children(self.render_rebase_state(cx))
+                    .children(self.render_sequencer_state(cx))
+                    .children(self.render_stash_list(cx))
                     .children(self.render_footer(window, cx))
                     .children(self.render_previous_commit(cx))
                     .into_any_element(),
@@ -4449,6 +5556,49 @@ impl ComponentPreview for PanelRepoFooter {
     }
 }
 
+/// Appends a `Co-authored-by` trailer for each co-author whose email isn't credited in the
+/// message yet, so that amending a commit doesn't repeat the trailers it already has.
+fn append_co_authors(message: &mut String, co_authors: Vec<(String, String)>) {
+    const CO_AUTHOR_PREFIX: &str = "Co-authored-by: ";
+
+    let mut ends_with_co_authors = false;
+    let mut credited_emails = message
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            let co_author = line
+                .get(..CO_AUTHOR_PREFIX.len())
+                .filter(|prefix| prefix.eq_ignore_ascii_case(CO_AUTHOR_PREFIX))
+                .map(|_| &line[CO_AUTHOR_PREFIX.len()..]);
+            ends_with_co_authors = co_author.is_some();
+            let (_, email) = co_author?.rsplit_once('<')?;
+            Some(email.trim_end_matches('>').trim().to_ascii_lowercase())
+        })
+        .collect::<HashSet<_>>();
+
+    let new_co_authors = co_authors
+        .into_iter()
+        .filter(|(_, email)| credited_emails.insert(email.trim().to_ascii_lowercase()))
+        .collect::<Vec<_>>();
+
+    if new_co_authors.is_empty() {
+        return;
+    }
+
+    if !ends_with_co_authors {
+        message.push('\n');
+    }
+    for (name, email) in new_co_authors {
+        message.push('\n');
+        message.push_str(CO_AUTHOR_PREFIX);
+        message.push_str(&name);
+        message.push_str(" <");
+        message.push_str(&email);
+        message.push('>');
+    }
+    message.push('\n');
+}
+
 #[cfg(test)]
 mod tests {
     use git::status::StatusCode;
@@ -4480,6 +5630,33 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_append_co_authors() {
+        let co_authors = vec![
+            ("Alice".to_string(), "Alice@Example.com".to_string()),
+            ("Bob".to_string(), "bob@example.com".to_string()),
+        ];
+
+        let mut message = "Fix the thing".to_string();
+        append_co_authors(&mut message, co_authors.clone());
+        assert_eq!(
+            message,
+            "Fix the thing\n\nCo-authored-by: Alice <Alice@Example.com>\nCo-authored-by: Bob <bob@example.com>\n"
+        );
+
+        // Amending a commit keeps its message, including the trailers it already has.
+        let mut amended_message = message.trim_end().to_string();
+        append_co_authors(&mut amended_message, co_authors.clone());
+        assert_eq!(amended_message, message.trim_end());
+
+        let mut message = "Fix the thing\n\nco-authored-by: alice <alice@example.com>".to_string();
+        append_co_authors(&mut message, co_authors);
+        assert_eq!(
+            message,
+            "Fix the thing\n\nco-authored-by: alice <alice@example.com>\nCo-authored-by: Bob <bob@example.com>\n"
+        );
+    }
+
     #[gpui::test]
     async fn test_entry_worktree_paths(cx: &mut TestAppContext) {
         init_test(cx);
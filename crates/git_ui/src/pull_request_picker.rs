@@ -0,0 +1,382 @@
+use fuzzy::StringMatchCandidate;
+
+use git::PullRequestDetails;
+use gpui::{
+    App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, InteractiveElement,
+    IntoElement, ParentElement, Render, SharedString, Styled, Subscription, Task, WeakEntity,
+    Window, rems,
+};
+use picker::{Picker, PickerDelegate, PickerEditorPosition};
+use project::git_store::Repository;
+use std::sync::Arc;
+use ui::{HighlightedLabel, ListItem, ListItemSpacing, Tooltip, prelude::*};
+use util::ResultExt;
+use workspace::{ModalView, Workspace};
+
+use crate::git_panel::GitPanel;
+use crate::pull_requests::{
+    HostedRemote, branch_remote, hosted_remote, pull_request_number_for_branch,
+};
+
+pub fn register(workspace: &mut Workspace) {
+    workspace.register_action(open);
+    workspace.register_action(crate::pull_requests::set_hosting_provider_token);
+}
+
+pub fn open(
+    workspace: &mut Workspace,
+    _: &git::ViewPullRequests,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let repository = workspace.project().read(cx).active_repository(cx).clone();
+    let weak_workspace = cx.entity().downgrade();
+    workspace.toggle_modal(window, cx, |window, cx| {
+        PullRequestList::new(repository, weak_workspace, window, cx)
+    })
+}
+
+pub struct PullRequestList {
+    pub picker: Entity<Picker<PullRequestListDelegate>>,
+    _subscription: Subscription,
+}
+
+impl PullRequestList {
+    fn new(
+        repository: Option<Entity<Repository>>,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let delegate = PullRequestListDelegate::new(repository, workspace);
+        let picker = cx.new(|cx| {
+            let mut picker = Picker::uniform_list(delegate, window, cx);
+            picker.delegate.reload(window, cx);
+            picker
+        });
+
+        let _subscription = cx.subscribe(&picker, |_, _, _, cx| {
+            cx.emit(DismissEvent);
+        });
+
+        Self {
+            picker,
+            _subscription,
+        }
+    }
+}
+
+impl ModalView for PullRequestList {}
+impl EventEmitter<DismissEvent> for PullRequestList {}
+
+impl Focusable for PullRequestList {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for PullRequestList {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .w(rems(34.))
+            .child(self.picker.clone())
+            .on_mouse_down_out({
+                cx.listener(move |this, _, window, cx| {
+                    this.picker.update(cx, |this, cx| {
+                        this.cancel(&Default::default(), window, cx);
+                    })
+                })
+            })
+    }
+}
+
+#[derive(Debug, Clone)]
+struct PullRequestEntry {
+    pull_request: PullRequestDetails,
+    positions: Vec<usize>,
+}
+
+pub struct PullRequestListDelegate {
+    matches: Vec<PullRequestEntry>,
+    /// The open pull requests, with those of the current branch first.
+    all_pull_requests: Option<Vec<PullRequestDetails>>,
+    hosted_remote: Option<HostedRemote>,
+    load_error: Option<SharedString>,
+    /// The remote branch tracked by the current branch.
+    current_branch: Option<SharedString>,
+    /// The pull request the current branch was checked out from.
+    current_number: Option<u32>,
+    repo: Option<Entity<Repository>>,
+    workspace: WeakEntity<Workspace>,
+    selected_index: usize,
+}
+
+impl PullRequestListDelegate {
+    fn new(repo: Option<Entity<Repository>>, workspace: WeakEntity<Workspace>) -> Self {
+        Self {
+            matches: Vec::new(),
+            all_pull_requests: None,
+            hosted_remote: None,
+            load_error: None,
+            current_branch: None,
+            current_number: None,
+            repo,
+            workspace,
+            selected_index: 0,
+        }
+    }
+
+    /// Loads the open pull requests of the remote that the current branch tracks.
+    fn reload(&mut self, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(repo) = self.repo.clone() else {
+            self.load_error = Some("No Git repositories".into());
+            return;
+        };
+        let branch = repo.read(cx).branch.clone();
+        let (_, current_branch) = branch_remote(branch.as_ref());
+        self.current_branch = current_branch;
+        self.current_number = branch
+            .as_ref()
+            .and_then(|branch| pull_request_number_for_branch(&branch.name));
+        let hosted_remote = hosted_remote(&repo, cx);
+        cx.spawn_in(window, async move |picker, cx| {
+            let result = async {
+                let hosted_remote = hosted_remote.await?;
+                let pull_requests = hosted_remote
+                    .api
+                    .pull_requests(&hosted_remote.remote, None)
+                    .await?;
+                anyhow::Ok((hosted_remote, pull_requests))
+            }
+            .await;
+
+            picker.update_in(cx, |picker, window, cx| {
+                match result {
+                    Ok((hosted_remote, mut pull_requests)) => {
+                        pull_requests
+                            .sort_by_key(|pull_request| !picker.delegate.is_current(pull_request));
+                        picker.delegate.hosted_remote = Some(hosted_remote);
+                        picker.delegate.all_pull_requests = Some(pull_requests);
+                    }
+                    Err(error) => {
+                        picker.delegate.load_error =
+                            Some(format!("Failed to load pull requests: {error}").into());
+                    }
+                }
+                picker.refresh(window, cx);
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn is_current(&self, pull_request: &PullRequestDetails) -> bool {
+        Some(pull_request.number) == self.current_number
+            || Some(&pull_request.head_branch) == self.current_branch.as_ref()
+    }
+
+    fn checkout(
+        &self,
+        pull_request: PullRequestDetails,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) {
+        let Some(hosted_remote) = self.hosted_remote.clone() else {
+            return;
+        };
+        let Some(panel) = self
+            .workspace
+            .read_with(cx, |workspace, cx| workspace.panel::<GitPanel>(cx))
+            .ok()
+            .flatten()
+        else {
+            return;
+        };
+        cx.emit(DismissEvent);
+        panel.update(cx, |panel, cx| {
+            panel.checkout_pull_request(hosted_remote, pull_request, window, cx);
+        });
+    }
+}
+
+impl PickerDelegate for PullRequestListDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        "Select pull request to check out...".into()
+    }
+
+    fn editor_position(&self) -> PickerEditorPosition {
+        PickerEditorPosition::Start
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let Some(all_pull_requests) = self.all_pull_requests.clone() else {
+            return Task::ready(());
+        };
+
+        cx.spawn_in(window, async move |picker, cx| {
+            let matches: Vec<PullRequestEntry> = if query.is_empty() {
+                all_pull_requests
+                    .into_iter()
+                    .map(|pull_request| PullRequestEntry {
+                        pull_request,
+                        positions: Vec::new(),
+                    })
+                    .collect()
+            } else {
+                let candidates = all_pull_requests
+                    .iter()
+                    .enumerate()
+                    .map(|(ix, pull_request)| {
+                        StringMatchCandidate::new(ix, &pull_request_label(pull_request))
+                    })
+                    .collect::<Vec<StringMatchCandidate>>();
+                fuzzy::match_strings(
+                    &candidates,
+                    &query,
+                    true,
+                    10000,
+                    &Default::default(),
+                    cx.background_executor().clone(),
+                )
+                .await
+                .into_iter()
+                .map(|candidate| PullRequestEntry {
+                    pull_request: all_pull_requests[candidate.candidate_id].clone(),
+                    positions: candidate.positions,
+                })
+                .collect()
+            };
+            picker
+                .update(cx, |picker, _| {
+                    let delegate = &mut picker.delegate;
+                    delegate.matches = matches;
+                    if delegate.matches.is_empty() {
+                        delegate.selected_index = 0;
+                    } else {
+                        delegate.selected_index =
+                            core::cmp::min(delegate.selected_index, delegate.matches.len() - 1);
+                    }
+                })
+                .log_err();
+        })
+    }
+
+    fn confirm(&mut self, secondary: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(entry) = self.matches.get(self.selected_index()).cloned() else {
+            return;
+        };
+        if secondary {
+            cx.open_url(entry.pull_request.url.as_str());
+            cx.emit(DismissEvent);
+        } else {
+            self.checkout(entry.pull_request, window, cx);
+        }
+    }
+
+    fn dismissed(&mut self, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let PullRequestEntry {
+            pull_request,
+            positions,
+        } = &self.matches[ix];
+
+        let mut detail = match &pull_request.author {
+            Some(author) => format!(
+                "{author} wants to merge {} into {}",
+                pull_request.head_branch, pull_request.base_branch
+            ),
+            None => format!(
+                "Merges {} into {}",
+                pull_request.head_branch, pull_request.base_branch
+            ),
+        };
+        if pull_request.is_draft {
+            detail.push_str(" (draft)");
+        }
+        if self.is_current(pull_request) {
+            detail.push_str(" (current branch)");
+        }
+        let url = pull_request.url.clone();
+
+        Some(
+            ListItem::new(SharedString::from(format!("pull-request-{ix}")))
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .child(
+                    v_flex()
+                        .w_full()
+                        .overflow_x_hidden()
+                        .child(
+                            HighlightedLabel::new(
+                                pull_request_label(pull_request),
+                                positions.clone(),
+                            )
+                            .truncate(),
+                        )
+                        .child(
+                            Label::new(detail)
+                                .size(LabelSize::Small)
+                                .truncate()
+                                .color(Color::Muted),
+                        ),
+                )
+                .end_hover_slot(
+                    IconButton::new(("open-pull-request", ix), IconName::ArrowUpRight)
+                        .icon_size(IconSize::Small)
+                        .icon_color(Color::Muted)
+                        .tooltip(Tooltip::text("Open in Browser"))
+                        .on_click(cx.listener(move |_, _, _, cx| {
+                            cx.open_url(url.as_str());
+                            cx.emit(DismissEvent);
+                        })),
+                ),
+        )
+    }
+
+    fn no_matches_text(&self, _window: &mut Window, _cx: &mut App) -> Option<SharedString> {
+        if let Some(error) = &self.load_error {
+            Some(error.clone())
+        } else if self.all_pull_requests.is_none() {
+            Some("Loading pull requests...".into())
+        } else {
+            Some("No open pull requests".into())
+        }
+    }
+}
+
+fn pull_request_label(pull_request: &PullRequestDetails) -> String {
+    format!("#{} {}", pull_request.number, pull_request.title)
+}
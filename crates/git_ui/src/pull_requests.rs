@@ -0,0 +1,337 @@
+use std::{sync::Arc, time::Duration};
+
+use anyhow::{Result, anyhow};
+use futures::channel::oneshot;
+use git::{
+    CiStatus, GitHostingProvider, GitHostingProviderApi, GitHostingProviderRegistry,
+    ParsedGitRemote, PullRequestDetails, parse_git_remote_url, repository::Branch,
+};
+use gpui::{App, Context, Entity, SharedString, Subscription, Task, Window};
+use project::{
+    Project,
+    git_store::{GitStoreEvent, Repository, RepositoryId},
+    project_settings::ProjectSettings,
+};
+use settings::{Settings as _, SettingsStore};
+use util::ResultExt as _;
+use workspace::{Workspace, notifications::DetachAndPromptErr};
+
+use crate::askpass_modal::AskPassModal;
+
+/// How often the CI status of a pull request is refreshed while its checks are running.
+const CI_STATUS_POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// A remote of a repository, hosted on a provider whose API we can talk to.
+#[derive(Clone)]
+pub struct HostedRemote {
+    pub remote_name: SharedString,
+    pub provider: Arc<dyn GitHostingProvider + Send + Sync + 'static>,
+    pub remote: Arc<ParsedGitRemote>,
+    pub api: Arc<dyn GitHostingProviderApi>,
+}
+
+/// Returns the name of the remote the branch tracks, along with the name of the branch on that
+/// remote. Branches without an upstream are assumed to be pushed to `origin` under the same name.
+pub fn branch_remote(branch: Option<&Branch>) -> (SharedString, Option<SharedString>) {
+    let upstream = branch.and_then(|branch| branch.upstream.as_ref());
+    let Some((remote_name, remote_branch)) = upstream.and_then(|upstream| {
+        let remote_name = upstream.remote_name()?;
+        let remote_branch = upstream
+            .ref_name
+            .strip_prefix("refs/remotes/")?
+            .strip_prefix(remote_name)?
+            .strip_prefix('/')?;
+        Some((remote_name, remote_branch))
+    }) else {
+        return ("origin".into(), branch.map(|branch| branch.name.clone()));
+    };
+    (
+        remote_name.to_string().into(),
+        Some(remote_branch.to_string().into()),
+    )
+}
+
+/// The name of the local branch a pull request is checked out as.
+pub fn pull_request_branch_name(number: u32) -> String {
+    format!("pr/{number}")
+}
+
+/// Returns the number of the pull request a local branch was checked out from, if it's named
+/// by [`pull_request_branch_name`].
+pub(crate) fn pull_request_number_for_branch(branch_name: &str) -> Option<u32> {
+    branch_name.strip_prefix("pr/")?.parse().ok()
+}
+
+/// Finds the open pull request of the given branch: the one it was checked out from, or the one
+/// that merges from the remote branch it tracks.
+pub async fn find_branch_pull_request(
+    hosted_remote: &HostedRemote,
+    branch: Option<&Branch>,
+) -> Result<Option<PullRequestDetails>> {
+    if let Some(number) = branch.and_then(|branch| pull_request_number_for_branch(&branch.name)) {
+        let pull_requests = hosted_remote
+            .api
+            .pull_requests(&hosted_remote.remote, None)
+            .await?;
+        return Ok(pull_requests
+            .into_iter()
+            .find(|pull_request| pull_request.number == number));
+    }
+
+    let (_, remote_branch) = branch_remote(branch);
+    let Some(remote_branch) = remote_branch else {
+        return Ok(None);
+    };
+    let pull_requests = hosted_remote
+        .api
+        .pull_requests(&hosted_remote.remote, Some(remote_branch.as_ref()))
+        .await?;
+    Ok(pull_requests.into_iter().next())
+}
+
+/// Resolves the hosting provider of the remote that the repository's current branch tracks.
+fn remote_provider(
+    repository: &Entity<Repository>,
+    cx: &mut App,
+) -> Task<
+    Result<(
+        SharedString,
+        Arc<dyn GitHostingProvider + Send + Sync + 'static>,
+        ParsedGitRemote,
+    )>,
+> {
+    let (remote_name, _) = branch_remote(repository.read(cx).branch.as_ref());
+    let remote_url = repository.read(cx).remote_url(remote_name.to_string());
+    let provider_registry = GitHostingProviderRegistry::default_global(cx);
+    cx.spawn(async move |_| {
+        let remote_url = remote_url
+            .await??
+            .ok_or_else(|| anyhow!("remote \"{remote_name}\" not found"))?;
+        let (provider, remote) = parse_git_remote_url(provider_registry, &remote_url)
+            .ok_or_else(|| anyhow!("\"{remote_name}\" is not hosted on a known Git provider"))?;
+        Ok((remote_name, provider, remote))
+    })
+}
+
+/// Resolves the hosting provider of the remote that the repository's current branch tracks, and
+/// creates a client for its API.
+pub fn hosted_remote(repository: &Entity<Repository>, cx: &mut App) -> Task<Result<HostedRemote>> {
+    let remote_provider = remote_provider(repository, cx);
+    let http_client = cx.http_client();
+    cx.spawn(async move |cx| {
+        let (remote_name, provider, remote) = remote_provider.await?;
+        let api = git_hosting_providers::api_client_for_provider(&provider, http_client, cx)
+            .await?
+            .ok_or_else(|| anyhow!("{} does not support pull requests", provider.name()))?;
+        Ok(HostedRemote {
+            remote_name,
+            provider,
+            remote: Arc::new(remote),
+            api,
+        })
+    })
+}
+
+/// Asks for the API token to use for the hosting provider of the active repository's remote, and
+/// stores it with the system's credentials. An empty token removes the stored one.
+pub(crate) fn set_hosting_provider_token(
+    workspace: &mut Workspace,
+    _: &git::SetHostingProviderToken,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(repository) = workspace.project().read(cx).active_repository(cx) else {
+        return;
+    };
+    let remote_provider = remote_provider(&repository, cx);
+    cx.spawn_in(window, async move |workspace, cx| {
+        let (_, provider, _) = remote_provider.await?;
+        let (tx, rx) = oneshot::channel();
+        workspace.update_in(cx, |workspace, window, cx| {
+            let operation = format!("{} API Token", provider.name());
+            let prompt = format!("Token for {} (leave empty to remove):", provider.base_url());
+            workspace.toggle_modal(window, cx, |window, cx| {
+                AskPassModal::new(operation.into(), prompt.into(), tx, window, cx)
+            });
+        })?;
+        // The modal was dismissed without a token.
+        let Ok(token) = rx.await else {
+            return Ok(());
+        };
+        git_hosting_providers::store_api_token(&provider, token, cx).await
+    })
+    .detach_and_prompt_err("Failed to set the API token", window, cx, |_, _, _| None);
+}
+
+/// The pull request of the current branch of the active repository, along with the status of
+/// its CI checks. Only looked up when the `git.pull_request_status` setting is enabled.
+pub struct BranchPullRequest {
+    project: Entity<Project>,
+    pull_request: Option<PullRequestDetails>,
+    ci_status: Option<CiStatus>,
+    /// The repository, branch and HEAD commit that the pull request was loaded for.
+    loaded_for: Option<(RepositoryId, SharedString, Option<SharedString>)>,
+    refresh_task: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl BranchPullRequest {
+    pub fn new(project: Entity<Project>, cx: &mut Context<Self>) -> Self {
+        let git_store = project.read(cx).git_store().clone();
+        let subscriptions = vec![
+            cx.subscribe(&git_store, |this, _, event, cx| match event {
+                GitStoreEvent::ActiveRepositoryChanged(_)
+                | GitStoreEvent::RepositoryUpdated(_, _, true)
+                | GitStoreEvent::RepositoryRemoved(_) => this.refresh_if_needed(cx),
+                _ => {}
+            }),
+            cx.observe_global::<SettingsStore>(|this, cx| this.refresh_if_needed(cx)),
+        ];
+        let mut this = Self {
+            project,
+            pull_request: None,
+            ci_status: None,
+            loaded_for: None,
+            refresh_task: Task::ready(()),
+            _subscriptions: subscriptions,
+        };
+        this.refresh_if_needed(cx);
+        this
+    }
+
+    pub fn pull_request(&self) -> Option<&PullRequestDetails> {
+        self.pull_request.as_ref()
+    }
+
+    pub fn ci_status(&self) -> Option<CiStatus> {
+        self.ci_status
+    }
+
+    fn refresh_if_needed(&mut self, cx: &mut Context<Self>) {
+        let enabled = ProjectSettings::get_global(cx)
+            .git
+            .pull_request_status_enabled();
+        let repository = self
+            .project
+            .read(cx)
+            .active_repository(cx)
+            .filter(|_| enabled);
+        let loaded_for = repository.as_ref().and_then(|repository| {
+            let repository = repository.read(cx);
+            let branch = repository.branch.as_ref()?;
+            Some((
+                repository.id,
+                branch.name.clone(),
+                branch
+                    .most_recent_commit
+                    .as_ref()
+                    .map(|commit| commit.sha.clone()),
+            ))
+        });
+        if loaded_for == self.loaded_for {
+            return;
+        }
+        self.loaded_for = loaded_for;
+        self.pull_request = None;
+        self.ci_status = None;
+        cx.notify();
+
+        let Some(repository) = repository.filter(|_| self.loaded_for.is_some()) else {
+            self.refresh_task = Task::ready(());
+            return;
+        };
+        let branch = repository.read(cx).branch.clone();
+        let hosted_remote = hosted_remote(&repository, cx);
+        self.refresh_task = cx.spawn(async move |this, cx| {
+            // Repositories that aren't hosted on a supported provider simply have no pull requests.
+            let Some(hosted_remote) = hosted_remote.await.ok() else {
+                return;
+            };
+            let Some(pull_request) = find_branch_pull_request(&hosted_remote, branch.as_ref())
+                .await
+                .log_err()
+                .flatten()
+            else {
+                return;
+            };
+            let head_sha = pull_request.head_sha.clone();
+            if this
+                .update(cx, |this, cx| {
+                    this.pull_request = Some(pull_request);
+                    cx.notify();
+                })
+                .is_err()
+            {
+                return;
+            }
+
+            let Some(head_sha) = head_sha else {
+                return;
+            };
+            loop {
+                let Some(ci_status) = hosted_remote
+                    .api
+                    .ci_status(&hosted_remote.remote, &head_sha)
+                    .await
+                    .log_err()
+                else {
+                    return;
+                };
+                if this
+                    .update(cx, |this, cx| {
+                        this.ci_status = ci_status;
+                        cx.notify();
+                    })
+                    .is_err()
+                    || ci_status != Some(CiStatus::Pending)
+                {
+                    return;
+                }
+                cx.background_executor()
+                    .timer(CI_STATUS_POLL_INTERVAL)
+                    .await;
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git::repository::{Upstream, UpstreamTracking};
+
+    #[test]
+    fn test_branch_remote() {
+        let branch = |upstream: Option<&str>| Branch {
+            is_head: true,
+            name: "local-name".into(),
+            upstream: upstream.map(|ref_name| Upstream {
+                ref_name: ref_name.to_string().into(),
+                tracking: UpstreamTracking::Gone,
+            }),
+            most_recent_commit: None,
+        };
+
+        assert_eq!(branch_remote(None), ("origin".into(), None));
+        assert_eq!(
+            branch_remote(Some(&branch(None))),
+            ("origin".into(), Some("local-name".into()))
+        );
+        assert_eq!(
+            branch_remote(Some(&branch(Some(
+                "refs/remotes/upstream/feature/some-thing"
+            )))),
+            ("upstream".into(), Some("feature/some-thing".into()))
+        );
+    }
+
+    #[test]
+    fn test_pull_request_number_for_branch() {
+        assert_eq!(
+            pull_request_number_for_branch(&pull_request_branch_name(42)),
+            Some(42)
+        );
+        assert_eq!(pull_request_number_for_branch("pr/some-feature"), None);
+        assert_eq!(pull_request_number_for_branch("main"), None);
+    }
+}
@@ -0,0 +1,490 @@
+use crate::picker_prompt;
+use anyhow::Result;
+use editor::Editor;
+use git::{
+    InteractiveRebase,
+    rebase::{RebaseAction, RebaseTodoEntry},
+    repository::{LogEntry, LogFilter},
+};
+use gpui::{
+    AnyElement, App, Corner, Entity, EventEmitter, FocusHandle, Focusable, KeyContext,
+    ScrollHandle, Task, actions,
+};
+use project::git_store::Repository;
+use ui::{ContextMenu, KeyBinding, ListItem, PopoverMenu, prelude::*};
+use util::ResultExt as _;
+use workspace::{
+    Workspace,
+    item::{Item, ItemEvent, TabContentParams},
+    notifications::DetachAndPromptErr,
+};
+
+actions!(
+    rebase_editor,
+    [
+        MoveUp,
+        MoveDown,
+        PickCommit,
+        RewordCommit,
+        EditCommit,
+        SquashCommit,
+        FixupCommit,
+        DropCommit,
+        StartRebase
+    ]
+);
+
+/// The number of recent commits offered as the base of a rebase.
+const BASE_CANDIDATE_COUNT: usize = 100;
+
+pub(crate) fn register(workspace: &mut Workspace) {
+    workspace.register_action(|workspace, _: &InteractiveRebase, window, cx| {
+        let Some(repository) = workspace.project().read(cx).active_repository(cx) else {
+            return;
+        };
+        RebaseEditor::select_base(repository, workspace, window, cx).detach_and_prompt_err(
+            "Failed to load commits",
+            window,
+            cx,
+            |_, _, _| None,
+        );
+    });
+}
+
+struct RebaseEditorEntry {
+    action: RebaseAction,
+    commit: LogEntry,
+    /// Edits the new message of the commit, once it has been reworded.
+    message_editor: Option<Entity<Editor>>,
+}
+
+/// Edits the todo list of an interactive rebase, and then runs it.
+pub struct RebaseEditor {
+    repository: Entity<Repository>,
+    base: LogEntry,
+    /// The commits to rebase, oldest first.
+    entries: Vec<RebaseEditorEntry>,
+    selected_ix: usize,
+    rebase_pending: bool,
+    scroll_handle: ScrollHandle,
+    focus_handle: FocusHandle,
+}
+
+impl RebaseEditor {
+    /// Prompts for the commit to rebase onto among the recent commits of HEAD, and opens an
+    /// editor for the commits after it.
+    fn select_base(
+        repository: Entity<Repository>,
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Task<Result<()>> {
+        let log = repository
+            .read(cx)
+            .log(LogFilter::Head, 0, BASE_CANDIDATE_COUNT);
+        let workspace_handle = workspace.weak_handle();
+        cx.spawn_in(window, async move |_, cx| {
+            let commits = log.await??;
+            // HEAD can't be the base, since there would be nothing to rebase.
+            let candidates: Vec<SharedString> = commits
+                .iter()
+                .skip(1)
+                .map(|commit| format!("{} {}", short_sha(&commit.sha), commit.subject).into())
+                .collect();
+            let selection = cx
+                .update(|window, cx| {
+                    picker_prompt::prompt(
+                        "Select the commit to rebase onto",
+                        candidates,
+                        workspace_handle.clone(),
+                        window,
+                        cx,
+                    )
+                })?
+                .await;
+            let Some(ix) = selection else {
+                return Ok(());
+            };
+
+            let base_ix = ix + 1;
+            let base = commits[base_ix].clone();
+            // Like `git rebase --interactive`, merge commits are left out.
+            let entries = commits[..base_ix]
+                .iter()
+                .rev()
+                .filter(|commit| commit.parent_shas.len() <= 1)
+                .cloned()
+                .collect();
+            workspace_handle.update_in(cx, |workspace, window, cx| {
+                let editor = cx.new(|cx| Self::new(repository, base, entries, cx));
+                workspace.add_item_to_active_pane(Box::new(editor), None, true, window, cx);
+            })
+        })
+    }
+
+    fn new(
+        repository: Entity<Repository>,
+        base: LogEntry,
+        commits: Vec<LogEntry>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        Self {
+            repository,
+            base,
+            entries: commits
+                .into_iter()
+                .map(|commit| RebaseEditorEntry {
+                    action: RebaseAction::Pick,
+                    commit,
+                    message_editor: None,
+                })
+                .collect(),
+            selected_ix: 0,
+            rebase_pending: false,
+            scroll_handle: ScrollHandle::new(),
+            focus_handle: cx.focus_handle(),
+        }
+    }
+
+    fn dispatch_context(&self, window: &Window) -> KeyContext {
+        let mut context = KeyContext::new_with_defaults();
+        context.add("RebaseEditor");
+        if self.focus_handle.is_focused(window) {
+            context.add("not_editing");
+        }
+        context
+    }
+
+    fn select(&mut self, ix: usize, cx: &mut Context<Self>) {
+        self.selected_ix = ix.min(self.entries.len().saturating_sub(1));
+        self.scroll_handle.scroll_to_item(self.selected_ix);
+        cx.notify();
+    }
+
+    fn select_next(&mut self, _: &menu::SelectNext, _: &mut Window, cx: &mut Context<Self>) {
+        self.select(self.selected_ix + 1, cx);
+    }
+
+    fn select_previous(
+        &mut self,
+        _: &menu::SelectPrevious,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.select(self.selected_ix.saturating_sub(1), cx);
+    }
+
+    fn move_up(&mut self, _: &MoveUp, _: &mut Window, cx: &mut Context<Self>) {
+        if self.selected_ix > 0 {
+            self.entries.swap(self.selected_ix, self.selected_ix - 1);
+            self.select(self.selected_ix - 1, cx);
+        }
+    }
+
+    fn move_down(&mut self, _: &MoveDown, _: &mut Window, cx: &mut Context<Self>) {
+        if self.selected_ix + 1 < self.entries.len() {
+            self.entries.swap(self.selected_ix, self.selected_ix + 1);
+            self.select(self.selected_ix + 1, cx);
+        }
+    }
+
+    fn set_action(
+        &mut self,
+        ix: usize,
+        action: RebaseAction,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(entry) = self.entries.get_mut(ix) else {
+            return;
+        };
+        entry.action = action;
+        cx.notify();
+        if action != RebaseAction::Reword {
+            return;
+        }
+
+        let message_editor = match &entry.message_editor {
+            Some(message_editor) => message_editor.clone(),
+            None => {
+                let subject = entry.commit.subject.clone();
+                let message_editor = cx.new(|cx| {
+                    let mut editor = Editor::auto_height(8, window, cx);
+                    editor.set_text(subject.to_string(), window, cx);
+                    editor
+                });
+                entry.message_editor = Some(message_editor.clone());
+
+                // Replace the subject with the full message, unless it was already edited.
+                let show = self.repository.read(cx).show(entry.commit.sha.to_string());
+                let editor = message_editor.downgrade();
+                cx.spawn_in(window, async move |_, cx| {
+                    let details = show.await??;
+                    editor.update_in(cx, |editor, window, cx| {
+                        if editor.text(cx) == subject.as_ref() {
+                            editor.set_text(details.message.trim_end(), window, cx);
+                        }
+                    })
+                })
+                .detach_and_log_err(cx);
+                message_editor
+            }
+        };
+        window.focus(&message_editor.focus_handle(cx));
+    }
+
+    fn set_selected_action(
+        &mut self,
+        action: RebaseAction,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.set_action(self.selected_ix, action, window, cx);
+    }
+
+    fn start_rebase(&mut self, _: &StartRebase, window: &mut Window, cx: &mut Context<Self>) {
+        if self.rebase_pending {
+            return;
+        }
+
+        let entries = self
+            .entries
+            .iter()
+            .map(|entry| RebaseTodoEntry {
+                action: entry.action,
+                sha: entry.commit.sha.clone(),
+                subject: entry.commit.subject.clone(),
+                message: entry
+                    .message_editor
+                    .as_ref()
+                    .filter(|_| entry.action == RebaseAction::Reword)
+                    .map(|editor| editor.read(cx).text(cx)),
+            })
+            .collect();
+        let rebase = self
+            .repository
+            .read(cx)
+            .rebase_interactive(Some(self.base.sha.to_string()), entries);
+        self.rebase_pending = true;
+        cx.notify();
+
+        cx.spawn_in(window, async move |this, cx| {
+            let result = rebase.await;
+            this.update(cx, |this, cx| {
+                this.rebase_pending = false;
+                cx.notify();
+            })?;
+            result??;
+            // A rebase that stopped is continued from the git panel.
+            this.update(cx, |_, cx| cx.emit(ItemEvent::CloseItem))
+        })
+        .detach_and_prompt_err("Failed to rebase", window, cx, |_, _, _| None);
+    }
+
+    fn render_entry(&self, ix: usize, cx: &mut Context<Self>) -> AnyElement {
+        let entry = &self.entries[ix];
+        let this = cx.entity().downgrade();
+        let action_menu = PopoverMenu::new(("rebase-action-menu", ix))
+            .trigger(
+                Button::new(("rebase-action", ix), entry.action.as_str())
+                    .label_size(LabelSize::Small)
+                    .style(ButtonStyle::Filled),
+            )
+            .menu(move |window, cx| {
+                let this = this.clone();
+                Some(ContextMenu::build(window, cx, move |mut menu, _, _| {
+                    for action in RebaseAction::ALL {
+                        let this = this.clone();
+                        menu = menu.entry(action.as_str(), None, move |window, cx| {
+                            this.update(cx, |this, cx| this.set_action(ix, action, window, cx))
+                                .ok();
+                        });
+                    }
+                    menu
+                }))
+            })
+            .anchor(Corner::TopLeft);
+
+        let is_dropped = entry.action == RebaseAction::Drop;
+        let mut subject = Label::new(entry.commit.subject.clone()).truncate();
+        if is_dropped {
+            subject = subject.strikethrough().color(Color::Muted);
+        }
+
+        ListItem::new(("rebase-entry", ix))
+            .toggle_state(ix == self.selected_ix)
+            .on_click(cx.listener(move |this, _, window, cx| {
+                this.select(ix, cx);
+                window.focus(&this.focus_handle);
+            }))
+            .child(
+                v_flex()
+                    .w_full()
+                    .gap_1()
+                    .child(
+                        h_flex()
+                            .w_full()
+                            .gap_2()
+                            .child(div().w(px(72.)).flex_none().child(action_menu))
+                            .when(entry.action.is_meld(), |row| {
+                                row.child(
+                                    Icon::new(IconName::ArrowUp)
+                                        .size(IconSize::Small)
+                                        .color(Color::Muted),
+                                )
+                            })
+                            .child(
+                                Label::new(short_sha(&entry.commit.sha))
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted)
+                                    .buffer_font(cx),
+                            )
+                            .child(div().flex_1().overflow_hidden().child(subject)),
+                    )
+                    .when_some(
+                        entry
+                            .message_editor
+                            .clone()
+                            .filter(|_| entry.action == RebaseAction::Reword),
+                        |column, message_editor| {
+                            column.child(
+                                div()
+                                    .ml(px(80.))
+                                    .p_1()
+                                    .rounded_sm()
+                                    .border_1()
+                                    .border_color(cx.theme().colors().border_variant)
+                                    .child(message_editor),
+                            )
+                        },
+                    ),
+            )
+            .into_any_element()
+    }
+
+    fn title(&self) -> SharedString {
+        format!("Rebase onto {}", short_sha(&self.base.sha)).into()
+    }
+}
+
+fn short_sha(sha: &str) -> String {
+    sha.chars().take(git::SHORT_SHA_LENGTH).collect()
+}
+
+impl EventEmitter<ItemEvent> for RebaseEditor {}
+
+impl Focusable for RebaseEditor {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for RebaseEditor {
+    type Event = ItemEvent;
+
+    fn to_item_events(event: &ItemEvent, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::GitBranch).color(Color::Muted))
+    }
+
+    fn tab_content(&self, params: TabContentParams, _window: &Window, _: &App) -> AnyElement {
+        Label::new(self.title())
+            .color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn tab_tooltip_text(&self, _: &App) -> Option<SharedString> {
+        Some(self.title())
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("Rebase Editor Opened")
+    }
+}
+
+impl Render for RebaseEditor {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let focus_handle = self.focus_handle.clone();
+        let header = h_flex()
+            .w_full()
+            .px_2()
+            .py_1()
+            .gap_2()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(
+                Label::new(format!(
+                    "Rebase {} commits onto {} {}",
+                    self.entries.len(),
+                    short_sha(&self.base.sha),
+                    self.base.subject
+                ))
+                .truncate(),
+            )
+            .child(div().flex_1())
+            .child(
+                Button::new("start-rebase", "Start Rebase")
+                    .style(ButtonStyle::Filled)
+                    .disabled(self.rebase_pending || self.entries.is_empty())
+                    .key_binding(KeyBinding::for_action_in(
+                        &StartRebase,
+                        &focus_handle,
+                        window,
+                        cx,
+                    ))
+                    .on_click(cx.listener(|this, _, window, cx| {
+                        this.start_rebase(&StartRebase, window, cx)
+                    })),
+            );
+
+        let entries = (0..self.entries.len())
+            .map(|ix| self.render_entry(ix, cx))
+            .collect::<Vec<_>>();
+
+        v_flex()
+            .key_context(self.dispatch_context(window))
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_previous))
+            .on_action(cx.listener(Self::move_up))
+            .on_action(cx.listener(Self::move_down))
+            .on_action(cx.listener(Self::start_rebase))
+            .on_action(cx.listener(|this, _: &PickCommit, window, cx| {
+                this.set_selected_action(RebaseAction::Pick, window, cx)
+            }))
+            .on_action(cx.listener(|this, _: &RewordCommit, window, cx| {
+                this.set_selected_action(RebaseAction::Reword, window, cx)
+            }))
+            .on_action(cx.listener(|this, _: &EditCommit, window, cx| {
+                this.set_selected_action(RebaseAction::Edit, window, cx)
+            }))
+            .on_action(cx.listener(|this, _: &SquashCommit, window, cx| {
+                this.set_selected_action(RebaseAction::Squash, window, cx)
+            }))
+            .on_action(cx.listener(|this, _: &FixupCommit, window, cx| {
+                this.set_selected_action(RebaseAction::Fixup, window, cx)
+            }))
+            .on_action(cx.listener(|this, _: &DropCommit, window, cx| {
+                this.set_selected_action(RebaseAction::Drop, window, cx)
+            }))
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(header)
+            .child(
+                v_flex()
+                    .id("rebase-entries")
+                    .flex_1()
+                    .p_1()
+                    .overflow_y_scroll()
+                    .track_scroll(&self.scroll_handle)
+                    .children(entries),
+            )
+    }
+}
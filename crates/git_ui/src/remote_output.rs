@@ -9,6 +9,9 @@ pub enum RemoteAction {
     Fetch,
     Pull(Remote),
     Push(SharedString, Remote),
+    PushTag(SharedString, Remote),
+    UpdateSubmodules,
+    CheckoutPullRequest(u32, SharedString),
 }
 
 impl RemoteAction {
@@ -17,6 +20,9 @@ impl RemoteAction {
             RemoteAction::Fetch => "fetch",
             RemoteAction::Pull(_) => "pull",
             RemoteAction::Push(_, _) => "push",
+            RemoteAction::PushTag(_, _) => "tag push",
+            RemoteAction::UpdateSubmodules => "submodule update",
+            RemoteAction::CheckoutPullRequest(_, _) => "pull request checkout",
         }
     }
 }
@@ -148,5 +154,35 @@ pub fn format_output(action: &RemoteAction, output: RemoteCommandOutput) -> Succ
                 }
             }
         }
+        RemoteAction::PushTag(tag_name, remote_ref) => {
+            if output.stderr.starts_with("Everything up to date") {
+                SuccessMessage {
+                    message: output.stderr.trim().to_owned(),
+                    style: SuccessStyle::Toast,
+                }
+            } else {
+                SuccessMessage {
+                    message: format!("Pushed tag {} to {}", tag_name, remote_ref.name),
+                    style: SuccessStyle::ToastWithLog { output },
+                }
+            }
+        }
+        RemoteAction::UpdateSubmodules => {
+            if output.is_empty() {
+                SuccessMessage {
+                    message: "Submodules already up to date".into(),
+                    style: SuccessStyle::Toast,
+                }
+            } else {
+                SuccessMessage {
+                    message: "Updated submodules".into(),
+                    style: SuccessStyle::ToastWithLog { output },
+                }
+            }
+        }
+        RemoteAction::CheckoutPullRequest(number, branch_name) => SuccessMessage {
+            message: format!("Checked out #{number} as {branch_name}"),
+            style: SuccessStyle::Toast,
+        },
     }
 }
@@ -0,0 +1,302 @@
+use std::{any::Any, path::Path, sync::Arc};
+
+use anyhow::Context as _;
+use collections::{HashMap, HashSet};
+use editor::{
+    Addon, Editor, EditorMode,
+    display_map::{BlockPlacement, BlockProperties, BlockStyle, CustomBlockId, RenderBlock},
+    scroll::Autoscroll,
+};
+use git::{HideReviewComments, PullRequestDetails, ReviewComment, ShowReviewComments};
+use gpui::{AppContext as _, Entity, FontWeight, Global, Subscription};
+use language::{OffsetRangeExt as _, Point};
+use project::File;
+use theme::ActiveTheme;
+use ui::prelude::*;
+use workspace::{
+    OpenOptions, Toast, Workspace,
+    notifications::{DetachAndPromptErr, NotificationId},
+};
+
+use crate::pull_requests::{find_branch_pull_request, hosted_remote};
+
+/// The review comments of a pull request, shown inline in the editors of the commented files.
+struct ReviewComments {
+    /// The working directory of the repository that the comments' paths are relative to.
+    work_directory: Arc<Path>,
+    pull_request: PullRequestDetails,
+    comments: Vec<ReviewComment>,
+}
+
+#[derive(Default)]
+struct GlobalReviewComments(Option<Arc<ReviewComments>>);
+
+impl Global for GlobalReviewComments {}
+
+/// Shows the review comments of the pull request in an editor, above the commented lines.
+struct ReviewCommentsAddon {
+    block_ids: Entity<HashSet<CustomBlockId>>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl Addon for ReviewCommentsAddon {
+    fn to_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+pub(crate) fn register(workspace: &mut Workspace) {
+    workspace.register_action(show_review_comments);
+    workspace.register_action(|workspace, _: &HideReviewComments, _, cx| {
+        cx.set_global(GlobalReviewComments(None));
+        workspace.dismiss_toast(&NotificationId::unique::<ReviewComments>(), cx);
+    });
+}
+
+pub(crate) fn register_editor(
+    editor: &mut Editor,
+    window: Option<&mut Window>,
+    cx: &mut Context<Editor>,
+) {
+    let Some(window) = window else {
+        return;
+    };
+    if editor.mode() != EditorMode::Full || editor.project.is_none() {
+        return;
+    }
+
+    let subscriptions = vec![
+        cx.observe_global_in::<GlobalReviewComments>(window, |editor, _, cx| {
+            refresh_review_comments(editor, cx)
+        }),
+        cx.subscribe_in(
+            editor.buffer(),
+            window,
+            |editor, _, event, _window, cx| match event {
+                multi_buffer::Event::ExcerptsAdded { .. }
+                | multi_buffer::Event::ExcerptsRemoved { .. }
+                | multi_buffer::Event::ExcerptsExpanded { .. }
+                | multi_buffer::Event::FileHandleChanged => refresh_review_comments(editor, cx),
+                _ => {}
+            },
+        ),
+    ];
+    editor.register_addon(ReviewCommentsAddon {
+        block_ids: cx.new(|_| HashSet::default()),
+        _subscriptions: subscriptions,
+    });
+    refresh_review_comments(editor, cx);
+}
+
+/// Loads the review comments of the pull request of the active repository's current branch.
+fn show_review_comments(
+    workspace: &mut Workspace,
+    _: &ShowReviewComments,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(repository) = workspace.project().read(cx).active_repository(cx) else {
+        return;
+    };
+    let branch = repository.read(cx).branch.clone();
+    let work_directory = repository.read(cx).work_directory_abs_path.clone();
+    let hosted_remote = hosted_remote(&repository, cx);
+    cx.spawn_in(window, async move |workspace, cx| {
+        let hosted_remote = hosted_remote.await?;
+        let pull_request = find_branch_pull_request(&hosted_remote, branch.as_ref())
+            .await?
+            .context("The current branch has no open pull request")?;
+        let comments = hosted_remote
+            .api
+            .review_comments(&hosted_remote.remote, pull_request.number)
+            .await?;
+
+        let first_comment = comments
+            .iter()
+            .find_map(|comment| Some((work_directory.join(&comment.path), comment.line?)));
+        let message = format!(
+            "Showing {} review comment{} of #{}",
+            comments.len(),
+            if comments.len() == 1 { "" } else { "s" },
+            pull_request.number
+        );
+        cx.update(|_, cx| {
+            cx.set_global(GlobalReviewComments(Some(Arc::new(ReviewComments {
+                work_directory,
+                pull_request,
+                comments,
+            }))));
+        })?;
+        workspace.update(cx, |workspace, cx| {
+            workspace.show_toast(
+                Toast::new(NotificationId::unique::<ReviewComments>(), message)
+                    .on_click("Hide", |window, cx| {
+                        window.dispatch_action(HideReviewComments.boxed_clone(), cx)
+                    }),
+                cx,
+            );
+        })?;
+
+        // Open the first commented file, so that the comments are visible right away.
+        let Some((abs_path, line)) = first_comment else {
+            return anyhow::Ok(());
+        };
+        let item = workspace
+            .update_in(cx, |workspace, window, cx| {
+                workspace.open_abs_path(abs_path, OpenOptions::default(), window, cx)
+            })?
+            .await?;
+        if let Some(editor) = item.downcast::<Editor>() {
+            editor.update_in(cx, |editor, window, cx| {
+                let point = Point::new(line.saturating_sub(1), 0);
+                editor.change_selections(Some(Autoscroll::center()), window, cx, |selections| {
+                    selections.select_ranges([point..point])
+                });
+            })?;
+        }
+        anyhow::Ok(())
+    })
+    .detach_and_prompt_err("Failed to load review comments", window, cx, |e, _, _| {
+        Some(e.to_string())
+    });
+}
+
+/// Redraws the review comments of the files shown in the editor.
+fn refresh_review_comments(editor: &mut Editor, cx: &mut Context<Editor>) {
+    let Some(block_ids) = editor
+        .addon::<ReviewCommentsAddon>()
+        .map(|addon| addon.block_ids.clone())
+    else {
+        return;
+    };
+    let review_comments = cx
+        .try_global::<GlobalReviewComments>()
+        .and_then(|global| global.0.clone());
+
+    let mut blocks = Vec::new();
+    if let Some(review_comments) = &review_comments {
+        let mut comments_by_path = HashMap::<&Path, Vec<&ReviewComment>>::default();
+        for comment in &review_comments.comments {
+            if comment.line.is_some() {
+                comments_by_path
+                    .entry(Path::new(&comment.path))
+                    .or_default()
+                    .push(comment);
+            }
+        }
+
+        let multibuffer = editor.buffer().read(cx);
+        let snapshot = multibuffer.snapshot(cx);
+        for buffer in multibuffer.all_buffers() {
+            let buffer = buffer.read(cx);
+            let Some(abs_path) = File::from_dyn(buffer.file())
+                .and_then(|file| file.worktree.read(cx).absolutize(&file.path).ok())
+            else {
+                continue;
+            };
+            let Some(comments) = abs_path
+                .strip_prefix(&review_comments.work_directory)
+                .ok()
+                .and_then(|path| comments_by_path.get(path))
+            else {
+                continue;
+            };
+
+            let buffer_snapshot = buffer.snapshot();
+            for (excerpt_id, excerpt_range) in
+                multibuffer.excerpts_for_buffer(buffer.remote_id(), cx)
+            {
+                let context = excerpt_range.context.to_point(&buffer_snapshot);
+                for comment in comments {
+                    let Some(line) = comment.line else {
+                        continue;
+                    };
+                    // The comment refers to the pull request's version of the file, which may
+                    // have fewer lines than the local one.
+                    let row = line.saturating_sub(1).min(buffer_snapshot.max_point().row);
+                    if row < context.start.row || row > context.end.row {
+                        continue;
+                    }
+                    let anchor = buffer_snapshot.anchor_before(Point::new(row, 0));
+                    let Some(anchor) = snapshot.anchor_in_excerpt(excerpt_id, anchor) else {
+                        continue;
+                    };
+                    blocks.push(BlockProperties {
+                        placement: BlockPlacement::Below(anchor),
+                        height: comment.body.lines().count().max(1) as u32 + 1,
+                        style: BlockStyle::Flex,
+                        render: render_review_comment(
+                            review_comments.pull_request.number,
+                            (*comment).clone(),
+                        ),
+                        priority: 0,
+                    });
+                }
+            }
+        }
+    }
+
+    if blocks.is_empty() && block_ids.read(cx).is_empty() {
+        return;
+    }
+    let old_block_ids = block_ids.update(cx, |block_ids, _| std::mem::take(block_ids));
+    editor.remove_blocks(old_block_ids, None, cx);
+    let new_block_ids = editor.insert_blocks(blocks, None, cx);
+    block_ids.update(cx, |block_ids, _| block_ids.extend(new_block_ids));
+}
+
+fn render_review_comment(pull_request_number: u32, comment: ReviewComment) -> RenderBlock {
+    Arc::new(move |cx| {
+        let author = comment
+            .author
+            .clone()
+            .unwrap_or_else(|| "Unknown author".into());
+        let url = comment.url.clone();
+
+        h_flex()
+            .id(cx.block_id)
+            .w_full()
+            .pl(cx.gutter_dimensions.full_width())
+            .child(
+                v_flex()
+                    .pl_2()
+                    .border_l_2()
+                    .border_color(cx.theme().status().info_border)
+                    .child(
+                        h_flex()
+                            .h(cx.line_height)
+                            .gap_1()
+                            .child(
+                                Icon::new(IconName::MessageBubbles)
+                                    .size(IconSize::XSmall)
+                                    .color(Color::Info),
+                            )
+                            .child(
+                                Label::new(author)
+                                    .size(LabelSize::Small)
+                                    .weight(FontWeight::BOLD),
+                            )
+                            .child(
+                                Label::new(format!("on #{pull_request_number}"))
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                            )
+                            .when_some(url, |this, url| {
+                                this.child(
+                                    IconButton::new("open-review-comment", IconName::ArrowUpRight)
+                                        .icon_size(IconSize::XSmall)
+                                        .icon_color(Color::Muted)
+                                        .tooltip(ui::Tooltip::text("Open in Browser"))
+                                        .on_click(move |_, _, cx| cx.open_url(url.as_str())),
+                                )
+                            }),
+                    )
+                    .children(comment.body.lines().map(|line| {
+                        h_flex()
+                            .h(cx.line_height)
+                            .child(Label::new(line.to_string()).size(LabelSize::Small))
+                    })),
+            )
+            .into_any_element()
+    })
+}
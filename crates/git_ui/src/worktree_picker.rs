@@ -0,0 +1,578 @@
+use fuzzy::StringMatchCandidate;
+
+use git::repository::GitWorktree;
+use gpui::{
+    App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, InteractiveElement,
+    IntoElement, ParentElement, PromptLevel, Render, SharedString, Styled, Subscription, Task,
+    WeakEntity, Window, rems,
+};
+use picker::{Picker, PickerDelegate, PickerEditorPosition};
+use project::git_store::Repository;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use ui::{HighlightedLabel, ListItem, ListItemSpacing, Tooltip, prelude::*};
+use util::ResultExt;
+use workspace::notifications::DetachAndPromptErr;
+use workspace::{ModalView, OpenOptions, Workspace};
+
+pub fn register(workspace: &mut Workspace) {
+    workspace.register_action(open);
+}
+
+pub fn open(
+    workspace: &mut Workspace,
+    _: &git::ManageWorktrees,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let repository = workspace.project().read(cx).active_repository(cx).clone();
+    let weak_workspace = cx.entity().downgrade();
+    workspace.toggle_modal(window, cx, |window, cx| {
+        WorktreeList::new(repository, weak_workspace, window, cx)
+    })
+}
+
+pub struct WorktreeList {
+    pub picker: Entity<Picker<WorktreeListDelegate>>,
+    _subscription: Subscription,
+}
+
+impl WorktreeList {
+    fn new(
+        repository: Option<Entity<Repository>>,
+        workspace: WeakEntity<Workspace>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let delegate = WorktreeListDelegate::new(repository, workspace);
+        let picker = cx.new(|cx| {
+            let mut picker = Picker::uniform_list(delegate, window, cx);
+            picker.delegate.reload(window, cx);
+            picker
+        });
+
+        let _subscription = cx.subscribe(&picker, |_, _, _, cx| {
+            cx.emit(DismissEvent);
+        });
+
+        Self {
+            picker,
+            _subscription,
+        }
+    }
+}
+
+impl ModalView for WorktreeList {}
+impl EventEmitter<DismissEvent> for WorktreeList {}
+
+impl Focusable for WorktreeList {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for WorktreeList {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .w(rems(34.))
+            .child(self.picker.clone())
+            .on_mouse_down_out({
+                cx.listener(move |this, _, window, cx| {
+                    this.picker.update(cx, |this, cx| {
+                        this.cancel(&Default::default(), window, cx);
+                    })
+                })
+            })
+    }
+}
+
+#[derive(Debug, Clone)]
+enum WorktreeEntry {
+    /// An existing worktree of the repository.
+    Worktree {
+        worktree: GitWorktree,
+        positions: Vec<usize>,
+    },
+    /// A branch that isn't checked out in any worktree.
+    Branch {
+        name: SharedString,
+        positions: Vec<usize>,
+    },
+    /// A branch to create from HEAD, named after the query.
+    NewBranch { name: SharedString },
+}
+
+pub struct WorktreeListDelegate {
+    matches: Vec<WorktreeEntry>,
+    all_worktrees: Option<Vec<GitWorktree>>,
+    available_branches: Vec<SharedString>,
+    repo: Option<Entity<Repository>>,
+    workspace: WeakEntity<Workspace>,
+    selected_index: usize,
+    last_query: String,
+}
+
+impl WorktreeListDelegate {
+    fn new(repo: Option<Entity<Repository>>, workspace: WeakEntity<Workspace>) -> Self {
+        Self {
+            matches: Vec::new(),
+            all_worktrees: None,
+            available_branches: Vec::new(),
+            repo,
+            workspace,
+            selected_index: 0,
+            last_query: String::new(),
+        }
+    }
+
+    /// Loads the worktrees of the repository, and the branches that can be checked out in a
+    /// new worktree.
+    fn reload(&mut self, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        let worktrees_request = repo.read(cx).worktrees();
+        let branches_request = repo.read(cx).branches();
+        cx.spawn_in(window, async move |picker, cx| {
+            let worktrees = worktrees_request.await??;
+            let mut branches = branches_request.await??;
+            branches.sort_by_key(|branch| {
+                branch
+                    .most_recent_commit
+                    .as_ref()
+                    .map(|commit| 0 - commit.commit_timestamp)
+            });
+            let available_branches = branches
+                .into_iter()
+                .filter(|branch| !branch.name.starts_with("refs/remotes/"))
+                .filter(|branch| {
+                    !worktrees
+                        .iter()
+                        .any(|worktree| worktree.branch.as_ref() == Some(&branch.name))
+                })
+                .map(|branch| branch.name)
+                .collect();
+
+            picker.update_in(cx, |picker, window, cx| {
+                picker.delegate.all_worktrees = Some(worktrees);
+                picker.delegate.available_branches = available_branches;
+                picker.refresh(window, cx);
+            })?;
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn is_current(&self, worktree: &GitWorktree, cx: &App) -> bool {
+        self.repo.as_ref().is_some_and(|repo| {
+            worktree.path.as_path() == repo.read(cx).work_directory_abs_path.as_ref()
+        })
+    }
+
+    fn can_open_in_new_window(&self, cx: &App) -> bool {
+        self.workspace
+            .read_with(cx, |workspace, cx| workspace.project().read(cx).is_local())
+            .unwrap_or(false)
+    }
+
+    fn can_add_to_project(&self, cx: &App) -> bool {
+        self.workspace
+            .read_with(cx, |workspace, cx| {
+                !workspace.project().read(cx).is_via_collab()
+            })
+            .unwrap_or(false)
+    }
+
+    fn create_worktree(
+        &self,
+        branch: SharedString,
+        create_branch: bool,
+        add_to_project: bool,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) {
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        let path = default_worktree_path(&repo.read(cx).work_directory_abs_path, &branch);
+        let create = repo
+            .read(cx)
+            .create_worktree(path.clone(), branch.to_string(), create_branch);
+        cx.spawn_in(window, async move |picker, cx| {
+            create.await??;
+            picker.update_in(cx, |picker, window, cx| {
+                picker
+                    .delegate
+                    .open_worktree(path, add_to_project, window, cx);
+            })?;
+            anyhow::Ok(())
+        })
+        .detach_and_prompt_err("Failed to create worktree", window, cx, |e, _, _| {
+            Some(e.to_string())
+        });
+    }
+
+    /// Opens a worktree in a new window, or as an additional worktree of the current project.
+    fn open_worktree(
+        &self,
+        path: PathBuf,
+        add_to_project: bool,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) {
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        if add_to_project || !self.can_open_in_new_window(cx) {
+            let project = workspace.read(cx).project().clone();
+            let task = project.update(cx, |project, cx| {
+                project.find_or_create_worktree(&path, true, cx)
+            });
+            cx.spawn_in(window, async move |_, _| {
+                task.await?;
+                anyhow::Ok(())
+            })
+            .detach_and_prompt_err("Failed to add worktree", window, cx, |e, _, _| {
+                Some(e.to_string())
+            });
+        } else {
+            let app_state = workspace.read(cx).app_state().clone();
+            workspace::open_paths(
+                &[path],
+                app_state,
+                OpenOptions {
+                    open_new_workspace: Some(true),
+                    ..Default::default()
+                },
+                cx,
+            )
+            .detach_and_log_err(cx);
+        }
+        cx.emit(DismissEvent);
+    }
+
+    fn remove_worktree(
+        &mut self,
+        worktree: GitWorktree,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) {
+        let Some(repo) = self.repo.clone() else {
+            return;
+        };
+        let prompt = window.prompt(
+            PromptLevel::Warning,
+            &format!("Remove the worktree at {}?", worktree.path.display()),
+            Some("Uncommitted changes prevent the worktree from being removed."),
+            &["Remove", "Cancel"],
+            cx,
+        );
+        cx.spawn_in(window, async move |picker, cx| {
+            if prompt.await != Ok(0) {
+                return anyhow::Ok(());
+            }
+            cx.update(|_, cx| repo.read(cx).remove_worktree(worktree.path, false))?
+                .await??;
+            picker.update_in(cx, |picker, window, cx| {
+                picker.delegate.reload(window, cx);
+            })?;
+            anyhow::Ok(())
+        })
+        .detach_and_prompt_err("Failed to remove worktree", window, cx, |e, _, _| {
+            Some(e.to_string())
+        });
+    }
+}
+
+impl PickerDelegate for WorktreeListDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        "Select worktree or branch...".into()
+    }
+
+    fn editor_position(&self) -> PickerEditorPosition {
+        PickerEditorPosition::Start
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let Some(all_worktrees) = self.all_worktrees.clone() else {
+            return Task::ready(());
+        };
+        let available_branches = self.available_branches.clone();
+
+        cx.spawn_in(window, async move |picker, cx| {
+            let mut matches: Vec<WorktreeEntry> = if query.is_empty() {
+                all_worktrees
+                    .into_iter()
+                    .map(|worktree| WorktreeEntry::Worktree {
+                        worktree,
+                        positions: Vec::new(),
+                    })
+                    .chain(
+                        available_branches
+                            .into_iter()
+                            .map(|name| WorktreeEntry::Branch {
+                                name,
+                                positions: Vec::new(),
+                            }),
+                    )
+                    .collect()
+            } else {
+                let candidates = all_worktrees
+                    .iter()
+                    .map(worktree_label)
+                    .chain(available_branches.iter().cloned())
+                    .enumerate()
+                    .map(|(ix, label)| StringMatchCandidate::new(ix, &label))
+                    .collect::<Vec<StringMatchCandidate>>();
+                fuzzy::match_strings(
+                    &candidates,
+                    &query,
+                    true,
+                    10000,
+                    &Default::default(),
+                    cx.background_executor().clone(),
+                )
+                .await
+                .into_iter()
+                .map(|candidate| {
+                    if let Some(worktree) = all_worktrees.get(candidate.candidate_id) {
+                        WorktreeEntry::Worktree {
+                            worktree: worktree.clone(),
+                            positions: candidate.positions,
+                        }
+                    } else {
+                        WorktreeEntry::Branch {
+                            name: available_branches[candidate.candidate_id - all_worktrees.len()]
+                                .clone(),
+                            positions: candidate.positions,
+                        }
+                    }
+                })
+                .collect()
+            };
+            picker
+                .update(cx, |picker, _| {
+                    let delegate = &mut picker.delegate;
+                    let query_names_branch = delegate
+                        .available_branches
+                        .iter()
+                        .any(|name| name.as_ref() == query)
+                        || delegate.all_worktrees.iter().flatten().any(|worktree| {
+                            worktree
+                                .branch
+                                .as_ref()
+                                .is_some_and(|branch| branch.as_ref() == query)
+                        });
+                    if !query.is_empty() && !query_names_branch {
+                        matches.push(WorktreeEntry::NewBranch {
+                            name: query.clone().into(),
+                        });
+                    }
+                    delegate.matches = matches;
+                    if delegate.matches.is_empty() {
+                        delegate.selected_index = 0;
+                    } else {
+                        delegate.selected_index =
+                            core::cmp::min(delegate.selected_index, delegate.matches.len() - 1);
+                    }
+                    delegate.last_query = query;
+                })
+                .log_err();
+        })
+    }
+
+    fn confirm(&mut self, secondary: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(entry) = self.matches.get(self.selected_index()).cloned() else {
+            return;
+        };
+        let add_to_project = secondary && self.can_add_to_project(cx);
+        match entry {
+            WorktreeEntry::Worktree { worktree, .. } => {
+                if self.is_current(&worktree, cx) {
+                    cx.emit(DismissEvent);
+                    return;
+                }
+                self.open_worktree(worktree.path, add_to_project, window, cx);
+            }
+            WorktreeEntry::Branch { name, .. } => {
+                self.create_worktree(name, false, add_to_project, window, cx);
+            }
+            WorktreeEntry::NewBranch { name } => {
+                self.create_worktree(name, true, add_to_project, window, cx);
+            }
+        }
+    }
+
+    fn dismissed(&mut self, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let entry = &self.matches[ix];
+        let work_directory = self
+            .repo
+            .as_ref()
+            .map(|repo| repo.read(cx).work_directory_abs_path.clone());
+
+        let (title, detail) = match entry {
+            WorktreeEntry::Worktree {
+                worktree,
+                positions,
+            } => {
+                let mut detail = worktree.path.display().to_string();
+                if self.is_current(worktree, cx) {
+                    detail.push_str(" (current)");
+                } else if worktree.is_locked {
+                    detail.push_str(" (locked)");
+                }
+                (
+                    HighlightedLabel::new(worktree_label(worktree), positions.clone())
+                        .truncate()
+                        .into_any_element(),
+                    detail,
+                )
+            }
+            WorktreeEntry::Branch { name, positions } => (
+                HighlightedLabel::new(name.clone(), positions.clone())
+                    .truncate()
+                    .into_any_element(),
+                work_directory
+                    .map(|work_directory| {
+                        format!(
+                            "Create worktree at {}",
+                            default_worktree_path(&work_directory, name).display()
+                        )
+                    })
+                    .unwrap_or_default(),
+            ),
+            WorktreeEntry::NewBranch { name } => (
+                Label::new(format!("Create branch \"{name}\" in a new worktree…"))
+                    .single_line()
+                    .into_any_element(),
+                match self
+                    .repo
+                    .as_ref()
+                    .and_then(|repo| repo.read(cx).branch.as_ref().map(|b| b.name.clone()))
+                {
+                    Some(current_branch) => format!("based off {current_branch}"),
+                    None => "based off the current branch".to_string(),
+                },
+            ),
+        };
+
+        let removable_worktree = match entry {
+            WorktreeEntry::Worktree { worktree, .. }
+                if !worktree.is_main && !worktree.is_locked && !self.is_current(worktree, cx) =>
+            {
+                Some(worktree.clone())
+            }
+            _ => None,
+        };
+
+        Some(
+            ListItem::new(SharedString::from(format!("worktree-{ix}")))
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .child(
+                    v_flex().w_full().overflow_x_hidden().child(title).child(
+                        Label::new(detail)
+                            .size(LabelSize::Small)
+                            .truncate()
+                            .color(Color::Muted),
+                    ),
+                )
+                .when_some(removable_worktree, |this, worktree| {
+                    this.end_hover_slot(
+                        IconButton::new(("remove-worktree", ix), IconName::Trash)
+                            .icon_size(IconSize::Small)
+                            .icon_color(Color::Muted)
+                            .tooltip(Tooltip::text("Remove Worktree"))
+                            .on_click(cx.listener(move |picker, _, window, cx| {
+                                picker
+                                    .delegate
+                                    .remove_worktree(worktree.clone(), window, cx);
+                            })),
+                    )
+                }),
+        )
+    }
+
+    fn no_matches_text(&self, _window: &mut Window, _cx: &mut App) -> Option<SharedString> {
+        None
+    }
+}
+
+/// The name under which a worktree is listed: its branch, or its directory when its HEAD is
+/// detached.
+fn worktree_label(worktree: &GitWorktree) -> SharedString {
+    worktree.branch.clone().unwrap_or_else(|| {
+        worktree
+            .path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string().into())
+            .unwrap_or_else(|| worktree.path.display().to_string().into())
+    })
+}
+
+/// New worktrees are created next to the repository's working directory, in a directory
+/// named after the repository and the branch, such as `zed-my-feature` for `my/feature`.
+fn default_worktree_path(work_directory: &Path, branch: &str) -> PathBuf {
+    let repository_name = work_directory
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "worktree".to_string());
+    let branch = branch.replace(['/', '\\'], "-");
+    let name = format!("{repository_name}-{branch}");
+    work_directory
+        .parent()
+        .map(|parent| parent.join(&name))
+        .unwrap_or_else(|| PathBuf::from(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_worktree_path() {
+        assert_eq!(
+            default_worktree_path(Path::new("/home/user/zed"), "my/feature"),
+            PathBuf::from("/home/user/zed-my-feature")
+        );
+        assert_eq!(
+            default_worktree_path(Path::new("/home/user/zed"), "main"),
+            PathBuf::from("/home/user/zed-main")
+        );
+    }
+}
@@ -0,0 +1,807 @@
+mod hierarchy_panel_settings;
+
+use std::sync::Arc;
+
+use anyhow::Context as _;
+use db::kvp::KEY_VALUE_STORE;
+use editor::{Editor, MultibufferSelectionMode, scroll::Autoscroll};
+use gpui::{
+    Action, AsyncApp, AsyncWindowContext, Entity, EventEmitter, FocusHandle, Focusable, KeyContext,
+    ListSizingBehavior, Render, ScrollStrategy, Subscription, Task, UniformListScrollHandle,
+    WeakEntity, actions, uniform_list,
+};
+use hierarchy_panel_settings::{HierarchyPanelDockPosition, HierarchyPanelSettings};
+use language::{Buffer, Location, OffsetRangeExt as _};
+use menu::{Confirm, SelectFirst, SelectLast, SelectNext, SelectPrevious};
+use project::{Fs, Project, lsp_command::HierarchyItem};
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsStore};
+use ui::{ListItem, ListItemSpacing, Tab, Tooltip, prelude::*};
+use util::ResultExt as _;
+use workspace::{
+    Workspace,
+    dock::{DockPosition, Panel, PanelEvent},
+};
+
+actions!(
+    hierarchy_panel,
+    [
+        CollapseSelectedEntry,
+        ExpandSelectedEntry,
+        FlipDirection,
+        OpenCallSites,
+        ShowIncomingCalls,
+        ShowOutgoingCalls,
+        ShowSubtypes,
+        ShowSupertypes,
+        ToggleFocus,
+    ]
+);
+
+const HIERARCHY_PANEL_KEY: &str = "HierarchyPanel";
+
+pub fn init_settings(cx: &mut App) {
+    HierarchyPanelSettings::register(cx);
+}
+
+pub fn init(cx: &mut App) {
+    init_settings(cx);
+
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        workspace
+            .register_action(|workspace, _: &ToggleFocus, window, cx| {
+                workspace.toggle_panel_focus::<HierarchyPanel>(window, cx);
+            })
+            .register_action(|workspace, _: &ShowIncomingCalls, window, cx| {
+                show_hierarchy(workspace, HierarchyKind::IncomingCalls, window, cx);
+            })
+            .register_action(|workspace, _: &ShowOutgoingCalls, window, cx| {
+                show_hierarchy(workspace, HierarchyKind::OutgoingCalls, window, cx);
+            })
+            .register_action(|workspace, _: &ShowSupertypes, window, cx| {
+                show_hierarchy(workspace, HierarchyKind::Supertypes, window, cx);
+            })
+            .register_action(|workspace, _: &ShowSubtypes, window, cx| {
+                show_hierarchy(workspace, HierarchyKind::Subtypes, window, cx);
+            });
+    })
+    .detach();
+}
+
+/// Shows the hierarchy of the symbol under the cursor of the active editor.
+fn show_hierarchy(
+    workspace: &mut Workspace,
+    kind: HierarchyKind,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(editor) = workspace.active_item_as::<Editor>(cx) else {
+        return;
+    };
+    let Some((buffer, position)) = editor.update(cx, |editor, cx| {
+        let head = editor.selections.newest_anchor().head();
+        editor.buffer().read(cx).text_anchor_for_position(head, cx)
+    }) else {
+        return;
+    };
+    if let Some(panel) = workspace.focus_panel::<HierarchyPanel>(window, cx) {
+        panel.update(cx, |panel, cx| {
+            panel.load_roots(kind, buffer, position, window, cx);
+        });
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum HierarchyKind {
+    IncomingCalls,
+    OutgoingCalls,
+    Supertypes,
+    Subtypes,
+}
+
+impl HierarchyKind {
+    fn title(self) -> &'static str {
+        match self {
+            Self::IncomingCalls => "Incoming Calls",
+            Self::OutgoingCalls => "Outgoing Calls",
+            Self::Supertypes => "Supertypes",
+            Self::Subtypes => "Subtypes",
+        }
+    }
+
+    fn flipped(self) -> Self {
+        match self {
+            Self::IncomingCalls => Self::OutgoingCalls,
+            Self::OutgoingCalls => Self::IncomingCalls,
+            Self::Supertypes => Self::Subtypes,
+            Self::Subtypes => Self::Supertypes,
+        }
+    }
+
+    fn is_call_hierarchy(self) -> bool {
+        matches!(self, Self::IncomingCalls | Self::OutgoingCalls)
+    }
+}
+
+/// An index into [`HierarchyPanel::nodes`].
+type NodeId = usize;
+
+struct Node {
+    item: HierarchyItem,
+    parent: Option<NodeId>,
+    depth: usize,
+    /// Where the item calls its parent, or is called by it. Empty for roots and types.
+    call_sites: Vec<Location>,
+    expanded: bool,
+    children: Children,
+}
+
+/// The children of a node, which are only requested once it's expanded.
+enum Children {
+    NotLoaded,
+    Loading { _task: Task<()> },
+    Loaded(Vec<NodeId>),
+    Failed(SharedString),
+}
+
+#[derive(Serialize, Deserialize)]
+struct SerializedHierarchyPanel {
+    width: Option<Pixels>,
+    active: Option<bool>,
+}
+
+pub struct HierarchyPanel {
+    project: Entity<Project>,
+    workspace: WeakEntity<Workspace>,
+    fs: Arc<dyn Fs>,
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+    width: Option<Pixels>,
+    active: bool,
+    pending_serialization: Task<Option<()>>,
+    kind: HierarchyKind,
+    /// Every node loaded since the roots were last replaced.
+    nodes: Vec<Node>,
+    roots: Vec<NodeId>,
+    /// The nodes whose ancestors are all expanded, in the order they're shown.
+    visible_entries: Vec<NodeId>,
+    selected_entry: Option<NodeId>,
+    /// Shown instead of the entries while there are none.
+    status: Option<SharedString>,
+    load_roots_task: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl HierarchyPanel {
+    pub async fn load(
+        workspace: WeakEntity<Workspace>,
+        mut cx: AsyncWindowContext,
+    ) -> anyhow::Result<Entity<Self>> {
+        let serialized_panel = cx
+            .background_spawn(async move { KEY_VALUE_STORE.read_kvp(HIERARCHY_PANEL_KEY) })
+            .await
+            .context("loading hierarchy panel")
+            .log_err()
+            .flatten()
+            .map(|panel| serde_json::from_str::<SerializedHierarchyPanel>(&panel))
+            .transpose()
+            .log_err()
+            .flatten();
+
+        workspace.update_in(&mut cx, |workspace, window, cx| {
+            let panel = cx.new(|cx| Self::new(workspace, window, cx));
+            if let Some(serialized_panel) = serialized_panel {
+                panel.update(cx, |panel, cx| {
+                    panel.width = serialized_panel.width.map(|px| px.round());
+                    panel.active = serialized_panel.active.unwrap_or(false);
+                    cx.notify();
+                });
+            }
+            panel
+        })
+    }
+
+    fn new(workspace: &Workspace, _: &mut Window, cx: &mut Context<Self>) -> Self {
+        let mut settings = *HierarchyPanelSettings::get_global(cx);
+        let settings_subscription = cx.observe_global::<SettingsStore>(move |_, cx| {
+            let new_settings = HierarchyPanelSettings::get_global(cx);
+            if &settings != new_settings {
+                settings = *new_settings;
+                cx.notify();
+            }
+        });
+
+        Self {
+            project: workspace.project().clone(),
+            workspace: workspace.weak_handle(),
+            fs: workspace.app_state().fs.clone(),
+            focus_handle: cx.focus_handle(),
+            scroll_handle: UniformListScrollHandle::new(),
+            width: None,
+            active: false,
+            pending_serialization: Task::ready(None),
+            kind: HierarchyKind::IncomingCalls,
+            nodes: Vec::new(),
+            roots: Vec::new(),
+            visible_entries: Vec::new(),
+            selected_entry: None,
+            status: None,
+            load_roots_task: Task::ready(()),
+            _subscriptions: vec![settings_subscription],
+        }
+    }
+
+    fn serialize(&mut self, cx: &mut Context<Self>) {
+        let width = self.width;
+        let active = Some(self.active);
+        self.pending_serialization = cx.background_spawn(
+            async move {
+                KEY_VALUE_STORE
+                    .write_kvp(
+                        HIERARCHY_PANEL_KEY.into(),
+                        serde_json::to_string(&SerializedHierarchyPanel { width, active })?,
+                    )
+                    .await?;
+                anyhow::Ok(())
+            }
+            .log_err(),
+        );
+    }
+
+    fn dispatch_context(&self) -> KeyContext {
+        let mut dispatch_context = KeyContext::new_with_defaults();
+        dispatch_context.add("HierarchyPanel");
+        dispatch_context.add("menu");
+        dispatch_context
+    }
+
+    /// Replaces the hierarchy with the one of the symbol at the given position.
+    fn load_roots(
+        &mut self,
+        kind: HierarchyKind,
+        buffer: Entity<Buffer>,
+        position: language::Anchor,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.kind = kind;
+        self.set_roots(Vec::new(), cx);
+        self.status = Some("Loading…".into());
+
+        let roots = self.project.update(cx, |project, cx| {
+            if kind.is_call_hierarchy() {
+                project.call_hierarchy(&buffer, position, cx)
+            } else {
+                project.type_hierarchy(&buffer, position, cx)
+            }
+        });
+        self.load_roots_task = cx.spawn_in(window, async move |panel, cx| {
+            let roots = roots.await;
+            panel
+                .update(cx, |panel, cx| {
+                    panel.status = match &roots {
+                        Ok(roots) if roots.is_empty() => {
+                            Some("No symbol with a hierarchy at the cursor".into())
+                        }
+                        Ok(_) => None,
+                        Err(error) => {
+                            Some(format!("Failed to load the hierarchy: {error:#}").into())
+                        }
+                    };
+                    panel.set_roots(roots.unwrap_or_default(), cx);
+                })
+                .ok();
+        });
+    }
+
+    fn set_roots(&mut self, items: Vec<HierarchyItem>, cx: &mut Context<Self>) {
+        self.nodes.clear();
+        self.roots = items
+            .into_iter()
+            .map(|item| self.push_node(item, None, Vec::new()))
+            .collect();
+        // The roots are expanded right away, as their relatives are what the panel was opened for.
+        for root in self.roots.clone() {
+            self.expand(root, cx);
+        }
+        self.selected_entry = self.roots.first().copied();
+        self.update_visible_entries(cx);
+    }
+
+    fn push_node(
+        &mut self,
+        item: HierarchyItem,
+        parent: Option<NodeId>,
+        call_sites: Vec<Location>,
+    ) -> NodeId {
+        let depth = parent.map_or(0, |parent| self.nodes[parent].depth + 1);
+        self.nodes.push(Node {
+            item,
+            parent,
+            depth,
+            call_sites,
+            expanded: false,
+            children: Children::NotLoaded,
+        });
+        self.nodes.len() - 1
+    }
+
+    fn flip_direction(&mut self, _: &FlipDirection, _: &mut Window, cx: &mut Context<Self>) {
+        let roots = self
+            .roots
+            .iter()
+            .map(|root| self.nodes[*root].item.clone())
+            .collect();
+        self.kind = self.kind.flipped();
+        self.set_roots(roots, cx);
+    }
+
+    fn expand(&mut self, node_id: NodeId, cx: &mut Context<Self>) {
+        self.nodes[node_id].expanded = true;
+        if matches!(self.nodes[node_id].children, Children::NotLoaded) {
+            let kind = self.kind;
+            let item = self.nodes[node_id].item.clone();
+            let project = self.project.clone();
+            let task = cx.spawn(async move |panel, cx| {
+                let children = load_children(kind, item, project, cx).await;
+                panel
+                    .update(cx, |panel, cx| {
+                        let children = match children {
+                            Ok(children) => Children::Loaded(
+                                children
+                                    .into_iter()
+                                    .map(|(item, call_sites)| {
+                                        panel.push_node(item, Some(node_id), call_sites)
+                                    })
+                                    .collect(),
+                            ),
+                            Err(error) => Children::Failed(format!("{error:#}").into()),
+                        };
+                        panel.nodes[node_id].children = children;
+                        panel.update_visible_entries(cx);
+                    })
+                    .ok();
+            });
+            self.nodes[node_id].children = Children::Loading { _task: task };
+        }
+        self.update_visible_entries(cx);
+    }
+
+    fn collapse(&mut self, node_id: NodeId, cx: &mut Context<Self>) {
+        self.nodes[node_id].expanded = false;
+        if let Some(selected_entry) = self.selected_entry {
+            if self.is_descendant(selected_entry, node_id) {
+                self.selected_entry = Some(node_id);
+            }
+        }
+        self.update_visible_entries(cx);
+    }
+
+    fn is_descendant(&self, mut node_id: NodeId, ancestor: NodeId) -> bool {
+        while let Some(parent) = self.nodes[node_id].parent {
+            if parent == ancestor {
+                return true;
+            }
+            node_id = parent;
+        }
+        false
+    }
+
+    fn toggle_expanded(&mut self, node_id: NodeId, cx: &mut Context<Self>) {
+        if self.nodes[node_id].expanded {
+            self.collapse(node_id, cx);
+        } else {
+            self.expand(node_id, cx);
+        }
+    }
+
+    fn update_visible_entries(&mut self, cx: &mut Context<Self>) {
+        self.visible_entries.clear();
+        let mut stack = self.roots.iter().rev().copied().collect::<Vec<_>>();
+        while let Some(node_id) = stack.pop() {
+            self.visible_entries.push(node_id);
+            let node = &self.nodes[node_id];
+            if let (true, Children::Loaded(children)) = (node.expanded, &node.children) {
+                stack.extend(children.iter().rev());
+            }
+        }
+        cx.notify();
+    }
+
+    fn selected_index(&self) -> Option<usize> {
+        let selected_entry = self.selected_entry?;
+        self.visible_entries
+            .iter()
+            .position(|node_id| *node_id == selected_entry)
+    }
+
+    fn select_index(&mut self, ix: usize, cx: &mut Context<Self>) {
+        if let Some(node_id) = self.visible_entries.get(ix) {
+            self.selected_entry = Some(*node_id);
+            self.scroll_handle
+                .scroll_to_item(ix, ScrollStrategy::Center);
+            cx.notify();
+        }
+    }
+
+    fn select_next(&mut self, _: &SelectNext, _: &mut Window, cx: &mut Context<Self>) {
+        let ix = self.selected_index().map_or(0, |ix| ix + 1);
+        self.select_index(ix, cx);
+    }
+
+    fn select_previous(&mut self, _: &SelectPrevious, _: &mut Window, cx: &mut Context<Self>) {
+        let ix = self.selected_index().map_or(0, |ix| ix.saturating_sub(1));
+        self.select_index(ix, cx);
+    }
+
+    fn select_first(&mut self, _: &SelectFirst, _: &mut Window, cx: &mut Context<Self>) {
+        self.select_index(0, cx);
+    }
+
+    fn select_last(&mut self, _: &SelectLast, _: &mut Window, cx: &mut Context<Self>) {
+        self.select_index(self.visible_entries.len().saturating_sub(1), cx);
+    }
+
+    fn expand_selected_entry(
+        &mut self,
+        _: &ExpandSelectedEntry,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(node_id) = self.selected_entry else {
+            return;
+        };
+        if !self.nodes[node_id].expanded {
+            self.expand(node_id, cx);
+        } else if let Children::Loaded(children) = &self.nodes[node_id].children {
+            if let Some(first_child) = children.first() {
+                self.selected_entry = Some(*first_child);
+                cx.notify();
+            }
+        }
+    }
+
+    fn collapse_selected_entry(
+        &mut self,
+        _: &CollapseSelectedEntry,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(node_id) = self.selected_entry else {
+            return;
+        };
+        if self.nodes[node_id].expanded {
+            self.collapse(node_id, cx);
+        } else if let Some(parent) = self.nodes[node_id].parent {
+            self.selected_entry = Some(parent);
+            cx.notify();
+        }
+    }
+
+    fn confirm(&mut self, _: &Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(node_id) = self.selected_entry {
+            self.open_item(node_id, window, cx);
+        }
+    }
+
+    fn open_selected_call_sites(
+        &mut self,
+        _: &OpenCallSites,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(node_id) = self.selected_entry {
+            self.open_call_sites(node_id, window, cx);
+        }
+    }
+
+    /// Opens the item in an editor, selecting its name.
+    fn open_item(&mut self, node_id: NodeId, window: &mut Window, cx: &mut Context<Self>) {
+        let item = &self.nodes[node_id].item;
+        let buffer = item.location.buffer.clone();
+        let range = item.selection_range.to_point(buffer.read(cx));
+        self.workspace
+            .update(cx, |workspace, cx| {
+                let pane = workspace.active_pane().clone();
+                let editor =
+                    workspace.open_project_item::<Editor>(pane, buffer, true, false, window, cx);
+                editor.update(cx, |editor, cx| {
+                    editor.change_selections(Some(Autoscroll::center()), window, cx, |s| {
+                        s.select_ranges([range])
+                    });
+                });
+            })
+            .ok();
+    }
+
+    /// Opens the call sites that connect the item to its parent in a multibuffer.
+    fn open_call_sites(&mut self, node_id: NodeId, window: &mut Window, cx: &mut Context<Self>) {
+        let node = &self.nodes[node_id];
+        if node.call_sites.is_empty() {
+            return;
+        }
+        let Some(parent) = node.parent else {
+            return;
+        };
+        let (caller, callee) = match self.kind {
+            HierarchyKind::OutgoingCalls => (&self.nodes[parent].item, &node.item),
+            _ => (&node.item, &self.nodes[parent].item),
+        };
+        let title = format!("Calls from {} to {}", caller.name, callee.name);
+        let call_sites = node.call_sites.clone();
+        self.workspace
+            .update(cx, |workspace, cx| {
+                Editor::open_locations_in_multibuffer(
+                    workspace,
+                    call_sites,
+                    title,
+                    false,
+                    MultibufferSelectionMode::All,
+                    window,
+                    cx,
+                );
+            })
+            .ok();
+    }
+
+    fn render_entry(&self, node_id: NodeId, cx: &mut Context<Self>) -> ListItem {
+        let node = &self.nodes[node_id];
+        let indent_size = HierarchyPanelSettings::get_global(cx).indent_size;
+        let can_expand =
+            !matches!(&node.children, Children::Loaded(children) if children.is_empty());
+        let detail: Option<SharedString> = match &node.children {
+            Children::Loading { .. } if node.expanded => Some("Loading…".into()),
+            Children::Failed(error) => Some(error.clone()),
+            _ => node.item.detail.clone().map(Into::into),
+        };
+        let call_count = node.call_sites.len();
+
+        ListItem::new(("hierarchy-entry", node_id))
+            .indent_level(node.depth)
+            .indent_step_size(px(indent_size))
+            .spacing(ListItemSpacing::Sparse)
+            .toggle(can_expand.then_some(node.expanded))
+            .on_toggle(cx.listener(move |panel, _, _, cx| {
+                panel.toggle_expanded(node_id, cx);
+            }))
+            .toggle_state(self.selected_entry == Some(node_id))
+            .child(
+                h_flex()
+                    .gap_1p5()
+                    .overflow_x_hidden()
+                    .child(Label::new(node.item.name.clone()).single_line())
+                    .when_some(detail, |this, detail| {
+                        this.child(
+                            Label::new(detail)
+                                .size(LabelSize::Small)
+                                .color(Color::Muted)
+                                .truncate(),
+                        )
+                    }),
+            )
+            .when(call_count > 0, |this| {
+                this.end_slot(
+                    Button::new(
+                        ("call-sites", node_id),
+                        if call_count == 1 {
+                            "1 call".to_string()
+                        } else {
+                            format!("{call_count} calls")
+                        },
+                    )
+                    .label_size(LabelSize::Small)
+                    .color(Color::Muted)
+                    .tooltip(Tooltip::for_action_title("Open Call Sites", &OpenCallSites))
+                    .on_click(cx.listener(move |panel, _, window, cx| {
+                        panel.selected_entry = Some(node_id);
+                        panel.open_call_sites(node_id, window, cx);
+                    })),
+                )
+            })
+            .on_click(cx.listener(move |panel, _, window, cx| {
+                panel.selected_entry = Some(node_id);
+                panel.open_item(node_id, window, cx);
+                cx.notify();
+            }))
+    }
+
+    fn render_header(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        h_flex()
+            .h(Tab::container_height(cx))
+            .flex_none()
+            .px_2()
+            .justify_between()
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .child(Label::new(self.kind.title()))
+            .child(
+                IconButton::new("flip-direction", IconName::ArrowRightLeft)
+                    .icon_size(IconSize::Small)
+                    .disabled(self.roots.is_empty())
+                    .tooltip(Tooltip::for_action_title(
+                        match self.kind.flipped() {
+                            HierarchyKind::IncomingCalls => "Show Incoming Calls",
+                            HierarchyKind::OutgoingCalls => "Show Outgoing Calls",
+                            HierarchyKind::Supertypes => "Show Supertypes",
+                            HierarchyKind::Subtypes => "Show Subtypes",
+                        },
+                        &FlipDirection,
+                    ))
+                    .on_click(cx.listener(|panel, _, window, cx| {
+                        panel.flip_direction(&FlipDirection, window, cx);
+                    })),
+            )
+    }
+}
+
+/// Loads the relatives of an item, along with the call sites that connect them to it.
+async fn load_children(
+    kind: HierarchyKind,
+    item: HierarchyItem,
+    project: Entity<Project>,
+    cx: &mut AsyncApp,
+) -> anyhow::Result<Vec<(HierarchyItem, Vec<Location>)>> {
+    let children = match kind {
+        HierarchyKind::IncomingCalls | HierarchyKind::OutgoingCalls => project
+            .update(cx, |project, cx| {
+                if kind == HierarchyKind::IncomingCalls {
+                    project.incoming_calls(&item, cx)
+                } else {
+                    project.outgoing_calls(&item, cx)
+                }
+            })?
+            .await?
+            .into_iter()
+            .map(|call| (call.item, call.call_sites))
+            .collect(),
+        HierarchyKind::Supertypes | HierarchyKind::Subtypes => project
+            .update(cx, |project, cx| {
+                if kind == HierarchyKind::Supertypes {
+                    project.supertypes(&item, cx)
+                } else {
+                    project.subtypes(&item, cx)
+                }
+            })?
+            .await?
+            .into_iter()
+            .map(|item| (item, Vec::new()))
+            .collect(),
+    };
+    Ok(children)
+}
+
+impl Panel for HierarchyPanel {
+    fn persistent_name() -> &'static str {
+        "Hierarchy Panel"
+    }
+
+    fn position(&self, _: &Window, cx: &App) -> DockPosition {
+        match HierarchyPanelSettings::get_global(cx).dock {
+            HierarchyPanelDockPosition::Left => DockPosition::Left,
+            HierarchyPanelDockPosition::Right => DockPosition::Right,
+        }
+    }
+
+    fn position_is_valid(&self, position: DockPosition) -> bool {
+        matches!(position, DockPosition::Left | DockPosition::Right)
+    }
+
+    fn set_position(&mut self, position: DockPosition, _: &mut Window, cx: &mut Context<Self>) {
+        settings::update_settings_file::<HierarchyPanelSettings>(
+            self.fs.clone(),
+            cx,
+            move |settings, _| {
+                let dock = match position {
+                    DockPosition::Left | DockPosition::Bottom => HierarchyPanelDockPosition::Left,
+                    DockPosition::Right => HierarchyPanelDockPosition::Right,
+                };
+                settings.dock = Some(dock);
+            },
+        );
+    }
+
+    fn size(&self, _: &Window, cx: &App) -> Pixels {
+        self.width
+            .unwrap_or_else(|| HierarchyPanelSettings::get_global(cx).default_width)
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, _: &mut Window, cx: &mut Context<Self>) {
+        self.width = size;
+        self.serialize(cx);
+        cx.notify();
+    }
+
+    fn icon(&self, _: &Window, cx: &App) -> Option<IconName> {
+        HierarchyPanelSettings::get_global(cx)
+            .button
+            .then_some(IconName::Route)
+    }
+
+    fn icon_tooltip(&self, _window: &Window, _: &App) -> Option<&'static str> {
+        Some("Hierarchy Panel")
+    }
+
+    fn toggle_action(&self) -> Box<dyn Action> {
+        Box::new(ToggleFocus)
+    }
+
+    fn starts_open(&self, _window: &Window, _: &App) -> bool {
+        self.active
+    }
+
+    fn set_active(&mut self, active: bool, _: &mut Window, cx: &mut Context<Self>) {
+        if self.active != active {
+            self.active = active;
+            self.serialize(cx);
+        }
+    }
+
+    fn activation_priority(&self) -> u32 {
+        10
+    }
+}
+
+impl Focusable for HierarchyPanel {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<PanelEvent> for HierarchyPanel {}
+
+impl Render for HierarchyPanel {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let contents = if self.visible_entries.is_empty() {
+            let message = self.status.clone().unwrap_or_else(|| {
+                "Show the calls or types related to the symbol under the cursor from an editor"
+                    .into()
+            });
+            v_flex()
+                .flex_1()
+                .size_full()
+                .justify_center()
+                .px_4()
+                .child(
+                    h_flex()
+                        .justify_center()
+                        .child(Label::new(message).color(Color::Muted)),
+                )
+                .into_any_element()
+        } else {
+            uniform_list(
+                cx.entity().clone(),
+                "entries",
+                self.visible_entries.len(),
+                |panel, range, _, cx| {
+                    panel.visible_entries[range]
+                        .to_vec()
+                        .into_iter()
+                        .map(|node_id| panel.render_entry(node_id, cx))
+                        .collect()
+                },
+            )
+            .with_sizing_behavior(ListSizingBehavior::Infer)
+            .track_scroll(self.scroll_handle.clone())
+            .size_full()
+            .into_any_element()
+        };
+
+        v_flex()
+            .id("hierarchy-panel")
+            .size_full()
+            .overflow_hidden()
+            .key_context(self.dispatch_context())
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_previous))
+            .on_action(cx.listener(Self::select_first))
+            .on_action(cx.listener(Self::select_last))
+            .on_action(cx.listener(Self::expand_selected_entry))
+            .on_action(cx.listener(Self::collapse_selected_entry))
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::open_selected_call_sites))
+            .on_action(cx.listener(Self::flip_direction))
+            .child(self.render_header(cx))
+            .child(contents)
+    }
+}
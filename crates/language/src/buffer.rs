@@ -226,6 +226,9 @@ pub struct Diagnostic {
     pub is_disk_based: bool,
     /// Whether this diagnostic marks unnecessary code.
     pub is_unnecessary: bool,
+    /// Whether the language server reported this diagnostic in response to a request for
+    /// diagnostics, rather than publishing it on its own.
+    pub is_pulled: bool,
     /// Data from language server that produced this diagnostic. Passed back to the LS when we request code actions for this diagnostic.
     pub data: Option<Value>,
 }
@@ -4504,6 +4507,7 @@ impl Default for Diagnostic {
             is_primary: false,
             is_disk_based: false,
             is_unnecessary: false,
+            is_pulled: false,
             data: None,
         }
     }
@@ -3287,6 +3287,68 @@ let word=öäpple.bar你 Öäpple word2-öÄpPlE-Pizza-word ÖÄPPLE word
     });
 }
 
+#[gpui::test]
+fn test_conflicts(cx: &mut gpui::App) {
+    init_settings(cx, |_| {});
+
+    cx.new(|cx| {
+        let text = indoc! {"
+            fn main() {
+            <<<<<<< HEAD
+                let x = 1;
+            =======
+                let x = 2;
+            >>>>>>> feature
+            }
+            <<<<<<< HEAD
+            one
+            ||||||| base
+            zero
+            =======
+            two
+            >>>>>>> feature
+        "};
+        let mut buffer = Buffer::local(text, cx);
+
+        let snapshot = buffer.snapshot();
+        let conflicts = snapshot.conflicts();
+        assert_eq!(conflicts.len(), 2);
+        let text_for =
+            |range: &Range<Anchor>| snapshot.text_for_range(range.clone()).collect::<String>();
+        assert_eq!(text_for(&conflicts[0].ours), "    let x = 1;\n");
+        assert_eq!(conflicts[0].base, None);
+        assert_eq!(text_for(&conflicts[0].theirs), "    let x = 2;\n");
+        assert_eq!(text_for(&conflicts[1].ours), "one\n");
+        assert_eq!(
+            conflicts[1].base.as_ref().map(text_for),
+            Some("zero\n".into())
+        );
+        assert_eq!(text_for(&conflicts[1].theirs), "two\n");
+
+        buffer.resolve_conflict(&conflicts[1], ConflictResolution::Both, cx);
+        assert_eq!(
+            buffer.text(),
+            indoc! {"
+                fn main() {
+                <<<<<<< HEAD
+                    let x = 1;
+                =======
+                    let x = 2;
+                >>>>>>> feature
+                }
+                one
+                two
+            "}
+        );
+
+        buffer.resolve_conflict(&conflicts[0], ConflictResolution::Theirs, cx);
+        assert_eq!(buffer.text(), "fn main() {\n    let x = 2;\n}\none\ntwo\n");
+        assert!(buffer.snapshot().conflicts().is_empty());
+
+        buffer
+    });
+}
+
 fn ruby_lang() -> Language {
     Language::new(
         LanguageConfig {
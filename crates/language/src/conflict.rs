@@ -0,0 +1,191 @@
+use crate::{Buffer, BufferSnapshot};
+use gpui::Context;
+use std::ops::Range;
+use text::Anchor;
+
+/// A merge conflict delimited by the conflict markers that git writes into a file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Conflict {
+    /// The whole conflict, including the marker lines.
+    pub range: Range<Anchor>,
+    /// Our side of the conflict, between the `<<<<<<<` marker and the next marker.
+    pub ours: Range<Anchor>,
+    /// The common ancestor, between the `|||||||` and `=======` markers. Only present
+    /// in conflicts written with the `diff3` or `zdiff3` conflict styles.
+    pub base: Option<Range<Anchor>>,
+    /// Their side of the conflict, between the `=======` and `>>>>>>>` markers.
+    pub theirs: Range<Anchor>,
+}
+
+/// Which side of a [`Conflict`] to keep when resolving it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConflictResolution {
+    Ours,
+    Theirs,
+    Both,
+}
+
+impl Conflict {
+    /// Returns the text that replaces the conflict, including its markers, when it is resolved.
+    pub fn resolved_text(&self, resolution: ConflictResolution, buffer: &BufferSnapshot) -> String {
+        let ours = buffer.text_for_range(self.ours.clone());
+        let theirs = buffer.text_for_range(self.theirs.clone());
+        match resolution {
+            ConflictResolution::Ours => ours.collect(),
+            ConflictResolution::Theirs => theirs.collect(),
+            ConflictResolution::Both => ours.chain(theirs).collect(),
+        }
+    }
+}
+
+impl BufferSnapshot {
+    /// Returns the merge conflicts in the buffer, in order.
+    pub fn conflicts(&self) -> Vec<Conflict> {
+        let text = self.text();
+        parse_conflicts(&text)
+            .into_iter()
+            .map(|conflict| Conflict {
+                range: self.anchor_range(conflict.range),
+                ours: self.anchor_range(conflict.ours),
+                base: conflict.base.map(|base| self.anchor_range(base)),
+                theirs: self.anchor_range(conflict.theirs),
+            })
+            .collect()
+    }
+
+    fn anchor_range(&self, range: Range<usize>) -> Range<Anchor> {
+        self.anchor_after(range.start)..self.anchor_before(range.end)
+    }
+}
+
+impl Buffer {
+    /// Replaces a conflict, including its markers, with the chosen side(s) of the conflict.
+    pub fn resolve_conflict(
+        &mut self,
+        conflict: &Conflict,
+        resolution: ConflictResolution,
+        cx: &mut Context<Self>,
+    ) {
+        let text = conflict.resolved_text(resolution, &self.snapshot());
+        self.edit([(conflict.range.clone(), text)], None, cx);
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ConflictMarker {
+    Ours,
+    Base,
+    Separator,
+    Theirs,
+}
+
+enum ParseState {
+    Outside,
+    Ours {
+        start: usize,
+        ours_start: usize,
+    },
+    Base {
+        start: usize,
+        ours: Range<usize>,
+        base_start: usize,
+    },
+    Theirs {
+        start: usize,
+        ours: Range<usize>,
+        base: Option<Range<usize>>,
+        theirs_start: usize,
+    },
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct ConflictOffsets {
+    pub range: Range<usize>,
+    pub ours: Range<usize>,
+    pub base: Option<Range<usize>>,
+    pub theirs: Range<usize>,
+}
+
+/// Finds the conflicts delimited by conflict markers in the given text.
+///
+/// Unterminated conflicts are ignored, and a new `<<<<<<<` marker restarts the
+/// current conflict, so that stray markers don't swallow the rest of the file.
+pub(crate) fn parse_conflicts(text: &str) -> Vec<ConflictOffsets> {
+    let mut conflicts = Vec::new();
+    let mut state = ParseState::Outside;
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        let line_start = offset;
+        offset += line.len();
+        let Some(marker) = conflict_marker(line) else {
+            continue;
+        };
+
+        state = match (state, marker) {
+            (_, ConflictMarker::Ours) => ParseState::Ours {
+                start: line_start,
+                ours_start: offset,
+            },
+            (ParseState::Ours { start, ours_start }, ConflictMarker::Base) => ParseState::Base {
+                start,
+                ours: ours_start..line_start,
+                base_start: offset,
+            },
+            (ParseState::Ours { start, ours_start }, ConflictMarker::Separator) => {
+                ParseState::Theirs {
+                    start,
+                    ours: ours_start..line_start,
+                    base: None,
+                    theirs_start: offset,
+                }
+            }
+            (
+                ParseState::Base {
+                    start,
+                    ours,
+                    base_start,
+                },
+                ConflictMarker::Separator,
+            ) => ParseState::Theirs {
+                start,
+                ours,
+                base: Some(base_start..line_start),
+                theirs_start: offset,
+            },
+            (
+                ParseState::Theirs {
+                    start,
+                    ours,
+                    base,
+                    theirs_start,
+                },
+                ConflictMarker::Theirs,
+            ) => {
+                conflicts.push(ConflictOffsets {
+                    range: start..offset,
+                    ours,
+                    base,
+                    theirs: theirs_start..line_start,
+                });
+                ParseState::Outside
+            }
+            (state, _) => state,
+        };
+    }
+    conflicts
+}
+
+fn conflict_marker(line: &str) -> Option<ConflictMarker> {
+    let line = line.trim_end_matches(['\n', '\r']);
+    let (marker, rest) = line.split_at_checked(7)?;
+    if !rest.is_empty() && !rest.starts_with(' ') {
+        return None;
+    }
+    match marker {
+        "<<<<<<<" => Some(ConflictMarker::Ours),
+        "|||||||" => Some(ConflictMarker::Base),
+        "=======" if rest.is_empty() => Some(ConflictMarker::Separator),
+        ">>>>>>>" => Some(ConflictMarker::Theirs),
+        _ => None,
+    }
+}
@@ -143,6 +143,13 @@ pub struct LanguageSettings {
     pub code_actions_on_format: HashMap<String, bool>,
     /// Whether to perform linked edits
     pub linked_edits: bool,
+    /// Whether to highlight the semantic tokens reported by language servers.
+    pub semantic_tokens: bool,
+    /// Whether to show the code lenses reported by language servers above their items.
+    pub code_lens: bool,
+    /// Whether to fold the ranges reported by language servers instead of those suggested by
+    /// the indentation.
+    pub lsp_folding_ranges: bool,
     /// Task configuration for this language.
     pub tasks: LanguageTaskConfig,
     /// Whether to pop the completions menu while typing in an editor without
@@ -531,6 +538,21 @@ pub struct LanguageSettingsContent {
     ///
     /// Default: true
     pub linked_edits: Option<bool>,
+    /// Whether to highlight code with the semantic tokens reported by language servers,
+    /// on top of the syntax highlighting.
+    ///
+    /// Default: false
+    pub semantic_tokens: Option<bool>,
+    /// Whether to show the code lenses reported by language servers above the items
+    /// they refer to. Clicking a lens runs its command.
+    ///
+    /// Default: false
+    pub code_lens: Option<bool>,
+    /// Whether to fold the ranges reported by language servers, such as regions and
+    /// import blocks, instead of those suggested by the indentation.
+    ///
+    /// Default: false
+    pub lsp_folding_ranges: Option<bool>,
     /// Whether indentation of pasted content should be adjusted based on the context.
     ///
     /// Default: true
@@ -1399,6 +1421,9 @@ fn merge_settings(settings: &mut LanguageSettings, src: &LanguageSettingsContent
         src.code_actions_on_format.clone(),
     );
     merge(&mut settings.linked_edits, src.linked_edits);
+    merge(&mut settings.semantic_tokens, src.semantic_tokens);
+    merge(&mut settings.code_lens, src.code_lens);
+    merge(&mut settings.lsp_folding_ranges, src.lsp_folding_ranges);
     merge(&mut settings.tasks, src.tasks.clone());
 
     merge(
@@ -215,6 +215,7 @@ pub fn serialize_diagnostics<'a>(
             code: entry.diagnostic.code.as_ref().map(|s| s.to_string()),
             is_disk_based: entry.diagnostic.is_disk_based,
             is_unnecessary: entry.diagnostic.is_unnecessary,
+            is_pulled: entry.diagnostic.is_pulled,
             data: entry.diagnostic.data.as_ref().map(|data| data.to_string()),
         })
         .collect()
@@ -422,6 +423,7 @@ pub fn deserialize_diagnostics(
                     is_primary: diagnostic.is_primary,
                     is_disk_based: diagnostic.is_disk_based,
                     is_unnecessary: diagnostic.is_unnecessary,
+                    is_pulled: diagnostic.is_pulled,
                     data,
                 },
             })
@@ -1,12 +1,14 @@
+use std::borrow::Cow;
 use std::io::{Cursor, Write};
 use std::sync::Arc;
 
 use crate::role::Role;
 use crate::{LanguageModelToolUse, LanguageModelToolUseId};
+use base64::Engine as _;
 use base64::write::EncoderWriter;
 use gpui::{
-    App, AppContext as _, DevicePixels, Image, ObjectFit, RenderImage, SharedString, Size, Task,
-    point, px, size,
+    App, AppContext as _, DevicePixels, Image, ImageFormat, ObjectFit, RenderImage, SharedString,
+    Size, Task, point, px, size,
 };
 use image::{DynamicImage, ImageDecoder, codecs::png::PngEncoder, imageops::resize};
 use serde::{Deserialize, Serialize};
@@ -91,6 +93,68 @@ impl LanguageModelImage {
         })
     }
 
+    /// Converts base64-encoded image data in any supported format (e.g. an image
+    /// returned by a context server) into an LLM-ready PNG.
+    pub fn from_base64(data: &str, cx: &App) -> Task<Option<Self>> {
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(data.trim())
+            .log_err();
+        match bytes {
+            Some(bytes) => Self::from_bytes(bytes, cx),
+            None => Task::ready(None),
+        }
+    }
+
+    /// Converts encoded image data in any supported format (e.g. the contents of
+    /// an image file) into an LLM-ready PNG.
+    pub fn from_bytes(bytes: Vec<u8>, cx: &App) -> Task<Option<Self>> {
+        cx.background_spawn(async move {
+            let mut image = image::load_from_memory(&bytes).log_err()?;
+            let image_size = size(
+                DevicePixels(image.width() as i32),
+                DevicePixels(image.height() as i32),
+            );
+
+            if image_size.width.0 > ANTHROPIC_SIZE_LIMT as i32
+                || image_size.height.0 > ANTHROPIC_SIZE_LIMT as i32
+            {
+                let new_bounds = ObjectFit::ScaleDown.get_bounds(
+                    gpui::Bounds {
+                        origin: point(px(0.0), px(0.0)),
+                        size: size(px(ANTHROPIC_SIZE_LIMT), px(ANTHROPIC_SIZE_LIMT)),
+                    },
+                    image_size,
+                );
+                image = image.resize(
+                    new_bounds.size.width.0 as u32,
+                    new_bounds.size.height.0 as u32,
+                    image::imageops::FilterType::Triangle,
+                );
+            }
+
+            let mut png = Vec::new();
+            image
+                .write_with_encoder(PngEncoder::new(&mut png))
+                .log_err()?;
+
+            Some(LanguageModelImage {
+                size: size(
+                    DevicePixels(image.width() as i32),
+                    DevicePixels(image.height() as i32),
+                ),
+                source: base64::engine::general_purpose::STANDARD.encode(png).into(),
+            })
+        })
+    }
+
+    /// Decodes the image, so that it can be rendered.
+    pub fn to_image(&self) -> Option<Arc<Image>> {
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(self.source.as_bytes())
+            .log_err()?;
+        Some(Arc::new(Image::from_bytes(ImageFormat::Png, bytes)))
+    }
+
     /// Resolves image into an LLM-ready format (base64).
     pub fn from_render_image(data: &RenderImage) -> Option<Self> {
         let image_size = data.size(0);
@@ -169,7 +233,93 @@ pub struct LanguageModelToolResult {
     pub tool_use_id: LanguageModelToolUseId,
     pub tool_name: Arc<str>,
     pub is_error: bool,
-    pub content: Arc<str>,
+    pub content: Vec<LanguageModelToolResultContent>,
+}
+
+impl LanguageModelToolResult {
+    /// Returns the text of the result, including its JSON parts but not its images.
+    pub fn text_contents(&self) -> String {
+        let mut text = String::new();
+        for part in &self.content {
+            if let Some(part_text) = part.to_text() {
+                if !text.is_empty() {
+                    text.push('\n');
+                }
+                text.push_str(&part_text);
+            }
+        }
+        text
+    }
+
+    /// Returns whether the result has no images and only whitespace text.
+    pub fn is_empty(&self) -> bool {
+        self.content.iter().all(|part| match part {
+            LanguageModelToolResultContent::Text { text } => text.trim().is_empty(),
+            LanguageModelToolResultContent::Image { .. }
+            | LanguageModelToolResultContent::Json { .. } => false,
+        })
+    }
+
+    pub fn images(&self) -> impl Iterator<Item = &LanguageModelImage> {
+        self.content.iter().filter_map(|part| match part {
+            LanguageModelToolResultContent::Image { image } => Some(image),
+            LanguageModelToolResultContent::Text { .. }
+            | LanguageModelToolResultContent::Json { .. } => None,
+        })
+    }
+}
+
+/// A part of the output of a tool.
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq, Hash)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LanguageModelToolResultContent {
+    Text {
+        text: Arc<str>,
+    },
+    Image {
+        image: LanguageModelImage,
+    },
+    /// Structured data, sent as JSON text to providers without native support for it.
+    Json {
+        value: serde_json::Value,
+    },
+}
+
+impl LanguageModelToolResultContent {
+    /// Returns the part as text, or `None` for images.
+    pub fn to_text(&self) -> Option<Cow<'_, str>> {
+        match self {
+            LanguageModelToolResultContent::Text { text } => Some(Cow::Borrowed(text.as_ref())),
+            LanguageModelToolResultContent::Json { value } => Some(Cow::Owned(
+                serde_json::to_string_pretty(value).unwrap_or_default(),
+            )),
+            LanguageModelToolResultContent::Image { .. } => None,
+        }
+    }
+}
+
+impl From<String> for LanguageModelToolResultContent {
+    fn from(text: String) -> Self {
+        LanguageModelToolResultContent::Text { text: text.into() }
+    }
+}
+
+impl From<&str> for LanguageModelToolResultContent {
+    fn from(text: &str) -> Self {
+        LanguageModelToolResultContent::Text { text: text.into() }
+    }
+}
+
+impl From<LanguageModelImage> for LanguageModelToolResultContent {
+    fn from(image: LanguageModelImage) -> Self {
+        LanguageModelToolResultContent::Image { image }
+    }
+}
+
+impl From<serde_json::Value> for LanguageModelToolResultContent {
+    fn from(value: serde_json::Value) -> Self {
+        LanguageModelToolResultContent::Json { value }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq, Hash)]
@@ -202,12 +352,14 @@ pub struct LanguageModelRequestMessage {
 impl LanguageModelRequestMessage {
     pub fn string_contents(&self) -> String {
         let mut buffer = String::new();
-        for string in self.content.iter().filter_map(|content| match content {
-            MessageContent::Text(text) => Some(text.as_str()),
-            MessageContent::ToolResult(tool_result) => Some(tool_result.content.as_ref()),
-            MessageContent::ToolUse(_) | MessageContent::Image(_) => None,
-        }) {
-            buffer.push_str(string);
+        for content in &self.content {
+            match content {
+                MessageContent::Text(text) => buffer.push_str(text),
+                MessageContent::ToolResult(tool_result) => {
+                    buffer.push_str(&tool_result.text_contents())
+                }
+                MessageContent::ToolUse(_) | MessageContent::Image(_) => {}
+            }
         }
 
         buffer
@@ -220,9 +372,7 @@ impl LanguageModelRequestMessage {
                 .first()
                 .map(|content| match content {
                     MessageContent::Text(text) => text.chars().all(|c| c.is_whitespace()),
-                    MessageContent::ToolResult(tool_result) => {
-                        tool_result.content.chars().all(|c| c.is_whitespace())
-                    }
+                    MessageContent::ToolResult(tool_result) => tool_result.is_empty(),
                     MessageContent::ToolUse(_) | MessageContent::Image(_) => true,
                 })
                 .unwrap_or(false)
@@ -242,6 +392,20 @@ pub struct LanguageModelRequest {
     pub tools: Vec<LanguageModelRequestTool>,
     pub stop: Vec<String>,
     pub temperature: Option<f32>,
+    /// The maximum number of tokens to generate, in addition to the model's own limit.
+    pub max_tokens: Option<u32>,
+}
+
+impl LanguageModelRequest {
+    /// Returns the maximum number of tokens to generate, given the model's own limit.
+    pub fn max_output_tokens(&self, model_max_output_tokens: Option<u32>) -> Option<u32> {
+        match (self.max_tokens, model_max_output_tokens) {
+            (Some(max_tokens), Some(model_max_output_tokens)) => {
+                Some(max_tokens.min(model_max_output_tokens))
+            }
+            (max_tokens, model_max_output_tokens) => max_tokens.or(model_max_output_tokens),
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq)]
@@ -14,7 +14,8 @@ use http_client::HttpClient;
 use language_model::{
     AuthenticateError, LanguageModel, LanguageModelCacheConfiguration, LanguageModelId,
     LanguageModelName, LanguageModelProvider, LanguageModelProviderId, LanguageModelProviderName,
-    LanguageModelProviderState, LanguageModelRequest, MessageContent, RateLimiter, Role,
+    LanguageModelProviderState, LanguageModelRequest, LanguageModelToolResultContent,
+    MessageContent, RateLimiter, Role,
 };
 use language_model::{LanguageModelCompletionEvent, LanguageModelToolUse, StopReason};
 use schemars::JsonSchema;
@@ -330,7 +331,11 @@ pub fn count_anthropic_tokens(
                         // TODO: Estimate token usage from tool uses.
                     }
                     MessageContent::ToolResult(tool_result) => {
-                        string_contents.push_str(&tool_result.content);
+                        string_contents.push_str(&tool_result.text_contents());
+                        tokens_from_images += tool_result
+                            .images()
+                            .map(|image| image.estimate_tokens())
+                            .sum::<usize>();
                     }
                 }
             }
@@ -466,6 +471,9 @@ pub fn into_anthropic(
     max_output_tokens: u32,
     mode: AnthropicModelMode,
 ) -> anthropic::Request {
+    let max_output_tokens = request
+        .max_output_tokens(Some(max_output_tokens))
+        .unwrap_or(max_output_tokens);
     let mut new_messages: Vec<anthropic::Message> = Vec::new();
     let mut system_message = String::new();
 
@@ -517,7 +525,7 @@ pub fn into_anthropic(
                             Some(anthropic::RequestContent::ToolResult {
                                 tool_use_id: tool_result.tool_use_id.to_string(),
                                 is_error: tool_result.is_error,
-                                content: tool_result.content.to_string(),
+                                content: into_anthropic_tool_result_content(tool_result.content),
                                 cache_control,
                             })
                         }
@@ -557,10 +565,14 @@ pub fn into_anthropic(
         } else {
             Some(anthropic::StringOrContents::String(system_message))
         },
-        thinking: if let AnthropicModelMode::Thinking { budget_tokens } = mode {
-            Some(anthropic::Thinking::Enabled { budget_tokens })
-        } else {
-            None
+        thinking: match mode {
+            // The thinking budget has to be lower than the maximum number of tokens.
+            AnthropicModelMode::Thinking { budget_tokens }
+                if budget_tokens.is_none_or(|budget_tokens| budget_tokens < max_output_tokens) =>
+            {
+                Some(anthropic::Thinking::Enabled { budget_tokens })
+            }
+            _ => None,
         },
         tools: request
             .tools
@@ -580,6 +592,34 @@ pub fn into_anthropic(
     }
 }
 
+fn into_anthropic_tool_result_content(
+    content: Vec<LanguageModelToolResultContent>,
+) -> anthropic::ToolResultContent {
+    if let [LanguageModelToolResultContent::Text { text }] = content.as_slice() {
+        return anthropic::ToolResultContent::Plain(text.to_string());
+    }
+
+    anthropic::ToolResultContent::Multipart(
+        content
+            .into_iter()
+            .map(|part| match part {
+                LanguageModelToolResultContent::Image { image } => {
+                    anthropic::ToolResultPart::Image {
+                        source: anthropic::ImageSource {
+                            source_type: "base64".to_string(),
+                            media_type: "image/png".to_string(),
+                            data: image.source.to_string(),
+                        },
+                    }
+                }
+                part => anthropic::ToolResultPart::Text {
+                    text: part.to_text().unwrap_or_default().into_owned(),
+                },
+            })
+            .collect(),
+    )
+}
+
 pub fn map_to_language_model_completion_events(
     events: Pin<Box<dyn Send + Stream<Item = Result<Event, AnthropicError>>>>,
 ) -> impl Stream<Item = Result<LanguageModelCompletionEvent>> {
@@ -421,6 +421,9 @@ pub fn into_bedrock(
     default_temperature: f32,
     max_output_tokens: u32,
 ) -> bedrock::Request {
+    let max_output_tokens = request
+        .max_output_tokens(Some(max_output_tokens))
+        .unwrap_or(max_output_tokens);
     let mut new_messages: Vec<BedrockMessage> = Vec::new();
     let mut system_message = String::new();
 
@@ -517,7 +520,11 @@ pub fn get_bedrock_tokens(
                             // TODO: Estimate token usage from tool uses.
                         }
                         MessageContent::ToolResult(tool_result) => {
-                            string_contents.push_str(&tool_result.content);
+                            string_contents.push_str(&tool_result.text_contents());
+                            tokens_from_images += tool_result
+                                .images()
+                                .map(|image| image.estimate_tokens())
+                                .sum::<usize>();
                         }
                     }
                 }
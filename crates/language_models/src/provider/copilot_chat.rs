@@ -296,7 +296,8 @@ impl LanguageModel for CopilotChatLanguageModel {
 
 impl CopilotChatLanguageModel {
     pub fn to_copilot_chat_request(&self, request: LanguageModelRequest) -> CopilotChatRequest {
-        CopilotChatRequest::new(
+        let max_tokens = request.max_output_tokens(None);
+        let copilot_request = CopilotChatRequest::new(
             self.model.clone(),
             request
                 .messages
@@ -310,7 +311,11 @@ impl CopilotChatLanguageModel {
                     content: msg.string_contents(),
                 })
                 .collect(),
-        )
+        );
+        CopilotChatRequest {
+            max_tokens,
+            ..copilot_request
+        }
     }
 }
 
@@ -363,6 +363,7 @@ pub fn into_deepseek(
     model: String,
     max_output_tokens: Option<u32>,
 ) -> deepseek::Request {
+    let max_output_tokens = request.max_output_tokens(max_output_tokens);
     let is_reasoner = model == "deepseek-reasoner";
 
     let len = request.messages.len();
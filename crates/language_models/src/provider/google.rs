@@ -13,9 +13,9 @@ use language_model::{
     LanguageModelToolUse, LanguageModelToolUseId, StopReason,
 };
 use language_model::{
-    LanguageModel, LanguageModelId, LanguageModelName, LanguageModelProvider,
+    LanguageModel, LanguageModelId, LanguageModelImage, LanguageModelName, LanguageModelProvider,
     LanguageModelProviderId, LanguageModelProviderName, LanguageModelProviderState,
-    LanguageModelRequest, RateLimiter, Role,
+    LanguageModelRequest, LanguageModelToolResultContent, RateLimiter, Role,
 };
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -371,34 +371,51 @@ pub fn into_google(
                 parts: message
                     .content
                     .into_iter()
-                    .filter_map(|content| match content {
+                    .flat_map(|content| match content {
                         language_model::MessageContent::Text(text) => {
                             if !text.is_empty() {
-                                Some(Part::TextPart(google_ai::TextPart { text }))
+                                vec![Part::TextPart(google_ai::TextPart { text })]
                             } else {
-                                None
+                                Vec::new()
                             }
                         }
-                        language_model::MessageContent::Image(_) => None,
+                        language_model::MessageContent::Image(image) => {
+                            vec![image_part(&image)]
+                        }
                         language_model::MessageContent::ToolUse(tool_use) => {
-                            Some(Part::FunctionCallPart(google_ai::FunctionCallPart {
+                            vec![Part::FunctionCallPart(google_ai::FunctionCallPart {
                                 function_call: google_ai::FunctionCall {
                                     name: tool_use.name.to_string(),
                                     args: tool_use.input,
                                 },
-                            }))
+                            })]
+                        }
+                        language_model::MessageContent::ToolResult(tool_result) => {
+                            let response =
+                                Part::FunctionResponsePart(google_ai::FunctionResponsePart {
+                                    function_response: google_ai::FunctionResponse {
+                                        name: tool_result.tool_name.to_string(),
+                                        // The API expects a valid JSON object
+                                        response: match tool_result.content.as_slice() {
+                                            [LanguageModelToolResultContent::Json { value }] => {
+                                                serde_json::json!({ "output": value })
+                                            }
+                                            _ => serde_json::json!({
+                                                "output": tool_result.text_contents()
+                                            }),
+                                        },
+                                    },
+                                });
+                            // Function responses can't contain images, so they follow the
+                            // response as parts of their own.
+                            let images = tool_result.content.iter().filter_map(|part| match part {
+                                LanguageModelToolResultContent::Image { image } => {
+                                    Some(image_part(image))
+                                }
+                                _ => None,
+                            });
+                            std::iter::once(response).chain(images).collect()
                         }
-                        language_model::MessageContent::ToolResult(tool_result) => Some(
-                            Part::FunctionResponsePart(google_ai::FunctionResponsePart {
-                                function_response: google_ai::FunctionResponse {
-                                    name: tool_result.tool_name.to_string(),
-                                    // The API expects a valid JSON object
-                                    response: serde_json::json!({
-                                        "output": tool_result.content
-                                    }),
-                                },
-                            }),
-                        ),
                     })
                     .collect(),
                 role: match message.role {
@@ -411,7 +428,7 @@ pub fn into_google(
         generation_config: Some(google_ai::GenerationConfig {
             candidate_count: Some(1),
             stop_sequences: Some(request.stop),
-            max_output_tokens: None,
+            max_output_tokens: request.max_tokens.map(|max_tokens| max_tokens as usize),
             temperature: request.temperature.map(|t| t as f64).or(Some(1.0)),
             top_p: None,
             top_k: None,
@@ -432,6 +449,15 @@ pub fn into_google(
     }
 }
 
+fn image_part(image: &LanguageModelImage) -> Part {
+    Part::InlineDataPart(google_ai::InlineDataPart {
+        inline_data: google_ai::GenerativeContentBlob {
+            mime_type: "image/png".to_string(),
+            data: image.source.to_string(),
+        },
+    })
+}
+
 pub fn map_to_language_model_completion_events(
     events: Pin<Box<dyn Send + Stream<Item = Result<GenerateContentResponse>>>>,
 ) -> impl Stream<Item = Result<LanguageModelCompletionEvent>> {
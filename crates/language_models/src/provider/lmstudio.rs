@@ -248,7 +248,12 @@ impl LmStudioLanguageModel {
                 })
                 .collect(),
             stream: true,
-            max_tokens: Some(-1),
+            max_tokens: Some(
+                request
+                    .max_tokens
+                    .and_then(|max_tokens| i32::try_from(max_tokens).ok())
+                    .unwrap_or(-1),
+            ),
             stop: Some(request.stop),
             temperature: request.temperature.or(Some(0.0)),
             tools: vec![],
@@ -375,6 +375,7 @@ pub fn into_mistral(
     model: String,
     max_output_tokens: Option<u32>,
 ) -> mistral::Request {
+    let max_output_tokens = request.max_output_tokens(max_output_tokens);
     let len = request.messages.len();
     let merged_messages =
         request
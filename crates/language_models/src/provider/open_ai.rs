@@ -336,6 +336,7 @@ pub fn into_open_ai(
     model: String,
     max_output_tokens: Option<u32>,
 ) -> open_ai::Request {
+    let max_output_tokens = request.max_output_tokens(max_output_tokens);
     let stream = !model.starts_with("o1-");
     open_ai::Request {
         model,
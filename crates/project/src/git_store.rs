@@ -20,10 +20,12 @@ use git::{
     BuildPermalinkParams, GitHostingProviderRegistry, WORK_DIRECTORY_REPO_PATH,
     blame::Blame,
     parse_git_remote_url,
+    rebase::{RebaseAction, RebaseOperation, RebaseState, RebaseTodoEntry},
     repository::{
-        Branch, CommitDetails, CommitDiff, CommitFile, DiffType, GitRepository,
-        GitRepositoryCheckpoint, PushOptions, Remote, RemoteCommandOutput, RepoPath, ResetMode,
-        UpstreamTrackingStatus,
+        Branch, CommitDetails, CommitDiff, CommitFile, CommitOptions, DetachedHead, DiffType,
+        GitRepository, GitRepositoryCheckpoint, GitWorktree, LogEntry, LogFilter, PushOptions,
+        Remote, RemoteCommandOutput, RepoPath, ResetMode, SequencerCommand, StashEntry, StashMode,
+        Submodule, SubmoduleStatus, Tag, UpstreamTrackingStatus,
     },
     status::{
         FileStatus, GitSummary, StatusCode, TrackedStatus, UnmergedStatus, UnmergedStatusCode,
@@ -40,7 +42,10 @@ use language::{
 use parking_lot::Mutex;
 use rpc::{
     AnyProtoClient, TypedEnvelope,
-    proto::{self, FromProto, SSH_PROJECT_ID, ToProto, git_reset, split_repository_update},
+    proto::{
+        self, FromProto, SSH_PROJECT_ID, ToProto, git_reset, git_stash_push,
+        split_repository_update,
+    },
 };
 use serde::Deserialize;
 use std::{
@@ -49,7 +54,7 @@ use std::{
     future::Future,
     mem,
     ops::Range,
-    path::{Path, PathBuf},
+    path::{Component, Path, PathBuf},
     sync::{
         Arc,
         atomic::{self, AtomicU64},
@@ -221,6 +226,12 @@ pub struct RepositorySnapshot {
     pub branch: Option<Branch>,
     pub merge_conflicts: TreeSet<RepoPath>,
     pub merge_head_shas: Vec<SharedString>,
+    pub rebase_state: Option<RebaseState>,
+    /// The cherry-pick or revert in progress, if any.
+    pub sequencer_command: Option<SequencerCommand>,
+    /// The commit checked out when HEAD doesn't point at a branch.
+    pub detached_head: Option<DetachedHead>,
+    pub submodules: Vec<Submodule>,
     pub scan_id: u64,
 }
 
@@ -384,6 +395,29 @@ impl GitStore {
         client.add_entity_request_handler(Self::handle_show);
         client.add_entity_request_handler(Self::handle_load_commit_diff);
         client.add_entity_request_handler(Self::handle_checkout_files);
+        client.add_entity_request_handler(Self::handle_stash_push);
+        client.add_entity_request_handler(Self::handle_stash_list);
+        client.add_entity_request_handler(Self::handle_stash_apply);
+        client.add_entity_request_handler(Self::handle_stash_pop);
+        client.add_entity_request_handler(Self::handle_stash_drop);
+        client.add_entity_request_handler(Self::handle_stash_show);
+        client.add_entity_request_handler(Self::handle_log);
+        client.add_entity_request_handler(Self::handle_rebase);
+        client.add_entity_request_handler(Self::handle_rebase_operation);
+        client.add_entity_request_handler(Self::handle_cherry_pick);
+        client.add_entity_request_handler(Self::handle_revert);
+        client.add_entity_request_handler(Self::handle_sequencer_operation);
+        client.add_entity_request_handler(Self::handle_tags);
+        client.add_entity_request_handler(Self::handle_create_tag);
+        client.add_entity_request_handler(Self::handle_delete_tag);
+        client.add_entity_request_handler(Self::handle_checkout_tag);
+        client.add_entity_request_handler(Self::handle_push_tag);
+        client.add_entity_request_handler(Self::handle_worktrees);
+        client.add_entity_request_handler(Self::handle_create_worktree);
+        client.add_entity_request_handler(Self::handle_remove_worktree);
+        client.add_entity_request_handler(Self::handle_update_submodules);
+        client.add_entity_request_handler(Self::handle_get_remote_url);
+        client.add_entity_request_handler(Self::handle_fetch_ref);
         client.add_entity_request_handler(Self::handle_open_commit_message_buffer);
         client.add_entity_request_handler(Self::handle_set_index_text);
         client.add_entity_request_handler(Self::handle_askpass);
@@ -1004,6 +1038,27 @@ impl GitStore {
                     paths_by_git_repo.entry(repo).or_default().push(repo_path)
                 }
 
+                // Changes inside a submodule can change its status in the superproject.
+                let changed_repos = paths_by_git_repo.keys().cloned().collect::<Vec<_>>();
+                for repo in changed_repos {
+                    let work_directory_abs_path = repo.read(cx).work_directory_abs_path.clone();
+                    for superproject in self.repositories.values() {
+                        let superproject_snapshot = superproject.read(cx);
+                        let Some(submodule_path) = superproject_snapshot
+                            .abs_path_to_repo_path(&work_directory_abs_path)
+                            .filter(|path| {
+                                superproject_snapshot.submodule_for_path(path).is_some()
+                            })
+                        else {
+                            continue;
+                        };
+                        paths_by_git_repo
+                            .entry(superproject.clone())
+                            .or_default()
+                            .push(submodule_path);
+                    }
+                }
+
                 for (repo, paths) in paths_by_git_repo {
                     repo.update(cx, |repo, cx| {
                         repo.paths_changed(
@@ -1447,6 +1502,23 @@ impl GitStore {
             .max_by_key(|(repo, _)| repo.read(cx).work_directory_abs_path.clone())
     }
 
+    /// Returns the repository of a submodule of `superproject`, once it has been discovered.
+    pub fn repository_for_submodule(
+        &self,
+        superproject: &Entity<Repository>,
+        submodule_path: &RepoPath,
+        cx: &App,
+    ) -> Option<Entity<Repository>> {
+        let abs_path = superproject
+            .read(cx)
+            .work_directory_abs_path
+            .join(submodule_path);
+        self.repositories
+            .values()
+            .find(|repo| repo.read(cx).work_directory_abs_path.as_ref() == abs_path)
+            .cloned()
+    }
+
     pub fn git_init(
         &self,
         path: Arc<Path>,
@@ -1600,6 +1672,77 @@ impl GitStore {
         })
     }
 
+    async fn handle_fetch_ref(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitFetchRef>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::RemoteMessageResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let askpass_id = envelope.payload.askpass_id;
+
+        let askpass = make_remote_delegate(
+            this,
+            envelope.payload.project_id,
+            repository_id,
+            askpass_id,
+            &mut cx,
+        );
+
+        let remote_output = repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.fetch_ref(
+                    envelope.payload.remote_name,
+                    envelope.payload.remote_ref,
+                    envelope.payload.branch_name,
+                    askpass,
+                    cx,
+                )
+            })?
+            .await??;
+
+        Ok(proto::RemoteMessageResponse {
+            stdout: remote_output.stdout,
+            stderr: remote_output.stderr,
+        })
+    }
+
+    async fn handle_update_submodules(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitUpdateSubmodules>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::RemoteMessageResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let askpass_id = envelope.payload.askpass_id;
+        let paths = envelope
+            .payload
+            .paths
+            .into_iter()
+            .map(PathBuf::from)
+            .map(RepoPath::new)
+            .collect();
+
+        let askpass = make_remote_delegate(
+            this,
+            envelope.payload.project_id,
+            repository_id,
+            askpass_id,
+            &mut cx,
+        );
+
+        let remote_output = repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.update_submodules(paths, askpass, cx)
+            })?
+            .await??;
+
+        Ok(proto::RemoteMessageResponse {
+            stdout: remote_output.stdout,
+            stderr: remote_output.stderr,
+        })
+    }
+
     async fn handle_push(
         this: Entity<Self>,
         envelope: TypedEnvelope<proto::Push>,
@@ -1751,10 +1894,20 @@ impl GitStore {
         let message = SharedString::from(envelope.payload.message);
         let name = envelope.payload.name.map(SharedString::from);
         let email = envelope.payload.email.map(SharedString::from);
+        let options = envelope
+            .payload
+            .options
+            .map(|options| CommitOptions {
+                amend: options.amend,
+                fixup: options.fixup,
+                signoff: options.signoff,
+                gpg_sign: options.gpg_sign,
+            })
+            .unwrap_or_default();
 
         repository_handle
             .update(&mut cx, |repository_handle, cx| {
-                repository_handle.commit(message, name.zip(email), cx)
+                repository_handle.commit(message, name.zip(email), options, cx)
             })?
             .await??;
         Ok(proto::Ack {})
@@ -1786,6 +1939,23 @@ impl GitStore {
         })
     }
 
+    async fn handle_get_remote_url(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitGetRemoteUrl>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitGetRemoteUrlResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let url = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.remote_url(envelope.payload.remote_name)
+            })?
+            .await??;
+
+        Ok(proto::GitGetRemoteUrlResponse { url })
+    }
+
     async fn handle_get_branches(
         this: Entity<Self>,
         envelope: TypedEnvelope<proto::GitGetBranches>,
@@ -1876,17 +2046,7 @@ impl GitStore {
                 repository_handle.load_commit_diff(envelope.payload.commit)
             })?
             .await??;
-        Ok(proto::LoadCommitDiffResponse {
-            files: commit_diff
-                .files
-                .into_iter()
-                .map(|file| proto::CommitFile {
-                    path: file.path.to_string(),
-                    old_text: file.old_text,
-                    new_text: file.new_text,
-                })
-                .collect(),
-        })
+        Ok(commit_diff_to_proto(commit_diff))
     }
 
     async fn handle_reset(
@@ -1932,339 +2092,741 @@ impl GitStore {
         Ok(proto::Ack {})
     }
 
-    async fn handle_open_commit_message_buffer(
+    async fn handle_stash_push(
         this: Entity<Self>,
-        envelope: TypedEnvelope<proto::OpenCommitMessageBuffer>,
+        envelope: TypedEnvelope<proto::GitStashPush>,
         mut cx: AsyncApp,
-    ) -> Result<proto::OpenBufferResponse> {
+    ) -> Result<proto::Ack> {
         let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
-        let repository = Self::repository_for_request(&this, repository_id, &mut cx)?;
-        let buffer = repository
-            .update(&mut cx, |repository, cx| {
-                repository.open_commit_buffer(None, this.read(cx).buffer_store.clone(), cx)
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let mode = match envelope.payload.mode() {
+            git_stash_push::StashMode::All => StashMode::All,
+            git_stash_push::StashMode::Staged => StashMode::Staged,
+            git_stash_push::StashMode::Paths => StashMode::Paths(
+                envelope
+                    .payload
+                    .paths
+                    .iter()
+                    .map(|s| RepoPath::from_str(s))
+                    .collect(),
+            ),
+        };
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.stash_push(envelope.payload.message, mode)
             })?
-            .await?;
+            .await??;
+        Ok(proto::Ack {})
+    }
 
-        let buffer_id = buffer.read_with(&cx, |buffer, _| buffer.remote_id())?;
-        this.update(&mut cx, |this, cx| {
-            this.buffer_store.update(cx, |buffer_store, cx| {
-                buffer_store
-                    .create_buffer_for_peer(
-                        &buffer,
-                        envelope.original_sender_id.unwrap_or(envelope.sender_id),
-                        cx,
-                    )
-                    .detach_and_log_err(cx);
-            })
-        })?;
+    async fn handle_stash_list(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitStashList>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitStashListResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
 
-        Ok(proto::OpenBufferResponse {
-            buffer_id: buffer_id.to_proto(),
+        let entries = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.stash_list()
+            })?
+            .await??;
+        Ok(proto::GitStashListResponse {
+            entries: entries.iter().map(stash_entry_to_proto).collect(),
         })
     }
 
-    async fn handle_askpass(
+    async fn handle_stash_apply(
         this: Entity<Self>,
-        envelope: TypedEnvelope<proto::AskPassRequest>,
+        envelope: TypedEnvelope<proto::GitStashApply>,
         mut cx: AsyncApp,
-    ) -> Result<proto::AskPassResponse> {
+    ) -> Result<proto::Ack> {
         let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
-        let repository = Self::repository_for_request(&this, repository_id, &mut cx)?;
-
-        let delegates = cx.update(|cx| repository.read(cx).askpass_delegates.clone())?;
-        let Some(mut askpass) = delegates.lock().remove(&envelope.payload.askpass_id) else {
-            debug_panic!("no askpass found");
-            return Err(anyhow::anyhow!("no askpass found"));
-        };
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
 
-        let response = askpass.ask_password(envelope.payload.prompt).await?;
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.stash_apply(envelope.payload.stash_index as usize)
+            })?
+            .await??;
+        Ok(proto::Ack {})
+    }
 
-        delegates
-            .lock()
-            .insert(envelope.payload.askpass_id, askpass);
+    async fn handle_stash_pop(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitStashPop>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
 
-        Ok(proto::AskPassResponse { response })
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.stash_pop(envelope.payload.stash_index as usize)
+            })?
+            .await??;
+        Ok(proto::Ack {})
     }
 
-    async fn handle_check_for_pushed_commits(
+    async fn handle_stash_drop(
         this: Entity<Self>,
-        envelope: TypedEnvelope<proto::CheckForPushedCommits>,
+        envelope: TypedEnvelope<proto::GitStashDrop>,
         mut cx: AsyncApp,
-    ) -> Result<proto::CheckForPushedCommitsResponse> {
+    ) -> Result<proto::Ack> {
         let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
         let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
 
-        let branches = repository_handle
+        repository_handle
             .update(&mut cx, |repository_handle, _| {
-                repository_handle.check_for_pushed_commits()
+                repository_handle.stash_drop(envelope.payload.stash_index as usize)
             })?
             .await??;
-        Ok(proto::CheckForPushedCommitsResponse {
-            pushed_to: branches
-                .into_iter()
-                .map(|commit| commit.to_string())
-                .collect(),
-        })
+        Ok(proto::Ack {})
     }
 
-    async fn handle_git_diff(
+    async fn handle_stash_show(
         this: Entity<Self>,
-        envelope: TypedEnvelope<proto::GitDiff>,
+        envelope: TypedEnvelope<proto::GitStashShow>,
         mut cx: AsyncApp,
-    ) -> Result<proto::GitDiffResponse> {
+    ) -> Result<proto::LoadCommitDiffResponse> {
         let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
         let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
-        let diff_type = match envelope.payload.diff_type() {
-            proto::git_diff::DiffType::HeadToIndex => DiffType::HeadToIndex,
-            proto::git_diff::DiffType::HeadToWorktree => DiffType::HeadToWorktree,
-        };
 
-        let mut diff = repository_handle
-            .update(&mut cx, |repository_handle, cx| {
-                repository_handle.diff(diff_type, cx)
+        let stash_diff = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.stash_show(envelope.payload.stash_index as usize)
             })?
             .await??;
-        const ONE_MB: usize = 1_000_000;
-        if diff.len() > ONE_MB {
-            diff = diff.chars().take(ONE_MB).collect()
-        }
-
-        Ok(proto::GitDiffResponse { diff })
+        Ok(commit_diff_to_proto(stash_diff))
     }
 
-    async fn handle_open_unstaged_diff(
+    async fn handle_log(
         this: Entity<Self>,
-        request: TypedEnvelope<proto::OpenUnstagedDiff>,
+        envelope: TypedEnvelope<proto::GitLog>,
         mut cx: AsyncApp,
-    ) -> Result<proto::OpenUnstagedDiffResponse> {
-        let buffer_id = BufferId::new(request.payload.buffer_id)?;
-        let diff = this
-            .update(&mut cx, |this, cx| {
-                let buffer = this.buffer_store.read(cx).get(buffer_id)?;
-                Some(this.open_unstaged_diff(buffer, cx))
+    ) -> Result<proto::GitLogResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let filter = match envelope.payload.filter {
+            None => LogFilter::Head,
+            Some(proto::git_log::Filter::Path(path)) => LogFilter::Path {
+                path: RepoPath::from_str(&path.path),
+                follow: path.follow,
+            },
+            Some(proto::git_log::Filter::Lines(lines)) => LogFilter::Lines {
+                path: RepoPath::from_str(&lines.path),
+                start: lines.start,
+                end: lines.end,
+            },
+        };
+        let entries = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.log(
+                    filter,
+                    envelope.payload.skip as usize,
+                    envelope.payload.limit as usize,
+                )
             })?
-            .ok_or_else(|| anyhow!("no such buffer"))?
-            .await?;
-        this.update(&mut cx, |this, _| {
-            let shared_diffs = this
-                .shared_diffs
-                .entry(request.original_sender_id.unwrap_or(request.sender_id))
-                .or_default();
-            shared_diffs.entry(buffer_id).or_default().unstaged = Some(diff.clone());
-        })?;
-        let staged_text = diff.read_with(&cx, |diff, _| diff.base_text_string())?;
-        Ok(proto::OpenUnstagedDiffResponse { staged_text })
+            .await??;
+        Ok(proto::GitLogResponse {
+            entries: entries.iter().map(log_entry_to_proto).collect(),
+        })
     }
 
-    async fn handle_open_uncommitted_diff(
+    async fn handle_rebase(
         this: Entity<Self>,
-        request: TypedEnvelope<proto::OpenUncommittedDiff>,
+        envelope: TypedEnvelope<proto::GitRebase>,
         mut cx: AsyncApp,
-    ) -> Result<proto::OpenUncommittedDiffResponse> {
-        let buffer_id = BufferId::new(request.payload.buffer_id)?;
-        let diff = this
-            .update(&mut cx, |this, cx| {
-                let buffer = this.buffer_store.read(cx).get(buffer_id)?;
-                Some(this.open_uncommitted_diff(buffer, cx))
-            })?
-            .ok_or_else(|| anyhow!("no such buffer"))?
-            .await?;
-        this.update(&mut cx, |this, _| {
-            let shared_diffs = this
-                .shared_diffs
-                .entry(request.original_sender_id.unwrap_or(request.sender_id))
-                .or_default();
-            shared_diffs.entry(buffer_id).or_default().uncommitted = Some(diff.clone());
-        })?;
-        diff.read_with(&cx, |diff, cx| {
-            use proto::open_uncommitted_diff_response::Mode;
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
 
-            let unstaged_diff = diff.secondary_diff();
-            let index_snapshot = unstaged_diff.and_then(|diff| {
-                let diff = diff.read(cx);
-                diff.base_text_exists().then(|| diff.base_text())
-            });
+        let entries = envelope
+            .payload
+            .entries
+            .iter()
+            .map(proto_to_rebase_todo_entry)
+            .collect();
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.rebase_interactive(envelope.payload.base, entries)
+            })?
+            .await??;
+        Ok(proto::Ack {})
+    }
 
-            let mode;
-            let staged_text;
-            let committed_text;
-            if diff.base_text_exists() {
-                let committed_snapshot = diff.base_text();
-                committed_text = Some(committed_snapshot.text());
-                if let Some(index_text) = index_snapshot {
-                    if index_text.remote_id() == committed_snapshot.remote_id() {
-                        mode = Mode::IndexMatchesHead;
-                        staged_text = None;
-                    } else {
-                        mode = Mode::IndexAndHead;
-                        staged_text = Some(index_text.text());
-                    }
-                } else {
-                    mode = Mode::IndexAndHead;
-                    staged_text = None;
-                }
-            } else {
-                mode = Mode::IndexAndHead;
-                committed_text = None;
-                staged_text = index_snapshot.as_ref().map(|buffer| buffer.text());
-            }
+    async fn handle_rebase_operation(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitRebaseOperation>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
 
-            proto::OpenUncommittedDiffResponse {
-                committed_text,
-                staged_text,
-                mode: mode.into(),
-            }
-        })
+        let operation = proto_to_rebase_operation(envelope.payload.operation());
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.rebase_operation(operation)
+            })?
+            .await??;
+        Ok(proto::Ack {})
     }
 
-    async fn handle_update_diff_bases(
+    async fn handle_cherry_pick(
         this: Entity<Self>,
-        request: TypedEnvelope<proto::UpdateDiffBases>,
+        envelope: TypedEnvelope<proto::GitCherryPick>,
         mut cx: AsyncApp,
-    ) -> Result<()> {
-        let buffer_id = BufferId::new(request.payload.buffer_id)?;
-        this.update(&mut cx, |this, cx| {
-            if let Some(diff_state) = this.diffs.get_mut(&buffer_id) {
-                if let Some(buffer) = this.buffer_store.read(cx).get(buffer_id) {
-                    let buffer = buffer.read(cx).text_snapshot();
-                    diff_state.update(cx, |diff_state, cx| {
-                        diff_state.handle_base_texts_updated(buffer, request.payload, cx);
-                    })
-                }
-            }
-        })
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.cherry_pick(envelope.payload.commits, envelope.payload.no_commit)
+            })?
+            .await??;
+        Ok(proto::Ack {})
     }
 
-    async fn handle_blame_buffer(
+    async fn handle_revert(
         this: Entity<Self>,
-        envelope: TypedEnvelope<proto::BlameBuffer>,
+        envelope: TypedEnvelope<proto::GitRevert>,
         mut cx: AsyncApp,
-    ) -> Result<proto::BlameBufferResponse> {
-        let buffer_id = BufferId::new(envelope.payload.buffer_id)?;
-        let version = deserialize_version(&envelope.payload.version);
-        let buffer = this.read_with(&cx, |this, cx| {
-            this.buffer_store.read(cx).get_existing(buffer_id)
-        })??;
-        buffer
-            .update(&mut cx, |buffer, _| {
-                buffer.wait_for_version(version.clone())
-            })?
-            .await?;
-        let blame = this
-            .update(&mut cx, |this, cx| {
-                this.blame_buffer(&buffer, Some(version), cx)
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.revert(envelope.payload.commits, envelope.payload.no_commit)
             })?
-            .await?;
-        Ok(serialize_blame_buffer_response(blame))
+            .await??;
+        Ok(proto::Ack {})
     }
 
-    async fn handle_get_permalink_to_line(
+    async fn handle_sequencer_operation(
         this: Entity<Self>,
-        envelope: TypedEnvelope<proto::GetPermalinkToLine>,
+        envelope: TypedEnvelope<proto::GitSequencerOperation>,
         mut cx: AsyncApp,
-    ) -> Result<proto::GetPermalinkToLineResponse> {
-        let buffer_id = BufferId::new(envelope.payload.buffer_id)?;
-        // let version = deserialize_version(&envelope.payload.version);
-        let selection = {
-            let proto_selection = envelope
-                .payload
-                .selection
-                .context("no selection to get permalink for defined")?;
-            proto_selection.start as u32..proto_selection.end as u32
-        };
-        let buffer = this.read_with(&cx, |this, cx| {
-            this.buffer_store.read(cx).get_existing(buffer_id)
-        })??;
-        let permalink = this
-            .update(&mut cx, |this, cx| {
-                this.get_permalink_to_line(&buffer, selection, cx)
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let command = proto_to_sequencer_command(envelope.payload.command());
+        let operation = proto_to_rebase_operation(envelope.payload.operation());
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.sequencer_operation(command, operation)
             })?
-            .await?;
-        Ok(proto::GetPermalinkToLineResponse {
-            permalink: permalink.to_string(),
-        })
+            .await??;
+        Ok(proto::Ack {})
     }
 
-    fn repository_for_request(
-        this: &Entity<Self>,
-        id: RepositoryId,
-        cx: &mut AsyncApp,
-    ) -> Result<Entity<Repository>> {
-        this.update(cx, |this, _| {
-            this.repositories
-                .get(&id)
-                .context("missing repository handle")
-                .cloned()
-        })?
-    }
+    async fn handle_tags(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitTags>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitTagsResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
 
-    pub fn repo_snapshots(&self, cx: &App) -> HashMap<RepositoryId, RepositorySnapshot> {
-        self.repositories
-            .iter()
-            .map(|(id, repo)| (*id, repo.read(cx).snapshot.clone()))
-            .collect()
+        let tags = repository_handle
+            .update(&mut cx, |repository_handle, _| repository_handle.tags())?
+            .await??;
+        Ok(proto::GitTagsResponse {
+            tags: tags.iter().map(tag_to_proto).collect(),
+        })
     }
-}
 
-impl BufferDiffState {
-    fn buffer_language_changed(&mut self, buffer: Entity<Buffer>, cx: &mut Context<Self>) {
-        self.language = buffer.read(cx).language().cloned();
-        self.language_changed = true;
-        let _ = self.recalculate_diffs(
-            buffer.read(cx).text_snapshot(),
-            self.hunk_staging_operation_count,
-            cx,
-        );
-    }
+    async fn handle_create_tag(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitCreateTag>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
 
-    fn unstaged_diff(&self) -> Option<Entity<BufferDiff>> {
-        self.unstaged_diff.as_ref().and_then(|set| set.upgrade())
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.create_tag(
+                    envelope.payload.name,
+                    envelope.payload.target,
+                    envelope.payload.message,
+                )
+            })?
+            .await??;
+        Ok(proto::Ack {})
     }
 
-    fn uncommitted_diff(&self) -> Option<Entity<BufferDiff>> {
-        self.uncommitted_diff.as_ref().and_then(|set| set.upgrade())
+    async fn handle_delete_tag(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitDeleteTag>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.delete_tag(envelope.payload.name)
+            })?
+            .await??;
+        Ok(proto::Ack {})
     }
 
-    fn handle_base_texts_updated(
-        &mut self,
-        buffer: text::BufferSnapshot,
-        message: proto::UpdateDiffBases,
-        cx: &mut Context<Self>,
-    ) {
-        use proto::update_diff_bases::Mode;
+    async fn handle_checkout_tag(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitCheckoutTag>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
 
-        let Some(mode) = Mode::from_i32(message.mode) else {
-            return;
-        };
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.checkout_tag(envelope.payload.name)
+            })?
+            .await??;
+        Ok(proto::Ack {})
+    }
 
-        let diff_bases_change = match mode {
-            Mode::HeadOnly => DiffBasesChange::SetHead(message.committed_text),
-            Mode::IndexOnly => DiffBasesChange::SetIndex(message.staged_text),
-            Mode::IndexMatchesHead => DiffBasesChange::SetBoth(message.committed_text),
-            Mode::IndexAndHead => DiffBasesChange::SetEach {
-                index: message.staged_text,
-                head: message.committed_text,
-            },
-        };
+    async fn handle_push_tag(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitPushTag>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::RemoteMessageResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let askpass_id = envelope.payload.askpass_id;
 
-        let _ = self.diff_bases_changed(
-            buffer,
-            diff_bases_change,
-            self.hunk_staging_operation_count,
-            cx,
+        let askpass = make_remote_delegate(
+            this,
+            envelope.payload.project_id,
+            repository_id,
+            askpass_id,
+            &mut cx,
         );
-    }
 
-    pub fn wait_for_recalculation(&mut self) -> Option<oneshot::Receiver<()>> {
-        if self.diff_updated_futures.is_empty() {
-            return None;
-        }
-        let (tx, rx) = oneshot::channel();
-        self.diff_updated_futures.push(tx);
-        Some(rx)
-    }
+        let remote_output = repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.push_tag(
+                    envelope.payload.name,
+                    envelope.payload.remote_name,
+                    askpass,
+                    cx,
+                )
+            })?
+            .await??;
 
-    fn diff_bases_changed(
+        Ok(proto::RemoteMessageResponse {
+            stdout: remote_output.stdout,
+            stderr: remote_output.stderr,
+        })
+    }
+
+    async fn handle_worktrees(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitWorktrees>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitWorktreesResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let worktrees = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.worktrees()
+            })?
+            .await??;
+        Ok(proto::GitWorktreesResponse {
+            worktrees: worktrees.iter().map(worktree_to_proto).collect(),
+        })
+    }
+
+    async fn handle_create_worktree(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitCreateWorktree>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let path = PathBuf::from_proto(envelope.payload.path);
+        let work_directory_abs_path = repository_handle
+            .read_with(&cx, |repository_handle, _| {
+                repository_handle.work_directory_abs_path.clone()
+            })?;
+        check_worktree_path(&work_directory_abs_path, &path)?;
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.create_worktree(
+                    path,
+                    envelope.payload.branch,
+                    envelope.payload.create_branch,
+                )
+            })?
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_remove_worktree(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitRemoveWorktree>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let path = PathBuf::from_proto(envelope.payload.path);
+        let work_directory_abs_path = repository_handle
+            .read_with(&cx, |repository_handle, _| {
+                repository_handle.work_directory_abs_path.clone()
+            })?;
+        check_worktree_path(&work_directory_abs_path, &path)?;
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.remove_worktree(path, envelope.payload.force)
+            })?
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_open_commit_message_buffer(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::OpenCommitMessageBuffer>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::OpenBufferResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let buffer = repository
+            .update(&mut cx, |repository, cx| {
+                repository.open_commit_buffer(None, this.read(cx).buffer_store.clone(), cx)
+            })?
+            .await?;
+
+        let buffer_id = buffer.read_with(&cx, |buffer, _| buffer.remote_id())?;
+        this.update(&mut cx, |this, cx| {
+            this.buffer_store.update(cx, |buffer_store, cx| {
+                buffer_store
+                    .create_buffer_for_peer(
+                        &buffer,
+                        envelope.original_sender_id.unwrap_or(envelope.sender_id),
+                        cx,
+                    )
+                    .detach_and_log_err(cx);
+            })
+        })?;
+
+        Ok(proto::OpenBufferResponse {
+            buffer_id: buffer_id.to_proto(),
+        })
+    }
+
+    async fn handle_askpass(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::AskPassRequest>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::AskPassResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let delegates = cx.update(|cx| repository.read(cx).askpass_delegates.clone())?;
+        let Some(mut askpass) = delegates.lock().remove(&envelope.payload.askpass_id) else {
+            debug_panic!("no askpass found");
+            return Err(anyhow::anyhow!("no askpass found"));
+        };
+
+        let response = askpass.ask_password(envelope.payload.prompt).await?;
+
+        delegates
+            .lock()
+            .insert(envelope.payload.askpass_id, askpass);
+
+        Ok(proto::AskPassResponse { response })
+    }
+
+    async fn handle_check_for_pushed_commits(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::CheckForPushedCommits>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::CheckForPushedCommitsResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let branches = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.check_for_pushed_commits()
+            })?
+            .await??;
+        Ok(proto::CheckForPushedCommitsResponse {
+            pushed_to: branches
+                .into_iter()
+                .map(|commit| commit.to_string())
+                .collect(),
+        })
+    }
+
+    async fn handle_git_diff(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitDiff>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitDiffResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let diff_type = match envelope.payload.diff_type() {
+            proto::git_diff::DiffType::HeadToIndex => DiffType::HeadToIndex,
+            proto::git_diff::DiffType::HeadToWorktree => DiffType::HeadToWorktree,
+        };
+
+        let mut diff = repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.diff(diff_type, cx)
+            })?
+            .await??;
+        const ONE_MB: usize = 1_000_000;
+        if diff.len() > ONE_MB {
+            diff = diff.chars().take(ONE_MB).collect()
+        }
+
+        Ok(proto::GitDiffResponse { diff })
+    }
+
+    async fn handle_open_unstaged_diff(
+        this: Entity<Self>,
+        request: TypedEnvelope<proto::OpenUnstagedDiff>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::OpenUnstagedDiffResponse> {
+        let buffer_id = BufferId::new(request.payload.buffer_id)?;
+        let diff = this
+            .update(&mut cx, |this, cx| {
+                let buffer = this.buffer_store.read(cx).get(buffer_id)?;
+                Some(this.open_unstaged_diff(buffer, cx))
+            })?
+            .ok_or_else(|| anyhow!("no such buffer"))?
+            .await?;
+        this.update(&mut cx, |this, _| {
+            let shared_diffs = this
+                .shared_diffs
+                .entry(request.original_sender_id.unwrap_or(request.sender_id))
+                .or_default();
+            shared_diffs.entry(buffer_id).or_default().unstaged = Some(diff.clone());
+        })?;
+        let staged_text = diff.read_with(&cx, |diff, _| diff.base_text_string())?;
+        Ok(proto::OpenUnstagedDiffResponse { staged_text })
+    }
+
+    async fn handle_open_uncommitted_diff(
+        this: Entity<Self>,
+        request: TypedEnvelope<proto::OpenUncommittedDiff>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::OpenUncommittedDiffResponse> {
+        let buffer_id = BufferId::new(request.payload.buffer_id)?;
+        let diff = this
+            .update(&mut cx, |this, cx| {
+                let buffer = this.buffer_store.read(cx).get(buffer_id)?;
+                Some(this.open_uncommitted_diff(buffer, cx))
+            })?
+            .ok_or_else(|| anyhow!("no such buffer"))?
+            .await?;
+        this.update(&mut cx, |this, _| {
+            let shared_diffs = this
+                .shared_diffs
+                .entry(request.original_sender_id.unwrap_or(request.sender_id))
+                .or_default();
+            shared_diffs.entry(buffer_id).or_default().uncommitted = Some(diff.clone());
+        })?;
+        diff.read_with(&cx, |diff, cx| {
+            use proto::open_uncommitted_diff_response::Mode;
+
+            let unstaged_diff = diff.secondary_diff();
+            let index_snapshot = unstaged_diff.and_then(|diff| {
+                let diff = diff.read(cx);
+                diff.base_text_exists().then(|| diff.base_text())
+            });
+
+            let mode;
+            let staged_text;
+            let committed_text;
+            if diff.base_text_exists() {
+                let committed_snapshot = diff.base_text();
+                committed_text = Some(committed_snapshot.text());
+                if let Some(index_text) = index_snapshot {
+                    if index_text.remote_id() == committed_snapshot.remote_id() {
+                        mode = Mode::IndexMatchesHead;
+                        staged_text = None;
+                    } else {
+                        mode = Mode::IndexAndHead;
+                        staged_text = Some(index_text.text());
+                    }
+                } else {
+                    mode = Mode::IndexAndHead;
+                    staged_text = None;
+                }
+            } else {
+                mode = Mode::IndexAndHead;
+                committed_text = None;
+                staged_text = index_snapshot.as_ref().map(|buffer| buffer.text());
+            }
+
+            proto::OpenUncommittedDiffResponse {
+                committed_text,
+                staged_text,
+                mode: mode.into(),
+            }
+        })
+    }
+
+    async fn handle_update_diff_bases(
+        this: Entity<Self>,
+        request: TypedEnvelope<proto::UpdateDiffBases>,
+        mut cx: AsyncApp,
+    ) -> Result<()> {
+        let buffer_id = BufferId::new(request.payload.buffer_id)?;
+        this.update(&mut cx, |this, cx| {
+            if let Some(diff_state) = this.diffs.get_mut(&buffer_id) {
+                if let Some(buffer) = this.buffer_store.read(cx).get(buffer_id) {
+                    let buffer = buffer.read(cx).text_snapshot();
+                    diff_state.update(cx, |diff_state, cx| {
+                        diff_state.handle_base_texts_updated(buffer, request.payload, cx);
+                    })
+                }
+            }
+        })
+    }
+
+    async fn handle_blame_buffer(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::BlameBuffer>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::BlameBufferResponse> {
+        let buffer_id = BufferId::new(envelope.payload.buffer_id)?;
+        let version = deserialize_version(&envelope.payload.version);
+        let buffer = this.read_with(&cx, |this, cx| {
+            this.buffer_store.read(cx).get_existing(buffer_id)
+        })??;
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(version.clone())
+            })?
+            .await?;
+        let blame = this
+            .update(&mut cx, |this, cx| {
+                this.blame_buffer(&buffer, Some(version), cx)
+            })?
+            .await?;
+        Ok(serialize_blame_buffer_response(blame))
+    }
+
+    async fn handle_get_permalink_to_line(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GetPermalinkToLine>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GetPermalinkToLineResponse> {
+        let buffer_id = BufferId::new(envelope.payload.buffer_id)?;
+        // let version = deserialize_version(&envelope.payload.version);
+        let selection = {
+            let proto_selection = envelope
+                .payload
+                .selection
+                .context("no selection to get permalink for defined")?;
+            proto_selection.start as u32..proto_selection.end as u32
+        };
+        let buffer = this.read_with(&cx, |this, cx| {
+            this.buffer_store.read(cx).get_existing(buffer_id)
+        })??;
+        let permalink = this
+            .update(&mut cx, |this, cx| {
+                this.get_permalink_to_line(&buffer, selection, cx)
+            })?
+            .await?;
+        Ok(proto::GetPermalinkToLineResponse {
+            permalink: permalink.to_string(),
+        })
+    }
+
+    fn repository_for_request(
+        this: &Entity<Self>,
+        id: RepositoryId,
+        cx: &mut AsyncApp,
+    ) -> Result<Entity<Repository>> {
+        this.update(cx, |this, _| {
+            this.repositories
+                .get(&id)
+                .context("missing repository handle")
+                .cloned()
+        })?
+    }
+
+    pub fn repo_snapshots(&self, cx: &App) -> HashMap<RepositoryId, RepositorySnapshot> {
+        self.repositories
+            .iter()
+            .map(|(id, repo)| (*id, repo.read(cx).snapshot.clone()))
+            .collect()
+    }
+}
+
+impl BufferDiffState {
+    fn buffer_language_changed(&mut self, buffer: Entity<Buffer>, cx: &mut Context<Self>) {
+        self.language = buffer.read(cx).language().cloned();
+        self.language_changed = true;
+        let _ = self.recalculate_diffs(
+            buffer.read(cx).text_snapshot(),
+            self.hunk_staging_operation_count,
+            cx,
+        );
+    }
+
+    fn unstaged_diff(&self) -> Option<Entity<BufferDiff>> {
+        self.unstaged_diff.as_ref().and_then(|set| set.upgrade())
+    }
+
+    fn uncommitted_diff(&self) -> Option<Entity<BufferDiff>> {
+        self.uncommitted_diff.as_ref().and_then(|set| set.upgrade())
+    }
+
+    fn handle_base_texts_updated(
+        &mut self,
+        buffer: text::BufferSnapshot,
+        message: proto::UpdateDiffBases,
+        cx: &mut Context<Self>,
+    ) {
+        use proto::update_diff_bases::Mode;
+
+        let Some(mode) = Mode::from_i32(message.mode) else {
+            return;
+        };
+
+        let diff_bases_change = match mode {
+            Mode::HeadOnly => DiffBasesChange::SetHead(message.committed_text),
+            Mode::IndexOnly => DiffBasesChange::SetIndex(message.staged_text),
+            Mode::IndexMatchesHead => DiffBasesChange::SetBoth(message.committed_text),
+            Mode::IndexAndHead => DiffBasesChange::SetEach {
+                index: message.staged_text,
+                head: message.committed_text,
+            },
+        };
+
+        let _ = self.diff_bases_changed(
+            buffer,
+            diff_bases_change,
+            self.hunk_staging_operation_count,
+            cx,
+        );
+    }
+
+    pub fn wait_for_recalculation(&mut self) -> Option<oneshot::Receiver<()>> {
+        if self.diff_updated_futures.is_empty() {
+            return None;
+        }
+        let (tx, rx) = oneshot::channel();
+        self.diff_updated_futures.push(tx);
+        Some(rx)
+    }
+
+    fn diff_bases_changed(
         &mut self,
         buffer: text::BufferSnapshot,
         diff_bases_change: DiffBasesChange,
@@ -2286,536 +2848,1114 @@ impl BufferDiffState {
                 });
                 self.head_changed = true;
             }
-            DiffBasesChange::SetBoth(text) => {
-                let text = text.map(|mut text| {
-                    text::LineEnding::normalize(&mut text);
-                    Arc::new(text)
-                });
-                self.head_text = text.clone();
-                self.index_text = text;
-                self.head_changed = true;
-                self.index_changed = true;
+            DiffBasesChange::SetBoth(text) => {
+                let text = text.map(|mut text| {
+                    text::LineEnding::normalize(&mut text);
+                    Arc::new(text)
+                });
+                self.head_text = text.clone();
+                self.index_text = text;
+                self.head_changed = true;
+                self.index_changed = true;
+            }
+            DiffBasesChange::SetEach { index, head } => {
+                self.index_text = index.map(|mut index| {
+                    text::LineEnding::normalize(&mut index);
+                    Arc::new(index)
+                });
+                self.index_changed = true;
+                self.head_text = head.map(|mut head| {
+                    text::LineEnding::normalize(&mut head);
+                    Arc::new(head)
+                });
+                self.head_changed = true;
+            }
+        }
+
+        self.recalculate_diffs(buffer, prev_hunk_staging_operation_count, cx)
+    }
+
+    fn recalculate_diffs(
+        &mut self,
+        buffer: text::BufferSnapshot,
+        prev_hunk_staging_operation_count: usize,
+        cx: &mut Context<Self>,
+    ) -> oneshot::Receiver<()> {
+        log::debug!("recalculate diffs");
+        let (tx, rx) = oneshot::channel();
+        self.diff_updated_futures.push(tx);
+
+        let language = self.language.clone();
+        let language_registry = self.language_registry.clone();
+        let unstaged_diff = self.unstaged_diff();
+        let uncommitted_diff = self.uncommitted_diff();
+        let head = self.head_text.clone();
+        let index = self.index_text.clone();
+        let index_changed = self.index_changed;
+        let head_changed = self.head_changed;
+        let language_changed = self.language_changed;
+        let index_matches_head = match (self.index_text.as_ref(), self.head_text.as_ref()) {
+            (Some(index), Some(head)) => Arc::ptr_eq(index, head),
+            (None, None) => true,
+            _ => false,
+        };
+        self.recalculate_diff_task = Some(cx.spawn(async move |this, cx| {
+            let mut new_unstaged_diff = None;
+            if let Some(unstaged_diff) = &unstaged_diff {
+                new_unstaged_diff = Some(
+                    BufferDiff::update_diff(
+                        unstaged_diff.clone(),
+                        buffer.clone(),
+                        index,
+                        index_changed,
+                        language_changed,
+                        language.clone(),
+                        language_registry.clone(),
+                        cx,
+                    )
+                    .await?,
+                );
+            }
+
+            let mut new_uncommitted_diff = None;
+            if let Some(uncommitted_diff) = &uncommitted_diff {
+                new_uncommitted_diff = if index_matches_head {
+                    new_unstaged_diff.clone()
+                } else {
+                    Some(
+                        BufferDiff::update_diff(
+                            uncommitted_diff.clone(),
+                            buffer.clone(),
+                            head,
+                            head_changed,
+                            language_changed,
+                            language.clone(),
+                            language_registry.clone(),
+                            cx,
+                        )
+                        .await?,
+                    )
+                }
+            }
+
+            if this.update(cx, |this, _| {
+                this.hunk_staging_operation_count > prev_hunk_staging_operation_count
+            })? {
+                eprintln!("early return");
+                return Ok(());
+            }
+
+            let unstaged_changed_range = if let Some((unstaged_diff, new_unstaged_diff)) =
+                unstaged_diff.as_ref().zip(new_unstaged_diff.clone())
+            {
+                unstaged_diff.update(cx, |diff, cx| {
+                    if language_changed {
+                        diff.language_changed(cx);
+                    }
+                    diff.set_snapshot(new_unstaged_diff, &buffer, None, cx)
+                })?
+            } else {
+                None
+            };
+
+            if let Some((uncommitted_diff, new_uncommitted_diff)) =
+                uncommitted_diff.as_ref().zip(new_uncommitted_diff.clone())
+            {
+                uncommitted_diff.update(cx, |diff, cx| {
+                    if language_changed {
+                        diff.language_changed(cx);
+                    }
+                    diff.set_snapshot(new_uncommitted_diff, &buffer, unstaged_changed_range, cx);
+                })?;
+            }
+
+            if let Some(this) = this.upgrade() {
+                this.update(cx, |this, _| {
+                    this.index_changed = false;
+                    this.head_changed = false;
+                    this.language_changed = false;
+                    for tx in this.diff_updated_futures.drain(..) {
+                        tx.send(()).ok();
+                    }
+                })?;
             }
-            DiffBasesChange::SetEach { index, head } => {
-                self.index_text = index.map(|mut index| {
-                    text::LineEnding::normalize(&mut index);
-                    Arc::new(index)
-                });
-                self.index_changed = true;
-                self.head_text = head.map(|mut head| {
-                    text::LineEnding::normalize(&mut head);
-                    Arc::new(head)
-                });
-                self.head_changed = true;
+
+            Ok(())
+        }));
+
+        rx
+    }
+}
+
+fn make_remote_delegate(
+    this: Entity<GitStore>,
+    project_id: u64,
+    repository_id: RepositoryId,
+    askpass_id: u64,
+    cx: &mut AsyncApp,
+) -> AskPassDelegate {
+    AskPassDelegate::new(cx, move |prompt, tx, cx| {
+        this.update(cx, |this, cx| {
+            let Some((client, _)) = this.downstream_client() else {
+                return;
+            };
+            let response = client.request(proto::AskPassRequest {
+                project_id,
+                repository_id: repository_id.to_proto(),
+                askpass_id,
+                prompt,
+            });
+            cx.spawn(async move |_, _| {
+                tx.send(response.await?.response).ok();
+                anyhow::Ok(())
+            })
+            .detach_and_log_err(cx);
+        })
+        .log_err();
+    })
+}
+
+impl RepositoryId {
+    pub fn to_proto(self) -> u64 {
+        self.0
+    }
+
+    pub fn from_proto(id: u64) -> Self {
+        RepositoryId(id)
+    }
+}
+
+impl RepositorySnapshot {
+    fn empty(id: RepositoryId, work_directory_abs_path: Arc<Path>) -> Self {
+        Self {
+            id,
+            merge_message: None,
+            statuses_by_path: Default::default(),
+            work_directory_abs_path,
+            branch: None,
+            merge_conflicts: Default::default(),
+            merge_head_shas: Default::default(),
+            rebase_state: None,
+            sequencer_command: None,
+            detached_head: None,
+            submodules: Vec::new(),
+            scan_id: 0,
+        }
+    }
+
+    fn initial_update(&self, project_id: u64) -> proto::UpdateRepository {
+        proto::UpdateRepository {
+            branch_summary: self.branch.as_ref().map(branch_to_proto),
+            updated_statuses: self
+                .statuses_by_path
+                .iter()
+                .map(|entry| entry.to_proto())
+                .collect(),
+            removed_statuses: Default::default(),
+            current_merge_conflicts: self
+                .merge_conflicts
+                .iter()
+                .map(|repo_path| repo_path.to_proto())
+                .collect(),
+            project_id,
+            id: self.id.to_proto(),
+            abs_path: self.work_directory_abs_path.to_proto(),
+            entry_ids: vec![self.id.to_proto()],
+            scan_id: self.scan_id,
+            is_last_update: true,
+            rebase_state: self.rebase_state.as_ref().map(rebase_state_to_proto),
+            submodules: self.submodules.iter().map(submodule_to_proto).collect(),
+            sequencer_command: self
+                .sequencer_command
+                .map(|command| sequencer_command_to_proto(command).into()),
+            detached_head: self.detached_head.as_ref().map(detached_head_to_proto),
+        }
+    }
+
+    fn build_update(&self, old: &Self, project_id: u64) -> proto::UpdateRepository {
+        let mut updated_statuses: Vec<proto::StatusEntry> = Vec::new();
+        let mut removed_statuses: Vec<String> = Vec::new();
+
+        let mut new_statuses = self.statuses_by_path.iter().peekable();
+        let mut old_statuses = old.statuses_by_path.iter().peekable();
+
+        let mut current_new_entry = new_statuses.next();
+        let mut current_old_entry = old_statuses.next();
+        loop {
+            match (current_new_entry, current_old_entry) {
+                (Some(new_entry), Some(old_entry)) => {
+                    match new_entry.repo_path.cmp(&old_entry.repo_path) {
+                        Ordering::Less => {
+                            updated_statuses.push(new_entry.to_proto());
+                            current_new_entry = new_statuses.next();
+                        }
+                        Ordering::Equal => {
+                            if new_entry.status != old_entry.status {
+                                updated_statuses.push(new_entry.to_proto());
+                            }
+                            current_old_entry = old_statuses.next();
+                            current_new_entry = new_statuses.next();
+                        }
+                        Ordering::Greater => {
+                            removed_statuses.push(old_entry.repo_path.as_ref().to_proto());
+                            current_old_entry = old_statuses.next();
+                        }
+                    }
+                }
+                (None, Some(old_entry)) => {
+                    removed_statuses.push(old_entry.repo_path.as_ref().to_proto());
+                    current_old_entry = old_statuses.next();
+                }
+                (Some(new_entry), None) => {
+                    updated_statuses.push(new_entry.to_proto());
+                    current_new_entry = new_statuses.next();
+                }
+                (None, None) => break,
             }
         }
-
-        self.recalculate_diffs(buffer, prev_hunk_staging_operation_count, cx)
+
+        proto::UpdateRepository {
+            branch_summary: self.branch.as_ref().map(branch_to_proto),
+            updated_statuses,
+            removed_statuses,
+            current_merge_conflicts: self
+                .merge_conflicts
+                .iter()
+                .map(|path| path.as_ref().to_proto())
+                .collect(),
+            project_id,
+            id: self.id.to_proto(),
+            abs_path: self.work_directory_abs_path.to_proto(),
+            entry_ids: vec![],
+            scan_id: self.scan_id,
+            is_last_update: true,
+            rebase_state: self.rebase_state.as_ref().map(rebase_state_to_proto),
+            submodules: self.submodules.iter().map(submodule_to_proto).collect(),
+            sequencer_command: self
+                .sequencer_command
+                .map(|command| sequencer_command_to_proto(command).into()),
+            detached_head: self.detached_head.as_ref().map(detached_head_to_proto),
+        }
+    }
+
+    pub fn status(&self) -> impl Iterator<Item = StatusEntry> + '_ {
+        self.statuses_by_path.iter().cloned()
+    }
+
+    pub fn status_summary(&self) -> GitSummary {
+        self.statuses_by_path.summary().item_summary
+    }
+
+    pub fn status_for_path(&self, path: &RepoPath) -> Option<StatusEntry> {
+        self.statuses_by_path
+            .get(&PathKey(path.0.clone()), &())
+            .cloned()
+    }
+
+    /// Returns the submodule checked out at the given path, if any.
+    pub fn submodule_for_path(&self, path: &RepoPath) -> Option<&Submodule> {
+        self.submodules
+            .iter()
+            .find(|submodule| &submodule.path == path)
+    }
+
+    pub fn abs_path_to_repo_path(&self, abs_path: &Path) -> Option<RepoPath> {
+        abs_path
+            .strip_prefix(&self.work_directory_abs_path)
+            .map(RepoPath::from)
+            .ok()
+    }
+
+    pub fn has_conflict(&self, repo_path: &RepoPath) -> bool {
+        self.statuses_by_path
+            .get(&PathKey(repo_path.0.clone()), &())
+            .map_or(false, |entry| entry.status.is_conflicted())
+    }
+
+    /// This is the name that will be displayed in the repository selector for this repository.
+    pub fn display_name(&self) -> SharedString {
+        self.work_directory_abs_path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string()
+            .into()
+    }
+}
+
+impl Repository {
+    fn local(
+        id: RepositoryId,
+        work_directory_abs_path: Arc<Path>,
+        dot_git_abs_path: Arc<Path>,
+        project_environment: WeakEntity<ProjectEnvironment>,
+        fs: Arc<dyn Fs>,
+        git_store: WeakEntity<GitStore>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let snapshot = RepositorySnapshot::empty(id, work_directory_abs_path.clone());
+        Repository {
+            git_store,
+            snapshot,
+            commit_message_buffer: None,
+            askpass_delegates: Default::default(),
+            paths_needing_status_update: Default::default(),
+            latest_askpass_id: 0,
+            job_sender: Repository::spawn_local_git_worker(
+                work_directory_abs_path,
+                dot_git_abs_path,
+                project_environment,
+                fs,
+                cx,
+            ),
+        }
     }
 
-    fn recalculate_diffs(
-        &mut self,
-        buffer: text::BufferSnapshot,
-        prev_hunk_staging_operation_count: usize,
+    fn remote(
+        id: RepositoryId,
+        work_directory_abs_path: Arc<Path>,
+        project_id: ProjectId,
+        client: AnyProtoClient,
+        git_store: WeakEntity<GitStore>,
         cx: &mut Context<Self>,
-    ) -> oneshot::Receiver<()> {
-        log::debug!("recalculate diffs");
-        let (tx, rx) = oneshot::channel();
-        self.diff_updated_futures.push(tx);
+    ) -> Self {
+        let snapshot = RepositorySnapshot::empty(id, work_directory_abs_path);
+        Self {
+            snapshot,
+            commit_message_buffer: None,
+            git_store,
+            paths_needing_status_update: Default::default(),
+            job_sender: Self::spawn_remote_git_worker(project_id, client, cx),
+            askpass_delegates: Default::default(),
+            latest_askpass_id: 0,
+        }
+    }
 
-        let language = self.language.clone();
-        let language_registry = self.language_registry.clone();
-        let unstaged_diff = self.unstaged_diff();
-        let uncommitted_diff = self.uncommitted_diff();
-        let head = self.head_text.clone();
-        let index = self.index_text.clone();
-        let index_changed = self.index_changed;
-        let head_changed = self.head_changed;
-        let language_changed = self.language_changed;
-        let index_matches_head = match (self.index_text.as_ref(), self.head_text.as_ref()) {
-            (Some(index), Some(head)) => Arc::ptr_eq(index, head),
-            (None, None) => true,
-            _ => false,
-        };
-        self.recalculate_diff_task = Some(cx.spawn(async move |this, cx| {
-            let mut new_unstaged_diff = None;
-            if let Some(unstaged_diff) = &unstaged_diff {
-                new_unstaged_diff = Some(
-                    BufferDiff::update_diff(
-                        unstaged_diff.clone(),
-                        buffer.clone(),
-                        index,
-                        index_changed,
-                        language_changed,
-                        language.clone(),
-                        language_registry.clone(),
-                        cx,
-                    )
-                    .await?,
-                );
-            }
+    pub fn git_store(&self) -> Option<Entity<GitStore>> {
+        self.git_store.upgrade()
+    }
 
-            let mut new_uncommitted_diff = None;
-            if let Some(uncommitted_diff) = &uncommitted_diff {
-                new_uncommitted_diff = if index_matches_head {
-                    new_unstaged_diff.clone()
-                } else {
-                    Some(
-                        BufferDiff::update_diff(
-                            uncommitted_diff.clone(),
-                            buffer.clone(),
-                            head,
-                            head_changed,
-                            language_changed,
-                            language.clone(),
-                            language_registry.clone(),
-                            cx,
-                        )
-                        .await?,
-                    )
-                }
-            }
+    pub fn send_job<F, Fut, R>(&self, job: F) -> oneshot::Receiver<R>
+    where
+        F: FnOnce(RepositoryState, AsyncApp) -> Fut + 'static,
+        Fut: Future<Output = R> + 'static,
+        R: Send + 'static,
+    {
+        self.send_keyed_job(None, job)
+    }
 
-            if this.update(cx, |this, _| {
-                this.hunk_staging_operation_count > prev_hunk_staging_operation_count
-            })? {
-                eprintln!("early return");
-                return Ok(());
-            }
+    fn send_keyed_job<F, Fut, R>(&self, key: Option<GitJobKey>, job: F) -> oneshot::Receiver<R>
+    where
+        F: FnOnce(RepositoryState, AsyncApp) -> Fut + 'static,
+        Fut: Future<Output = R> + 'static,
+        R: Send + 'static,
+    {
+        let (result_tx, result_rx) = futures::channel::oneshot::channel();
+        self.job_sender
+            .unbounded_send(GitJob {
+                key,
+                job: Box::new(|state, cx: &mut AsyncApp| {
+                    let job = job(state, cx.clone());
+                    cx.spawn(async move |_| {
+                        let result = job.await;
+                        result_tx.send(result).ok();
+                    })
+                }),
+            })
+            .ok();
+        result_rx
+    }
 
-            let unstaged_changed_range = if let Some((unstaged_diff, new_unstaged_diff)) =
-                unstaged_diff.as_ref().zip(new_unstaged_diff.clone())
-            {
-                unstaged_diff.update(cx, |diff, cx| {
-                    if language_changed {
-                        diff.language_changed(cx);
-                    }
-                    diff.set_snapshot(new_unstaged_diff, &buffer, None, cx)
-                })?
-            } else {
-                None
+    pub fn set_as_active_repository(&self, cx: &mut Context<Self>) {
+        let Some(git_store) = self.git_store.upgrade() else {
+            return;
+        };
+        let entity = cx.entity();
+        git_store.update(cx, |git_store, cx| {
+            let Some((&id, _)) = git_store
+                .repositories
+                .iter()
+                .find(|(_, handle)| *handle == &entity)
+            else {
+                return;
             };
+            git_store.active_repo_id = Some(id);
+            cx.emit(GitStoreEvent::ActiveRepositoryChanged(Some(id)));
+        });
+    }
 
-            if let Some((uncommitted_diff, new_uncommitted_diff)) =
-                uncommitted_diff.as_ref().zip(new_uncommitted_diff.clone())
-            {
-                uncommitted_diff.update(cx, |diff, cx| {
-                    if language_changed {
-                        diff.language_changed(cx);
+    pub fn cached_status(&self) -> impl '_ + Iterator<Item = StatusEntry> {
+        self.snapshot.status()
+    }
+
+    pub fn repo_path_to_project_path(&self, path: &RepoPath, cx: &App) -> Option<ProjectPath> {
+        let git_store = self.git_store.upgrade()?;
+        let worktree_store = git_store.read(cx).worktree_store.read(cx);
+        let abs_path = self.snapshot.work_directory_abs_path.join(&path.0);
+        let (worktree, relative_path) = worktree_store.find_worktree(abs_path, cx)?;
+        Some(ProjectPath {
+            worktree_id: worktree.read(cx).id(),
+            path: relative_path.into(),
+        })
+    }
+
+    pub fn project_path_to_repo_path(&self, path: &ProjectPath, cx: &App) -> Option<RepoPath> {
+        let git_store = self.git_store.upgrade()?;
+        let worktree_store = git_store.read(cx).worktree_store.read(cx);
+        let abs_path = worktree_store.absolutize(path, cx)?;
+        self.snapshot.abs_path_to_repo_path(&abs_path)
+    }
+
+    pub fn contains_sub_repo(&self, other: &Entity<Self>, cx: &App) -> bool {
+        other
+            .read(cx)
+            .snapshot
+            .work_directory_abs_path
+            .starts_with(&self.snapshot.work_directory_abs_path)
+    }
+
+    pub fn open_commit_buffer(
+        &mut self,
+        languages: Option<Arc<LanguageRegistry>>,
+        buffer_store: Entity<BufferStore>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Entity<Buffer>>> {
+        let id = self.id;
+        if let Some(buffer) = self.commit_message_buffer.clone() {
+            return Task::ready(Ok(buffer));
+        }
+        let this = cx.weak_entity();
+
+        let rx = self.send_job(move |state, mut cx| async move {
+            let Some(this) = this.upgrade() else {
+                bail!("git store was dropped");
+            };
+            match state {
+                RepositoryState::Local { .. } => {
+                    this.update(&mut cx, |_, cx| {
+                        Self::open_local_commit_buffer(languages, buffer_store, cx)
+                    })?
+                    .await
+                }
+                RepositoryState::Remote { project_id, client } => {
+                    let request = client.request(proto::OpenCommitMessageBuffer {
+                        project_id: project_id.0,
+                        repository_id: id.to_proto(),
+                    });
+                    let response = request.await.context("requesting to open commit buffer")?;
+                    let buffer_id = BufferId::new(response.buffer_id)?;
+                    let buffer = buffer_store
+                        .update(&mut cx, |buffer_store, cx| {
+                            buffer_store.wait_for_remote_buffer(buffer_id, cx)
+                        })?
+                        .await?;
+                    if let Some(language_registry) = languages {
+                        let git_commit_language =
+                            language_registry.language_for_name("Git Commit").await?;
+                        buffer.update(&mut cx, |buffer, cx| {
+                            buffer.set_language(Some(git_commit_language), cx);
+                        })?;
                     }
-                    diff.set_snapshot(new_uncommitted_diff, &buffer, unstaged_changed_range, cx);
-                })?;
+                    this.update(&mut cx, |this, _| {
+                        this.commit_message_buffer = Some(buffer.clone());
+                    })?;
+                    Ok(buffer)
+                }
             }
+        });
 
-            if let Some(this) = this.upgrade() {
-                this.update(cx, |this, _| {
-                    this.index_changed = false;
-                    this.head_changed = false;
-                    this.language_changed = false;
-                    for tx in this.diff_updated_futures.drain(..) {
-                        tx.send(()).ok();
-                    }
+        cx.spawn(|_, _: &mut AsyncApp| async move { rx.await? })
+    }
+
+    fn open_local_commit_buffer(
+        language_registry: Option<Arc<LanguageRegistry>>,
+        buffer_store: Entity<BufferStore>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Entity<Buffer>>> {
+        cx.spawn(async move |repository, cx| {
+            let buffer = buffer_store
+                .update(cx, |buffer_store, cx| buffer_store.create_buffer(cx))?
+                .await?;
+
+            if let Some(language_registry) = language_registry {
+                let git_commit_language = language_registry.language_for_name("Git Commit").await?;
+                buffer.update(cx, |buffer, cx| {
+                    buffer.set_language(Some(git_commit_language), cx);
                 })?;
             }
 
-            Ok(())
-        }));
-
-        rx
+            repository.update(cx, |repository, _| {
+                repository.commit_message_buffer = Some(buffer.clone());
+            })?;
+            Ok(buffer)
+        })
     }
-}
 
-fn make_remote_delegate(
-    this: Entity<GitStore>,
-    project_id: u64,
-    repository_id: RepositoryId,
-    askpass_id: u64,
-    cx: &mut AsyncApp,
-) -> AskPassDelegate {
-    AskPassDelegate::new(cx, move |prompt, tx, cx| {
-        this.update(cx, |this, cx| {
-            let Some((client, _)) = this.downstream_client() else {
-                return;
-            };
-            let response = client.request(proto::AskPassRequest {
-                project_id,
-                repository_id: repository_id.to_proto(),
-                askpass_id,
-                prompt,
-            });
-            cx.spawn(async move |_, _| {
-                tx.send(response.await?.response).ok();
-                anyhow::Ok(())
-            })
-            .detach_and_log_err(cx);
-        })
-        .log_err();
-    })
-}
+    pub fn checkout_files(
+        &self,
+        commit: &str,
+        paths: Vec<RepoPath>,
+        _cx: &mut App,
+    ) -> oneshot::Receiver<Result<()>> {
+        let commit = commit.to_string();
+        let id = self.id;
 
-impl RepositoryId {
-    pub fn to_proto(self) -> u64 {
-        self.0
-    }
+        self.send_job(move |git_repo, _| async move {
+            match git_repo {
+                RepositoryState::Local {
+                    backend,
+                    environment,
+                    ..
+                } => {
+                    backend
+                        .checkout_files(commit, paths, environment.clone())
+                        .await
+                }
+                RepositoryState::Remote { project_id, client } => {
+                    client
+                        .request(proto::GitCheckoutFiles {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            commit,
+                            paths: paths
+                                .into_iter()
+                                .map(|p| p.to_string_lossy().to_string())
+                                .collect(),
+                        })
+                        .await?;
 
-    pub fn from_proto(id: u64) -> Self {
-        RepositoryId(id)
+                    Ok(())
+                }
+            }
+        })
     }
-}
 
-impl RepositorySnapshot {
-    fn empty(id: RepositoryId, work_directory_abs_path: Arc<Path>) -> Self {
-        Self {
-            id,
-            merge_message: None,
-            statuses_by_path: Default::default(),
-            work_directory_abs_path,
-            branch: None,
-            merge_conflicts: Default::default(),
-            merge_head_shas: Default::default(),
-            scan_id: 0,
-        }
-    }
+    pub fn reset(
+        &self,
+        commit: String,
+        reset_mode: ResetMode,
+        _cx: &mut App,
+    ) -> oneshot::Receiver<Result<()>> {
+        let commit = commit.to_string();
+        let id = self.id;
 
-    fn initial_update(&self, project_id: u64) -> proto::UpdateRepository {
-        proto::UpdateRepository {
-            branch_summary: self.branch.as_ref().map(branch_to_proto),
-            updated_statuses: self
-                .statuses_by_path
-                .iter()
-                .map(|entry| entry.to_proto())
-                .collect(),
-            removed_statuses: Default::default(),
-            current_merge_conflicts: self
-                .merge_conflicts
-                .iter()
-                .map(|repo_path| repo_path.to_proto())
-                .collect(),
-            project_id,
-            id: self.id.to_proto(),
-            abs_path: self.work_directory_abs_path.to_proto(),
-            entry_ids: vec![self.id.to_proto()],
-            scan_id: self.scan_id,
-            is_last_update: true,
-        }
+        self.send_job(move |git_repo, _| async move {
+            match git_repo {
+                RepositoryState::Local {
+                    backend,
+                    environment,
+                    ..
+                } => backend.reset(commit, reset_mode, environment).await,
+                RepositoryState::Remote { project_id, client } => {
+                    client
+                        .request(proto::GitReset {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            commit,
+                            mode: match reset_mode {
+                                ResetMode::Soft => git_reset::ResetMode::Soft.into(),
+                                ResetMode::Mixed => git_reset::ResetMode::Mixed.into(),
+                            },
+                        })
+                        .await?;
+
+                    Ok(())
+                }
+            }
+        })
     }
 
-    fn build_update(&self, old: &Self, project_id: u64) -> proto::UpdateRepository {
-        let mut updated_statuses: Vec<proto::StatusEntry> = Vec::new();
-        let mut removed_statuses: Vec<String> = Vec::new();
+    pub fn show(&self, commit: String) -> oneshot::Receiver<Result<CommitDetails>> {
+        let id = self.id;
+        self.send_job(move |git_repo, _cx| async move {
+            match git_repo {
+                RepositoryState::Local { backend, .. } => backend.show(commit).await,
+                RepositoryState::Remote { project_id, client } => {
+                    let resp = client
+                        .request(proto::GitShow {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            commit,
+                        })
+                        .await?;
 
-        let mut new_statuses = self.statuses_by_path.iter().peekable();
-        let mut old_statuses = old.statuses_by_path.iter().peekable();
+                    Ok(CommitDetails {
+                        sha: resp.sha.into(),
+                        message: resp.message.into(),
+                        commit_timestamp: resp.commit_timestamp,
+                        author_email: resp.author_email.into(),
+                        author_name: resp.author_name.into(),
+                    })
+                }
+            }
+        })
+    }
 
-        let mut current_new_entry = new_statuses.next();
-        let mut current_old_entry = old_statuses.next();
-        loop {
-            match (current_new_entry, current_old_entry) {
-                (Some(new_entry), Some(old_entry)) => {
-                    match new_entry.repo_path.cmp(&old_entry.repo_path) {
-                        Ordering::Less => {
-                            updated_statuses.push(new_entry.to_proto());
-                            current_new_entry = new_statuses.next();
-                        }
-                        Ordering::Equal => {
-                            if new_entry.status != old_entry.status {
-                                updated_statuses.push(new_entry.to_proto());
-                            }
-                            current_old_entry = old_statuses.next();
-                            current_new_entry = new_statuses.next();
-                        }
-                        Ordering::Greater => {
-                            removed_statuses.push(old_entry.repo_path.as_ref().to_proto());
-                            current_old_entry = old_statuses.next();
-                        }
-                    }
+    pub fn load_commit_diff(&self, commit: String) -> oneshot::Receiver<Result<CommitDiff>> {
+        let id = self.id;
+        self.send_job(move |git_repo, cx| async move {
+            match git_repo {
+                RepositoryState::Local { backend, .. } => backend.load_commit(commit, cx).await,
+                RepositoryState::Remote {
+                    client, project_id, ..
+                } => {
+                    let response = client
+                        .request(proto::LoadCommitDiff {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            commit,
+                        })
+                        .await?;
+                    Ok(proto_to_commit_diff(response))
                 }
-                (None, Some(old_entry)) => {
-                    removed_statuses.push(old_entry.repo_path.as_ref().to_proto());
-                    current_old_entry = old_statuses.next();
+            }
+        })
+    }
+
+    pub fn stash_push(
+        &self,
+        message: Option<String>,
+        mode: StashMode,
+    ) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(move |git_repo, _| async move {
+            match git_repo {
+                RepositoryState::Local {
+                    backend,
+                    environment,
+                    ..
+                } => backend.stash_push(message, mode, environment).await,
+                RepositoryState::Remote { project_id, client } => {
+                    let (mode, paths) = match mode {
+                        StashMode::All => (git_stash_push::StashMode::All, Vec::new()),
+                        StashMode::Staged => (git_stash_push::StashMode::Staged, Vec::new()),
+                        StashMode::Paths(paths) => (
+                            git_stash_push::StashMode::Paths,
+                            paths
+                                .into_iter()
+                                .map(|p| p.to_string_lossy().to_string())
+                                .collect(),
+                        ),
+                    };
+                    client
+                        .request(proto::GitStashPush {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            message,
+                            mode: mode.into(),
+                            paths,
+                        })
+                        .await?;
+
+                    Ok(())
                 }
-                (Some(new_entry), None) => {
-                    updated_statuses.push(new_entry.to_proto());
-                    current_new_entry = new_statuses.next();
+            }
+        })
+    }
+
+    pub fn stash_list(&self) -> oneshot::Receiver<Result<Vec<StashEntry>>> {
+        let id = self.id;
+        self.send_job(move |git_repo, _| async move {
+            match git_repo {
+                RepositoryState::Local { backend, .. } => backend.stash_list().await,
+                RepositoryState::Remote { project_id, client } => {
+                    let response = client
+                        .request(proto::GitStashList {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                        })
+                        .await?;
+
+                    Ok(response.entries.iter().map(proto_to_stash_entry).collect())
                 }
-                (None, None) => break,
             }
-        }
-
-        proto::UpdateRepository {
-            branch_summary: self.branch.as_ref().map(branch_to_proto),
-            updated_statuses,
-            removed_statuses,
-            current_merge_conflicts: self
-                .merge_conflicts
-                .iter()
-                .map(|path| path.as_ref().to_proto())
-                .collect(),
-            project_id,
-            id: self.id.to_proto(),
-            abs_path: self.work_directory_abs_path.to_proto(),
-            entry_ids: vec![],
-            scan_id: self.scan_id,
-            is_last_update: true,
-        }
+        })
     }
 
-    pub fn status(&self) -> impl Iterator<Item = StatusEntry> + '_ {
-        self.statuses_by_path.iter().cloned()
-    }
+    pub fn stash_apply(&self, index: usize) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(move |git_repo, _| async move {
+            match git_repo {
+                RepositoryState::Local {
+                    backend,
+                    environment,
+                    ..
+                } => backend.stash_apply(index, environment).await,
+                RepositoryState::Remote { project_id, client } => {
+                    client
+                        .request(proto::GitStashApply {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            stash_index: index as u64,
+                        })
+                        .await?;
 
-    pub fn status_summary(&self) -> GitSummary {
-        self.statuses_by_path.summary().item_summary
+                    Ok(())
+                }
+            }
+        })
     }
 
-    pub fn status_for_path(&self, path: &RepoPath) -> Option<StatusEntry> {
-        self.statuses_by_path
-            .get(&PathKey(path.0.clone()), &())
-            .cloned()
-    }
+    pub fn stash_pop(&self, index: usize) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(move |git_repo, _| async move {
+            match git_repo {
+                RepositoryState::Local {
+                    backend,
+                    environment,
+                    ..
+                } => backend.stash_pop(index, environment).await,
+                RepositoryState::Remote { project_id, client } => {
+                    client
+                        .request(proto::GitStashPop {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            stash_index: index as u64,
+                        })
+                        .await?;
 
-    pub fn abs_path_to_repo_path(&self, abs_path: &Path) -> Option<RepoPath> {
-        abs_path
-            .strip_prefix(&self.work_directory_abs_path)
-            .map(RepoPath::from)
-            .ok()
+                    Ok(())
+                }
+            }
+        })
     }
 
-    pub fn has_conflict(&self, repo_path: &RepoPath) -> bool {
-        self.statuses_by_path
-            .get(&PathKey(repo_path.0.clone()), &())
-            .map_or(false, |entry| entry.status.is_conflicted())
-    }
+    pub fn stash_drop(&self, index: usize) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(move |git_repo, _| async move {
+            match git_repo {
+                RepositoryState::Local {
+                    backend,
+                    environment,
+                    ..
+                } => backend.stash_drop(index, environment).await,
+                RepositoryState::Remote { project_id, client } => {
+                    client
+                        .request(proto::GitStashDrop {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            stash_index: index as u64,
+                        })
+                        .await?;
 
-    /// This is the name that will be displayed in the repository selector for this repository.
-    pub fn display_name(&self) -> SharedString {
-        self.work_directory_abs_path
-            .file_name()
-            .unwrap_or_default()
-            .to_string_lossy()
-            .to_string()
-            .into()
+                    Ok(())
+                }
+            }
+        })
     }
-}
 
-impl Repository {
-    fn local(
-        id: RepositoryId,
-        work_directory_abs_path: Arc<Path>,
-        dot_git_abs_path: Arc<Path>,
-        project_environment: WeakEntity<ProjectEnvironment>,
-        fs: Arc<dyn Fs>,
-        git_store: WeakEntity<GitStore>,
-        cx: &mut Context<Self>,
-    ) -> Self {
-        let snapshot = RepositorySnapshot::empty(id, work_directory_abs_path.clone());
-        Repository {
-            git_store,
-            snapshot,
-            commit_message_buffer: None,
-            askpass_delegates: Default::default(),
-            paths_needing_status_update: Default::default(),
-            latest_askpass_id: 0,
-            job_sender: Repository::spawn_local_git_worker(
-                work_directory_abs_path,
-                dot_git_abs_path,
-                project_environment,
-                fs,
-                cx,
-            ),
-        }
+    pub fn stash_show(&self, index: usize) -> oneshot::Receiver<Result<CommitDiff>> {
+        let id = self.id;
+        self.send_job(move |git_repo, cx| async move {
+            match git_repo {
+                RepositoryState::Local { backend, .. } => backend.stash_show(index, cx).await,
+                RepositoryState::Remote { project_id, client } => {
+                    let response = client
+                        .request(proto::GitStashShow {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            stash_index: index as u64,
+                        })
+                        .await?;
+                    Ok(proto_to_commit_diff(response))
+                }
+            }
+        })
     }
 
-    fn remote(
-        id: RepositoryId,
-        work_directory_abs_path: Arc<Path>,
-        project_id: ProjectId,
-        client: AnyProtoClient,
-        git_store: WeakEntity<GitStore>,
-        cx: &mut Context<Self>,
-    ) -> Self {
-        let snapshot = RepositorySnapshot::empty(id, work_directory_abs_path);
-        Self {
-            snapshot,
-            commit_message_buffer: None,
-            git_store,
-            paths_needing_status_update: Default::default(),
-            job_sender: Self::spawn_remote_git_worker(project_id, client, cx),
-            askpass_delegates: Default::default(),
-            latest_askpass_id: 0,
-        }
-    }
+    pub fn log(
+        &self,
+        filter: LogFilter,
+        skip: usize,
+        limit: usize,
+    ) -> oneshot::Receiver<Result<Vec<LogEntry>>> {
+        let id = self.id;
+        self.send_job(move |git_repo, _| async move {
+            match git_repo {
+                RepositoryState::Local { backend, .. } => backend.log(filter, skip, limit).await,
+                RepositoryState::Remote { project_id, client } => {
+                    let filter = match filter {
+                        LogFilter::Head => None,
+                        LogFilter::Path { path, follow } => {
+                            Some(proto::git_log::Filter::Path(proto::git_log::LogPath {
+                                path: path.to_string_lossy().to_string(),
+                                follow,
+                            }))
+                        }
+                        LogFilter::Lines { path, start, end } => {
+                            Some(proto::git_log::Filter::Lines(proto::git_log::LogLines {
+                                path: path.to_string_lossy().to_string(),
+                                start,
+                                end,
+                            }))
+                        }
+                    };
+                    let response = client
+                        .request(proto::GitLog {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            skip: skip as u64,
+                            limit: limit as u64,
+                            filter,
+                        })
+                        .await?;
 
-    pub fn git_store(&self) -> Option<Entity<GitStore>> {
-        self.git_store.upgrade()
+                    Ok(response.entries.iter().map(proto_to_log_entry).collect())
+                }
+            }
+        })
     }
 
-    pub fn send_job<F, Fut, R>(&self, job: F) -> oneshot::Receiver<R>
-    where
-        F: FnOnce(RepositoryState, AsyncApp) -> Fut + 'static,
-        Fut: Future<Output = R> + 'static,
-        R: Send + 'static,
-    {
-        self.send_keyed_job(None, job)
-    }
+    pub fn rebase_interactive(
+        &self,
+        base: Option<String>,
+        entries: Vec<RebaseTodoEntry>,
+    ) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(move |git_repo, _| async move {
+            match git_repo {
+                RepositoryState::Local {
+                    backend,
+                    environment,
+                    ..
+                } => backend.rebase_interactive(base, entries, environment).await,
+                RepositoryState::Remote { project_id, client } => {
+                    client
+                        .request(proto::GitRebase {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            base,
+                            entries: entries.iter().map(rebase_todo_entry_to_proto).collect(),
+                        })
+                        .await?;
 
-    fn send_keyed_job<F, Fut, R>(&self, key: Option<GitJobKey>, job: F) -> oneshot::Receiver<R>
-    where
-        F: FnOnce(RepositoryState, AsyncApp) -> Fut + 'static,
-        Fut: Future<Output = R> + 'static,
-        R: Send + 'static,
-    {
-        let (result_tx, result_rx) = futures::channel::oneshot::channel();
-        self.job_sender
-            .unbounded_send(GitJob {
-                key,
-                job: Box::new(|state, cx: &mut AsyncApp| {
-                    let job = job(state, cx.clone());
-                    cx.spawn(async move |_| {
-                        let result = job.await;
-                        result_tx.send(result).ok();
-                    })
-                }),
-            })
-            .ok();
-        result_rx
+                    Ok(())
+                }
+            }
+        })
     }
 
-    pub fn set_as_active_repository(&self, cx: &mut Context<Self>) {
-        let Some(git_store) = self.git_store.upgrade() else {
-            return;
-        };
-        let entity = cx.entity();
-        git_store.update(cx, |git_store, cx| {
-            let Some((&id, _)) = git_store
-                .repositories
-                .iter()
-                .find(|(_, handle)| *handle == &entity)
-            else {
-                return;
-            };
-            git_store.active_repo_id = Some(id);
-            cx.emit(GitStoreEvent::ActiveRepositoryChanged(Some(id)));
-        });
+    pub fn rebase_operation(&self, operation: RebaseOperation) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(move |git_repo, _| async move {
+            match git_repo {
+                RepositoryState::Local {
+                    backend,
+                    environment,
+                    ..
+                } => backend.rebase_operation(operation, environment).await,
+                RepositoryState::Remote { project_id, client } => {
+                    client
+                        .request(proto::GitRebaseOperation {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            operation: rebase_operation_to_proto(operation).into(),
+                        })
+                        .await?;
+
+                    Ok(())
+                }
+            }
+        })
     }
 
-    pub fn cached_status(&self) -> impl '_ + Iterator<Item = StatusEntry> {
-        self.snapshot.status()
-    }
+    pub fn cherry_pick(
+        &self,
+        commits: Vec<String>,
+        no_commit: bool,
+    ) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(move |git_repo, _| async move {
+            match git_repo {
+                RepositoryState::Local {
+                    backend,
+                    environment,
+                    ..
+                } => backend.cherry_pick(commits, no_commit, environment).await,
+                RepositoryState::Remote { project_id, client } => {
+                    client
+                        .request(proto::GitCherryPick {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            commits,
+                            no_commit,
+                        })
+                        .await?;
 
-    pub fn repo_path_to_project_path(&self, path: &RepoPath, cx: &App) -> Option<ProjectPath> {
-        let git_store = self.git_store.upgrade()?;
-        let worktree_store = git_store.read(cx).worktree_store.read(cx);
-        let abs_path = self.snapshot.work_directory_abs_path.join(&path.0);
-        let (worktree, relative_path) = worktree_store.find_worktree(abs_path, cx)?;
-        Some(ProjectPath {
-            worktree_id: worktree.read(cx).id(),
-            path: relative_path.into(),
+                    Ok(())
+                }
+            }
         })
     }
 
-    pub fn project_path_to_repo_path(&self, path: &ProjectPath, cx: &App) -> Option<RepoPath> {
-        let git_store = self.git_store.upgrade()?;
-        let worktree_store = git_store.read(cx).worktree_store.read(cx);
-        let abs_path = worktree_store.absolutize(path, cx)?;
-        self.snapshot.abs_path_to_repo_path(&abs_path)
-    }
+    pub fn revert(&self, commits: Vec<String>, no_commit: bool) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(move |git_repo, _| async move {
+            match git_repo {
+                RepositoryState::Local {
+                    backend,
+                    environment,
+                    ..
+                } => backend.revert(commits, no_commit, environment).await,
+                RepositoryState::Remote { project_id, client } => {
+                    client
+                        .request(proto::GitRevert {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            commits,
+                            no_commit,
+                        })
+                        .await?;
 
-    pub fn contains_sub_repo(&self, other: &Entity<Self>, cx: &App) -> bool {
-        other
-            .read(cx)
-            .snapshot
-            .work_directory_abs_path
-            .starts_with(&self.snapshot.work_directory_abs_path)
+                    Ok(())
+                }
+            }
+        })
     }
 
-    pub fn open_commit_buffer(
-        &mut self,
-        languages: Option<Arc<LanguageRegistry>>,
-        buffer_store: Entity<BufferStore>,
-        cx: &mut Context<Self>,
-    ) -> Task<Result<Entity<Buffer>>> {
+    pub fn sequencer_operation(
+        &self,
+        command: SequencerCommand,
+        operation: RebaseOperation,
+    ) -> oneshot::Receiver<Result<()>> {
         let id = self.id;
-        if let Some(buffer) = self.commit_message_buffer.clone() {
-            return Task::ready(Ok(buffer));
-        }
-        let this = cx.weak_entity();
+        self.send_job(move |git_repo, _| async move {
+            match git_repo {
+                RepositoryState::Local {
+                    backend,
+                    environment,
+                    ..
+                } => {
+                    backend
+                        .sequencer_operation(command, operation, environment)
+                        .await
+                }
+                RepositoryState::Remote { project_id, client } => {
+                    client
+                        .request(proto::GitSequencerOperation {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            command: sequencer_command_to_proto(command).into(),
+                            operation: rebase_operation_to_proto(operation).into(),
+                        })
+                        .await?;
 
-        let rx = self.send_job(move |state, mut cx| async move {
-            let Some(this) = this.upgrade() else {
-                bail!("git store was dropped");
-            };
-            match state {
-                RepositoryState::Local { .. } => {
-                    this.update(&mut cx, |_, cx| {
-                        Self::open_local_commit_buffer(languages, buffer_store, cx)
-                    })?
-                    .await
+                    Ok(())
                 }
+            }
+        })
+    }
+
+    pub fn tags(&self) -> oneshot::Receiver<Result<Vec<Tag>>> {
+        let id = self.id;
+        self.send_job(move |git_repo, _| async move {
+            match git_repo {
+                RepositoryState::Local { backend, .. } => backend.tags().await,
                 RepositoryState::Remote { project_id, client } => {
-                    let request = client.request(proto::OpenCommitMessageBuffer {
-                        project_id: project_id.0,
-                        repository_id: id.to_proto(),
-                    });
-                    let response = request.await.context("requesting to open commit buffer")?;
-                    let buffer_id = BufferId::new(response.buffer_id)?;
-                    let buffer = buffer_store
-                        .update(&mut cx, |buffer_store, cx| {
-                            buffer_store.wait_for_remote_buffer(buffer_id, cx)
-                        })?
+                    let response = client
+                        .request(proto::GitTags {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                        })
                         .await?;
-                    if let Some(language_registry) = languages {
-                        let git_commit_language =
-                            language_registry.language_for_name("Git Commit").await?;
-                        buffer.update(&mut cx, |buffer, cx| {
-                            buffer.set_language(Some(git_commit_language), cx);
-                        })?;
-                    }
-                    this.update(&mut cx, |this, _| {
-                        this.commit_message_buffer = Some(buffer.clone());
-                    })?;
-                    Ok(buffer)
+
+                    Ok(response.tags.iter().map(proto_to_tag).collect())
                 }
             }
-        });
+        })
+    }
 
-        cx.spawn(|_, _: &mut AsyncApp| async move { rx.await? })
+    pub fn create_tag(
+        &self,
+        name: String,
+        target: Option<String>,
+        message: Option<String>,
+    ) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(move |git_repo, _| async move {
+            match git_repo {
+                RepositoryState::Local {
+                    backend,
+                    environment,
+                    ..
+                } => backend.create_tag(name, target, message, environment).await,
+                RepositoryState::Remote { project_id, client } => {
+                    client
+                        .request(proto::GitCreateTag {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            name,
+                            target,
+                            message,
+                        })
+                        .await?;
+
+                    Ok(())
+                }
+            }
+        })
     }
 
-    fn open_local_commit_buffer(
-        language_registry: Option<Arc<LanguageRegistry>>,
-        buffer_store: Entity<BufferStore>,
-        cx: &mut Context<Self>,
-    ) -> Task<Result<Entity<Buffer>>> {
-        cx.spawn(async move |repository, cx| {
-            let buffer = buffer_store
-                .update(cx, |buffer_store, cx| buffer_store.create_buffer(cx))?
-                .await?;
+    pub fn delete_tag(&self, name: String) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(move |git_repo, _| async move {
+            match git_repo {
+                RepositoryState::Local {
+                    backend,
+                    environment,
+                    ..
+                } => backend.delete_tag(name, environment).await,
+                RepositoryState::Remote { project_id, client } => {
+                    client
+                        .request(proto::GitDeleteTag {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            name,
+                        })
+                        .await?;
 
-            if let Some(language_registry) = language_registry {
-                let git_commit_language = language_registry.language_for_name("Git Commit").await?;
-                buffer.update(cx, |buffer, cx| {
-                    buffer.set_language(Some(git_commit_language), cx);
-                })?;
+                    Ok(())
+                }
             }
+        })
+    }
 
-            repository.update(cx, |repository, _| {
-                repository.commit_message_buffer = Some(buffer.clone());
-            })?;
-            Ok(buffer)
+    pub fn checkout_tag(&self, name: String) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(move |git_repo, _| async move {
+            match git_repo {
+                RepositoryState::Local {
+                    backend,
+                    environment,
+                    ..
+                } => backend.checkout_tag(name, environment).await,
+                RepositoryState::Remote { project_id, client } => {
+                    client
+                        .request(proto::GitCheckoutTag {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            name,
+                        })
+                        .await?;
+
+                    Ok(())
+                }
+            }
         })
     }
 
-    pub fn checkout_files(
-        &self,
-        commit: &str,
-        paths: Vec<RepoPath>,
+    pub fn push_tag(
+        &mut self,
+        name: String,
+        remote_name: String,
+        askpass: AskPassDelegate,
         _cx: &mut App,
-    ) -> oneshot::Receiver<Result<()>> {
-        let commit = commit.to_string();
+    ) -> oneshot::Receiver<Result<RemoteCommandOutput>> {
+        let askpass_delegates = self.askpass_delegates.clone();
+        let askpass_id = util::post_inc(&mut self.latest_askpass_id);
         let id = self.id;
 
-        self.send_job(move |git_repo, _| async move {
+        self.send_job(move |git_repo, cx| async move {
             match git_repo {
                 RepositoryState::Local {
                     backend,
@@ -2823,54 +3963,81 @@ impl Repository {
                     ..
                 } => {
                     backend
-                        .checkout_files(commit, paths, environment.clone())
+                        .push_tag(name, remote_name, askpass, environment, cx)
                         .await
                 }
                 RepositoryState::Remote { project_id, client } => {
-                    client
-                        .request(proto::GitCheckoutFiles {
+                    askpass_delegates.lock().insert(askpass_id, askpass);
+                    let _defer = util::defer(|| {
+                        let askpass_delegate = askpass_delegates.lock().remove(&askpass_id);
+                        debug_assert!(askpass_delegate.is_some());
+                    });
+
+                    let response = client
+                        .request(proto::GitPushTag {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            name,
+                            remote_name,
+                            askpass_id,
+                        })
+                        .await
+                        .context("sending tag push request")?;
+
+                    Ok(RemoteCommandOutput {
+                        stdout: response.stdout,
+                        stderr: response.stderr,
+                    })
+                }
+            }
+        })
+    }
+
+    pub fn worktrees(&self) -> oneshot::Receiver<Result<Vec<GitWorktree>>> {
+        let id = self.id;
+        self.send_job(move |git_repo, _| async move {
+            match git_repo {
+                RepositoryState::Local { backend, .. } => backend.worktrees().await,
+                RepositoryState::Remote { project_id, client } => {
+                    let response = client
+                        .request(proto::GitWorktrees {
                             project_id: project_id.0,
                             repository_id: id.to_proto(),
-                            commit,
-                            paths: paths
-                                .into_iter()
-                                .map(|p| p.to_string_lossy().to_string())
-                                .collect(),
                         })
                         .await?;
 
-                    Ok(())
+                    Ok(response.worktrees.iter().map(proto_to_worktree).collect())
                 }
             }
         })
     }
 
-    pub fn reset(
+    pub fn create_worktree(
         &self,
-        commit: String,
-        reset_mode: ResetMode,
-        _cx: &mut App,
+        path: PathBuf,
+        branch: String,
+        create_branch: bool,
     ) -> oneshot::Receiver<Result<()>> {
-        let commit = commit.to_string();
         let id = self.id;
-
         self.send_job(move |git_repo, _| async move {
             match git_repo {
                 RepositoryState::Local {
                     backend,
                     environment,
                     ..
-                } => backend.reset(commit, reset_mode, environment).await,
+                } => {
+                    backend
+                        .create_worktree(path, branch, create_branch, environment)
+                        .await
+                }
                 RepositoryState::Remote { project_id, client } => {
                     client
-                        .request(proto::GitReset {
+                        .request(proto::GitCreateWorktree {
                             project_id: project_id.0,
                             repository_id: id.to_proto(),
-                            commit,
-                            mode: match reset_mode {
-                                ResetMode::Soft => git_reset::ResetMode::Soft.into(),
-                                ResetMode::Mixed => git_reset::ResetMode::Mixed.into(),
-                            },
+                            path: path.to_proto(),
+                            branch,
+                            create_branch,
                         })
                         .await?;
 
@@ -2880,58 +4047,26 @@ impl Repository {
         })
     }
 
-    pub fn show(&self, commit: String) -> oneshot::Receiver<Result<CommitDetails>> {
+    pub fn remove_worktree(&self, path: PathBuf, force: bool) -> oneshot::Receiver<Result<()>> {
         let id = self.id;
-        self.send_job(move |git_repo, _cx| async move {
+        self.send_job(move |git_repo, _| async move {
             match git_repo {
-                RepositoryState::Local { backend, .. } => backend.show(commit).await,
+                RepositoryState::Local {
+                    backend,
+                    environment,
+                    ..
+                } => backend.remove_worktree(path, force, environment).await,
                 RepositoryState::Remote { project_id, client } => {
-                    let resp = client
-                        .request(proto::GitShow {
+                    client
+                        .request(proto::GitRemoveWorktree {
                             project_id: project_id.0,
                             repository_id: id.to_proto(),
-                            commit,
+                            path: path.to_proto(),
+                            force,
                         })
                         .await?;
 
-                    Ok(CommitDetails {
-                        sha: resp.sha.into(),
-                        message: resp.message.into(),
-                        commit_timestamp: resp.commit_timestamp,
-                        author_email: resp.author_email.into(),
-                        author_name: resp.author_name.into(),
-                    })
-                }
-            }
-        })
-    }
-
-    pub fn load_commit_diff(&self, commit: String) -> oneshot::Receiver<Result<CommitDiff>> {
-        let id = self.id;
-        self.send_job(move |git_repo, cx| async move {
-            match git_repo {
-                RepositoryState::Local { backend, .. } => backend.load_commit(commit, cx).await,
-                RepositoryState::Remote {
-                    client, project_id, ..
-                } => {
-                    let response = client
-                        .request(proto::LoadCommitDiff {
-                            project_id: project_id.0,
-                            repository_id: id.to_proto(),
-                            commit,
-                        })
-                        .await?;
-                    Ok(CommitDiff {
-                        files: response
-                            .files
-                            .into_iter()
-                            .map(|file| CommitFile {
-                                path: Path::new(&file.path).into(),
-                                old_text: file.old_text,
-                                new_text: file.new_text,
-                            })
-                            .collect(),
-                    })
+                    Ok(())
                 }
             }
         })
@@ -3097,6 +4232,7 @@ impl Repository {
         &self,
         message: SharedString,
         name_and_email: Option<(SharedString, SharedString)>,
+        options: CommitOptions,
         _cx: &mut App,
     ) -> oneshot::Receiver<Result<()>> {
         let id = self.id;
@@ -3107,7 +4243,11 @@ impl Repository {
                     backend,
                     environment,
                     ..
-                } => backend.commit(message, name_and_email, environment).await,
+                } => {
+                    backend
+                        .commit(message, name_and_email, options, environment)
+                        .await
+                }
                 RepositoryState::Remote { project_id, client } => {
                     let (name, email) = name_and_email.unzip();
                     client
@@ -3117,6 +4257,12 @@ impl Repository {
                             message: String::from(message),
                             name: name.map(String::from),
                             email: email.map(String::from),
+                            options: Some(proto::commit::CommitOptions {
+                                amend: options.amend,
+                                fixup: options.fixup,
+                                signoff: options.signoff,
+                                gpg_sign: options.gpg_sign,
+                            }),
                         })
                         .await
                         .context("sending commit request")?;
@@ -3168,6 +4314,112 @@ impl Repository {
         })
     }
 
+    pub fn update_submodules(
+        &mut self,
+        paths: Vec<RepoPath>,
+        askpass: AskPassDelegate,
+        _cx: &mut App,
+    ) -> oneshot::Receiver<Result<RemoteCommandOutput>> {
+        let askpass_delegates = self.askpass_delegates.clone();
+        let askpass_id = util::post_inc(&mut self.latest_askpass_id);
+        let id = self.id;
+
+        self.send_job(move |git_repo, cx| async move {
+            match git_repo {
+                RepositoryState::Local {
+                    backend,
+                    environment,
+                    ..
+                } => {
+                    backend
+                        .update_submodules(paths, askpass, environment, cx)
+                        .await
+                }
+                RepositoryState::Remote { project_id, client } => {
+                    askpass_delegates.lock().insert(askpass_id, askpass);
+                    let _defer = util::defer(|| {
+                        let askpass_delegate = askpass_delegates.lock().remove(&askpass_id);
+                        debug_assert!(askpass_delegate.is_some());
+                    });
+
+                    let response = client
+                        .request(proto::GitUpdateSubmodules {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            paths: paths.iter().map(|path| path.as_ref().to_proto()).collect(),
+                            askpass_id,
+                        })
+                        .await
+                        .context("sending submodule update request")?;
+
+                    Ok(RemoteCommandOutput {
+                        stdout: response.stdout,
+                        stderr: response.stderr,
+                    })
+                }
+            }
+        })
+    }
+
+    /// Fetches a ref of the given remote, such as the head of a pull request, into a local branch.
+    pub fn fetch_ref(
+        &mut self,
+        remote_name: String,
+        remote_ref: String,
+        branch_name: String,
+        askpass: AskPassDelegate,
+        _cx: &mut App,
+    ) -> oneshot::Receiver<Result<RemoteCommandOutput>> {
+        let askpass_delegates = self.askpass_delegates.clone();
+        let askpass_id = util::post_inc(&mut self.latest_askpass_id);
+        let id = self.id;
+
+        self.send_job(move |git_repo, cx| async move {
+            match git_repo {
+                RepositoryState::Local {
+                    backend,
+                    environment,
+                    ..
+                } => {
+                    backend
+                        .fetch_ref(
+                            remote_name,
+                            remote_ref,
+                            branch_name,
+                            askpass,
+                            environment,
+                            cx,
+                        )
+                        .await
+                }
+                RepositoryState::Remote { project_id, client } => {
+                    askpass_delegates.lock().insert(askpass_id, askpass);
+                    let _defer = util::defer(|| {
+                        let askpass_delegate = askpass_delegates.lock().remove(&askpass_id);
+                        debug_assert!(askpass_delegate.is_some());
+                    });
+
+                    let response = client
+                        .request(proto::GitFetchRef {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            remote_name,
+                            remote_ref,
+                            branch_name,
+                            askpass_id,
+                        })
+                        .await
+                        .context("sending fetch ref request")?;
+
+                    Ok(RemoteCommandOutput {
+                        stdout: response.stdout,
+                        stderr: response.stderr,
+                    })
+                }
+            }
+        })
+    }
+
     pub fn push(
         &mut self,
         branch: SharedString,
@@ -3350,6 +4602,26 @@ impl Repository {
         })
     }
 
+    pub fn remote_url(&self, remote_name: String) -> oneshot::Receiver<Result<Option<String>>> {
+        let id = self.id;
+        self.send_job(move |repo, _cx| async move {
+            match repo {
+                RepositoryState::Local { backend, .. } => Ok(backend.remote_url(&remote_name)),
+                RepositoryState::Remote { project_id, client } => {
+                    let response = client
+                        .request(proto::GitGetRemoteUrl {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            remote_name,
+                        })
+                        .await?;
+
+                    Ok(response.url)
+                }
+            }
+        })
+    }
+
     pub fn branches(&self) -> oneshot::Receiver<Result<Vec<Branch>>> {
         let id = self.id;
         self.send_job(move |repo, cx| async move {
@@ -3503,6 +4775,13 @@ impl Repository {
         );
         self.snapshot.branch = update.branch_summary.as_ref().map(proto_to_branch);
         self.snapshot.merge_conflicts = conflicted_paths;
+        self.snapshot.rebase_state = update.rebase_state.as_ref().map(proto_to_rebase_state);
+        self.snapshot.submodules = update.submodules.iter().map(proto_to_submodule).collect();
+        self.snapshot.sequencer_command = update
+            .sequencer_command
+            .and_then(proto::SequencerCommand::from_i32)
+            .map(proto_to_sequencer_command);
+        self.snapshot.detached_head = update.detached_head.as_ref().map(proto_to_detached_head);
 
         let edits = update
             .removed_statuses
@@ -3822,6 +5101,19 @@ impl Repository {
 
                 let paths = changed_paths.iter().cloned().collect::<Vec<_>>();
                 let statuses = backend.status(&paths).await?;
+                // Changes to a submodule's files can change the submodule's status.
+                let refresh_submodules = paths.iter().any(|path| {
+                    path.0.as_ref() == Path::new(".gitmodules")
+                        || prev_snapshot
+                            .submodules
+                            .iter()
+                            .any(|submodule| path.0.starts_with(&submodule.path.0))
+                });
+                let submodules = if refresh_submodules {
+                    backend.submodules().await.log_err()
+                } else {
+                    None
+                };
 
                 let changed_path_statuses = cx
                     .background_spawn(async move {
@@ -3853,10 +5145,20 @@ impl Repository {
                     .await;
 
                 this.update(&mut cx, |this, cx| {
+                    let mut changed = false;
                     if !changed_path_statuses.is_empty() {
                         this.snapshot
                             .statuses_by_path
                             .edit(changed_path_statuses, &());
+                        changed = true;
+                    }
+                    if let Some(submodules) = submodules {
+                        if submodules != this.snapshot.submodules {
+                            this.snapshot.submodules = submodules;
+                            changed = true;
+                        }
+                    }
+                    if changed {
                         this.snapshot.scan_id += 1;
                         if let Some(updates_tx) = updates_tx {
                             updates_tx
@@ -4038,6 +5340,267 @@ fn branch_to_proto(branch: &git::repository::Branch) -> proto::Branch {
     }
 }
 
+fn commit_diff_to_proto(commit_diff: CommitDiff) -> proto::LoadCommitDiffResponse {
+    proto::LoadCommitDiffResponse {
+        files: commit_diff
+            .files
+            .into_iter()
+            .map(|file| proto::CommitFile {
+                path: file.path.to_string(),
+                old_text: file.old_text,
+                new_text: file.new_text,
+            })
+            .collect(),
+    }
+}
+
+fn proto_to_commit_diff(response: proto::LoadCommitDiffResponse) -> CommitDiff {
+    CommitDiff {
+        files: response
+            .files
+            .into_iter()
+            .map(|file| CommitFile {
+                path: Path::new(&file.path).into(),
+                old_text: file.old_text,
+                new_text: file.new_text,
+            })
+            .collect(),
+    }
+}
+
+fn stash_entry_to_proto(entry: &StashEntry) -> proto::StashEntry {
+    proto::StashEntry {
+        index: entry.index as u64,
+        sha: entry.sha.to_string(),
+        message: entry.message.to_string(),
+        timestamp: entry.timestamp,
+    }
+}
+
+fn proto_to_stash_entry(proto: &proto::StashEntry) -> StashEntry {
+    StashEntry {
+        index: proto.index as usize,
+        sha: proto.sha.clone().into(),
+        message: proto.message.clone().into(),
+        timestamp: proto.timestamp,
+    }
+}
+
+/// Checks that a worktree path requested by a collaborator is inside the repository's working
+/// directory or next to it, and outside of any `.git` directory, so that they can't create or
+/// remove directories elsewhere on the host.
+pub(crate) fn check_worktree_path(work_directory_abs_path: &Path, path: &Path) -> Result<()> {
+    let is_normalized = path.components().all(|component| match component {
+        Component::ParentDir | Component::CurDir => false,
+        Component::Normal(name) => name != ".git",
+        Component::Prefix(_) | Component::RootDir => true,
+    });
+    let is_inside_or_next_to_repository = path.starts_with(work_directory_abs_path)
+        || path.parent() == work_directory_abs_path.parent();
+    if !path.is_absolute()
+        || !is_normalized
+        || !is_inside_or_next_to_repository
+        || path == work_directory_abs_path
+    {
+        return Err(anyhow!(
+            "worktree path {path:?} must be inside or next to the repository"
+        ));
+    }
+    Ok(())
+}
+
+fn worktree_to_proto(worktree: &GitWorktree) -> proto::GitWorktree {
+    proto::GitWorktree {
+        path: worktree.path.as_path().to_proto(),
+        sha: worktree.sha.as_ref().map(ToString::to_string),
+        branch: worktree.branch.as_ref().map(ToString::to_string),
+        is_main: worktree.is_main,
+        is_locked: worktree.is_locked,
+    }
+}
+
+fn proto_to_worktree(proto: &proto::GitWorktree) -> GitWorktree {
+    GitWorktree {
+        path: PathBuf::from_proto(proto.path.clone()),
+        sha: proto.sha.clone().map(Into::into),
+        branch: proto.branch.clone().map(Into::into),
+        is_main: proto.is_main,
+        is_locked: proto.is_locked,
+    }
+}
+
+fn submodule_to_proto(submodule: &Submodule) -> proto::Submodule {
+    proto::Submodule {
+        name: submodule.name.to_string(),
+        path: submodule.path.as_ref().to_proto(),
+        url: submodule.url.as_ref().map(ToString::to_string),
+        head_sha: submodule.head_sha.as_ref().map(ToString::to_string),
+        workdir_sha: submodule.workdir_sha.as_ref().map(ToString::to_string),
+        uninitialized: submodule.status.uninitialized,
+        new_commits: submodule.status.new_commits,
+        modified: submodule.status.modified,
+        untracked: submodule.status.untracked,
+    }
+}
+
+fn proto_to_submodule(proto: &proto::Submodule) -> Submodule {
+    Submodule {
+        name: proto.name.clone().into(),
+        path: RepoPath(Arc::<Path>::from_proto(proto.path.clone())),
+        url: proto.url.clone().map(Into::into),
+        head_sha: proto.head_sha.clone().map(Into::into),
+        workdir_sha: proto.workdir_sha.clone().map(Into::into),
+        status: SubmoduleStatus {
+            uninitialized: proto.uninitialized,
+            new_commits: proto.new_commits,
+            modified: proto.modified,
+            untracked: proto.untracked,
+        },
+    }
+}
+
+fn log_entry_to_proto(entry: &LogEntry) -> proto::LogEntry {
+    proto::LogEntry {
+        sha: entry.sha.to_string(),
+        parent_shas: entry.parent_shas.iter().map(ToString::to_string).collect(),
+        author_name: entry.author_name.to_string(),
+        author_email: entry.author_email.to_string(),
+        commit_timestamp: entry.commit_timestamp,
+        refs: entry.refs.iter().map(ToString::to_string).collect(),
+        subject: entry.subject.to_string(),
+    }
+}
+
+fn proto_to_log_entry(proto: &proto::LogEntry) -> LogEntry {
+    LogEntry {
+        sha: proto.sha.clone().into(),
+        parent_shas: proto.parent_shas.iter().cloned().map(Into::into).collect(),
+        author_name: proto.author_name.clone().into(),
+        author_email: proto.author_email.clone().into(),
+        commit_timestamp: proto.commit_timestamp,
+        refs: proto.refs.iter().cloned().map(Into::into).collect(),
+        subject: proto.subject.clone().into(),
+    }
+}
+
+fn rebase_todo_entry_to_proto(entry: &RebaseTodoEntry) -> proto::RebaseTodoEntry {
+    use proto::rebase_todo_entry::Action;
+
+    let action = match entry.action {
+        RebaseAction::Pick => Action::Pick,
+        RebaseAction::Reword => Action::Reword,
+        RebaseAction::Edit => Action::Edit,
+        RebaseAction::Squash => Action::Squash,
+        RebaseAction::Fixup => Action::Fixup,
+        RebaseAction::Drop => Action::Drop,
+    };
+    proto::RebaseTodoEntry {
+        action: action.into(),
+        sha: entry.sha.to_string(),
+        subject: entry.subject.to_string(),
+        message: entry.message.clone(),
+    }
+}
+
+fn proto_to_rebase_todo_entry(proto: &proto::RebaseTodoEntry) -> RebaseTodoEntry {
+    use proto::rebase_todo_entry::Action;
+
+    let action = match proto.action() {
+        Action::Pick => RebaseAction::Pick,
+        Action::Reword => RebaseAction::Reword,
+        Action::Edit => RebaseAction::Edit,
+        Action::Squash => RebaseAction::Squash,
+        Action::Fixup => RebaseAction::Fixup,
+        Action::Drop => RebaseAction::Drop,
+    };
+    RebaseTodoEntry {
+        action,
+        sha: proto.sha.clone().into(),
+        subject: proto.subject.clone().into(),
+        message: proto.message.clone(),
+    }
+}
+
+fn rebase_state_to_proto(state: &RebaseState) -> proto::RebaseState {
+    proto::RebaseState {
+        branch: state.branch.as_ref().map(ToString::to_string),
+        onto: state.onto.to_string(),
+        step: state.step as u64,
+        total: state.total as u64,
+    }
+}
+
+fn proto_to_rebase_state(proto: &proto::RebaseState) -> RebaseState {
+    RebaseState {
+        branch: proto.branch.clone().map(Into::into),
+        onto: proto.onto.clone().into(),
+        step: proto.step as usize,
+        total: proto.total as usize,
+    }
+}
+
+fn rebase_operation_to_proto(operation: RebaseOperation) -> proto::git_rebase_operation::Operation {
+    match operation {
+        RebaseOperation::Continue => proto::git_rebase_operation::Operation::Continue,
+        RebaseOperation::Skip => proto::git_rebase_operation::Operation::Skip,
+        RebaseOperation::Abort => proto::git_rebase_operation::Operation::Abort,
+    }
+}
+
+fn proto_to_rebase_operation(operation: proto::git_rebase_operation::Operation) -> RebaseOperation {
+    match operation {
+        proto::git_rebase_operation::Operation::Continue => RebaseOperation::Continue,
+        proto::git_rebase_operation::Operation::Skip => RebaseOperation::Skip,
+        proto::git_rebase_operation::Operation::Abort => RebaseOperation::Abort,
+    }
+}
+
+fn sequencer_command_to_proto(command: SequencerCommand) -> proto::SequencerCommand {
+    match command {
+        SequencerCommand::CherryPick => proto::SequencerCommand::CherryPick,
+        SequencerCommand::Revert => proto::SequencerCommand::Revert,
+    }
+}
+
+fn proto_to_sequencer_command(command: proto::SequencerCommand) -> SequencerCommand {
+    match command {
+        proto::SequencerCommand::CherryPick => SequencerCommand::CherryPick,
+        proto::SequencerCommand::Revert => SequencerCommand::Revert,
+    }
+}
+
+fn detached_head_to_proto(detached_head: &DetachedHead) -> proto::DetachedHead {
+    proto::DetachedHead {
+        sha: detached_head.sha.to_string(),
+        tag: detached_head.tag.as_ref().map(ToString::to_string),
+    }
+}
+
+fn proto_to_detached_head(proto: &proto::DetachedHead) -> DetachedHead {
+    DetachedHead {
+        sha: proto.sha.clone().into(),
+        tag: proto.tag.clone().map(Into::into),
+    }
+}
+
+fn tag_to_proto(tag: &Tag) -> proto::GitTag {
+    proto::GitTag {
+        name: tag.name.to_string(),
+        sha: tag.sha.to_string(),
+        message: tag.message.as_ref().map(ToString::to_string),
+        timestamp: tag.timestamp,
+    }
+}
+
+fn proto_to_tag(proto: &proto::GitTag) -> Tag {
+    Tag {
+        name: proto.name.clone().into(),
+        sha: proto.sha.clone().into(),
+        message: proto.message.clone().map(Into::into),
+        timestamp: proto.timestamp,
+    }
+}
+
 fn proto_to_branch(proto: &proto::Branch) -> git::repository::Branch {
     git::repository::Branch {
         is_head: proto.is_head,
@@ -4088,6 +5651,10 @@ async fn compute_snapshot(
         .into_iter()
         .map(SharedString::from)
         .collect();
+    let rebase_state = backend.rebase_state().await;
+    let sequencer_command = backend.sequencer_command().await;
+    let detached_head = backend.detached_head().await;
+    let submodules = backend.submodules().await.log_err().unwrap_or_default();
 
     let statuses_by_path = SumTree::from_iter(
         statuses
@@ -4101,10 +5668,16 @@ async fn compute_snapshot(
     );
 
     let merge_head_shas_changed = merge_head_shas != prev_snapshot.merge_head_shas;
+    // Each step of a rebase can stop with new conflicts.
+    let rebase_state_changed = rebase_state != prev_snapshot.rebase_state;
 
     if merge_head_shas_changed
+        || rebase_state_changed
+        || sequencer_command != prev_snapshot.sequencer_command
+        || detached_head != prev_snapshot.detached_head
         || branch != prev_snapshot.branch
         || statuses_by_path != prev_snapshot.statuses_by_path
+        || submodules != prev_snapshot.submodules
     {
         events.push(RepositoryEvent::Updated { full_scan: true });
     }
@@ -4119,7 +5692,7 @@ async fn compute_snapshot(
     // Cache merge conflict paths so they don't change from staging/unstaging,
     // until the merge heads change (at commit time, etc.).
     let mut merge_conflicts = prev_snapshot.merge_conflicts.clone();
-    if merge_head_shas_changed {
+    if merge_head_shas_changed || rebase_state_changed {
         merge_conflicts = current_merge_conflicts;
         events.push(RepositoryEvent::MergeHeadsChanged);
     }
@@ -4133,6 +5706,10 @@ async fn compute_snapshot(
         branch,
         merge_conflicts,
         merge_head_shas,
+        rebase_state,
+        sequencer_command,
+        detached_head,
+        submodules,
     };
 
     Ok((snapshot, events))
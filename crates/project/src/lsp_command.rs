@@ -1,8 +1,10 @@
+mod hierarchy;
+mod semantic_tokens;
 mod signature_help;
 
 use crate::{
-    CodeAction, CompletionSource, CoreCompletion, DocumentHighlight, DocumentSymbol, Hover,
-    HoverBlock, HoverBlockKind, InlayHint, InlayHintLabel, InlayHintLabelPart,
+    CodeAction, CompletionSource, CoreCompletion, DocumentHighlight, DocumentSymbol, FoldingRange,
+    Hover, HoverBlock, HoverBlockKind, InlayHint, InlayHintLabel, InlayHintLabelPart,
     InlayHintLabelPartTooltip, InlayHintTooltip, Location, LocationLink, LspAction, MarkupContent,
     PrepareRenameResponse, ProjectTransaction, ResolveState,
     lsp_store::{LocalLspStore, LspStore},
@@ -14,9 +16,13 @@ use clock::Global;
 use collections::HashSet;
 use futures::future;
 use gpui::{App, AsyncApp, Entity};
+use hierarchy::{
+    hierarchy_calls_from_proto, hierarchy_calls_to_proto, hierarchy_items_from_lsp,
+    hierarchy_items_from_proto, hierarchy_items_to_proto,
+};
 use language::{
-    Anchor, Bias, Buffer, BufferSnapshot, CachedLspAdapter, CharKind, OffsetRangeExt, PointUtf16,
-    ToOffset, ToPointUtf16, Transaction, Unclipped,
+    Anchor, Bias, Buffer, BufferSnapshot, CachedLspAdapter, CharKind, OffsetRangeExt, Point,
+    PointUtf16, ToOffset, ToPointUtf16, Transaction, Unclipped,
     language_settings::{
         AllLanguageSettings, InlayHintKind, LanguageSettings, LspInsertMode, language_settings,
     },
@@ -35,6 +41,12 @@ use signature_help::{lsp_to_proto_signature, proto_to_lsp_signature};
 use std::{cmp::Reverse, mem, ops::Range, path::Path, sync::Arc};
 use text::{BufferId, LineEnding};
 
+pub use hierarchy::{HierarchyCall, HierarchyItem};
+pub use semantic_tokens::{BufferSemanticTokens, SemanticToken};
+pub(crate) use semantic_tokens::{
+    LspSemanticTokens, semantic_tokens_legend, supports_full_semantic_tokens,
+    supports_semantic_tokens_delta,
+};
 pub use signature_help::SignatureHelp;
 
 pub fn lsp_formatting_options(settings: &LanguageSettings) -> lsp::FormattingOptions {
@@ -259,6 +271,62 @@ pub(crate) struct LinkedEditingRange {
     pub position: Anchor,
 }
 
+pub(crate) struct GetSemanticTokens {
+    /// The buffer as of the request, which the positions in the response refer to.
+    pub snapshot: BufferSnapshot,
+}
+
+impl std::fmt::Debug for GetSemanticTokens {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GetSemanticTokens")
+            .field("version", self.snapshot.version())
+            .finish()
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct PrepareCallHierarchy {
+    pub position: PointUtf16,
+}
+
+#[derive(Debug)]
+pub(crate) struct GetIncomingCalls {
+    pub item: HierarchyItem,
+}
+
+#[derive(Debug)]
+pub(crate) struct GetOutgoingCalls {
+    pub item: HierarchyItem,
+}
+
+#[derive(Debug)]
+pub(crate) struct PrepareTypeHierarchy {
+    pub position: PointUtf16,
+}
+
+#[derive(Debug)]
+pub(crate) struct GetSupertypes {
+    pub item: HierarchyItem,
+}
+
+#[derive(Debug)]
+pub(crate) struct GetSubtypes {
+    pub item: HierarchyItem,
+}
+
+pub(crate) struct GetFoldingRanges {
+    /// The buffer as of the request, which the lines in the response refer to.
+    pub snapshot: BufferSnapshot,
+}
+
+impl std::fmt::Debug for GetFoldingRanges {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GetFoldingRanges")
+            .field("version", self.snapshot.version())
+            .finish()
+    }
+}
+
 #[async_trait(?Send)]
 impl LspCommand for PrepareRename {
     type Response = PrepareRenameResponse;
@@ -3615,3 +3683,868 @@ impl LspCommand for LinkedEditingRange {
         BufferId::new(message.buffer_id)
     }
 }
+
+#[async_trait(?Send)]
+impl LspCommand for GetSemanticTokens {
+    type Response = BufferSemanticTokens;
+    type LspRequest = lsp::request::SemanticTokensFullRequest;
+    type ProtoRequest = proto::GetSemanticTokens;
+
+    fn display_name(&self) -> &str {
+        "Semantic tokens"
+    }
+
+    fn check_capabilities(&self, capabilities: AdapterServerCapabilities) -> bool {
+        supports_full_semantic_tokens(&capabilities.server_capabilities)
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::SemanticTokensParams> {
+        Ok(lsp::SemanticTokensParams {
+            text_document: make_text_document_identifier(path)?,
+            work_done_progress_params: lsp::WorkDoneProgressParams::default(),
+            partial_result_params: lsp::PartialResultParams::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        message: Option<lsp::SemanticTokensResult>,
+        lsp_store: Entity<LspStore>,
+        _: Entity<Buffer>,
+        server_id: LanguageServerId,
+        mut cx: AsyncApp,
+    ) -> Result<BufferSemanticTokens> {
+        let (result_id, data) = match message {
+            Some(lsp::SemanticTokensResult::Tokens(tokens)) => (tokens.result_id, tokens.data),
+            Some(lsp::SemanticTokensResult::Partial(partial)) => (None, partial.data),
+            None => return Ok(BufferSemanticTokens::default()),
+        };
+        lsp_store.update(&mut cx, |lsp_store, _| {
+            lsp_store.update_semantic_tokens(&self.snapshot, server_id, result_id, data)
+        })?
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetSemanticTokens {
+        proto::GetSemanticTokens {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetSemanticTokens,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Self> {
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        Ok(Self {
+            snapshot: buffer.read_with(&cx, |buffer, _| buffer.snapshot())?,
+        })
+    }
+
+    fn response_to_proto(
+        response: BufferSemanticTokens,
+        _: &mut LspStore,
+        _: PeerId,
+        buffer_version: &clock::Global,
+        _: &mut App,
+    ) -> proto::GetSemanticTokensResponse {
+        proto::GetSemanticTokensResponse {
+            token_types: response.token_types.to_vec(),
+            token_modifiers: response.token_modifiers.to_vec(),
+            tokens: response
+                .tokens
+                .into_iter()
+                .map(|token| proto::SemanticToken {
+                    start: Some(serialize_anchor(&token.range.start)),
+                    end: Some(serialize_anchor(&token.range.end)),
+                    token_type: token.token_type,
+                    token_modifiers: token.token_modifiers,
+                })
+                .collect(),
+            version: serialize_version(buffer_version),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetSemanticTokensResponse,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<BufferSemanticTokens> {
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        let tokens = message
+            .tokens
+            .into_iter()
+            .filter_map(|token| {
+                Some(SemanticToken {
+                    range: deserialize_anchor(token.start?)?..deserialize_anchor(token.end?)?,
+                    token_type: token.token_type,
+                    token_modifiers: token.token_modifiers,
+                })
+            })
+            .collect();
+        Ok(BufferSemanticTokens {
+            token_types: message.token_types.into(),
+            token_modifiers: message.token_modifiers.into(),
+            tokens,
+        })
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetSemanticTokens) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for PrepareCallHierarchy {
+    type Response = Vec<HierarchyItem>;
+    type LspRequest = lsp::request::CallHierarchyPrepare;
+    type ProtoRequest = proto::PrepareCallHierarchy;
+
+    fn display_name(&self) -> &str {
+        "Prepare call hierarchy"
+    }
+
+    fn check_capabilities(&self, capabilities: AdapterServerCapabilities) -> bool {
+        !matches!(
+            &capabilities.server_capabilities.call_hierarchy_provider,
+            None | Some(lsp::CallHierarchyServerCapability::Simple(false))
+        )
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::CallHierarchyPrepareParams> {
+        Ok(lsp::CallHierarchyPrepareParams {
+            text_document_position_params: make_lsp_text_document_position(path, self.position)?,
+            work_done_progress_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        message: Option<Vec<lsp::CallHierarchyItem>>,
+        lsp_store: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        server_id: LanguageServerId,
+        cx: AsyncApp,
+    ) -> Result<Vec<HierarchyItem>> {
+        hierarchy_items_from_lsp(message, lsp_store, buffer, server_id, cx).await
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::PrepareCallHierarchy {
+        proto::PrepareCallHierarchy {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            position: Some(language::proto::serialize_anchor(
+                &buffer.anchor_before(self.position),
+            )),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::PrepareCallHierarchy,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Self> {
+        let position = message
+            .position
+            .and_then(deserialize_anchor)
+            .ok_or_else(|| anyhow!("invalid position"))?;
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        Ok(Self {
+            position: buffer.update(&mut cx, |buffer, _| position.to_point_utf16(buffer))?,
+        })
+    }
+
+    fn response_to_proto(
+        response: Vec<HierarchyItem>,
+        lsp_store: &mut LspStore,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut App,
+    ) -> proto::PrepareCallHierarchyResponse {
+        proto::PrepareCallHierarchyResponse {
+            items: hierarchy_items_to_proto(response, lsp_store, peer_id, cx),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::PrepareCallHierarchyResponse,
+        lsp_store: Entity<LspStore>,
+        _: Entity<Buffer>,
+        cx: AsyncApp,
+    ) -> Result<Vec<HierarchyItem>> {
+        hierarchy_items_from_proto(message.items, lsp_store, cx).await
+    }
+
+    fn buffer_id_from_proto(message: &proto::PrepareCallHierarchy) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetIncomingCalls {
+    type Response = Vec<HierarchyCall>;
+    type LspRequest = lsp::request::CallHierarchyIncomingCalls;
+    type ProtoRequest = proto::GetIncomingCalls;
+
+    fn display_name(&self) -> &str {
+        "Get incoming calls"
+    }
+
+    fn check_capabilities(&self, capabilities: AdapterServerCapabilities) -> bool {
+        !matches!(
+            &capabilities.server_capabilities.call_hierarchy_provider,
+            None | Some(lsp::CallHierarchyServerCapability::Simple(false))
+        )
+    }
+
+    fn to_lsp(
+        &self,
+        _: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::CallHierarchyIncomingCallsParams> {
+        Ok(lsp::CallHierarchyIncomingCallsParams {
+            item: self.item.lsp_item_as::<lsp::CallHierarchyItem>()?,
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        message: Option<Vec<lsp::CallHierarchyIncomingCall>>,
+        lsp_store: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        server_id: LanguageServerId,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<HierarchyCall>> {
+        let mut calls = Vec::new();
+        for call in message.into_iter().flatten() {
+            calls.push(
+                HierarchyCall::from_lsp(
+                    serde_json::to_value(call.from)?,
+                    call.from_ranges,
+                    None,
+                    &lsp_store,
+                    &buffer,
+                    server_id,
+                    &mut cx,
+                )
+                .await?,
+            );
+        }
+        Ok(calls)
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetIncomingCalls {
+        proto::GetIncomingCalls {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            item: Some(self.item.to_proto_in_buffer(buffer.remote_id())),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetIncomingCalls,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        _: AsyncApp,
+    ) -> Result<Self> {
+        let item = message
+            .item
+            .ok_or_else(|| anyhow!("missing hierarchy item"))?;
+        Ok(Self {
+            item: HierarchyItem::from_proto_in_buffer(item, buffer)?,
+        })
+    }
+
+    fn response_to_proto(
+        response: Vec<HierarchyCall>,
+        lsp_store: &mut LspStore,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut App,
+    ) -> proto::GetIncomingCallsResponse {
+        proto::GetIncomingCallsResponse {
+            calls: hierarchy_calls_to_proto(response, lsp_store, peer_id, cx),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetIncomingCallsResponse,
+        lsp_store: Entity<LspStore>,
+        _: Entity<Buffer>,
+        cx: AsyncApp,
+    ) -> Result<Vec<HierarchyCall>> {
+        hierarchy_calls_from_proto(message.calls, lsp_store, cx).await
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetIncomingCalls) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetOutgoingCalls {
+    type Response = Vec<HierarchyCall>;
+    type LspRequest = lsp::request::CallHierarchyOutgoingCalls;
+    type ProtoRequest = proto::GetOutgoingCalls;
+
+    fn display_name(&self) -> &str {
+        "Get outgoing calls"
+    }
+
+    fn check_capabilities(&self, capabilities: AdapterServerCapabilities) -> bool {
+        !matches!(
+            &capabilities.server_capabilities.call_hierarchy_provider,
+            None | Some(lsp::CallHierarchyServerCapability::Simple(false))
+        )
+    }
+
+    fn to_lsp(
+        &self,
+        _: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::CallHierarchyOutgoingCallsParams> {
+        Ok(lsp::CallHierarchyOutgoingCallsParams {
+            item: self.item.lsp_item_as::<lsp::CallHierarchyItem>()?,
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        message: Option<Vec<lsp::CallHierarchyOutgoingCall>>,
+        lsp_store: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        server_id: LanguageServerId,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<HierarchyCall>> {
+        let mut calls = Vec::new();
+        for call in message.into_iter().flatten() {
+            calls.push(
+                HierarchyCall::from_lsp(
+                    serde_json::to_value(call.to)?,
+                    call.from_ranges,
+                    Some(&buffer),
+                    &lsp_store,
+                    &buffer,
+                    server_id,
+                    &mut cx,
+                )
+                .await?,
+            );
+        }
+        Ok(calls)
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetOutgoingCalls {
+        proto::GetOutgoingCalls {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            item: Some(self.item.to_proto_in_buffer(buffer.remote_id())),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetOutgoingCalls,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        _: AsyncApp,
+    ) -> Result<Self> {
+        let item = message
+            .item
+            .ok_or_else(|| anyhow!("missing hierarchy item"))?;
+        Ok(Self {
+            item: HierarchyItem::from_proto_in_buffer(item, buffer)?,
+        })
+    }
+
+    fn response_to_proto(
+        response: Vec<HierarchyCall>,
+        lsp_store: &mut LspStore,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut App,
+    ) -> proto::GetOutgoingCallsResponse {
+        proto::GetOutgoingCallsResponse {
+            calls: hierarchy_calls_to_proto(response, lsp_store, peer_id, cx),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetOutgoingCallsResponse,
+        lsp_store: Entity<LspStore>,
+        _: Entity<Buffer>,
+        cx: AsyncApp,
+    ) -> Result<Vec<HierarchyCall>> {
+        hierarchy_calls_from_proto(message.calls, lsp_store, cx).await
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetOutgoingCalls) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for PrepareTypeHierarchy {
+    type Response = Vec<HierarchyItem>;
+    type LspRequest = lsp::request::TypeHierarchyPrepare;
+    type ProtoRequest = proto::PrepareTypeHierarchy;
+
+    fn display_name(&self) -> &str {
+        "Prepare type hierarchy"
+    }
+
+    // lsp-types doesn't expose the `typeHierarchyProvider` server capability, so the request is
+    // sent to the first language server of the buffer and fails if it isn't supported.
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::TypeHierarchyPrepareParams> {
+        Ok(lsp::TypeHierarchyPrepareParams {
+            text_document_position_params: make_lsp_text_document_position(path, self.position)?,
+            work_done_progress_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        message: Option<Vec<lsp::TypeHierarchyItem>>,
+        lsp_store: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        server_id: LanguageServerId,
+        cx: AsyncApp,
+    ) -> Result<Vec<HierarchyItem>> {
+        hierarchy_items_from_lsp(message, lsp_store, buffer, server_id, cx).await
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::PrepareTypeHierarchy {
+        proto::PrepareTypeHierarchy {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            position: Some(language::proto::serialize_anchor(
+                &buffer.anchor_before(self.position),
+            )),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::PrepareTypeHierarchy,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Self> {
+        let position = message
+            .position
+            .and_then(deserialize_anchor)
+            .ok_or_else(|| anyhow!("invalid position"))?;
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        Ok(Self {
+            position: buffer.update(&mut cx, |buffer, _| position.to_point_utf16(buffer))?,
+        })
+    }
+
+    fn response_to_proto(
+        response: Vec<HierarchyItem>,
+        lsp_store: &mut LspStore,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut App,
+    ) -> proto::PrepareTypeHierarchyResponse {
+        proto::PrepareTypeHierarchyResponse {
+            items: hierarchy_items_to_proto(response, lsp_store, peer_id, cx),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::PrepareTypeHierarchyResponse,
+        lsp_store: Entity<LspStore>,
+        _: Entity<Buffer>,
+        cx: AsyncApp,
+    ) -> Result<Vec<HierarchyItem>> {
+        hierarchy_items_from_proto(message.items, lsp_store, cx).await
+    }
+
+    fn buffer_id_from_proto(message: &proto::PrepareTypeHierarchy) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetSupertypes {
+    type Response = Vec<HierarchyItem>;
+    type LspRequest = lsp::request::TypeHierarchySupertypes;
+    type ProtoRequest = proto::GetSupertypes;
+
+    fn display_name(&self) -> &str {
+        "Get supertypes"
+    }
+
+    fn to_lsp(
+        &self,
+        _: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::TypeHierarchySupertypesParams> {
+        Ok(lsp::TypeHierarchySupertypesParams {
+            item: self.item.lsp_item_as::<lsp::TypeHierarchyItem>()?,
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        message: Option<Vec<lsp::TypeHierarchyItem>>,
+        lsp_store: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        server_id: LanguageServerId,
+        cx: AsyncApp,
+    ) -> Result<Vec<HierarchyItem>> {
+        hierarchy_items_from_lsp(message, lsp_store, buffer, server_id, cx).await
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetSupertypes {
+        proto::GetSupertypes {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            item: Some(self.item.to_proto_in_buffer(buffer.remote_id())),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetSupertypes,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        _: AsyncApp,
+    ) -> Result<Self> {
+        let item = message
+            .item
+            .ok_or_else(|| anyhow!("missing hierarchy item"))?;
+        Ok(Self {
+            item: HierarchyItem::from_proto_in_buffer(item, buffer)?,
+        })
+    }
+
+    fn response_to_proto(
+        response: Vec<HierarchyItem>,
+        lsp_store: &mut LspStore,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut App,
+    ) -> proto::GetSupertypesResponse {
+        proto::GetSupertypesResponse {
+            items: hierarchy_items_to_proto(response, lsp_store, peer_id, cx),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetSupertypesResponse,
+        lsp_store: Entity<LspStore>,
+        _: Entity<Buffer>,
+        cx: AsyncApp,
+    ) -> Result<Vec<HierarchyItem>> {
+        hierarchy_items_from_proto(message.items, lsp_store, cx).await
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetSupertypes) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetSubtypes {
+    type Response = Vec<HierarchyItem>;
+    type LspRequest = lsp::request::TypeHierarchySubtypes;
+    type ProtoRequest = proto::GetSubtypes;
+
+    fn display_name(&self) -> &str {
+        "Get subtypes"
+    }
+
+    fn to_lsp(
+        &self,
+        _: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::TypeHierarchySubtypesParams> {
+        Ok(lsp::TypeHierarchySubtypesParams {
+            item: self.item.lsp_item_as::<lsp::TypeHierarchyItem>()?,
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        message: Option<Vec<lsp::TypeHierarchyItem>>,
+        lsp_store: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        server_id: LanguageServerId,
+        cx: AsyncApp,
+    ) -> Result<Vec<HierarchyItem>> {
+        hierarchy_items_from_lsp(message, lsp_store, buffer, server_id, cx).await
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetSubtypes {
+        proto::GetSubtypes {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            item: Some(self.item.to_proto_in_buffer(buffer.remote_id())),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetSubtypes,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        _: AsyncApp,
+    ) -> Result<Self> {
+        let item = message
+            .item
+            .ok_or_else(|| anyhow!("missing hierarchy item"))?;
+        Ok(Self {
+            item: HierarchyItem::from_proto_in_buffer(item, buffer)?,
+        })
+    }
+
+    fn response_to_proto(
+        response: Vec<HierarchyItem>,
+        lsp_store: &mut LspStore,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut App,
+    ) -> proto::GetSubtypesResponse {
+        proto::GetSubtypesResponse {
+            items: hierarchy_items_to_proto(response, lsp_store, peer_id, cx),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetSubtypesResponse,
+        lsp_store: Entity<LspStore>,
+        _: Entity<Buffer>,
+        cx: AsyncApp,
+    ) -> Result<Vec<HierarchyItem>> {
+        hierarchy_items_from_proto(message.items, lsp_store, cx).await
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetSubtypes) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetFoldingRanges {
+    type Response = Vec<FoldingRange>;
+    type LspRequest = lsp::request::FoldingRangeRequest;
+    type ProtoRequest = proto::GetFoldingRanges;
+
+    fn display_name(&self) -> &str {
+        "Get folding ranges"
+    }
+
+    fn check_capabilities(&self, capabilities: AdapterServerCapabilities) -> bool {
+        match capabilities.server_capabilities.folding_range_provider {
+            Some(lsp::FoldingRangeProviderCapability::Simple(enabled)) => enabled,
+            Some(_) => true,
+            None => false,
+        }
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::FoldingRangeParams> {
+        Ok(lsp::FoldingRangeParams {
+            text_document: make_text_document_identifier(path)?,
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        lsp_ranges: Option<Vec<lsp::FoldingRange>>,
+        _: Entity<LspStore>,
+        _: Entity<Buffer>,
+        _: LanguageServerId,
+        _: AsyncApp,
+    ) -> Result<Vec<FoldingRange>> {
+        let buffer = &self.snapshot;
+        let max_row = buffer.max_point().row;
+        // The client asks for line folding only, so the ranges span from the end of their
+        // first line, which stays visible, to the end of their last line.
+        let mut ranges = lsp_ranges
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|lsp_range| {
+                let start_row = lsp_range.start_line;
+                let end_row = lsp_range.end_line.min(max_row);
+                if start_row >= end_row {
+                    return None;
+                }
+                let start = Point::new(start_row, buffer.line_len(start_row));
+                let end = Point::new(end_row, buffer.line_len(end_row));
+                Some(FoldingRange {
+                    range: buffer.anchor_after(start)..buffer.anchor_before(end),
+                    kind: lsp_range.kind,
+                })
+            })
+            .collect::<Vec<_>>();
+        ranges.sort_by(|a, b| {
+            a.range
+                .start
+                .cmp(&b.range.start, buffer)
+                .then_with(|| b.range.end.cmp(&a.range.end, buffer))
+        });
+        Ok(ranges)
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetFoldingRanges {
+        proto::GetFoldingRanges {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetFoldingRanges,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Self> {
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        Ok(Self {
+            snapshot: buffer.read_with(&cx, |buffer, _| buffer.snapshot())?,
+        })
+    }
+
+    fn response_to_proto(
+        response: Vec<FoldingRange>,
+        _: &mut LspStore,
+        _: PeerId,
+        buffer_version: &clock::Global,
+        _: &mut App,
+    ) -> proto::GetFoldingRangesResponse {
+        proto::GetFoldingRangesResponse {
+            ranges: response
+                .into_iter()
+                .map(|range| proto::FoldingRange {
+                    start: Some(serialize_anchor(&range.range.start)),
+                    end: Some(serialize_anchor(&range.range.end)),
+                    kind: range.kind.map(|kind| {
+                        match kind {
+                            lsp::FoldingRangeKind::Comment => proto::folding_range::Kind::Comment,
+                            lsp::FoldingRangeKind::Imports => proto::folding_range::Kind::Imports,
+                            lsp::FoldingRangeKind::Region => proto::folding_range::Kind::Region,
+                        }
+                        .into()
+                    }),
+                })
+                .collect(),
+            version: serialize_version(buffer_version),
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetFoldingRangesResponse,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Vec<FoldingRange>> {
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        Ok(message
+            .ranges
+            .into_iter()
+            .filter_map(|range| {
+                let kind = match range.kind.map(proto::folding_range::Kind::from_i32) {
+                    Some(Some(proto::folding_range::Kind::Comment)) => {
+                        Some(lsp::FoldingRangeKind::Comment)
+                    }
+                    Some(Some(proto::folding_range::Kind::Imports)) => {
+                        Some(lsp::FoldingRangeKind::Imports)
+                    }
+                    Some(Some(proto::folding_range::Kind::Region)) => {
+                        Some(lsp::FoldingRangeKind::Region)
+                    }
+                    Some(None) | None => None,
+                };
+                Some(FoldingRange {
+                    range: deserialize_anchor(range.start?)?..deserialize_anchor(range.end?)?,
+                    kind,
+                })
+            })
+            .collect())
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetFoldingRanges) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
@@ -0,0 +1,353 @@
+use std::{mem, ops::Range};
+
+use anyhow::{Context as _, Result, anyhow};
+use client::proto::{self, PeerId};
+use gpui::{App, AsyncApp, Entity};
+use language::{
+    Anchor, Bias, Buffer, Location, point_from_lsp,
+    proto::{deserialize_anchor, serialize_anchor},
+};
+use lsp::LanguageServerId;
+use serde::{Deserialize, Serialize};
+use text::BufferId;
+
+use super::language_server_for_buffer;
+use crate::lsp_store::LspStore;
+
+/// An item of a call or type hierarchy: a function, method or type that the hierarchy can be
+/// expanded from.
+#[derive(Clone, Debug)]
+pub struct HierarchyItem {
+    pub name: String,
+    pub kind: lsp::SymbolKind,
+    pub detail: Option<String>,
+    /// The whole symbol, including its body.
+    pub location: Location,
+    /// The part of the symbol to reveal when the item is opened, e.g. the name of a function.
+    pub selection_range: Range<Anchor>,
+    /// The language server that reported the item, and that must be asked for its relatives.
+    pub server_id: LanguageServerId,
+    /// The item as the language server reported it, which is sent back to it verbatim.
+    pub(super) lsp_item: serde_json::Value,
+}
+
+/// Calls from or to a [`HierarchyItem`].
+#[derive(Clone, Debug)]
+pub struct HierarchyCall {
+    /// The caller for incoming calls, or the callee for outgoing calls.
+    pub item: HierarchyItem,
+    /// Where the calls are made: in the caller for incoming calls, or in the item whose outgoing
+    /// calls were requested.
+    pub call_sites: Vec<Location>,
+}
+
+/// The fields shared by call and type hierarchy items.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LspHierarchyItem {
+    name: String,
+    kind: lsp::SymbolKind,
+    detail: Option<String>,
+    uri: lsp::Url,
+    range: lsp::Range,
+    selection_range: lsp::Range,
+}
+
+impl HierarchyItem {
+    pub(super) fn lsp_item_as<T: serde::de::DeserializeOwned>(&self) -> Result<T> {
+        serde_json::from_value(self.lsp_item.clone()).context("deserializing hierarchy item")
+    }
+
+    /// Resolves an item reported by a language server, opening the buffer that contains it.
+    pub(super) async fn from_lsp(
+        lsp_item: serde_json::Value,
+        lsp_store: &Entity<LspStore>,
+        buffer: &Entity<Buffer>,
+        server_id: LanguageServerId,
+        cx: &mut AsyncApp,
+    ) -> Result<Self> {
+        let item = LspHierarchyItem::deserialize(&lsp_item).context("invalid hierarchy item")?;
+        let (lsp_adapter, language_server) =
+            language_server_for_buffer(lsp_store, buffer, server_id, cx)?;
+        let item_buffer = lsp_store
+            .update(cx, |lsp_store, cx| {
+                lsp_store.open_local_buffer_via_lsp(
+                    item.uri,
+                    language_server.server_id(),
+                    lsp_adapter.name.clone(),
+                    cx,
+                )
+            })?
+            .await?;
+        item_buffer.clone().read_with(cx, |snapshot, _| Self {
+            name: item.name,
+            kind: item.kind,
+            detail: item.detail,
+            location: Location {
+                buffer: item_buffer,
+                range: range_from_lsp(snapshot, item.range),
+            },
+            selection_range: range_from_lsp(snapshot, item.selection_range),
+            server_id,
+            lsp_item,
+        })
+    }
+
+    /// Serializes the item for a peer, replicating its buffer to them.
+    pub(super) fn to_proto(
+        &self,
+        lsp_store: &mut LspStore,
+        peer_id: PeerId,
+        cx: &mut App,
+    ) -> proto::HierarchyItem {
+        proto::HierarchyItem {
+            range: Some(location_to_proto(&self.location, lsp_store, peer_id, cx)),
+            ..self.to_proto_in_buffer(self.location.buffer.read(cx).remote_id())
+        }
+    }
+
+    /// Serializes an item that's contained in the buffer with the given id.
+    pub(super) fn to_proto_in_buffer(&self, buffer_id: BufferId) -> proto::HierarchyItem {
+        proto::HierarchyItem {
+            name: self.name.clone(),
+            kind: unsafe { mem::transmute::<lsp::SymbolKind, i32>(self.kind) },
+            detail: self.detail.clone(),
+            range: Some(proto::Location {
+                buffer_id: buffer_id.into(),
+                start: Some(serialize_anchor(&self.location.range.start)),
+                end: Some(serialize_anchor(&self.location.range.end)),
+            }),
+            selection_start: Some(serialize_anchor(&self.selection_range.start)),
+            selection_end: Some(serialize_anchor(&self.selection_range.end)),
+            server_id: self.server_id.to_proto(),
+            lsp_item: serde_json::to_vec(&self.lsp_item).unwrap_or_default(),
+        }
+    }
+
+    /// Deserializes an item sent by a peer, waiting for its buffer to be replicated.
+    pub(super) async fn from_proto(
+        item: proto::HierarchyItem,
+        lsp_store: &Entity<LspStore>,
+        cx: &mut AsyncApp,
+    ) -> Result<Self> {
+        let location = item
+            .range
+            .clone()
+            .ok_or_else(|| anyhow!("missing hierarchy item range"))?;
+        let buffer_id = BufferId::new(location.buffer_id)?;
+        let buffer = lsp_store
+            .update(cx, |lsp_store, cx| {
+                lsp_store.wait_for_remote_buffer(buffer_id, cx)
+            })?
+            .await?;
+        let item = Self::from_proto_in_buffer(item, buffer.clone())?;
+        buffer
+            .update(cx, |buffer, _| {
+                buffer.wait_for_anchors([
+                    item.location.range.start,
+                    item.location.range.end,
+                    item.selection_range.start,
+                    item.selection_range.end,
+                ])
+            })?
+            .await?;
+        Ok(item)
+    }
+
+    /// Deserializes an item that's contained in the given buffer.
+    pub(super) fn from_proto_in_buffer(
+        item: proto::HierarchyItem,
+        buffer: Entity<Buffer>,
+    ) -> Result<Self> {
+        let location = item
+            .range
+            .ok_or_else(|| anyhow!("missing hierarchy item range"))?;
+        let anchor = |anchor: Option<proto::Anchor>| {
+            anchor
+                .and_then(deserialize_anchor)
+                .ok_or_else(|| anyhow!("invalid hierarchy item anchor"))
+        };
+        Ok(Self {
+            name: item.name,
+            kind: unsafe { mem::transmute::<i32, lsp::SymbolKind>(item.kind) },
+            detail: item.detail,
+            location: Location {
+                buffer,
+                range: anchor(location.start)?..anchor(location.end)?,
+            },
+            selection_range: anchor(item.selection_start)?..anchor(item.selection_end)?,
+            server_id: LanguageServerId::from_proto(item.server_id),
+            lsp_item: serde_json::from_slice(&item.lsp_item)
+                .context("invalid hierarchy item data")?,
+        })
+    }
+}
+
+impl HierarchyCall {
+    /// Resolves calls reported by a language server. `ranges_buffer` is the buffer that contains
+    /// the `ranges` of the calls, if not the buffer of the other item.
+    pub(super) async fn from_lsp(
+        lsp_item: serde_json::Value,
+        ranges: Vec<lsp::Range>,
+        ranges_buffer: Option<&Entity<Buffer>>,
+        lsp_store: &Entity<LspStore>,
+        buffer: &Entity<Buffer>,
+        server_id: LanguageServerId,
+        cx: &mut AsyncApp,
+    ) -> Result<Self> {
+        let item = HierarchyItem::from_lsp(lsp_item, lsp_store, buffer, server_id, cx).await?;
+        let ranges_buffer = ranges_buffer.unwrap_or(&item.location.buffer).clone();
+        let call_sites = ranges_buffer.read_with(cx, |snapshot, _| {
+            ranges
+                .into_iter()
+                .map(|range| Location {
+                    buffer: ranges_buffer.clone(),
+                    range: range_from_lsp(snapshot, range),
+                })
+                .collect()
+        })?;
+        Ok(Self { item, call_sites })
+    }
+
+    pub(super) fn to_proto(
+        &self,
+        lsp_store: &mut LspStore,
+        peer_id: PeerId,
+        cx: &mut App,
+    ) -> proto::HierarchyCall {
+        proto::HierarchyCall {
+            item: Some(self.item.to_proto(lsp_store, peer_id, cx)),
+            call_sites: self
+                .call_sites
+                .iter()
+                .map(|call_site| location_to_proto(call_site, lsp_store, peer_id, cx))
+                .collect(),
+        }
+    }
+
+    pub(super) async fn from_proto(
+        call: proto::HierarchyCall,
+        lsp_store: &Entity<LspStore>,
+        cx: &mut AsyncApp,
+    ) -> Result<Self> {
+        let item = call
+            .item
+            .ok_or_else(|| anyhow!("missing hierarchy call item"))?;
+        let item = HierarchyItem::from_proto(item, lsp_store, cx).await?;
+        let mut call_sites = Vec::with_capacity(call.call_sites.len());
+        for call_site in call.call_sites {
+            let buffer_id = BufferId::new(call_site.buffer_id)?;
+            let buffer = lsp_store
+                .update(cx, |lsp_store, cx| {
+                    lsp_store.wait_for_remote_buffer(buffer_id, cx)
+                })?
+                .await?;
+            let start = call_site
+                .start
+                .and_then(deserialize_anchor)
+                .ok_or_else(|| anyhow!("missing call site start"))?;
+            let end = call_site
+                .end
+                .and_then(deserialize_anchor)
+                .ok_or_else(|| anyhow!("missing call site end"))?;
+            buffer
+                .update(cx, |buffer, _| buffer.wait_for_anchors([start, end]))?
+                .await?;
+            call_sites.push(Location {
+                buffer,
+                range: start..end,
+            });
+        }
+        Ok(Self { item, call_sites })
+    }
+}
+
+fn range_from_lsp(buffer: &Buffer, range: lsp::Range) -> Range<Anchor> {
+    let start = buffer.clip_point_utf16(point_from_lsp(range.start), Bias::Left);
+    let end = buffer.clip_point_utf16(point_from_lsp(range.end), Bias::Left);
+    buffer.anchor_after(start)..buffer.anchor_before(end)
+}
+
+fn location_to_proto(
+    location: &Location,
+    lsp_store: &mut LspStore,
+    peer_id: PeerId,
+    cx: &mut App,
+) -> proto::Location {
+    lsp_store
+        .buffer_store()
+        .update(cx, |buffer_store, cx| {
+            buffer_store.create_buffer_for_peer(&location.buffer, peer_id, cx)
+        })
+        .detach_and_log_err(cx);
+    proto::Location {
+        buffer_id: location.buffer.read(cx).remote_id().into(),
+        start: Some(serialize_anchor(&location.range.start)),
+        end: Some(serialize_anchor(&location.range.end)),
+    }
+}
+
+pub(super) async fn hierarchy_items_from_lsp<T: Serialize>(
+    lsp_items: Option<Vec<T>>,
+    lsp_store: Entity<LspStore>,
+    buffer: Entity<Buffer>,
+    server_id: LanguageServerId,
+    mut cx: AsyncApp,
+) -> Result<Vec<HierarchyItem>> {
+    let mut items = Vec::new();
+    for lsp_item in lsp_items.into_iter().flatten() {
+        let lsp_item = serde_json::to_value(lsp_item)?;
+        items.push(
+            HierarchyItem::from_lsp(lsp_item, &lsp_store, &buffer, server_id, &mut cx).await?,
+        );
+    }
+    Ok(items)
+}
+
+pub(super) fn hierarchy_items_to_proto(
+    items: Vec<HierarchyItem>,
+    lsp_store: &mut LspStore,
+    peer_id: PeerId,
+    cx: &mut App,
+) -> Vec<proto::HierarchyItem> {
+    items
+        .iter()
+        .map(|item| item.to_proto(lsp_store, peer_id, cx))
+        .collect()
+}
+
+pub(super) async fn hierarchy_items_from_proto(
+    proto_items: Vec<proto::HierarchyItem>,
+    lsp_store: Entity<LspStore>,
+    mut cx: AsyncApp,
+) -> Result<Vec<HierarchyItem>> {
+    let mut items = Vec::with_capacity(proto_items.len());
+    for item in proto_items {
+        items.push(HierarchyItem::from_proto(item, &lsp_store, &mut cx).await?);
+    }
+    Ok(items)
+}
+
+pub(super) fn hierarchy_calls_to_proto(
+    calls: Vec<HierarchyCall>,
+    lsp_store: &mut LspStore,
+    peer_id: PeerId,
+    cx: &mut App,
+) -> Vec<proto::HierarchyCall> {
+    calls
+        .iter()
+        .map(|call| call.to_proto(lsp_store, peer_id, cx))
+        .collect()
+}
+
+pub(super) async fn hierarchy_calls_from_proto(
+    proto_calls: Vec<proto::HierarchyCall>,
+    lsp_store: Entity<LspStore>,
+    mut cx: AsyncApp,
+) -> Result<Vec<HierarchyCall>> {
+    let mut calls = Vec::with_capacity(proto_calls.len());
+    for call in proto_calls {
+        calls.push(HierarchyCall::from_proto(call, &lsp_store, &mut cx).await?);
+    }
+    Ok(calls)
+}
@@ -0,0 +1,323 @@
+use std::{ops::Range, sync::Arc};
+
+use language::{Anchor, Bias, BufferSnapshot, PointUtf16, Unclipped};
+use lsp::{
+    SemanticTokensFullOptions, SemanticTokensLegend, SemanticTokensServerCapabilities,
+    ServerCapabilities,
+};
+
+/// The semantic tokens that a language server reported for a buffer.
+#[derive(Clone, Debug, Default)]
+pub struct BufferSemanticTokens {
+    /// The names of the token types, indexed by [`SemanticToken::token_type`].
+    pub token_types: Arc<[String]>,
+    /// The names of the token modifiers, indexed by the bits of [`SemanticToken::token_modifiers`].
+    pub token_modifiers: Arc<[String]>,
+    /// The tokens, ordered by their position in the buffer.
+    pub tokens: Vec<SemanticToken>,
+}
+
+#[derive(Clone, Debug)]
+pub struct SemanticToken {
+    pub range: Range<Anchor>,
+    pub token_type: u32,
+    pub token_modifiers: u32,
+}
+
+impl BufferSemanticTokens {
+    /// Resolves the tokens encoded in the LSP's relative format against the buffer.
+    pub(crate) fn new(
+        legend: &SemanticTokensLegend,
+        data: &[lsp::SemanticToken],
+        snapshot: &BufferSnapshot,
+    ) -> Self {
+        let mut tokens = Vec::with_capacity(data.len());
+        let mut row = 0;
+        let mut column = 0;
+        for token in data {
+            if token.delta_line == 0 {
+                column += token.delta_start;
+            } else {
+                row += token.delta_line;
+                column = token.delta_start;
+            }
+            let start =
+                snapshot.clip_point_utf16(Unclipped(PointUtf16::new(row, column)), Bias::Left);
+            let end = snapshot.clip_point_utf16(
+                Unclipped(PointUtf16::new(row, column + token.length)),
+                Bias::Right,
+            );
+            if start == end {
+                continue;
+            }
+            tokens.push(SemanticToken {
+                range: snapshot.anchor_after(start)..snapshot.anchor_before(end),
+                token_type: token.token_type,
+                token_modifiers: token.token_modifiers_bitset,
+            });
+        }
+
+        Self {
+            token_types: legend
+                .token_types
+                .iter()
+                .map(|token_type| token_type.as_str().to_string())
+                .collect(),
+            token_modifiers: legend
+                .token_modifiers
+                .iter()
+                .map(|token_modifier| token_modifier.as_str().to_string())
+                .collect(),
+            tokens,
+        }
+    }
+
+    pub fn token_type(&self, token: &SemanticToken) -> Option<&str> {
+        self.token_types
+            .get(token.token_type as usize)
+            .map(String::as_str)
+    }
+
+    pub fn token_modifiers<'a>(&'a self, token: &SemanticToken) -> impl Iterator<Item = &'a str> {
+        let bitset = token.token_modifiers;
+        self.token_modifiers
+            .iter()
+            .enumerate()
+            .filter(move |(ix, _)| *ix < 32 && bitset & (1 << ix) != 0)
+            .map(|(_, token_modifier)| token_modifier.as_str())
+    }
+}
+
+/// The last tokens a language server reported for a buffer, which its next response may be a
+/// delta against.
+#[derive(Debug)]
+pub(crate) struct LspSemanticTokens {
+    pub result_id: String,
+    pub data: Vec<lsp::SemanticToken>,
+}
+
+impl LspSemanticTokens {
+    /// Returns the result id and the tokens of a delta response, or `None` if its edits don't
+    /// apply to these tokens.
+    pub(crate) fn apply_delta(
+        self,
+        response: lsp::SemanticTokensFullDeltaResult,
+    ) -> Option<(Option<String>, Vec<lsp::SemanticToken>)> {
+        match response {
+            lsp::SemanticTokensFullDeltaResult::Tokens(tokens) => {
+                Some((tokens.result_id, tokens.data))
+            }
+            lsp::SemanticTokensFullDeltaResult::TokensDelta(delta) => {
+                let data = apply_semantic_token_edits(self.data, delta.edits)?;
+                Some((delta.result_id, data))
+            }
+            lsp::SemanticTokensFullDeltaResult::PartialTokensDelta { edits } => {
+                let data = apply_semantic_token_edits(self.data, edits)?;
+                Some((None, data))
+            }
+        }
+    }
+}
+
+/// Applies the edits of a delta response. Their offsets count the integers of the encoded
+/// tokens, so edits that don't fall on token boundaries are rejected.
+fn apply_semantic_token_edits(
+    mut data: Vec<lsp::SemanticToken>,
+    mut edits: Vec<lsp::SemanticTokensEdit>,
+) -> Option<Vec<lsp::SemanticToken>> {
+    const INTEGERS_PER_TOKEN: u32 = 5;
+
+    // Apply the edits back to front, so that their offsets remain valid.
+    edits.sort_by_key(|edit| std::cmp::Reverse(edit.start));
+    for edit in edits {
+        if edit.start % INTEGERS_PER_TOKEN != 0 || edit.delete_count % INTEGERS_PER_TOKEN != 0 {
+            return None;
+        }
+        let start = (edit.start / INTEGERS_PER_TOKEN) as usize;
+        let end = start + (edit.delete_count / INTEGERS_PER_TOKEN) as usize;
+        if end > data.len() {
+            return None;
+        }
+        data.splice(start..end, edit.data.unwrap_or_default());
+    }
+    Some(data)
+}
+
+fn semantic_tokens_options(
+    capabilities: &ServerCapabilities,
+) -> Option<&lsp::SemanticTokensOptions> {
+    match capabilities.semantic_tokens_provider.as_ref()? {
+        SemanticTokensServerCapabilities::SemanticTokensOptions(options) => Some(options),
+        SemanticTokensServerCapabilities::SemanticTokensRegistrationOptions(options) => {
+            Some(&options.semantic_tokens_options)
+        }
+    }
+}
+
+pub(crate) fn semantic_tokens_legend(
+    capabilities: &ServerCapabilities,
+) -> Option<&SemanticTokensLegend> {
+    Some(&semantic_tokens_options(capabilities)?.legend)
+}
+
+pub(crate) fn supports_full_semantic_tokens(capabilities: &ServerCapabilities) -> bool {
+    semantic_tokens_options(capabilities).is_some_and(|options| match options.full {
+        Some(SemanticTokensFullOptions::Bool(full)) => full,
+        Some(SemanticTokensFullOptions::Delta { .. }) => true,
+        None => false,
+    })
+}
+
+pub(crate) fn supports_semantic_tokens_delta(capabilities: &ServerCapabilities) -> bool {
+    semantic_tokens_options(capabilities).is_some_and(|options| {
+        matches!(
+            options.full,
+            Some(SemanticTokensFullOptions::Delta { delta: Some(true) })
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{AppContext as _, TestAppContext};
+    use language::{Buffer, ToPoint as _};
+    use lsp::{SemanticTokenModifier, SemanticTokenType, SemanticTokensEdit};
+
+    fn token(delta_line: u32, delta_start: u32, length: u32) -> lsp::SemanticToken {
+        lsp::SemanticToken {
+            delta_line,
+            delta_start,
+            length,
+            token_type: 0,
+            token_modifiers_bitset: 0,
+        }
+    }
+
+    #[test]
+    fn test_apply_semantic_token_edits() {
+        let data = vec![token(0, 0, 1), token(0, 2, 1), token(1, 0, 1)];
+
+        assert_eq!(
+            apply_semantic_token_edits(
+                data.clone(),
+                vec![
+                    SemanticTokensEdit {
+                        start: 0,
+                        delete_count: 5,
+                        data: Some(vec![token(0, 0, 2)]),
+                    },
+                    SemanticTokensEdit {
+                        start: 10,
+                        delete_count: 5,
+                        data: None,
+                    },
+                ],
+            ),
+            Some(vec![token(0, 0, 2), token(0, 2, 1)])
+        );
+        assert_eq!(
+            apply_semantic_token_edits(
+                data.clone(),
+                vec![SemanticTokensEdit {
+                    start: 15,
+                    delete_count: 0,
+                    data: Some(vec![token(2, 4, 3)]),
+                }],
+            ),
+            Some(vec![
+                token(0, 0, 1),
+                token(0, 2, 1),
+                token(1, 0, 1),
+                token(2, 4, 3)
+            ])
+        );
+        assert_eq!(
+            apply_semantic_token_edits(
+                data.clone(),
+                vec![SemanticTokensEdit {
+                    start: 3,
+                    delete_count: 5,
+                    data: None,
+                }],
+            ),
+            None
+        );
+        assert_eq!(
+            apply_semantic_token_edits(
+                data,
+                vec![SemanticTokensEdit {
+                    start: 10,
+                    delete_count: 10,
+                    data: None,
+                }],
+            ),
+            None
+        );
+    }
+
+    #[gpui::test]
+    fn test_decode_semantic_tokens(cx: &mut TestAppContext) {
+        let buffer = cx.new(|cx| Buffer::local("fn main() {\n    let x = 1;\n}\n", cx));
+        let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot());
+        let legend = SemanticTokensLegend {
+            token_types: vec![SemanticTokenType::FUNCTION, SemanticTokenType::VARIABLE],
+            token_modifiers: vec![
+                SemanticTokenModifier::DECLARATION,
+                SemanticTokenModifier::READONLY,
+            ],
+        };
+        let data = [
+            lsp::SemanticToken {
+                delta_line: 0,
+                delta_start: 3,
+                length: 4,
+                token_type: 0,
+                token_modifiers_bitset: 0b01,
+            },
+            lsp::SemanticToken {
+                delta_line: 1,
+                delta_start: 8,
+                length: 1,
+                token_type: 1,
+                token_modifiers_bitset: 0b11,
+            },
+            // Tokens past the end of the line are dropped.
+            lsp::SemanticToken {
+                delta_line: 0,
+                delta_start: 10,
+                length: 5,
+                token_type: 1,
+                token_modifiers_bitset: 0,
+            },
+        ];
+
+        let tokens = BufferSemanticTokens::new(&legend, &data, &snapshot);
+        let decoded = tokens
+            .tokens
+            .iter()
+            .map(|token| {
+                (
+                    token.range.start.to_point(&snapshot)..token.range.end.to_point(&snapshot),
+                    tokens.token_type(token),
+                    tokens.token_modifiers(token).collect::<Vec<_>>(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            decoded,
+            [
+                (
+                    language::Point::new(0, 3)..language::Point::new(0, 7),
+                    Some("function"),
+                    vec!["declaration"],
+                ),
+                (
+                    language::Point::new(1, 8)..language::Point::new(1, 9),
+                    Some("variable"),
+                    vec!["declaration", "readonly"],
+                ),
+            ]
+        );
+    }
+}
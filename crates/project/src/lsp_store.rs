@@ -101,6 +101,7 @@ pub use worktree::{
 
 const SERVER_LAUNCHING_BEFORE_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
 pub const SERVER_PROGRESS_THROTTLE_TIMEOUT: Duration = Duration::from_millis(100);
+const DIAGNOSTIC_PULL_DEBOUNCE: Duration = Duration::from_millis(125);
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum FormatTrigger {
@@ -158,6 +159,12 @@ pub struct LocalLspStore {
         >,
     >,
     buffer_snapshots: HashMap<BufferId, HashMap<LanguageServerId, Vec<LspBufferSnapshot>>>, // buffer_id -> server_id -> vec of snapshots
+    semantic_tokens: HashMap<BufferId, HashMap<LanguageServerId, LspSemanticTokens>>,
+    /// The result ids of the diagnostics pulled from each language server, by file, which let
+    /// the server skip the files whose diagnostics didn't change.
+    pulled_diagnostic_result_ids: HashMap<LanguageServerId, HashMap<PathBuf, String>>,
+    document_diagnostic_pulls: HashMap<BufferId, Task<()>>,
+    workspace_diagnostic_pulls: HashMap<LanguageServerId, Task<()>>,
     _subscription: gpui::Subscription,
     lsp_tree: Entity<LanguageServerTree>,
     registered_buffers: HashMap<BufferId, usize>,
@@ -465,10 +472,13 @@ impl LocalLspStore {
                                 adapter.process_diagnostics(&mut params, server_id, buffer);
                             }
 
-                            this.update_diagnostics(
+                            // Published diagnostics replace the previously published ones, but
+                            // keep those the server reported on request.
+                            this.merge_diagnostics(
                                 server_id,
                                 params,
                                 &adapter.disk_based_diagnostic_sources,
+                                |diagnostic| diagnostic.is_pulled,
                                 cx,
                             )
                             .log_err();
@@ -844,6 +854,44 @@ impl LocalLspStore {
             })
             .detach();
 
+        language_server
+            .on_request::<lsp::request::SemanticTokensRefresh, _, _>({
+                let this = this.clone();
+                move |(), cx| {
+                    let this = this.clone();
+                    let mut cx = cx.clone();
+                    async move {
+                        this.update(&mut cx, |this, cx| {
+                            cx.emit(LspStoreEvent::RefreshSemanticTokens);
+                            this.downstream_client.as_ref().map(|(client, project_id)| {
+                                client.send(proto::RefreshSemanticTokens {
+                                    project_id: *project_id,
+                                })
+                            })
+                        })?
+                        .transpose()?;
+                        Ok(())
+                    }
+                }
+            })
+            .detach();
+
+        language_server
+            .on_request::<lsp::request::WorkspaceDiagnosticRefresh, _, _>({
+                let this = this.clone();
+                move |(), cx| {
+                    let this = this.clone();
+                    let mut cx = cx.clone();
+                    async move {
+                        this.update(&mut cx, |this, cx| {
+                            this.pull_diagnostics_for_server(server_id, cx);
+                        })?;
+                        Ok(())
+                    }
+                }
+            })
+            .detach();
+
         language_server
             .on_request::<lsp::request::ShowMessageRequest, _, _>({
                 let this = this.clone();
@@ -2434,6 +2482,8 @@ impl LocalLspStore {
     ) {
         buffer.update(cx, |buffer, cx| {
             let _ = self.buffer_snapshots.remove(&buffer.remote_id());
+            let _ = self.semantic_tokens.remove(&buffer.remote_id());
+            let _ = self.document_diagnostic_pulls.remove(&buffer.remote_id());
 
             for (_, language_server) in self.language_servers_for_buffer(buffer, cx) {
                 language_server.unregister_buffer(file_url.clone());
@@ -3350,6 +3400,7 @@ pub enum LspStoreEvent {
     Notification(String),
     RefreshInlayHints,
     RefreshCodeLens,
+    RefreshSemanticTokens,
     DiagnosticsUpdated {
         language_server_id: LanguageServerId,
         path: ProjectPath,
@@ -3403,9 +3454,12 @@ impl LspStore {
         client.add_entity_request_handler(Self::handle_inlay_hints);
         client.add_entity_request_handler(Self::handle_get_project_symbols);
         client.add_entity_request_handler(Self::handle_resolve_inlay_hint);
+        client.add_entity_request_handler(Self::handle_resolve_code_lens);
         client.add_entity_request_handler(Self::handle_open_buffer_for_symbol);
         client.add_entity_request_handler(Self::handle_refresh_inlay_hints);
         client.add_entity_request_handler(Self::handle_refresh_code_lens);
+        client.add_entity_request_handler(Self::handle_semantic_tokens);
+        client.add_entity_request_handler(Self::handle_refresh_semantic_tokens);
         client.add_entity_request_handler(Self::handle_on_type_formatting);
         client.add_entity_request_handler(Self::handle_apply_additional_edits_for_completion);
         client.add_entity_request_handler(Self::handle_register_buffer_with_language_servers);
@@ -3423,6 +3477,13 @@ impl LspStore {
         client.add_entity_request_handler(Self::handle_lsp_command::<PrepareRename>);
         client.add_entity_request_handler(Self::handle_lsp_command::<PerformRename>);
         client.add_entity_request_handler(Self::handle_lsp_command::<LinkedEditingRange>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<PrepareCallHierarchy>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetIncomingCalls>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetOutgoingCalls>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<PrepareTypeHierarchy>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetSupertypes>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetSubtypes>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetFoldingRanges>);
 
         client.add_entity_request_handler(Self::handle_lsp_command::<lsp_ext_command::ExpandMacro>);
         client.add_entity_request_handler(Self::handle_lsp_command::<lsp_ext_command::OpenDocs>);
@@ -3523,6 +3584,10 @@ impl LspStore {
                 language_server_watcher_registrations: Default::default(),
                 buffers_being_formatted: Default::default(),
                 buffer_snapshots: Default::default(),
+                semantic_tokens: Default::default(),
+                pulled_diagnostic_result_ids: Default::default(),
+                document_diagnostic_pulls: Default::default(),
+                workspace_diagnostic_pulls: Default::default(),
                 prettier_store,
                 environment,
                 http_client,
@@ -3728,11 +3793,13 @@ impl LspStore {
     ) {
         match event {
             language::BufferEvent::Edited { .. } => {
-                self.on_buffer_edited(buffer, cx);
+                self.on_buffer_edited(buffer.clone(), cx);
+                self.pull_document_diagnostics(buffer, true, cx);
             }
 
             language::BufferEvent::Saved => {
-                self.on_buffer_saved(buffer, cx);
+                self.on_buffer_saved(buffer.clone(), cx);
+                self.pull_diagnostics_after_save(buffer, cx);
             }
 
             _ => {}
@@ -3883,6 +3950,7 @@ impl LspStore {
 
             if ignore_refcounts || *refcount == 1 {
                 local.register_buffer_with_language_servers(buffer, cx);
+                self.pull_document_diagnostics(buffer.clone(), false, cx);
             }
             if !ignore_refcounts {
                 cx.observe_release(&handle, move |this, buffer, cx| {
@@ -4631,6 +4699,50 @@ impl LspStore {
         }
     }
 
+    /// Resolves the command of a code lens that was sent without one, which holds its title.
+    pub fn resolve_code_lens(
+        &self,
+        buffer_handle: Entity<Buffer>,
+        lens: CodeAction,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<CodeAction>> {
+        if lens.resolved {
+            return Task::ready(Ok(lens));
+        }
+        if let Some((upstream_client, project_id)) = self.upstream_client() {
+            let request = proto::ResolveCodeLens {
+                project_id,
+                buffer_id: buffer_handle.read(cx).remote_id().into(),
+                lens: Some(Self::serialize_code_action(&lens)),
+            };
+            cx.spawn(async move |_, _| {
+                let response = upstream_client
+                    .request(request)
+                    .await
+                    .context("code lens proto request")?;
+                match response.lens {
+                    Some(resolved_lens) => Self::deserialize_code_action(resolved_lens)
+                        .context("code lens proto resolve response conversion"),
+                    None => Ok(lens),
+                }
+            })
+        } else {
+            let Some(lang_server) = buffer_handle.update(cx, |buffer, cx| {
+                self.language_server_for_local_buffer(buffer, lens.server_id, cx)
+                    .map(|(_, server)| server.clone())
+            }) else {
+                return Task::ready(Ok(lens));
+            };
+            cx.spawn(async move |_, _| {
+                let mut lens = lens;
+                LocalLspStore::try_resolve_code_action(&lang_server, &mut lens)
+                    .await
+                    .context("code lens resolve LSP request")?;
+                Ok(lens)
+            })
+        }
+    }
+
     pub(crate) fn linked_edit(
         &mut self,
         buffer: &Entity<Buffer>,
@@ -5585,6 +5697,549 @@ impl LspStore {
         }
     }
 
+    /// Returns the semantic tokens of the buffer, asking the language server for the changes since
+    /// its last response when it supports that.
+    pub fn semantic_tokens(
+        &mut self,
+        buffer_handle: &Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<BufferSemanticTokens>> {
+        if let Some((client, project_id)) = self.upstream_client() {
+            let command = GetSemanticTokens {
+                snapshot: buffer_handle.read(cx).snapshot(),
+            };
+            let request = command.to_proto(project_id, buffer_handle.read(cx));
+            let buffer_handle = buffer_handle.clone();
+            return cx.spawn(async move |lsp_store, cx| {
+                let response = client
+                    .request(request)
+                    .await
+                    .context("semantic tokens proto request")?;
+                command
+                    .response_from_proto(
+                        response,
+                        lsp_store.upgrade().ok_or_else(|| anyhow!("No LSP store"))?,
+                        buffer_handle,
+                        cx.clone(),
+                    )
+                    .await
+                    .context("semantic tokens proto response conversion")
+            });
+        }
+
+        let language_server = buffer_handle.update(cx, |buffer, cx| {
+            self.as_local()?
+                .language_servers_for_buffer(buffer, cx)
+                .find(|(_, server)| {
+                    supports_full_semantic_tokens(
+                        &server.adapter_server_capabilities().server_capabilities,
+                    )
+                })
+                .map(|(_, server)| server.clone())
+        });
+        let Some(language_server) = language_server else {
+            return Task::ready(Ok(BufferSemanticTokens::default()));
+        };
+        let server_id = language_server.server_id();
+        let buffer = buffer_handle.read(cx);
+        let buffer_id = buffer.remote_id();
+        let snapshot = buffer.snapshot();
+        let previous_result_id = self
+            .as_local()
+            .and_then(|local| local.semantic_tokens.get(&buffer_id)?.get(&server_id))
+            .map(|previous| previous.result_id.clone())
+            .filter(|_| supports_semantic_tokens_delta(&language_server.capabilities()));
+        let text_document = File::from_dyn(buffer.file())
+            .and_then(File::as_local)
+            .and_then(|file| make_text_document_identifier(&file.abs_path(cx)).ok());
+        let (Some(previous_result_id), Some(text_document)) = (previous_result_id, text_document)
+        else {
+            return self.request_lsp(
+                buffer_handle.clone(),
+                LanguageServerToQuery::Other(server_id),
+                GetSemanticTokens { snapshot },
+                cx,
+            );
+        };
+
+        let buffer_handle = buffer_handle.clone();
+        cx.spawn(async move |lsp_store, cx| {
+            let response = language_server
+                .request::<lsp::request::SemanticTokensFullDeltaRequest>(
+                    lsp::SemanticTokensDeltaParams {
+                        text_document,
+                        previous_result_id,
+                        work_done_progress_params: Default::default(),
+                        partial_result_params: Default::default(),
+                    },
+                )
+                .await;
+            let tokens = lsp_store.update(cx, |lsp_store, _| {
+                let response = response.log_err()??;
+                let previous = lsp_store
+                    .as_local_mut()?
+                    .semantic_tokens
+                    .get_mut(&buffer_id)?
+                    .remove(&server_id)?;
+                let (result_id, data) = previous.apply_delta(response)?;
+                Some(lsp_store.update_semantic_tokens(&snapshot, server_id, result_id, data))
+            })?;
+            match tokens {
+                Some(tokens) => tokens,
+                // The delta didn't apply to the tokens we have, so start over from the full set.
+                None => {
+                    lsp_store
+                        .update(cx, |lsp_store, cx| {
+                            let snapshot = buffer_handle.read(cx).snapshot();
+                            lsp_store.request_lsp(
+                                buffer_handle,
+                                LanguageServerToQuery::Other(server_id),
+                                GetSemanticTokens { snapshot },
+                                cx,
+                            )
+                        })?
+                        .await
+                }
+            }
+        })
+    }
+
+    /// Records the tokens that a language server reported for a buffer, so that the next request
+    /// can ask for the changes to them, and resolves them against the buffer as of the request.
+    pub(crate) fn update_semantic_tokens(
+        &mut self,
+        snapshot: &BufferSnapshot,
+        server_id: LanguageServerId,
+        result_id: Option<String>,
+        data: Vec<lsp::SemanticToken>,
+    ) -> Result<BufferSemanticTokens> {
+        let language_server = self
+            .language_server_for_id(server_id)
+            .with_context(|| format!("missing language server {server_id}"))?;
+        let capabilities = language_server.capabilities();
+        let legend = semantic_tokens_legend(&capabilities)
+            .context("language server did not provide a semantic tokens legend")?;
+        let tokens = BufferSemanticTokens::new(legend, &data, snapshot);
+
+        if let Some(local) = self.as_local_mut() {
+            let buffer_tokens = local
+                .semantic_tokens
+                .entry(snapshot.remote_id())
+                .or_default();
+            match result_id {
+                Some(result_id) => {
+                    buffer_tokens.insert(server_id, LspSemanticTokens { result_id, data });
+                }
+                None => {
+                    buffer_tokens.remove(&server_id);
+                }
+            }
+        }
+        Ok(tokens)
+    }
+
+    /// Pulls the diagnostics of the buffer from its language servers that report them on request.
+    fn pull_document_diagnostics(
+        &mut self,
+        buffer: Entity<Buffer>,
+        debounce: bool,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(local) = self.as_local_mut() else {
+            return;
+        };
+        let buffer_id = buffer.read(cx).remote_id();
+        if !local.registered_buffers.contains_key(&buffer_id) {
+            return;
+        }
+        let supports_pull = buffer.update(cx, |buffer, cx| {
+            local
+                .language_servers_for_buffer(buffer, cx)
+                .any(|(_, server)| diagnostic_options(server).is_some())
+        });
+        if !supports_pull {
+            return;
+        }
+
+        let task = cx.spawn(async move |lsp_store, cx| {
+            if debounce {
+                cx.background_executor()
+                    .timer(DIAGNOSTIC_PULL_DEBOUNCE)
+                    .await;
+            }
+            let Ok(requests) = lsp_store.update(cx, |lsp_store, cx| {
+                lsp_store
+                    .document_diagnostic_requests(&buffer, cx)
+                    .into_iter()
+                    .map(|(server, params, version)| {
+                        let document_versions =
+                            lsp_store.lsp_document_versions(server.server_id(), cx);
+                        (server, params, version, document_versions)
+                    })
+                    .collect::<Vec<_>>()
+            }) else {
+                return;
+            };
+            let responses = join_all(requests.into_iter().map(
+                |(server, params, version, document_versions)| async move {
+                    let uri = params.text_document.uri.clone();
+                    let response = server
+                        .request::<lsp::request::DocumentDiagnosticRequest>(params)
+                        .await;
+                    (
+                        server.server_id(),
+                        uri,
+                        version,
+                        document_versions,
+                        response,
+                    )
+                },
+            ))
+            .await;
+            lsp_store
+                .update(cx, |lsp_store, cx| {
+                    for (server_id, uri, version, document_versions, response) in responses {
+                        let Some(report) =
+                            response.context("pulling document diagnostics").log_err()
+                        else {
+                            continue;
+                        };
+                        lsp_store
+                            .apply_document_diagnostic_report(
+                                server_id,
+                                uri,
+                                version,
+                                &document_versions,
+                                report,
+                                cx,
+                            )
+                            .log_err();
+                    }
+                })
+                .ok();
+        });
+        if let Some(local) = self.as_local_mut() {
+            local.document_diagnostic_pulls.insert(buffer_id, task);
+        }
+    }
+
+    fn document_diagnostic_requests(
+        &self,
+        buffer: &Entity<Buffer>,
+        cx: &mut App,
+    ) -> Vec<(
+        Arc<LanguageServer>,
+        lsp::DocumentDiagnosticParams,
+        Option<i32>,
+    )> {
+        let Some(local) = self.as_local() else {
+            return Vec::new();
+        };
+        buffer.update(cx, |buffer, cx| {
+            let Some(abs_path) = File::from_dyn(buffer.file())
+                .and_then(File::as_local)
+                .map(|file| file.abs_path(cx))
+            else {
+                return Vec::new();
+            };
+            let Some(uri) = lsp::Url::from_file_path(&abs_path).log_err() else {
+                return Vec::new();
+            };
+            let buffer_id = buffer.remote_id();
+            local
+                .language_servers_for_buffer(buffer, cx)
+                .filter_map(|(_, server)| {
+                    let options = diagnostic_options(server)?;
+                    let server_id = server.server_id();
+                    // Diagnostics are reported against the version of the document that the
+                    // server had when it received the request.
+                    let version = local
+                        .buffer_snapshots
+                        .get(&buffer_id)?
+                        .get(&server_id)?
+                        .last()?
+                        .version;
+                    let previous_result_id = local
+                        .pulled_diagnostic_result_ids
+                        .get(&server_id)
+                        .and_then(|result_ids| result_ids.get(&abs_path))
+                        .cloned();
+                    let params = lsp::DocumentDiagnosticParams {
+                        text_document: lsp::TextDocumentIdentifier::new(uri.clone()),
+                        identifier: options.identifier,
+                        previous_result_id,
+                        work_done_progress_params: Default::default(),
+                        partial_result_params: Default::default(),
+                    };
+                    Some((server.clone(), params, Some(version)))
+                })
+                .collect()
+        })
+    }
+
+    /// Returns the versions of the open documents that the language server last received, by path.
+    fn lsp_document_versions(
+        &self,
+        server_id: LanguageServerId,
+        cx: &App,
+    ) -> HashMap<PathBuf, i32> {
+        let Some(local) = self.as_local() else {
+            return HashMap::default();
+        };
+        let buffer_store = self.buffer_store.read(cx);
+        local
+            .buffer_snapshots
+            .iter()
+            .filter_map(|(buffer_id, snapshots)| {
+                let version = snapshots.get(&server_id)?.last()?.version;
+                let buffer = buffer_store.get(*buffer_id)?;
+                let abs_path = File::from_dyn(buffer.read(cx).file())?
+                    .as_local()?
+                    .abs_path(cx);
+                Some((abs_path, version))
+            })
+            .collect()
+    }
+
+    /// Pulls the diagnostics of the whole workspace from the language server, if it reports
+    /// them on request.
+    ///
+    /// Starting a pull replaces the pending one, which cancels its request.
+    fn pull_workspace_diagnostics(
+        &mut self,
+        server_id: LanguageServerId,
+        debounce: bool,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(local) = self.as_local_mut() else {
+            return;
+        };
+        let Some(server) = local.running_language_server_for_id(server_id).cloned() else {
+            return;
+        };
+        let Some(options) =
+            diagnostic_options(&server).filter(|options| options.workspace_diagnostics)
+        else {
+            return;
+        };
+        let previous_result_ids = local
+            .pulled_diagnostic_result_ids
+            .get(&server_id)
+            .into_iter()
+            .flatten()
+            .filter_map(|(abs_path, result_id)| {
+                Some(lsp::PreviousResultId {
+                    uri: lsp::Url::from_file_path(abs_path).ok()?,
+                    value: result_id.clone(),
+                })
+            })
+            .collect();
+
+        let task = cx.spawn(async move |lsp_store, cx| {
+            if debounce {
+                cx.background_executor()
+                    .timer(DIAGNOSTIC_PULL_DEBOUNCE)
+                    .await;
+            }
+            let response = server
+                .request::<lsp::request::WorkspaceDiagnosticRequest>(
+                    lsp::WorkspaceDiagnosticParams {
+                        identifier: options.identifier,
+                        previous_result_ids,
+                        work_done_progress_params: Default::default(),
+                        partial_result_params: Default::default(),
+                    },
+                )
+                .await;
+            let Some(response) = response.context("pulling workspace diagnostics").log_err() else {
+                return;
+            };
+            let items = match response {
+                lsp::WorkspaceDiagnosticReportResult::Report(report) => report.items,
+                lsp::WorkspaceDiagnosticReportResult::Partial(report) => report.items,
+            };
+            lsp_store
+                .update(cx, |lsp_store, cx| {
+                    if let Some(local) = lsp_store.as_local_mut() {
+                        local.workspace_diagnostic_pulls.remove(&server_id);
+                    }
+                    for item in items {
+                        let (uri, version, report) = match item {
+                            lsp::WorkspaceDocumentDiagnosticReport::Full(report) => (
+                                report.uri,
+                                report.version,
+                                lsp::DocumentDiagnosticReportKind::Full(
+                                    report.full_document_diagnostic_report,
+                                ),
+                            ),
+                            lsp::WorkspaceDocumentDiagnosticReport::Unchanged(report) => (
+                                report.uri,
+                                report.version,
+                                lsp::DocumentDiagnosticReportKind::Unchanged(
+                                    report.unchanged_document_diagnostic_report,
+                                ),
+                            ),
+                        };
+                        let version = version.and_then(|version| i32::try_from(version).ok());
+                        lsp_store
+                            .apply_diagnostic_report(server_id, uri, version, report, cx)
+                            .log_err();
+                    }
+                })
+                .ok();
+        });
+        local.workspace_diagnostic_pulls.insert(server_id, task);
+    }
+
+    /// Pulls the diagnostics of every open buffer that the language server handles, along with
+    /// those of the whole workspace.
+    fn pull_diagnostics_for_server(&mut self, server_id: LanguageServerId, cx: &mut Context<Self>) {
+        let Some(local) = self.as_local() else {
+            return;
+        };
+        let buffers = self.buffer_store.read(cx).buffers().collect::<Vec<_>>();
+        let buffers = buffers
+            .into_iter()
+            .filter(|buffer| {
+                buffer.update(cx, |buffer, cx| {
+                    local.registered_buffers.contains_key(&buffer.remote_id())
+                        && local
+                            .language_server_ids_for_buffer(buffer, cx)
+                            .contains(&server_id)
+                })
+            })
+            .collect::<Vec<_>>();
+        for buffer in buffers {
+            self.pull_document_diagnostics(buffer, false, cx);
+        }
+        self.pull_workspace_diagnostics(server_id, false, cx);
+    }
+
+    /// Saving a file may change the diagnostics of the files that depend on it, so pull those of
+    /// the whole workspace too, from the servers that report them.
+    fn pull_diagnostics_after_save(&mut self, buffer: Entity<Buffer>, cx: &mut Context<Self>) {
+        let Some(local) = self.as_local() else {
+            return;
+        };
+        let server_ids = buffer.update(cx, |buffer, cx| {
+            local
+                .language_servers_for_buffer(buffer, cx)
+                .filter(|(_, server)| {
+                    diagnostic_options(server).is_some_and(|options| options.workspace_diagnostics)
+                })
+                .map(|(_, server)| server.server_id())
+                .collect::<Vec<_>>()
+        });
+        self.pull_document_diagnostics(buffer, true, cx);
+        for server_id in server_ids {
+            // Debounced, so that saving several files at once pulls only once.
+            self.pull_workspace_diagnostics(server_id, true, cx);
+        }
+    }
+
+    fn apply_document_diagnostic_report(
+        &mut self,
+        server_id: LanguageServerId,
+        uri: lsp::Url,
+        version: Option<i32>,
+        document_versions: &HashMap<PathBuf, i32>,
+        report: lsp::DocumentDiagnosticReportResult,
+        cx: &mut Context<Self>,
+    ) -> Result<()> {
+        let (report, related_documents) = match report {
+            lsp::DocumentDiagnosticReportResult::Report(lsp::DocumentDiagnosticReport::Full(
+                report,
+            )) => (
+                Some(lsp::DocumentDiagnosticReportKind::Full(
+                    report.full_document_diagnostic_report,
+                )),
+                report.related_documents,
+            ),
+            lsp::DocumentDiagnosticReportResult::Report(
+                lsp::DocumentDiagnosticReport::Unchanged(report),
+            ) => (
+                Some(lsp::DocumentDiagnosticReportKind::Unchanged(
+                    report.unchanged_document_diagnostic_report,
+                )),
+                report.related_documents,
+            ),
+            lsp::DocumentDiagnosticReportResult::Partial(report) => {
+                (None, report.related_documents)
+            }
+        };
+        if let Some(report) = report {
+            self.apply_diagnostic_report(server_id, uri, version, report, cx)?;
+        }
+        // The server reports the diagnostics of the related documents against the versions it
+        // had when it received the request.
+        for (uri, report) in related_documents.into_iter().flatten() {
+            let version = uri
+                .to_file_path()
+                .ok()
+                .and_then(|abs_path| document_versions.get(&abs_path).copied());
+            self.apply_diagnostic_report(server_id, uri, version, report, cx)?;
+        }
+        Ok(())
+    }
+
+    /// Replaces the diagnostics of the file with the pulled ones, unless the server reported that
+    /// they didn't change since the last pull.
+    fn apply_diagnostic_report(
+        &mut self,
+        server_id: LanguageServerId,
+        uri: lsp::Url,
+        version: Option<i32>,
+        report: lsp::DocumentDiagnosticReportKind,
+        cx: &mut Context<Self>,
+    ) -> Result<()> {
+        let abs_path = uri
+            .to_file_path()
+            .map_err(|_| anyhow!("URI is not a file"))?;
+        let (result_id, diagnostics) = match report {
+            lsp::DocumentDiagnosticReportKind::Full(report) => {
+                (report.result_id, Some(report.items))
+            }
+            lsp::DocumentDiagnosticReportKind::Unchanged(report) => (Some(report.result_id), None),
+        };
+        if let Some(local) = self.as_local_mut() {
+            let result_ids = local
+                .pulled_diagnostic_result_ids
+                .entry(server_id)
+                .or_default();
+            match result_id {
+                Some(result_id) => {
+                    result_ids.insert(abs_path.clone(), result_id);
+                }
+                None => {
+                    result_ids.remove(&abs_path);
+                }
+            }
+        }
+        let Some(diagnostics) = diagnostics else {
+            return Ok(());
+        };
+
+        let adapter = self
+            .language_server_adapter_for_id(server_id)
+            .with_context(|| format!("missing language server {server_id}"))?;
+        let mut params = lsp::PublishDiagnosticsParams {
+            uri,
+            diagnostics,
+            version,
+        };
+        adapter.process_diagnostics(&mut params, server_id, self.get_buffer(&abs_path, cx));
+        // Pulled diagnostics replace the previously pulled ones, but keep those the server
+        // published on its own.
+        self.merge_lsp_diagnostics(
+            server_id,
+            params,
+            true,
+            &adapter.disk_based_diagnostic_sources,
+            |diagnostic| !diagnostic.is_pulled,
+            cx,
+        )
+    }
+
     pub fn signature_help<T: ToPointUtf16>(
         &mut self,
         buffer: &Entity<Buffer>,
@@ -6405,6 +7060,24 @@ impl LspStore {
                 diagnostics.clone(),
                 cx,
             )?;
+        } else if let Some(local) = self.as_local() {
+            // Without an open buffer, the diagnostics to keep are only stored in the worktree.
+            diagnostics.extend(
+                local
+                    .diagnostics
+                    .get(&project_path.worktree_id)
+                    .and_then(|diagnostics_for_tree| diagnostics_for_tree.get(&project_path.path))
+                    .and_then(|diagnostics_by_server_id| {
+                        diagnostics_by_server_id
+                            .binary_search_by_key(&server_id, |e| e.0)
+                            .ok()
+                            .map(|ix| &diagnostics_by_server_id[ix].1)
+                    })
+                    .into_iter()
+                    .flatten()
+                    .filter(|entry| filter(&entry.diagnostic))
+                    .cloned(),
+            );
         }
 
         let updated = worktree.update(cx, |worktree, cx| {
@@ -7860,6 +8533,29 @@ impl LspStore {
         })
     }
 
+    async fn handle_resolve_code_lens(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::ResolveCodeLens>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::ResolveCodeLensResponse> {
+        let lens = Self::deserialize_code_action(
+            envelope
+                .payload
+                .lens
+                .context("invalid resolve code lens message: missing the code lens")?,
+        )?;
+        let buffer = this.update(&mut cx, |this, cx| {
+            let buffer_id = BufferId::new(envelope.payload.buffer_id)?;
+            this.buffer_store.read(cx).get_existing(buffer_id)
+        })??;
+        let resolved_lens = this
+            .update(&mut cx, |this, cx| this.resolve_code_lens(buffer, lens, cx))?
+            .await?;
+        Ok(proto::ResolveCodeLensResponse {
+            lens: Some(Self::serialize_code_action(&resolved_lens)),
+        })
+    }
+
     async fn handle_refresh_code_lens(
         this: Entity<Self>,
         _: TypedEnvelope<proto::RefreshCodeLens>,
@@ -7871,6 +8567,52 @@ impl LspStore {
         Ok(proto::Ack {})
     }
 
+    async fn handle_semantic_tokens(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GetSemanticTokens>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GetSemanticTokensResponse> {
+        let sender_id = envelope.original_sender_id().unwrap_or_default();
+        let buffer_id = BufferId::new(envelope.payload.buffer_id)?;
+        let buffer = this.update(&mut cx, |this, cx| {
+            this.buffer_store.read(cx).get_existing(buffer_id)
+        })??;
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&envelope.payload.version))
+            })?
+            .await
+            .with_context(|| format!("waiting for version for buffer {}", buffer.entity_id()))?;
+
+        let tokens = this
+            .update(&mut cx, |lsp_store, cx| {
+                lsp_store.semantic_tokens(&buffer, cx)
+            })?
+            .await
+            .context("semantic tokens fetch")?;
+
+        this.update(&mut cx, |lsp_store, cx| {
+            GetSemanticTokens::response_to_proto(
+                tokens,
+                lsp_store,
+                sender_id,
+                &buffer.read(cx).version(),
+                cx,
+            )
+        })
+    }
+
+    async fn handle_refresh_semantic_tokens(
+        this: Entity<Self>,
+        _: TypedEnvelope<proto::RefreshSemanticTokens>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        this.update(&mut cx, |_, cx| {
+            cx.emit(LspStoreEvent::RefreshSemanticTokens);
+        })?;
+        Ok(proto::Ack {})
+    }
+
     async fn handle_open_buffer_for_symbol(
         this: Entity<Self>,
         envelope: TypedEnvelope<proto::OpenBufferForSymbol>,
@@ -8347,6 +9089,8 @@ impl LspStore {
                 true
             }
         });
+        local.pulled_diagnostic_result_ids.remove(&server_id);
+        local.workspace_diagnostic_pulls.remove(&server_id);
         let _ = self.language_server_statuses.remove(&server_id);
         log::info!("stopping language server {name}");
         self.buffer_store.update(cx, |buffer_store, cx| {
@@ -8491,9 +9235,28 @@ impl LspStore {
     }
 
     pub fn merge_diagnostics<F: Fn(&Diagnostic) -> bool + Clone>(
+        &mut self,
+        language_server_id: LanguageServerId,
+        params: lsp::PublishDiagnosticsParams,
+        disk_based_sources: &[String],
+        filter: F,
+        cx: &mut Context<Self>,
+    ) -> Result<()> {
+        self.merge_lsp_diagnostics(
+            language_server_id,
+            params,
+            false,
+            disk_based_sources,
+            filter,
+            cx,
+        )
+    }
+
+    fn merge_lsp_diagnostics<F: Fn(&Diagnostic) -> bool + Clone>(
         &mut self,
         language_server_id: LanguageServerId,
         mut params: lsp::PublishDiagnosticsParams,
+        is_pulled: bool,
         disk_based_sources: &[String],
         filter: F,
         cx: &mut Context<Self>,
@@ -8558,6 +9321,7 @@ impl LspStore {
                         is_primary: true,
                         is_disk_based,
                         is_unnecessary,
+                        is_pulled,
                         data: diagnostic.data.clone(),
                     },
                 });
@@ -8576,6 +9340,7 @@ impl LspStore {
                                     is_primary: false,
                                     is_disk_based,
                                     is_unnecessary: false,
+                                    is_pulled,
                                     data: diagnostic.data.clone(),
                                 },
                             });
@@ -8776,6 +9541,7 @@ impl LspStore {
             }
         });
 
+        self.pull_diagnostics_for_server(server_id, cx);
         cx.notify();
     }
 
@@ -10121,6 +10887,15 @@ async fn populate_labels_for_symbols(
     }
 }
 
+fn diagnostic_options(server: &lsp::LanguageServer) -> Option<lsp::DiagnosticOptions> {
+    match server.capabilities().diagnostic_provider? {
+        lsp::DiagnosticServerCapabilities::Options(options) => Some(options),
+        lsp::DiagnosticServerCapabilities::RegistrationOptions(options) => {
+            Some(options.diagnostic_options)
+        }
+    }
+}
+
 fn include_text(server: &lsp::LanguageServer) -> Option<bool> {
     match server.capabilities().text_document_sync.as_ref()? {
         lsp::TextDocumentSyncCapability::Kind(kind) => match *kind {
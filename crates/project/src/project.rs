@@ -297,6 +297,7 @@ pub enum Event {
     Rejoined,
     RefreshInlayHints,
     RefreshCodeLens,
+    RefreshSemanticTokens,
     RevealInProjectPanel(ProjectEntryId),
     SnippetEdit(BufferId, Vec<(lsp::Range, Snippet)>),
     ExpandedAllForEntry(WorktreeId, ProjectEntryId),
@@ -646,6 +647,15 @@ pub struct DocumentHighlight {
     pub kind: DocumentHighlightKind,
 }
 
+/// A range of lines that a language server reported as foldable.
+#[derive(Clone, Debug)]
+pub struct FoldingRange {
+    /// Spans from the end of the first line of the range, which stays visible when it is folded,
+    /// to the end of its last line.
+    pub range: Range<language::Anchor>,
+    pub kind: Option<lsp::FoldingRangeKind>,
+}
+
 #[derive(Clone, Debug)]
 pub struct Symbol {
     pub language_server_name: LanguageServerName,
@@ -2708,6 +2718,7 @@ impl Project {
             }
             LspStoreEvent::RefreshInlayHints => cx.emit(Event::RefreshInlayHints),
             LspStoreEvent::RefreshCodeLens => cx.emit(Event::RefreshCodeLens),
+            LspStoreEvent::RefreshSemanticTokens => cx.emit(Event::RefreshSemanticTokens),
             LspStoreEvent::LanguageServerPrompt(prompt) => {
                 cx.emit(Event::LanguageServerPrompt(prompt.clone()))
             }
@@ -3250,6 +3261,92 @@ impl Project {
         )
     }
 
+    /// Returns the items of the call hierarchy at the given position, whose incoming and
+    /// outgoing calls can then be requested.
+    pub fn call_hierarchy<T: ToPointUtf16>(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        position: T,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<HierarchyItem>>> {
+        let position = position.to_point_utf16(buffer.read(cx));
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::FirstCapable,
+            PrepareCallHierarchy { position },
+            cx,
+        )
+    }
+
+    pub fn incoming_calls(
+        &mut self,
+        item: &HierarchyItem,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<HierarchyCall>>> {
+        self.request_lsp(
+            item.location.buffer.clone(),
+            LanguageServerToQuery::Other(item.server_id),
+            GetIncomingCalls { item: item.clone() },
+            cx,
+        )
+    }
+
+    pub fn outgoing_calls(
+        &mut self,
+        item: &HierarchyItem,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<HierarchyCall>>> {
+        self.request_lsp(
+            item.location.buffer.clone(),
+            LanguageServerToQuery::Other(item.server_id),
+            GetOutgoingCalls { item: item.clone() },
+            cx,
+        )
+    }
+
+    /// Returns the items of the type hierarchy at the given position, whose supertypes and
+    /// subtypes can then be requested.
+    pub fn type_hierarchy<T: ToPointUtf16>(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        position: T,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<HierarchyItem>>> {
+        let position = position.to_point_utf16(buffer.read(cx));
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::FirstCapable,
+            PrepareTypeHierarchy { position },
+            cx,
+        )
+    }
+
+    pub fn supertypes(
+        &mut self,
+        item: &HierarchyItem,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<HierarchyItem>>> {
+        self.request_lsp(
+            item.location.buffer.clone(),
+            LanguageServerToQuery::Other(item.server_id),
+            GetSupertypes { item: item.clone() },
+            cx,
+        )
+    }
+
+    pub fn subtypes(
+        &mut self,
+        item: &HierarchyItem,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<HierarchyItem>>> {
+        self.request_lsp(
+            item.location.buffer.clone(),
+            LanguageServerToQuery::Other(item.server_id),
+            GetSubtypes { item: item.clone() },
+            cx,
+        )
+    }
+
     fn document_highlights_impl(
         &mut self,
         buffer: &Entity<Buffer>,
@@ -3287,6 +3384,20 @@ impl Project {
         )
     }
 
+    pub fn folding_ranges(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<FoldingRange>>> {
+        let snapshot = buffer.read(cx).snapshot();
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::FirstCapable,
+            GetFoldingRanges { snapshot },
+            cx,
+        )
+    }
+
     pub fn symbols(&self, query: &str, cx: &mut Context<Self>) -> Task<Result<Vec<Symbol>>> {
         self.lsp_store
             .update(cx, |lsp_store, cx| lsp_store.symbols(query, cx))
@@ -3532,6 +3643,16 @@ impl Project {
         })
     }
 
+    pub fn semantic_tokens(
+        &mut self,
+        buffer_handle: &Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<BufferSemanticTokens>> {
+        self.lsp_store.update(cx, |lsp_store, cx| {
+            lsp_store.semantic_tokens(buffer_handle, cx)
+        })
+    }
+
     pub fn resolve_inlay_hint(
         &self,
         hint: InlayHint,
@@ -3544,6 +3665,17 @@ impl Project {
         })
     }
 
+    pub fn resolve_code_lens(
+        &self,
+        buffer_handle: Entity<Buffer>,
+        lens: CodeAction,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<CodeAction>> {
+        self.lsp_store.update(cx, |lsp_store, cx| {
+            lsp_store.resolve_code_lens(buffer_handle, lens, cx)
+        })
+    }
+
     pub fn search(&mut self, query: SearchQuery, cx: &mut Context<Self>) -> Receiver<SearchResult> {
         let (result_tx, result_rx) = smol::channel::unbounded();
 
@@ -188,6 +188,11 @@ pub struct GitSettings {
     ///
     /// Default: staged_hollow
     pub hunk_style: Option<GitHunkStyleSetting>,
+    /// Whether to look up the pull request of the current branch on its Git hosting
+    /// provider, and show the status of its CI checks in the title bar.
+    ///
+    /// Default: false
+    pub pull_request_status: Option<bool>,
 }
 
 impl GitSettings {
@@ -209,6 +214,10 @@ impl GitSettings {
         }
     }
 
+    pub fn pull_request_status_enabled(&self) -> bool {
+        self.pull_request_status.unwrap_or(false)
+    }
+
     pub fn show_inline_commit_summary(&self) -> bool {
         match self.inline_blame {
             Some(InlineBlameSettings {
@@ -1,7 +1,10 @@
 #![allow(clippy::format_collect)]
 
 use crate::{
-    Event, git_store::StatusEntry, task_inventory::TaskContexts, task_store::TaskSettingsLocation,
+    Event,
+    git_store::{Repository, StatusEntry, check_worktree_path},
+    task_inventory::TaskContexts,
+    task_store::TaskSettingsLocation,
     *,
 };
 use buffer_diff::{
@@ -10,8 +13,12 @@ use buffer_diff::{
 use fs::FakeFs;
 use futures::{StreamExt, future};
 use git::{
-    repository::RepoPath,
-    status::{StatusCode, TrackedStatus},
+    rebase::{RebaseAction, RebaseOperation, RebaseTodoEntry},
+    repository::{
+        AskPassDelegate, LogFilter, RepoPath, SequencerCommand, StashMode, Submodule,
+        SubmoduleStatus,
+    },
+    status::{FileStatus, StatusCode, TrackedStatus},
 };
 use git2::RepositoryInitOptions;
 use gpui::{App, BackgroundExecutor, SemanticVersion, UpdateGlobal};
@@ -1758,6 +1765,178 @@ async fn test_restarting_server_with_diagnostics_published(cx: &mut gpui::TestAp
     });
 }
 
+#[gpui::test]
+async fn test_pull_diagnostics(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(path!("/dir"), json!({ "a.rs": "x" })).await;
+
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+
+    let previous_result_ids = Arc::new(Mutex::new(Vec::new()));
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                diagnostic_provider: Some(lsp::DiagnosticServerCapabilities::Options(
+                    lsp::DiagnosticOptions {
+                        identifier: None,
+                        inter_file_dependencies: false,
+                        workspace_diagnostics: false,
+                        work_done_progress_options: Default::default(),
+                    },
+                )),
+                ..Default::default()
+            },
+            initializer: Some(Box::new({
+                let previous_result_ids = previous_result_ids.clone();
+                move |fake_server| {
+                    let previous_result_ids = previous_result_ids.clone();
+                    fake_server
+                        .set_request_handler::<lsp::request::DocumentDiagnosticRequest, _, _>(
+                            move |params, _| {
+                                previous_result_ids
+                                    .lock()
+                                    .push(params.previous_result_id.clone());
+                                async move {
+                                    let report = match params.previous_result_id {
+                                        Some(result_id) => {
+                                            lsp::DocumentDiagnosticReport::Unchanged(
+                                                lsp::RelatedUnchangedDocumentDiagnosticReport {
+                                                    related_documents: None,
+                                                    unchanged_document_diagnostic_report:
+                                                        lsp::UnchangedDocumentDiagnosticReport {
+                                                            result_id,
+                                                        },
+                                                },
+                                            )
+                                        }
+                                        None => lsp::DocumentDiagnosticReport::Full(
+                                            lsp::RelatedFullDocumentDiagnosticReport {
+                                                related_documents: None,
+                                                full_document_diagnostic_report:
+                                                    lsp::FullDocumentDiagnosticReport {
+                                                        result_id: Some("1".to_string()),
+                                                        items: vec![lsp::Diagnostic {
+                                                            range: lsp::Range::new(
+                                                                lsp::Position::new(0, 0),
+                                                                lsp::Position::new(0, 1),
+                                                            ),
+                                                            severity: Some(
+                                                                lsp::DiagnosticSeverity::ERROR,
+                                                            ),
+                                                            message: "the message".to_string(),
+                                                            ..Default::default()
+                                                        }],
+                                                    },
+                                            },
+                                        ),
+                                    };
+                                    Ok(lsp::DocumentDiagnosticReportResult::Report(report))
+                                }
+                            },
+                        );
+                }
+            })),
+            ..Default::default()
+        },
+    );
+
+    let (buffer, _) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp(path!("/dir/a.rs"), cx)
+        })
+        .await
+        .unwrap();
+    let fake_server = fake_servers.next().await.unwrap();
+
+    // The diagnostics are pulled once the server starts.
+    cx.executor().run_until_parked();
+    buffer.update(cx, |buffer, _| {
+        assert_eq!(
+            buffer
+                .snapshot()
+                .diagnostics_in_range::<_, usize>(0..1, false)
+                .map(|entry| entry.diagnostic.message.clone())
+                .collect::<Vec<_>>(),
+            ["the message".to_string()]
+        );
+    });
+    assert!(
+        previous_result_ids
+            .lock()
+            .iter()
+            .all(|result_id| result_id.is_none())
+    );
+    previous_result_ids.lock().clear();
+
+    // After an edit, they are pulled again, reporting the result of the previous pull, and kept
+    // when the server reports that they didn't change.
+    buffer.update(cx, |buffer, cx| buffer.edit([(1..1, " ")], None, cx));
+    cx.executor().advance_clock(Duration::from_millis(500));
+    cx.executor().run_until_parked();
+    assert_eq!(*previous_result_ids.lock(), [Some("1".to_string())]);
+    buffer.update(cx, |buffer, _| {
+        assert_eq!(
+            buffer
+                .snapshot()
+                .diagnostics_in_range::<_, usize>(0..1, false)
+                .map(|entry| entry.diagnostic.message.clone())
+                .collect::<Vec<_>>(),
+            ["the message".to_string()]
+        );
+    });
+
+    // Diagnostics published by the server are kept alongside the pulled ones, and only replace
+    // the ones it published before.
+    fake_server.notify::<lsp::notification::PublishDiagnostics>(&lsp::PublishDiagnosticsParams {
+        uri: Url::from_file_path(path!("/dir/a.rs")).unwrap(),
+        version: None,
+        diagnostics: vec![lsp::Diagnostic {
+            range: lsp::Range::new(lsp::Position::new(0, 0), lsp::Position::new(0, 1)),
+            severity: Some(lsp::DiagnosticSeverity::WARNING),
+            message: "the published message".to_string(),
+            ..Default::default()
+        }],
+    });
+    cx.executor().run_until_parked();
+    buffer.update(cx, |buffer, _| {
+        let mut messages = buffer
+            .snapshot()
+            .diagnostics_in_range::<_, usize>(0..1, false)
+            .map(|entry| entry.diagnostic.message.clone())
+            .collect::<Vec<_>>();
+        messages.sort();
+        assert_eq!(
+            messages,
+            [
+                "the message".to_string(),
+                "the published message".to_string()
+            ]
+        );
+    });
+
+    fake_server.notify::<lsp::notification::PublishDiagnostics>(&lsp::PublishDiagnosticsParams {
+        uri: Url::from_file_path(path!("/dir/a.rs")).unwrap(),
+        version: None,
+        diagnostics: Default::default(),
+    });
+    cx.executor().run_until_parked();
+    buffer.update(cx, |buffer, _| {
+        assert_eq!(
+            buffer
+                .snapshot()
+                .diagnostics_in_range::<_, usize>(0..1, false)
+                .map(|entry| entry.diagnostic.message.clone())
+                .collect::<Vec<_>>(),
+            ["the message".to_string()]
+        );
+    });
+}
+
 #[gpui::test]
 async fn test_restarted_server_reporting_invalid_buffer_version(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -2885,6 +3064,127 @@ async fn test_definition(cx: &mut gpui::TestAppContext) {
     }
 }
 
+#[gpui::test]
+async fn test_call_hierarchy(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "a.rs": "fn a() {}",
+            "b.rs": "fn b() {\n    crate::a();\n    crate::a();\n}",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                call_hierarchy_provider: Some(lsp::CallHierarchyServerCapability::Simple(true)),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let (buffer, _handle) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp(path!("/dir/a.rs"), cx)
+        })
+        .await
+        .unwrap();
+
+    let fake_server = fake_servers.next().await.unwrap();
+    let item = |path: &str, name: &str, range: lsp::Range| lsp::CallHierarchyItem {
+        name: name.to_string(),
+        kind: lsp::SymbolKind::FUNCTION,
+        tags: None,
+        detail: None,
+        uri: lsp::Url::from_file_path(path).unwrap(),
+        range,
+        selection_range: range,
+        data: Some(json!({ "id": name })),
+    };
+    let a_range = lsp::Range::new(lsp::Position::new(0, 3), lsp::Position::new(0, 4));
+    let b_range = lsp::Range::new(lsp::Position::new(0, 3), lsp::Position::new(0, 4));
+    fake_server.set_request_handler::<lsp::request::CallHierarchyPrepare, _, _>({
+        let item = item(path!("/dir/a.rs"), "a", a_range);
+        move |params, _| {
+            assert_eq!(
+                params.text_document_position_params.position,
+                lsp::Position::new(0, 3)
+            );
+            let item = item.clone();
+            async move { Ok(Some(vec![item])) }
+        }
+    });
+    fake_server.set_request_handler::<lsp::request::CallHierarchyIncomingCalls, _, _>({
+        let item = item(path!("/dir/b.rs"), "b", b_range);
+        move |params, _| {
+            // The item is sent back to the server as it was reported.
+            assert_eq!(params.item.data, Some(json!({ "id": "a" })));
+            let item = item.clone();
+            async move {
+                Ok(Some(vec![lsp::CallHierarchyIncomingCall {
+                    from: item,
+                    from_ranges: vec![
+                        lsp::Range::new(lsp::Position::new(1, 11), lsp::Position::new(1, 12)),
+                        lsp::Range::new(lsp::Position::new(2, 11), lsp::Position::new(2, 12)),
+                    ],
+                }]))
+            }
+        }
+    });
+
+    let items = project
+        .update(cx, |project, cx| project.call_hierarchy(&buffer, 3, cx))
+        .await
+        .unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].name, "a");
+    assert_eq!(items[0].location.buffer, buffer);
+
+    let calls = project
+        .update(cx, |project, cx| project.incoming_calls(&items[0], cx))
+        .await
+        .unwrap();
+    assert_eq!(calls.len(), 1);
+    cx.update(|cx| {
+        let call = &calls[0];
+        let caller_buffer = call.item.location.buffer.read(cx);
+        assert_eq!(
+            caller_buffer
+                .file()
+                .unwrap()
+                .as_local()
+                .unwrap()
+                .abs_path(cx),
+            Path::new(path!("/dir/b.rs")),
+        );
+        assert_eq!(call.item.name, "b");
+        assert_eq!(call.item.selection_range.to_offset(caller_buffer), 3..4);
+        assert_eq!(
+            call.call_sites
+                .iter()
+                .map(|call_site| {
+                    assert_eq!(call_site.buffer, call.item.location.buffer);
+                    call_site.range.to_point(caller_buffer)
+                })
+                .collect::<Vec<_>>(),
+            [
+                Point::new(1, 11)..Point::new(1, 12),
+                Point::new(2, 11)..Point::new(2, 12),
+            ]
+        );
+    });
+}
+
 #[gpui::test]
 async fn test_completions_with_text_edit(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -8194,6 +8494,683 @@ async fn test_repository_deduplication(cx: &mut gpui::TestAppContext) {
     pretty_assertions::assert_eq!(repos, [Path::new(path!("/root/project")).into()]);
 }
 
+#[gpui::test]
+async fn test_git_stash(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+    let fs = FakeFs::new(cx.background_executor.clone());
+    fs.insert_tree(
+        path!("/project"),
+        json!({
+            ".git": {},
+            "a.txt": "modified\n",
+            "b.txt": "b\n",
+        }),
+    )
+    .await;
+    let dot_git = Path::new(path!("/project/.git"));
+    fs.set_branch_name(dot_git, Some("main"));
+    fs.set_head_and_index_for_repo(
+        dot_git,
+        &[
+            ("a.txt".into(), "a\n".into()),
+            ("b.txt".into(), "b\n".into()),
+        ],
+    );
+    let (_project, repository) = init_fake_git_repository(&fs, cx).await;
+
+    repository
+        .update(cx, |repository, _| {
+            repository.stash_push(None, StashMode::All)
+        })
+        .await
+        .unwrap()
+        .unwrap();
+    cx.executor().run_until_parked();
+
+    assert_eq!(read_file_text(&fs, path!("/project/a.txt")), "a\n");
+    let stash_entries = repository
+        .update(cx, |repository, _| repository.stash_list())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        stash_entries
+            .iter()
+            .map(|entry| (entry.index, entry.message.as_ref()))
+            .collect::<Vec<_>>(),
+        [(0, "WIP on main")]
+    );
+    repository.read_with(cx, |repository, _| {
+        assert!(repository.cached_status().next().is_none());
+    });
+
+    let diff = repository
+        .update(cx, |repository, _| repository.stash_show(0))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        diff.files
+            .iter()
+            .map(|file| (
+                file.path.clone(),
+                file.old_text.as_deref(),
+                file.new_text.as_deref()
+            ))
+            .collect::<Vec<_>>(),
+        [(RepoPath::from("a.txt"), Some("a\n"), Some("modified\n"))]
+    );
+
+    repository
+        .update(cx, |repository, _| repository.stash_pop(0))
+        .await
+        .unwrap()
+        .unwrap();
+    cx.executor().run_until_parked();
+
+    assert_eq!(read_file_text(&fs, path!("/project/a.txt")), "modified\n");
+    let stash_entries = repository
+        .update(cx, |repository, _| repository.stash_list())
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(stash_entries.is_empty());
+    repository.read_with(cx, |repository, _| {
+        assert_eq!(
+            repository.cached_status().collect::<Vec<_>>(),
+            [StatusEntry {
+                repo_path: "a.txt".into(),
+                status: StatusCode::Modified.worktree(),
+            }]
+        );
+    });
+
+    // There is nothing left to stash in the index.
+    assert!(
+        repository
+            .update(cx, |repository, _| repository
+                .stash_push(None, StashMode::Staged))
+            .await
+            .unwrap()
+            .is_err()
+    );
+}
+
+#[gpui::test]
+async fn test_git_log(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+    let fs = FakeFs::new(cx.background_executor.clone());
+    fs.insert_tree(
+        path!("/project"),
+        json!({
+            ".git": {},
+            "a.txt": "a2\n",
+            "b.txt": "b\n",
+        }),
+    )
+    .await;
+    let dot_git = Path::new(path!("/project/.git"));
+    fs.set_branch_name(dot_git, Some("main"));
+    let first = fs.commit_for_repo(dot_git, "Add a", &[("a.txt".into(), Some("a\n".into()))]);
+    let second = fs.commit_for_repo(dot_git, "Add b", &[("b.txt".into(), Some("b\n".into()))]);
+    let third = fs.commit_for_repo(
+        dot_git,
+        "Change a",
+        &[("a.txt".into(), Some("a2\n".into()))],
+    );
+    let (_project, repository) = init_fake_git_repository(&fs, cx).await;
+
+    let entries = repository
+        .update(cx, |repository, _| repository.log(LogFilter::Head, 0, 10))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        entries
+            .iter()
+            .map(|entry| (entry.sha.to_string(), entry.subject.as_ref()))
+            .collect::<Vec<_>>(),
+        [
+            (third.clone(), "Change a"),
+            (second.clone(), "Add b"),
+            (first.clone(), "Add a"),
+        ]
+    );
+    assert_eq!(entries[0].refs, [String::from("HEAD -> main")]);
+    assert_eq!(entries[0].parent_shas, [second.clone()]);
+    assert!(entries[2].parent_shas.is_empty());
+
+    let entries = repository
+        .update(cx, |repository, _| {
+            repository.log(
+                LogFilter::Path {
+                    path: "a.txt".into(),
+                    follow: false,
+                },
+                1,
+                10,
+            )
+        })
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        entries
+            .iter()
+            .map(|entry| entry.sha.to_string())
+            .collect::<Vec<_>>(),
+        [first]
+    );
+}
+
+#[gpui::test]
+async fn test_git_interactive_rebase(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+    let fs = FakeFs::new(cx.background_executor.clone());
+    fs.insert_tree(
+        path!("/project"),
+        json!({
+            ".git": {},
+            "a.txt": "a\n",
+            "b.txt": "b\n",
+            "c.txt": "c\n",
+        }),
+    )
+    .await;
+    let dot_git = Path::new(path!("/project/.git"));
+    fs.set_branch_name(dot_git, Some("main"));
+    let first = fs.commit_for_repo(dot_git, "Add a", &[("a.txt".into(), Some("a\n".into()))]);
+    let second = fs.commit_for_repo(dot_git, "Add b", &[("b.txt".into(), Some("b\n".into()))]);
+    let third = fs.commit_for_repo(dot_git, "Add c", &[("c.txt".into(), Some("c\n".into()))]);
+    let (_project, repository) = init_fake_git_repository(&fs, cx).await;
+
+    let todo_entry = |action, sha: &String, subject: &'static str| RebaseTodoEntry {
+        action,
+        sha: sha.clone().into(),
+        subject: subject.into(),
+        message: None,
+    };
+    repository
+        .update(cx, |repository, _| {
+            repository.rebase_interactive(
+                Some(first.clone()),
+                vec![
+                    todo_entry(RebaseAction::Edit, &third, "Add c"),
+                    todo_entry(RebaseAction::Pick, &second, "Add b"),
+                ],
+            )
+        })
+        .await
+        .unwrap()
+        .unwrap();
+    cx.executor().run_until_parked();
+
+    repository.read_with(cx, |repository, _| {
+        let rebase_state = repository.rebase_state.as_ref().unwrap();
+        assert_eq!(rebase_state.branch.as_deref(), Some("main"));
+        assert_eq!(rebase_state.onto.as_ref(), first);
+        assert_eq!((rebase_state.step, rebase_state.total), (1, 2));
+    });
+
+    repository
+        .update(cx, |repository, _| {
+            repository.rebase_operation(RebaseOperation::Continue)
+        })
+        .await
+        .unwrap()
+        .unwrap();
+    cx.executor().run_until_parked();
+
+    repository.read_with(cx, |repository, _| {
+        assert_eq!(repository.rebase_state, None);
+    });
+    let subjects = repository
+        .update(cx, |repository, _| repository.log(LogFilter::Head, 0, 10))
+        .await
+        .unwrap()
+        .unwrap()
+        .into_iter()
+        .map(|entry| entry.subject.to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(subjects, ["Add b", "Add c", "Add a"]);
+
+    // Commits that aren't being rebased can't be part of the todo list.
+    assert!(
+        repository
+            .update(cx, |repository, _| {
+                repository.rebase_interactive(
+                    Some(second.clone()),
+                    vec![todo_entry(RebaseAction::Pick, &first, "Add a")],
+                )
+            })
+            .await
+            .unwrap()
+            .is_err()
+    );
+}
+
+#[gpui::test]
+async fn test_git_cherry_pick_and_revert(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+    let fs = FakeFs::new(cx.background_executor.clone());
+    fs.insert_tree(
+        path!("/project"),
+        json!({
+            ".git": {},
+            "a.txt": "two\n",
+        }),
+    )
+    .await;
+    let dot_git = Path::new(path!("/project/.git"));
+    fs.set_branch_name(dot_git, Some("main"));
+    fs.commit_for_repo(dot_git, "Add a", &[("a.txt".into(), Some("one\n".into()))]);
+    let second = fs.commit_for_repo(
+        dot_git,
+        "Change a",
+        &[("a.txt".into(), Some("two\n".into()))],
+    );
+    let (_project, repository) = init_fake_git_repository(&fs, cx).await;
+
+    repository
+        .update(cx, |repository, _| {
+            repository.revert(vec![second.clone()], false)
+        })
+        .await
+        .unwrap()
+        .unwrap();
+    cx.executor().run_until_parked();
+    assert_eq!(read_file_text(&fs, path!("/project/a.txt")), "one\n");
+
+    repository
+        .update(cx, |repository, _| {
+            repository.cherry_pick(vec![second.clone()], false)
+        })
+        .await
+        .unwrap()
+        .unwrap();
+    cx.executor().run_until_parked();
+    assert_eq!(read_file_text(&fs, path!("/project/a.txt")), "two\n");
+
+    let subjects = repository
+        .update(cx, |repository, _| repository.log(LogFilter::Head, 0, 10))
+        .await
+        .unwrap()
+        .unwrap()
+        .into_iter()
+        .map(|entry| entry.subject.to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        subjects,
+        ["Change a", "Revert \"Change a\"", "Change a", "Add a"]
+    );
+    repository.read_with(cx, |repository, _| {
+        assert!(repository.cached_status().next().is_none());
+    });
+
+    // Reverting a commit that changed a file with staged changes stops with a conflict.
+    fs.set_index_for_repo(dot_git, &[("a.txt".into(), "staged\n".into())]);
+    assert!(
+        repository
+            .update(cx, |repository, _| repository
+                .revert(vec![second.clone()], false))
+            .await
+            .unwrap()
+            .is_err()
+    );
+    cx.executor().run_until_parked();
+    repository.read_with(cx, |repository, _| {
+        assert_eq!(repository.sequencer_command, Some(SequencerCommand::Revert));
+        assert_matches!(
+            repository.cached_status().next().unwrap().status,
+            FileStatus::Unmerged(_)
+        );
+    });
+
+    repository
+        .update(cx, |repository, _| {
+            repository.sequencer_operation(SequencerCommand::Revert, RebaseOperation::Abort)
+        })
+        .await
+        .unwrap()
+        .unwrap();
+    cx.executor().run_until_parked();
+    repository.read_with(cx, |repository, _| {
+        assert_eq!(repository.sequencer_command, None);
+        assert_eq!(
+            repository.cached_status().collect::<Vec<_>>(),
+            [StatusEntry {
+                repo_path: "a.txt".into(),
+                status: TrackedStatus {
+                    index_status: StatusCode::Modified,
+                    worktree_status: StatusCode::Modified,
+                }
+                .into(),
+            }]
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_git_submodules(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+    let fs = FakeFs::new(cx.background_executor.clone());
+    fs.insert_tree(
+        path!("/project"),
+        json!({
+            ".git": {},
+            ".gitmodules": "",
+            "sub": {},
+        }),
+    )
+    .await;
+    let dot_git = Path::new(path!("/project/.git"));
+    fs.with_git_state(dot_git, true, |state| {
+        state.submodules = vec![Submodule {
+            name: "sub".into(),
+            path: "sub".into(),
+            url: Some("https://example.com/sub.git".into()),
+            head_sha: Some("abc".into()),
+            workdir_sha: None,
+            status: SubmoduleStatus {
+                uninitialized: true,
+                ..Default::default()
+            },
+        }];
+    })
+    .unwrap();
+    let (_project, repository) = init_fake_git_repository(&fs, cx).await;
+
+    repository.read_with(cx, |repository, _| {
+        assert_eq!(repository.submodules.len(), 1);
+        assert!(repository.submodules[0].status.uninitialized);
+    });
+
+    let askpass = AskPassDelegate::new(&mut cx.to_async(), |_, _, _| {});
+    let output = repository
+        .update(cx, |repository, cx| {
+            repository.update_submodules(Vec::new(), askpass, cx)
+        })
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(output.stdout, "Submodule path 'sub': checked out 'abc'\n");
+    cx.executor().run_until_parked();
+
+    repository.read_with(cx, |repository, _| {
+        let submodule = &repository.submodules[0];
+        assert_eq!(submodule.workdir_sha.as_deref(), Some("abc"));
+        assert!(submodule.status.is_clean());
+    });
+}
+
+#[gpui::test]
+async fn test_git_worktrees(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+    let fs = FakeFs::new(cx.background_executor.clone());
+    fs.insert_tree(
+        path!("/project"),
+        json!({
+            ".git": {},
+            "a.txt": "a\n",
+        }),
+    )
+    .await;
+    let dot_git = Path::new(path!("/project/.git"));
+    fs.set_branch_name(dot_git, Some("main"));
+    let (_project, repository) = init_fake_git_repository(&fs, cx).await;
+
+    let linked_path = PathBuf::from(path!("/project-feature"));
+    repository
+        .update(cx, |repository, _| {
+            repository.create_worktree(linked_path.clone(), "feature".into(), true)
+        })
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(fs.directories(false).contains(&linked_path));
+
+    let worktrees = repository
+        .update(cx, |repository, _| repository.worktrees())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        worktrees
+            .iter()
+            .map(|worktree| (
+                worktree.path.clone(),
+                worktree.branch.as_deref(),
+                worktree.is_main
+            ))
+            .collect::<Vec<_>>(),
+        [
+            (PathBuf::from(path!("/project")), Some("main"), true),
+            (linked_path.clone(), Some("feature"), false),
+        ]
+    );
+
+    // A branch can only be checked out in one worktree.
+    assert!(
+        repository
+            .update(cx, |repository, _| {
+                repository.create_worktree(path!("/project-other").into(), "feature".into(), false)
+            })
+            .await
+            .unwrap()
+            .is_err()
+    );
+
+    repository
+        .update(cx, |repository, _| {
+            repository.remove_worktree(linked_path.clone(), false)
+        })
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(!fs.directories(false).contains(&linked_path));
+    let worktrees = repository
+        .update(cx, |repository, _| repository.worktrees())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(worktrees.len(), 1);
+}
+
+#[test]
+fn test_check_worktree_path() {
+    let work_directory = Path::new(path!("/code/project"));
+    for path in [
+        path!("/code/project-feature"),
+        path!("/code/project/worktrees/feature"),
+    ] {
+        assert!(
+            check_worktree_path(work_directory, Path::new(path)).is_ok(),
+            "{path} should be allowed"
+        );
+    }
+    for path in [
+        "project-feature",
+        path!("/code/project"),
+        path!("/code/project/../../etc"),
+        path!("/code/project/.git/worktrees"),
+        path!("/elsewhere/feature"),
+    ] {
+        assert!(
+            check_worktree_path(work_directory, Path::new(path)).is_err(),
+            "{path} should be rejected"
+        );
+    }
+}
+
+#[gpui::test]
+async fn test_git_tags(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+    let fs = FakeFs::new(cx.background_executor.clone());
+    fs.insert_tree(
+        path!("/project"),
+        json!({
+            ".git": {},
+            "a.txt": "a2\n",
+        }),
+    )
+    .await;
+    let dot_git = Path::new(path!("/project/.git"));
+    fs.set_branch_name(dot_git, Some("main"));
+    let first = fs.commit_for_repo(dot_git, "Add a", &[("a.txt".into(), Some("a\n".into()))]);
+    let second = fs.commit_for_repo(
+        dot_git,
+        "Change a",
+        &[("a.txt".into(), Some("a2\n".into()))],
+    );
+    let (_project, repository) = init_fake_git_repository(&fs, cx).await;
+
+    repository
+        .update(cx, |repository, _| {
+            repository.create_tag("v1".into(), Some(first.clone()), None)
+        })
+        .await
+        .unwrap()
+        .unwrap();
+    repository
+        .update(cx, |repository, _| {
+            repository.create_tag("v2".into(), None, Some("Release 2\n\nNotes".into()))
+        })
+        .await
+        .unwrap()
+        .unwrap();
+    let tags = repository
+        .update(cx, |repository, _| repository.tags())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        tags.iter()
+            .map(|tag| (
+                tag.name.as_ref(),
+                tag.sha.to_string(),
+                tag.message.as_deref()
+            ))
+            .collect::<Vec<_>>(),
+        [
+            ("v1", first.clone(), None),
+            ("v2", second.clone(), Some("Release 2")),
+        ]
+    );
+
+    let askpass = AskPassDelegate::new(&mut cx.to_async(), |_, _, _| {});
+    repository
+        .update(cx, |repository, cx| {
+            repository.push_tag("v2".into(), "origin".into(), askpass, cx)
+        })
+        .await
+        .unwrap()
+        .unwrap();
+
+    repository
+        .update(cx, |repository, _| repository.checkout_tag("v1".into()))
+        .await
+        .unwrap()
+        .unwrap();
+    cx.executor().run_until_parked();
+    repository.read_with(cx, |repository, _| {
+        assert_eq!(repository.branch, None);
+        let detached_head = repository.detached_head.as_ref().unwrap();
+        assert_eq!(detached_head.sha.as_ref(), first);
+        assert_eq!(detached_head.tag.as_deref(), Some("v1"));
+    });
+
+    repository
+        .update(cx, |repository, _| repository.delete_tag("v1".into()))
+        .await
+        .unwrap()
+        .unwrap();
+    let tags = repository
+        .update(cx, |repository, _| repository.tags())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        tags.iter().map(|tag| tag.name.as_ref()).collect::<Vec<_>>(),
+        ["v2"]
+    );
+}
+
+#[gpui::test]
+async fn test_git_fetch_ref(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+    let fs = FakeFs::new(cx.background_executor.clone());
+    fs.insert_tree(
+        path!("/project"),
+        json!({
+            ".git": {},
+            "a.txt": "a\n",
+        }),
+    )
+    .await;
+    let dot_git = Path::new(path!("/project/.git"));
+    fs.set_branch_name(dot_git, Some("main"));
+    let (_project, repository) = init_fake_git_repository(&fs, cx).await;
+
+    let askpass = AskPassDelegate::new(&mut cx.to_async(), |_, _, _| {});
+    repository
+        .update(cx, |repository, cx| {
+            repository.fetch_ref(
+                "origin".into(),
+                "refs/pull/1/head".into(),
+                "pr-1".into(),
+                askpass,
+                cx,
+            )
+        })
+        .await
+        .unwrap()
+        .unwrap();
+    let mut branches = repository
+        .update(cx, |repository, _| repository.branches())
+        .await
+        .unwrap()
+        .unwrap()
+        .into_iter()
+        .map(|branch| branch.name.to_string())
+        .collect::<Vec<_>>();
+    branches.sort();
+    assert_eq!(branches, ["main", "pr-1"]);
+
+    // The checked out branch can't be overwritten.
+    let askpass = AskPassDelegate::new(&mut cx.to_async(), |_, _, _| {});
+    assert!(
+        repository
+            .update(cx, |repository, cx| {
+                repository.fetch_ref(
+                    "origin".into(),
+                    "refs/pull/2/head".into(),
+                    "main".into(),
+                    askpass,
+                    cx,
+                )
+            })
+            .await
+            .unwrap()
+            .is_err()
+    );
+}
+
+async fn init_fake_git_repository(
+    fs: &Arc<FakeFs>,
+    cx: &mut gpui::TestAppContext,
+) -> (Entity<Project>, Entity<Repository>) {
+    let project = Project::test(fs.clone(), [path!("/project").as_ref()], cx).await;
+    cx.executor().run_until_parked();
+    let repository = project.read_with(cx, |project, cx| {
+        project.repositories(cx).values().next().unwrap().clone()
+    });
+    (project, repository)
+}
+
+fn read_file_text(fs: &FakeFs, path: &str) -> String {
+    String::from_utf8(fs.read_file_sync(path).unwrap()).unwrap()
+}
+
 async fn search(
     project: &Entity<Project>,
     query: SearchQuery,
@@ -15,7 +15,7 @@ use editor::{
     scroll::{Autoscroll, ScrollbarAutoHide},
 };
 use file_icons::FileIcons;
-use git::status::GitSummary;
+use git::{repository::SubmoduleStatus, status::GitSummary};
 use gpui::{
     Action, AnyElement, App, ArcCow, AsyncWindowContext, Bounds, ClipboardItem, Context,
     DismissEvent, Div, DragMoveEvent, Entity, EventEmitter, ExternalPaths, FocusHandle, Focusable,
@@ -162,6 +162,7 @@ struct EntryDetails {
     is_private: bool,
     worktree_id: WorktreeId,
     canonical_path: Option<Arc<Path>>,
+    submodule_status: Option<SubmoduleStatus>,
 }
 
 #[derive(PartialEq, Clone, Default, Debug, Deserialize, JsonSchema)]
@@ -715,6 +716,21 @@ impl ProjectPanel {
             let is_read_only = project.is_read_only(cx);
             let is_remote = project.is_via_collab();
             let is_local = project.is_local();
+            let has_git_repo = project
+                .git_store()
+                .read(cx)
+                .repository_and_path_for_project_path(
+                    &ProjectPath {
+                        worktree_id,
+                        path: entry.path.clone(),
+                    },
+                    cx,
+                )
+                .is_some();
+            let history_path = worktree
+                .absolutize(&entry.path)
+                .ok()
+                .filter(|_| has_git_repo);
 
             let context_menu = ContextMenu::build(window, cx, |menu, _, _| {
                 menu.context(self.focus_handle.clone()).map(|menu| {
@@ -764,6 +780,12 @@ impl ProjectPanel {
                                 "Copy Relative Path",
                                 Box::new(zed_actions::workspace::CopyRelativePath),
                             )
+                            .when_some(history_path.clone(), |menu, path| {
+                                menu.separator().action(
+                                    "View File History",
+                                    Box::new(git::FileHistory { path: Some(path) }),
+                                )
+                            })
                             .separator()
                             .when(!is_root || !cfg!(target_os = "windows"), |menu| {
                                 menu.action("Rename", Box::new(Rename))
@@ -3132,6 +3154,12 @@ impl ProjectPanel {
             if let Some(worktree) = self.project.read(cx).worktree_for_id(*worktree_id, cx) {
                 let snapshot = worktree.read(cx).snapshot();
                 let root_name = OsStr::new(snapshot.root_name());
+                let repo_snapshots = self
+                    .project
+                    .read(cx)
+                    .git_store()
+                    .read(cx)
+                    .repo_snapshots(cx);
                 let expanded_entry_ids = self
                     .expanded_dir_ids
                     .get(&snapshot.id())
@@ -3222,8 +3250,17 @@ impl ProjectPanel {
                         is_private: entry.is_private,
                         worktree_id: *worktree_id,
                         canonical_path: entry.canonical_path.clone(),
+                        submodule_status: None,
                     };
 
+                    if entry.is_dir() {
+                        let abs_path = snapshot.abs_path().join(&entry.path);
+                        details.submodule_status = repo_snapshots.values().find_map(|repo| {
+                            let repo_path = repo.abs_path_to_repo_path(&abs_path)?;
+                            Some(repo.submodule_for_path(&repo_path)?.status)
+                        });
+                    }
+
                     if let Some(edit_state) = &self.edit_state {
                         let is_edited_entry = if edit_state.is_new_entry() {
                             entry.id == NEW_ENTRY_ID
@@ -3605,6 +3642,7 @@ impl ProjectPanel {
             .canonical_path
             .as_ref()
             .map(|f| f.to_string_lossy().to_string());
+        let submodule_status = details.submodule_status;
         let path = details.path.clone();
 
         let depth = details.depth;
@@ -3890,6 +3928,33 @@ impl ProjectPanel {
                                 .into_any_element(),
                         )
                     })
+                    .when_some(submodule_status, |this, status| {
+                        let meta = if status.uninitialized {
+                            "Not initialized"
+                        } else if status.is_clean() {
+                            "Up to date"
+                        } else {
+                            "Has changes"
+                        };
+                        this.end_slot::<AnyElement>(
+                            div()
+                                .id("submodule_icon")
+                                .pr_3()
+                                .tooltip(move |window, cx| {
+                                    Tooltip::with_meta("Git Submodule", None, meta, window, cx)
+                                })
+                                .child(
+                                    Icon::new(IconName::GitBranch)
+                                        .size(IconSize::Indicator)
+                                        .color(if status.is_clean() {
+                                            Color::Muted
+                                        } else {
+                                            filename_text_color
+                                        }),
+                                )
+                                .into_any_element(),
+                        )
+                    })
                     .child(if let Some(icon) = &icon {
                         if let Some((_, decoration_color)) =
                             entry_diagnostic_aware_icon_decoration_and_color(diagnostic_severity)
@@ -904,6 +904,7 @@ impl PromptLibrary {
                                     tools: Vec::new(),
                                     stop: Vec::new(),
                                     temperature: None,
+                                    max_tokens: None,
                                 },
                                 cx,
                             )
@@ -397,6 +397,25 @@ messages!(
     (RefreshCodeLens, Background),
     (GetCodeLens, Background),
     (GetCodeLensResponse, Background),
+    (GetSemanticTokens, Background),
+    (GetSemanticTokensResponse, Background),
+    (RefreshSemanticTokens, Background),
+    (PrepareCallHierarchy, Background),
+    (PrepareCallHierarchyResponse, Background),
+    (GetIncomingCalls, Background),
+    (GetIncomingCallsResponse, Background),
+    (GetOutgoingCalls, Background),
+    (GetOutgoingCallsResponse, Background),
+    (PrepareTypeHierarchy, Background),
+    (PrepareTypeHierarchyResponse, Background),
+    (GetSupertypes, Background),
+    (GetSupertypesResponse, Background),
+    (GetSubtypes, Background),
+    (GetSubtypesResponse, Background),
+    (GetFoldingRanges, Background),
+    (GetFoldingRangesResponse, Background),
+    (ResolveCodeLens, Background),
+    (ResolveCodeLensResponse, Background),
     (RespondToChannelInvite, Foreground),
     (RespondToContactRequest, Foreground),
     (RestartLanguageServers, Foreground),
@@ -457,6 +476,34 @@ messages!(
     (GitReset, Background),
     (GitCheckoutFiles, Background),
     (GitShow, Background),
+    (GitStashPush, Background),
+    (GitStashList, Background),
+    (GitStashListResponse, Background),
+    (GitStashApply, Background),
+    (GitStashPop, Background),
+    (GitStashDrop, Background),
+    (GitStashShow, Background),
+    (GitLog, Background),
+    (GitLogResponse, Background),
+    (GitRebase, Background),
+    (GitRebaseOperation, Background),
+    (GitWorktrees, Background),
+    (GitWorktreesResponse, Background),
+    (GitCreateWorktree, Background),
+    (GitRemoveWorktree, Background),
+    (GitUpdateSubmodules, Background),
+    (GitGetRemoteUrl, Background),
+    (GitGetRemoteUrlResponse, Background),
+    (GitFetchRef, Background),
+    (GitCherryPick, Background),
+    (GitRevert, Background),
+    (GitSequencerOperation, Background),
+    (GitTags, Background),
+    (GitTagsResponse, Background),
+    (GitCreateTag, Background),
+    (GitDeleteTag, Background),
+    (GitCheckoutTag, Background),
+    (GitPushTag, Background),
     (GitCommitDetails, Background),
     (SetIndexText, Background),
     (Push, Background),
@@ -528,6 +575,15 @@ request_messages!(
     (IncomingCall, Ack),
     (InlayHints, InlayHintsResponse),
     (GetCodeLens, GetCodeLensResponse),
+    (GetSemanticTokens, GetSemanticTokensResponse),
+    (PrepareCallHierarchy, PrepareCallHierarchyResponse),
+    (GetIncomingCalls, GetIncomingCallsResponse),
+    (GetOutgoingCalls, GetOutgoingCallsResponse),
+    (PrepareTypeHierarchy, PrepareTypeHierarchyResponse),
+    (GetSupertypes, GetSupertypesResponse),
+    (GetSubtypes, GetSubtypesResponse),
+    (GetFoldingRanges, GetFoldingRangesResponse),
+    (ResolveCodeLens, ResolveCodeLensResponse),
     (InviteChannelMember, Ack),
     (JoinChannel, JoinRoomResponse),
     (JoinChannelBuffer, JoinChannelBufferResponse),
@@ -551,6 +607,7 @@ request_messages!(
     (CountLanguageModelTokens, CountLanguageModelTokensResponse),
     (RefreshInlayHints, Ack),
     (RefreshCodeLens, Ack),
+    (RefreshSemanticTokens, Ack),
     (RejoinChannelBuffers, RejoinChannelBuffersResponse),
     (RejoinRoom, RejoinRoomResponse),
     (ReloadBuffers, ReloadBuffersResponse),
@@ -618,6 +675,29 @@ request_messages!(
     (GitShow, GitCommitDetails),
     (GitReset, Ack),
     (GitCheckoutFiles, Ack),
+    (GitStashPush, Ack),
+    (GitStashList, GitStashListResponse),
+    (GitStashApply, Ack),
+    (GitStashPop, Ack),
+    (GitStashDrop, Ack),
+    (GitStashShow, LoadCommitDiffResponse),
+    (GitLog, GitLogResponse),
+    (GitRebase, Ack),
+    (GitRebaseOperation, Ack),
+    (GitWorktrees, GitWorktreesResponse),
+    (GitCreateWorktree, Ack),
+    (GitRemoveWorktree, Ack),
+    (GitUpdateSubmodules, RemoteMessageResponse),
+    (GitGetRemoteUrl, GitGetRemoteUrlResponse),
+    (GitFetchRef, RemoteMessageResponse),
+    (GitCherryPick, Ack),
+    (GitRevert, Ack),
+    (GitSequencerOperation, Ack),
+    (GitTags, GitTagsResponse),
+    (GitCreateTag, Ack),
+    (GitDeleteTag, Ack),
+    (GitCheckoutTag, Ack),
+    (GitPushTag, RemoteMessageResponse),
     (SetIndexText, Ack),
     (Push, RemoteMessageResponse),
     (Fetch, RemoteMessageResponse),
@@ -654,6 +734,7 @@ entity_messages!(
     FormatBuffers,
     GetCodeActions,
     GetCodeLens,
+    GetSemanticTokens,
     GetCompletions,
     GetDefinition,
     GetDeclaration,
@@ -684,6 +765,15 @@ entity_messages!(
     PrepareRename,
     RefreshInlayHints,
     RefreshCodeLens,
+    RefreshSemanticTokens,
+    PrepareCallHierarchy,
+    GetIncomingCalls,
+    GetOutgoingCalls,
+    PrepareTypeHierarchy,
+    GetSupertypes,
+    GetSubtypes,
+    GetFoldingRanges,
+    ResolveCodeLens,
     ReloadBuffers,
     RemoveProjectCollaborator,
     RenameProjectEntry,
@@ -733,6 +823,29 @@ entity_messages!(
     GitShow,
     GitReset,
     GitCheckoutFiles,
+    GitStashPush,
+    GitStashList,
+    GitStashApply,
+    GitStashPop,
+    GitStashDrop,
+    GitStashShow,
+    GitLog,
+    GitRebase,
+    GitRebaseOperation,
+    GitWorktrees,
+    GitCreateWorktree,
+    GitRemoveWorktree,
+    GitUpdateSubmodules,
+    GitGetRemoteUrl,
+    GitFetchRef,
+    GitCherryPick,
+    GitRevert,
+    GitSequencerOperation,
+    GitTags,
+    GitCreateTag,
+    GitDeleteTag,
+    GitCheckoutTag,
+    GitPushTag,
     SetIndexText,
 
     Push,
@@ -565,6 +565,7 @@ impl SummaryIndex {
             tools: Vec::new(),
             stop: Vec::new(),
             temperature: None,
+            max_tokens: None,
         };
 
         let code_len = code.len();
@@ -155,4 +155,130 @@ impl PtyProcessInfo {
     pub fn pid(&self) -> Option<Pid> {
         self.pid_getter.pid()
     }
+
+    /// Kills the PTY's foreground process group along with the process group of the PTY's own
+    /// process, so that the processes started by the running command go down with it. Returns
+    /// whether any process was killed.
+    #[cfg(unix)]
+    pub fn kill_current_process(&mut self) -> bool {
+        let foreground_group = unsafe { libc::tcgetpgrp(self.pid_getter.handle) };
+        let own_group = self.pid_getter.fallback_pid as libc::pid_t;
+
+        let mut killed = false;
+        if foreground_group > 0 {
+            killed |= unsafe { libc::killpg(foreground_group, libc::SIGKILL) } == 0;
+        }
+        if own_group > 0 && own_group != foreground_group {
+            killed |= unsafe { libc::killpg(own_group, libc::SIGKILL) } == 0;
+        }
+        killed
+    }
+
+    /// Kills the PTY's process along with all of its descendants, so that the processes started
+    /// by the running command go down with it. Returns whether any process was killed.
+    #[cfg(windows)]
+    pub fn kill_current_process(&mut self) -> bool {
+        let Some(pid) = self.pid_getter.pid() else {
+            return false;
+        };
+        self.system.refresh_processes_specifics(
+            sysinfo::ProcessesToUpdate::All,
+            ProcessRefreshKind::new(),
+        );
+
+        let mut tree = vec![pid];
+        let mut ix = 0;
+        while let Some(&parent) = tree.get(ix) {
+            let children = self
+                .system
+                .processes()
+                .iter()
+                .filter(|(child, process)| {
+                    process.parent() == Some(parent) && !tree.contains(child)
+                })
+                .map(|(child, _)| *child)
+                .collect::<Vec<_>>();
+            tree.extend(children);
+            ix += 1;
+        }
+
+        // Parents go first, so that they can't start new processes in place of the killed ones.
+        let mut killed = false;
+        for pid in tree {
+            if let Some(process) = self.system.process(pid) {
+                killed |= process.kill();
+            }
+        }
+        killed
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use alacritty_terminal::{event::WindowSize, tty};
+    use sysinfo::{ProcessStatus, ProcessesToUpdate};
+
+    use super::*;
+
+    fn wait_until(mut condition: impl FnMut() -> bool) -> bool {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while Instant::now() < deadline {
+            if condition() {
+                return true;
+            }
+            std::thread::sleep(Duration::from_millis(50));
+        }
+        false
+    }
+
+    #[test]
+    fn test_kill_current_process_kills_children() {
+        let options = tty::Options {
+            shell: Some(tty::Shell::new(
+                "/bin/sh".into(),
+                vec!["-c".into(), "sleep 100 & wait".into()],
+            )),
+            working_directory: None,
+            drain_on_exit: false,
+            env: Default::default(),
+        };
+        let window_size = WindowSize {
+            num_lines: 24,
+            num_cols: 80,
+            cell_width: 1,
+            cell_height: 1,
+        };
+        let pty = tty::new(&options, window_size, 0).unwrap();
+        let mut pty_info = PtyProcessInfo::new(&pty);
+        let shell_pid = Pid::from_u32(pty_info.pid_getter().fallback_pid());
+
+        let mut system = System::new();
+        let mut child_pid = None;
+        assert!(
+            wait_until(|| {
+                system.refresh_processes(ProcessesToUpdate::All);
+                child_pid = system
+                    .processes()
+                    .iter()
+                    .find(|(_, process)| process.parent() == Some(shell_pid))
+                    .map(|(pid, _)| *pid);
+                child_pid.is_some()
+            }),
+            "the shell never started its child"
+        );
+        let child_pid = child_pid.unwrap();
+
+        assert!(pty_info.kill_current_process());
+        assert!(
+            wait_until(|| {
+                system.refresh_processes(ProcessesToUpdate::Some(&[child_pid]));
+                system
+                    .process(child_pid)
+                    .is_none_or(|process| process.status() == ProcessStatus::Zombie)
+            }),
+            "the shell's child outlived it"
+        );
+    }
 }
@@ -1423,6 +1423,14 @@ impl Terminal {
         }
     }
 
+    /// Returns the entire text of the terminal, including the scrollback history.
+    pub fn get_content(&self) -> String {
+        let term = self.term.lock_unfair();
+        let start = AlacPoint::new(term.topmost_line(), Column(0));
+        let end = AlacPoint::new(term.bottommost_line(), term.last_column());
+        term.bounds_to_string(start, end)
+    }
+
     pub fn last_n_non_empty_lines(&self, n: usize) -> Vec<String> {
         let term = self.term.clone();
         let terminal = term.lock_unfair();
@@ -1859,6 +1867,15 @@ impl Terminal {
         Task::ready(())
     }
 
+    /// Kills the processes of the currently running task, if any, including those it started.
+    pub fn kill_active_task(&mut self) {
+        if let Some(task) = self.task() {
+            if task.status == TaskStatus::Running {
+                self.pty_info.kill_current_process();
+            }
+        }
+    }
+
     fn register_task_finished(&mut self, error_code: Option<i32>, cx: &mut Context<Terminal>) {
         self.completion_tx.try_send(()).ok();
         let task = match &mut self.task {
@@ -51,6 +51,42 @@ impl SyntaxTheme {
         Some(ix as u32)
     }
 
+    /// Returns the style of a semantic token reported by a language server.
+    ///
+    /// Themes style token types with `semantic.<type>` entries, and token modifiers with
+    /// `semantic.<type>.<modifier>` entries, which are layered over the type's style. Token types
+    /// that the theme doesn't style fall back to the syntax style of the closest tree-sitter
+    /// capture, and unknown token types aren't styled at all.
+    pub fn semantic_token_style<'a>(
+        &self,
+        token_type: &str,
+        token_modifiers: impl IntoIterator<Item = &'a str>,
+    ) -> Option<HighlightStyle> {
+        let mut style = self
+            .style_for_name(&format!("semantic.{token_type}"))
+            .or_else(|| {
+                semantic_token_fallbacks(token_type)
+                    .iter()
+                    .find_map(|name| self.style_for_name(name))
+            });
+        for token_modifier in token_modifiers {
+            if let Some(modifier_style) =
+                self.style_for_name(&format!("semantic.{token_type}.{token_modifier}"))
+            {
+                style
+                    .get_or_insert_with(HighlightStyle::default)
+                    .highlight(modifier_style);
+            }
+        }
+        style
+    }
+
+    fn style_for_name(&self, name: &str) -> Option<HighlightStyle> {
+        self.highlights
+            .iter()
+            .find_map(|(entry_name, style)| (entry_name == name).then_some(*style))
+    }
+
     /// Returns a new [`Arc<SyntaxTheme>`] with the given syntax styles merged in.
     pub fn merge(base: Arc<Self>, user_syntax_styles: Vec<(String, HighlightStyle)>) -> Arc<Self> {
         if user_syntax_styles.is_empty() {
@@ -87,6 +123,30 @@ impl SyntaxTheme {
     }
 }
 
+/// The syntax styles to use for the standard LSP semantic token types, most specific first.
+fn semantic_token_fallbacks(token_type: &str) -> &'static [&'static str] {
+    match token_type {
+        "namespace" => &["namespace", "type"],
+        "type" | "class" | "struct" | "interface" | "typeParameter" => &["type"],
+        "enum" => &["enum", "type"],
+        "enumMember" => &["variant", "constant"],
+        "parameter" => &["variable.parameter", "variable"],
+        "variable" => &["variable"],
+        "property" | "event" => &["property"],
+        "function" => &["function"],
+        "method" => &["function.method", "function"],
+        "macro" => &["function.macro", "function"],
+        "keyword" | "modifier" => &["keyword"],
+        "comment" => &["comment"],
+        "string" => &["string"],
+        "number" => &["number"],
+        "regexp" => &["string.regex", "string"],
+        "operator" => &["operator"],
+        "decorator" => &["attribute"],
+        _ => &[],
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use gpui::FontStyle;
@@ -194,4 +254,50 @@ mod tests {
             ]))
         );
     }
+
+    #[test]
+    fn test_semantic_token_style() {
+        let syntax_theme = SyntaxTheme::new_test_styles([
+            (
+                "function",
+                HighlightStyle {
+                    color: Some(gpui::red()),
+                    ..Default::default()
+                },
+            ),
+            (
+                "semantic.variable",
+                HighlightStyle {
+                    color: Some(gpui::green()),
+                    ..Default::default()
+                },
+            ),
+            (
+                "semantic.variable.mutable",
+                HighlightStyle {
+                    font_style: Some(FontStyle::Italic),
+                    ..Default::default()
+                },
+            ),
+        ]);
+
+        // Token types without a semantic style fall back to the closest syntax style.
+        assert_eq!(
+            syntax_theme.semantic_token_style("method", []),
+            Some(HighlightStyle {
+                color: Some(gpui::red()),
+                ..Default::default()
+            })
+        );
+        assert_eq!(
+            syntax_theme.semantic_token_style("variable", ["mutable", "declaration"]),
+            Some(HighlightStyle {
+                color: Some(gpui::green()),
+                font_style: Some(FontStyle::Italic),
+                ..Default::default()
+            })
+        );
+        assert_eq!(syntax_theme.semantic_token_style("lifetime", []), None);
+        assert_eq!(syntax_theme.semantic_token_style("string", []), None);
+    }
 }
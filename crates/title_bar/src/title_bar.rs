@@ -19,6 +19,8 @@ use auto_update::AutoUpdateStatus;
 use call::ActiveCall;
 use client::{Client, UserStore};
 use feature_flags::{FeatureFlagAppExt, ZedPro};
+use git::CiStatus;
+use git_ui::pull_requests::BranchPullRequest;
 use gpui::{
     Action, AnyElement, App, Context, Corner, Decorations, Element, Entity, InteractiveElement,
     Interactivity, IntoElement, MouseButton, ParentElement, Render, Stateful,
@@ -129,6 +131,7 @@ pub struct TitleBar {
     application_menu: Option<Entity<ApplicationMenu>>,
     _subscriptions: Vec<Subscription>,
     banner: Entity<OnboardingBanner>,
+    branch_pull_request: Entity<BranchPullRequest>,
 }
 
 impl Render for TitleBar {
@@ -315,6 +318,9 @@ impl TitleBar {
         subscriptions.push(cx.observe_window_activation(window, Self::window_activation_changed));
         subscriptions.push(cx.observe(&user_store, |_, _, cx| cx.notify()));
 
+        let branch_pull_request = cx.new(|cx| BranchPullRequest::new(project.clone(), cx));
+        subscriptions.push(cx.observe(&branch_pull_request, |_, _, cx| cx.notify()));
+
         let banner = cx.new(|cx| {
             OnboardingBanner::new(
                 "Git Onboarding",
@@ -338,6 +344,7 @@ impl TitleBar {
             client,
             _subscriptions: subscriptions,
             banner,
+            branch_pull_request,
         }
     }
 
@@ -522,27 +529,88 @@ impl TitleBar {
     pub fn render_project_branch(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
         let repository = self.project.read(cx).active_repository(cx)?;
         let workspace = self.workspace.upgrade()?;
-        let branch_name = repository.read(cx).branch.as_ref()?.name.clone();
+        let repository = repository.read(cx);
+        // When HEAD is detached, show the checked out tag or commit instead of a branch.
+        let (branch_name, detached_head) = match (&repository.branch, &repository.detached_head) {
+            (Some(branch), _) => (branch.name.clone(), None),
+            (None, Some(detached_head)) => (
+                detached_head
+                    .tag
+                    .clone()
+                    .unwrap_or_else(|| detached_head.short_sha()),
+                Some(detached_head.short_sha()),
+            ),
+            (None, None) => return None,
+        };
         let branch_name = util::truncate_and_trailoff(&branch_name, MAX_BRANCH_NAME_LENGTH);
+        let is_detached = detached_head.is_some();
         Some(
-            Button::new("project_branch_trigger", branch_name)
-                .color(Color::Muted)
+            h_flex()
+                .child(
+                    Button::new("project_branch_trigger", branch_name)
+                        .color(if is_detached {
+                            Color::Warning
+                        } else {
+                            Color::Muted
+                        })
+                        .style(ButtonStyle::Subtle)
+                        .label_size(LabelSize::Small)
+                        .when(is_detached, |this| {
+                            this.icon(IconName::Warning)
+                                .icon_position(IconPosition::Start)
+                                .icon_size(IconSize::XSmall)
+                                .icon_color(Color::Warning)
+                        })
+                        .tooltip(move |window, cx| {
+                            if let Some(sha) = &detached_head {
+                                Tooltip::with_meta(
+                                    "Detached HEAD",
+                                    Some(&zed_actions::git::Branch),
+                                    format!("Checked out at {sha}, not on a branch"),
+                                    window,
+                                    cx,
+                                )
+                            } else {
+                                Tooltip::with_meta(
+                                    "Recent Branches",
+                                    Some(&zed_actions::git::Branch),
+                                    "Local branches only",
+                                    window,
+                                    cx,
+                                )
+                            }
+                        })
+                        .on_click(move |_, window, cx| {
+                            let _ = workspace.update(cx, |_this, cx| {
+                                window.dispatch_action(zed_actions::git::Branch.boxed_clone(), cx);
+                            });
+                        }),
+                )
+                .children(self.render_pull_request_status(cx)),
+        )
+    }
+
+    /// Renders the status of the CI checks of the current branch's pull request.
+    fn render_pull_request_status(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        let branch_pull_request = self.branch_pull_request.read(cx);
+        let pull_request = branch_pull_request.pull_request()?;
+        let (icon, color, meta) = match branch_pull_request.ci_status() {
+            Some(CiStatus::Success) => (IconName::Check, Color::Success, "Checks passing"),
+            Some(CiStatus::Failure) => (IconName::XCircle, Color::Error, "Checks failing"),
+            Some(CiStatus::Pending) => (IconName::CountdownTimer, Color::Warning, "Checks running"),
+            None => (IconName::PullRequest, Color::Muted, "No checks"),
+        };
+        let title = format!("#{} {}", pull_request.number, pull_request.title);
+        let url = pull_request.url.clone();
+        Some(
+            IconButton::new("project_branch_pull_request", icon)
+                .icon_size(IconSize::Small)
+                .icon_color(color)
                 .style(ButtonStyle::Subtle)
-                .label_size(LabelSize::Small)
                 .tooltip(move |window, cx| {
-                    Tooltip::with_meta(
-                        "Recent Branches",
-                        Some(&zed_actions::git::Branch),
-                        "Local branches only",
-                        window,
-                        cx,
-                    )
+                    Tooltip::with_meta(title.clone(), None, meta, window, cx)
                 })
-                .on_click(move |_, window, cx| {
-                    let _ = workspace.update(cx, |_this, cx| {
-                        window.dispatch_action(zed_actions::git::Branch.boxed_clone(), cx);
-                    });
-                }),
+                .on_click(move |_, _, cx| cx.open_url(url.as_str())),
         )
     }
 
@@ -518,6 +518,7 @@ fn main() {
         project_symbols::init(cx);
         project_panel::init(cx);
         outline_panel::init(cx);
+        hierarchy_panel::init(cx);
         tasks_ui::init(cx);
         snippets_ui::init(cx);
         channel::init(&app_state.client.clone(), app_state.user_store.clone(), cx);
@@ -747,6 +748,41 @@ fn handle_open_request(request: OpenRequest, app_state: Arc<AppState>, cx: &mut
             }
         })
         .detach()
+    } else if !request.import_threads.is_empty() {
+        cx.spawn(async move |mut cx| {
+            let result = maybe!(async {
+                if let Some(task) = task {
+                    task.await?;
+                }
+
+                let workspace_window =
+                    workspace::get_any_active_workspace(app_state, cx.clone()).await?;
+                let workspace = workspace_window.entity(cx)?;
+                let import_tasks = cx.update_window(workspace_window.into(), |_, window, cx| {
+                    workspace.update(cx, |workspace, cx| {
+                        let panel = workspace.focus_panel::<agent::AssistantPanel>(window, cx)?;
+                        Some(panel.update(cx, |panel, cx| {
+                            request
+                                .import_threads
+                                .iter()
+                                .map(|path| panel.import_thread_from_path(path, window, cx))
+                                .collect::<Vec<_>>()
+                        }))
+                    })
+                })?;
+                let import_tasks =
+                    import_tasks.ok_or_else(|| anyhow!("the agent panel is not available"))?;
+                for result in future::join_all(import_tasks).await {
+                    result?;
+                }
+                anyhow::Ok(())
+            })
+            .await;
+            if let Err(err) = result {
+                fail_to_open_window_async(err, &mut cx);
+            }
+        })
+        .detach()
     } else if let Some(task) = task {
         cx.spawn(async move |mut cx| {
             if let Err(err) = task.await {
@@ -30,6 +30,7 @@ use gpui::{
     ReadGlobal, SharedString, Styled, Task, TitlebarOptions, UpdateGlobal, Window, WindowKind,
     WindowOptions, actions, point, px,
 };
+use hierarchy_panel::HierarchyPanel;
 use image_viewer::ImageInfo;
 use migrate::{MigrationBanner, MigrationEvent, MigrationNotification, MigrationType};
 use migrator::{migrate_keymap, migrate_settings};
@@ -404,6 +405,7 @@ fn initialize_panels(
     cx.spawn_in(window, async move |workspace_handle, cx| {
         let project_panel = ProjectPanel::load(workspace_handle.clone(), cx.clone());
         let outline_panel = OutlinePanel::load(workspace_handle.clone(), cx.clone());
+        let hierarchy_panel = HierarchyPanel::load(workspace_handle.clone(), cx.clone());
         let terminal_panel = TerminalPanel::load(workspace_handle.clone(), cx.clone());
         let channels_panel =
             collab_ui::collab_panel::CollabPanel::load(workspace_handle.clone(), cx.clone());
@@ -417,6 +419,7 @@ fn initialize_panels(
         let (
             project_panel,
             outline_panel,
+            hierarchy_panel,
             terminal_panel,
             channels_panel,
             chat_panel,
@@ -424,6 +427,7 @@ fn initialize_panels(
         ) = futures::try_join!(
             project_panel,
             outline_panel,
+            hierarchy_panel,
             terminal_panel,
             channels_panel,
             chat_panel,
@@ -433,6 +437,7 @@ fn initialize_panels(
         workspace_handle.update_in(cx, |workspace, window, cx| {
             workspace.add_panel(project_panel, window, cx);
             workspace.add_panel(outline_panel, window, cx);
+            workspace.add_panel(hierarchy_panel, window, cx);
             workspace.add_panel(terminal_panel, window, cx);
             workspace.add_panel(channels_panel, window, cx);
             workspace.add_panel(chat_panel, window, cx);
@@ -4277,6 +4282,7 @@ mod tests {
             git_ui::init(cx);
             project_panel::init(cx);
             outline_panel::init(cx);
+            hierarchy_panel::init(cx);
             terminal_view::init(cx);
             copilot::copilot_chat::init(
                 app_state.fs.clone(),
@@ -35,6 +35,7 @@ pub struct OpenRequest {
     pub join_channel: Option<u64>,
     pub ssh_connection: Option<SshConnectionOptions>,
     pub dock_menu_action: Option<usize>,
+    pub import_threads: Vec<PathBuf>,
 }
 
 impl OpenRequest {
@@ -45,6 +46,8 @@ impl OpenRequest {
                 this.cli_connection = Some(connect_to_cli(server_name)?);
             } else if let Some(action_index) = url.strip_prefix("zed-dock-action://") {
                 this.dock_menu_action = Some(action_index.parse()?);
+            } else if let Some(path) = url.strip_prefix("zed-import-thread://") {
+                this.import_threads.push(PathBuf::from(path));
             } else if let Some(file) = url.strip_prefix("file://") {
                 this.parse_file_path(file)
             } else if let Some(file) = url.strip_prefix("zed://file") {
@@ -265,7 +268,10 @@ pub async fn handle_cli_connection(
                 if !urls.is_empty() {
                     cx.update(|cx| {
                         match OpenRequest::parse(urls, cx) {
-                            Ok(open_request) => {
+                            Ok(mut open_request) => {
+                                // Paths can accompany an imported thread, which is then
+                                // imported into the workspace that they are opened in.
+                                open_request.open_paths.extend(paths);
                                 handle_open_request(open_request, app_state.clone(), cx);
                                 responses.send(CliResponse::Exit { status: 0 }).log_err();
                             }